@@ -0,0 +1,355 @@
+// Source analysis
+// Charts of a source file that help decide whether and how to compress it.
+// analyze_bitrate sums the packet sizes ffprobe lists into fixed time intervals: a
+// file far above the bitrate the presets produce is worth recompressing, and the
+// spikes show the scenes that will need the most bits.
+// get_audio_peaks decodes the audio to mono PCM at a low rate and keeps the min/max
+// of each slice, enough to draw a waveform under the trim handles.
+
+use serde::Serialize;
+
+/// Intervals beyond this are dropped (a packet with a bogus timestamp would
+/// otherwise allocate a huge curve)
+const MAX_INTERVALS: usize = 100_000;
+const MIN_INTERVAL_SECONDS: f64 = 0.1;
+/// Rate the audio is decoded at for peaks; plenty for a waveform drawing
+const PEAKS_DECODE_RATE: u32 = 8000;
+const MAX_PEAKS_PER_SECOND: u32 = 1000;
+
+/// Bitrate per interval, as parallel arrays for charting
+#[derive(Debug, Clone, Serialize)]
+pub struct BitrateCurve {
+    pub interval_seconds: f64,
+    /// Start of each interval in seconds
+    pub times: Vec<f64>,
+    /// kbit/s of the video packets
+    pub video_kbps: Vec<f64>,
+    /// kbit/s of all packets (video, audio, subtitles)
+    pub total_kbps: Vec<f64>,
+    pub average_kbps: f64,
+    pub peak_kbps: f64,
+    /// Start of the interval with the highest total bitrate
+    pub peak_time: f64,
+}
+
+/// One line of `ffprobe -show_entries packet=codec_type,pts_time,dts_time,size -of csv=p=0`:
+/// (is video, time, size in bytes). Packets without a timestamp are skipped.
+fn parse_packet_line(line: &str) -> Option<(bool, f64, u64)> {
+    let mut fields = line.trim().split(',');
+    let kind = fields.next()?;
+    let pts = fields.next()?.parse::<f64>().ok();
+    let dts = fields.next()?.parse::<f64>().ok();
+    let size = fields.next()?.parse::<u64>().ok()?;
+    let time = pts.or(dts).filter(|t| t.is_finite() && *t >= 0.0)?;
+    Some((kind == "video", time, size))
+}
+
+/// Packet bytes summed per interval
+struct BitrateAccumulator {
+    interval: f64,
+    video: Vec<u64>,
+    total: Vec<u64>,
+}
+
+impl BitrateAccumulator {
+    fn new(interval: f64) -> Self {
+        Self {
+            interval,
+            video: Vec::new(),
+            total: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, video: bool, time: f64, size: u64) {
+        let index = (time / self.interval) as usize;
+        if index >= MAX_INTERVALS {
+            return;
+        }
+        if index >= self.total.len() {
+            self.video.resize(index + 1, 0);
+            self.total.resize(index + 1, 0);
+        }
+        if video {
+            self.video[index] += size;
+        }
+        self.total[index] += size;
+    }
+
+    fn finish(self) -> BitrateCurve {
+        let kbps = |bytes: &u64| *bytes as f64 * 8.0 / 1000.0 / self.interval;
+        let video_kbps: Vec<f64> = self.video.iter().map(kbps).collect();
+        let total_kbps: Vec<f64> = self.total.iter().map(kbps).collect();
+        let times = (0..total_kbps.len())
+            .map(|i| i as f64 * self.interval)
+            .collect();
+        let average_kbps = if total_kbps.is_empty() {
+            0.0
+        } else {
+            total_kbps.iter().sum::<f64>() / total_kbps.len() as f64
+        };
+        let (peak_index, peak_kbps) = total_kbps
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        BitrateCurve {
+            interval_seconds: self.interval,
+            times,
+            video_kbps,
+            total_kbps,
+            average_kbps,
+            peak_kbps,
+            peak_time: peak_index as f64 * self.interval,
+        }
+    }
+}
+
+/// Waveform of the source's audio
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioPeaks {
+    pub samples_per_second: u32,
+    pub duration_seconds: f64,
+    /// Flat (min, max) pairs of 16-bit sample values, one pair per slice
+    pub peaks: Vec<i16>,
+}
+
+/// Min/max of each slice of `per_peak` samples from a stream of s16le bytes
+struct PeakAccumulator {
+    per_peak: usize,
+    /// Odd byte left over from the previous chunk
+    carry: Option<u8>,
+    count: usize,
+    min: i16,
+    max: i16,
+    samples: u64,
+    peaks: Vec<i16>,
+}
+
+impl PeakAccumulator {
+    fn new(per_peak: usize) -> Self {
+        Self {
+            per_peak: per_peak.max(1),
+            carry: None,
+            count: 0,
+            min: i16::MAX,
+            max: i16::MIN,
+            samples: 0,
+            peaks: Vec::new(),
+        }
+    }
+
+    fn push_sample(&mut self, sample: i16) {
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+        self.count += 1;
+        self.samples += 1;
+        if self.count == self.per_peak {
+            self.close_peak();
+        }
+    }
+
+    fn close_peak(&mut self) {
+        self.peaks.extend([self.min, self.max]);
+        self.count = 0;
+        self.min = i16::MAX;
+        self.max = i16::MIN;
+    }
+
+    fn add(&mut self, mut data: &[u8]) {
+        if let Some(low) = self.carry.take() {
+            let Some((&high, rest)) = data.split_first() else {
+                self.carry = Some(low);
+                return;
+            };
+            self.push_sample(i16::from_le_bytes([low, high]));
+            data = rest;
+        }
+        let mut pairs = data.chunks_exact(2);
+        for pair in &mut pairs {
+            self.push_sample(i16::from_le_bytes([pair[0], pair[1]]));
+        }
+        self.carry = pairs.remainder().first().copied();
+    }
+
+    /// Peaks, with the last partial slice included
+    fn finish(mut self) -> (Vec<i16>, u64) {
+        if self.count > 0 {
+            self.close_peak();
+        }
+        (self.peaks, self.samples)
+    }
+}
+
+/// Min/max peak pairs of the source's first audio track (downmixed to mono),
+/// `samples_per_second` pairs per second of audio
+#[tauri::command]
+pub async fn get_audio_peaks(input: String, samples_per_second: u32) -> Result<AudioPeaks, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    let samples_per_second = samples_per_second.clamp(1, MAX_PEAKS_PER_SECOND);
+
+    let mut accumulator = PeakAccumulator::new((PEAKS_DECODE_RATE / samples_per_second) as usize);
+    let output = crate::proc::run_reading(
+        crate::proc::command(&config.ffmpeg_path).args([
+            "-hide_banner",
+            "-v",
+            "error",
+            "-i",
+            &input,
+            "-map",
+            "0:a:0",
+            "-ac",
+            "1",
+            "-ar",
+            &PEAKS_DECODE_RATE.to_string(),
+            "-f",
+            "s16le",
+            "-",
+        ]),
+        &crate::proc::RunOptions::with_timeout(600).label("audio peaks"),
+        |data| accumulator.add(data),
+    )?;
+    if output.timed_out {
+        return Err("Audio decoding timed out".to_string());
+    }
+    if !output.success {
+        let error = output
+            .stderr
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        return Err(if error.contains("matches no streams") {
+            "The file has no audio track".to_string()
+        } else {
+            format!("FFmpeg failed: {}", error)
+        });
+    }
+
+    let (peaks, samples) = accumulator.finish();
+    Ok(AudioPeaks {
+        samples_per_second,
+        duration_seconds: samples as f64 / PEAKS_DECODE_RATE as f64,
+        peaks,
+    })
+}
+
+/// Bitrate of the source over time from its packet sizes; the last interval may be
+/// partial and read low
+#[tauri::command]
+pub async fn analyze_bitrate(input: String, interval_seconds: f64) -> Result<BitrateCurve, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
+    let interval = if interval_seconds.is_finite() && interval_seconds > 0.0 {
+        interval_seconds.max(MIN_INTERVAL_SECONDS)
+    } else {
+        1.0
+    };
+
+    // Hours of video list millions of packets: sum them while ffprobe runs
+    let options = crate::proc::RunOptions::with_timeout(600).label("ffprobe packets");
+    let mut child = crate::proc::spawn_piped(
+        crate::proc::command(&config.ffprobe_path).args([
+            "-v",
+            "error",
+            "-show_entries",
+            "packet=codec_type,pts_time,dts_time,size",
+            "-of",
+            "csv=p=0",
+            &input,
+        ]),
+        &options,
+    )?;
+    let mut accumulator = BitrateAccumulator::new(interval);
+    let mut error = String::new();
+    let output = crate::proc::run_streaming(&mut child, &options, |stream, line| match stream {
+        crate::proc::Stream::Stdout => {
+            if let Some((video, time, size)) = parse_packet_line(line) {
+                accumulator.add(video, time, size);
+            }
+        }
+        crate::proc::Stream::Stderr => {
+            if error.is_empty() {
+                error = line.trim().to_string();
+            }
+        }
+    })?;
+    if output.timed_out {
+        return Err("Bitrate analysis timed out".to_string());
+    }
+    if !output.success {
+        return Err(format!("ffprobe failed: {}", error));
+    }
+
+    let curve = accumulator.finish();
+    if curve.times.is_empty() {
+        return Err("No packets with timestamps found".to_string());
+    }
+    let _ = crate::write_log(format!(
+        "[ANALYSIS] Bitrate of {}: {} x {}s, avg {:.0} kbps, peak {:.0} kbps at {:.1}s",
+        input,
+        curve.times.len(),
+        interval,
+        curve.average_kbps,
+        curve.peak_kbps,
+        curve.peak_time
+    ));
+    Ok(curve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_packet_line() {
+        assert_eq!(
+            parse_packet_line("video,1.500000,1.433333,12000"),
+            Some((true, 1.5, 12000))
+        );
+        // No pts: the dts is used
+        assert_eq!(
+            parse_packet_line("audio,N/A,2.000000,400"),
+            Some((false, 2.0, 400))
+        );
+        assert_eq!(parse_packet_line("video,N/A,N/A,500"), None);
+        assert_eq!(parse_packet_line(""), None);
+    }
+
+    #[test]
+    fn test_peak_accumulator() {
+        let bytes: Vec<u8> = [100i16, -200, 300, -50, 7]
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        let mut accumulator = PeakAccumulator::new(2);
+        // Chunks split in the middle of a sample
+        accumulator.add(&bytes[..3]);
+        accumulator.add(&bytes[3..]);
+        let (peaks, samples) = accumulator.finish();
+        assert_eq!(samples, 5);
+        assert_eq!(peaks, vec![-200, 100, -50, 300, 7, 7]);
+    }
+
+    #[test]
+    fn test_bitrate_curve() {
+        let mut accumulator = BitrateAccumulator::new(2.0);
+        accumulator.add(true, 0.5, 100_000);
+        accumulator.add(false, 1.0, 25_000);
+        accumulator.add(true, 4.2, 500_000);
+        let curve = accumulator.finish();
+        assert_eq!(curve.times, vec![0.0, 2.0, 4.0]);
+        // 100 kB over 2 s = 400 kbit/s
+        assert_eq!(curve.video_kbps, vec![400.0, 0.0, 2000.0]);
+        assert_eq!(curve.total_kbps, vec![500.0, 0.0, 2000.0]);
+        assert_eq!(curve.peak_kbps, 2000.0);
+        assert_eq!(curve.peak_time, 4.0);
+        assert!((curve.average_kbps - 2500.0 / 3.0).abs() < 1e-9);
+    }
+}
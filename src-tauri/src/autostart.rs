@@ -0,0 +1,206 @@
+// Start with the system
+// Registers the app to launch at login: the Run key on Windows, an XDG autostart entry
+// on Linux and a LaunchAgent on macOS. With `minimized` the launch passes --minimized
+// and the window starts hidden in the tray (useful with watch folders).
+
+use serde::Serialize;
+
+/// Launch argument that keeps the window hidden at startup
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    pub minimized: bool,
+}
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(windows)]
+const RUN_VALUE: &str = "Szhimatar";
+
+#[cfg(windows)]
+fn register(exe: &str, minimized: bool) -> Result<(), String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    let mut command = format!(r#""{}""#, exe);
+    if minimized {
+        command.push(' ');
+        command.push_str(MINIMIZED_ARG);
+    }
+    let (key, _) = winreg::RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(RUN_KEY)
+        .map_err(|e| format!("Registry error: {}", e))?;
+    key.set_value(RUN_VALUE, &command)
+        .map_err(|e| format!("Registry error: {}", e))
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    use winreg::enums::{HKEY_CURRENT_USER, KEY_WRITE};
+
+    let key = match winreg::RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(RUN_KEY, KEY_WRITE)
+    {
+        Ok(key) => key,
+        Err(_) => return Ok(()),
+    };
+    match key.delete_value(RUN_VALUE) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Registry error: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/// Registered command line, if any
+#[cfg(windows)]
+fn registered_command() -> Option<String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    winreg::RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(RUN_KEY)
+        .ok()?
+        .get_value(RUN_VALUE)
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+fn entry_path() -> Result<std::path::PathBuf, String> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config"))
+        })
+        .map(|config| config.join("autostart/szhimatar.desktop"))
+        .ok_or_else(|| "Failed to find the home folder".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn register(exe: &str, minimized: bool) -> Result<(), String> {
+    let mut exec = crate::file_manager::desktop_exec_quote(exe);
+    if minimized {
+        exec.push(' ');
+        exec.push_str(MINIMIZED_ARG);
+    }
+    let content = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Szhimatar\n\
+         Exec={}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exec
+    );
+    crate::file_manager::write_file(&entry_path()?, &content, false)
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), String> {
+    crate::file_manager::remove_path(&entry_path()?)
+}
+
+#[cfg(target_os = "linux")]
+fn registered_command() -> Option<String> {
+    std::fs::read_to_string(entry_path().ok()?)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Exec=").map(|exec| exec.to_string()))
+}
+
+#[cfg(target_os = "macos")]
+const AGENT_LABEL: &str = "com.szhimatar.app";
+
+#[cfg(target_os = "macos")]
+fn agent_path() -> Result<std::path::PathBuf, String> {
+    std::env::var_os("HOME")
+        .map(|home| {
+            std::path::Path::new(&home)
+                .join("Library/LaunchAgents")
+                .join(format!("{}.plist", AGENT_LABEL))
+        })
+        .ok_or_else(|| "Failed to find the home folder".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn register(exe: &str, minimized: bool) -> Result<(), String> {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let mut arguments = format!("\t\t<string>{}</string>\n", escape(exe));
+    if minimized {
+        arguments.push_str(&format!("\t\t<string>{}</string>\n", MINIMIZED_ARG));
+    }
+    let content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Label</key>
+	<string>{}</string>
+	<key>ProgramArguments</key>
+	<array>
+{}	</array>
+	<key>RunAtLoad</key>
+	<true/>
+</dict>
+</plist>
+"#,
+        AGENT_LABEL, arguments
+    );
+    crate::file_manager::write_file(&agent_path()?, &content, false)
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<(), String> {
+    crate::file_manager::remove_path(&agent_path()?)
+}
+
+#[cfg(target_os = "macos")]
+fn registered_command() -> Option<String> {
+    std::fs::read_to_string(agent_path().ok()?).ok()
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn register(_exe: &str, _minimized: bool) -> Result<(), String> {
+    Err("Autostart is not supported on this platform".to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn unregister() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn registered_command() -> Option<String> {
+    None
+}
+
+/// Register (or remove) the launch at login
+#[tauri::command]
+pub fn set_autostart(enabled: bool, minimized: bool) -> Result<(), String> {
+    if enabled {
+        let exe = crate::file_manager::exe_path()?;
+        register(&exe, minimized)?;
+    } else {
+        unregister()?;
+    }
+    let _ = crate::write_log(format!(
+        "[AUTOSTART] enabled={} minimized={}",
+        enabled, minimized
+    ));
+    Ok(())
+}
+
+/// Whether the app starts with the system, and whether it starts hidden
+#[tauri::command]
+pub fn get_autostart() -> AutostartStatus {
+    match registered_command() {
+        Some(command) => AutostartStatus {
+            enabled: true,
+            minimized: command.contains(MINIMIZED_ARG),
+        },
+        None => AutostartStatus::default(),
+    }
+}
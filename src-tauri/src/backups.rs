@@ -0,0 +1,536 @@
+// Preset and settings snapshots
+// Daily copies of settings.json + presets/ under ~/.szhimatar/backups/<timestamp>/
+// with a SHA-256 manifest, so a restore can prove the files were not damaged.
+// backup_app_data / restore_app_data move the same files plus ffmpeg.json and stats/
+// through a zip, for moving to another PC.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// How many snapshots to keep before the oldest are pruned
+const BACKUPS_TO_KEEP: usize = 10;
+const MANIFEST_NAME: &str = "manifest.json";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// Manifest entry of an app data archive
+const ARCHIVE_MANIFEST_NAME: &str = "szhimatar-backup.json";
+/// Layout version of app data archives; newer archives are refused
+const ARCHIVE_FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupFileEntry {
+    /// Path relative to the app data directory (always '/'-separated)
+    pub path: String,
+    pub sha256: String,
+    pub size: crate::units::ByteSize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupManifest {
+    pub timestamp: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub files: Vec<BackupFileEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveManifest {
+    pub format: u32,
+    pub created_at: String,
+    pub app_version: String,
+    pub files: Vec<BackupFileEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub timestamp: String,
+    pub created_at: String,
+    pub file_count: usize,
+    pub valid: bool,
+}
+
+fn get_backups_dir() -> PathBuf {
+    crate::get_app_data_dir().join("backups")
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    crate::hash::hash_file(path, crate::hash::HashAlgorithm::Sha256)
+}
+
+/// Files covered by a snapshot, relative to `app_dir`
+fn collect_backup_sources(app_dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+
+    if app_dir.join("settings.json").is_file() {
+        files.push("settings.json".to_string());
+    }
+
+    if let Ok(entries) = fs::read_dir(app_dir.join("presets")) {
+        let mut presets: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("json"))
+            .filter_map(|p| {
+                p.file_name()
+                    .map(|n| format!("presets/{}", n.to_string_lossy()))
+            })
+            .collect();
+        presets.sort();
+        files.extend(presets);
+    }
+
+    files
+}
+
+/// Files of an app data archive: the snapshot files, ffmpeg.json and stats/
+fn collect_archive_sources() -> Vec<String> {
+    let app_dir = crate::get_app_data_dir();
+    let mut files = collect_backup_sources(&app_dir);
+    if app_dir.join("ffmpeg.json").is_file() {
+        files.push("ffmpeg.json".to_string());
+    }
+    if let Ok(entries) = fs::read_dir(app_dir.join("stats")) {
+        let mut stats: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .map(|e| format!("stats/{}", e.file_name().to_string_lossy()))
+            .collect();
+        stats.sort();
+        files.extend(stats);
+    }
+    files
+}
+
+/// Archive entries restore_app_data may write: the known files and plain file names
+/// directly in presets/ and stats/
+fn is_archive_path(path: &str) -> bool {
+    match path.split_once('/') {
+        None => path == "settings.json" || path == "ffmpeg.json",
+        Some((folder, name)) => {
+            ((folder == "presets" && name.ends_with(".json")) || folder == "stats")
+                && !name.is_empty()
+                && !name.contains(['/', '\\'])
+                && name != ".."
+        }
+    }
+}
+
+/// Refuse archives this version cannot read: a newer layout or a newer app version
+fn check_archive_version(manifest: &ArchiveManifest) -> Result<(), String> {
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(format!(
+            "The backup was made by Szhimatar {} and uses a newer format; update the app first",
+            manifest.app_version
+        ));
+    }
+    let running = crate::updater::Version::parse(env!("CARGO_PKG_VERSION"));
+    let archived = crate::updater::Version::parse(&manifest.app_version);
+    if let (Some(running), Some(archived)) = (running, archived) {
+        if archived > running {
+            return Err(format!(
+                "The backup is from Szhimatar {}, newer than {}; update the app first",
+                manifest.app_version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Snapshot directories sorted oldest first
+fn list_snapshot_dirs(backups_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(backups_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.join(MANIFEST_NAME).is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn read_manifest(dir: &Path) -> Result<BackupManifest, String> {
+    let content = fs::read_to_string(dir.join(MANIFEST_NAME))
+        .map_err(|e| format!("Failed to read backup manifest: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid backup manifest: {}", e))
+}
+
+/// Check every file of a snapshot against its manifest checksum
+fn verify_snapshot(dir: &Path, manifest: &BackupManifest) -> Result<(), String> {
+    for entry in &manifest.files {
+        let actual = sha256_file(&dir.join(&entry.path))?;
+        if actual != entry.sha256 {
+            return Err(format!("Checksum mismatch for {}", entry.path));
+        }
+    }
+    Ok(())
+}
+
+/// Copy the files of `app_dir` into a new snapshot of `backups_dir` (nothing is pruned)
+fn write_snapshot(app_dir: &Path, backups_dir: &Path) -> Result<BackupManifest, String> {
+    let now = chrono::Local::now();
+    let timestamp = now.format(TIMESTAMP_FORMAT).to_string();
+    let snapshot_dir = backups_dir.join(&timestamp);
+
+    fs::create_dir_all(snapshot_dir.join("presets"))
+        .map_err(|e| format!("Failed to create backup dir: {}", e))?;
+
+    let mut files = Vec::new();
+    for relative in collect_backup_sources(app_dir) {
+        let source = app_dir.join(&relative);
+        let target = snapshot_dir.join(&relative);
+        fs::copy(&source, &target).map_err(|e| format!("Failed to back up {}: {}", relative, e))?;
+
+        files.push(BackupFileEntry {
+            sha256: sha256_file(&target)?,
+            size: crate::units::ByteSize::from_bytes(
+                fs::metadata(&target).map(|m| m.len()).unwrap_or(0),
+            ),
+            path: relative,
+        });
+    }
+
+    let manifest = BackupManifest {
+        timestamp,
+        created_at: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files,
+    };
+
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(snapshot_dir.join(MANIFEST_NAME), content)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    Ok(manifest)
+}
+
+/// Keep only the newest BACKUPS_TO_KEEP snapshots
+fn prune_snapshots(backups_dir: &Path) {
+    let snapshots = list_snapshot_dirs(backups_dir);
+    if snapshots.len() > BACKUPS_TO_KEEP {
+        for old in &snapshots[..snapshots.len() - BACKUPS_TO_KEEP] {
+            let _ = fs::remove_dir_all(old);
+        }
+    }
+}
+
+/// Create a new snapshot and prune old ones
+pub fn create_snapshot() -> Result<BackupManifest, String> {
+    let backups_dir = get_backups_dir();
+    let manifest = write_snapshot(&crate::get_app_data_dir(), &backups_dir)?;
+    prune_snapshots(&backups_dir);
+    Ok(manifest)
+}
+
+/// Create today's snapshot unless one already exists (called on startup)
+pub fn create_daily_snapshot_if_due() -> Result<(), String> {
+    let today = chrono::Local::now().format("%Y%m%d").to_string();
+    let has_today = list_snapshot_dirs(&get_backups_dir()).iter().any(|dir| {
+        dir.file_name()
+            .map(|n| n.to_string_lossy().starts_with(&today))
+            .unwrap_or(false)
+    });
+
+    if has_today || collect_backup_sources(&crate::get_app_data_dir()).is_empty() {
+        return Ok(());
+    }
+
+    create_snapshot().map(|_| ())
+}
+
+/// List available snapshots (newest first) with their integrity status
+#[tauri::command]
+pub fn list_backups() -> Result<Vec<BackupInfo>, String> {
+    let mut backups = Vec::new();
+
+    for dir in list_snapshot_dirs(&get_backups_dir()).into_iter().rev() {
+        let Ok(manifest) = read_manifest(&dir) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            valid: verify_snapshot(&dir, &manifest).is_ok(),
+            timestamp: manifest.timestamp,
+            created_at: manifest.created_at,
+            file_count: manifest.files.len(),
+        });
+    }
+
+    Ok(backups)
+}
+
+/// Create a snapshot on demand
+#[tauri::command]
+pub fn create_backup() -> Result<BackupInfo, String> {
+    let manifest = create_snapshot()?;
+    Ok(BackupInfo {
+        timestamp: manifest.timestamp,
+        created_at: manifest.created_at,
+        file_count: manifest.files.len(),
+        valid: true,
+    })
+}
+
+/// Restore settings and presets from a snapshot after verifying its checksums.
+/// The current state is snapshotted first so a restore can itself be undone.
+#[tauri::command]
+pub fn restore_backup(timestamp: String) -> Result<(), String> {
+    if timestamp.is_empty() || !timestamp.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err("Invalid backup timestamp".to_string());
+    }
+
+    let manifest = restore_snapshot(&crate::get_app_data_dir(), &get_backups_dir(), &timestamp)?;
+    let _ = crate::write_log(format!(
+        "[BACKUP] Restored {} files from snapshot {}",
+        manifest.files.len(),
+        timestamp
+    ));
+
+    Ok(())
+}
+
+fn restore_snapshot(
+    app_dir: &Path,
+    backups_dir: &Path,
+    timestamp: &str,
+) -> Result<BackupManifest, String> {
+    let snapshot_dir = backups_dir.join(timestamp);
+    let manifest = read_manifest(&snapshot_dir)?;
+
+    // Everything is read and checked before the first file is replaced: the safety
+    // snapshot below may be the one that pushes this snapshot out
+    let mut contents = Vec::new();
+    for entry in &manifest.files {
+        let content = fs::read(snapshot_dir.join(&entry.path))
+            .map_err(|e| format!("Failed to read {} from the backup: {}", entry.path, e))?;
+        if crate::hash::sha256_bytes(&content) != entry.sha256 {
+            return Err(format!("Checksum mismatch for {}", entry.path));
+        }
+        contents.push((entry.path.as_str(), content));
+    }
+
+    // Pruned only once the restore is done
+    write_snapshot(app_dir, backups_dir)?;
+
+    let presets_dir = app_dir.join("presets");
+    fs::create_dir_all(&presets_dir).map_err(|e| e.to_string())?;
+
+    // Presets that did not exist at backup time are removed to mirror the snapshot
+    if let Ok(entries) = fs::read_dir(&presets_dir) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let relative = format!(
+                "presets/{}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            );
+            let is_preset = path.extension().and_then(|s| s.to_str()) == Some("json");
+            if is_preset && !manifest.files.iter().any(|f| f.path == relative) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    for (relative, content) in contents {
+        fs::write(app_dir.join(relative), content)
+            .map_err(|e| format!("Failed to restore {}: {}", relative, e))?;
+    }
+
+    prune_snapshots(backups_dir);
+    Ok(manifest)
+}
+
+/// Zip settings, presets, FFmpeg config and statistics with a checksum manifest
+#[tauri::command]
+pub fn backup_app_data(output_zip: String) -> Result<ArchiveManifest, String> {
+    let app_dir = crate::get_app_data_dir();
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write zip: {}", e);
+    let write_error = |e: std::io::Error| format!("Failed to write zip: {}", e);
+
+    let file = fs::File::create(&output_zip)
+        .map_err(|e| format!("Failed to create {}: {}", output_zip, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut files = Vec::new();
+    for relative in collect_archive_sources() {
+        let content = fs::read(app_dir.join(&relative))
+            .map_err(|e| format!("Failed to read {}: {}", relative, e))?;
+        zip.start_file(relative.as_str(), options)
+            .map_err(zip_error)?;
+        zip.write_all(&content).map_err(write_error)?;
+        files.push(BackupFileEntry {
+            sha256: crate::hash::sha256_bytes(&content),
+            size: crate::units::ByteSize::from_bytes(content.len() as u64),
+            path: relative,
+        });
+    }
+
+    let manifest = ArchiveManifest {
+        format: ARCHIVE_FORMAT,
+        created_at: chrono::Local::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files,
+    };
+    zip.start_file(ARCHIVE_MANIFEST_NAME, options)
+        .map_err(zip_error)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
+        .map_err(write_error)?;
+    zip.finish().map_err(zip_error)?;
+
+    let _ = crate::write_log(format!(
+        "[BACKUP] Exported {} files to {}",
+        manifest.files.len(),
+        output_zip
+    ));
+    Ok(manifest)
+}
+
+/// Restore an archive of backup_app_data after checking its version and checksums.
+/// The current settings and presets are snapshotted first; presets missing from the
+/// archive are removed, as with restore_backup.
+#[tauri::command]
+pub fn restore_app_data(zip_path: String) -> Result<ArchiveManifest, String> {
+    let file =
+        fs::File::open(&zip_path).map_err(|e| format!("Failed to open {}: {}", zip_path, e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
+    let mut read_entry = |name: &str| -> Result<Vec<u8>, String> {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| format!("Backup is missing {}: {}", name, e))?;
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {} from the backup: {}", name, e))?;
+        Ok(content)
+    };
+
+    let manifest: ArchiveManifest = serde_json::from_slice(&read_entry(ARCHIVE_MANIFEST_NAME)?)
+        .map_err(|e| format!("Not a Szhimatar backup: {}", e))?;
+    check_archive_version(&manifest)?;
+
+    // Everything is read and checked before the first file is replaced
+    let mut contents = Vec::new();
+    for entry in &manifest.files {
+        if !is_archive_path(&entry.path) {
+            return Err(format!("Unexpected file in the backup: {}", entry.path));
+        }
+        let content = read_entry(&entry.path)?;
+        if crate::hash::sha256_bytes(&content) != entry.sha256 {
+            return Err(format!("Checksum mismatch for {}", entry.path));
+        }
+        contents.push((entry.path.as_str(), content));
+    }
+
+    create_snapshot()?;
+
+    let app_dir = crate::get_app_data_dir();
+    for folder in ["presets", "stats"] {
+        fs::create_dir_all(app_dir.join(folder)).map_err(|e| e.to_string())?;
+    }
+    if let Ok(entries) = fs::read_dir(crate::get_presets_dir()) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            let relative = format!(
+                "presets/{}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            );
+            let is_preset = path.extension().and_then(|s| s.to_str()) == Some("json");
+            if is_preset && !manifest.files.iter().any(|f| f.path == relative) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+    for (relative, content) in contents {
+        fs::write(app_dir.join(relative), content)
+            .map_err(|e| format!("Failed to restore {}: {}", relative, e))?;
+    }
+
+    let _ = crate::write_log(format!(
+        "[BACKUP] Restored {} files from {} (made by {})",
+        manifest.files.len(),
+        zip_path,
+        manifest.app_version
+    ));
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_archive_path() {
+        assert!(is_archive_path("settings.json"));
+        assert!(is_archive_path("ffmpeg.json"));
+        assert!(is_archive_path("presets/YouTube 1080p.json"));
+        assert!(is_archive_path("stats/stat.json"));
+        assert!(!is_archive_path("presets/../settings.json"));
+        assert!(!is_archive_path("presets/readme.txt"));
+        assert!(!is_archive_path("stats/.."));
+        assert!(!is_archive_path("logs/app.log"));
+        assert!(!is_archive_path("../settings.json"));
+    }
+
+    #[test]
+    fn test_check_archive_version() {
+        let manifest = |format: u32, app_version: &str| ArchiveManifest {
+            format,
+            created_at: String::new(),
+            app_version: app_version.to_string(),
+            files: Vec::new(),
+        };
+        assert!(check_archive_version(&manifest(1, "0.0.0")).is_ok());
+        assert!(check_archive_version(&manifest(1, env!("CARGO_PKG_VERSION"))).is_ok());
+        assert!(check_archive_version(&manifest(1, "999.0.0")).is_err());
+        assert!(check_archive_version(&manifest(2, "0.0.0")).is_err());
+    }
+
+    #[test]
+    fn test_restore_oldest_snapshot() {
+        let root = std::env::temp_dir().join(format!("szhimatar-backups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (app_dir, backups_dir) = (root.join("app"), root.join("backups"));
+        fs::create_dir_all(app_dir.join("presets")).unwrap();
+        fs::create_dir_all(&backups_dir).unwrap();
+
+        // The oldest of a full set of snapshots holds the state to go back to
+        fs::write(app_dir.join("settings.json"), "old").unwrap();
+        fs::write(app_dir.join("presets/a.json"), "{}").unwrap();
+        for day in 1..=BACKUPS_TO_KEEP {
+            let manifest = write_snapshot(&app_dir, &backups_dir).unwrap();
+            let name = format!("202001{:02}-000000", day);
+            fs::rename(
+                backups_dir.join(&manifest.timestamp),
+                backups_dir.join(name),
+            )
+            .unwrap();
+            fs::write(app_dir.join("settings.json"), "new").unwrap();
+            fs::write(app_dir.join("presets/extra.json"), "{}").unwrap();
+        }
+
+        restore_snapshot(&app_dir, &backups_dir, "20200101-000000").unwrap();
+        assert_eq!(
+            fs::read_to_string(app_dir.join("settings.json")).unwrap(),
+            "old"
+        );
+        assert!(app_dir.join("presets/a.json").is_file());
+        assert!(!app_dir.join("presets/extra.json").exists());
+
+        // The restored snapshot made room for the safety snapshot of the replaced state
+        let snapshots = list_snapshot_dirs(&backups_dir);
+        assert_eq!(snapshots.len(), BACKUPS_TO_KEEP);
+        assert!(!backups_dir.join("20200101-000000").exists());
+        let newest = snapshots.last().unwrap();
+        assert_eq!(
+            fs::read_to_string(newest.join("settings.json")).unwrap(),
+            "new"
+        );
+        assert!(newest.join("presets/extra.json").is_file());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,246 @@
+// Hardware benchmark
+// Encodes a synthetic 1080p test source with every usable encoder and reports
+// fps / speed, so users can pick the render mode that suits their machine.
+
+use crate::encode_args::{EncodeOptions, ResolvedEncoder};
+use serde::Serialize;
+use std::time::Instant;
+
+const BENCHMARK_SIZE: &str = "1920x1080";
+const BENCHMARK_FPS: u32 = 30;
+
+/// (codec, encoder) pairs benchmarked when the FFmpeg build has them
+const BENCHMARK_ENCODERS: &[(&str, &str)] = &[
+    ("h264", "libx264"),
+    ("hevc", "libx265"),
+    ("av1", "libsvtav1"),
+    ("h264", "h264_nvenc"),
+    ("hevc", "hevc_nvenc"),
+    ("av1", "av1_nvenc"),
+    ("h264", "h264_qsv"),
+    ("hevc", "hevc_qsv"),
+    ("av1", "av1_qsv"),
+    ("h264", "h264_amf"),
+    ("hevc", "hevc_amf"),
+    ("h264", "h264_vaapi"),
+    ("hevc", "hevc_vaapi"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkResult {
+    pub codec: String,
+    pub encoder: String,
+    pub hardware: bool,
+    /// Encoded frames per second (None when the encode failed)
+    pub fps: Option<f64>,
+    /// Realtime multiple (1.0 = as fast as playback)
+    pub speed: Option<f64>,
+    /// fps relative to libx264 (100 = same speed)
+    pub score: Option<u32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub duration_seconds: u32,
+    pub resolution: String,
+    pub results: Vec<BenchmarkResult>,
+    /// Render mode suggested by the results: cpu | gpu | duo
+    pub recommended_mode: String,
+}
+
+/// Payload of the `benchmark-progress` event (sent before each encoder runs)
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkProgress {
+    pub encoder: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+/// Fill in scores relative to libx264 and pick a render mode. A GPU at least twice
+/// as fast as the CPU gets the queue on its own; otherwise both are worth using (duo).
+pub fn summarize(results: &mut [BenchmarkResult]) -> String {
+    let fps_of = |encoder: &str| {
+        results
+            .iter()
+            .find(|r| r.encoder == encoder)
+            .and_then(|r| r.fps)
+    };
+    let baseline = fps_of("libx264");
+    for result in results.iter_mut() {
+        result.score = result
+            .fps
+            .zip(baseline)
+            .filter(|(_, base)| *base > 0.0)
+            .map(|(fps, base)| (fps / base * 100.0).round() as u32);
+    }
+
+    let best_h264 = |hardware: bool| {
+        results
+            .iter()
+            .filter(|r| r.hardware == hardware && r.codec == "h264")
+            .filter_map(|r| r.fps)
+            .reduce(f64::max)
+    };
+    match (best_h264(false), best_h264(true)) {
+        (_, None) => "cpu",
+        (Some(cpu), Some(gpu)) if gpu < cpu * 2.0 => "duo",
+        _ => "gpu",
+    }
+    .to_string()
+}
+
+fn benchmark_encoder(
+    ffmpeg_path: &str,
+    codec: &str,
+    encoder: &str,
+    duration_seconds: u32,
+) -> BenchmarkResult {
+    let resolved = ResolvedEncoder::named(encoder);
+    let options = EncodeOptions {
+        codec: codec.to_string(),
+        ..Default::default()
+    };
+
+    let mut args = crate::encode_args::hw_device_args(encoder);
+    args.extend([
+        "-hide_banner".to_string(),
+        "-v".to_string(),
+        "error".to_string(),
+        "-f".to_string(),
+        "lavfi".to_string(),
+        "-i".to_string(),
+        format!(
+            "testsrc2=size={}:rate={}:duration={}",
+            BENCHMARK_SIZE, BENCHMARK_FPS, duration_seconds
+        ),
+    ]);
+    if let Some(filter) = crate::encode_args::hw_upload_filter(encoder) {
+        args.extend(["-vf".to_string(), filter.to_string()]);
+    }
+    args.extend(["-c:v".to_string(), encoder.to_string()]);
+    args.extend(crate::encode_args::video_quality_args(&options, &resolved));
+    args.extend(["-f", "null", "-"].iter().map(|a| a.to_string()));
+
+    let mut result = BenchmarkResult {
+        codec: codec.to_string(),
+        encoder: encoder.to_string(),
+        hardware: resolved.hardware,
+        fps: None,
+        speed: None,
+        score: None,
+        error: None,
+    };
+
+    // Slow encoders (x265 on a laptop) can take well over realtime
+    let timeout = 60 + u64::from(duration_seconds) * 30;
+    let started = Instant::now();
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args(&args),
+        &crate::proc::RunOptions::with_timeout(timeout).label("benchmark"),
+    );
+    let elapsed = started.elapsed().as_secs_f64().max(0.001);
+
+    match output {
+        Ok(o) if o.timed_out => result.error = Some("Timed out".to_string()),
+        Ok(o) if !o.success => {
+            let message = o.stderr.lines().last().unwrap_or("").trim().to_string();
+            result.error = Some(if message.is_empty() {
+                format!("FFmpeg exited with code {:?}", o.code)
+            } else {
+                message
+            })
+        }
+        Ok(_) => {
+            let frames = f64::from(duration_seconds * BENCHMARK_FPS);
+            result.fps = Some(frames / elapsed);
+            result.speed = Some(f64::from(duration_seconds) / elapsed);
+        }
+        Err(e) => result.error = Some(e),
+    }
+    result
+}
+
+/// Encode a synthetic test source with each available encoder and score them
+#[tauri::command]
+pub async fn run_benchmark(
+    window: tauri::Window,
+    duration_seconds: u32,
+) -> Result<BenchmarkReport, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    let duration_seconds = duration_seconds.clamp(1, 60);
+
+    let available = crate::encode_args::available_encoder_names();
+    let encoders: Vec<&(&str, &str)> = BENCHMARK_ENCODERS
+        .iter()
+        .filter(|(_, encoder)| available.iter().any(|a| a == encoder))
+        .filter(|(_, encoder)| {
+            !ResolvedEncoder::named(encoder).hardware || crate::encoders::encoder_works(encoder)
+        })
+        .collect();
+    if encoders.is_empty() {
+        return Err("No supported encoders found in this FFmpeg build".to_string());
+    }
+
+    let mut results = Vec::new();
+    for (index, (codec, encoder)) in encoders.iter().enumerate() {
+        let _ = window.emit(
+            "benchmark-progress",
+            BenchmarkProgress {
+                encoder: encoder.to_string(),
+                index,
+                total: encoders.len(),
+            },
+        );
+        let result = benchmark_encoder(&config.ffmpeg_path, codec, encoder, duration_seconds);
+        let _ = crate::write_log(format!(
+            "[BENCHMARK] {} fps={:?} speed={:?} error={:?}",
+            encoder, result.fps, result.speed, result.error
+        ));
+        results.push(result);
+    }
+
+    let recommended_mode = summarize(&mut results);
+    Ok(BenchmarkReport {
+        duration_seconds,
+        resolution: BENCHMARK_SIZE.to_string(),
+        results,
+        recommended_mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(codec: &str, encoder: &str, fps: Option<f64>) -> BenchmarkResult {
+        BenchmarkResult {
+            codec: codec.to_string(),
+            encoder: encoder.to_string(),
+            hardware: ResolvedEncoder::named(encoder).hardware,
+            fps,
+            speed: None,
+            score: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_scores_and_mode() {
+        let mut results = vec![
+            result("h264", "libx264", Some(80.0)),
+            result("hevc", "libx265", Some(20.0)),
+            result("h264", "h264_nvenc", Some(400.0)),
+        ];
+        assert_eq!(summarize(&mut results), "gpu");
+        assert_eq!(results[1].score, Some(25));
+        assert_eq!(results[2].score, Some(500));
+
+        results[2].fps = Some(120.0);
+        assert_eq!(summarize(&mut results), "duo");
+        assert_eq!(summarize(&mut results[..2]), "cpu");
+    }
+}
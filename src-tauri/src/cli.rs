@@ -0,0 +1,278 @@
+// Command line arguments
+// Parses launches from the Explorer context menu and "Open with":
+//   szhimatar [--preset NAME] [--output-dir DIR] [--queue-only] [--minimized] <files or folders>...
+// Folders expand into the videos they contain, minus the folderExcludePatterns setting.
+// szhimatar:// links are accepted too (see url_protocol.rs).
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Extensions accepted from the command line and from folder arguments
+pub const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "m4v", "mpeg", "mpg", "3gp",
+];
+
+/// Result of get_cli_launch; also the payload of `files-received`
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CliLaunch {
+    pub files: Vec<String>,
+    /// Preset to apply before queueing (--preset)
+    pub preset: Option<String>,
+    /// Output folder for these files (--output-dir)
+    pub output_dir: Option<String>,
+    /// Only add to the queue. Without it, a launch with --preset also starts rendering.
+    pub queue_only: bool,
+    /// Keep the window hidden (launch at login, see autostart.rs)
+    pub minimized: bool,
+    /// Arguments that were neither options nor existing videos / folders
+    pub ignored: Vec<String>,
+    /// Queued in bulk without the user picking each file (folder import, HTTP API)
+    pub unattended: bool,
+}
+
+fn is_video_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Which videos a folder argument expands into
+#[derive(Debug, Clone, Default)]
+pub struct FolderFilter {
+    /// Wildcards (`*`, `?`): "*.tmp.mp4" matches file names, "Proxy/" any folder of
+    /// that name, "raw/*.mov" paths relative to the folder
+    pub exclude: Vec<String>,
+    /// Same wildcards; when any is given, only matching files are taken
+    pub include: Vec<String>,
+    /// Files whose name ends with this (the output suffix) are earlier renders
+    pub output_suffix: String,
+    /// Smaller files are skipped (bytes, 0 = none)
+    pub min_size: u64,
+}
+
+impl FolderFilter {
+    pub fn from_settings() -> Self {
+        let settings = crate::load_settings().unwrap_or_default();
+        FolderFilter {
+            exclude: settings.folder_exclude_patterns,
+            output_suffix: settings.output_suffix,
+            ..Default::default()
+        }
+    }
+
+    fn excludes(&self, relative: &Path) -> bool {
+        let relative = relative.to_string_lossy().replace('\\', "/").to_lowercase();
+        let file_name = relative.rsplit('/').next().unwrap_or(&relative);
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem);
+
+        if !self.output_suffix.is_empty() && stem.ends_with(&self.output_suffix.to_lowercase()) {
+            return true;
+        }
+        let matches = |pattern: &String| pattern_matches(pattern, &relative, file_name);
+        let included =
+            self.include.iter().all(|p| p.trim().is_empty()) || self.include.iter().any(matches);
+        !included || self.exclude.iter().any(matches)
+    }
+}
+
+/// Match a FolderFilter wildcard against a lowercase, '/'-separated relative path
+fn pattern_matches(pattern: &str, relative: &str, file_name: &str) -> bool {
+    let pattern = pattern.trim().replace('\\', "/").to_lowercase();
+    if pattern.is_empty() {
+        false
+    } else if let Some(folder) = pattern.strip_suffix('/') {
+        let mut folders = relative.split('/').rev().skip(1);
+        folders.any(|part| wildcard_match(folder, part))
+    } else if pattern.contains('/') {
+        wildcard_match(&pattern, relative)
+    } else {
+        wildcard_match(&pattern, file_name)
+    }
+}
+
+/// `*` matches any run of characters, `?` exactly one
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), text.chars().collect());
+    let (mut p, mut t) = (0, 0);
+    // Position after the last '*' and the text position it is currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p + 1, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last '*' swallow one more character
+            backtrack = Some((star_p, star_t + 1));
+            p = star_p;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Videos inside a folder (recursively), sorted by path
+pub fn folder_videos(folder: &Path, filter: &FolderFilter) -> Vec<String> {
+    videos_in(folder, filter, usize::MAX)
+}
+
+/// Videos inside a folder down to `max_depth` (1 = only the folder itself), sorted by path
+pub fn videos_in(folder: &Path, filter: &FolderFilter, max_depth: usize) -> Vec<String> {
+    let mut files: Vec<String> = walkdir::WalkDir::new(folder)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_video_file(entry.path()))
+        .filter(|entry| {
+            filter.min_size == 0 || entry.metadata().is_ok_and(|m| m.len() >= filter.min_size)
+        })
+        .filter(|entry| {
+            let relative = entry.path().strip_prefix(folder).unwrap_or(entry.path());
+            !filter.excludes(relative)
+        })
+        .map(|entry| entry.path().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Parse launch arguments (without the program name). Relative paths are resolved
+/// against `cwd` when given (arguments handed over from another launch).
+pub fn parse_args(args: Vec<String>, cwd: Option<&Path>, filter: &FolderFilter) -> CliLaunch {
+    let mut launch = CliLaunch::default();
+    // Rebuilding from components also drops "." segments, so "dir/./a.mp4" == "dir/a.mp4"
+    let resolve = |arg: &str| -> PathBuf {
+        match cwd {
+            Some(cwd) => cwd.join(arg).components().collect(),
+            None => Path::new(arg).components().collect(),
+        }
+    };
+
+    let mut args = crate::url_protocol::expand_urls(args).into_iter();
+    while let Some(arg) = args.next() {
+        // --name=value and --name value
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || inline_value.clone().or_else(|| args.next());
+
+        match name {
+            "--preset" => launch.preset = value().filter(|v| !v.is_empty()),
+            "--output-dir" => {
+                launch.output_dir = value()
+                    .filter(|v| !v.is_empty())
+                    .map(|v| resolve(&v).to_string_lossy().to_string())
+            }
+            "--queue-only" => launch.queue_only = true,
+            crate::autostart::MINIMIZED_ARG => launch.minimized = true,
+            // Handled by get_app_data_dir
+            crate::portable::PORTABLE_ARG => {}
+            _ => {
+                let path = resolve(&arg);
+                if path.is_dir() {
+                    launch.files.extend(folder_videos(&path, filter));
+                } else if is_video_file(&path) {
+                    launch.files.push(path.to_string_lossy().to_string());
+                } else {
+                    launch.ignored.push(arg);
+                }
+            }
+        }
+    }
+
+    // A file selected together with its folder is queued once
+    let mut seen = std::collections::HashSet::new();
+    launch.files.retain(|file| seen.insert(file.clone()));
+    launch
+}
+
+/// Files and options this process was launched with
+#[tauri::command]
+pub fn get_cli_launch() -> CliLaunch {
+    parse_args(
+        std::env::args().skip(1).collect(),
+        None,
+        &FolderFilter::from_settings(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_args_options_and_folders() {
+        let dir = std::env::temp_dir().join(format!("szhimatar-cli-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.mp4"), b"x").unwrap();
+        fs::write(dir.join("sub").join("b.MKV"), b"x").unwrap();
+        fs::write(dir.join("notes.txt"), b"x").unwrap();
+
+        let args = [
+            "--preset=Web 720p",
+            "--queue-only",
+            "--output-dir",
+            "out",
+            ".",
+            "a.mp4",
+            "missing.mp4",
+        ];
+        let launch = parse_args(
+            args.iter().map(|a| a.to_string()).collect(),
+            Some(&dir),
+            &FolderFilter::default(),
+        );
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(launch.preset.as_deref(), Some("Web 720p"));
+        assert!(launch.queue_only);
+        assert_eq!(
+            launch.output_dir,
+            Some(dir.join("out").to_string_lossy().to_string())
+        );
+        assert_eq!(launch.files.len(), 2);
+        assert!(launch.files.iter().any(|f| f.ends_with("b.MKV")));
+        assert_eq!(launch.ignored, vec!["missing.mp4".to_string()]);
+    }
+
+    #[test]
+    fn test_folder_filter_excludes() {
+        let filter = FolderFilter {
+            exclude: vec![
+                "*.tmp.mp4".to_string(),
+                "Proxy/".to_string(),
+                "raw/cam?.mov".to_string(),
+            ],
+            output_suffix: "_szhatoe".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.excludes(Path::new("clip.tmp.mp4")));
+        assert!(filter.excludes(Path::new("day1/proxy/clip.mp4")));
+        assert!(filter.excludes(Path::new("raw/cam1.mov")));
+        assert!(filter.excludes(Path::new("clip_szhatoe.mp4")));
+        assert!(!filter.excludes(Path::new("raw/cam10.mov")));
+        assert!(!filter.excludes(Path::new("proxy.mp4")));
+        assert!(wildcard_match("a*b*c", "axxbyyc") && !wildcard_match("a*b", "ac"));
+
+        let only_mov = FolderFilter {
+            include: vec!["*.mov".to_string()],
+            exclude: vec!["raw/".to_string()],
+            ..Default::default()
+        };
+        assert!(!only_mov.excludes(Path::new("day1/clip.MOV")));
+        assert!(only_mov.excludes(Path::new("day1/clip.mp4")));
+        assert!(only_mov.excludes(Path::new("raw/clip.mov")));
+    }
+}
@@ -0,0 +1,265 @@
+// Codec / container compatibility matrix
+// Catches combinations FFmpeg refuses to mux (e.g. AAC in WebM, mov_text in MKV)
+// before arguments are built, and suggests the closest working combination.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CompatibilityResult {
+    /// True when the requested combination can be muxed as-is (possibly with extra_args)
+    pub compatible: bool,
+    /// True when one of the codecs below differs from the request
+    pub adjusted: bool,
+    pub container: String,
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub subtitle_codec: Option<String>,
+    pub warnings: Vec<String>,
+    /// Additional output arguments the combination needs (e.g. "-strict -2")
+    pub extra_args: Vec<String>,
+}
+
+struct ContainerRules {
+    video: &'static [&'static str],
+    audio: &'static [&'static str],
+    subtitles: &'static [&'static str],
+    fallback_video: &'static str,
+    fallback_audio: &'static str,
+    fallback_subtitle: Option<&'static str>,
+}
+
+/// Map encoder names and aliases onto codec names used by the matrix
+pub fn normalize_codec(codec: &str) -> String {
+    let lower = codec.trim().to_lowercase();
+    let normalized = match lower.as_str() {
+        "h265" | "libx265" | "hevc_nvenc" | "hevc_qsv" | "hevc_amf" | "hevc_vaapi" => "hevc",
+        "libx264" | "h264_nvenc" | "h264_qsv" | "h264_amf" | "h264_vaapi" => "h264",
+        "libsvtav1" | "libaom-av1" | "av1_nvenc" | "av1_qsv" | "av1_amf" | "av1_vaapi" => "av1",
+        "libvpx-vp9" | "vp9_qsv" | "vp9_vaapi" => "vp9",
+        "libvpx" => "vp8",
+        "prores_ks" => "prores",
+        "libmp3lame" => "mp3",
+        "libopus" => "opus",
+        "libvorbis" => "vorbis",
+        "subrip" => "srt",
+        "" => "none",
+        other => other,
+    };
+    normalized.to_string()
+}
+
+fn normalize_container(container: &str) -> String {
+    let lower = container.trim().trim_start_matches('.').to_lowercase();
+    match lower.as_str() {
+        "matroska" => "mkv".to_string(),
+        "m4v" => "mp4".to_string(),
+        "mpegts" | "m2ts" => "ts".to_string(),
+        _ => lower,
+    }
+}
+
+fn rules_for(container: &str) -> Option<ContainerRules> {
+    let rules = match container {
+        "mp4" => ContainerRules {
+            video: &["h264", "hevc", "av1", "vp9", "mpeg4"],
+            audio: &["aac", "mp3", "opus", "flac", "ac3", "eac3", "alac"],
+            subtitles: &["mov_text"],
+            fallback_video: "h264",
+            fallback_audio: "aac",
+            fallback_subtitle: Some("mov_text"),
+        },
+        "mov" => ContainerRules {
+            video: &["h264", "hevc", "prores", "mpeg4", "mjpeg"],
+            audio: &["aac", "mp3", "alac", "pcm_s16le", "pcm_s24le", "ac3"],
+            subtitles: &["mov_text"],
+            fallback_video: "h264",
+            fallback_audio: "aac",
+            fallback_subtitle: Some("mov_text"),
+        },
+        "webm" => ContainerRules {
+            video: &["vp9", "vp8", "av1"],
+            audio: &["opus", "vorbis"],
+            subtitles: &["webvtt"],
+            fallback_video: "vp9",
+            fallback_audio: "opus",
+            fallback_subtitle: Some("webvtt"),
+        },
+        "mkv" => ContainerRules {
+            video: &[
+                "h264", "hevc", "av1", "vp9", "vp8", "mpeg4", "prores", "mjpeg", "ffv1",
+            ],
+            audio: &[
+                "aac", "mp3", "opus", "vorbis", "flac", "ac3", "eac3", "dts", "truehd",
+                "alac", "pcm_s16le", "pcm_s24le",
+            ],
+            subtitles: &["srt", "ass", "ssa", "webvtt", "dvd_subtitle", "hdmv_pgs_subtitle"],
+            fallback_video: "h264",
+            fallback_audio: "aac",
+            fallback_subtitle: Some("srt"),
+        },
+        "avi" => ContainerRules {
+            video: &["h264", "mpeg4", "mjpeg"],
+            audio: &["mp3", "ac3", "pcm_s16le", "aac"],
+            subtitles: &[],
+            fallback_video: "mpeg4",
+            fallback_audio: "mp3",
+            fallback_subtitle: None,
+        },
+        "ts" => ContainerRules {
+            video: &["h264", "hevc", "mpeg2video"],
+            audio: &["aac", "mp3", "ac3", "eac3", "opus"],
+            subtitles: &["dvb_subtitle"],
+            fallback_video: "h264",
+            fallback_audio: "aac",
+            fallback_subtitle: None,
+        },
+        _ => return None,
+    };
+    Some(rules)
+}
+
+/// Check (and if needed adjust) a codec combination for the target container
+pub fn check_compatibility(
+    video_codec: &str,
+    audio_codec: &str,
+    subtitle_codec: Option<&str>,
+    container: &str,
+) -> CompatibilityResult {
+    let container = normalize_container(container);
+    let mut result = CompatibilityResult {
+        compatible: true,
+        adjusted: false,
+        container: container.clone(),
+        video_codec: normalize_codec(video_codec),
+        audio_codec: normalize_codec(audio_codec),
+        subtitle_codec: subtitle_codec.map(normalize_codec),
+        warnings: Vec::new(),
+        extra_args: Vec::new(),
+    };
+
+    let Some(rules) = rules_for(&container) else {
+        result
+            .warnings
+            .push(format!("Unknown container '{}', compatibility not checked", container));
+        return result;
+    };
+
+    // "copy" and "none" pass through; the source stream decides
+    let passthrough = |codec: &str| codec == "copy" || codec == "none";
+
+    if !passthrough(&result.video_codec) && !rules.video.contains(&result.video_codec.as_str()) {
+        result.warnings.push(format!(
+            "Video codec '{}' is not supported in {}, using '{}'",
+            result.video_codec, container, rules.fallback_video
+        ));
+        result.video_codec = rules.fallback_video.to_string();
+        result.compatible = false;
+        result.adjusted = true;
+    }
+
+    if !passthrough(&result.audio_codec) && !rules.audio.contains(&result.audio_codec.as_str()) {
+        result.warnings.push(format!(
+            "Audio codec '{}' is not supported in {}, using '{}'",
+            result.audio_codec, container, rules.fallback_audio
+        ));
+        result.audio_codec = rules.fallback_audio.to_string();
+        result.compatible = false;
+        result.adjusted = true;
+    }
+
+    if let Some(subtitle) = result.subtitle_codec.clone() {
+        if !passthrough(&subtitle) && !rules.subtitles.contains(&subtitle.as_str()) {
+            match rules.fallback_subtitle {
+                Some(fallback) => result.warnings.push(format!(
+                    "Subtitle codec '{}' is not supported in {}, using '{}'",
+                    subtitle, container, fallback
+                )),
+                None => result.warnings.push(format!(
+                    "{} cannot carry subtitles, subtitle stream will be dropped",
+                    container
+                )),
+            }
+            result.subtitle_codec = rules.fallback_subtitle.map(|s| s.to_string());
+            result.compatible = false;
+            result.adjusted = true;
+        }
+    }
+
+    // Combinations that mux only with extra flags
+    if container == "mp4" && result.audio_codec == "flac" {
+        result
+            .warnings
+            .push("FLAC in MP4 needs '-strict experimental' on older FFmpeg builds".to_string());
+        result.extra_args.extend(["-strict".to_string(), "-2".to_string()]);
+    }
+    if container == "mp4" && result.video_codec == "hevc" {
+        // Apple players refuse hev1-tagged HEVC
+        result.extra_args.extend(["-tag:v".to_string(), "hvc1".to_string()]);
+    }
+    if container == "avi" && result.audio_codec == "aac" {
+        result
+            .warnings
+            .push("AAC in AVI is poorly supported by players, MP3 is recommended".to_string());
+    }
+
+    result
+}
+
+/// Whether `container` can carry an audio stream of `codec` as-is (unknown containers
+/// carry nothing)
+pub fn container_accepts_audio(container: &str, codec: &str) -> bool {
+    rules_for(&normalize_container(container))
+        .is_some_and(|rules| rules.audio.contains(&normalize_codec(codec).as_str()))
+}
+
+/// Validate a codec/container combination before building FFmpeg arguments
+#[tauri::command]
+pub fn validate_codec_container(
+    video_codec: String,
+    audio_codec: String,
+    container: String,
+    subtitle_codec: Option<String>,
+) -> CompatibilityResult {
+    check_compatibility(
+        &video_codec,
+        &audio_codec,
+        subtitle_codec.as_deref(),
+        &container,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webm_adjusts_to_vp9_opus() {
+        let result = check_compatibility("h264", "aac", None, "webm");
+        assert!(!result.compatible);
+        assert!(result.adjusted);
+        assert_eq!(result.video_codec, "vp9");
+        assert_eq!(result.audio_codec, "opus");
+    }
+
+    #[test]
+    fn test_mp4_flac_needs_strict() {
+        let result = check_compatibility("libx264", "flac", None, ".mp4");
+        assert!(result.compatible);
+        assert_eq!(result.extra_args, vec!["-strict", "-2"]);
+    }
+
+    #[test]
+    fn test_container_accepts_surround_audio() {
+        assert!(container_accepts_audio("mkv", "dts"));
+        assert!(container_accepts_audio(".mp4", "eac3"));
+        assert!(!container_accepts_audio("mp4", "dts"));
+        assert!(!container_accepts_audio("webm", "ac3"));
+    }
+
+    #[test]
+    fn test_mkv_rejects_mov_text() {
+        let result = check_compatibility("hevc", "copy", Some("mov_text"), "mkv");
+        assert!(!result.compatible);
+        assert_eq!(result.subtitle_codec.as_deref(), Some("srt"));
+    }
+}
@@ -0,0 +1,215 @@
+// Color adjustment
+// A .cube LUT (lut3d) and brightness / contrast / saturation (eq) applied in the encode.
+// Both go first in the -vf chain, so they grade the source frames before scaling and
+// the final pixel format conversion. The LUT file is parsed before FFmpeg is spawned:
+// lut3d only reports a broken file once the encode has started.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest LUT_3D_SIZE accepted (lut3d's own limit)
+const MAX_LUT_SIZE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorOptions {
+    /// .cube file applied with lut3d (none when empty)
+    pub lut_path: String,
+    /// -1.0 - 1.0, 0 = unchanged
+    pub brightness: f64,
+    /// 0.0 - 2.0, 1 = unchanged
+    pub contrast: f64,
+    /// 0.0 - 3.0, 1 = unchanged
+    pub saturation: f64,
+}
+
+impl Default for ColorOptions {
+    fn default() -> Self {
+        Self {
+            lut_path: String::new(),
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+        }
+    }
+}
+
+/// What a parsed .cube file holds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LutInfo {
+    pub title: Option<String>,
+    /// Points per axis (the table has size³ entries)
+    pub size: usize,
+}
+
+impl ColorOptions {
+    fn in_range(value: f64, min: f64, max: f64) -> bool {
+        value.is_finite() && (min..=max).contains(&value)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !Self::in_range(self.brightness, -1.0, 1.0) {
+            return Err("Brightness must be between -1 and 1".to_string());
+        }
+        if !Self::in_range(self.contrast, 0.0, 2.0) {
+            return Err("Contrast must be between 0 and 2".to_string());
+        }
+        if !Self::in_range(self.saturation, 0.0, 3.0) {
+            return Err("Saturation must be between 0 and 3".to_string());
+        }
+        if !self.lut_path.trim().is_empty() {
+            read_lut(Path::new(self.lut_path.trim()))?;
+        }
+        Ok(())
+    }
+
+    fn has_eq(&self) -> bool {
+        self.brightness != 0.0 || self.contrast != 1.0 || self.saturation != 1.0
+    }
+}
+
+/// Check a .cube file: a LUT_3D_SIZE header followed by exactly size³ RGB triplets
+pub fn parse_cube(text: &str) -> Result<LutInfo, String> {
+    let mut title = None;
+    let mut size = None;
+    let mut entries = 0usize;
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let number = index + 1;
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "TITLE" => {
+                title = Some(line["TITLE".len()..].trim().trim_matches('"').to_string());
+            }
+            "LUT_3D_SIZE" => {
+                let value = line["LUT_3D_SIZE".len()..]
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (2..=MAX_LUT_SIZE).contains(n))
+                    .ok_or_else(|| format!("Line {}: invalid LUT_3D_SIZE", number))?;
+                if entries > 0 {
+                    return Err(format!("Line {}: LUT_3D_SIZE after the table", number));
+                }
+                size = Some(value);
+            }
+            "LUT_1D_SIZE" => {
+                return Err("1D LUTs are not supported, use a 3D .cube file".to_string());
+            }
+            "DOMAIN_MIN" | "DOMAIN_MAX" | "LUT_3D_INPUT_RANGE" => {}
+            _ => {
+                let values: Vec<&str> = line.split_whitespace().collect();
+                if values.len() != 3 || values.iter().any(|v| v.parse::<f64>().is_err()) {
+                    return Err(format!("Line {}: expected three numbers", number));
+                }
+                entries += 1;
+            }
+        }
+    }
+
+    let size = size.ok_or("The LUT has no LUT_3D_SIZE")?;
+    if entries != size.pow(3) {
+        return Err(format!(
+            "The LUT has {} entries, LUT_3D_SIZE {} needs {}",
+            entries,
+            size,
+            size.pow(3)
+        ));
+    }
+    Ok(LutInfo { title, size })
+}
+
+fn read_lut(path: &Path) -> Result<LutInfo, String> {
+    if !path.is_file() {
+        return Err(format!("LUT file not found: {}", path.display()));
+    }
+    let is_cube = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cube"));
+    if !is_cube {
+        return Err("Only .cube LUT files are supported".to_string());
+    }
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read the LUT file: {}", e))?;
+    parse_cube(&text).map_err(|e| format!("Invalid LUT file: {}", e))
+}
+
+/// Put the LUT and eq filters at the start of the -vf chain (nothing when `options`
+/// leave the picture unchanged)
+pub fn apply_color(args: &mut Vec<String>, options: &ColorOptions) -> Result<(), String> {
+    let lut_path = options.lut_path.trim();
+    let mut filters = Vec::new();
+    if !lut_path.is_empty() {
+        filters.push(format!(
+            "lut3d=file={}",
+            crate::encode_args::filter_path(Path::new(lut_path))
+        ));
+    }
+    if options.has_eq() {
+        filters.push(format!(
+            "eq=brightness={:.3}:contrast={:.3}:saturation={:.3}",
+            options.brightness, options.contrast, options.saturation
+        ));
+    }
+    if filters.is_empty() {
+        return Ok(());
+    }
+    if crate::encode_args::video_encoder_in(args) == Some("copy") {
+        return Err("Color adjustments cannot be used with video codec \"copy\"".to_string());
+    }
+    crate::encode_args::prepend_video_filter(args, &filters.join(","));
+    Ok(())
+}
+
+/// Parse a .cube file for the settings page (the same check a render does)
+#[tauri::command]
+pub fn validate_lut(path: String) -> Result<LutInfo, String> {
+    read_lut(Path::new(path.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cube() {
+        let mut cube = String::from("# comment\nTITLE \"Warm\"\nLUT_3D_SIZE 2\n");
+        cube.push_str("DOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n");
+        for i in 0..8 {
+            cube.push_str(&format!("{} 0.5 1.0e0\n", i as f64 / 8.0));
+        }
+        let info = parse_cube(&cube).unwrap();
+        assert_eq!(info.title.as_deref(), Some("Warm"));
+        assert_eq!(info.size, 2);
+
+        assert!(parse_cube(&cube.replace("LUT_3D_SIZE 2", "LUT_3D_SIZE 3")).is_err());
+        assert!(parse_cube(&cube.replace("0.5 1.0e0", "0.5")).is_err());
+        assert!(parse_cube("LUT_1D_SIZE 4\n").is_err());
+        assert!(parse_cube("0 0 0\n").is_err());
+    }
+
+    #[test]
+    fn test_apply_color() {
+        let mut args: Vec<String> = ["-c:v", "libx264", "-vf", "scale=1280:720"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let options = ColorOptions {
+            saturation: 1.2,
+            ..Default::default()
+        };
+        apply_color(&mut args, &options).unwrap();
+        assert_eq!(
+            args[3],
+            "eq=brightness=0.000:contrast=1.000:saturation=1.200,scale=1280:720"
+        );
+
+        let mut unchanged = vec!["-c:v".to_string(), "copy".to_string()];
+        apply_color(&mut unchanged, &ColorOptions::default()).unwrap();
+        assert_eq!(unchanged.len(), 2);
+        assert!(apply_color(&mut unchanged, &options).is_err());
+    }
+}
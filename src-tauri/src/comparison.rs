@@ -0,0 +1,184 @@
+// Comparison clips
+// generate_comparison renders the same stretch of the original and an encode of it
+// into one clip, so users can judge a preset on their own footage instead of trusting
+// a VMAF number. The encode is scaled to the original's frame size; both are cut at
+// the same timestamps, so the encode must not have been trimmed.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Longest comparison clip
+const MAX_DURATION_SECONDS: f64 = 120.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonMode {
+    /// Left half of the original next to the right half of the encode, with a divider
+    Split,
+    /// Both full frames next to each other (twice the width)
+    SideBySide,
+    /// The encode wipes over the original from left to right during the clip
+    Wipe,
+}
+
+impl ComparisonMode {
+    fn suffix(self) -> &'static str {
+        match self {
+            ComparisonMode::Split => "split",
+            ComparisonMode::SideBySide => "side-by-side",
+            ComparisonMode::Wipe => "wipe",
+        }
+    }
+}
+
+/// Filtergraph from the original (input 0) and the encode (input 1) to [v]
+fn comparison_filter(mode: ComparisonMode, width: u32, height: u32, duration: f64) -> String {
+    let prepare = |input: u32, label: &str| {
+        format!(
+            "[{}:v:0]scale={}:{},setsar=1,format=yuv420p[{}]",
+            input, width, height, label
+        )
+    };
+    let inputs = format!("{};{}", prepare(0, "a"), prepare(1, "b"));
+    match mode {
+        ComparisonMode::Split => {
+            // Even widths for yuv420p
+            let half = (width / 2) & !1;
+            format!(
+                "{};[a]crop={}:{}:0:0[l];[b]crop={}:{}:{}:0[r];[l][r]hstack,\
+                 drawbox=x={}:y=0:w=2:h={}:color=white@0.8:t=fill[v]",
+                inputs,
+                half,
+                height,
+                width - half,
+                height,
+                half,
+                half.saturating_sub(1),
+                height
+            )
+        }
+        ComparisonMode::SideBySide => format!("{};[a][b]hstack[v]", inputs),
+        ComparisonMode::Wipe => format!(
+            "{};[a][b]blend=all_expr='if(lte(X,W*T/{:.3}),B,A)'[v]",
+            inputs,
+            duration.max(0.001)
+        ),
+    }
+}
+
+/// "<encoded stem>.compare-<mode>.mp4" next to the encode
+fn default_output(encoded: &Path, mode: ComparisonMode) -> std::path::PathBuf {
+    let stem = encoded.file_stem().unwrap_or_default().to_string_lossy();
+    encoded.with_file_name(format!("{}.compare-{}.mp4", stem, mode.suffix()))
+}
+
+/// Render a comparison clip of `duration_seconds` from `start_seconds` and return its path
+#[tauri::command]
+pub async fn generate_comparison(
+    original: String,
+    encoded: String,
+    mode: ComparisonMode,
+    start_seconds: f64,
+    duration_seconds: f64,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() || config.ffprobe_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    let start = start_seconds.max(0.0);
+    let duration = duration_seconds.clamp(0.1, MAX_DURATION_SECONDS);
+
+    let probe = crate::media_cache::probe(&config.ffprobe_path, &original)?;
+    let video = probe["streams"]
+        .as_array()
+        .and_then(|streams| streams.iter().find(|s| s["codec_type"] == "video"))
+        .ok_or("The original has no video stream")?;
+    let (width, height) = (
+        video["width"].as_u64().unwrap_or(0) as u32,
+        video["height"].as_u64().unwrap_or(0) as u32,
+    );
+    if width == 0 || height == 0 {
+        return Err("Could not read the frame size of the original".to_string());
+    }
+
+    let output = output_path
+        .filter(|path| !path.trim().is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| default_output(Path::new(&encoded), mode));
+    let start_arg = format!("{:.3}", start);
+    let duration_arg = format!("{:.3}", duration);
+    let result = crate::proc::run_captured(
+        crate::proc::command(&config.ffmpeg_path)
+            .args(["-hide_banner", "-v", "error", "-y"])
+            .args(["-ss", &start_arg, "-t", &duration_arg, "-i", &original])
+            .args(["-ss", &start_arg, "-t", &duration_arg, "-i", &encoded])
+            .args([
+                "-filter_complex",
+                &comparison_filter(mode, width, height, duration),
+                "-map",
+                "[v]",
+                "-an",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "fast",
+                "-crf",
+                "16",
+                "-movflags",
+                "+faststart",
+            ])
+            .arg(&output),
+        &crate::proc::RunOptions::with_timeout(60 + duration as u64 * 30).label("comparison"),
+    )?;
+    if result.timed_out {
+        return Err("Comparison render timed out".to_string());
+    }
+    if !result.success {
+        let _ = std::fs::remove_file(&output);
+        let error = result
+            .stderr
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        return Err(format!("Comparison render failed: {}", error));
+    }
+
+    let output = output.to_string_lossy().to_string();
+    let _ = crate::write_log(format!(
+        "[COMPARISON] {:?} {} vs {} ({}s from {}s) -> {}",
+        mode, original, encoded, duration, start, output
+    ));
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_filter() {
+        let split = comparison_filter(ComparisonMode::Split, 1920, 1080, 10.0);
+        assert!(split.contains("[a]crop=960:1080:0:0[l];[b]crop=960:1080:960:0[r]"));
+        assert!(split.contains("drawbox=x=959"));
+        // Odd halves are rounded down to even widths
+        assert!(comparison_filter(ComparisonMode::Split, 1278, 720, 10.0).contains("crop=638:720"));
+        assert!(
+            comparison_filter(ComparisonMode::SideBySide, 1280, 720, 10.0)
+                .ends_with("[a][b]hstack[v]")
+        );
+        assert!(comparison_filter(ComparisonMode::Wipe, 1280, 720, 8.0)
+            .contains("if(lte(X,W*T/8.000),B,A)"));
+    }
+
+    #[test]
+    fn test_default_output() {
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            default_output(&dir.join("clip.mkv"), ComparisonMode::SideBySide),
+            dir.join("clip.compare-side-by-side.mp4")
+        );
+    }
+}
@@ -0,0 +1,272 @@
+// Per-title complexity
+// A static screencast and an action clip look equally good at very different CRFs.
+// analyze_complexity encodes a few short probes of the source with libx264 at
+// REFERENCE_CRF, measures their bitrate and SSIM against the source, and moves the
+// CRF until the predicted SSIM reaches TARGET_SSIM_DB. The model is deliberately
+// rough (x264 roughly halves the bitrate every 6 CRF steps); it picks a starting
+// point, not an exact value.
+
+use serde::Serialize;
+
+/// CRF the probes are encoded at
+pub const REFERENCE_CRF: u32 = 23;
+/// Quality aimed for: SSIM 0.98 in decibels
+const TARGET_SSIM_DB: f64 = 17.0;
+/// SSIM lost per CRF step around the reference
+const SSIM_DB_PER_CRF: f64 = 0.4;
+const MIN_CRF: u32 = 18;
+const MAX_CRF: u32 = 30;
+const PROBE_COUNT: usize = 3;
+const PROBE_SECONDS: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Complexity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplexityProbe {
+    pub start_seconds: f64,
+    pub bitrate_kbps: f64,
+    pub ssim: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplexityReport {
+    pub reference_crf: u32,
+    pub probes: Vec<ComplexityProbe>,
+    pub average_bitrate_kbps: f64,
+    pub average_ssim: f64,
+    /// Bits per pixel per frame at the reference CRF
+    pub bits_per_pixel: f64,
+    pub complexity: Complexity,
+    pub recommended_crf: u32,
+    /// Predicted libx264 bitrate at the recommended CRF
+    pub recommended_bitrate_kbps: f64,
+}
+
+/// SSIM (0..1) in decibels, the scale on which it changes about linearly with CRF
+fn ssim_db(ssim: f64) -> f64 {
+    -10.0 * (1.0 - ssim.min(0.999_999)).log10()
+}
+
+/// Start times of the probes, spread over the source
+fn probe_starts(duration: f64) -> Vec<f64> {
+    if duration <= PROBE_SECONDS * PROBE_COUNT as f64 {
+        return vec![0.0];
+    }
+    (1..=PROBE_COUNT)
+        .map(|i| duration * i as f64 / (PROBE_COUNT + 1) as f64 - PROBE_SECONDS / 2.0)
+        .collect()
+}
+
+/// "All:0.985123" of the ssim filter's summary line
+fn parse_ssim(stderr: &str) -> Option<f64> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("SSIM"))
+        .find_map(|line| line.split("All:").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse::<f64>().ok())
+}
+
+fn classify(bits_per_pixel: f64) -> Complexity {
+    if bits_per_pixel < 0.03 {
+        Complexity::Low
+    } else if bits_per_pixel < 0.1 {
+        Complexity::Medium
+    } else {
+        Complexity::High
+    }
+}
+
+/// CRF predicted to reach TARGET_SSIM_DB and the bitrate expected at it
+fn recommend(average_ssim: f64, average_bitrate_kbps: f64) -> (u32, f64) {
+    let steps = (ssim_db(average_ssim) - TARGET_SSIM_DB) / SSIM_DB_PER_CRF;
+    let crf = (REFERENCE_CRF as f64 + steps)
+        .round()
+        .clamp(MIN_CRF as f64, MAX_CRF as f64) as u32;
+    let bitrate = average_bitrate_kbps * 2f64.powf((REFERENCE_CRF as f64 - crf as f64) / 6.0);
+    (crf, bitrate)
+}
+
+/// Encode one probe and compare it with the same stretch of the source
+fn run_probe(
+    config: &crate::FfmpegConfig,
+    input: &str,
+    start: f64,
+    index: usize,
+) -> Result<ComplexityProbe, String> {
+    let encoded = std::env::temp_dir().join(format!(
+        "szhimatar-complexity-{}-{}.mkv",
+        std::process::id(),
+        index
+    ));
+    let start_arg = format!("{:.3}", start);
+    let length_arg = format!("{:.3}", PROBE_SECONDS);
+    let encode = crate::proc::run_captured(
+        crate::proc::command(&config.ffmpeg_path)
+            .args([
+                "-hide_banner",
+                "-v",
+                "error",
+                "-y",
+                "-ss",
+                &start_arg,
+                "-t",
+                &length_arg,
+                "-i",
+                input,
+                "-map",
+                "0:v:0",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "veryfast",
+                "-crf",
+                &REFERENCE_CRF.to_string(),
+                "-an",
+                "-sn",
+            ])
+            .arg(&encoded),
+        &crate::proc::RunOptions::with_timeout(300).label("complexity probe"),
+    );
+    let measured = encode.and_then(|output| {
+        if !output.success {
+            let error = output
+                .stderr
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            return Err(format!("Probe encode failed: {}", error));
+        }
+        let size = std::fs::metadata(&encoded).map(|m| m.len()).unwrap_or(0);
+        let length =
+            crate::verify::probe_duration(&config.ffprobe_path, &encoded.to_string_lossy())
+                .map(|d| d.as_secs_f64())
+                .filter(|d| *d > 0.0)
+                .unwrap_or(PROBE_SECONDS);
+
+        let compare = crate::proc::run_captured(
+            crate::proc::command(&config.ffmpeg_path)
+                .args(["-hide_banner", "-nostats", "-i"])
+                .arg(&encoded)
+                .args(["-ss", &start_arg, "-t", &length_arg, "-i", input])
+                .args(["-lavfi", "[0:v][1:v:0]ssim", "-f", "null", "-"]),
+            &crate::proc::RunOptions::with_timeout(300).label("complexity ssim"),
+        )?;
+        let ssim = parse_ssim(&compare.stderr).ok_or("SSIM measurement failed")?;
+        Ok(ComplexityProbe {
+            start_seconds: start,
+            bitrate_kbps: size as f64 * 8.0 / 1000.0 / length,
+            ssim,
+        })
+    });
+    let _ = std::fs::remove_file(&encoded);
+    measured
+}
+
+/// Measure how hard the source is to compress and recommend a CRF for it
+#[tauri::command]
+pub async fn analyze_complexity(input: String) -> Result<ComplexityReport, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() || config.ffprobe_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    if !crate::encode_args::available_encoder_names()
+        .iter()
+        .any(|name| name == "libx264")
+    {
+        return Err("Complexity analysis needs an FFmpeg build with libx264".to_string());
+    }
+
+    let probe = crate::media_cache::probe(&config.ffprobe_path, &input)?;
+    let video = probe["streams"]
+        .as_array()
+        .and_then(|streams| streams.iter().find(|s| s["codec_type"] == "video"))
+        .ok_or("The file has no video stream")?;
+    let pixels = video["width"].as_f64().unwrap_or(0.0) * video["height"].as_f64().unwrap_or(0.0);
+    let fps =
+        crate::library::parse_frame_rate(video["avg_frame_rate"].as_str().unwrap_or_default());
+    let duration = probe["format"]["duration"]
+        .as_str()
+        .and_then(|d| d.parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    let probes = probe_starts(duration)
+        .into_iter()
+        .enumerate()
+        .map(|(index, start)| run_probe(&config, &input, start, index))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let count = probes.len() as f64;
+    let average_bitrate_kbps = probes.iter().map(|p| p.bitrate_kbps).sum::<f64>() / count;
+    let average_ssim = probes.iter().map(|p| p.ssim).sum::<f64>() / count;
+    let bits_per_pixel = if pixels > 0.0 && fps > 0.0 {
+        average_bitrate_kbps * 1000.0 / (pixels * fps)
+    } else {
+        0.0
+    };
+    let (recommended_crf, recommended_bitrate_kbps) = recommend(average_ssim, average_bitrate_kbps);
+    let report = ComplexityReport {
+        reference_crf: REFERENCE_CRF,
+        probes,
+        average_bitrate_kbps,
+        average_ssim,
+        bits_per_pixel,
+        complexity: classify(bits_per_pixel),
+        recommended_crf,
+        recommended_bitrate_kbps,
+    };
+    let _ = crate::write_log(format!(
+        "[COMPLEXITY] {}: {:.0} kbps, SSIM {:.4}, {:.3} bpp -> {:?}, CRF {}",
+        input,
+        report.average_bitrate_kbps,
+        report.average_ssim,
+        report.bits_per_pixel,
+        report.complexity,
+        report.recommended_crf
+    ));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ssim() {
+        let stderr = "frame=  100 fps=0.0 q=-0.0 size=N/A\n\
+            [Parsed_ssim_0 @ 0x5581] SSIM Y:0.982310 (17.523) U:0.990 (20.0) \
+            V:0.991 (20.4) All:0.985123 (18.275)\n";
+        assert_eq!(parse_ssim(stderr), Some(0.985123));
+        assert_eq!(parse_ssim("no summary"), None);
+    }
+
+    #[test]
+    fn test_probe_starts() {
+        assert_eq!(probe_starts(10.0), vec![0.0]);
+        assert_eq!(probe_starts(400.0), vec![98.0, 198.0, 298.0]);
+    }
+
+    #[test]
+    fn test_recommend() {
+        // At the target quality the reference CRF stays
+        let (crf, bitrate) = recommend(0.98, 2000.0);
+        assert_eq!(crf, REFERENCE_CRF);
+        assert!((bitrate - 2000.0).abs() < 1e-9);
+        // An easy screencast gets a higher CRF and less bitrate
+        let (crf, bitrate) = recommend(0.995, 300.0);
+        assert_eq!(crf, MAX_CRF);
+        assert!(bitrate < 300.0);
+        // A hard clip gets a lower CRF
+        assert!(recommend(0.96, 8000.0).0 < REFERENCE_CRF);
+        assert_eq!(classify(0.01), Complexity::Low);
+        assert_eq!(classify(0.2), Complexity::High);
+    }
+}
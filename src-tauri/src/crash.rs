@@ -0,0 +1,247 @@
+// Crash reports
+// A panic hook and a check on how FFmpeg exited (access violation / SIGSEGV and the like)
+// write JSON reports with the message, backtrace and the end of the relevant log to
+// ~/.szhimatar/crashes/. With the showCrashReports setting the frontend lists the
+// reports it has not shown yet on the next launch.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How much of the end of the log goes into a report
+const LOG_TAIL_BYTES: u64 = 32 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// File name without extension; filled in when listing
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// "panic" or "ffmpeg"
+    pub kind: String,
+    /// RFC 3339 local time
+    pub timestamp: String,
+    pub app_version: String,
+    pub message: String,
+    #[serde(default)]
+    pub backtrace: Option<String>,
+    /// Render job of a crashed FFmpeg process
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// End of app.log (panics) or of the render log (FFmpeg crashes)
+    #[serde(default)]
+    pub log_tail: String,
+    /// Already shown to the user at launch
+    #[serde(default)]
+    pub seen: bool,
+}
+
+fn crashes_dir() -> PathBuf {
+    crate::get_app_data_dir().join("crashes")
+}
+
+fn log_tail(path: PathBuf) -> String {
+    crate::system_info::read_tail(&path, LOG_TAIL_BYTES)
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        .unwrap_or_default()
+}
+
+fn new_report(kind: &str, message: String) -> CrashReport {
+    CrashReport {
+        id: String::new(),
+        kind: kind.to_string(),
+        timestamp: chrono::Local::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        message,
+        backtrace: None,
+        job_id: None,
+        log_tail: String::new(),
+        seen: false,
+    }
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = crashes_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let stem = format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        report.kind
+    );
+    let mut path = dir.join(format!("{}.json", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.json", stem, n));
+        n += 1;
+    }
+    let content = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Write a report for every panic, then run the default hook (which prints it)
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let location = info
+            .location()
+            .map(|l| format!(" at {}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_default();
+        let thread = std::thread::current();
+
+        let mut report = new_report(
+            "panic",
+            format!(
+                "thread '{}' panicked{}: {}",
+                thread.name().unwrap_or("<unnamed>"),
+                location,
+                message
+            ),
+        );
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        report.log_tail = log_tail(crate::get_app_data_dir().join("logs").join("app.log"));
+        let _ = write_report(&report);
+
+        default_hook(info);
+    }));
+}
+
+/// What went wrong when a process exit looks like a crash rather than an error exit:
+/// a fatal signal on Unix or an NTSTATUS exception code on Windows
+pub fn describe_crash(code: Option<i32>, signal: Option<i32>) -> Option<String> {
+    if let Some(signal) = signal {
+        let sigbus = if cfg!(target_os = "macos") { 10 } else { 7 };
+        let name = match signal {
+            4 => "SIGILL",
+            6 => "SIGABRT",
+            8 => "SIGFPE",
+            11 => "SIGSEGV",
+            s if s == sigbus => "SIGBUS",
+            // SIGKILL / SIGTERM come from stopping the render (or the user)
+            _ => return None,
+        };
+        return Some(format!("terminated by {} (signal {})", name, signal));
+    }
+
+    let status = code? as u32;
+    let name = match status {
+        0xC000_0005 => "access violation",
+        0xC000_001D => "illegal instruction",
+        0xC000_0094 => "integer division by zero",
+        0xC000_00FD => "stack overflow",
+        0xC000_0374 => "heap corruption",
+        0xC000_0409 => "stack buffer overrun",
+        s if s & 0xF000_0000 == 0xC000_0000 => "unhandled exception",
+        _ => return None,
+    };
+    Some(format!("{} (0x{:08X})", name, status))
+}
+
+/// Record a crashed FFmpeg render; `description` comes from describe_crash
+pub fn record_ffmpeg_crash(job_id: &str, input_path: &str, description: &str) {
+    let mut report = new_report(
+        "ffmpeg",
+        format!("FFmpeg crashed rendering {}: {}", input_path, description),
+    );
+    report.job_id = Some(job_id.to_string());
+    report.log_tail = log_tail(
+        crate::get_app_data_dir()
+            .join("logs")
+            .join("renders")
+            .join(format!("{}.log", job_id)),
+    );
+    match write_report(&report) {
+        Ok(path) => {
+            let _ = crate::write_log(format!("[CRASH] {} -> {}", report.message, path.display()));
+        }
+        Err(e) => {
+            let _ = crate::write_log(format!("[CRASH] Failed to write report: {}", e));
+        }
+    }
+}
+
+fn read_reports() -> Vec<(PathBuf, CrashReport)> {
+    let mut reports: Vec<(PathBuf, CrashReport)> = std::fs::read_dir(crashes_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|path| {
+                    let content = std::fs::read_to_string(&path).ok()?;
+                    let mut report: CrashReport = serde_json::from_str(&content).ok()?;
+                    report.id = path.file_stem()?.to_string_lossy().to_string();
+                    Some((path, report))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Ids start with the timestamp
+    reports.sort_by(|a, b| b.1.id.cmp(&a.1.id));
+    reports
+}
+
+/// Crash reports, newest first
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashReport> {
+    read_reports()
+        .into_iter()
+        .map(|(_, report)| report)
+        .collect()
+}
+
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> Result<(), String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid crash report id: {}", id));
+    }
+    std::fs::remove_file(crashes_dir().join(format!("{}.json", id)))
+        .map_err(|e| format!("Failed to delete crash report: {}", e))
+}
+
+/// Called once the launch dialog showed the reports
+#[tauri::command]
+pub fn mark_crash_reports_seen() -> Result<(), String> {
+    for (path, mut report) in read_reports() {
+        if report.seen {
+            continue;
+        }
+        report.seen = true;
+        report.id.clear();
+        let content = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_crash() {
+        assert_eq!(
+            describe_crash(Some(0xC000_0005_u32 as i32), None).as_deref(),
+            Some("access violation (0xC0000005)")
+        );
+        assert_eq!(
+            describe_crash(None, Some(11)).as_deref(),
+            Some("terminated by SIGSEGV (signal 11)")
+        );
+        // Stopped by the user / ordinary error exits are not crashes
+        assert_eq!(describe_crash(None, Some(9)), None);
+        assert_eq!(describe_crash(None, Some(15)), None);
+        assert_eq!(describe_crash(Some(1), None), None);
+        assert_eq!(describe_crash(Some(-22), None), None);
+        assert_eq!(describe_crash(None, None), None);
+    }
+}
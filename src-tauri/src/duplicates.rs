@@ -0,0 +1,216 @@
+// Duplicate job detection
+// Adding a folder twice, or a file that was compressed yesterday, used to render it
+// again without a word. Before queueing, the frontend asks which inputs are already
+// queued, rendering, or were compressed successfully within RECENT_DAYS, matched by
+// path or, for copies under another name, by a quick content fingerprint, and lets
+// the user skip them or re-encode anyway.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Successful renders younger than this count as duplicates
+const RECENT_DAYS: i64 = 7;
+
+/// Job of the frontend queue
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueuedInput {
+    pub input_path: String,
+    /// Frontend RenderStatus
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    Running,
+    Queued,
+    RecentlyCompressed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateConflict {
+    pub input_path: String,
+    pub kind: DuplicateKind,
+    /// Input of the job or history entry it collides with
+    pub existing_path: String,
+    /// Matched by content rather than by path
+    pub same_content: bool,
+    /// Output of the earlier render (recently_compressed only)
+    pub output_path: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// Job or finished render an input is compared against
+struct Existing {
+    path: String,
+    kind: DuplicateKind,
+    output_path: Option<String>,
+    finished_at: Option<String>,
+}
+
+/// Comparable form of a path: resolved when it exists, case-insensitive on Windows
+fn normalized(path: &str) -> PathBuf {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    if cfg!(windows) {
+        PathBuf::from(path.to_string_lossy().to_lowercase())
+    } else {
+        path
+    }
+}
+
+fn file_size(path: &str) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+/// Fingerprints computed during one check, by path
+#[derive(Default)]
+struct Fingerprints(HashMap<String, Option<String>>);
+
+impl Fingerprints {
+    fn get(&mut self, path: &str) -> Option<String> {
+        self.0
+            .entry(path.to_string())
+            .or_insert_with(|| crate::hash::quick_fingerprint(Path::new(path)).ok())
+            .clone()
+    }
+}
+
+/// First collision of `input` with `existing` (ordered by precedence): by path, else
+/// by content for files of the same size
+fn find_conflict(
+    input: &str,
+    existing: &[Existing],
+    fingerprints: &mut Fingerprints,
+) -> Option<DuplicateConflict> {
+    let input_path = normalized(input);
+    let by_path = existing
+        .iter()
+        .find(|e| normalized(&e.path) == input_path)
+        .map(|e| (e, false));
+    let found = by_path.or_else(|| {
+        let size = file_size(input)?;
+        existing
+            .iter()
+            .filter(|e| file_size(&e.path) == Some(size))
+            .find(|e| {
+                let theirs = fingerprints.get(&e.path);
+                theirs.is_some() && theirs == fingerprints.get(input)
+            })
+            .map(|e| (e, true))
+    });
+    found.map(|(e, same_content)| DuplicateConflict {
+        input_path: input.to_string(),
+        kind: e.kind,
+        existing_path: e.path.clone(),
+        same_content,
+        output_path: e.output_path.clone(),
+        finished_at: e.finished_at.clone(),
+    })
+}
+
+fn existing_jobs(queued: Vec<QueuedInput>) -> Vec<Existing> {
+    let queue_entry = |job: &QueuedInput, kind| Existing {
+        path: job.input_path.clone(),
+        kind,
+        output_path: None,
+        finished_at: None,
+    };
+    let mut existing: Vec<Existing> = queued
+        .iter()
+        .filter(|job| job.status == "processing")
+        .map(|job| queue_entry(job, DuplicateKind::Running))
+        .collect();
+    existing.extend(
+        queued
+            .iter()
+            .filter(|job| {
+                matches!(
+                    job.status.as_str(),
+                    "pending" | "waiting-for-file" | "paused"
+                )
+            })
+            .map(|job| queue_entry(job, DuplicateKind::Queued)),
+    );
+
+    let since = chrono::Local::now() - chrono::Duration::days(RECENT_DAYS);
+    let recent = crate::history::list_job_history(Some(crate::history::HistoryFilter {
+        status: Some("success".to_string()),
+        since: Some(since.to_rfc3339()),
+        ..Default::default()
+    }));
+    existing.extend(recent.into_iter().map(|entry| Existing {
+        path: entry.job.input_path,
+        kind: DuplicateKind::RecentlyCompressed,
+        output_path: Some(entry.job.output_path),
+        finished_at: Some(entry.finished_at),
+    }));
+    existing
+}
+
+/// Inputs about to be queued that are already queued, rendering or were compressed
+/// recently. `queued` is the current frontend queue.
+#[tauri::command]
+pub async fn check_duplicate_jobs(
+    input_paths: Vec<String>,
+    queued: Vec<QueuedInput>,
+) -> Vec<DuplicateConflict> {
+    let existing = existing_jobs(queued);
+    let mut fingerprints = Fingerprints::default();
+    let conflicts: Vec<DuplicateConflict> = input_paths
+        .iter()
+        .filter_map(|input| find_conflict(input, &existing, &mut fingerprints))
+        .collect();
+    if !conflicts.is_empty() {
+        let _ = crate::write_log(format!(
+            "[DUPLICATES] {} of {} input(s) already queued or compressed",
+            conflicts.len(),
+            input_paths.len()
+        ));
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(path: &str, kind: DuplicateKind) -> Existing {
+        Existing {
+            path: path.to_string(),
+            kind,
+            output_path: None,
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn test_find_conflict() {
+        let dir = std::env::temp_dir().join("szhimatar-duplicates-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("original.mp4");
+        let copy = dir.join("copy.mp4");
+        let other = dir.join("other.mp4");
+        std::fs::write(&original, b"same content").unwrap();
+        std::fs::write(&copy, b"same content").unwrap();
+        std::fs::write(&other, b"other bytes!").unwrap();
+        let path = |p: &PathBuf| p.to_string_lossy().to_string();
+
+        let jobs = [
+            existing(&path(&original), DuplicateKind::Running),
+            existing("/videos/queued.mp4", DuplicateKind::Queued),
+        ];
+        let mut fingerprints = Fingerprints::default();
+
+        let conflict = find_conflict("/videos/queued.mp4", &jobs, &mut fingerprints).unwrap();
+        assert_eq!(conflict.kind, DuplicateKind::Queued);
+        assert!(!conflict.same_content);
+
+        let conflict = find_conflict(&path(&copy), &jobs, &mut fingerprints).unwrap();
+        assert_eq!(conflict.kind, DuplicateKind::Running);
+        assert!(conflict.same_content);
+
+        assert!(find_conflict(&path(&other), &jobs, &mut fingerprints).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,100 @@
+// Elevated registry helper
+// Machine-wide context menu registration needs administrator rights. Instead of asking
+// the user to restart the whole app as administrator, the app relaunches itself through
+// UAC with a helper argument; that process only does the registry writes and exits.
+
+/// `szhimatar --context-menu-helper=register|unregister`
+const HELPER_ARG: &str = "--context-menu-helper=";
+
+/// Run the helper when this process was launched as one. Returns the exit code to
+/// use, or None for a normal launch.
+pub fn run_helper(args: &[String]) -> Option<i32> {
+    let action = args.iter().find_map(|arg| arg.strip_prefix(HELPER_ARG))?;
+
+    #[cfg(windows)]
+    {
+        use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_ALL_ACCESS};
+
+        let result = winreg::RegKey::predef(HKEY_LOCAL_MACHINE)
+            .open_subkey_with_flags(r"SOFTWARE\Classes", KEY_ALL_ACCESS)
+            .map_err(|e| format!("Failed to open HKLM\\SOFTWARE\\Classes: {}", e))
+            .and_then(|classes| match action {
+                "register" => crate::write_context_menu(&classes),
+                "unregister" => crate::delete_context_menu(&classes),
+                other => Err(format!("Unknown helper action: {}", other)),
+            });
+        let _ = crate::write_log(format!(
+            "[ELEVATION] Context menu helper '{}': {:?}",
+            action, result
+        ));
+        Some(if result.is_ok() { 0 } else { 1 })
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = action;
+        Some(1)
+    }
+}
+
+/// Relaunch this executable elevated (UAC prompt) with the helper argument and wait
+#[cfg(windows)]
+fn run_elevated(action: &str) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to get exe path: {}", e))?;
+    // Single quotes are the only thing to escape inside a PowerShell literal
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let script = format!(
+        "$p = Start-Process -FilePath {} -ArgumentList {} -Verb RunAs -Wait -PassThru; exit $p.ExitCode",
+        quote(&exe.to_string_lossy()),
+        quote(&format!("{}{}", HELPER_ARG, action)),
+    );
+
+    let output = crate::proc::run_captured(
+        crate::proc::command("powershell").args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &script,
+        ]),
+        &crate::proc::RunOptions::with_timeout(300).label("elevated helper"),
+    )?;
+    if output.success {
+        return Ok(());
+    }
+    if output.stderr.to_lowercase().contains("canceled") {
+        Err("Administrator permission was not granted".to_string())
+    } else {
+        Err(format!(
+            "Elevated registration failed (exit code {:?}), see app.log",
+            output.code
+        ))
+    }
+}
+
+/// Register the context menu machine-wide through a UAC prompt
+#[tauri::command]
+pub async fn elevate_and_register_context_menu() -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        run_elevated("register")
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err("Context menu is only supported on Windows".to_string())
+    }
+}
+
+/// Remove a machine-wide context menu registration through a UAC prompt
+#[tauri::command]
+pub async fn elevate_and_unregister_context_menu() -> Result<(), String> {
+    #[cfg(windows)]
+    {
+        run_elevated("unregister")
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err("Context menu is only supported on Windows".to_string())
+    }
+}
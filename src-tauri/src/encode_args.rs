@@ -0,0 +1,2199 @@
+// Backend encode argument builder
+// Resolves the concrete FFmpeg encoder for a codec + hardware choice and builds
+// the matching rate-control / preset arguments.
+
+use serde::{Deserialize, Serialize};
+
+/// Codecs accepted as `default_video_codec` in settings
+pub const SUPPORTED_VIDEO_CODECS: [&str; 5] = ["h264", "h265", "hevc", "av1", "vp9"];
+
+/// Encoding options sent by the UI (mirrors the video part of a preset)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EncodeOptions {
+    /// h264 | h265 | hevc | av1 | vp9
+    pub codec: String,
+    /// CRF / CQ value, empty or "auto" for the encoder default
+    pub crf: String,
+    /// Target bitrate in Mbit/s, empty or "auto" for quality mode; the peak bitrate
+    /// with RateControl::CappedCrf (empty = the codec's default peak)
+    pub bitrate: String,
+    pub rate_control: RateControl,
+    /// x264-style preset name (mapped per encoder) or an encoder-native preset
+    pub preset: String,
+    /// Use a hardware encoder when one is available
+    pub prefer_gpu: bool,
+    /// Hardware backend for prefer_gpu: nvenc | qsv | amf | vaapi (defaults to the API of
+    /// the detected GPU, see detected_hardware)
+    pub hardware: Option<String>,
+    /// Rotation / flip and what to do with the source's rotation metadata (None leaves
+    /// both to FFmpeg's defaults)
+    pub orientation: Option<Orientation>,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            codec: "h264".to_string(),
+            crf: "23".to_string(),
+            bitrate: String::new(),
+            rate_control: RateControl::Auto,
+            preset: "medium".to_string(),
+            prefer_gpu: false,
+            hardware: None,
+            orientation: None,
+        }
+    }
+}
+
+/// How crf and bitrate drive the encoder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateControl {
+    /// Bitrate when one is set, otherwise constant quality
+    #[default]
+    Auto,
+    /// Constant quality, with the bitrate as a peak (-maxrate / -bufsize) so streaming
+    /// and upload limits hold on busy scenes
+    CappedCrf,
+}
+
+/// Peak bitrate (Mbit/s) of a capped CRF encode without a bitrate; sized for 1080p
+fn default_peak_bitrate(codec: &str) -> f64 {
+    match codec {
+        "h265" | "hevc" | "vp9" => 6.0,
+        "av1" => 5.0,
+        _ => 8.0,
+    }
+}
+
+/// Peak a capped CRF encode of `codec` uses when the bitrate is left empty
+#[tauri::command]
+pub fn get_default_peak_bitrate(codec: String) -> Result<f64, String> {
+    Ok(default_peak_bitrate(&codec))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncoderFamily {
+    X264,
+    X265,
+    SvtAv1,
+    AomAv1,
+    Vpx,
+    Nvenc,
+    Qsv,
+    /// AMD Advanced Media Framework (Windows)
+    Amf,
+    /// VA-API (Linux, AMD and Intel)
+    Vaapi,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedEncoder {
+    pub name: String,
+    pub family: EncoderFamily,
+    pub hardware: bool,
+}
+
+impl ResolvedEncoder {
+    pub fn named(name: &str) -> Self {
+        let family = family_of(name);
+        Self {
+            name: name.to_string(),
+            family,
+            hardware: is_hardware_family(family),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodeArgsResult {
+    pub encoder: ResolvedEncoder,
+    /// Global hardware-device arguments (e.g. QSV device setup); empty for CPU encoders
+    #[serde(default)]
+    pub input_args: Vec<String>,
+    /// Rotation / flip filter for the -vf chain (before upload_filter)
+    #[serde(default)]
+    pub orientation_filter: Option<String>,
+    /// Filter that must end the -vf chain (VAAPI frame upload)
+    #[serde(default)]
+    pub upload_filter: Option<String>,
+    pub args: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+pub fn is_hardware_family(family: EncoderFamily) -> bool {
+    matches!(
+        family,
+        EncoderFamily::Nvenc | EncoderFamily::Qsv | EncoderFamily::Amf | EncoderFamily::Vaapi
+    )
+}
+
+fn family_of(encoder: &str) -> EncoderFamily {
+    match encoder {
+        "libx264" => EncoderFamily::X264,
+        "libx265" => EncoderFamily::X265,
+        "libsvtav1" => EncoderFamily::SvtAv1,
+        "libaom-av1" => EncoderFamily::AomAv1,
+        "libvpx-vp9" => EncoderFamily::Vpx,
+        e if e.ends_with("_nvenc") => EncoderFamily::Nvenc,
+        e if e.ends_with("_qsv") => EncoderFamily::Qsv,
+        e if e.ends_with("_amf") => EncoderFamily::Amf,
+        e if e.ends_with("_vaapi") => EncoderFamily::Vaapi,
+        _ => EncoderFamily::Other,
+    }
+}
+
+/// Hardware encoder API for a GPU vendor as reported by hardware::gpu_vendor; AMD
+/// encodes through AMF on Windows and VAAPI elsewhere
+pub fn hardware_for_vendor(vendor: &str) -> Option<&'static str> {
+    match vendor {
+        "nvidia" => Some("nvenc"),
+        "intel" => Some("qsv"),
+        "amd" if cfg!(target_os = "windows") => Some("amf"),
+        "amd" => Some("vaapi"),
+        _ => None,
+    }
+}
+
+/// Hardware encoder API of this machine's GPU (None when the vendor is unknown, in
+/// which case prefer_gpu tries every API). FFmpeg lists NVENC whenever it was built
+/// with it, so without this an Intel-only machine would pick h264_nvenc and fail.
+pub fn detected_hardware() -> Option<&'static str> {
+    static DETECTED: std::sync::OnceLock<Option<&'static str>> = std::sync::OnceLock::new();
+    *DETECTED.get_or_init(|| {
+        let adapters = crate::hardware::display_adapters().unwrap_or_default();
+        hardware_for_vendor(crate::hardware::gpu_vendor(&adapters))
+    })
+}
+
+/// Candidate encoders for a codec in priority order
+fn encoder_candidates(codec: &str, hardware: Option<&str>) -> Vec<&'static str> {
+    let codec = match codec {
+        "h265" | "hevc" => "hevc",
+        other => other,
+    };
+
+    if hardware == Some("auto") {
+        // No explicit choice: NVENC, Intel Quick Sync, then AMD (AMF / VAAPI)
+        let mut auto: Vec<&'static str> = ["nvenc", "qsv", "amf", "vaapi"]
+            .iter()
+            .flat_map(|hw| encoder_candidates(codec, Some(hw)))
+            .filter(|e| is_hardware_family(family_of(e)))
+            .collect();
+        auto.extend(encoder_candidates(codec, None));
+        return auto;
+    }
+
+    let hw: Vec<&'static str> = match (hardware, codec) {
+        (Some("nvenc"), "h264") => vec!["h264_nvenc"],
+        (Some("nvenc"), "hevc") => vec!["hevc_nvenc"],
+        (Some("nvenc"), "av1") => vec!["av1_nvenc"],
+        (Some("qsv"), "h264") => vec!["h264_qsv"],
+        (Some("qsv"), "hevc") => vec!["hevc_qsv"],
+        (Some("qsv"), "av1") => vec!["av1_qsv"],
+        (Some("qsv"), "vp9") => vec!["vp9_qsv"],
+        (Some("amf"), "h264") => vec!["h264_amf"],
+        (Some("amf"), "hevc") => vec!["hevc_amf"],
+        (Some("amf"), "av1") => vec!["av1_amf"],
+        (Some("vaapi"), "h264") => vec!["h264_vaapi"],
+        (Some("vaapi"), "hevc") => vec!["hevc_vaapi"],
+        (Some("vaapi"), "av1") => vec!["av1_vaapi"],
+        (Some("vaapi"), "vp9") => vec!["vp9_vaapi"],
+        _ => vec![],
+    };
+
+    let cpu: Vec<&'static str> = match codec {
+        "hevc" => vec!["libx265"],
+        "av1" => vec!["libsvtav1", "libaom-av1"],
+        "vp9" => vec!["libvpx-vp9"],
+        // Older presets
+        "vp8" => vec!["libvpx"],
+        "mpeg4" => vec!["mpeg4"],
+        "prores" => vec!["prores_ks"],
+        _ => vec!["libx264"],
+    };
+
+    hw.into_iter().chain(cpu).collect()
+}
+
+/// Pick the first candidate present in `available` (all candidates count as available
+/// when the encoder list could not be read, so the caller still gets a sensible name)
+pub fn resolve_video_encoder(
+    options: &EncodeOptions,
+    available: &[String],
+) -> (ResolvedEncoder, Vec<String>) {
+    let mut warnings = Vec::new();
+    let hardware = if options.prefer_gpu {
+        Some(options.hardware.as_deref().unwrap_or("auto"))
+    } else {
+        None
+    };
+
+    let candidates = encoder_candidates(options.codec.as_str(), hardware);
+    let is_available = |name: &str| available.is_empty() || available.iter().any(|a| a == name);
+
+    let chosen = candidates
+        .iter()
+        .copied()
+        .find(|name| is_available(name))
+        .or_else(|| candidates.last().copied())
+        .unwrap_or("libx264");
+
+    let encoder = ResolvedEncoder::named(chosen);
+
+    if let Some(hw) = hardware {
+        if !encoder.hardware {
+            warnings.push(format!(
+                "No {} encoder for {}, falling back to {}",
+                if hw == "auto" { "hardware" } else { hw },
+                options.codec,
+                chosen
+            ));
+        }
+    }
+    if chosen == "libaom-av1" {
+        warnings.push("libsvtav1 not available, using the much slower libaom-av1".to_string());
+    }
+
+    (encoder, warnings)
+}
+
+fn parse_crf(crf: &str) -> Option<i32> {
+    if crf.is_empty() || crf == "auto" {
+        return None;
+    }
+    crf.parse::<f64>().ok().map(|v| v.round() as i32)
+}
+
+fn parse_bitrate(bitrate: &str) -> Option<f64> {
+    if bitrate.is_empty() || bitrate == "auto" {
+        return None;
+    }
+    bitrate
+        .parse::<f64>()
+        .ok()
+        .filter(|v| *v > 0.0)
+        .map(|v| v.clamp(0.1, 100.0))
+}
+
+/// x264 CRF (0-51) to the 0-63 scale of SVT-AV1, libaom and libvpx; 23 maps to ~32
+fn crf_63_from_x264(crf: i32) -> i32 {
+    ((crf.clamp(0, 51) as f64) * 1.4).round().min(63.0) as i32
+}
+
+/// x264 preset names to SVT-AV1 presets (0 = slowest, 13 = fastest)
+fn svt_av1_preset(preset: &str) -> String {
+    let mapped = match preset {
+        "ultrafast" => "12",
+        "superfast" => "11",
+        "veryfast" => "10",
+        "faster" => "9",
+        "fast" => "8",
+        "medium" => "7",
+        "slow" => "5",
+        "slower" => "4",
+        "veryslow" => "3",
+        "placebo" => "2",
+        p if p.parse::<u8>().map(|v| v <= 13).unwrap_or(false) => p,
+        _ => "7",
+    };
+    mapped.to_string()
+}
+
+/// x264 preset names to NVENC p1..p7
+fn nvenc_preset(preset: &str) -> String {
+    let mapped = match preset {
+        "ultrafast" => "p1",
+        "superfast" => "p2",
+        "veryfast" => "p3",
+        "faster" | "fast" => "p4",
+        "medium" => "p5",
+        "slow" => "p6",
+        "slower" | "veryslow" | "placebo" => "p7",
+        p if p.len() == 2 && p.starts_with('p') => p,
+        _ => "p4",
+    };
+    mapped.to_string()
+}
+
+/// x264 preset names to libvpx -cpu-used for the "good" deadline (0 = slowest)
+fn vp9_cpu_used(preset: &str) -> String {
+    let mapped = match preset {
+        "ultrafast" | "superfast" => "5",
+        "veryfast" => "4",
+        "faster" | "fast" => "3",
+        "medium" => "2",
+        "slow" => "1",
+        "slower" | "veryslow" | "placebo" => "0",
+        _ => "2",
+    };
+    mapped.to_string()
+}
+
+/// QSV accepts veryfast..veryslow
+fn qsv_preset(preset: &str) -> String {
+    let mapped = match preset {
+        "ultrafast" | "superfast" | "veryfast" => "veryfast",
+        "faster" => "faster",
+        "fast" => "fast",
+        "slow" => "slow",
+        "slower" => "slower",
+        "veryslow" | "placebo" => "veryslow",
+        _ => "medium",
+    };
+    mapped.to_string()
+}
+
+/// AMF -quality: speed / balanced / quality
+fn amf_quality(preset: &str) -> String {
+    let mapped = match preset {
+        "ultrafast" | "superfast" | "veryfast" | "faster" => "speed",
+        "slow" | "slower" | "veryslow" | "placebo" => "quality",
+        _ => "balanced",
+    };
+    mapped.to_string()
+}
+
+fn x26x_preset(preset: &str) -> String {
+    const VALID: [&str; 10] = [
+        "ultrafast",
+        "superfast",
+        "veryfast",
+        "faster",
+        "fast",
+        "medium",
+        "slow",
+        "slower",
+        "veryslow",
+        "placebo",
+    ];
+    if VALID.contains(&preset) {
+        preset.to_string()
+    } else {
+        "medium".to_string()
+    }
+}
+
+/// Rate control + preset arguments for a resolved encoder
+pub fn video_quality_args(options: &EncodeOptions, encoder: &ResolvedEncoder) -> Vec<String> {
+    let crf = parse_crf(&options.crf);
+    let bitrate = parse_bitrate(&options.bitrate);
+    let peak = (options.rate_control == RateControl::CappedCrf)
+        .then(|| bitrate.unwrap_or_else(|| default_peak_bitrate(&options.codec)));
+    // Encoders without a capped quality mode get VBR whose maxrate (1.5x) is the peak
+    let bitrate = peak.map(|p| (p / 1.5 * 10.0).round() / 10.0).or(bitrate);
+    let preset = options.preset.to_lowercase();
+    let mut args: Vec<String> = Vec::new();
+    let mut push = |items: &[&str]| args.extend(items.iter().map(|s| s.to_string()));
+
+    match encoder.family {
+        EncoderFamily::Nvenc => {
+            if let Some(p) = peak {
+                // Constant quality VBR: -cq drives the quality, -b:v 0 lifts the average
+                let cq = crf.unwrap_or(23).clamp(0, 51).to_string();
+                push(&[
+                    "-rc",
+                    "vbr",
+                    "-cq",
+                    &cq,
+                    "-b:v",
+                    "0",
+                    "-maxrate",
+                    &format!("{}M", p),
+                    "-bufsize",
+                    &format!("{}M", p * 2.0),
+                ]);
+            } else if let Some(b) = bitrate {
+                push(&[
+                    "-rc",
+                    "vbr",
+                    "-b:v",
+                    &format!("{}M", b),
+                    "-maxrate",
+                    &format!("{:.1}M", b * 1.5),
+                    "-bufsize",
+                    &format!("{:.0}M", b * 3.0),
+                ]);
+            } else {
+                let cq = crf.unwrap_or(23).clamp(0, 51).to_string();
+                push(&["-rc", "constqp", "-cq", &cq, "-qp", &cq]);
+            }
+            push(&["-spatial-aq", "1", "-temporal-aq", "1"]);
+            if encoder.name == "hevc_nvenc" {
+                push(&["-b_ref_mode", "middle"]);
+            }
+            push(&["-preset", &nvenc_preset(&preset), "-tune", "hq"]);
+        }
+        EncoderFamily::Qsv => {
+            if let Some(b) = bitrate {
+                push(&[
+                    "-b:v",
+                    &format!("{}M", b),
+                    "-maxrate",
+                    &format!("{:.1}M", b * 1.5),
+                ]);
+            } else {
+                let quality = crf.unwrap_or(23).clamp(1, 51).to_string();
+                push(&["-global_quality", &quality]);
+            }
+            push(&["-preset", &qsv_preset(&preset)]);
+        }
+        EncoderFamily::Amf => {
+            if let Some(b) = bitrate {
+                push(&[
+                    "-rc",
+                    "vbr_peak",
+                    "-b:v",
+                    &format!("{}M", b),
+                    "-maxrate",
+                    &format!("{:.1}M", b * 1.5),
+                ]);
+            } else {
+                let qp = crf.unwrap_or(23).clamp(0, 51).to_string();
+                push(&["-rc", "cqp", "-qp_i", &qp, "-qp_p", &qp]);
+            }
+            push(&["-quality", &amf_quality(&preset)]);
+        }
+        EncoderFamily::Vaapi => {
+            if let Some(b) = bitrate {
+                push(&[
+                    "-rc_mode",
+                    "VBR",
+                    "-b:v",
+                    &format!("{}M", b),
+                    "-maxrate",
+                    &format!("{:.1}M", b * 1.5),
+                ]);
+            } else {
+                // AV1 / VP9 quantizers run 0-255 instead of 0-51
+                let qp = crf.unwrap_or(23).clamp(0, 51);
+                let qp = if encoder.name.starts_with("av1") || encoder.name.starts_with("vp9") {
+                    qp * 5
+                } else {
+                    qp
+                };
+                push(&["-rc_mode", "CQP", "-qp", &qp.to_string()]);
+            }
+        }
+        EncoderFamily::SvtAv1 | EncoderFamily::AomAv1 => {
+            let crf_value = crf_63_from_x264(crf.unwrap_or(23)).to_string();
+            match (peak, bitrate) {
+                // SVT-AV1 caps its CRF with -maxrate; libaom's constrained quality mode
+                // treats -b:v as the ceiling
+                (Some(p), _) if encoder.family == EncoderFamily::SvtAv1 => push(&[
+                    "-crf",
+                    &crf_value,
+                    "-maxrate",
+                    &format!("{}M", p),
+                    "-bufsize",
+                    &format!("{}M", p * 2.0),
+                ]),
+                (Some(p), _) => push(&["-crf", &crf_value, "-b:v", &format!("{}M", p)]),
+                (None, Some(b)) => push(&["-b:v", &format!("{}M", b)]),
+                (None, None) => {
+                    push(&["-crf", &crf_value]);
+                    if encoder.family == EncoderFamily::AomAv1 {
+                        // libaom needs -b:v 0 for pure constant quality
+                        push(&["-b:v", "0"]);
+                    }
+                }
+            }
+            if encoder.family == EncoderFamily::SvtAv1 {
+                push(&["-preset", &svt_av1_preset(&preset)]);
+            } else {
+                push(&["-cpu-used", "6", "-row-mt", "1"]);
+            }
+        }
+        EncoderFamily::Vpx => {
+            match (peak, bitrate) {
+                // Constrained quality: -crf with -b:v as the ceiling
+                (Some(p), _) => push(&[
+                    "-crf",
+                    &crf_63_from_x264(crf.unwrap_or(23)).to_string(),
+                    "-b:v",
+                    &format!("{}M", p),
+                ]),
+                // Constrained VBR around the target
+                (None, Some(b)) => push(&[
+                    "-b:v",
+                    &format!("{}M", b),
+                    "-minrate",
+                    &format!("{:.1}M", b * 0.5),
+                    "-maxrate",
+                    &format!("{:.1}M", b * 1.45),
+                ]),
+                // Constant quality (libvpx needs -b:v 0 for pure CRF)
+                (None, None) => push(&[
+                    "-crf",
+                    &crf_63_from_x264(crf.unwrap_or(23)).to_string(),
+                    "-b:v",
+                    "0",
+                ]),
+            }
+            // row-mt + tile columns are what make libvpx use more than a couple of cores
+            push(&[
+                "-deadline",
+                "good",
+                "-cpu-used",
+                &vp9_cpu_used(&preset),
+                "-row-mt",
+                "1",
+                "-tile-columns",
+                "2",
+                "-frame-parallel",
+                "0",
+                "-auto-alt-ref",
+                "1",
+                "-lag-in-frames",
+                "25",
+            ]);
+        }
+        EncoderFamily::X264 | EncoderFamily::X265 | EncoderFamily::Other => {
+            let crf = crf.or(peak.map(|_| 23));
+            match (crf, peak.or(bitrate)) {
+                (Some(c), Some(b)) => push(&[
+                    "-crf",
+                    &c.clamp(0, 51).to_string(),
+                    "-maxrate",
+                    &format!("{}M", b),
+                    "-bufsize",
+                    &format!("{:.0}M", b * 2.0),
+                ]),
+                (None, Some(b)) => push(&["-b:v", &format!("{}M", b)]),
+                (Some(c), None) => push(&["-crf", &c.clamp(0, 51).to_string()]),
+                (None, None) => push(&["-crf", "23"]),
+            }
+            if encoder.family != EncoderFamily::Other {
+                push(&["-preset", &x26x_preset(&preset)]);
+            }
+        }
+    }
+
+    args
+}
+
+/// Hardware device setup an encoder needs. QSV gets an explicit device so the
+/// Intel adapter is used even when another GPU is the primary one (Windows picks
+/// it through D3D11 by vendor id, Linux through the Intel render node); VAAPI is
+/// bound to the AMD render node. AMF finds its adapter itself. The options are
+/// global, so their position on the command line does not matter.
+pub fn hw_device_args(encoder: &str) -> Vec<String> {
+    let args: Vec<String> = match family_of(encoder) {
+        #[cfg(target_os = "windows")]
+        EncoderFamily::Qsv => vec![
+            "-init_hw_device".to_string(),
+            "d3d11va=qsvdx:,vendor_id=0x8086".to_string(),
+            "-init_hw_device".to_string(),
+            "qsv=qsv@qsvdx".to_string(),
+        ],
+        #[cfg(not(target_os = "windows"))]
+        EncoderFamily::Qsv => match crate::gpu::render_node_for("intel") {
+            Some(node) => vec![
+                "-init_hw_device".to_string(),
+                format!("vaapi=qsvva:{}", node),
+                "-init_hw_device".to_string(),
+                "qsv=qsv@qsvva".to_string(),
+            ],
+            None => vec!["-init_hw_device".to_string(), "qsv=qsv:hw_any".to_string()],
+        },
+        EncoderFamily::Vaapi => {
+            let node = crate::gpu::render_node_for("amd")
+                .unwrap_or_else(|| crate::gpu::DEFAULT_RENDER_NODE.to_string());
+            vec!["-init_hw_device".to_string(), format!("vaapi=va:{}", node)]
+        }
+        _ => return Vec::new(),
+    };
+
+    let device = if family_of(encoder) == EncoderFamily::Vaapi {
+        "va"
+    } else {
+        "qsv"
+    };
+    args.into_iter()
+        .chain(["-filter_hw_device".to_string(), device.to_string()])
+        .collect()
+}
+
+/// Filter that has to end the -vf chain so frames reach the GPU (VAAPI encoders
+/// only accept hardware frames; QSV and AMF upload on their own)
+pub fn hw_upload_filter(encoder: &str) -> Option<&'static str> {
+    match family_of(encoder) {
+        EncoderFamily::Vaapi => Some("format=nv12,hwupload"),
+        _ => None,
+    }
+}
+
+/// Append a filter to the `option` (-vf / -af) chain in `args`, adding the option
+/// when there is none
+fn push_filter(args: &mut Vec<String>, option: &str, filter: &str) {
+    if let Some(pos) = args.iter().position(|a| a == option) {
+        if let Some(chain) = args.get_mut(pos + 1) {
+            *chain = format!("{},{}", chain, filter);
+            return;
+        }
+    }
+
+    args.push(option.to_string());
+    args.push(filter.to_string());
+}
+
+/// Append a filter to the -vf chain in `args`, adding -vf when there is none
+pub fn push_video_filter(args: &mut Vec<String>, filter: &str) {
+    push_filter(args, "-vf", filter);
+}
+
+/// Put a filter at the start of the -vf chain in `args`, adding -vf when there is none
+pub fn prepend_video_filter(args: &mut Vec<String>, filter: &str) {
+    match args.iter().position(|a| a == "-vf") {
+        Some(pos) if pos + 1 < args.len() => {
+            args[pos + 1] = format!("{},{}", filter, args[pos + 1]);
+        }
+        _ => args.extend(["-vf".to_string(), filter.to_string()]),
+    }
+}
+
+/// A path as a filter option value: quoted for the filtergraph, with the drive colon
+/// escaped for the option parser ('C\:/Users/...')
+pub fn filter_path(path: &std::path::Path) -> String {
+    format!(
+        "'{}'",
+        path.to_string_lossy()
+            .replace('\\', "/")
+            .replace(':', "\\:")
+    )
+}
+
+/// Add the hardware device setup and upload filter for the video encoder in
+/// `args` unless the caller already initialized a device
+pub fn ensure_hw_device_args(args: &mut Vec<String>) {
+    if args.iter().any(|a| a == "-init_hw_device") {
+        return;
+    }
+    let Some(encoder) = video_encoder_in(args).map(str::to_string) else {
+        return;
+    };
+
+    if let Some(filter) = hw_upload_filter(&encoder) {
+        push_video_filter(args, filter);
+    }
+    args.splice(0..0, hw_device_args(&encoder));
+}
+
+/// Source codecs the common hardware decoders handle (NVDEC, D3D11VA, VAAPI, VideoToolbox)
+const HW_DECODABLE_CODECS: [&str; 6] = ["h264", "hevc", "vp9", "av1", "mpeg2video", "vc1"];
+
+/// Pick a `-hwaccel` method for decoding the source: the decoder of the GPU that
+/// also encodes, otherwise CUDA on NVIDIA machines and the platform API elsewhere.
+/// Frames are copied back to system memory, so CPU filters keep working.
+pub fn decode_hwaccel(
+    source_codec: &str,
+    video_encoder: Option<&str>,
+    nvidia: bool,
+) -> Option<&'static str> {
+    if !HW_DECODABLE_CODECS.contains(&source_codec) {
+        return None;
+    }
+
+    let platform = if cfg!(target_os = "windows") {
+        "d3d11va"
+    } else if cfg!(target_os = "macos") {
+        "videotoolbox"
+    } else {
+        "vaapi"
+    };
+
+    let family = video_encoder.map(family_of);
+    Some(match family {
+        Some(EncoderFamily::Nvenc) => "cuda",
+        Some(EncoderFamily::Qsv | EncoderFamily::Amf | EncoderFamily::Vaapi) => platform,
+        _ if nvidia => "cuda",
+        _ => platform,
+    })
+}
+
+/// Build "-c:v <encoder>" plus quality arguments for the given options
+pub fn build_video_args(options: &EncodeOptions, available: &[String]) -> EncodeArgsResult {
+    let (encoder, mut warnings) = resolve_video_encoder(options, available);
+    let mut args = vec!["-c:v".to_string(), encoder.name.clone()];
+    args.extend(video_quality_args(options, &encoder));
+    let mut input_args = hw_device_args(&encoder.name);
+
+    let orientation_filter = match options.orientation.map(|o| (o, o.validate())) {
+        Some((orientation, Ok(()))) => {
+            input_args.extend(orientation.input_args());
+            args.extend(orientation.output_args());
+            orientation.filter()
+        }
+        Some((_, Err(e))) => {
+            warnings.push(e);
+            None
+        }
+        None => None,
+    };
+
+    EncodeArgsResult {
+        input_args,
+        orientation_filter,
+        upload_filter: hw_upload_filter(&encoder.name).map(str::to_string),
+        encoder,
+        args,
+        warnings,
+    }
+}
+
+// ============================================================================
+// Orientation
+// ============================================================================
+
+/// Clockwise rotation of the picture
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rotation {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "90")]
+    Quarter,
+    #[serde(rename = "180")]
+    Half,
+    #[serde(rename = "270")]
+    ThreeQuarters,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Flip {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+}
+
+/// What happens to the display rotation phones store next to the (sideways) frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceRotation {
+    /// FFmpeg's autorotate turns the frames upright and the rotation tag is dropped, so
+    /// every player shows the same picture
+    #[default]
+    Bake,
+    /// Frames stay as stored and the tag is carried over (players that ignore it show
+    /// the video sideways)
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    pub flip: Flip,
+    pub source_rotation: SourceRotation,
+}
+
+impl Orientation {
+    pub fn validate(&self) -> Result<(), String> {
+        let transformed = self.rotation != Rotation::None || self.flip != Flip::None;
+        if transformed && self.source_rotation == SourceRotation::Keep {
+            return Err(
+                "Rotation and flip need the source rotation baked in, not kept as metadata"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// transpose / hflip / vflip chain for the rotation and flip (applied to the frames
+    /// after autorotate, i.e. to the picture as players show it)
+    pub fn filter(&self) -> Option<String> {
+        let mut filters: Vec<&str> = match self.rotation {
+            Rotation::None => vec![],
+            Rotation::Quarter => vec!["transpose=clock"],
+            Rotation::Half => vec!["hflip", "vflip"],
+            Rotation::ThreeQuarters => vec!["transpose=cclock"],
+        };
+        match self.flip {
+            Flip::None => {}
+            Flip::Horizontal => filters.push("hflip"),
+            Flip::Vertical => filters.push("vflip"),
+        }
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    /// Options placed before -i
+    pub fn input_args(&self) -> Vec<String> {
+        match self.source_rotation {
+            SourceRotation::Bake => vec![],
+            SourceRotation::Keep => vec!["-noautorotate".to_string()],
+        }
+    }
+
+    /// Output options: a baked rotation clears the legacy rotate tag, which some muxers
+    /// would otherwise copy and players would apply a second time
+    pub fn output_args(&self) -> Vec<String> {
+        match self.source_rotation {
+            SourceRotation::Bake => vec!["-metadata:s:v:0".to_string(), "rotate=0".to_string()],
+            SourceRotation::Keep => vec![],
+        }
+    }
+}
+
+/// Add the orientation to a render: its filter joins the -vf chain and the
+/// autorotate / metadata options go to the input and output. A copied video stream
+/// keeps its frames and tag as they are.
+pub fn apply_orientation(
+    args: &mut Vec<String>,
+    input_args: &mut Vec<String>,
+    orientation: &Orientation,
+) -> Result<(), String> {
+    orientation.validate()?;
+    if video_encoder_in(args) == Some("copy") {
+        if orientation.filter().is_some() {
+            return Err("Rotation and flip cannot be used with video codec \"copy\"".to_string());
+        }
+        return Ok(());
+    }
+    if let Some(filter) = orientation.filter() {
+        push_video_filter(args, &filter);
+    }
+    input_args.extend(orientation.input_args());
+    args.extend(orientation.output_args());
+    Ok(())
+}
+
+// ============================================================================
+// Resolution and frame rate caps
+// ============================================================================
+
+/// Upper bounds for the output picture (RenderJob::limits); a source already within
+/// a cap is left as it is, so nothing is ever upscaled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputLimits {
+    /// Short side in pixels (720 / 1080 / 1440): the height of a landscape video, the
+    /// width of a portrait one
+    pub max_height: Option<u32>,
+    pub max_fps: Option<f64>,
+}
+
+/// Size and frame rate of a source's first video stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceVideo {
+    pub width: u32,
+    pub height: u32,
+    /// 0 when unknown
+    pub fps: f64,
+}
+
+/// The first video stream of an ffprobe JSON (cover art skipped)
+pub fn source_video(probe: &serde_json::Value) -> Option<SourceVideo> {
+    let video = probe["streams"]
+        .as_array()?
+        .iter()
+        .find(|s| s["codec_type"] == "video" && s["disposition"]["attached_pic"] != 1)?;
+    let rate =
+        |key: &str| crate::library::parse_frame_rate(video[key].as_str().unwrap_or_default());
+    let fps = match rate("avg_frame_rate") {
+        avg if avg > 0.0 => avg,
+        _ => rate("r_frame_rate"),
+    };
+    Some(SourceVideo {
+        width: video["width"].as_u64().filter(|w| *w > 0)? as u32,
+        height: video["height"].as_u64().filter(|h| *h > 0)? as u32,
+        fps,
+    })
+}
+
+impl OutputLimits {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_height.is_some_and(|h| !(144..=4320).contains(&h)) {
+            return Err("The resolution cap must be between 144p and 4320p".to_string());
+        }
+        if self
+            .max_fps
+            .is_some_and(|fps| !fps.is_finite() || !(1.0..=240.0).contains(&fps))
+        {
+            return Err("The frame rate cap must be between 1 and 240 fps".to_string());
+        }
+        Ok(())
+    }
+
+    /// Downscale filter when the source's short side is over the cap. The expressions
+    /// pick the side from the frames the filter sees, so a phone video that autorotate
+    /// turned upright is capped by its width.
+    fn scale_filter(&self, source: &SourceVideo) -> Option<String> {
+        let cap = self.max_height?;
+        (source.width.min(source.height) > cap).then(|| {
+            format!(
+                "scale='if(gte(iw,ih),-2,{cap})':'if(gte(iw,ih),{cap},-2)'",
+                cap = cap
+            )
+        })
+    }
+
+    /// Frame rate of the output, when the cap lowers it
+    fn capped_fps(&self, current: f64) -> Option<f64> {
+        self.max_fps.filter(|cap| current > cap + 0.01)
+    }
+}
+
+/// Add the caps a source exceeds to a render: the scale goes first in the -vf chain,
+/// the frame rate cap lowers an explicit -r or adds an fps filter. Returns the output
+/// frame rate when it was capped.
+pub fn apply_limits(
+    args: &mut Vec<String>,
+    limits: &OutputLimits,
+    source: &SourceVideo,
+) -> Result<Option<f64>, String> {
+    limits.validate()?;
+    let output_rate = args
+        .windows(2)
+        .position(|w| w[0] == "-r")
+        .map(|pos| pos + 1)
+        .and_then(|pos| args[pos].parse::<f64>().ok().map(|rate| (pos, rate)));
+    let fps = limits.capped_fps(output_rate.map_or(source.fps, |(_, rate)| rate));
+    let scale = limits.scale_filter(source);
+    if (fps.is_some() || scale.is_some()) && video_encoder_in(args) == Some("copy") {
+        return Err(
+            "Resolution and frame rate caps cannot be used with video codec \"copy\"".to_string(),
+        );
+    }
+
+    let mut filters = Vec::new();
+    filters.extend(scale);
+    if let Some(cap) = fps {
+        match output_rate {
+            Some((pos, _)) => args[pos] = format!("{}", cap),
+            None => filters.push(format!("fps={}", cap)),
+        }
+    }
+    if !filters.is_empty() {
+        prepend_video_filter(args, &filters.join(","));
+    }
+    Ok(fps)
+}
+
+// ============================================================================
+// Pixel format
+// ============================================================================
+
+/// Output pixel format / bit depth (RenderJob::pixel_format)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PixelFormat {
+    /// 10-bit sources stay 10-bit when the encoder can, everything else becomes yuv420p
+    #[default]
+    Auto,
+    Yuv420p,
+    Yuv420p10le,
+}
+
+/// Pixel format an encoder takes for 10-bit 4:2:0, None when it only encodes 8-bit.
+/// libx264 is left out: High 10 needs a matching libx264 build and barely plays
+/// anywhere. VAAPI is left out because its upload filter converts to 8-bit nv12.
+fn ten_bit_format(encoder: &str) -> Option<&'static str> {
+    match encoder {
+        "libx265" | "libsvtav1" | "libaom-av1" | "libvpx-vp9" => Some("yuv420p10le"),
+        "hevc_nvenc" | "av1_nvenc" | "hevc_qsv" | "av1_qsv" | "hevc_amf" | "av1_amf" => {
+            Some("p010le")
+        }
+        _ => None,
+    }
+}
+
+/// More than 8 bits per component ("yuv420p10le", "p010le", "gbrp12be", ...)
+fn high_bit_depth(pix_fmt: &str) -> bool {
+    let name = pix_fmt.trim_end_matches("le").trim_end_matches("be");
+    let packed = name.len() == 4
+        && (name.starts_with('p') || name.starts_with("y2"))
+        && name[1..].chars().all(|c| c.is_ascii_digit());
+    packed
+        || ["p10", "p12", "p14", "p16"]
+            .iter()
+            .any(|d| name.ends_with(d))
+}
+
+/// pix_fmt of a source's first video stream (cover art skipped)
+pub fn source_pix_fmt(probe: &serde_json::Value) -> Option<String> {
+    first_stream(probe, "video")?["pix_fmt"]
+        .as_str()
+        .map(|f| f.to_string())
+}
+
+/// End the -vf chain with the conversion to the output pixel format, after checking
+/// the encoder supports it. An explicit -pix_fmt is set to the same format. Returns
+/// the format, None when the video is copied.
+pub fn apply_pixel_format(
+    args: &mut Vec<String>,
+    format: PixelFormat,
+    source_pix_fmt: Option<&str>,
+) -> Result<Option<&'static str>, String> {
+    let encoder = video_encoder_in(args).unwrap_or_default().to_string();
+    if encoder == "copy" {
+        return match format {
+            PixelFormat::Auto => Ok(None),
+            _ => Err("A pixel format cannot be set with video codec \"copy\"".to_string()),
+        };
+    }
+    let ten_bit = ten_bit_format(&encoder);
+    let pix_fmt = match format {
+        PixelFormat::Yuv420p => "yuv420p",
+        PixelFormat::Yuv420p10le => ten_bit
+            .ok_or_else(|| format!("{} cannot encode 10-bit video (yuv420p10le)", encoder))?,
+        // 10-bit sources are converted down for 8-bit-only encoders
+        PixelFormat::Auto => match ten_bit {
+            Some(ten_bit) if source_pix_fmt.is_some_and(high_bit_depth) => ten_bit,
+            _ => "yuv420p",
+        },
+    };
+
+    if let Some(pos) = args.iter().position(|a| a == "-pix_fmt") {
+        if let Some(value) = args.get_mut(pos + 1) {
+            *value = pix_fmt.to_string();
+        }
+    }
+    push_video_filter(args, &format!("format={}", pix_fmt));
+    Ok(Some(pix_fmt))
+}
+
+// ============================================================================
+// Speed change
+// ============================================================================
+
+/// Slowest and fastest speed_factor a render accepts (0.25 = 4x slow motion)
+pub const MIN_SPEED_FACTOR: f64 = 0.1;
+pub const MAX_SPEED_FACTOR: f64 = 100.0;
+
+/// The job's speed factor when it changes the speed (None for 1.0)
+pub fn effective_speed(speed_factor: Option<f64>) -> Option<f64> {
+    speed_factor.filter(|factor| (factor - 1.0).abs() > 1e-6)
+}
+
+/// Length of the output of a render of `duration` at `speed_factor`
+pub fn speed_adjusted(
+    duration: crate::units::Duration,
+    speed_factor: Option<f64>,
+) -> crate::units::Duration {
+    match effective_speed(speed_factor) {
+        Some(factor) => crate::units::Duration::from_secs_f64(duration.as_secs_f64() / factor),
+        None => duration,
+    }
+}
+
+/// atempo filters for `factor`; one stage only handles 0.5 - 2.0, so larger changes
+/// are split into stages of 2.0 (or 0.5) and a remainder
+pub fn atempo_chain(factor: f64) -> String {
+    let mut stages = Vec::new();
+    let mut rest = factor;
+    while rest > 2.0 {
+        stages.push(2.0);
+        rest /= 2.0;
+    }
+    while rest < 0.5 {
+        stages.push(0.5);
+        rest /= 0.5;
+    }
+    if (rest - 1.0).abs() > 1e-6 || stages.is_empty() {
+        stages.push(rest);
+    }
+    stages
+        .iter()
+        .map(|stage| format!("atempo={:.6}", stage))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Play the output `factor` times faster (timelapse) or slower (slow motion): setpts
+/// retimes the video, atempo the audio, and output -ss / -t / -to (given in source time)
+/// are moved to output time. Call before apply_audio_source, whose second input has
+/// its own -ss.
+pub fn apply_speed(args: &mut Vec<String>, factor: f64) -> Result<(), String> {
+    if !factor.is_finite() || !(MIN_SPEED_FACTOR..=MAX_SPEED_FACTOR).contains(&factor) {
+        return Err(format!(
+            "The speed factor must be between {} and {}",
+            MIN_SPEED_FACTOR, MAX_SPEED_FACTOR
+        ));
+    }
+    if effective_speed(Some(factor)).is_none() {
+        return Ok(());
+    }
+    if video_encoder_in(args) == Some("copy") {
+        return Err("Changing the speed cannot be used with video codec \"copy\"".to_string());
+    }
+    if audio_encoder_in(args) == Some("copy") {
+        return Err("Changing the speed cannot be used with audio codec \"copy\"".to_string());
+    }
+
+    for pair in (0..args.len().saturating_sub(1)).rev() {
+        if matches!(args[pair].as_str(), "-ss" | "-t" | "-to") {
+            if let Ok(seconds) = args[pair + 1].parse::<f64>() {
+                args[pair + 1] = format!("{:.3}", seconds / factor);
+            }
+        }
+    }
+    push_video_filter(args, &format!("setpts=PTS/{:.6}", factor));
+    if !args.iter().any(|a| a == "-an") {
+        push_filter(args, "-af", &atempo_chain(factor));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Audio track
+// ============================================================================
+
+/// Loudest level a mixed-in track may be set to (1.0 = as recorded)
+pub const MAX_MIX_VOLUME: f64 = 4.0;
+
+fn full_volume() -> f64 {
+    1.0
+}
+
+/// What the output's audio is made of (RenderJob::audio_source)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum AudioSource {
+    /// The source's audio, encoded with the job's audio arguments
+    #[default]
+    Keep,
+    /// No audio stream at all
+    Mute,
+    /// An external file instead of the source's audio, cut or padded to the video
+    Replace {
+        path: String,
+        /// Skip this much of the file first
+        #[serde(default)]
+        start_seconds: f64,
+        /// Repeat the file when it is shorter than the video (otherwise pad with silence)
+        #[serde(default)]
+        loop_audio: bool,
+    },
+    /// An external file mixed under the source's audio
+    Mix {
+        path: String,
+        /// Level of the mixed-in track (0 - MAX_MIX_VOLUME)
+        #[serde(default = "full_volume")]
+        volume: f64,
+        #[serde(default)]
+        loop_audio: bool,
+    },
+}
+
+/// Options that only concern the output's audio, each followed by a value
+const AUDIO_OPTIONS: [&str; 8] = [
+    "-c:a", "-acodec", "-b:a", "-ar", "-ac", "-af", "-q:a", "-aq",
+];
+
+/// Remove `option` and its value from `args`, returning the value
+fn take_option(args: &mut Vec<String>, option: &str) -> Option<String> {
+    let pos = args.iter().position(|a| a == option)?;
+    let value = args.get(pos + 1).cloned();
+    args.drain(pos..(pos + 2).min(args.len()));
+    value
+}
+
+/// Audio encoder named by "-c:a" / "-acodec" in an argument list
+pub fn audio_encoder_in(args: &[String]) -> Option<&str> {
+    args.windows(2)
+        .find(|w| w[0] == "-c:a" || w[0] == "-acodec")
+        .map(|w| w[1].as_str())
+}
+
+/// Check an external track and build the input arguments that read it
+fn track_input_args(
+    path: &str,
+    start_seconds: f64,
+    loop_audio: bool,
+) -> Result<Vec<String>, String> {
+    if path.trim().is_empty() {
+        return Err("No audio file selected".to_string());
+    }
+    if !std::path::Path::new(path).is_file() {
+        return Err(format!("Audio file not found: {}", path));
+    }
+    if !start_seconds.is_finite() || start_seconds < 0.0 {
+        return Err("The audio start must be a positive number of seconds".to_string());
+    }
+
+    let mut input = Vec::new();
+    if loop_audio {
+        input.extend(["-stream_loop".to_string(), "-1".to_string()]);
+    }
+    if start_seconds > 0.0 {
+        input.extend(["-ss".to_string(), format!("{:.3}", start_seconds)]);
+    }
+    input.extend(["-i".to_string(), path.to_string()]);
+    Ok(input)
+}
+
+/// The new track is cut to the video's length, which a stream copy cannot do
+fn require_audio_encoder(args: &[String]) -> Result<(), String> {
+    match audio_encoder_in(args) {
+        Some(encoder) if encoder != "copy" => Ok(()),
+        _ => Err("Replacing or mixing audio needs an audio encoder, not \"copy\"".to_string()),
+    }
+}
+
+/// Rewrite the output arguments of a render (which follow `-i <source>`) for `source`:
+/// mute drops every audio option for `-an`; replace and mix add the file as a second
+/// input and map it. Audio filters already in `args` apply to the resulting track.
+pub fn apply_audio_source(args: &mut Vec<String>, source: &AudioSource) -> Result<(), String> {
+    let prefix = match source {
+        AudioSource::Keep => return Ok(()),
+        AudioSource::Mute => {
+            for option in AUDIO_OPTIONS {
+                while take_option(args, option).is_some() {}
+            }
+            args.push("-an".to_string());
+            return Ok(());
+        }
+        AudioSource::Replace {
+            path,
+            start_seconds,
+            loop_audio,
+        } => {
+            require_audio_encoder(args)?;
+            let mut prefix = track_input_args(path, *start_seconds, *loop_audio)?;
+            // -shortest ends the output with the video: a longer track is cut and a
+            // shorter one that does not loop is padded with silence
+            if !loop_audio {
+                push_filter(args, "-af", "apad");
+            }
+            prefix.extend(["-map", "0:v:0", "-map", "1:a:0", "-shortest"].map(String::from));
+            prefix
+        }
+        AudioSource::Mix {
+            path,
+            volume,
+            loop_audio,
+        } => {
+            require_audio_encoder(args)?;
+            if !volume.is_finite() || !(0.0..=MAX_MIX_VOLUME).contains(volume) {
+                return Err(format!(
+                    "The mix volume must be between 0 and {}",
+                    MAX_MIX_VOLUME
+                ));
+            }
+            let mut prefix = track_input_args(path, 0.0, *loop_audio)?;
+            // Audio filters cannot follow a complex filtergraph, so they join it
+            let filters = take_option(args, "-af")
+                .map(|chain| format!(",{}", chain))
+                .unwrap_or_default();
+            let graph = format!(
+                "[1:a]volume={:.2}[mix];[0:a][mix]amix=inputs=2:duration=first:\
+                 dropout_transition=0:normalize=0{}[aout]",
+                volume, filters
+            );
+            prefix.extend(["-filter_complex".to_string(), graph]);
+            prefix.extend(["-map", "0:v:0", "-map", "[aout]"].map(String::from));
+            prefix
+        }
+    };
+
+    args.splice(0..0, prefix);
+    Ok(())
+}
+
+/// Check an audio track choice before queueing (the render applies it again)
+#[tauri::command]
+pub fn validate_audio_source(source: AudioSource, audio_args: Vec<String>) -> Result<(), String> {
+    let mut args = audio_args;
+    apply_audio_source(&mut args, &source)
+}
+
+// ============================================================================
+// Smart stream copy
+// ============================================================================
+
+/// Output options of the video encoder, each followed by a value; dropped when the
+/// stream is copied
+const VIDEO_ENCODER_OPTIONS: [&str; 30] = [
+    "-b:v",
+    "-maxrate",
+    "-bufsize",
+    "-minrate",
+    "-crf",
+    "-cq",
+    "-qp",
+    "-rc",
+    "-rc_mode",
+    "-preset",
+    "-tune",
+    "-profile:v",
+    "-level",
+    "-tier",
+    "-pix_fmt",
+    "-g",
+    "-bf",
+    "-x264-params",
+    "-x265-params",
+    "-svtav1-params",
+    "-row-mt",
+    "-cpu-used",
+    "-deadline",
+    "-global_quality",
+    "-look_ahead",
+    "-quality",
+    "-usage",
+    "-rc-lookahead",
+    "-spatial-aq",
+    "-temporal-aq",
+];
+
+/// ffprobe codec_name an encoder produces
+fn codec_name_of(encoder: &str) -> &str {
+    match encoder {
+        "libx264" => "h264",
+        "libx265" => "hevc",
+        "libsvtav1" | "libaom-av1" | "librav1e" => "av1",
+        "libvpx-vp9" => "vp9",
+        "libfdk_aac" => "aac",
+        "libmp3lame" => "mp3",
+        "libopus" => "opus",
+        "libvorbis" => "vorbis",
+        e => e.split('_').next().unwrap_or(e),
+    }
+}
+
+/// Bits per second of "128k", "5M" or "800000"
+fn parse_rate(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (number, scale) = match value.chars().last()? {
+        'k' | 'K' => (&value[..value.len() - 1], 1e3),
+        'm' | 'M' => (&value[..value.len() - 1], 1e6),
+        _ => (value, 1.0),
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+fn option_value<'a>(args: &'a [String], option: &str) -> Option<&'a str> {
+    args.windows(2)
+        .find(|w| w[0] == option)
+        .map(|w| w[1].as_str())
+}
+
+/// Re-encoding at `requested` (the -b:v / -b:a value) would not make the stream smaller
+fn bitrate_within(stream: &serde_json::Value, requested: Option<&str>) -> bool {
+    let source = stream["bit_rate"]
+        .as_str()
+        .and_then(|rate| rate.parse::<f64>().ok());
+    match (source, requested.and_then(parse_rate)) {
+        (Some(source), Some(requested)) => source <= requested * 1.05,
+        _ => false,
+    }
+}
+
+fn first_stream<'a>(probe: &'a serde_json::Value, kind: &str) -> Option<&'a serde_json::Value> {
+    probe["streams"]
+        .as_array()?
+        .iter()
+        .find(|s| s["codec_type"] == kind && s["disposition"]["attached_pic"] != 1)
+}
+
+/// The video encode only repeats the source: same codec, at most the source's bitrate,
+/// no filters besides a format conversion to the pixel format it already has, no frame
+/// rate change and no trim (a copy can only cut at keyframes)
+fn video_copyable(args: &[String], stream: &serde_json::Value) -> bool {
+    let Some(encoder) = video_encoder_in(args).filter(|e| *e != "copy") else {
+        return false;
+    };
+    let pix_fmt = stream["pix_fmt"].as_str().unwrap_or_default();
+    let filters_noop = option_value(args, "-vf").is_none_or(|chain| {
+        chain
+            .split(',')
+            .all(|filter| filter.strip_prefix("format=") == Some(pix_fmt))
+    });
+    let source_fps =
+        crate::library::parse_frame_rate(stream["avg_frame_rate"].as_str().unwrap_or_default());
+    let same_rate = option_value(args, "-r").is_none_or(|rate| {
+        rate.parse::<f64>()
+            .is_ok_and(|rate| (rate - source_fps).abs() < 0.01)
+    });
+    let changes = ["-filter_complex", "-filter:v", "-s", "-ss", "-t", "-to"];
+    stream["codec_name"].as_str() == Some(codec_name_of(encoder))
+        && filters_noop
+        && same_rate
+        && !args.iter().any(|a| changes.contains(&a.as_str()))
+        && bitrate_within(stream, option_value(args, "-b:v"))
+}
+
+/// The audio encode only repeats the source's own track: same codec, sample rate and
+/// channels, no filters and at most the source's bitrate (any bitrate for FLAC)
+fn audio_copyable(args: &[String], stream: &serde_json::Value) -> bool {
+    let Some(encoder) = audio_encoder_in(args).filter(|e| *e != "copy") else {
+        return false;
+    };
+    let codec = codec_name_of(encoder);
+    // ffprobe reports sample_rate as a string and channels as a number
+    let same = |option: &str, key: &str| {
+        option_value(args, option)
+            .is_none_or(|value| stream[key].to_string().trim_matches('"') == value)
+    };
+    // A second input is an external track (replace / mix), not the probed source
+    let changes = [
+        "-af",
+        "-filter:a",
+        "-filter_complex",
+        "-an",
+        "-i",
+        "-q:a",
+        "-aq",
+    ];
+    stream["codec_name"].as_str() == Some(codec)
+        && same("-ar", "sample_rate")
+        && same("-ac", "channels")
+        && !args.iter().any(|a| changes.contains(&a.as_str()))
+        && (codec == "flac" || bitrate_within(stream, option_value(args, "-b:a")))
+}
+
+/// Switch the streams whose re-encode would gain nothing to "copy", judged against
+/// the source's ffprobe JSON. Returns the copied streams ("video", "audio").
+pub fn apply_smart_copy(args: &mut Vec<String>, probe: &serde_json::Value) -> Vec<String> {
+    let mut copied = Vec::new();
+    if first_stream(probe, "video").is_some_and(|stream| video_copyable(args, stream)) {
+        for option in VIDEO_ENCODER_OPTIONS.iter().chain(&["-vf"]) {
+            while take_option(args, option).is_some() {}
+        }
+        if let Some(pos) = args.iter().position(|a| a == "-c:v" || a == "-vcodec") {
+            args[pos + 1] = "copy".to_string();
+        }
+        copied.push("video".to_string());
+    }
+    if first_stream(probe, "audio").is_some_and(|stream| audio_copyable(args, stream)) {
+        for option in ["-b:a", "-ar", "-ac"] {
+            take_option(args, option);
+        }
+        if let Some(pos) = args.iter().position(|a| a == "-c:a" || a == "-acodec") {
+            args[pos + 1] = "copy".to_string();
+        }
+        copied.push("audio".to_string());
+    }
+    copied
+}
+
+// ============================================================================
+// Audio passthrough
+// ============================================================================
+
+/// Surround formats copied as they are (RenderJob::audio_passthrough)
+const PASSTHROUGH_CODECS: [&str; 3] = ["ac3", "eac3", "dts"];
+
+/// Copy the source's AC3 / E-AC3 / DTS track instead of re-encoding it, when the
+/// codec / container matrix says the output container carries it; any other source
+/// keeps the configured encode. Returns the copied codec.
+pub fn apply_audio_passthrough(
+    args: &mut Vec<String>,
+    probe: &serde_json::Value,
+    container: &str,
+) -> Result<Option<String>, String> {
+    if audio_encoder_in(args).is_none_or(|encoder| encoder == "copy") {
+        return Ok(None);
+    }
+    // aformat only fits the samples to the encoder, a copy does without it
+    let filtered = option_value(args, "-af")
+        .is_some_and(|chain| !chain.split(',').all(|f| f.starts_with("aformat=")));
+    let changes = ["-filter:a", "-filter_complex", "-i"];
+    if filtered || args.iter().any(|a| changes.contains(&a.as_str())) {
+        return Err(
+            "Audio passthrough cannot be combined with audio filters, a speed change or an \
+             external audio track"
+                .to_string(),
+        );
+    }
+    let Some(codec) = first_stream(probe, "audio")
+        .and_then(|stream| stream["codec_name"].as_str())
+        .filter(|codec| PASSTHROUGH_CODECS.contains(codec))
+    else {
+        return Ok(None);
+    };
+    if !crate::codec_compat::container_accepts_audio(container, codec) {
+        return Ok(None);
+    }
+
+    for option in [
+        "-af",
+        "-b:a",
+        "-ar",
+        "-ac",
+        "-q:a",
+        "-aq",
+        "-compression_level",
+    ] {
+        take_option(args, option);
+    }
+    if let Some(pos) = args.iter().position(|a| a == "-c:a" || a == "-acodec") {
+        args[pos + 1] = "copy".to_string();
+    }
+    Ok(Some(codec.to_string()))
+}
+
+// ============================================================================
+// Two-pass encoding
+// ============================================================================
+
+/// Encoders whose quality benefits from (and that support) a two-pass encode
+pub fn supports_two_pass(encoder: &str) -> bool {
+    matches!(
+        encoder,
+        "libvpx-vp9" | "libvpx" | "libx264" | "libx265" | "libaom-av1"
+    )
+}
+
+/// Video encoder named by "-c:v" / "-vcodec" in an argument list
+pub fn video_encoder_in(args: &[String]) -> Option<&str> {
+    args.windows(2)
+        .find(|w| w[0] == "-c:v" || w[0] == "-vcodec")
+        .map(|w| w[1].as_str())
+}
+
+/// `args` plus the options of pass 1 or 2. x265 ignores -pass / -passlogfile and
+/// takes both through -x265-params, merged into the one `args` may already have
+/// (the quotes keep the colon of a Windows drive letter out of the option list).
+fn with_pass(args: &[String], pass: u8, passlog: &str) -> Vec<String> {
+    let mut out = args.to_vec();
+    if video_encoder_in(args) == Some("libx265") {
+        let params = format!("pass={}:stats='{}.log'", pass, passlog);
+        match out.iter().position(|a| a == "-x265-params") {
+            Some(pos) if pos + 1 < out.len() => {
+                out[pos + 1] = format!("{}:{}", out[pos + 1], params);
+            }
+            _ => out.extend(["-x265-params".to_string(), params]),
+        }
+    } else {
+        out.extend([
+            "-pass".to_string(),
+            pass.to_string(),
+            "-passlogfile".to_string(),
+            passlog.to_string(),
+        ]);
+    }
+    out
+}
+
+/// Arguments for the analysis pass: audio dropped, output discarded (write to "-")
+pub fn first_pass_args(args: &[String], passlog: &str) -> Vec<String> {
+    let mut pass = with_pass(args, 1, passlog);
+    pass.extend(["-an", "-f", "null"].iter().map(|s| s.to_string()));
+    pass
+}
+
+/// Arguments for the final pass that reads the analysis log
+pub fn second_pass_args(args: &[String], passlog: &str) -> Vec<String> {
+    with_pass(args, 2, passlog)
+}
+
+/// Hardware encoders that FFmpeg lists even when the GPU cannot run them;
+/// they only count as available after a successful test encode. Full builds ship
+/// every QSV / AMF / VAAPI encoder, so those are probed on every machine.
+fn needs_probe(encoder: &str) -> bool {
+    encoder == "av1_nvenc"
+        || matches!(
+            family_of(encoder),
+            EncoderFamily::Qsv | EncoderFamily::Amf | EncoderFamily::Vaapi
+        )
+}
+
+/// Names of the usable encoders of the configured FFmpeg (empty if unknown)
+pub fn available_encoder_names() -> Vec<String> {
+    crate::encoders::list_encoders()
+        .map(|list| {
+            list.into_iter()
+                .map(|e| e.name)
+                .filter(|name| !needs_probe(name) || crate::encoders::encoder_works(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Av1Support {
+    pub svt_av1: bool,
+    pub aom_av1: bool,
+    /// av1_nvenc initialized (RTX 40-series and newer)
+    pub nvenc_av1: bool,
+    /// av1_qsv initialized (Intel Arc / 11th gen+ iGPU)
+    pub qsv_av1: bool,
+    /// Encoder "av1" resolves to with the current hardware
+    pub recommended: Option<String>,
+}
+
+/// Report which AV1 encoders work with the configured FFmpeg and GPU
+#[tauri::command]
+pub fn get_av1_support() -> Result<Av1Support, String> {
+    let listed: Vec<String> = crate::encoders::list_encoders()?
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    let has = |name: &str| listed.iter().any(|l| l == name);
+    let works = |name: &str| has(name) && crate::encoders::encoder_works(name);
+
+    let support = Av1Support {
+        svt_av1: has("libsvtav1"),
+        aom_av1: has("libaom-av1"),
+        nvenc_av1: works("av1_nvenc"),
+        qsv_av1: works("av1_qsv"),
+        recommended: None,
+    };
+
+    let recommended = [
+        (support.nvenc_av1, "av1_nvenc"),
+        (support.qsv_av1, "av1_qsv"),
+        (support.svt_av1, "libsvtav1"),
+        (support.aom_av1, "libaom-av1"),
+    ]
+    .iter()
+    .find(|(ok, _)| *ok)
+    .map(|(_, name)| name.to_string());
+
+    let _ = crate::write_log(format!(
+        "[AV1] svt={} aom={} nvenc={} qsv={} -> {:?}",
+        support.svt_av1, support.aom_av1, support.nvenc_av1, support.qsv_av1, recommended
+    ));
+
+    Ok(Av1Support {
+        recommended,
+        ..support
+    })
+}
+
+/// Build video encoder arguments for a render
+#[tauri::command]
+pub fn build_encode_args(mut options: EncodeOptions) -> Result<EncodeArgsResult, String> {
+    if options.prefer_gpu && options.hardware.is_none() {
+        options.hardware = detected_hardware().map(str::to_string);
+    }
+    Ok(build_video_args(&options, &available_encoder_names()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_av1_prefers_svt() {
+        let options = EncodeOptions {
+            codec: "av1".to_string(),
+            preset: "slow".to_string(),
+            ..Default::default()
+        };
+        let available = vec!["libaom-av1".to_string(), "libsvtav1".to_string()];
+        let result = build_video_args(&options, &available);
+        assert_eq!(result.encoder.name, "libsvtav1");
+        assert_eq!(
+            result.args,
+            vec!["-c:v", "libsvtav1", "-crf", "32", "-preset", "5"]
+        );
+    }
+
+    #[test]
+    fn test_vp9_constant_quality_uses_row_mt() {
+        let options = EncodeOptions {
+            codec: "vp9".to_string(),
+            ..Default::default()
+        };
+        let result = build_video_args(&options, &[]);
+        assert_eq!(result.encoder.family, EncoderFamily::Vpx);
+        let args = result.args.join(" ");
+        assert!(args.starts_with("-c:v libvpx-vp9 -crf 32 -b:v 0"));
+        assert!(args.contains("-row-mt 1"));
+        assert!(args.contains("-cpu-used 2"));
+    }
+
+    #[test]
+    fn test_av1_nvenc_falls_back_to_cpu() {
+        let options = EncodeOptions {
+            codec: "av1".to_string(),
+            prefer_gpu: true,
+            ..Default::default()
+        };
+        let available = vec!["libsvtav1".to_string(), "h264_nvenc".to_string()];
+        let result = build_video_args(&options, &available);
+        assert_eq!(result.encoder.name, "libsvtav1");
+        assert!(!result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_intel_gpu_uses_qsv() {
+        assert_eq!(hardware_for_vendor("nvidia"), Some("nvenc"));
+        assert_eq!(hardware_for_vendor("intel"), Some("qsv"));
+        assert_eq!(hardware_for_vendor("unknown"), None);
+
+        // NVENC is listed by most FFmpeg builds even without an NVIDIA GPU
+        let options = EncodeOptions {
+            codec: "hevc".to_string(),
+            prefer_gpu: true,
+            hardware: hardware_for_vendor("intel").map(str::to_string),
+            ..Default::default()
+        };
+        let available = vec![
+            "hevc_nvenc".to_string(),
+            "hevc_qsv".to_string(),
+            "libx265".to_string(),
+        ];
+        let result = build_video_args(&options, &available);
+        assert_eq!(result.encoder.name, "hevc_qsv");
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_amd_gpu_uses_amf_or_vaapi() {
+        let api = hardware_for_vendor("amd");
+        let expected = if cfg!(target_os = "windows") {
+            "amf"
+        } else {
+            "vaapi"
+        };
+        assert_eq!(api, Some(expected));
+
+        let options = EncodeOptions {
+            codec: "h264".to_string(),
+            prefer_gpu: true,
+            hardware: api.map(str::to_string),
+            ..Default::default()
+        };
+        let available = vec![
+            "h264_nvenc".to_string(),
+            "h264_amf".to_string(),
+            "h264_vaapi".to_string(),
+            "libx264".to_string(),
+        ];
+        let result = build_video_args(&options, &available);
+        assert_eq!(result.encoder.name, format!("h264_{}", expected));
+        assert_eq!(
+            result.upload_filter.is_some(),
+            expected == "vaapi",
+            "only VAAPI needs hwupload"
+        );
+    }
+
+    #[test]
+    fn test_older_codecs_keep_their_encoder() {
+        let encoder = |codec: &str| {
+            let options = EncodeOptions {
+                codec: codec.to_string(),
+                ..Default::default()
+            };
+            build_video_args(&options, &[]).encoder.name
+        };
+        assert_eq!(encoder("vp8"), "libvpx");
+        assert_eq!(encoder("prores"), "prores_ks");
+        assert_eq!(encoder("h265"), "libx265");
+    }
+
+    #[test]
+    fn test_qsv_used_when_nvenc_missing() {
+        let options = EncodeOptions {
+            codec: "hevc".to_string(),
+            prefer_gpu: true,
+            ..Default::default()
+        };
+        let available = vec!["libx265".to_string(), "hevc_qsv".to_string()];
+        let result = build_video_args(&options, &available);
+        assert_eq!(result.encoder.name, "hevc_qsv");
+        assert!(result.warnings.is_empty());
+        assert!(result.input_args.contains(&"-init_hw_device".to_string()));
+
+        let mut args: Vec<String> = ["-c:v", "h264_qsv", "-global_quality", "23"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        ensure_hw_device_args(&mut args);
+        assert_eq!(args[0], "-init_hw_device");
+        let len = args.len();
+        ensure_hw_device_args(&mut args);
+        assert_eq!(args.len(), len);
+    }
+
+    #[test]
+    fn test_decode_hwaccel_follows_encoder() {
+        assert_eq!(
+            decode_hwaccel("hevc", Some("hevc_nvenc"), false),
+            Some("cuda")
+        );
+        assert_eq!(decode_hwaccel("h264", Some("libx264"), true), Some("cuda"));
+        assert_eq!(decode_hwaccel("prores", Some("h264_nvenc"), true), None);
+        assert_ne!(decode_hwaccel("vp9", Some("h264_qsv"), true), Some("cuda"));
+    }
+
+    #[test]
+    fn test_vaapi_uploads_frames() {
+        let options = EncodeOptions {
+            codec: "h264".to_string(),
+            prefer_gpu: true,
+            hardware: Some("vaapi".to_string()),
+            ..Default::default()
+        };
+        let result = build_video_args(&options, &["h264_vaapi".to_string()]);
+        assert_eq!(result.encoder.family, EncoderFamily::Vaapi);
+        assert!(result.encoder.hardware);
+        assert_eq!(result.args.join(" "), "-c:v h264_vaapi -rc_mode CQP -qp 23");
+        assert_eq!(
+            result.upload_filter.as_deref(),
+            Some("format=nv12,hwupload")
+        );
+
+        let mut args: Vec<String> = ["-vf", "scale=1280:-2", "-c:v", "hevc_vaapi"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        ensure_hw_device_args(&mut args);
+        assert!(args.contains(&"scale=1280:-2,format=nv12,hwupload".to_string()));
+        assert!(args.contains(&"-filter_hw_device".to_string()));
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_speed() {
+        assert_eq!(atempo_chain(1.5), "atempo=1.500000");
+        assert_eq!(
+            atempo_chain(8.0),
+            "atempo=2.000000,atempo=2.000000,atempo=2.000000"
+        );
+        assert_eq!(atempo_chain(0.3), "atempo=0.500000,atempo=0.600000");
+
+        let mut timelapse = args(&[
+            "-ss", "10.000", "-t", "30.000", "-c:v", "libx264", "-c:a", "aac",
+        ]);
+        apply_speed(&mut timelapse, 4.0).unwrap();
+        assert_eq!(
+            timelapse.join(" "),
+            "-ss 2.500 -t 7.500 -c:v libx264 -c:a aac -vf setpts=PTS/4.000000 \
+             -af atempo=2.000000,atempo=2.000000"
+        );
+        assert_eq!(
+            speed_adjusted(crate::units::Duration::from_secs_f64(30.0), Some(4.0)).as_secs_f64(),
+            7.5
+        );
+
+        let mut muted = args(&["-c:v", "libx264", "-an"]);
+        apply_speed(&mut muted, 0.5).unwrap();
+        assert!(!muted.contains(&"-af".to_string()));
+        assert!(apply_speed(&mut args(&["-c:v", "copy"]), 2.0).is_err());
+        assert!(apply_speed(&mut args(&["-c:v", "libx264"]), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_audio_source() {
+        let track = std::env::temp_dir().join("szhimatar-audio-source-test.wav");
+        std::fs::write(&track, b"RIFF").unwrap();
+        let path = track.to_string_lossy().to_string();
+        let encoded = args(&[
+            "-c:v",
+            "libx264",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "192k",
+            "-af",
+            "volume=0.5",
+        ]);
+
+        let mut muted = encoded.clone();
+        apply_audio_source(&mut muted, &AudioSource::Mute).unwrap();
+        assert_eq!(muted, args(&["-c:v", "libx264", "-an"]));
+
+        let mut replaced = encoded.clone();
+        let replace = AudioSource::Replace {
+            path: path.clone(),
+            start_seconds: 2.5,
+            loop_audio: false,
+        };
+        apply_audio_source(&mut replaced, &replace).unwrap();
+        let joined = replaced.join(" ");
+        assert!(joined.starts_with(&format!("-ss 2.500 -i {} -map 0:v:0 -map 1:a:0", path)));
+        assert!(joined.ends_with("-af volume=0.5,apad"));
+
+        let mut mixed = encoded.clone();
+        let mix: AudioSource = serde_json::from_value(serde_json::json!({
+            "mode": "mix", "path": path, "volume": 0.3, "loop_audio": true
+        }))
+        .unwrap();
+        apply_audio_source(&mut mixed, &mix).unwrap();
+        assert_eq!(&mixed[..4], &args(&["-stream_loop", "-1", "-i", &path])[..]);
+        assert!(mixed
+            .iter()
+            .any(|a| a.starts_with("[1:a]volume=0.30[mix]") && a.ends_with(",volume=0.5[aout]")));
+        assert!(!mixed.contains(&"-af".to_string()));
+
+        // Copied audio, a loud mix and a missing file are refused
+        let mut copied = args(&["-c:v", "libx264", "-c:a", "copy"]);
+        assert!(apply_audio_source(&mut copied, &replace).is_err());
+        let loud = AudioSource::Mix {
+            path: path.clone(),
+            volume: 10.0,
+            loop_audio: false,
+        };
+        assert!(apply_audio_source(&mut encoded.clone(), &loud).is_err());
+        let missing = AudioSource::Replace {
+            path: format!("{}.missing", path),
+            start_seconds: 0.0,
+            loop_audio: true,
+        };
+        assert!(apply_audio_source(&mut encoded.clone(), &missing).is_err());
+        let _ = std::fs::remove_file(&track);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let rotated = Orientation {
+            rotation: Rotation::Quarter,
+            flip: Flip::Horizontal,
+            ..Default::default()
+        };
+        let mut encode = args(&["-c:v", "libx264", "-vf", "scale=1280:720"]);
+        let mut input = Vec::new();
+        apply_orientation(&mut encode, &mut input, &rotated).unwrap();
+        assert!(input.is_empty());
+        assert_eq!(
+            encode.join(" "),
+            "-c:v libx264 -vf scale=1280:720,transpose=clock,hflip -metadata:s:v:0 rotate=0"
+        );
+
+        let keep = Orientation {
+            source_rotation: SourceRotation::Keep,
+            ..Default::default()
+        };
+        let mut encode = args(&["-c:v", "libx264"]);
+        apply_orientation(&mut encode, &mut input, &keep).unwrap();
+        assert_eq!(input, args(&["-noautorotate"]));
+        assert_eq!(encode.len(), 2);
+
+        let json: Orientation =
+            serde_json::from_str(r#"{"rotation": "270", "flip": "none"}"#).unwrap();
+        assert_eq!(json.filter().as_deref(), Some("transpose=cclock"));
+        assert!(Orientation {
+            rotation: Rotation::Half,
+            ..keep
+        }
+        .validate()
+        .is_err());
+        assert!(apply_orientation(&mut args(&["-c:v", "copy"]), &mut input, &rotated).is_err());
+    }
+
+    #[test]
+    fn test_output_limits() {
+        let probe = serde_json::json!({"streams": [
+            {"codec_type": "audio"},
+            {"codec_type": "video", "width": 3840, "height": 2160, "avg_frame_rate": "60000/1001"}
+        ]});
+        let source = source_video(&probe).unwrap();
+        assert_eq!((source.width, source.height), (3840, 2160));
+
+        let limits = OutputLimits {
+            max_height: Some(1080),
+            max_fps: Some(30.0),
+        };
+        let mut encode = args(&["-c:v", "libx264", "-vf", "format=yuv420p"]);
+        assert_eq!(
+            apply_limits(&mut encode, &limits, &source).unwrap(),
+            Some(30.0)
+        );
+        assert_eq!(
+            encode[3],
+            "scale='if(gte(iw,ih),-2,1080)':'if(gte(iw,ih),1080,-2)',fps=30,format=yuv420p"
+        );
+
+        // An explicit -r is lowered instead of adding a filter
+        let mut encode = args(&["-c:v", "libx264", "-r", "60"]);
+        apply_limits(&mut encode, &limits, &source).unwrap();
+        assert_eq!(encode[3], "30");
+
+        // Never upscaled
+        let small = SourceVideo {
+            width: 1280,
+            height: 720,
+            fps: 25.0,
+        };
+        let mut encode = args(&["-c:v", "copy"]);
+        assert_eq!(apply_limits(&mut encode, &limits, &small).unwrap(), None);
+        assert_eq!(encode.len(), 2);
+        assert!(apply_limits(&mut encode, &limits, &source).is_err());
+    }
+
+    #[test]
+    fn test_smart_copy() {
+        let probe = serde_json::json!({"streams": [
+            {"codec_type": "video", "codec_name": "h264", "pix_fmt": "yuv420p",
+             "avg_frame_rate": "30/1", "bit_rate": "4000000"},
+            {"codec_type": "audio", "codec_name": "aac", "sample_rate": "48000",
+             "channels": 2, "bit_rate": "128000"}
+        ]});
+        let mut encode = args(&[
+            "-c:v",
+            "libx264",
+            "-b:v",
+            "5M",
+            "-preset",
+            "medium",
+            "-vf",
+            "format=yuv420p",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "128k",
+            "-ar",
+            "48000",
+            "-ac",
+            "2",
+        ]);
+        assert_eq!(
+            apply_smart_copy(&mut encode, &probe),
+            vec!["video", "audio"]
+        );
+        assert_eq!(encode, args(&["-c:v", "copy", "-c:a", "copy"]));
+
+        // Quality mode, a lower bitrate, a scale or a trim keep the re-encode
+        for extra in [
+            &["-crf", "23"][..],
+            &["-b:v", "2M"],
+            &["-b:v", "5M", "-vf", "scale=1280:720"],
+            &["-b:v", "5M", "-t", "10"],
+        ] {
+            let mut encode = args(&["-c:v", "libx264"]);
+            encode.extend(args(extra));
+            encode.extend(args(&["-c:a", "libopus", "-b:a", "128k"]));
+            assert!(apply_smart_copy(&mut encode, &probe).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_capped_crf() {
+        let capped = |codec: &str, bitrate: &str, encoder: &str| {
+            let options = EncodeOptions {
+                codec: codec.to_string(),
+                bitrate: bitrate.to_string(),
+                rate_control: RateControl::CappedCrf,
+                ..Default::default()
+            };
+            video_quality_args(&options, &ResolvedEncoder::named(encoder)).join(" ")
+        };
+        assert!(capped("h264", "", "libx264").starts_with("-crf 23 -maxrate 8M -bufsize 16M"));
+        assert!(capped("h264", "4", "h264_nvenc")
+            .starts_with("-rc vbr -cq 23 -b:v 0 -maxrate 4M -bufsize 8M"));
+        assert!(capped("av1", "", "libsvtav1").starts_with("-crf 32 -maxrate 5M -bufsize 10M"));
+        assert!(capped("vp9", "", "libvpx-vp9").starts_with("-crf 32 -b:v 6M"));
+        assert!(capped("h264", "6", "h264_qsv").starts_with("-b:v 4M -maxrate 6.0M"));
+    }
+
+    #[test]
+    fn test_pixel_format() {
+        let probe = serde_json::json!({"streams": [
+            {"codec_type": "video", "pix_fmt": "yuv420p10le"}
+        ]});
+        let source = source_pix_fmt(&probe);
+        let source = source.as_deref();
+
+        // 10-bit source: kept by x265 / NVENC HEVC, converted down for x264
+        let mut encode = args(&["-c:v", "libx265", "-vf", "scale=1280:720"]);
+        assert_eq!(
+            apply_pixel_format(&mut encode, PixelFormat::Auto, source).unwrap(),
+            Some("yuv420p10le")
+        );
+        assert_eq!(encode[3], "scale=1280:720,format=yuv420p10le");
+        let mut encode = args(&["-c:v", "hevc_nvenc", "-pix_fmt", "yuv420p"]);
+        apply_pixel_format(&mut encode, PixelFormat::Yuv420p10le, Some("yuv420p")).unwrap();
+        assert_eq!(
+            encode,
+            args(&[
+                "-c:v",
+                "hevc_nvenc",
+                "-pix_fmt",
+                "p010le",
+                "-vf",
+                "format=p010le"
+            ])
+        );
+        let mut encode = args(&["-c:v", "libx264"]);
+        apply_pixel_format(&mut encode, PixelFormat::Auto, source).unwrap();
+        assert_eq!(encode[3], "format=yuv420p");
+
+        assert!(apply_pixel_format(&mut encode, PixelFormat::Yuv420p10le, source).is_err());
+        let mut copy = args(&["-c:v", "copy"]);
+        assert_eq!(
+            apply_pixel_format(&mut copy, PixelFormat::Auto, source).unwrap(),
+            None
+        );
+        assert!(apply_pixel_format(&mut copy, PixelFormat::Yuv420p, source).is_err());
+        assert!(!high_bit_depth("nv12") && high_bit_depth("p010le"));
+    }
+
+    #[test]
+    fn test_pass_args() {
+        let to_args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+        let vp9 = to_args(&["-c:v", "libvpx-vp9", "-crf", "32"]);
+        assert_eq!(
+            first_pass_args(&vp9, "/logs/job-pass")[4..],
+            to_args(&[
+                "-pass",
+                "1",
+                "-passlogfile",
+                "/logs/job-pass",
+                "-an",
+                "-f",
+                "null"
+            ])
+        );
+
+        let x265 = to_args(&["-c:v", "libx265", "-b:v", "4M"]);
+        let second = second_pass_args(&x265, "C:/logs/job-pass");
+        assert!(!second.contains(&"-pass".to_string()));
+        assert_eq!(
+            second[4..],
+            to_args(&["-x265-params", "pass=2:stats='C:/logs/job-pass.log'"])
+        );
+
+        let tuned = to_args(&["-c:v", "libx265", "-x265-params", "aq-mode=3"]);
+        assert_eq!(
+            first_pass_args(&tuned, "job-pass")[3],
+            "aq-mode=3:pass=1:stats='job-pass.log'"
+        );
+    }
+
+    #[test]
+    fn test_audio_passthrough() {
+        let probe = |codec: &str| {
+            serde_json::json!({"streams": [
+                {"codec_type": "video", "codec_name": "h264"},
+                {"codec_type": "audio", "codec_name": codec, "channels": 6}
+            ]})
+        };
+        let encode = args(&[
+            "-c:v",
+            "libx264",
+            "-c:a",
+            "libopus",
+            "-b:a",
+            "256k",
+            "-ac",
+            "6",
+            "-af",
+            "aformat=channel_layouts=5.1",
+        ]);
+
+        let mut mkv = encode.clone();
+        assert_eq!(
+            apply_audio_passthrough(&mut mkv, &probe("dts"), "mkv").unwrap(),
+            Some("dts".to_string())
+        );
+        assert_eq!(mkv, args(&["-c:v", "libx264", "-c:a", "copy"]));
+
+        // DTS does not go into MP4 and AAC is not passed through: both are encoded
+        for (codec, container) in [("dts", "mp4"), ("aac", "mkv")] {
+            let mut other = encode.clone();
+            assert_eq!(
+                apply_audio_passthrough(&mut other, &probe(codec), container).unwrap(),
+                None
+            );
+            assert_eq!(other, encode);
+        }
+
+        let mut filtered = encode.clone();
+        *filtered.last_mut().unwrap() = "loudnorm,aformat=channel_layouts=5.1".to_string();
+        assert!(apply_audio_passthrough(&mut filtered, &probe("ac3"), "mp4").is_err());
+    }
+}
@@ -0,0 +1,406 @@
+// Encoder introspection
+// Parses `ffmpeg -h encoder=<name>` and the -encoders/-decoders/-muxers listings
+// into structured data for the UI
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Single AVOption exposed by an encoder
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EncoderOption {
+    pub name: String,
+    pub value_type: String,
+    pub description: String,
+    pub default: Option<String>,
+    /// Named constants accepted by the option (empty for free-form values)
+    pub values: Vec<String>,
+}
+
+/// Capabilities of one encoder, used to populate dropdowns dynamically
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EncoderCapabilities {
+    pub encoder: String,
+    pub description: String,
+    pub pixel_formats: Vec<String>,
+    pub presets: Vec<String>,
+    pub profiles: Vec<String>,
+    pub tunes: Vec<String>,
+    pub rate_control_modes: Vec<String>,
+    pub options: Vec<EncoderOption>,
+}
+
+/// x264/x265 take free-form preset strings, so ffmpeg does not list them
+const X26X_PRESETS: &[&str] = &[
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
+];
+
+/// Parse the output of `ffmpeg -h encoder=<name>`
+pub fn parse_encoder_help(encoder: &str, help: &str) -> EncoderCapabilities {
+    let mut caps = EncoderCapabilities {
+        encoder: encoder.to_string(),
+        ..Default::default()
+    };
+
+    let default_re = regex::Regex::new(r#"\(default "?([^")]*)"?\)"#).ok();
+
+    for line in help.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("Encoder ") {
+            if let (Some(start), Some(end)) = (trimmed.find('['), trimmed.rfind(']')) {
+                if start < end {
+                    caps.description = trimmed[start + 1..end].to_string();
+                }
+            }
+        } else if let Some(formats) = trimmed.strip_prefix("Supported pixel formats:") {
+            caps.pixel_formats = formats.split_whitespace().map(|s| s.to_string()).collect();
+        } else if trimmed.starts_with('-') && line.starts_with("  -") {
+            // Option line: "  -preset <int> E..V....... Set the encoding preset (default p4)"
+            let mut parts = trimmed.split_whitespace();
+            let name = parts.next().unwrap_or("").trim_start_matches('-').to_string();
+            let value_type = parts
+                .next()
+                .unwrap_or("")
+                .trim_matches(|c| c == '<' || c == '>')
+                .to_string();
+            let _flags = parts.next();
+            let description = parts.collect::<Vec<_>>().join(" ");
+            let default = default_re
+                .as_ref()
+                .and_then(|re| re.captures(&description))
+                .and_then(|c| c.get(1))
+                .map(|m| m.as_str().to_string());
+
+            caps.options.push(EncoderOption {
+                name,
+                value_type,
+                description,
+                default,
+                values: Vec::new(),
+            });
+        } else if line.starts_with("     ") && !trimmed.is_empty() {
+            // Named constant belonging to the previous option
+            if let (Some(option), Some(value)) =
+                (caps.options.last_mut(), trimmed.split_whitespace().next())
+            {
+                option.values.push(value.to_string());
+            }
+        }
+    }
+
+    let values_of = |name: &str| -> Vec<String> {
+        caps.options
+            .iter()
+            .find(|o| o.name == name)
+            .map(|o| o.values.clone())
+            .unwrap_or_default()
+    };
+
+    caps.presets = values_of("preset");
+    if caps.presets.is_empty() && (encoder == "libx264" || encoder == "libx265") {
+        caps.presets = X26X_PRESETS.iter().map(|s| s.to_string()).collect();
+    }
+    caps.profiles = values_of("profile");
+    caps.tunes = values_of("tune");
+
+    // Rate control: explicit -rc constants (NVENC/AMF/QSV) plus generic quality knobs
+    let mut modes = values_of("rc");
+    if modes.is_empty() {
+        modes = values_of("rate_control");
+    }
+    let has_option = |name: &str| caps.options.iter().any(|o| o.name == name);
+    for (option, mode) in [("crf", "crf"), ("cq", "cq"), ("qp", "qp"), ("global_quality", "icq")] {
+        if has_option(option) && !modes.iter().any(|m| m == mode) {
+            modes.push(mode.to_string());
+        }
+    }
+    if !modes.iter().any(|m| m == "bitrate") {
+        modes.push("bitrate".to_string());
+    }
+    caps.rate_control_modes = modes;
+
+    caps
+}
+
+/// Run `ffmpeg -h encoder=<name>` and return its raw output
+pub fn encoder_help(ffmpeg_path: &str, encoder: &str) -> Result<String, String> {
+    let arg = format!("encoder={}", encoder);
+
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args(["-hide_banner", "-h", &arg]),
+        &crate::proc::RunOptions::with_timeout(10),
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    let stdout = output.stdout;
+    if stdout.contains(&format!("Unknown encoder '{}'", encoder))
+        || stdout.contains(&format!("Codec '{}' is not recognized", encoder))
+    {
+        return Err(format!("Encoder '{}' is not available in this FFmpeg build", encoder));
+    }
+
+    Ok(stdout)
+}
+
+/// Get presets, profiles, pixel formats and rate-control modes supported by an encoder
+#[tauri::command]
+pub fn get_encoder_options(encoder_name: String) -> Result<EncoderCapabilities, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+
+    let help = encoder_help(&config.ffmpeg_path, encoder_name.trim())?;
+    Ok(parse_encoder_help(encoder_name.trim(), &help))
+}
+
+// ============================================================================
+// Encoder / decoder / muxer listings
+// ============================================================================
+
+/// One entry of `ffmpeg -encoders`, `-decoders` or `-muxers`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FfmpegComponent {
+    pub name: String,
+    /// video, audio, subtitle, data or muxer
+    pub kind: String,
+    pub description: String,
+    pub flags: String,
+}
+
+lazy_static! {
+    /// Listings cached per (binary path, version line, listing flag)
+    static ref LISTING_CACHE: Mutex<HashMap<(String, String, String), Vec<FfmpegComponent>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Parse a listing table; entries start after the "------" / "--" separator line
+pub fn parse_component_listing(output: &str, is_format_list: bool) -> Vec<FfmpegComponent> {
+    let mut components = Vec::new();
+    let mut in_table = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if !in_table {
+            in_table = !trimmed.is_empty() && trimmed.chars().all(|c| c == '-');
+            continue;
+        }
+
+        let mut parts = trimmed.split_whitespace();
+        let (Some(flags), Some(name)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let description = parts.collect::<Vec<_>>().join(" ");
+
+        let kind = if is_format_list {
+            "muxer"
+        } else {
+            match flags.chars().next() {
+                Some('V') => "video",
+                Some('A') => "audio",
+                Some('S') => "subtitle",
+                Some('D') => "data",
+                _ => "unknown",
+            }
+        };
+
+        components.push(FfmpegComponent {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            description,
+            flags: flags.to_string(),
+        });
+    }
+
+    components
+}
+
+fn list_components(listing_flag: &str) -> Result<Vec<FfmpegComponent>, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+
+    let version = crate::get_binary_version_internal(&config.ffmpeg_path)
+        .ok_or_else(|| "Cannot execute FFmpeg".to_string())?;
+    let key = (
+        config.ffmpeg_path.clone(),
+        version,
+        listing_flag.to_string(),
+    );
+
+    if let Some(cached) = LISTING_CACHE.lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return Ok(cached);
+    }
+
+    let output = crate::proc::run_captured(
+        crate::proc::command(&config.ffmpeg_path).args(["-hide_banner", listing_flag]),
+        &crate::proc::RunOptions::with_timeout(10),
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    let stdout = output.stdout;
+    let components = parse_component_listing(&stdout, listing_flag == "-muxers");
+
+    if let Ok(mut cache) = LISTING_CACHE.lock() {
+        cache.insert(key, components.clone());
+    }
+
+    Ok(components)
+}
+
+/// List encoders supported by the configured FFmpeg
+#[tauri::command]
+pub fn list_encoders() -> Result<Vec<FfmpegComponent>, String> {
+    list_components("-encoders")
+}
+
+/// List decoders supported by the configured FFmpeg
+#[tauri::command]
+pub fn list_decoders() -> Result<Vec<FfmpegComponent>, String> {
+    list_components("-decoders")
+}
+
+/// List output formats (muxers) supported by the configured FFmpeg
+#[tauri::command]
+pub fn list_muxers() -> Result<Vec<FfmpegComponent>, String> {
+    list_components("-muxers")
+}
+
+// ============================================================================
+// Hardware encoder probing
+// ============================================================================
+
+lazy_static! {
+    /// Probe results cached per (binary path, encoder)
+    static ref PROBE_CACHE: Mutex<HashMap<(String, String), bool>> = Mutex::new(HashMap::new());
+}
+
+/// Check that an encoder actually initializes on this machine by encoding a tiny
+/// test clip. Hardware encoders are listed by `-encoders` whenever FFmpeg was built
+/// with them, even if the GPU cannot run them (e.g. av1_nvenc before RTX 40-series).
+pub fn encoder_works(encoder: &str) -> bool {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return false;
+    }
+
+    let key = (config.ffmpeg_path.clone(), encoder.to_string());
+    if let Some(cached) = PROBE_CACHE.lock().ok().and_then(|c| c.get(&key).copied()) {
+        return cached;
+    }
+
+    // QSV / VAAPI need their device (and upload filter) set up the same way a real render does
+    let mut args = crate::encode_args::hw_device_args(encoder);
+    args.extend(
+        [
+            "-hide_banner",
+            "-v",
+            "error",
+            "-f",
+            "lavfi",
+            "-i",
+            "color=black:s=256x256:d=0.1",
+            "-frames:v",
+            "1",
+        ]
+        .iter()
+        .map(|a| a.to_string()),
+    );
+    if let Some(filter) = crate::encode_args::hw_upload_filter(encoder) {
+        args.extend(["-vf".to_string(), filter.to_string()]);
+    }
+    args.extend(
+        ["-c:v", encoder, "-f", "null", "-"]
+            .iter()
+            .map(|a| a.to_string()),
+    );
+
+    // A broken driver can hang encoder initialization, so the probe is time-boxed
+    let works = crate::proc::run_captured(
+        crate::proc::command(&config.ffmpeg_path).args(&args),
+        &crate::proc::RunOptions::with_timeout(15).label("encoder probe"),
+    )
+    .map(|o| o.success)
+    .unwrap_or(false);
+
+    if let Ok(mut cache) = PROBE_CACHE.lock() {
+        cache.insert(key, works);
+    }
+
+    works
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NVENC_HELP: &str = r#"Encoder hevc_nvenc [NVIDIA NVENC hevc encoder]:
+    General capabilities: dr1 delay hardware
+    Threading capabilities: none
+    Supported pixel formats: yuv420p nv12 p010le yuv444p
+hevc_nvenc AVOptions:
+  -preset            <int>        E..V....... Set the encoding preset (from 0 to 18) (default p4)
+     default         0            E..V.......
+     slow            1            E..V....... hq 2 passes
+     p1              12           E..V....... fastest (lowest quality)
+  -profile           <int>        E..V....... Set the encoding profile (from 0 to 4) (default main)
+     main            0            E..V.......
+     main10          1            E..V.......
+  -rc                <int>        E..V....... Override the preset rate-control (from -1 to INT_MAX) (default -1)
+     constqp                      E..V....... Constant QP mode
+     vbr                          E..V....... Variable bitrate mode
+  -cq                <float>      E..V....... Set target quality level (from 0 to 51) (default 0)
+"#;
+
+    #[test]
+    fn test_parse_nvenc_help() {
+        let caps = parse_encoder_help("hevc_nvenc", NVENC_HELP);
+        assert_eq!(caps.description, "NVIDIA NVENC hevc encoder");
+        assert_eq!(caps.pixel_formats, vec!["yuv420p", "nv12", "p010le", "yuv444p"]);
+        assert_eq!(caps.presets, vec!["default", "slow", "p1"]);
+        assert_eq!(caps.profiles, vec!["main", "main10"]);
+        assert_eq!(caps.rate_control_modes, vec!["constqp", "vbr", "cq", "bitrate"]);
+        assert_eq!(caps.options[0].default.as_deref(), Some("p4"));
+    }
+
+    #[test]
+    fn test_x264_preset_fallback() {
+        let help = "Encoder libx264 [libx264 H.264 / AVC]:\nlibx264 AVOptions:\n  -preset            <string>     E..V....... Set the encoding preset (default \"medium\")\n  -crf               <float>      E..V....... Select the quality for constant quality mode (from -1 to FLT_MAX) (default -1)\n";
+        let caps = parse_encoder_help("libx264", help);
+        assert_eq!(caps.presets.len(), X26X_PRESETS.len());
+        assert_eq!(caps.options[0].default.as_deref(), Some("medium"));
+        assert_eq!(caps.rate_control_modes, vec!["crf", "bitrate"]);
+    }
+
+    #[test]
+    fn test_parse_encoder_listing() {
+        let output = "Encoders:\n V..... = Video\n A..... = Audio\n ------\n V....D libx264              libx264 H.264 / AVC (codec h264)\n A....D aac                  AAC (Advanced Audio Coding)\n";
+        let list = parse_component_listing(output, false);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].name, "libx264");
+        assert_eq!(list[0].kind, "video");
+        assert_eq!(list[1].kind, "audio");
+        assert_eq!(list[1].description, "AAC (Advanced Audio Coding)");
+    }
+
+    #[test]
+    fn test_parse_muxer_listing() {
+        let output = "File formats:\n D. = Demuxing supported\n .E = Muxing supported\n --\n  E 3g2             3GP2 (3GPP2 file format)\n  E webm            WebM\n";
+        let list = parse_component_listing(output, true);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].name, "webm");
+        assert_eq!(list[1].kind, "muxer");
+    }
+}
@@ -0,0 +1,237 @@
+// FFmpeg feature check
+// A build without libx265 or NVENC only fails once a render starts, with an FFmpeg error
+// like "Unknown encoder 'libx265'". check_ffmpeg_features reads the version, the
+// configuration line and the encoder list of the configured binary up front and reports
+// each capability the app relies on, with what to do when it is missing.
+
+use serde::Serialize;
+
+/// Oldest release with the NVENC p1-p7 presets the encode arguments use
+const MIN_VERSION: (u32, u32) = (4, 3);
+
+/// Capability the app relies on
+struct Feature {
+    id: &'static str,
+    /// Any of these encoders provides it
+    encoders: &'static [&'static str],
+    /// Configure flag providing it (for filters, which -encoders does not list)
+    configure_flag: Option<&'static str>,
+    used_for: &'static str,
+    /// What to do without it
+    hint: &'static str,
+}
+
+const FEATURES: &[Feature] = &[
+    Feature {
+        id: "libx264",
+        encoders: &["libx264"],
+        configure_flag: None,
+        used_for: "H.264 CPU encoding",
+        hint: "Use a GPL build of FFmpeg (e.g. the gyan.dev or BtbN builds)",
+    },
+    Feature {
+        id: "libx265",
+        encoders: &["libx265"],
+        configure_flag: None,
+        used_for: "H.265 CPU encoding",
+        hint: "Use a GPL build of FFmpeg (e.g. the gyan.dev or BtbN builds), or pick a GPU encoder",
+    },
+    Feature {
+        id: "libopus",
+        encoders: &["libopus"],
+        configure_flag: None,
+        used_for: "Opus audio",
+        hint: "Use a build with --enable-libopus, or pick AAC audio",
+    },
+    Feature {
+        id: "libvmaf",
+        encoders: &[],
+        configure_flag: Some("--enable-libvmaf"),
+        used_for: "VMAF quality measurement",
+        hint: "Use a \"full\" build with --enable-libvmaf; quality checks are skipped meanwhile",
+    },
+    Feature {
+        id: "libvidstab",
+        encoders: &[],
+        configure_flag: Some("--enable-libvidstab"),
+        used_for: "Video stabilization",
+        hint: "Use a \"full\" build with --enable-libvidstab, or turn stabilization off",
+    },
+    Feature {
+        id: "nvenc",
+        encoders: &["h264_nvenc", "hevc_nvenc"],
+        configure_flag: None,
+        used_for: "NVIDIA GPU encoding",
+        hint: "Use a build with NVENC (--enable-nvenc / ffnvcodec), or pick a CPU encoder",
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureStatus {
+    pub id: String,
+    pub available: bool,
+    pub used_for: String,
+    /// What to do about a missing feature
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FfmpegFeatureReport {
+    /// First line of `ffmpeg -version`
+    pub version_line: String,
+    /// "major.minor" of a release build; None for git snapshots
+    pub version: Option<String>,
+    /// Older than MIN_VERSION; None when unknown
+    pub version_too_old: Option<bool>,
+    /// Flags of the configuration line
+    pub configuration: Vec<String>,
+    pub features: Vec<FeatureStatus>,
+    /// Messages of the missing features and of an outdated version
+    pub problems: Vec<String>,
+}
+
+/// Major and minor of "ffmpeg version 6.1.1-full_build-www.gyan.dev ..." or
+/// "ffmpeg version n7.0 ..."; None for snapshots like "N-113000-g1234abcd"
+fn parse_version(version_line: &str) -> Option<(u32, u32)> {
+    let version = version_line
+        .split_whitespace()
+        .skip_while(|word| *word != "version")
+        .nth(1)?;
+    let version = version.strip_prefix('n').unwrap_or(version);
+    let mut numbers = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u32>().ok());
+    let major = numbers.next()??;
+    let minor = numbers.next().flatten().unwrap_or(0);
+    Some((major, minor))
+}
+
+/// Flags of the "configuration:" line of `ffmpeg -version`
+fn parse_configuration(version_output: &str) -> Vec<String> {
+    version_output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("configuration:"))
+        .map(|flags| flags.split_whitespace().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn check_features(configuration: &[String], encoders: &[String]) -> Vec<FeatureStatus> {
+    FEATURES
+        .iter()
+        .map(|feature| {
+            let available = feature
+                .encoders
+                .iter()
+                .any(|e| encoders.iter().any(|x| x == e))
+                || feature
+                    .configure_flag
+                    .is_some_and(|flag| configuration.iter().any(|f| f == flag));
+            FeatureStatus {
+                id: feature.id.to_string(),
+                available,
+                used_for: feature.used_for.to_string(),
+                message: (!available).then(|| {
+                    format!(
+                        "This FFmpeg build has no {} ({}). {}.",
+                        feature.id, feature.used_for, feature.hint
+                    )
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Version and capabilities of the configured FFmpeg, with a message per missing one
+#[tauri::command]
+pub async fn check_ffmpeg_features() -> Result<FfmpegFeatureReport, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    let output = crate::proc::run_captured(
+        crate::proc::command(&config.ffmpeg_path).arg("-version"),
+        &crate::proc::RunOptions::with_timeout(10),
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.success {
+        return Err("Cannot execute FFmpeg".to_string());
+    }
+    let version_line = output.stdout.lines().next().unwrap_or_default().to_string();
+    let configuration = parse_configuration(&output.stdout);
+    let encoders: Vec<String> = crate::encoders::list_encoders()?
+        .into_iter()
+        .map(|component| component.name)
+        .collect();
+
+    let version = parse_version(&version_line);
+    let version_too_old = version.map(|v| v < MIN_VERSION);
+    let features = check_features(&configuration, &encoders);
+    let mut problems: Vec<String> = features.iter().filter_map(|f| f.message.clone()).collect();
+    if let Some((major, minor)) = version.filter(|v| *v < MIN_VERSION) {
+        problems.insert(
+            0,
+            format!(
+                "FFmpeg {}.{} is older than {}.{}; GPU presets will fail. Update FFmpeg.",
+                major, minor, MIN_VERSION.0, MIN_VERSION.1
+            ),
+        );
+    }
+    if !problems.is_empty() {
+        let _ = crate::write_log(format!(
+            "[FFMPEG] {}: {}",
+            version_line,
+            problems.join(" | ")
+        ));
+    }
+
+    Ok(FfmpegFeatureReport {
+        version_line,
+        version: version.map(|(major, minor)| format!("{}.{}", major, minor)),
+        version_too_old,
+        configuration,
+        features,
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("ffmpeg version 6.1.1-full_build-www.gyan.dev Copyright (c) 2000-2023"),
+            Some((6, 1))
+        );
+        assert_eq!(parse_version("ffmpeg version n7.0 Copyright"), Some((7, 0)));
+        assert_eq!(parse_version("ffmpeg version 5 Copyright"), Some((5, 0)));
+        assert_eq!(
+            parse_version("ffmpeg version N-113000-g1234abcd Copyright"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_features() {
+        let output = "ffmpeg version 6.0\nbuilt with gcc 12\nconfiguration: --enable-gpl --enable-libx264 --enable-libvmaf\nlibavutil 58. 2.100\n";
+        let configuration = parse_configuration(output);
+        assert_eq!(configuration.len(), 3);
+        let encoders = vec!["libx264".to_string(), "hevc_nvenc".to_string()];
+        let features = check_features(&configuration, &encoders);
+        let available = |id: &str| features.iter().find(|f| f.id == id).unwrap().available;
+        assert!(available("libx264"));
+        assert!(available("libvmaf"));
+        assert!(available("nvenc"));
+        assert!(!available("libx265"));
+        assert!(!available("libopus"));
+        assert!(features
+            .iter()
+            .find(|f| f.id == "libx265")
+            .unwrap()
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("GPL build"));
+    }
+}
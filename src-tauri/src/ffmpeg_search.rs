@@ -0,0 +1,435 @@
+// FFmpeg search
+// Fast search: besides PATH and a few fixed folders, the install locations of package
+// managers (winget, Chocolatey, Scoop, Homebrew, MacPorts, snap) and the Windows
+// App Paths registry keys are checked, which finds most installs in milliseconds.
+// Deep search: last resort when ffmpeg/ffprobe are still missing; every local drive is
+// walked for the missing binaries at once. The top-level folders of each drive are
+// shared out to a pool of walker threads; network drives and mounts, system folders and
+// virtual filesystems are skipped. The walk reports the folder it is in through
+// `ffmpeg-search-progress` and stops early on `cancel_ffmpeg_search`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Folder levels below a drive root that are searched
+const MAX_DEPTH: usize = 10;
+/// Parallel walker threads
+const MAX_WALKERS: usize = 8;
+/// Minimum time between two progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Folders never worth descending into (matched lowercase, anywhere in the path)
+#[cfg(windows)]
+const SKIPPED_FOLDERS: &[&str] = &[
+    "windows\\winsxs",
+    "windows\\system32",
+    "$recycle.bin",
+    "system volume information",
+];
+
+/// Virtual filesystems and folders never worth descending into
+#[cfg(not(windows))]
+const SKIPPED_FOLDERS: &[&str] = &["/proc", "/sys", "/dev", "/run", "/snap"];
+
+static SEARCH_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Payload of `ffmpeg-search-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepSearchProgress {
+    /// Files and folders looked at so far
+    pub checked: usize,
+    /// Folder being scanned
+    pub directory: String,
+}
+
+/// Binaries found by name; `cancelled` when the user stopped the search
+#[derive(Debug, Default)]
+pub struct DeepSearchResult {
+    pub found: HashMap<String, String>,
+    pub cancelled: bool,
+}
+
+fn exe_name(binary: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", binary)
+    } else {
+        binary.to_string()
+    }
+}
+
+fn same_file_name(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// "6.1.1" -> [6, 1, 1], for newest-first ordering of versioned folders
+#[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+fn version_key(name: &str) -> Vec<u64> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// `<parent>/<version>/bin` folders, newest version first (Homebrew Cellar, Scoop apps)
+#[cfg_attr(not(any(windows, target_os = "macos")), allow(dead_code))]
+fn versioned_bin_dirs(parent: &Path) -> Vec<PathBuf> {
+    let mut versions: Vec<PathBuf> = std::fs::read_dir(parent)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("bin").is_dir())
+        .collect();
+    versions.sort_by_key(|path| {
+        std::cmp::Reverse(version_key(
+            &path.file_name().unwrap_or_default().to_string_lossy(),
+        ))
+    });
+    versions.into_iter().map(|path| path.join("bin")).collect()
+}
+
+/// Folders package managers put ffmpeg in (or link it from)
+pub fn package_manager_dirs() -> Vec<PathBuf> {
+    let mut folders = Vec::new();
+
+    #[cfg(windows)]
+    {
+        let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+        let home = dirs::home_dir().unwrap_or_default();
+        let local_app_data = dirs::data_local_dir().unwrap_or_default();
+        let program_data =
+            env_dir("ProgramData").unwrap_or_else(|| PathBuf::from("C:\\ProgramData"));
+
+        // winget: links for portable installs, then the package folders themselves
+        // (e.g. Packages\Gyan.FFmpeg_...\ffmpeg-7.1-full_build\bin)
+        let winget_roots = [
+            local_app_data.join("Microsoft\\WinGet"),
+            PathBuf::from("C:\\Program Files\\WinGet"),
+        ];
+        for root in &winget_roots {
+            folders.push(root.join("Links"));
+            let packages = std::fs::read_dir(root.join("Packages"))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .contains("ffmpeg")
+                })
+                .map(|entry| entry.path());
+            for package in packages {
+                folders.extend(versioned_bin_dirs(&package));
+            }
+        }
+
+        // Chocolatey shims and the package's own folder
+        let chocolatey =
+            env_dir("ChocolateyInstall").unwrap_or_else(|| program_data.join("chocolatey"));
+        folders.push(chocolatey.join("bin"));
+        folders.push(chocolatey.join("lib\\ffmpeg\\tools\\ffmpeg\\bin"));
+        folders.push(chocolatey.join("lib\\ffmpeg-full\\tools\\ffmpeg\\bin"));
+
+        // Scoop, per user and global
+        let scoop_roots = [
+            env_dir("SCOOP").unwrap_or_else(|| home.join("scoop")),
+            env_dir("SCOOP_GLOBAL").unwrap_or_else(|| program_data.join("scoop")),
+        ];
+        for root in &scoop_roots {
+            folders.push(root.join("shims"));
+            for app in ["ffmpeg", "ffmpeg-shared", "ffmpeg-nightly"] {
+                folders.push(root.join("apps").join(app).join("current\\bin"));
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Homebrew (Apple Silicon, Intel), then MacPorts
+        for prefix in ["/opt/homebrew", "/usr/local"] {
+            folders.push(PathBuf::from(prefix).join("opt/ffmpeg/bin"));
+            folders.extend(versioned_bin_dirs(&Path::new(prefix).join("Cellar/ffmpeg")));
+        }
+        folders.push(PathBuf::from("/opt/local/bin"));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        folders.push(PathBuf::from("/snap/bin"));
+        folders.push(PathBuf::from("/home/linuxbrew/.linuxbrew/bin"));
+        folders.push(dirs::home_dir().unwrap_or_default().join(".linuxbrew/bin"));
+    }
+
+    folders
+}
+
+/// Path registered for `exe_name` under the App Paths registry key (machine, then user)
+#[cfg(windows)]
+pub fn app_paths_entry(exe_name: &str) -> Option<PathBuf> {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    let key = format!(
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\{}",
+        exe_name
+    );
+    [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER]
+        .into_iter()
+        .filter_map(|hive| winreg::RegKey::predef(hive).open_subkey(&key).ok())
+        .filter_map(|key| key.get_value::<String, _>("").ok())
+        .map(|path| PathBuf::from(path.trim_matches('"')))
+        .find(|path| path.is_file())
+}
+
+#[cfg(not(windows))]
+pub fn app_paths_entry(_exe_name: &str) -> Option<PathBuf> {
+    None
+}
+
+/// Local drives to walk: fixed drive letters on Windows, / elsewhere
+fn search_roots() -> Vec<PathBuf> {
+    #[cfg(windows)]
+    {
+        ('C'..='Z')
+            .map(|letter| format!("{}:\\", letter))
+            .filter(|root| Path::new(root).is_dir())
+            .filter(|root| !crate::network_paths::is_mapped_drive(root))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    #[cfg(not(windows))]
+    {
+        vec![PathBuf::from("/")]
+    }
+}
+
+/// Folders not to descend into: system folders, plus network mounts
+fn is_skipped(path: &Path, network_mounts: &[PathBuf]) -> bool {
+    let system = if cfg!(windows) {
+        let lower = path.to_string_lossy().to_lowercase();
+        SKIPPED_FOLDERS.iter().any(|folder| lower.contains(folder))
+    } else {
+        SKIPPED_FOLDERS
+            .iter()
+            .any(|folder| path == Path::new(folder))
+    };
+    system || network_mounts.iter().any(|mount| path == mount)
+}
+
+/// Walk `roots` for the `binaries` (names without extension) with a pool of threads.
+/// `accept` validates a candidate file; the walk ends when all are found, when
+/// `cancelled` turns true, or when every folder was seen.
+pub fn find_binaries(
+    roots: &[PathBuf],
+    binaries: &[&str],
+    skip: &(dyn Fn(&Path) -> bool + Sync),
+    accept: &(dyn Fn(&Path) -> bool + Sync),
+    cancelled: &(dyn Fn() -> bool + Sync),
+    on_progress: &(dyn Fn(DeepSearchProgress) + Sync),
+) -> DeepSearchResult {
+    let wanted: Vec<(String, String)> = binaries
+        .iter()
+        .map(|binary| (binary.to_string(), exe_name(binary)))
+        .collect();
+    let found: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    let all_found = || {
+        found
+            .lock()
+            .map(|f| f.len() == wanted.len())
+            .unwrap_or(true)
+    };
+
+    // Binaries right at a root are checked here; its folders are the walkers' work units
+    let check_file = |path: &Path| {
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            return;
+        };
+        for (binary, exe) in &wanted {
+            if !same_file_name(&name, exe) {
+                continue;
+            }
+            let known = found.lock().map(|f| f.contains_key(binary)).unwrap_or(true);
+            if !known && accept(path) {
+                let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+                if let Ok(mut found) = found.lock() {
+                    found
+                        .entry(binary.clone())
+                        .or_insert_with(|| path.to_string_lossy().to_string());
+                }
+            }
+        }
+    };
+    let mut units: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        for entry in std::fs::read_dir(root).into_iter().flatten().flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !skip(&path) => units.push(path),
+                Ok(kind) if kind.is_file() => check_file(&path),
+                _ => {}
+            }
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let checked = AtomicUsize::new(0);
+    let throttle = Mutex::new(crate::progress::Throttle::new(PROGRESS_INTERVAL));
+    let walkers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(2)
+        .min(MAX_WALKERS)
+        .min(units.len().max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..walkers {
+            scope.spawn(|| {
+                while let Some(unit) = units.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let walker = WalkDir::new(unit)
+                        .max_depth(MAX_DEPTH - 1)
+                        .follow_links(false)
+                        .into_iter()
+                        .filter_entry(|e| !e.file_type().is_dir() || !skip(e.path()));
+                    for entry in walker.filter_map(|e| e.ok()) {
+                        if cancelled() || all_found() {
+                            return;
+                        }
+                        let count = checked.fetch_add(1, Ordering::Relaxed) + 1;
+                        if entry.file_type().is_dir() {
+                            // Another walker holding the throttle reports for both
+                            if let Ok(mut throttle) = throttle.try_lock() {
+                                let progress = DeepSearchProgress {
+                                    checked: count,
+                                    directory: entry.path().to_string_lossy().to_string(),
+                                };
+                                if let Some(progress) = throttle.offer(progress, Instant::now()) {
+                                    on_progress(progress);
+                                }
+                            }
+                        } else {
+                            check_file(entry.path());
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    DeepSearchResult {
+        found: found.into_inner().unwrap_or_default(),
+        cancelled: cancelled(),
+    }
+}
+
+/// Search every local drive for the missing binaries, reporting progress to `window`
+pub fn deep_search(binaries: &[&str], window: &tauri::Window) -> DeepSearchResult {
+    SEARCH_CANCELLED.store(false, Ordering::SeqCst);
+    let roots = search_roots();
+    let network_mounts = crate::network_paths::network_mount_points();
+    let _ = crate::write_log(format!(
+        "[FFMPEG] Deep search for {} in {:?}",
+        binaries.join(", "),
+        roots
+    ));
+    let started = Instant::now();
+    let result = find_binaries(
+        &roots,
+        binaries,
+        &|path| is_skipped(path, &network_mounts),
+        &|path| crate::get_binary_version_internal(&path.to_string_lossy()).is_some(),
+        &|| SEARCH_CANCELLED.load(Ordering::SeqCst),
+        &|progress| {
+            let _ = window.emit("ffmpeg-search-progress", &progress);
+        },
+    );
+    let _ = crate::write_log(format!(
+        "[FFMPEG] Deep search {} after {:.0}s, found {:?}",
+        if result.cancelled {
+            "cancelled"
+        } else {
+            "finished"
+        },
+        started.elapsed().as_secs_f64(),
+        result.found
+    ));
+    result
+}
+
+/// Stop a running deep search; what it found so far is kept
+#[tauri::command]
+pub fn cancel_ffmpeg_search() {
+    SEARCH_CANCELLED.store(true, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_bin_dirs() {
+        let cellar = std::env::temp_dir().join("szhimatar-cellar-test");
+        for version in ["6.0", "6.1.1_2", "10.0", "no-bin"] {
+            let dir = cellar.join(version);
+            std::fs::create_dir_all(if version == "no-bin" {
+                dir
+            } else {
+                dir.join("bin")
+            })
+            .unwrap();
+        }
+        let dirs = versioned_bin_dirs(&cellar);
+        assert_eq!(
+            dirs,
+            vec![
+                cellar.join("10.0").join("bin"),
+                cellar.join("6.1.1_2").join("bin"),
+                cellar.join("6.0").join("bin"),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&cellar);
+    }
+
+    #[test]
+    fn test_find_binaries() {
+        let root = std::env::temp_dir().join("szhimatar-deep-search-test");
+        let bin = root.join("tools").join("ffmpeg").join("bin");
+        let skipped = root.join("skipped");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::create_dir_all(&skipped).unwrap();
+        std::fs::write(bin.join(exe_name("ffmpeg")), b"").unwrap();
+        std::fs::write(skipped.join(exe_name("ffprobe")), b"").unwrap();
+        let roots = [root.clone()];
+
+        let result = find_binaries(
+            &roots,
+            &["ffmpeg", "ffprobe"],
+            &|path| path.ends_with("skipped"),
+            &|_| true,
+            &|| false,
+            &|_| {},
+        );
+        assert!(!result.cancelled);
+        assert!(result.found["ffmpeg"].ends_with(&exe_name("ffmpeg")));
+        assert!(!result.found.contains_key("ffprobe"));
+
+        let result = find_binaries(
+            &roots,
+            &["ffmpeg"],
+            &|_| false,
+            &|_| true,
+            &|| true,
+            &|_| {},
+        );
+        assert!(result.cancelled);
+        assert!(result.found.is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,291 @@
+// FFmpeg installations
+// Several FFmpeg builds can live side by side in ~/.szhimatar/ffmpeg/<id>/ (binaries in
+// the folder itself or in its bin/ subfolder). One of them, or any build found by the
+// search, is active through ffmpeg.json as before. Presets can pin another build, e.g. a
+// recent one for AV1 NVENC while everything else stays on a proven release; the pins are
+// kept in ffmpeg/overrides.json by preset name.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Id of the configured binaries when they are not one of the managed builds
+const CONFIGURED_ID: &str = "configured";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FfmpegInstallation {
+    pub id: String,
+    pub ffmpeg_path: String,
+    pub ffprobe_path: String,
+    /// First line of `ffmpeg -version`; None when the binary does not run
+    pub version: Option<String>,
+    /// Lives in the app data folder (can be removed from the app)
+    pub managed: bool,
+    /// Used by renders without a preset override
+    pub active: bool,
+}
+
+fn installations_dir() -> PathBuf {
+    crate::get_app_data_dir().join("ffmpeg")
+}
+
+fn overrides_file() -> PathBuf {
+    installations_dir().join("overrides.json")
+}
+
+fn exe_name(binary: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", binary)
+    } else {
+        binary.to_string()
+    }
+}
+
+/// ffmpeg and ffprobe of a build folder: in the folder itself or in its bin/
+fn binaries_in(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    [dir.to_path_buf(), dir.join("bin")]
+        .into_iter()
+        .find_map(|dir| {
+            let (ffmpeg, ffprobe) = (dir.join(exe_name("ffmpeg")), dir.join(exe_name("ffprobe")));
+            (ffmpeg.is_file() && ffprobe.is_file()).then_some((ffmpeg, ffprobe))
+        })
+}
+
+/// Folder name for an imported build: letters, digits, '.', '-' and '_'
+fn sanitize_id(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    let canonical = |p: &str| std::fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p));
+    canonical(a) == canonical(b)
+}
+
+fn managed_installations() -> Vec<(String, PathBuf, PathBuf)> {
+    let mut found: Vec<(String, PathBuf, PathBuf)> = std::fs::read_dir(installations_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let (ffmpeg, ffprobe) = binaries_in(&entry.path())?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                ffmpeg,
+                ffprobe,
+            ))
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+fn load_overrides() -> HashMap<String, String> {
+    std::fs::read_to_string(overrides_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_overrides(overrides: &HashMap<String, String>) -> Result<(), String> {
+    std::fs::create_dir_all(installations_dir())
+        .map_err(|e| format!("Failed to create FFmpeg folder: {}", e))?;
+    let content = serde_json::to_string_pretty(overrides).map_err(|e| e.to_string())?;
+    std::fs::write(overrides_file(), content)
+        .map_err(|e| format!("Failed to save preset overrides: {}", e))
+}
+
+fn find_installation(id: &str) -> Result<FfmpegInstallation, String> {
+    list_ffmpeg_installations()
+        .into_iter()
+        .find(|installation| installation.id == id)
+        .ok_or_else(|| format!("FFmpeg installation not found: {}", id))
+}
+
+/// ffmpeg and ffprobe pinned by a preset, if its installation still exists
+pub fn preset_override(preset_name: &str) -> Option<(String, String)> {
+    let id = load_overrides().remove(preset_name)?;
+    match binaries_in(&installations_dir().join(&id)) {
+        Some((ffmpeg, ffprobe)) => Some((
+            ffmpeg.to_string_lossy().to_string(),
+            ffprobe.to_string_lossy().to_string(),
+        )),
+        None => {
+            let _ = crate::write_log(format!(
+                "[FFMPEG] Installation '{}' of preset '{}' is missing, using the active one",
+                id, preset_name
+            ));
+            None
+        }
+    }
+}
+
+/// Managed builds plus the configured binaries when they live elsewhere
+#[tauri::command]
+pub fn list_ffmpeg_installations() -> Vec<FfmpegInstallation> {
+    let config = crate::load_ffmpeg_config();
+    let mut installations: Vec<FfmpegInstallation> = managed_installations()
+        .into_iter()
+        .map(|(id, ffmpeg, ffprobe)| {
+            let ffmpeg_path = ffmpeg.to_string_lossy().to_string();
+            FfmpegInstallation {
+                id,
+                version: crate::get_binary_version_internal(&ffmpeg_path),
+                active: !config.ffmpeg_path.is_empty()
+                    && same_path(&ffmpeg_path, &config.ffmpeg_path),
+                ffmpeg_path,
+                ffprobe_path: ffprobe.to_string_lossy().to_string(),
+                managed: true,
+            }
+        })
+        .collect();
+    if !config.ffmpeg_path.is_empty() && !installations.iter().any(|i| i.active) {
+        installations.insert(
+            0,
+            FfmpegInstallation {
+                id: CONFIGURED_ID.to_string(),
+                version: crate::get_binary_version_internal(&config.ffmpeg_path),
+                ffmpeg_path: config.ffmpeg_path,
+                ffprobe_path: config.ffprobe_path,
+                managed: false,
+                active: true,
+            },
+        );
+    }
+    installations
+}
+
+/// Make an installation the one renders use by default
+#[tauri::command]
+pub fn set_active_ffmpeg(id: String) -> Result<FfmpegInstallation, String> {
+    let installation = find_installation(&id)?;
+    if installation.version.is_none() {
+        return Err(format!("FFmpeg of '{}' does not run", id));
+    }
+    crate::save_ffmpeg_config(&crate::FfmpegConfig {
+        ffmpeg_path: installation.ffmpeg_path.clone(),
+        ffprobe_path: installation.ffprobe_path.clone(),
+        discovered_at: chrono::Utc::now().to_rfc3339(),
+    })?;
+    let _ = crate::write_log(format!(
+        "[FFMPEG] Active installation: {} ({:?})",
+        id, installation.version
+    ));
+    Ok(FfmpegInstallation {
+        active: true,
+        ..installation
+    })
+}
+
+/// Copy a build (a folder with ffmpeg and ffprobe, or its bin/) into the app data folder
+#[tauri::command]
+pub fn import_ffmpeg_installation(
+    source_dir: String,
+    name: String,
+) -> Result<FfmpegInstallation, String> {
+    let id = sanitize_id(&name);
+    if id.chars().all(|c| c == '.') || id == CONFIGURED_ID {
+        return Err(format!("Invalid installation name: {}", name));
+    }
+    let (ffmpeg, ffprobe) = binaries_in(Path::new(&source_dir))
+        .ok_or_else(|| format!("No ffmpeg and ffprobe found in {}", source_dir))?;
+    let version = crate::get_binary_version_internal(&ffmpeg.to_string_lossy())
+        .ok_or_else(|| format!("{} does not run", ffmpeg.display()))?;
+
+    let target = installations_dir().join(&id);
+    if target.exists() {
+        return Err(format!("An installation named '{}' already exists", id));
+    }
+    std::fs::create_dir_all(&target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    for binary in [&ffmpeg, &ffprobe] {
+        let destination = target.join(binary.file_name().unwrap_or_default());
+        if let Err(e) = std::fs::copy(binary, &destination) {
+            let _ = std::fs::remove_dir_all(&target);
+            return Err(format!("Failed to copy {}: {}", binary.display(), e));
+        }
+    }
+    let _ = crate::write_log(format!("[FFMPEG] Imported '{}': {}", id, version));
+    find_installation(&id)
+}
+
+/// Delete a managed build; refused while it is active or pinned by a preset
+#[tauri::command]
+pub fn remove_ffmpeg_installation(id: String) -> Result<(), String> {
+    let installation = find_installation(&id)?;
+    if !installation.managed {
+        return Err("Only installations in the app data folder can be removed".to_string());
+    }
+    if installation.active {
+        return Err("The active installation cannot be removed".to_string());
+    }
+    if let Some((preset, _)) = load_overrides().iter().find(|(_, pinned)| **pinned == id) {
+        return Err(format!("The installation is used by preset '{}'", preset));
+    }
+    std::fs::remove_dir_all(installations_dir().join(&id))
+        .map_err(|e| format!("Failed to remove installation: {}", e))
+}
+
+/// Installation ids pinned by preset name
+#[tauri::command]
+pub fn get_preset_ffmpeg_overrides() -> HashMap<String, String> {
+    load_overrides()
+}
+
+/// Pin a preset to an installation, or back to the active one with None
+#[tauri::command]
+pub fn set_preset_ffmpeg(preset_name: String, id: Option<String>) -> Result<(), String> {
+    let mut overrides = load_overrides();
+    match id {
+        Some(id) => {
+            if !managed_installations()
+                .iter()
+                .any(|(managed, _, _)| *managed == id)
+            {
+                return Err(format!("FFmpeg installation not found: {}", id));
+            }
+            overrides.insert(preset_name, id);
+        }
+        None => {
+            overrides.remove(&preset_name);
+        }
+    }
+    save_overrides(&overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_id() {
+        assert_eq!(sanitize_id(" ffmpeg 7.1 "), "ffmpeg_7.1");
+        assert_eq!(sanitize_id("../evil"), ".._evil");
+        assert_eq!(sanitize_id("a/b\\c"), "a_b_c");
+        assert_eq!(sanitize_id("nightly-2024_05"), "nightly-2024_05");
+    }
+
+    #[test]
+    fn test_binaries_in() {
+        let dir = std::env::temp_dir().join("szhimatar-ffmpeg-versions-test");
+        let bin = dir.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        assert!(binaries_in(&dir).is_none());
+        std::fs::write(bin.join(exe_name("ffmpeg")), b"").unwrap();
+        std::fs::write(bin.join(exe_name("ffprobe")), b"").unwrap();
+        assert_eq!(
+            binaries_in(&dir).map(|(ffmpeg, _)| ffmpeg),
+            Some(bin.join(exe_name("ffmpeg")))
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,152 @@
+// Source file access checks
+// Detects sources that are still being written (an OBS recording in progress, a copy
+// that has not finished) so the queue can wait instead of rendering a truncated file.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Time between the two size samples used to detect a growing file
+const SETTLE_TIME: Duration = Duration::from_millis(1500);
+
+#[cfg(windows)]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+#[cfg(windows)]
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAccess {
+    pub path: String,
+    pub exists: bool,
+    /// Another program holds the file open for writing (or exclusively)
+    pub locked: bool,
+    /// Size or modification time changed while sampling
+    pub growing: bool,
+    pub size_bytes: u64,
+    /// Safe to render now
+    pub ready: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSnapshot {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+fn snapshot(path: &Path) -> std::io::Result<FileSnapshot> {
+    let metadata = fs::metadata(path)?;
+    Ok(FileSnapshot {
+        size: metadata.len(),
+        modified: metadata.modified().ok(),
+    })
+}
+
+/// Whether the file is held by a writer. On Windows the file is opened while denying
+/// write sharing, which fails if anyone has it open for writing. Unix has no mandatory
+/// locks, so only a failing read-open counts there.
+fn is_locked(path: &Path) -> Result<bool, String> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_SHARE_READ: u32 = 0x1;
+
+        match fs::OpenOptions::new()
+            .read(true)
+            .share_mode(FILE_SHARE_READ)
+            .open(path)
+        {
+            Ok(_) => Ok(false),
+            Err(e)
+                if matches!(
+                    e.raw_os_error(),
+                    Some(ERROR_SHARING_VIOLATION) | Some(ERROR_LOCK_VIOLATION)
+                ) =>
+            {
+                Ok(true)
+            }
+            Err(e) => Err(format!("Cannot open {}: {}", path.display(), e)),
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        fs::File::open(path)
+            .map(|_| false)
+            .map_err(|e| format!("Cannot open {}: {}", path.display(), e))
+    }
+}
+
+/// Check a source, sampling its size twice `settle` apart
+pub fn check_access(path: &Path, settle: Duration) -> FileAccess {
+    let mut access = FileAccess {
+        path: path.to_string_lossy().to_string(),
+        exists: path.is_file(),
+        locked: false,
+        growing: false,
+        size_bytes: 0,
+        ready: false,
+        reason: None,
+    };
+    if !access.exists {
+        access.reason = Some("File not found".to_string());
+        return access;
+    }
+
+    let before = match snapshot(path) {
+        Ok(s) => s,
+        Err(e) => {
+            access.reason = Some(format!("Failed to read file metadata: {}", e));
+            return access;
+        }
+    };
+    match is_locked(path) {
+        Ok(locked) => access.locked = locked,
+        Err(e) => {
+            access.reason = Some(e);
+            return access;
+        }
+    }
+
+    if !settle.is_zero() {
+        std::thread::sleep(settle);
+    }
+    let after = snapshot(path).unwrap_or(before);
+    access.size_bytes = after.size;
+    access.growing = before != after;
+
+    access.reason = match (access.locked, access.growing) {
+        (true, _) => Some("File is in use by another program".to_string()),
+        (false, true) => Some("File is still being written".to_string()),
+        (false, false) if after.size == 0 => Some("File is empty".to_string()),
+        _ => None,
+    };
+    access.ready = access.reason.is_none();
+    access
+}
+
+/// Whether a source can be rendered now (not locked, not growing, not empty)
+#[tauri::command]
+pub async fn check_file_access(path: String) -> FileAccess {
+    check_access(Path::new(&path), SETTLE_TIME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_access_ready_and_missing() {
+        let path =
+            std::env::temp_dir().join(format!("szhimatar-access-{}.bin", std::process::id()));
+        fs::write(&path, b"data").unwrap();
+        let access = check_access(&path, Duration::ZERO);
+        fs::remove_file(&path).ok();
+        assert!(access.ready && !access.locked && !access.growing);
+        assert_eq!(access.size_bytes, 4);
+
+        let missing = check_access(&path, Duration::ZERO);
+        assert!(!missing.exists && !missing.ready);
+    }
+}
@@ -0,0 +1,593 @@
+// File manager integration (Linux / macOS)
+// Counterpart of the Windows Explorer context menu: a .desktop file so the app shows up
+// in "Open With", a Dolphin service menu and Nautilus scripts on Linux, and Finder
+// Quick Actions on macOS. Like the registry verbs, there is one entry per saved preset.
+
+#![cfg_attr(windows, allow(dead_code))]
+
+use crate::ContextMenuStatus;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// MIME types of crate::VIDEO_EXTENSIONS, for .desktop files
+#[cfg(any(target_os = "linux", test))]
+const VIDEO_MIME_TYPES: &[&str] = &[
+    "video/mp4",
+    "video/x-matroska",
+    "video/x-msvideo",
+    "video/quicktime",
+    "video/x-ms-wmv",
+    "video/x-flv",
+    "video/webm",
+    "video/x-m4v",
+    "video/mpeg",
+    "video/3gpp",
+];
+
+/// Comment in generated scripts, so they are recognisable when found by hand
+const GENERATED_MARKER: &str = "# Generated by Szhimatar";
+
+/// Executable to launch. Inside an AppImage current_exe() is a temporary mount, the
+/// stable path is in $APPIMAGE.
+pub fn exe_path() -> Result<String, String> {
+    if let Ok(appimage) = std::env::var("APPIMAGE") {
+        return Ok(appimage);
+    }
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to get exe path: {}", e))
+}
+
+/// Quote an argument for a POSIX shell
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Script body running the app with the selected files (passed as "$@")
+fn launch_script(exe: &str, preset: Option<&str>) -> String {
+    let preset_arg = preset
+        .map(|p| format!(" --preset {}", shell_quote(p)))
+        .unwrap_or_default();
+    format!("exec {}{} \"$@\"", shell_quote(exe), preset_arg)
+}
+
+pub fn write_file(path: &Path, content: &str, executable: bool) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    if executable {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+    Ok(())
+}
+
+pub fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove {}: {}", path.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Linux
+// ---------------------------------------------------------------------------
+
+/// Exec= argument quoting from the Desktop Entry spec: reserved characters are
+/// backslash-escaped inside double quotes, then backslashes are escaped once more
+/// as for any string value. A literal '%' is written as "%%".
+#[cfg(any(target_os = "linux", test))]
+pub fn desktop_exec_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str(r"\\\\"),
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn desktop_exec(exe: &str, preset: Option<&str>) -> String {
+    match preset {
+        Some(preset) => format!(
+            "{} --preset {} %F",
+            desktop_exec_quote(exe),
+            desktop_exec_quote(preset)
+        ),
+        None => format!("{} %F", desktop_exec_quote(exe)),
+    }
+}
+
+/// Application entry: "Open With" for videos
+#[cfg(any(target_os = "linux", test))]
+fn application_desktop_file(exe: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Szhimatar\n\
+         GenericName=Video compressor\n\
+         Comment={}\n\
+         Exec={}\n\
+         Terminal=false\n\
+         Categories=AudioVideo;Video;\n\
+         MimeType={};\n",
+        crate::CONTEXT_MENU_LABEL,
+        desktop_exec(exe, None),
+        VIDEO_MIME_TYPES.join(";")
+    )
+}
+
+/// Dolphin service menu: videos and folders, a submenu when presets exist
+#[cfg(any(target_os = "linux", test))]
+fn dolphin_service_menu(exe: &str, entries: &[crate::ContextMenuEntry]) -> String {
+    // Folders are expanded into their videos by cli::parse_args
+    let mut content = format!(
+        "[Desktop Entry]\n\
+         Type=Service\n\
+         MimeType={};inode/directory;\n\
+         Actions={};\n",
+        VIDEO_MIME_TYPES.join(";"),
+        entries
+            .iter()
+            .map(|e| e.key.as_str())
+            .collect::<Vec<_>>()
+            .join(";")
+    );
+    let single = entries.len() == 1;
+    if !single {
+        content.push_str(&format!(
+            "X-KDE-Submenu={}\nX-KDE-Priority=TopLevel\n",
+            crate::CONTEXT_MENU_LABEL
+        ));
+    }
+    for entry in entries {
+        let name = if single {
+            crate::CONTEXT_MENU_LABEL
+        } else {
+            entry.label.as_str()
+        };
+        content.push_str(&format!(
+            "\n[Desktop Action {}]\nName={}\nIcon=video-x-generic\nExec={}\n",
+            entry.key,
+            name,
+            desktop_exec(exe, entry.preset.as_deref())
+        ));
+    }
+    content
+}
+
+#[cfg(target_os = "linux")]
+struct LinuxPaths {
+    application: PathBuf,
+    /// Plasma 5.85+ / 6 and older Plasma 5
+    service_menus: [PathBuf; 2],
+    nautilus_scripts: PathBuf,
+}
+
+/// $XDG_DATA_HOME, ~/.local/share by default
+#[cfg(target_os = "linux")]
+pub fn data_home() -> Result<PathBuf, String> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .ok_or_else(|| "Failed to find the home folder".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn linux_paths() -> Result<LinuxPaths, String> {
+    let data_home = data_home()?;
+    Ok(LinuxPaths {
+        application: data_home.join("applications/szhimatar.desktop"),
+        service_menus: [
+            data_home.join("kio/servicemenus/szhimatar.desktop"),
+            data_home.join("kservices5/ServiceMenus/szhimatar.desktop"),
+        ],
+        nautilus_scripts: data_home.join("nautilus/scripts"),
+    })
+}
+
+/// Nautilus script (or folder of scripts with presets) named after the menu label
+#[cfg(target_os = "linux")]
+fn write_nautilus_scripts(
+    dir: &Path,
+    exe: &str,
+    entries: &[crate::ContextMenuEntry],
+) -> Result<(), String> {
+    let target = dir.join(crate::CONTEXT_MENU_LABEL);
+    remove_path(&target)?;
+    let script = |preset: Option<&str>| {
+        format!(
+            "#!/bin/sh\n{}\n{}\n",
+            GENERATED_MARKER,
+            launch_script(exe, preset)
+        )
+    };
+
+    if entries.len() == 1 {
+        return write_file(&target, &script(None), true);
+    }
+    for entry in entries {
+        // Nautilus sorts scripts by name, the key keeps the current settings first
+        let name = format!("{} {}", &entry.key[..2], entry.label.replace('/', "_"));
+        write_file(&target.join(name), &script(entry.preset.as_deref()), true)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn install(exe: &str, entries: &[crate::ContextMenuEntry]) -> Result<(), String> {
+    let paths = linux_paths()?;
+    write_file(&paths.application, &application_desktop_file(exe), false)?;
+    // Plasma 6 ignores service menus that are not executable
+    let service_menu = dolphin_service_menu(exe, entries);
+    for path in &paths.service_menus {
+        write_file(path, &service_menu, true)?;
+    }
+    write_nautilus_scripts(&paths.nautilus_scripts, exe, entries)?;
+
+    // Refresh the MIME cache so "Open With" picks up the entry; harmless if missing
+    if let Some(dir) = paths.application.parent() {
+        let _ = crate::proc::run_captured(
+            crate::proc::command("update-desktop-database").arg(dir),
+            &crate::proc::RunOptions::with_timeout(15).label("update-desktop-database"),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn uninstall() -> Result<(), String> {
+    let paths = linux_paths()?;
+    remove_path(&paths.application)?;
+    for path in &paths.service_menus {
+        remove_path(path)?;
+    }
+    remove_path(&paths.nautilus_scripts.join(crate::CONTEXT_MENU_LABEL))
+}
+
+/// The file whose presence means "installed", and whether it launches this exe
+#[cfg(target_os = "linux")]
+fn installed_entry() -> Option<(PathBuf, String)> {
+    let path = linux_paths().ok()?.application;
+    let content = fs::read_to_string(&path).ok()?;
+    Some((path, content))
+}
+
+// ---------------------------------------------------------------------------
+// macOS
+// ---------------------------------------------------------------------------
+
+#[cfg(any(target_os = "macos", test))]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Contents/Info.plist of a Quick Action: shown in Finder for movies and folders
+#[cfg(any(target_os = "macos", test))]
+fn quick_action_info_plist(menu_title: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>NSServices</key>
+	<array>
+		<dict>
+			<key>NSMenuItem</key>
+			<dict>
+				<key>default</key>
+				<string>{}</string>
+			</dict>
+			<key>NSMessage</key>
+			<string>runWorkflowAsService</string>
+			<key>NSRequiredContext</key>
+			<dict>
+				<key>NSApplicationIdentifier</key>
+				<string>com.apple.finder</string>
+			</dict>
+			<key>NSSendFileTypes</key>
+			<array>
+				<string>public.movie</string>
+				<string>public.folder</string>
+			</array>
+		</dict>
+	</array>
+</dict>
+</plist>
+"#,
+        xml_escape(menu_title)
+    )
+}
+
+/// Contents/document.wflow: a single "Run Shell Script" action receiving the
+/// selected Finder items as arguments
+#[cfg(any(target_os = "macos", test))]
+fn quick_action_workflow(script: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>AMApplicationBuild</key>
+	<string>523</string>
+	<key>AMApplicationVersion</key>
+	<string>2.10</string>
+	<key>AMDocumentVersion</key>
+	<string>2</string>
+	<key>actions</key>
+	<array>
+		<dict>
+			<key>action</key>
+			<dict>
+				<key>AMAccepts</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Optional</key>
+					<true/>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>AMActionVersion</key>
+				<string>2.0.3</string>
+				<key>AMApplication</key>
+				<array>
+					<string>Automator</string>
+				</array>
+				<key>AMProvides</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>ActionBundlePath</key>
+				<string>/System/Library/Automator/Run Shell Script.action</string>
+				<key>ActionName</key>
+				<string>Run Shell Script</string>
+				<key>ActionParameters</key>
+				<dict>
+					<key>COMMAND_STRING</key>
+					<string>{}</string>
+					<key>CheckedForUserDefaultShell</key>
+					<true/>
+					<key>inputMethod</key>
+					<integer>1</integer>
+					<key>shell</key>
+					<string>/bin/sh</string>
+					<key>source</key>
+					<string></string>
+				</dict>
+				<key>BundleIdentifier</key>
+				<string>com.apple.RunShellScript</string>
+				<key>CFBundleVersion</key>
+				<string>2.0.3</string>
+				<key>Class Name</key>
+				<string>RunShellScriptAction</string>
+				<key>UUID</key>
+				<string>6C3C3B5E-0E4C-4F4B-9B9E-5A7A1C7E2D10</string>
+			</dict>
+			<key>isViewVisible</key>
+			<true/>
+		</dict>
+	</array>
+	<key>connectors</key>
+	<dict/>
+	<key>workflowMetaData</key>
+	<dict>
+		<key>serviceInputTypeIdentifier</key>
+		<string>com.apple.Automator.fileSystemObject</string>
+		<key>serviceOutputTypeIdentifier</key>
+		<string>com.apple.Automator.nothing</string>
+		<key>serviceProcessesInput</key>
+		<integer>0</integer>
+		<key>workflowTypeIdentifier</key>
+		<string>com.apple.Automator.servicesMenu</string>
+	</dict>
+</dict>
+</plist>
+"#,
+        xml_escape(script)
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn services_dir() -> Result<PathBuf, String> {
+    std::env::var_os("HOME")
+        .map(|home| Path::new(&home).join("Library/Services"))
+        .ok_or_else(|| "Failed to find the home folder".to_string())
+}
+
+/// Our Quick Actions: every workflow named after the menu label
+#[cfg(target_os = "macos")]
+fn installed_workflows(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    name.starts_with(crate::CONTEXT_MENU_LABEL) && name.ends_with(".workflow")
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn install(exe: &str, entries: &[crate::ContextMenuEntry]) -> Result<(), String> {
+    let dir = services_dir()?;
+    for workflow in installed_workflows(&dir) {
+        remove_path(&workflow)?;
+    }
+
+    // Finder has no submenus for Quick Actions; with presets there is one per preset
+    for entry in entries {
+        let title = if entries.len() == 1 {
+            crate::CONTEXT_MENU_LABEL.to_string()
+        } else {
+            format!("{}: {}", crate::CONTEXT_MENU_LABEL, entry.label)
+        };
+        let bundle = dir.join(format!(
+            "{} {}.workflow",
+            crate::CONTEXT_MENU_LABEL,
+            entry.key
+        ));
+        let script = format!(
+            "{}\n{}",
+            GENERATED_MARKER,
+            launch_script(exe, entry.preset.as_deref())
+        );
+        write_file(
+            &bundle.join("Contents/Info.plist"),
+            &quick_action_info_plist(&title),
+            false,
+        )?;
+        write_file(
+            &bundle.join("Contents/document.wflow"),
+            &quick_action_workflow(&script),
+            false,
+        )?;
+    }
+
+    // Make Finder pick up the new services without a logout; harmless if it fails
+    let _ = crate::proc::run_captured(
+        crate::proc::command("/System/Library/CoreServices/pbs").arg("-update"),
+        &crate::proc::RunOptions::with_timeout(15).label("pbs"),
+    );
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn uninstall() -> Result<(), String> {
+    let dir = services_dir()?;
+    for workflow in installed_workflows(&dir) {
+        remove_path(&workflow)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn installed_entry() -> Option<(PathBuf, String)> {
+    let workflow = installed_workflows(&services_dir().ok()?)
+        .into_iter()
+        .min()?;
+    let content = fs::read_to_string(workflow.join("Contents/document.wflow")).ok()?;
+    Some((workflow, content))
+}
+
+// ---------------------------------------------------------------------------
+// Other platforms (nothing to integrate with)
+// ---------------------------------------------------------------------------
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn install(_exe: &str, _entries: &[crate::ContextMenuEntry]) -> Result<(), String> {
+    Err("File manager integration is not supported on this platform".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn uninstall() -> Result<(), String> {
+    Err("File manager integration is not supported on this platform".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn installed_entry() -> Option<(PathBuf, String)> {
+    None
+}
+
+/// Whether the integration is installed and still points at this executable
+pub fn integration_status() -> ContextMenuStatus {
+    let exe_path = exe_path().unwrap_or_default();
+    match installed_entry() {
+        Some((path, content)) => ContextMenuStatus {
+            enabled: true,
+            registry_path: path.to_string_lossy().to_string(),
+            exe_valid: !exe_path.is_empty() && content.contains(&exe_path),
+            exe_path,
+            needs_admin: false,
+        },
+        None => ContextMenuStatus {
+            enabled: false,
+            registry_path: String::new(),
+            exe_path,
+            exe_valid: false,
+            needs_admin: false,
+        },
+    }
+}
+
+/// Install the "Open With" entry and file manager menus for videos and folders
+#[tauri::command]
+pub fn install_file_manager_integration() -> Result<(), String> {
+    let exe = exe_path()?;
+    let presets = crate::preset_names();
+    install(&exe, &crate::context_menu_entries(&presets))
+}
+
+/// Remove everything install_file_manager_integration created
+#[tauri::command]
+pub fn remove_file_manager_integration() -> Result<(), String> {
+    uninstall()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<crate::ContextMenuEntry> {
+        crate::context_menu_entries(&["Web 720p".to_string()])
+    }
+
+    #[test]
+    fn test_desktop_exec_quoting() {
+        assert_eq!(
+            desktop_exec("/opt/my app/szhimatar", Some("50% $size")),
+            r#""/opt/my app/szhimatar" --preset "50%% \\$size" %F"#
+        );
+        assert_eq!(shell_quote("it's"), r#"'it'\''s'"#);
+    }
+
+    #[test]
+    fn test_service_menu_and_quick_action() {
+        let menu = dolphin_service_menu("/usr/bin/szhimatar", &entries());
+        assert!(menu.contains("Actions=00_current;01_Web_720p;"));
+        assert!(menu.contains("X-KDE-Submenu="));
+        assert!(menu.contains("\"/usr/bin/szhimatar\" --preset \"Web 720p\" %F"));
+        assert!(application_desktop_file("/usr/bin/szhimatar").contains("video/webm;"));
+
+        let workflow = quick_action_workflow(&launch_script("/Applications/A&B", None));
+        assert!(workflow.contains("exec '/Applications/A&amp;B' &quot;$@&quot;"));
+        assert!(quick_action_info_plist("x").contains("public.movie"));
+    }
+}
@@ -0,0 +1,182 @@
+// Folder import
+// enqueue_folder queues a whole folder tree with one preset in one call. Include /
+// exclude wildcards and a minimum size pick the videos (the folderExcludePatterns
+// setting and earlier renders are skipped as for folders on the command line), every
+// match is probed for a preview of the work, and the readable files reach the frontend
+// queue through `files-received`. With `preview_only` nothing is queued, so the
+// dialog can show the preview before the user commits to it.
+
+use crate::library::{LibraryFile, ScanProgress};
+use crate::units;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use tauri::Manager;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FolderImportOptions {
+    /// Wildcards as in cli::FolderFilter; none = every video
+    pub include: Vec<String>,
+    /// Skipped on top of the folderExcludePatterns setting
+    pub exclude: Vec<String>,
+    /// Smaller files are skipped (bytes or "50 MB")
+    pub min_size: units::ByteSize,
+    /// Walk subfolders too
+    pub recursive: bool,
+    pub output_dir: Option<String>,
+    /// Only add to the queue. Without it, queueing with a preset also starts rendering.
+    pub queue_only: bool,
+    /// Return the preview without queueing anything
+    pub preview_only: bool,
+}
+
+impl Default for FolderImportOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            min_size: units::ByteSize::ZERO,
+            recursive: true,
+            output_dir: None,
+            queue_only: false,
+            preview_only: false,
+        }
+    }
+}
+
+impl FolderImportOptions {
+    fn filter(&self) -> crate::cli::FolderFilter {
+        let mut filter = crate::cli::FolderFilter::from_settings();
+        filter.exclude.extend(self.exclude.iter().cloned());
+        filter.include = self.include.clone();
+        filter.min_size = self.min_size.bytes();
+        filter
+    }
+}
+
+/// The matched files and the work they add up to
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderImportPreview {
+    /// Sorted by path; unreadable ones carry `error` and are not queued
+    pub files: Vec<LibraryFile>,
+    pub total_size: units::ByteSize,
+    /// Media time to encode over the readable files
+    pub total_duration: units::Duration,
+    pub unreadable: usize,
+    pub queued: bool,
+}
+
+fn summarize(mut files: Vec<LibraryFile>) -> FolderImportPreview {
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let readable = || files.iter().filter(|f| f.error.is_none());
+    let total_size = readable().fold(units::ByteSize::ZERO, |total, f| total + f.size);
+    let seconds = readable().map(|f| f.duration.as_secs_f64()).sum();
+    FolderImportPreview {
+        total_size,
+        total_duration: units::Duration::from_secs_f64(seconds),
+        unreadable: files.len() - readable().count(),
+        files,
+        queued: false,
+    }
+}
+
+/// Probe the videos of `path` that pass `options` and, unless `preview_only`, queue
+/// the readable ones with `preset`
+#[tauri::command]
+pub async fn enqueue_folder(
+    app: tauri::AppHandle,
+    path: String,
+    preset: Option<String>,
+    options: FolderImportOptions,
+) -> Result<FolderImportPreview, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
+    let folder = Path::new(&path);
+    if !folder.is_dir() {
+        return Err(format!("Not a folder: {}", path));
+    }
+    let max_depth = if options.recursive { usize::MAX } else { 1 };
+    let paths = crate::cli::videos_in(folder, &options.filter(), max_depth);
+    let total = paths.len();
+
+    let mut files: Vec<LibraryFile> = Vec::with_capacity(total);
+    let mut throttle = crate::progress::Throttle::new(crate::progress::PROGRESS_INTERVAL);
+    crate::media_cache::probe_all(
+        &paths,
+        |path| crate::library::probe_file(&config.ffprobe_path, path),
+        |file| {
+            let progress = ScanProgress {
+                scanned: files.len() + 1,
+                total,
+                current: file.path.clone(),
+            };
+            if let Some(progress) = throttle.offer(progress, Instant::now()) {
+                let _ = app.emit_all("folder-import-progress", &progress);
+            }
+            files.push(file);
+        },
+    );
+    if let Some(progress) = throttle.flush() {
+        let _ = app.emit_all("folder-import-progress", &progress);
+    }
+
+    let mut preview = summarize(files);
+    if options.preview_only || preview.files.len() == preview.unreadable {
+        return Ok(preview);
+    }
+
+    let launch = crate::cli::CliLaunch {
+        files: preview
+            .files
+            .iter()
+            .filter(|f| f.error.is_none())
+            .map(|f| f.path.clone())
+            .collect(),
+        preset: preset.filter(|p| !p.is_empty()),
+        output_dir: options.output_dir.filter(|d| !d.trim().is_empty()),
+        queue_only: options.queue_only,
+        unattended: true,
+        ..Default::default()
+    };
+    let _ = crate::write_log(format!(
+        "[FOLDER] Queueing {} file(s) from {} ({}, {}), preset {:?}",
+        launch.files.len(),
+        path,
+        preview.total_size,
+        preview.total_duration,
+        launch.preset
+    ));
+    app.emit_all("files-received", &launch)
+        .map_err(|e| format!("Failed to queue the files: {}", e))?;
+    preview.queued = true;
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let file = |path: &str, mb: u64, seconds: f64, error: Option<&str>| LibraryFile {
+            path: path.to_string(),
+            size: units::ByteSize::from_bytes(mb * 1_048_576),
+            duration: units::Duration::from_secs_f64(seconds),
+            error: error.map(String::from),
+            ..Default::default()
+        };
+        let preview = summarize(vec![
+            file("b.mp4", 300, 90.0, None),
+            file("a.mkv", 100, 30.0, None),
+            file("c.mov", 50, 0.0, Some("FFprobe failed to analyze file")),
+        ]);
+        assert_eq!(preview.files[0].path, "a.mkv");
+        assert_eq!(preview.total_size.bytes(), 400 * 1_048_576);
+        assert_eq!(preview.total_duration.as_secs_f64(), 120.0);
+        assert_eq!(preview.unreadable, 1);
+        assert!(!preview.queued);
+    }
+}
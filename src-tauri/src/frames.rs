@@ -0,0 +1,304 @@
+// Frame export
+// export_frames saves stills from a video as PNG or JPEG: either one frame every N
+// seconds (a single FFmpeg pass with the fps filter) or one frame per listed timestamp
+// (a fast seek per frame). Progress is reported with `frame-export-progress`.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Fewest seconds between two frames of an interval export
+const MIN_INTERVAL_SECONDS: f64 = 0.04;
+
+/// Which frames to export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FrameSelection {
+    /// One frame every `seconds`, starting at the first frame
+    Interval { seconds: f64 },
+    /// One frame at each timestamp (seconds)
+    Timestamps { times: Vec<f64> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameFormat {
+    Png,
+    Jpeg,
+}
+
+impl FrameFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Png => "png",
+            FrameFormat::Jpeg => "jpg",
+        }
+    }
+
+    /// Encoder quality arguments
+    fn quality_args(self) -> &'static [&'static str] {
+        match self {
+            FrameFormat::Png => &["-compression_level", "6"],
+            FrameFormat::Jpeg => &["-q:v", "2"],
+        }
+    }
+}
+
+/// Payload of the `frame-export-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameExportProgress {
+    pub input: String,
+    pub done: u32,
+    pub total: u32,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameExport {
+    pub output_dir: String,
+    pub files: Vec<String>,
+}
+
+/// "<stem>_<h>-<mm>-<ss>.<mmm>.<ext>": sorts by time and is valid on every file system
+fn timestamp_file_name(stem: &str, seconds: f64, format: FrameFormat) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{}_{}-{:02}-{:02}.{:03}.{}",
+        stem,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+        format.extension()
+    )
+}
+
+fn emit_progress(app: &tauri::AppHandle, input: &str, done: u32, total: u32) {
+    let percent = if total == 0 {
+        0.0
+    } else {
+        (done as f64 / total as f64 * 100.0).min(100.0)
+    };
+    let _ = app.emit_all(
+        "frame-export-progress",
+        FrameExportProgress {
+            input: input.to_string(),
+            done,
+            total,
+            percent,
+        },
+    );
+}
+
+fn last_error_line(stderr: &str) -> String {
+    stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// What and where one export_frames call writes
+struct Export<'a> {
+    app: &'a tauri::AppHandle,
+    ffmpeg_path: &'a str,
+    input: &'a str,
+    format: FrameFormat,
+    output_dir: &'a Path,
+    /// File name prefix (the input's stem)
+    stem: &'a str,
+}
+
+fn export_timestamps(export: &Export, times: &[f64]) -> Result<Vec<String>, String> {
+    let Export {
+        app,
+        ffmpeg_path,
+        input,
+        format,
+        output_dir,
+        stem,
+    } = *export;
+    let total = times.len() as u32;
+    let mut files = Vec::new();
+    for (index, &time) in times.iter().enumerate() {
+        let output = output_dir.join(timestamp_file_name(stem, time, format));
+        let result = crate::proc::run_captured(
+            crate::proc::command(ffmpeg_path)
+                .args(["-hide_banner", "-v", "error", "-y"])
+                .args(["-ss", &format!("{:.3}", time.max(0.0)), "-i", input])
+                .args(["-frames:v", "1"])
+                .args(format.quality_args())
+                .arg(&output),
+            &crate::proc::RunOptions::with_timeout(60).label("frame export"),
+        )?;
+        if !result.success || !output.exists() {
+            return Err(format!(
+                "Failed to export the frame at {:.3}s: {}",
+                time,
+                last_error_line(&result.stderr)
+            ));
+        }
+        files.push(output.to_string_lossy().to_string());
+        emit_progress(app, input, index as u32 + 1, total);
+    }
+    Ok(files)
+}
+
+fn export_interval(export: &Export, duration: f64, seconds: f64) -> Result<Vec<String>, String> {
+    let Export {
+        app,
+        ffmpeg_path,
+        input,
+        format,
+        output_dir,
+        stem,
+    } = *export;
+    let total = ((duration / seconds).ceil() as u32).max(1);
+    let pattern = output_dir.join(format!("{}_%05d.{}", stem, format.extension()));
+    let mut stderr_tail = String::new();
+
+    let mut cmd = crate::proc::command(ffmpeg_path);
+    cmd.args(["-hide_banner", "-v", "error", "-nostats", "-y", "-i", input])
+        .args(["-vf", &format!("fps=1/{:.3}", seconds)])
+        .args(format.quality_args())
+        .args(["-progress", "pipe:1"])
+        .arg(&pattern);
+    let options = crate::proc::RunOptions::default().label("frame export");
+    let mut child = crate::proc::spawn_piped(&mut cmd, &options)?;
+    let output = crate::proc::run_streaming(&mut child, &options, |stream, line| match stream {
+        crate::proc::Stream::Stdout => {
+            // Despite the name, out_time_ms is in microseconds
+            if let Some(Ok(micros)) = line.strip_prefix("out_time_ms=").map(str::parse::<i64>) {
+                let elapsed = micros.max(0) as f64 / 1_000_000.0;
+                let done = ((elapsed / seconds).floor() as u32).min(total);
+                emit_progress(app, input, done, total);
+            }
+        }
+        crate::proc::Stream::Stderr => stderr_tail = line.to_string(),
+    })?;
+    if !output.success {
+        return Err(format!("Frame export failed: {}", stderr_tail.trim()));
+    }
+
+    // The fps filter numbers its frames from 1 (and may emit one more than estimated);
+    // the bound keeps files of an earlier, longer export out
+    let files: Vec<String> = (1..=total + 1)
+        .map(|n| output_dir.join(format!("{}_{:05}.{}", stem, n, format.extension())))
+        .take_while(|path| path.exists())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    emit_progress(app, input, total, total);
+    Ok(files)
+}
+
+/// Save stills of `input` into `output_dir` (created when missing)
+#[tauri::command]
+pub async fn export_frames(
+    app: tauri::AppHandle,
+    input: String,
+    selection: FrameSelection,
+    format: FrameFormat,
+    output_dir: String,
+) -> Result<FrameExport, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() || config.ffprobe_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    if output_dir.trim().is_empty() {
+        return Err("No output folder selected".to_string());
+    }
+    let output_dir = PathBuf::from(output_dir.trim());
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create the output folder: {}", e))?;
+    let stem = Path::new(&input)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "frame".to_string());
+
+    let files = tokio::task::spawn_blocking({
+        let (input, output_dir) = (input.clone(), output_dir.clone());
+        move || {
+            let export = Export {
+                app: &app,
+                ffmpeg_path: &config.ffmpeg_path,
+                input: &input,
+                format,
+                output_dir: &output_dir,
+                stem: &stem,
+            };
+            match selection {
+                FrameSelection::Interval { seconds } => {
+                    if !seconds.is_finite() || seconds < MIN_INTERVAL_SECONDS {
+                        return Err(format!(
+                            "The interval must be at least {}s",
+                            MIN_INTERVAL_SECONDS
+                        ));
+                    }
+                    let probe = crate::media_cache::probe(&config.ffprobe_path, &input)?;
+                    let duration = probe["format"]["duration"]
+                        .as_str()
+                        .and_then(|d| d.parse::<f64>().ok())
+                        .filter(|d| *d > 0.0)
+                        .ok_or("Could not determine the video duration")?;
+                    export_interval(&export, duration, seconds)
+                }
+                FrameSelection::Timestamps { mut times } => {
+                    times.retain(|t| t.is_finite() && *t >= 0.0);
+                    times.sort_by(|a, b| a.total_cmp(b));
+                    times.dedup();
+                    if times.is_empty() {
+                        return Err("No timestamps given".to_string());
+                    }
+                    export_timestamps(&export, &times)
+                }
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    let _ = crate::write_log(format!(
+        "[FRAMES] Exported {} frames of {} to {}",
+        files.len(),
+        input,
+        output_dir.display()
+    ));
+    Ok(FrameExport {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_file_name() {
+        assert_eq!(
+            timestamp_file_name("clip", 3723.5, FrameFormat::Png),
+            "clip_1-02-03.500.png"
+        );
+        assert_eq!(
+            timestamp_file_name("clip", 0.0, FrameFormat::Jpeg),
+            "clip_0-00-00.000.jpg"
+        );
+    }
+
+    #[test]
+    fn test_selection_json() {
+        let interval: FrameSelection =
+            serde_json::from_str(r#"{"mode": "interval", "seconds": 5}"#).unwrap();
+        assert_eq!(interval, FrameSelection::Interval { seconds: 5.0 });
+        let times: FrameSelection =
+            serde_json::from_str(r#"{"mode": "timestamps", "times": [1.5, 10]}"#).unwrap();
+        assert_eq!(
+            times,
+            FrameSelection::Timestamps {
+                times: vec![1.5, 10.0]
+            }
+        );
+    }
+}
@@ -0,0 +1,761 @@
+// GPU capability detection (NVENC, Intel Quick Sync, AMD AMF / VAAPI)
+// Combines nvidia-smi (model, driver, compute capability) with FFmpeg's NVENC
+// encoder help and short test encodes, so the UI can grey out options the
+// installed GPU cannot encode.
+
+use serde::{Deserialize, Serialize};
+
+/// NVIDIA GPU as reported by nvidia-smi
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct NvidiaGpu {
+    pub name: String,
+    pub driver_version: String,
+    /// "8.9" etc. (None on drivers older than 510, which lack the compute_cap field)
+    pub compute_capability: Option<String>,
+}
+
+/// What one NVENC encoder can do on this machine
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NvencCodecSupport {
+    /// h264, hevc or av1
+    pub codec: String,
+    pub encoder: String,
+    /// Compiled into this FFmpeg build
+    pub in_build: bool,
+    /// A test encode succeeded on this GPU
+    pub working: bool,
+    pub ten_bit: bool,
+    pub b_frames: bool,
+}
+
+/// Result of get_gpu_capabilities
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GpuCapabilities {
+    pub gpu: Option<NvidiaGpu>,
+    /// Architecture name derived from the compute capability ("Turing", "Ada Lovelace", ...)
+    pub generation: Option<String>,
+    pub nvenc: Vec<NvencCodecSupport>,
+    pub warnings: Vec<String>,
+}
+
+const NVENC_CODECS: [(&str, &str); 3] = [
+    ("h264", "h264_nvenc"),
+    ("hevc", "hevc_nvenc"),
+    ("av1", "av1_nvenc"),
+];
+
+/// Parse "8.9" into (8, 9)
+fn parse_compute_capability(text: &str) -> Option<(u32, u32)> {
+    let (major, minor) = text.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// NVENC generation for a compute capability
+pub fn generation_name(compute_capability: &str) -> Option<&'static str> {
+    let name = match parse_compute_capability(compute_capability)? {
+        (3, _) => "Kepler",
+        (5, _) => "Maxwell",
+        (6, _) => "Pascal",
+        (7, 0) | (7, 2) => "Volta",
+        (7, 5) => "Turing",
+        (8, 9) => "Ada Lovelace",
+        (8, _) => "Ampere",
+        (9, _) => "Hopper",
+        (10..=12, _) => "Blackwell",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Parse the first line of
+/// `nvidia-smi --query-gpu=name,driver_version[,compute_cap] --format=csv,noheader`
+pub fn parse_nvidia_smi(output: &str) -> Option<NvidiaGpu> {
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 2 || fields[0].is_empty() || fields[1].is_empty() {
+        return None;
+    }
+
+    Some(NvidiaGpu {
+        name: fields[0].to_string(),
+        driver_version: fields[1].to_string(),
+        compute_capability: fields
+            .get(2)
+            .filter(|c| parse_compute_capability(c).is_some())
+            .map(|c| c.to_string()),
+    })
+}
+
+/// Query the first NVIDIA GPU; None when nvidia-smi is missing or finds no GPU
+pub fn query_nvidia_smi() -> Option<NvidiaGpu> {
+    let run = |fields: &str| {
+        crate::proc::run_captured(
+            crate::proc::command("nvidia-smi").args([
+                format!("--query-gpu={}", fields).as_str(),
+                "--format=csv,noheader",
+            ]),
+            &crate::proc::RunOptions::with_timeout(10).label("nvidia-smi"),
+        )
+        .ok()
+        .filter(|o| o.success)
+        .and_then(|o| parse_nvidia_smi(&o.stdout))
+    };
+
+    // compute_cap only exists since driver 510; older drivers reject the whole query
+    run("name,driver_version,compute_cap").or_else(|| run("name,driver_version"))
+}
+
+/// Work out 10-bit and B-frame support for one NVENC encoder.
+/// `compute` is the GPU's compute capability when nvidia-smi reported it;
+/// without it the answer falls back to what the FFmpeg build advertises.
+fn codec_support(
+    codec: &str,
+    encoder: &str,
+    help: Option<&str>,
+    working: bool,
+    compute: Option<(u32, u32)>,
+) -> NvencCodecSupport {
+    let caps = help.map(|h| crate::encoders::parse_encoder_help(encoder, h));
+    let has_10bit_format = caps
+        .as_ref()
+        .map(|c| {
+            c.pixel_formats
+                .iter()
+                .any(|f| f == "p010le" || f == "yuv420p10le" || f == "p016le")
+        })
+        .unwrap_or(false);
+    let has_b_ref_mode = caps
+        .as_ref()
+        .map(|c| c.options.iter().any(|o| o.name == "b_ref_mode"))
+        .unwrap_or(false);
+
+    // Hardware limits per generation: HEVC 10-bit since Pascal, HEVC B-frames since
+    // Turing, H.264 10-bit since Blackwell; AV1 (Ada+) always has both
+    let at_least = |major: u32, minor: u32| compute.map(|c| c >= (major, minor));
+    let (gpu_10bit, gpu_b_frames) = match codec {
+        "h264" => (at_least(10, 0).unwrap_or(false), true),
+        "hevc" => (
+            at_least(6, 0).unwrap_or(true),
+            at_least(7, 5).unwrap_or(has_b_ref_mode),
+        ),
+        _ => (true, true),
+    };
+
+    NvencCodecSupport {
+        codec: codec.to_string(),
+        encoder: encoder.to_string(),
+        in_build: help.is_some(),
+        working,
+        ten_bit: working && has_10bit_format && gpu_10bit,
+        b_frames: working && gpu_b_frames,
+    }
+}
+
+/// Report which NVENC codecs work and whether they support 10-bit and B-frames
+#[tauri::command]
+pub fn get_gpu_capabilities() -> Result<GpuCapabilities, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+
+    let mut result = GpuCapabilities {
+        gpu: query_nvidia_smi(),
+        ..Default::default()
+    };
+
+    let compute = result
+        .gpu
+        .as_ref()
+        .and_then(|g| g.compute_capability.as_deref())
+        .and_then(parse_compute_capability);
+    result.generation = result
+        .gpu
+        .as_ref()
+        .and_then(|g| g.compute_capability.as_deref())
+        .and_then(generation_name)
+        .map(str::to_string);
+
+    match &result.gpu {
+        None => result
+            .warnings
+            .push("nvidia-smi not found or no NVIDIA GPU detected".to_string()),
+        Some(gpu) if compute.is_none() => result.warnings.push(format!(
+            "Driver {} does not report the compute capability; 10-bit / B-frame support is estimated",
+            gpu.driver_version
+        )),
+        _ => {}
+    }
+
+    for (codec, encoder) in NVENC_CODECS {
+        let help = crate::encoders::encoder_help(&config.ffmpeg_path, encoder).ok();
+        let working = help.is_some() && crate::encoders::encoder_works(encoder);
+        result.nvenc.push(codec_support(
+            codec,
+            encoder,
+            help.as_deref(),
+            working,
+            compute,
+        ));
+    }
+
+    let _ = crate::write_log(format!(
+        "[GPU CAPS] gpu={:?} generation={:?} nvenc=[{}]",
+        result.gpu.as_ref().map(|g| g.name.as_str()),
+        result.generation,
+        result
+            .nvenc
+            .iter()
+            .map(|n| format!(
+                "{}: working={} 10bit={} bframes={}",
+                n.codec, n.working, n.ten_bit, n.b_frames
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    Ok(result)
+}
+
+// ============================================================================
+// Intel Quick Sync
+// ============================================================================
+
+/// Intel GPU and the QSV encoders that passed a test encode
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QsvSupport {
+    /// First Intel display adapter found (None on AMD/NVIDIA-only machines)
+    pub intel_gpu: Option<String>,
+    pub h264: bool,
+    pub hevc: bool,
+    pub av1: bool,
+    pub vp9: bool,
+}
+
+/// Detect an Intel iGPU / Arc card and which QSV encoders work on it
+#[tauri::command]
+pub fn get_qsv_support() -> Result<QsvSupport, String> {
+    let listed: Vec<String> = crate::encoders::list_encoders()?
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    let works =
+        |name: &str| listed.iter().any(|l| l == name) && crate::encoders::encoder_works(name);
+
+    let support = QsvSupport {
+        intel_gpu: crate::hardware::display_adapters()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|name| name.to_lowercase().contains("intel")),
+        h264: works("h264_qsv"),
+        hevc: works("hevc_qsv"),
+        av1: works("av1_qsv"),
+        vp9: works("vp9_qsv"),
+    };
+
+    let _ = crate::write_log(format!(
+        "[QSV] intel_gpu={:?} h264={} hevc={} av1={} vp9={}",
+        support.intel_gpu, support.h264, support.hevc, support.av1, support.vp9
+    ));
+
+    Ok(support)
+}
+
+// ============================================================================
+// AMD AMF / VAAPI
+// ============================================================================
+
+/// Render node used when the GPU vendor of the nodes cannot be read
+pub const DEFAULT_RENDER_NODE: &str = "/dev/dri/renderD128";
+
+/// One DRM render node (/dev/dri/renderD*) and the vendor of its GPU
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RenderNode {
+    pub path: String,
+    /// amd, intel, nvidia or unknown
+    pub vendor: String,
+}
+
+fn vendor_from_pci_id(id: &str) -> &'static str {
+    match id.trim().to_lowercase().as_str() {
+        "0x1002" => "amd",
+        "0x8086" => "intel",
+        "0x10de" => "nvidia",
+        _ => "unknown",
+    }
+}
+
+/// Render nodes sorted by name, with the PCI vendor read from sysfs (Linux only)
+pub fn render_nodes() -> Vec<RenderNode> {
+    let Ok(entries) = std::fs::read_dir("/dev/dri") else {
+        return Vec::new();
+    };
+
+    let mut nodes: Vec<RenderNode> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("renderD"))
+        .map(|name| {
+            let vendor = std::fs::read_to_string(format!("/sys/class/drm/{}/device/vendor", name))
+                .map(|id| vendor_from_pci_id(&id).to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            RenderNode {
+                path: format!("/dev/dri/{}", name),
+                vendor,
+            }
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+    nodes
+}
+
+/// Render node of a GPU vendor; falls back to the first node of unknown vendor
+/// (never an NVIDIA node, which has no VAAPI encoder)
+pub fn render_node_for(vendor: &str) -> Option<String> {
+    let nodes = render_nodes();
+    nodes
+        .iter()
+        .find(|n| n.vendor == vendor)
+        .or_else(|| nodes.iter().find(|n| n.vendor == "unknown"))
+        .map(|n| n.path.clone())
+}
+
+/// AMD GPU and the hardware encoders that passed a test encode
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AmdSupport {
+    /// First AMD display adapter found
+    pub amd_gpu: Option<String>,
+    /// amf (Windows) or vaapi (Linux)
+    pub api: String,
+    /// Render node VAAPI encodes on (Linux only)
+    pub render_node: Option<String>,
+    pub h264: bool,
+    pub hevc: bool,
+    pub av1: bool,
+}
+
+/// Detect an AMD GPU and which AMF (Windows) / VAAPI (Linux) encoders work on it
+#[tauri::command]
+pub fn get_amd_support() -> Result<AmdSupport, String> {
+    let listed: Vec<String> = crate::encoders::list_encoders()?
+        .into_iter()
+        .map(|e| e.name)
+        .collect();
+    let works =
+        |name: &str| listed.iter().any(|l| l == name) && crate::encoders::encoder_works(name);
+
+    let api = crate::encode_args::hardware_for_vendor("amd").unwrap_or("vaapi");
+    let support = AmdSupport {
+        amd_gpu: crate::hardware::display_adapters()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|name| {
+                let lower = name.to_lowercase();
+                lower.contains("amd") || lower.contains("radeon") || lower.contains("ati ")
+            }),
+        api: api.to_string(),
+        render_node: if api == "vaapi" {
+            render_node_for("amd")
+        } else {
+            None
+        },
+        h264: works(&format!("h264_{}", api)),
+        hevc: works(&format!("hevc_{}", api)),
+        av1: works(&format!("av1_{}", api)),
+    };
+
+    let _ = crate::write_log(format!(
+        "[AMD] gpu={:?} api={} node={:?} h264={} hevc={} av1={}",
+        support.amd_gpu, support.api, support.render_node, support.h264, support.hevc, support.av1
+    ));
+
+    Ok(support)
+}
+
+// ============================================================================
+// Driver / NVENC compatibility
+// ============================================================================
+
+/// Outcome of check_gpu_compatibility
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuStatus {
+    Ok,
+    /// FFmpeg was built without the GPU's encoder (NVENC, QSV, ...)
+    NotInBuild,
+    NoGpu,
+    /// The driver is older than the NVENC API the FFmpeg build was compiled against
+    DriverTooOld,
+    EncoderFailed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GpuCompatibility {
+    pub available: bool,
+    pub status: GpuStatus,
+    pub driver_version: Option<String>,
+    /// Minimum driver required by the FFmpeg build (known when NVENC refused to start)
+    pub required_driver: Option<String>,
+    /// Human readable explanation / suggested fix
+    pub message: String,
+}
+
+impl GpuCompatibility {
+    fn new(status: GpuStatus, message: impl Into<String>) -> Self {
+        Self {
+            available: status == GpuStatus::Ok,
+            status,
+            driver_version: None,
+            required_driver: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Driver version from /proc/driver/nvidia/version (Linux only), used when
+/// nvidia-smi is not on PATH
+fn proc_driver_version() -> Option<String> {
+    let text = std::fs::read_to_string("/proc/driver/nvidia/version").ok()?;
+    let line = text.lines().find(|l| l.starts_with("NVRM version"))?;
+    line.split_whitespace()
+        .find(|part| part.contains('.') && part.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        .map(str::to_string)
+}
+
+/// Compare dotted driver versions ("551.86" vs "550.54.14"); missing parts count as 0
+pub fn driver_at_least(found: &str, required: &str) -> bool {
+    let parts = |v: &str| -> Vec<u32> {
+        v.trim()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (found, required) = (parts(found), parts(required));
+    for i in 0..found.len().max(required.len()) {
+        let (f, r) = (
+            found.get(i).copied().unwrap_or(0),
+            required.get(i).copied().unwrap_or(0),
+        );
+        if f != r {
+            return f > r;
+        }
+    }
+    true
+}
+
+/// Pull the minimum driver version out of FFmpeg's NVENC init error, e.g.
+/// "The minimum required Nvidia driver for nvenc is 551.76 or newer"
+pub fn parse_required_driver(stderr: &str) -> Option<String> {
+    lazy_static::lazy_static! {
+        static ref MIN_DRIVER: regex::Regex =
+            regex::Regex::new(r"(?i)minimum required nvidia driver for nvenc is ([0-9.]+)").unwrap();
+    }
+    MIN_DRIVER
+        .captures(stderr)
+        .map(|c| c[1].trim_end_matches('.').to_string())
+}
+
+/// FFmpeg reports an API mismatch as "Driver does not support the required nvenc API version"
+fn is_api_mismatch(stderr: &str) -> bool {
+    let lower = stderr.to_lowercase();
+    lower.contains("driver does not support the required nvenc api version")
+        || lower.contains("minimum required nvidia driver")
+}
+
+/// Classify an NVENC test encode into an actionable status
+pub fn classify_nvenc_probe(
+    success: bool,
+    stderr: &str,
+    driver_version: Option<String>,
+) -> GpuCompatibility {
+    let required = parse_required_driver(stderr);
+
+    let mut result = if success {
+        GpuCompatibility::new(GpuStatus::Ok, "NVENC is available")
+    } else if is_api_mismatch(stderr)
+        || matches!((&driver_version, &required), (Some(found), Some(min)) if !driver_at_least(found, min))
+    {
+        GpuCompatibility::new(
+            GpuStatus::DriverTooOld,
+            format!(
+                "NVIDIA driver {} is too old for this FFmpeg build (requires {} or newer). Update the GPU driver or use an older FFmpeg build.",
+                driver_version.as_deref().unwrap_or("(unknown)"),
+                required.as_deref().unwrap_or("a newer version")
+            ),
+        )
+    } else if driver_version.is_none() {
+        GpuCompatibility::new(GpuStatus::NoGpu, "No NVIDIA GPU / driver detected")
+    } else {
+        let reason = stderr
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or("unknown error");
+        GpuCompatibility::new(
+            GpuStatus::EncoderFailed,
+            format!("NVENC failed to initialize: {}", reason),
+        )
+    };
+
+    result.driver_version = driver_version;
+    result.required_driver = required;
+    result
+}
+
+/// Check that NVENC is compiled in, a driver is installed and new enough, and a
+/// test encode succeeds
+pub fn diagnose_nvenc(ffmpeg_path: &str) -> Result<GpuCompatibility, String> {
+    let encoders = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args(["-hide_banner", "-encoders"]),
+        &crate::proc::RunOptions::with_timeout(10).label("gpu check"),
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !encoders.stdout.to_lowercase().contains("nvenc") {
+        return Ok(GpuCompatibility::new(
+            GpuStatus::NotInBuild,
+            "This FFmpeg build has no NVENC encoders",
+        ));
+    }
+
+    let driver_version = query_nvidia_smi()
+        .map(|gpu| gpu.driver_version)
+        .or_else(proc_driver_version);
+
+    let probe = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args([
+            "-hide_banner",
+            "-v",
+            "error",
+            "-f",
+            "lavfi",
+            "-i",
+            "color=black:s=256x256:d=0.1",
+            "-frames:v",
+            "1",
+            "-c:v",
+            "h264_nvenc",
+            "-f",
+            "null",
+            "-",
+        ]),
+        &crate::proc::RunOptions::with_timeout(15).label("nvenc probe"),
+    )
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    Ok(classify_nvenc_probe(
+        probe.success,
+        &probe.stderr,
+        driver_version,
+    ))
+}
+
+/// Classify the H.264 test encode of a hardware API other than NVENC
+pub fn classify_hw_probe(api: &str, listed: bool, works: bool) -> GpuCompatibility {
+    let name = match api {
+        "qsv" => "Intel Quick Sync",
+        "amf" => "AMD AMF",
+        "vaapi" => "VAAPI",
+        other => other,
+    };
+    let encoder = format!("h264_{}", api);
+    if !listed {
+        GpuCompatibility::new(
+            GpuStatus::NotInBuild,
+            format!("This FFmpeg build has no {} encoders ({})", name, encoder),
+        )
+    } else if works {
+        GpuCompatibility::new(GpuStatus::Ok, format!("{} is available", name))
+    } else {
+        GpuCompatibility::new(
+            GpuStatus::EncoderFailed,
+            format!(
+                "{} failed to initialize ({}). Update the GPU driver.",
+                name, encoder
+            ),
+        )
+    }
+}
+
+/// Check the encoder of the detected GPU: NVENC on NVIDIA (and when the vendor is
+/// unknown), otherwise a test encode with the vendor's own H.264 encoder
+pub fn diagnose_gpu(ffmpeg_path: &str) -> Result<GpuCompatibility, String> {
+    match crate::encode_args::detected_hardware() {
+        Some(api) if api != "nvenc" => {
+            let encoder = format!("h264_{}", api);
+            let listed = crate::encoders::list_encoders()?
+                .iter()
+                .any(|e| e.name == encoder);
+            Ok(classify_hw_probe(
+                api,
+                listed,
+                listed && crate::encoders::encoder_works(&encoder),
+            ))
+        }
+        _ => diagnose_nvenc(ffmpeg_path),
+    }
+}
+
+// ============================================================================
+// NVENC session limit
+// ============================================================================
+
+// Consumer GeForce drivers allow only a few NVENC sessions at once (3, later 5 and
+// 8) across all programs. A render refused with OpenEncodeSessionEx records how
+// many sessions were busy at that moment as the limit; the queue then keeps its
+// GPU slot free until nvidia-smi reports fewer sessions than that.
+
+lazy_static::lazy_static! {
+    /// Sessions that were busy when NVENC last refused one (None until it happens)
+    static ref NVENC_SESSION_LIMIT: std::sync::Mutex<Option<u32>> = std::sync::Mutex::new(None);
+}
+
+/// Result of get_nvenc_session_state
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct NvencSessionState {
+    /// Detected session limit (None until a render hit it)
+    pub limit: Option<u32>,
+    /// Sessions open on the GPUs now, by any program (None without nvidia-smi)
+    pub active_sessions: Option<u32>,
+    /// A GPU render may start now
+    pub can_start: bool,
+}
+
+/// Sum of `nvidia-smi --query-gpu=encoder.stats.sessionCount --format=csv,noheader,nounits`
+pub fn parse_session_count(output: &str) -> Option<u32> {
+    let counts: Vec<u32> = output
+        .lines()
+        .filter_map(|line| line.trim().parse::<u32>().ok())
+        .collect();
+    (!counts.is_empty()).then(|| counts.iter().sum())
+}
+
+/// NVENC sessions open now, by any program
+pub fn query_nvenc_sessions() -> Option<u32> {
+    crate::proc::run_captured(
+        crate::proc::command("nvidia-smi").args([
+            "--query-gpu=encoder.stats.sessionCount",
+            "--format=csv,noheader,nounits",
+        ]),
+        &crate::proc::RunOptions::with_timeout(10).label("nvidia-smi"),
+    )
+    .ok()
+    .filter(|o| o.success)
+    .and_then(|o| parse_session_count(&o.stdout))
+}
+
+fn session_state(limit: Option<u32>, active_sessions: Option<u32>) -> NvencSessionState {
+    NvencSessionState {
+        limit,
+        active_sessions,
+        // Without both numbers the queue simply retries
+        can_start: match (limit, active_sessions) {
+            (Some(limit), Some(active)) => active < limit,
+            _ => true,
+        },
+    }
+}
+
+/// Called when a render failed with RenderErrorKind::NvencSessionLimit
+pub fn note_nvenc_session_limit(job_id: &str) {
+    // The refused session is gone already: what is left is what the driver allowed
+    let active = query_nvenc_sessions().filter(|count| *count > 0);
+    if let (Some(active), Ok(mut limit)) = (active, NVENC_SESSION_LIMIT.lock()) {
+        *limit = Some(active);
+    }
+    let _ = crate::write_render_log(
+        job_id.to_string(),
+        format!(
+            "[NVENC] Session refused with {:?} session(s) open; GPU slot held until one closes",
+            active
+        ),
+    );
+}
+
+/// Detected NVENC session limit and whether a GPU render may start now
+#[tauri::command]
+pub fn get_nvenc_session_state() -> NvencSessionState {
+    let limit = NVENC_SESSION_LIMIT.lock().ok().and_then(|limit| *limit);
+    let active = limit.and_then(|_| query_nvenc_sessions());
+    session_state(limit, active)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nvenc_sessions() {
+        assert_eq!(parse_session_count("2\n1\n"), Some(3));
+        assert_eq!(parse_session_count("[N/A]\n"), None);
+        assert!(!session_state(Some(3), Some(3)).can_start);
+        assert!(session_state(Some(3), Some(2)).can_start);
+        // Nothing detected yet
+        assert!(session_state(None, None).can_start);
+    }
+
+    const HEVC_HELP: &str = r#"Encoder hevc_nvenc [NVIDIA NVENC hevc encoder]:
+    Supported pixel formats: yuv420p nv12 p010le yuv444p
+hevc_nvenc AVOptions:
+  -b_ref_mode        <int>        E..V....... Use B frames as references (from -1 to 2) (default -1)
+"#;
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let gpu = parse_nvidia_smi("NVIDIA GeForce RTX 4070, 551.86, 8.9\n").unwrap();
+        assert_eq!(gpu.name, "NVIDIA GeForce RTX 4070");
+        assert_eq!(gpu.driver_version, "551.86");
+        assert_eq!(gpu.compute_capability.as_deref(), Some("8.9"));
+        assert_eq!(generation_name("8.9"), Some("Ada Lovelace"));
+
+        let old = parse_nvidia_smi("GeForce GTX 1060 6GB, 472.12").unwrap();
+        assert_eq!(old.compute_capability, None);
+        assert!(parse_nvidia_smi("").is_none());
+    }
+
+    #[test]
+    fn test_hevc_support_by_generation() {
+        let pascal = codec_support("hevc", "hevc_nvenc", Some(HEVC_HELP), true, Some((6, 1)));
+        assert!(pascal.ten_bit);
+        assert!(!pascal.b_frames);
+
+        let turing = codec_support("hevc", "hevc_nvenc", Some(HEVC_HELP), true, Some((7, 5)));
+        assert!(turing.ten_bit && turing.b_frames);
+
+        let missing = codec_support("av1", "av1_nvenc", None, false, Some((7, 5)));
+        assert!(!missing.in_build && !missing.ten_bit && !missing.b_frames);
+    }
+
+    #[test]
+    fn test_driver_too_old_is_reported() {
+        assert!(driver_at_least("551.86", "551.76"));
+        assert!(driver_at_least("550.54.14", "550.54"));
+        assert!(!driver_at_least("537.58", "551.76"));
+
+        let stderr = "[h264_nvenc @ 0x1] Driver does not support the required nvenc API version. Required: 12.2 Found: 12.1\n\
+                      [h264_nvenc @ 0x1] The minimum required Nvidia driver for nvenc is 551.76 or newer\n";
+        let result = classify_nvenc_probe(false, stderr, Some("537.58".to_string()));
+        assert_eq!(result.status, GpuStatus::DriverTooOld);
+        assert_eq!(result.required_driver.as_deref(), Some("551.76"));
+        assert!(!result.available);
+
+        let no_device = classify_nvenc_probe(false, "No capable devices found", None);
+        assert_eq!(no_device.status, GpuStatus::NoGpu);
+        assert!(classify_nvenc_probe(true, "", Some("551.86".to_string())).available);
+    }
+
+    #[test]
+    fn test_qsv_probe_is_reported() {
+        let ok = classify_hw_probe("qsv", true, true);
+        assert!(ok.available);
+        assert!(ok.message.contains("Quick Sync"));
+        assert_eq!(
+            classify_hw_probe("qsv", true, false).status,
+            GpuStatus::EncoderFailed
+        );
+        assert_eq!(
+            classify_hw_probe("qsv", false, false).status,
+            GpuStatus::NotInBuild
+        );
+    }
+}
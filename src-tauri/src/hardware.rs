@@ -0,0 +1,326 @@
+// Hardware detection
+// CPU vendor, model and core counts come from sysinfo (CPUID on x86, /proc/cpuinfo or
+// sysctl elsewhere). Display adapters come from WMI's Win32_VideoController on Windows,
+// lspci on Linux and system_profiler on macOS. Nothing here shells out to `wmic`, which
+// recent Windows 11 builds no longer ship.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuInfo {
+    /// "intel", "amd", "apple" or "unknown"
+    pub vendor: String,
+    /// e.g. "AMD Ryzen 7 5800X 8-Core Processor"
+    pub model: Option<String>,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+}
+
+/// CPU vendor from a CPUID vendor id ("GenuineIntel") or a model name
+fn cpu_vendor_from(text: &str) -> &'static str {
+    let lower = text.to_lowercase();
+    if lower.contains("intel") {
+        "intel"
+    } else if lower.contains("amd") {
+        "amd"
+    } else if lower.contains("apple") {
+        "apple"
+    } else {
+        "unknown"
+    }
+}
+
+/// GPU vendor of one display adapter name
+fn gpu_vendor_from(name: &str) -> Option<&'static str> {
+    let lower = name.to_lowercase();
+    if ["nvidia", "geforce", "quadro", "rtx", "gtx"]
+        .iter()
+        .any(|key| lower.contains(key))
+    {
+        Some("nvidia")
+    } else if lower.contains("amd") || lower.contains("radeon") || lower.contains("ati ") {
+        Some("amd")
+    } else if lower.contains("intel") {
+        Some("intel")
+    } else if lower.contains("apple") {
+        Some("apple")
+    } else {
+        None
+    }
+}
+
+/// Vendor of the GPU to encode on: a discrete NVIDIA/AMD card wins over an Intel iGPU
+pub fn gpu_vendor(adapters: &[String]) -> &'static str {
+    let vendors: Vec<&str> = adapters.iter().filter_map(|a| gpu_vendor_from(a)).collect();
+    ["nvidia", "amd", "intel", "apple"]
+        .into_iter()
+        .find(|vendor| vendors.contains(vendor))
+        .unwrap_or("unknown")
+}
+
+pub fn cpu_info() -> CpuInfo {
+    let mut system = sysinfo::System::new();
+    system.refresh_cpu();
+    let cpus = system.cpus();
+    let (vendor_id, brand) = cpus
+        .first()
+        .map(|cpu| (cpu.vendor_id().trim(), cpu.brand().trim()))
+        .unwrap_or_default();
+
+    let mut vendor = cpu_vendor_from(vendor_id);
+    if vendor == "unknown" {
+        vendor = cpu_vendor_from(brand);
+    }
+    CpuInfo {
+        vendor: vendor.to_string(),
+        model: Some(brand.to_string()).filter(|b| !b.is_empty()),
+        physical_cores: system.physical_core_count(),
+        logical_cores: cpus.len().max(1),
+    }
+}
+
+/// One display adapter
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GpuAdapter {
+    pub name: String,
+    /// Dedicated video memory, when the OS reports it
+    pub vram_bytes: Option<u64>,
+}
+
+#[cfg(windows)]
+#[derive(serde::Deserialize)]
+#[serde(rename = "Win32_VideoController", rename_all = "PascalCase")]
+struct VideoController {
+    name: Option<String>,
+    /// uint32 in WMI, so it tops out at 4 GiB; nvidia-smi corrects NVIDIA cards
+    adapter_ram: Option<u64>,
+}
+
+#[cfg(windows)]
+fn list_adapters() -> Result<Vec<GpuAdapter>, String> {
+    // WMI needs COM in the multithreaded apartment, while the thread running Tauri
+    // commands may already be initialised as single-threaded; use a fresh thread
+    std::thread::spawn(|| {
+        let com = wmi::COMLibrary::new().map_err(|e| format!("COM init failed: {}", e))?;
+        let connection =
+            wmi::WMIConnection::new(com).map_err(|e| format!("WMI connection failed: {}", e))?;
+        let controllers: Vec<VideoController> = connection
+            .query()
+            .map_err(|e| format!("WMI query failed: {}", e))?;
+        Ok(controllers
+            .into_iter()
+            .filter_map(|c| {
+                let name = c.name?.trim().to_string();
+                (!name.is_empty()).then_some(GpuAdapter {
+                    name,
+                    vram_bytes: c.adapter_ram.filter(|&ram| ram > 0),
+                })
+            })
+            .collect())
+    })
+    .join()
+    .map_err(|_| "WMI query panicked".to_string())?
+}
+
+/// "00:02.0 VGA compatible controller: Intel Corporation ..." -> "Intel Corporation ..."
+#[cfg(any(test, target_os = "linux"))]
+fn parse_lspci(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| {
+            line.contains("VGA compatible controller")
+                || line.contains("Display controller")
+                || line.contains("3D controller")
+        })
+        .filter_map(|line| {
+            line.split_once(": ")
+                .map(|(_, name)| name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn list_adapters() -> Result<Vec<GpuAdapter>, String> {
+    let output = crate::proc::run_captured(
+        &mut crate::proc::command("lspci"),
+        &crate::proc::RunOptions::with_timeout(10).label("lspci"),
+    )?;
+    Ok(parse_lspci(&output.stdout)
+        .into_iter()
+        .map(|name| GpuAdapter {
+            name,
+            vram_bytes: None,
+        })
+        .collect())
+}
+
+/// VRAM of amdgpu cards from sysfs, in card order
+#[cfg(target_os = "linux")]
+fn amd_vram_sizes() -> Vec<u64> {
+    let mut cards: Vec<(u32, u64)> = std::fs::read_dir("/sys/class/drm")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let index = name.strip_prefix("card")?.parse::<u32>().ok()?;
+                    let device = entry.path().join("device");
+                    let vendor = std::fs::read_to_string(device.join("vendor")).ok()?;
+                    if vendor.trim() != "0x1002" {
+                        return None;
+                    }
+                    let vram = std::fs::read_to_string(device.join("mem_info_vram_total")).ok()?;
+                    Some((index, vram.trim().parse().ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    cards.sort();
+    cards.into_iter().map(|(_, vram)| vram).collect()
+}
+
+/// `system_profiler SPDisplaysDataType`: "Chipset Model:" starts an adapter, followed by
+/// "VRAM (Total): 8 GB" on discrete GPUs (Apple Silicon shares system memory)
+#[cfg(any(test, target_os = "macos"))]
+fn parse_system_profiler(output: &str) -> Vec<GpuAdapter> {
+    let mut adapters: Vec<GpuAdapter> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Chipset Model:") {
+            adapters.push(GpuAdapter {
+                name: name.trim().to_string(),
+                vram_bytes: None,
+            });
+        } else if let Some((key, value)) = line.split_once(':') {
+            let Some(adapter) = adapters.last_mut() else {
+                continue;
+            };
+            if !key.starts_with("VRAM") || adapter.vram_bytes.is_some() {
+                continue;
+            }
+            let mut parts = value.split_whitespace();
+            let amount: Option<u64> = parts.next().and_then(|n| n.parse().ok());
+            let unit = match parts.next() {
+                Some("GB") => 1024 * 1024 * 1024,
+                Some("MB") => 1024 * 1024,
+                _ => continue,
+            };
+            adapter.vram_bytes = amount.map(|n| n * unit);
+        }
+    }
+    adapters.retain(|adapter| !adapter.name.is_empty());
+    adapters
+}
+
+#[cfg(target_os = "macos")]
+fn list_adapters() -> Result<Vec<GpuAdapter>, String> {
+    let output = crate::proc::run_captured(
+        crate::proc::command("system_profiler").arg("SPDisplaysDataType"),
+        &crate::proc::RunOptions::with_timeout(15).label("system_profiler"),
+    )?;
+    Ok(parse_system_profiler(&output.stdout))
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn list_adapters() -> Result<Vec<GpuAdapter>, String> {
+    Err("Display adapters cannot be listed on this platform".to_string())
+}
+
+/// Display adapter names reported by the OS
+pub fn display_adapters() -> Result<Vec<String>, String> {
+    Ok(list_adapters()?
+        .into_iter()
+        .map(|adapter| adapter.name)
+        .collect())
+}
+
+/// `nvidia-smi --query-gpu=memory.total --format=csv,noheader,nounits` (MiB per GPU)
+fn parse_nvidia_memory(output: &str) -> Vec<u64> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().parse::<u64>().ok())
+        .map(|mib| mib * 1024 * 1024)
+        .collect()
+}
+
+/// Display adapters with their video memory, for diagnostics
+pub fn gpu_adapters() -> Result<Vec<GpuAdapter>, String> {
+    let mut adapters = list_adapters()?;
+
+    // nvidia-smi and the OS both list GPUs in PCI bus order
+    let fill = |adapters: &mut Vec<GpuAdapter>, vendor: &str, sizes: Vec<u64>| {
+        let matching = adapters
+            .iter_mut()
+            .filter(|adapter| gpu_vendor_from(&adapter.name) == Some(vendor));
+        for (adapter, size) in matching.zip(sizes) {
+            adapter.vram_bytes = Some(size);
+        }
+    };
+    if adapters
+        .iter()
+        .any(|adapter| gpu_vendor_from(&adapter.name) == Some("nvidia"))
+    {
+        let smi = crate::proc::run_captured(
+            crate::proc::command("nvidia-smi")
+                .args(["--query-gpu=memory.total", "--format=csv,noheader,nounits"]),
+            &crate::proc::RunOptions::with_timeout(10).label("nvidia-smi"),
+        );
+        if let Ok(output) = smi {
+            if output.success {
+                fill(&mut adapters, "nvidia", parse_nvidia_memory(&output.stdout));
+            }
+        }
+    }
+    #[cfg(target_os = "linux")]
+    fill(&mut adapters, "amd", amd_vram_sizes());
+
+    Ok(adapters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_detection() {
+        assert_eq!(cpu_vendor_from("GenuineIntel"), "intel");
+        assert_eq!(cpu_vendor_from("AuthenticAMD"), "amd");
+        assert_eq!(cpu_vendor_from("Apple M2 Pro"), "apple");
+        assert_eq!(cpu_vendor_from(""), "unknown");
+
+        let adapters = parse_lspci(
+            "00:02.0 VGA compatible controller: Intel Corporation UHD Graphics 630 (rev 02)\n\
+             00:1f.3 Audio device: Intel Corporation Cannon Lake PCH cAVS (rev 10)\n\
+             01:00.0 3D controller: NVIDIA Corporation TU117M [GeForce GTX 1650 Mobile] (rev a1)\n",
+        );
+        assert_eq!(
+            adapters,
+            vec![
+                "Intel Corporation UHD Graphics 630 (rev 02)",
+                "NVIDIA Corporation TU117M [GeForce GTX 1650 Mobile] (rev a1)",
+            ]
+        );
+        assert_eq!(gpu_vendor(&adapters), "nvidia");
+        assert_eq!(gpu_vendor(&adapters[..1]), "intel");
+        assert_eq!(gpu_vendor(&["AMD Radeon RX 6600".to_string()]), "amd");
+        assert_eq!(gpu_vendor(&[]), "unknown");
+    }
+
+    #[test]
+    fn test_vram_parsing() {
+        assert_eq!(
+            parse_nvidia_memory("12288\n8192\n"),
+            vec![12288 * 1024 * 1024, 8192 * 1024 * 1024]
+        );
+
+        let adapters = parse_system_profiler(
+            "Graphics/Displays:\n\n    Intel UHD Graphics 630:\n\n      Chipset Model: Intel UHD Graphics 630\n      \
+             VRAM (Dynamic, Max): 1536 MB\n\n    AMD Radeon Pro 5500M:\n\n      Chipset Model: AMD Radeon Pro 5500M\n      \
+             VRAM (Total): 8 GB\n      Vendor: AMD (0x1002)\n",
+        );
+        assert_eq!(adapters.len(), 2);
+        assert_eq!(adapters[0].vram_bytes, Some(1536 * 1024 * 1024));
+        assert_eq!(adapters[1].name, "AMD Radeon Pro 5500M");
+        assert_eq!(adapters[1].vram_bytes, Some(8 * 1024 * 1024 * 1024));
+    }
+}
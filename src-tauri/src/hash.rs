@@ -0,0 +1,112 @@
+// File hashing
+// Streaming SHA-2 checksums for backups and for the input/output manifest that
+// archival users keep in the statistics file.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Bytes read from each end of a file for `quick_fingerprint`
+const FINGERPRINT_SAMPLE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+}
+
+/// Payload of the `render-hashes` event
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderHashes {
+    pub job_id: String,
+    pub algorithm: HashAlgorithm,
+    pub input_hash: Option<String>,
+    pub output_hash: Option<String>,
+}
+
+fn digest_reader<D: Digest>(mut reader: impl Read) -> std::io::Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Lowercase hex digest of a file, read in chunks so multi-GB videos are fine
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let reader = std::io::BufReader::new(file);
+    match algorithm {
+        HashAlgorithm::Sha256 => digest_reader::<Sha256>(reader),
+        HashAlgorithm::Sha512 => digest_reader::<Sha512>(reader),
+    }
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Lowercase hex SHA-256 of data already in memory
+pub fn sha256_bytes(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Cheap content identity of a large file: SHA-256 over its size and the first and last
+/// megabyte. Recognizes a copy of a video under another name without reading it all.
+pub fn quick_fingerprint(path: &Path) -> Result<String, String> {
+    let read_error = |e: std::io::Error| format!("Failed to read {}: {}", path.display(), e);
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let len = file.metadata().map_err(read_error)?.len();
+
+    let mut sample = Vec::new();
+    (&mut file)
+        .take(FINGERPRINT_SAMPLE)
+        .read_to_end(&mut sample)
+        .map_err(read_error)?;
+    if len > FINGERPRINT_SAMPLE {
+        let tail_start = len
+            .saturating_sub(FINGERPRINT_SAMPLE)
+            .max(FINGERPRINT_SAMPLE);
+        file.seek(SeekFrom::Start(tail_start)).map_err(read_error)?;
+        file.read_to_end(&mut sample).map_err(read_error)?;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(len.to_le_bytes());
+    hasher.update(&sample);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Checksum of a file (SHA-256 unless another algorithm is given)
+#[tauri::command]
+pub async fn compute_file_hash(
+    path: String,
+    algorithm: Option<HashAlgorithm>,
+) -> Result<String, String> {
+    hash_file(Path::new(&path), algorithm.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_reader_known_values() {
+        assert_eq!(
+            digest_reader::<Sha256>(&b"abc"[..]).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(digest_reader::<Sha512>(&b""[..])
+            .unwrap()
+            .starts_with("cf83e1357eefb8bd"));
+    }
+}
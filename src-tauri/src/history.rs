@@ -0,0 +1,231 @@
+// Job history
+// Every finished render (completed or failed, not stopped) is stored in
+// ~/.szhimatar/history.json with the exact job the frontend sent: paths, FFmpeg
+// arguments and timings. A failed overnight batch can then be re-queued as it was,
+// without rebuilding the settings it used.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Oldest entries are dropped beyond this
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// The job as run_ffmpeg_render received it
+    pub job: crate::RenderJob,
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// RFC 3339 local time
+    pub started_at: String,
+    pub finished_at: String,
+    pub elapsed_seconds: f64,
+    pub input_size_bytes: Option<u64>,
+    pub output_size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// "success" or "failed"; anything else lists both
+    pub status: Option<String>,
+    /// Case-insensitive match on the input or output path
+    pub search: Option<String>,
+    /// Only entries that started at or after this RFC 3339 time
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+}
+
+lazy_static! {
+    /// Serializes read-modify-write of the history file between parallel renders
+    static ref HISTORY_LOCK: Mutex<()> = Mutex::new(());
+}
+
+fn history_file() -> PathBuf {
+    crate::get_app_data_dir().join("history.json")
+}
+
+fn load() -> Vec<HistoryEntry> {
+    std::fs::read_to_string(history_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(entries: &[HistoryEntry]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    std::fs::write(history_file(), content).map_err(|e| format!("Failed to save history: {}", e))
+}
+
+fn file_size(path: &str) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+/// Store the outcome of a render; `job` is the job before run_ffmpeg_render adjusted it
+pub fn record(
+    job: &crate::RenderJob,
+    started_at: chrono::DateTime<chrono::Local>,
+    result: &Result<crate::RenderResult, String>,
+) {
+    let (success, error, warnings) = match result {
+        // Stopped by the user: nothing to remember
+        Ok(result) if result.error.as_deref() == Some("stopped") => return,
+        Ok(result) => (
+            result.success,
+            result.error.clone(),
+            result.warnings.clone(),
+        ),
+        Err(e) => (false, Some(e.clone()), Vec::new()),
+    };
+    let finished_at = chrono::Local::now();
+    let entry = HistoryEntry {
+        id: format!("{}-{}", finished_at.format("%Y%m%d%H%M%S%3f"), job.job_id),
+        job: job.clone(),
+        success,
+        error,
+        warnings,
+        started_at: started_at.to_rfc3339(),
+        finished_at: finished_at.to_rfc3339(),
+        elapsed_seconds: (finished_at - started_at).num_milliseconds() as f64 / 1000.0,
+        input_size_bytes: file_size(&job.input_path),
+        output_size_bytes: if success {
+            file_size(&job.output_path)
+        } else {
+            None
+        },
+    };
+
+    let _guard = HISTORY_LOCK.lock();
+    let mut entries = load();
+    entries.push(entry);
+    let excess = entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    entries.drain(..excess);
+    if let Err(e) = save(&entries) {
+        let _ = crate::write_log(format!("[HISTORY] {}", e));
+    }
+}
+
+fn matches(entry: &HistoryEntry, filter: &HistoryFilter) -> bool {
+    let status_ok = match filter.status.as_deref() {
+        Some("success") => entry.success,
+        Some("failed") => !entry.success,
+        _ => true,
+    };
+    let search_ok = filter
+        .search
+        .as_deref()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .is_none_or(|search| {
+            entry.job.input_path.to_lowercase().contains(&search)
+                || entry.job.output_path.to_lowercase().contains(&search)
+        });
+    let since_ok = filter
+        .since
+        .as_deref()
+        .and_then(|since| chrono::DateTime::parse_from_rfc3339(since).ok())
+        .is_none_or(|since| {
+            chrono::DateTime::parse_from_rfc3339(&entry.started_at)
+                .is_ok_and(|started| started >= since)
+        });
+    status_ok && search_ok && since_ok
+}
+
+/// Finished renders matching `filter`, newest first
+#[tauri::command]
+pub fn list_job_history(filter: Option<HistoryFilter>) -> Vec<HistoryEntry> {
+    let filter = filter.unwrap_or_default();
+    let _guard = HISTORY_LOCK.lock();
+    load()
+        .into_iter()
+        .rev()
+        .filter(|entry| matches(entry, &filter))
+        .take(filter.limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// The job of a history entry, ready to be queued again with its original arguments
+#[tauri::command]
+pub fn rerun_job(history_id: String) -> Result<crate::RenderJob, String> {
+    let _guard = HISTORY_LOCK.lock();
+    let entry = load()
+        .into_iter()
+        .find(|entry| entry.id == history_id)
+        .ok_or_else(|| format!("History entry not found: {}", history_id))?;
+    if !std::path::Path::new(&entry.job.input_path).exists() {
+        return Err(format!("Source no longer exists: {}", entry.job.input_path));
+    }
+    let _ = crate::write_log(format!(
+        "[HISTORY] Re-running {} ({})",
+        history_id, entry.job.input_path
+    ));
+    Ok(entry.job)
+}
+
+#[tauri::command]
+pub fn clear_history() -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock();
+    match std::fs::remove_file(history_file()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to clear history: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(input: &str, success: bool, started_at: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: "1".to_string(),
+            job: crate::RenderJob {
+                job_id: "job".to_string(),
+                input_path: input.to_string(),
+                output_path: "/out/clip.mp4".to_string(),
+                ..Default::default()
+            },
+            success,
+            error: None,
+            warnings: Vec::new(),
+            started_at: started_at.to_string(),
+            finished_at: started_at.to_string(),
+            elapsed_seconds: 1.0,
+            input_size_bytes: None,
+            output_size_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_history_filter() {
+        let ok = entry("/videos/Holiday.mov", true, "2024-05-01T10:00:00+02:00");
+        let failed = entry("/videos/party.mkv", false, "2024-05-02T10:00:00+02:00");
+        let filter =
+            |status: Option<&str>, search: Option<&str>, since: Option<&str>| HistoryFilter {
+                status: status.map(String::from),
+                search: search.map(String::from),
+                since: since.map(String::from),
+                limit: None,
+            };
+
+        assert!(matches(&ok, &HistoryFilter::default()));
+        assert!(matches(&failed, &filter(Some("failed"), None, None)));
+        assert!(!matches(&ok, &filter(Some("failed"), None, None)));
+        assert!(matches(&ok, &filter(None, Some("holiday"), None)));
+        assert!(!matches(&failed, &filter(None, Some("holiday"), None)));
+        assert!(!matches(
+            &ok,
+            &filter(None, None, Some("2024-05-02T00:00:00+02:00"))
+        ));
+        assert!(matches(
+            &failed,
+            &filter(None, None, Some("2024-05-02T00:00:00+02:00"))
+        ));
+    }
+}
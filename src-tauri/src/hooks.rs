@@ -0,0 +1,199 @@
+// External tool hooks
+// Runs user scripts around a render: pre_render before FFmpeg starts (a failing
+// pre-render hook fails the job), post_render_success / post_render_failure after it.
+// Each hook gets the input and output paths as arguments and the job details as
+// SZHIMATAR_* environment variables; its output goes to the job's render log.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookSettings {
+    /// Executable or script run before the render; empty = no hook
+    pub pre_render: String,
+    pub post_render_success: String,
+    pub post_render_failure: String,
+    /// A hook still running after this long is killed (and counts as failed)
+    pub timeout_secs: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            pre_render: String::new(),
+            post_render_success: String::new(),
+            post_render_failure: String::new(),
+            timeout_secs: 600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPoint {
+    PreRender,
+    PostRenderSuccess,
+    PostRenderFailure,
+}
+
+impl HookPoint {
+    fn name(self) -> &'static str {
+        match self {
+            HookPoint::PreRender => "pre_render",
+            HookPoint::PostRenderSuccess => "post_render_success",
+            HookPoint::PostRenderFailure => "post_render_failure",
+        }
+    }
+
+    fn program(self, settings: &HookSettings) -> &str {
+        match self {
+            HookPoint::PreRender => &settings.pre_render,
+            HookPoint::PostRenderSuccess => &settings.post_render_success,
+            HookPoint::PostRenderFailure => &settings.post_render_failure,
+        }
+    }
+}
+
+/// Environment of a hook process
+fn hook_env(
+    point: HookPoint,
+    job: &crate::RenderJob,
+    result: Option<&Result<crate::RenderResult, String>>,
+) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("SZHIMATAR_HOOK", point.name().to_string()),
+        ("SZHIMATAR_JOB_ID", job.job_id.clone()),
+        ("SZHIMATAR_INPUT", job.input_path.clone()),
+        ("SZHIMATAR_OUTPUT", job.output_path.clone()),
+        (
+            "SZHIMATAR_PRESET",
+            job.preset_name.clone().unwrap_or_default(),
+        ),
+    ];
+    match result {
+        Some(Ok(result)) => {
+            if let Some(error) = &result.error {
+                env.push(("SZHIMATAR_ERROR", error.clone()));
+            }
+            if let Some(kind) = result.error_kind {
+                let kind = serde_json::to_value(kind)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                env.push(("SZHIMATAR_ERROR_KIND", kind));
+            }
+        }
+        Some(Err(error)) => env.push(("SZHIMATAR_ERROR", error.clone())),
+        None => {}
+    }
+    env
+}
+
+/// Run the hook configured for `point`, if any. Err when it could not start, timed out
+/// or exited with a non-zero code.
+fn run(
+    point: HookPoint,
+    job: &crate::RenderJob,
+    result: Option<&Result<crate::RenderResult, String>>,
+) -> Result<(), String> {
+    let settings = crate::load_settings().unwrap_or_default().hooks;
+    let program = point.program(&settings).trim();
+    if program.is_empty() {
+        return Ok(());
+    }
+
+    let log = |message: String| {
+        let _ = crate::write_render_log(job.job_id.clone(), message);
+    };
+    log(format!("[HOOK] {}: {}", point.name(), program));
+
+    let mut cmd = crate::proc::command(program);
+    cmd.arg(&job.input_path)
+        .arg(&job.output_path)
+        .envs(hook_env(point, job, result));
+    let options = crate::proc::RunOptions {
+        timeout: Some(Duration::from_secs(settings.timeout_secs.max(1))),
+        ..Default::default()
+    }
+    .label(&format!("hook {}", point.name()));
+    let outcome = crate::proc::spawn_piped(&mut cmd, &options).and_then(|mut child| {
+        crate::proc::run_streaming(&mut child, &options, |stream, line| {
+            let tag = match stream {
+                crate::proc::Stream::Stdout => "out",
+                crate::proc::Stream::Stderr => "err",
+            };
+            log(format!("[HOOK {}] {}", tag, line));
+        })
+    });
+
+    let error = match outcome {
+        Ok(output) if output.success => None,
+        Ok(output) if output.timed_out => Some(format!(
+            "{} hook timed out after {}s",
+            point.name(),
+            settings.timeout_secs
+        )),
+        Ok(output) => Some(format!(
+            "{} hook exited with code {}",
+            point.name(),
+            output
+                .code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        )),
+        Err(e) => Some(format!("{} hook failed to start: {}", point.name(), e)),
+    };
+    match error {
+        None => {
+            log(format!("[HOOK] {} finished", point.name()));
+            Ok(())
+        }
+        Some(error) => {
+            log(format!("[HOOK] {}", error));
+            Err(error)
+        }
+    }
+}
+
+/// Called by run_ffmpeg_render before the render; an Err fails the job
+pub async fn pre_render(job: &crate::RenderJob) -> Result<(), String> {
+    let job = job.clone();
+    tokio::task::spawn_blocking(move || run(HookPoint::PreRender, &job, None))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Called by run_ffmpeg_render after the render; a failing hook is only logged.
+/// Jobs stopped by the user run no hook.
+pub async fn post_render(job: &crate::RenderJob, result: &Result<crate::RenderResult, String>) {
+    let point = match result {
+        Ok(result) if result.error.as_deref() == Some("stopped") => return,
+        Ok(result) if result.success => HookPoint::PostRenderSuccess,
+        _ => HookPoint::PostRenderFailure,
+    };
+    let (job, result) = (job.clone(), result.clone());
+    let _ = tokio::task::spawn_blocking(move || run(point, &job, Some(&result))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_env() {
+        let job = crate::RenderJob {
+            job_id: "job-1".to_string(),
+            input_path: "/in.mkv".to_string(),
+            output_path: "/out.mp4".to_string(),
+            ..Default::default()
+        };
+        let env = hook_env(HookPoint::PreRender, &job, None);
+        assert!(env.contains(&("SZHIMATAR_HOOK", "pre_render".to_string())));
+        assert!(env.contains(&("SZHIMATAR_OUTPUT", "/out.mp4".to_string())));
+        assert!(!env.iter().any(|(key, _)| *key == "SZHIMATAR_ERROR"));
+
+        let failed = Err("Conversion failed".to_string());
+        let env = hook_env(HookPoint::PostRenderFailure, &job, Some(&failed));
+        assert!(env.contains(&("SZHIMATAR_ERROR", "Conversion failed".to_string())));
+    }
+}
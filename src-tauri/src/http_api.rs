@@ -0,0 +1,417 @@
+// Local HTTP API
+// An opt-in REST + WebSocket server so scripts, Stream Deck plugins or another machine
+// on the LAN can drive the queue. It listens on 127.0.0.1 (0.0.0.0 with allowLan) and
+// every request must carry the token from the settings, as `Authorization: Bearer
+// <token>` or `?token=`. The queue lives in the frontend, so submissions and queue
+// actions go to it as the same `files-received` / `queue-control` events the CLI and
+// the tray use; status comes from the queue the frontend persists, and render events
+// are forwarded to the `/api/events` WebSocket subscribers as they are emitted.
+//
+//   GET  /api/status                          queue counters, jobs and their progress
+//   POST /api/jobs                            {"files": [...], "preset", "outputDir", "queueOnly"}
+//   POST /api/queue/{start|pause|resume|stop} control the queue
+//   GET  /api/events                          WebSocket stream of {"event", "payload"}
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest accepted request body
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Required on every request; the server does not start without one
+    pub token: String,
+    /// Listen on all interfaces instead of localhost only
+    pub allow_lan: bool,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8765,
+            token: String::new(),
+            allow_lan: false,
+        }
+    }
+}
+
+/// Body of POST /api/jobs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JobSubmission {
+    /// Videos or folders (expanded like on the command line); absolute paths
+    files: Vec<String>,
+    #[serde(default)]
+    preset: Option<String>,
+    #[serde(default)]
+    output_dir: Option<String>,
+    /// Only add to the queue; without it a submission with a preset also starts it
+    #[serde(default)]
+    queue_only: bool,
+}
+
+/// Job of GET /api/status
+#[derive(Debug, Clone, Serialize)]
+struct JobStatus {
+    id: String,
+    input_path: String,
+    output_path: String,
+    status: String,
+    depends_on: Vec<String>,
+    /// Last render-progress of the job while it renders
+    progress: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ApiStatus {
+    version: &'static str,
+    active_jobs: usize,
+    pending_jobs: usize,
+    progress: f64,
+    paused: bool,
+    jobs: Vec<JobStatus>,
+}
+
+lazy_static! {
+    static ref SERVER: Mutex<Option<Arc<Server>>> = Mutex::new(None);
+    /// Senders of the connected /api/events sockets
+    static ref SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+    /// Last render-progress per running job
+    static ref PROGRESS: Mutex<HashMap<String, serde_json::Value>> = Mutex::new(HashMap::new());
+}
+
+/// Random 256-bit token as hex
+fn new_token() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    // Every RandomState is keyed from the OS random source
+    (0..4u64)
+        .map(|i| {
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            hasher.write_u64(i);
+            hasher.write_u128(nanos);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// Forward an event emitted to the window to the WebSocket subscribers
+pub fn broadcast<T: Serialize>(event: &str, payload: &T) {
+    let Ok(payload) = serde_json::to_value(payload) else {
+        return;
+    };
+    if let Ok(mut progress) = PROGRESS.lock() {
+        match event {
+            "render-progress" => {
+                if let Some(job_id) = payload["job_id"].as_str() {
+                    progress.insert(job_id.to_string(), payload.clone());
+                }
+            }
+            "render-complete" => {
+                if let Some(job_id) = payload.as_str() {
+                    progress.remove(job_id);
+                }
+            }
+            "render-error" | "render-stopped" => {
+                if let Some(job_id) = payload["job_id"].as_str() {
+                    progress.remove(job_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(mut subscribers) = SUBSCRIBERS.lock() else {
+        return;
+    };
+    if subscribers.is_empty() {
+        return;
+    }
+    let message = serde_json::json!({ "event": event, "payload": payload }).to_string();
+    subscribers.retain(|subscriber| subscriber.send(message.clone()).is_ok());
+}
+
+/// Path and token query parameter of a request URL
+fn split_url(url: &str) -> (&str, Option<&str>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty());
+    (path.trim_end_matches('/'), token)
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+fn authorized(request: &Request, query_token: Option<&str>, token: &str) -> bool {
+    let presented = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .or(query_token);
+    // Compare every byte so the time taken does not reveal the matching prefix
+    presented.is_some_and(|presented| {
+        presented.len() == token.len()
+            && presented
+                .bytes()
+                .zip(token.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    })
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<std::io::Cursor<Vec<u8>>> {
+    let body = serde_json::to_string(body).unwrap_or_default();
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+}
+
+fn error_response(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+fn status() -> ApiStatus {
+    let queue = crate::tray::status();
+    let progress = PROGRESS.lock().map(|p| p.clone()).unwrap_or_default();
+    ApiStatus {
+        version: env!("CARGO_PKG_VERSION"),
+        active_jobs: queue.active_jobs,
+        pending_jobs: queue.pending_jobs,
+        progress: queue.progress,
+        paused: queue.paused,
+        jobs: crate::queue::current_queue()
+            .jobs
+            .into_iter()
+            .map(|job| JobStatus {
+                progress: progress.get(&job.id).cloned(),
+                id: job.id,
+                input_path: job.input_path,
+                output_path: job.output_path,
+                status: job.status,
+                depends_on: job.depends_on,
+            })
+            .collect(),
+    }
+}
+
+fn submit_jobs(
+    app: &tauri::AppHandle,
+    request: &mut Request,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    let mut body = String::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .is_err()
+    {
+        return error_response(400, "Could not read the request body");
+    }
+    let submission: JobSubmission = match serde_json::from_str(&body) {
+        Ok(submission) => submission,
+        Err(e) => return error_response(400, &format!("Invalid job: {}", e)),
+    };
+
+    let mut launch = crate::cli::parse_args(
+        submission.files,
+        None,
+        &crate::cli::FolderFilter::from_settings(),
+    );
+    if launch.files.is_empty() {
+        return json_response(
+            422,
+            &serde_json::json!({ "error": "No videos to queue", "ignored": launch.ignored }),
+        );
+    }
+    launch.preset = submission.preset.filter(|p| !p.is_empty());
+    launch.output_dir = submission.output_dir.filter(|d| !d.is_empty());
+    launch.queue_only = submission.queue_only;
+    launch.unattended = true;
+    let _ = crate::write_log(format!(
+        "[HTTP-API] Queueing {} file(s), preset {:?}",
+        launch.files.len(),
+        launch.preset
+    ));
+    let _ = app.emit_all("files-received", &launch);
+    json_response(
+        202,
+        &serde_json::json!({ "files": launch.files, "ignored": launch.ignored }),
+    )
+}
+
+/// Queue action of POST /api/queue/<action>, as sent in `queue-control`
+fn queue_action(action: &str) -> Option<&'static str> {
+    match action {
+        "start" => Some("start"),
+        "pause" => Some("pause"),
+        "resume" => Some("resume"),
+        "stop" => Some("stop_all"),
+        _ => None,
+    }
+}
+
+/// Upgrade GET /api/events to a WebSocket that receives every broadcast event
+fn open_event_stream(request: Request) {
+    let Some(key) = header(&request, "Sec-WebSocket-Key").map(str::to_string) else {
+        let _ = request.respond(error_response(400, "Expected a WebSocket upgrade"));
+        return;
+    };
+    let response = Response::empty(101).with_header(
+        Header::from_bytes(
+            "Sec-WebSocket-Accept",
+            tungstenite::handshake::derive_accept_key(key.as_bytes()),
+        )
+        .unwrap(),
+    );
+    let stream = request.upgrade("websocket", response);
+
+    let (sender, receiver) = channel::<String>();
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.push(sender);
+    }
+    std::thread::spawn(move || {
+        let mut socket = tungstenite::WebSocket::from_raw_socket(
+            stream,
+            tungstenite::protocol::Role::Server,
+            None,
+        );
+        // Ends when the client goes away (send fails) or the server stops (sender dropped)
+        for message in receiver {
+            if socket.send(tungstenite::Message::Text(message)).is_err() {
+                break;
+            }
+        }
+        let _ = socket.close(None);
+    });
+}
+
+fn handle(app: &tauri::AppHandle, token: &str, mut request: Request) {
+    let url = request.url().to_string();
+    let (path, query_token) = split_url(&url);
+    if !authorized(&request, query_token, token) {
+        let _ = request.respond(error_response(401, "Missing or wrong API token"));
+        return;
+    }
+
+    let method = request.method().clone();
+    let response = match (method, path) {
+        (Method::Get, "/api/status") => json_response(200, &status()),
+        (Method::Get, "/api/events") => return open_event_stream(request),
+        (Method::Post, "/api/jobs") => submit_jobs(app, &mut request),
+        (Method::Post, path) if path.starts_with("/api/queue/") => {
+            match queue_action(&path["/api/queue/".len()..]) {
+                Some(action) => {
+                    let _ = crate::write_log(format!("[HTTP-API] Queue {}", action));
+                    crate::queue::request_queue_control(app, action);
+                    json_response(202, &serde_json::json!({ "action": action }))
+                }
+                None => error_response(404, "Unknown queue action"),
+            }
+        }
+        _ => error_response(404, "Not found"),
+    };
+    let _ = request.respond(response);
+}
+
+/// Stop the running server, if any, and drop its WebSocket subscribers
+fn stop() {
+    if let Some(server) = SERVER.lock().ok().and_then(|mut s| s.take()) {
+        server.unblock();
+    }
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.clear();
+    }
+}
+
+/// (Re-)start the server from settings; stops it when the API is disabled
+pub fn apply(app: &tauri::AppHandle) -> Result<(), String> {
+    stop();
+    let settings = crate::load_settings().unwrap_or_default().http_api;
+    if !settings.enabled {
+        return Ok(());
+    }
+    let token = settings.token.trim().to_string();
+    if token.is_empty() {
+        return Err("The HTTP API needs a token".to_string());
+    }
+
+    let host = if settings.allow_lan {
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+    let server = Server::http((host, settings.port))
+        .map(Arc::new)
+        .map_err(|e| format!("Failed to listen on {}:{}: {}", host, settings.port, e))?;
+    *SERVER.lock().map_err(|e| e.to_string())? = Some(server.clone());
+    let _ = crate::write_log(format!(
+        "[HTTP-API] Listening on {}:{}",
+        host, settings.port
+    ));
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // incoming_requests ends once stop() unblocks the server
+        for request in server.incoming_requests() {
+            handle(&app, &token, request);
+        }
+    });
+    Ok(())
+}
+
+/// Called by the frontend after the API settings were saved
+#[tauri::command]
+pub fn apply_http_api(app: tauri::AppHandle) -> Result<(), String> {
+    apply(&app)
+}
+
+/// A fresh token for the settings page (not saved until the settings are)
+#[tauri::command]
+pub fn generate_api_token() -> String {
+    new_token()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(split_url("/api/status"), ("/api/status", None));
+        assert_eq!(
+            split_url("/api/events/?token=abc&x=1"),
+            ("/api/events", Some("abc"))
+        );
+        assert_eq!(split_url("/api/status?token="), ("/api/status", None));
+        assert_eq!(queue_action("stop"), Some("stop_all"));
+        assert_eq!(queue_action("shutdown"), None);
+    }
+
+    #[test]
+    fn test_new_token() {
+        let token = new_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, new_token());
+    }
+}
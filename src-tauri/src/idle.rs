@@ -0,0 +1,239 @@
+// Idle trigger
+// Lets the queue run only while nobody is using the machine. The time since the last
+// keyboard/mouse input is read with GetLastInputInfo on Windows, from IOHIDSystem on
+// macOS and with xprintidle or GNOME's IdleMonitor on Linux. A monitor thread asks the
+// frontend queue to hold until the machine has been idle for the configured minutes
+// and, when the user comes back, pauses the queue and suspends the running FFmpeg
+// processes so the encode stops competing with interactive work.
+
+use serde::{Deserialize, Serialize};
+
+/// How often the idle time is checked; short so a returning user gets the CPU back quickly
+const IDLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct LastInputInfo {
+    cb_size: u32,
+    dw_time: u32,
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "user32")]
+extern "system" {
+    fn GetLastInputInfo(info: *mut LastInputInfo) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetTickCount() -> u32;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleTrigger {
+    pub enabled: bool,
+    /// Minutes without input before the queue may run
+    pub idle_minutes: u32,
+}
+
+impl Default for IdleTrigger {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 10,
+        }
+    }
+}
+
+/// Payload of the `idle-state-changed` event and result of get_idle_state
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleState {
+    pub enabled: bool,
+    /// None when the idle time cannot be read here (the queue is then never held)
+    pub idle_seconds: Option<u64>,
+    /// Renders may run: the trigger is off, the machine is idle, or idle time is unknown
+    pub idle: bool,
+    /// The user is back: the frontend should hold its queue
+    pub pause_queue: bool,
+    /// The machine went idle; a queue held for the user may continue
+    pub resume_queue: bool,
+}
+
+/// `ioreg -c IOHIDSystem` line `"HIDIdleTime" = 123456789` (nanoseconds)
+#[cfg(any(target_os = "macos", test))]
+fn parse_hid_idle_time(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|nanos| nanos / 1_000_000_000)
+}
+
+/// GNOME IdleMonitor GetIdletime reply `(uint64 12345,)` (milliseconds)
+#[cfg(any(all(unix, not(target_os = "macos")), test))]
+fn parse_gdbus_idle_time(output: &str) -> Option<u64> {
+    output
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim_end_matches(',')
+        .trim_start_matches("uint64")
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|millis| millis / 1000)
+}
+
+/// Seconds since the last keyboard or mouse input, None when it cannot be read
+pub fn read_idle_seconds() -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut info = LastInputInfo {
+            cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+            dw_time: 0,
+        };
+        // SAFETY: `info` is a properly laid out LASTINPUTINFO with cbSize set
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // Both tick counts wrap after 49.7 days
+        let now = unsafe { GetTickCount() };
+        Some(u64::from(now.wrapping_sub(info.dw_time)) / 1000)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::proc::run_captured(
+            crate::proc::command("ioreg").args(["-c", "IOHIDSystem", "-d", "4"]),
+            &crate::proc::RunOptions::with_timeout(10),
+        )
+        .ok()
+        .and_then(|o| parse_hid_idle_time(&o.stdout))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let run = |program: &str, args: &[&str]| {
+            crate::proc::run_captured(
+                crate::proc::command(program).args(args),
+                &crate::proc::RunOptions::with_timeout(10),
+            )
+            .ok()
+            .filter(|o| o.success)
+            .map(|o| o.stdout)
+        };
+        // X11 (xprintidle prints milliseconds), then GNOME, which also covers Wayland
+        run("xprintidle", &[])
+            .and_then(|out| out.trim().parse::<u64>().ok())
+            .map(|millis| millis / 1000)
+            .or_else(|| {
+                run(
+                    "gdbus",
+                    &[
+                        "call",
+                        "--session",
+                        "--dest",
+                        "org.gnome.Mutter.IdleMonitor",
+                        "--object-path",
+                        "/org/gnome/Mutter/IdleMonitor/Core",
+                        "--method",
+                        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+                    ],
+                )
+                .and_then(|out| parse_gdbus_idle_time(&out))
+            })
+    }
+}
+
+impl IdleTrigger {
+    /// Whether renders may run after `idle_seconds` without input
+    pub fn allows(&self, idle_seconds: Option<u64>) -> bool {
+        match idle_seconds {
+            Some(seconds) if self.enabled => seconds >= u64::from(self.idle_minutes) * 60,
+            _ => true,
+        }
+    }
+}
+
+fn load_trigger() -> IdleTrigger {
+    crate::load_settings()
+        .map(|settings| settings.idle_trigger)
+        .unwrap_or_default()
+}
+
+fn current_state(trigger: &IdleTrigger) -> IdleState {
+    let idle_seconds = if trigger.enabled {
+        read_idle_seconds()
+    } else {
+        None
+    };
+    IdleState {
+        enabled: trigger.enabled,
+        idle_seconds,
+        idle: trigger.allows(idle_seconds),
+        pause_queue: false,
+        resume_queue: false,
+    }
+}
+
+/// Current idle state, e.g. for the frontend to hold a queue started while the user is active
+#[tauri::command]
+pub fn get_idle_state() -> IdleState {
+    current_state(&load_trigger())
+}
+
+/// Watch the idle time and emit `idle-state-changed` when the machine goes idle or the
+/// user comes back
+pub fn start_idle_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_idle: Option<bool> = None;
+        loop {
+            let trigger = load_trigger();
+            let mut state = current_state(&trigger);
+            if last_idle != Some(state.idle) {
+                let returned = !state.idle && last_idle.is_some();
+                if returned {
+                    crate::shortcuts::suspend_renders();
+                }
+                state.pause_queue = !state.idle;
+                state.resume_queue = state.idle && last_idle.is_some();
+                let _ = crate::write_log(format!(
+                    "[IDLE] idle={} idle_seconds={:?} threshold={}min",
+                    state.idle, state.idle_seconds, trigger.idle_minutes
+                ));
+                let _ = tauri::Manager::emit_all(&app, "idle-state-changed", &state);
+                last_idle = Some(state.idle);
+            }
+            std::thread::sleep(IDLE_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let trigger = IdleTrigger {
+            enabled: true,
+            idle_minutes: 10,
+        };
+        assert!(!trigger.allows(Some(30)));
+        assert!(trigger.allows(Some(600)));
+        // Unknown idle time never holds the queue
+        assert!(trigger.allows(None));
+        assert!(IdleTrigger::default().allows(Some(0)));
+    }
+
+    #[test]
+    fn test_parse_idle_outputs() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 75000000000\n    | |   \"HIDParameters\" = {}\n";
+        assert_eq!(parse_hid_idle_time(ioreg), Some(75));
+        assert_eq!(parse_gdbus_idle_time("(uint64 125400,)\n"), Some(125));
+        assert_eq!(parse_gdbus_idle_time("Error: no such name"), None);
+    }
+}
@@ -0,0 +1,243 @@
+// Media library scan
+// Data layer of the library tab: scan_folder probes every video of a folder with a
+// bounded pool of ffprobe workers and reports codec, resolution, bitrate and size, plus
+// an estimate of how much a re-encode would save. Probes are cached (media_cache.rs),
+// so re-opening a folder is quick. Large libraries report progress through
+// `library-scan-progress` events.
+
+use crate::units;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+
+/// Bits per pixel and frame of a good-quality HEVC encode, the target of the estimate
+const TARGET_BITS_PER_PIXEL: f64 = 0.06;
+/// Audio bitrate assumed for the re-encode
+const TARGET_AUDIO_KBPS: f64 = 128.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PotentialLevel {
+    High,
+    Medium,
+    Low,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressionPotential {
+    pub level: PotentialLevel,
+    pub estimated_size: units::ByteSize,
+    /// Share of the current size a re-encode would save, 0-100
+    pub savings_percent: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryFile {
+    pub path: String,
+    pub size: units::ByteSize,
+    pub duration: units::Duration,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    /// Overall bitrate in kbit/s
+    pub bitrate_kbps: Option<u64>,
+    pub potential: Option<CompressionPotential>,
+    /// Why the file could not be probed
+    pub error: Option<String>,
+}
+
+/// Payload of `library-scan-progress` and `folder-import-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanProgress {
+    pub scanned: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+/// "30000/1001" -> 29.97
+pub fn parse_frame_rate(rate: &str) -> f64 {
+    match rate.split_once('/') {
+        Some((num, den)) => {
+            let (num, den) = (
+                num.parse::<f64>().unwrap_or(0.0),
+                den.parse::<f64>().unwrap_or(0.0),
+            );
+            if den > 0.0 {
+                num / den
+            } else {
+                0.0
+            }
+        }
+        None => rate.parse().unwrap_or(0.0),
+    }
+}
+
+/// Fill a LibraryFile from `ffprobe -of json` output
+fn parse_probe(file: &mut LibraryFile, json: &serde_json::Value) {
+    let format = &json["format"];
+    file.duration = format["duration"]
+        .as_str()
+        .and_then(units::Duration::parse)
+        .unwrap_or_default();
+    file.bitrate_kbps = format["bit_rate"]
+        .as_str()
+        .and_then(|rate| rate.parse::<u64>().ok())
+        .map(|bps| bps / 1000);
+
+    let streams = json["streams"].as_array().cloned().unwrap_or_default();
+    let stream_of = |kind: &str| streams.iter().find(|s| s["codec_type"] == kind);
+    if let Some(video) = stream_of("video") {
+        file.video_codec = video["codec_name"].as_str().map(String::from);
+        file.width = video["width"].as_u64().unwrap_or(0) as u32;
+        file.height = video["height"].as_u64().unwrap_or(0) as u32;
+        file.fps = parse_frame_rate(video["avg_frame_rate"].as_str().unwrap_or_default());
+    }
+    if let Some(audio) = stream_of("audio") {
+        file.audio_codec = audio["codec_name"].as_str().map(String::from);
+    }
+}
+
+/// Expected size of a re-encode at TARGET_BITS_PER_PIXEL, compared with the file
+fn estimate_potential(file: &LibraryFile) -> Option<CompressionPotential> {
+    let seconds = file.duration.as_secs_f64();
+    if file.width == 0 || file.height == 0 || seconds <= 0.0 || file.size.bytes() == 0 {
+        return None;
+    }
+    // Variable frame rate files can report absurd averages
+    let fps = if file.fps > 0.0 {
+        file.fps.min(120.0)
+    } else {
+        30.0
+    };
+    let video_kbps =
+        f64::from(file.width) * f64::from(file.height) * fps * TARGET_BITS_PER_PIXEL / 1000.0;
+    let audio_kbps = if file.audio_codec.is_some() {
+        TARGET_AUDIO_KBPS
+    } else {
+        0.0
+    };
+    let estimated_bytes = (video_kbps + audio_kbps) * 1000.0 / 8.0 * seconds;
+
+    let current = file.size.bytes() as f64;
+    let savings_percent = ((1.0 - estimated_bytes / current) * 100.0).clamp(0.0, 100.0);
+    let level = match savings_percent {
+        p if p >= 50.0 => PotentialLevel::High,
+        p if p >= 25.0 => PotentialLevel::Medium,
+        p if p >= 10.0 => PotentialLevel::Low,
+        _ => PotentialLevel::None,
+    };
+    Some(CompressionPotential {
+        level,
+        estimated_size: units::ByteSize::from_bytes(estimated_bytes.min(current) as u64),
+        savings_percent,
+    })
+}
+
+pub fn probe_file(ffprobe_path: &str, path: &str) -> LibraryFile {
+    let mut file = LibraryFile {
+        path: path.to_string(),
+        size: units::ByteSize::from_bytes(std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)),
+        ..Default::default()
+    };
+    match crate::media_cache::probe(ffprobe_path, path) {
+        Ok(json) => {
+            parse_probe(&mut file, &json);
+            file.potential = estimate_potential(&file);
+        }
+        Err(e) => file.error = Some(e),
+    }
+    file
+}
+
+/// Probe every video of `path` (subfolders too when `recursive`), sorted by path.
+/// The folderExcludePatterns setting applies as for folders given on the command line.
+#[tauri::command]
+pub async fn scan_folder(
+    window: tauri::Window,
+    path: String,
+    recursive: bool,
+) -> Result<Vec<LibraryFile>, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
+    let folder = Path::new(&path);
+    if !folder.is_dir() {
+        return Err(format!("Not a folder: {}", path));
+    }
+    let max_depth = if recursive { usize::MAX } else { 1 };
+    let paths = crate::cli::videos_in(
+        folder,
+        &crate::cli::FolderFilter::from_settings(),
+        max_depth,
+    );
+    let total = paths.len();
+
+    let mut files: Vec<LibraryFile> = Vec::with_capacity(total);
+    let mut throttle = crate::progress::Throttle::new(crate::progress::PROGRESS_INTERVAL);
+    crate::media_cache::probe_all(
+        &paths,
+        |path| probe_file(&config.ffprobe_path, path),
+        |file| {
+            let progress = ScanProgress {
+                scanned: files.len() + 1,
+                total,
+                current: file.path.clone(),
+            };
+            if let Some(progress) = throttle.offer(progress, Instant::now()) {
+                let _ = window.emit("library-scan-progress", &progress);
+            }
+            files.push(file);
+        },
+    );
+    if let Some(progress) = throttle.flush() {
+        let _ = window.emit("library-scan-progress", &progress);
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let _ = crate::write_log(format!(
+        "[LIBRARY] Scanned {} video(s) in {} ({} unreadable)",
+        files.len(),
+        path,
+        files.iter().filter(|f| f.error.is_some()).count()
+    ));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_probe_and_potential() {
+        let json = serde_json::json!({
+            "streams": [
+                {"codec_type": "video", "codec_name": "h264", "width": 1920, "height": 1080, "avg_frame_rate": "30000/1001"},
+                {"codec_type": "audio", "codec_name": "aac"}
+            ],
+            "format": {"duration": "60.000000", "bit_rate": "20000000"}
+        });
+        let mut file = LibraryFile {
+            size: units::ByteSize::from_bytes(150_000_000),
+            ..Default::default()
+        };
+        parse_probe(&mut file, &json);
+        assert_eq!(file.video_codec.as_deref(), Some("h264"));
+        assert_eq!(file.audio_codec.as_deref(), Some("aac"));
+        assert_eq!((file.width, file.height), (1920, 1080));
+        assert!((file.fps - 29.97).abs() < 0.01);
+        assert_eq!(file.bitrate_kbps, Some(20_000));
+
+        // 20 Mbit/s 1080p compresses well; the same minute at 3 Mbit/s hardly
+        let potential = estimate_potential(&file).unwrap();
+        assert_eq!(potential.level, PotentialLevel::High);
+        file.size = units::ByteSize::from_bytes(22_500_000);
+        assert_eq!(
+            estimate_potential(&file).unwrap().level,
+            PotentialLevel::None
+        );
+    }
+}
@@ -3,7 +3,6 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use tauri::Manager;
@@ -18,6 +17,150 @@ use winreg::RegKey;
 mod process_manager;
 use process_manager::PROCESS_MANAGER;
 
+// Encoder introspection module
+mod encoders;
+
+// Preset/settings snapshot module
+mod backups;
+
+// Failed input quarantine module
+mod quarantine;
+
+// Codec/container compatibility module
+mod codec_compat;
+// Encode argument builder module
+mod encode_args;
+// Subprocess runner module
+mod proc;
+// Typed duration / byte size module
+mod units;
+// GPU capability detection module
+mod gpu;
+// Power management module
+mod power;
+// Render queue state module
+mod queue;
+// Render resource telemetry module
+mod resource_monitor;
+// CPU temperature / overheat guard module
+mod thermal;
+// Encoder benchmark module
+mod benchmark;
+// Output verification module
+mod verify;
+// File checksum module
+mod hash;
+// Recycle-bin handling of rendered sources module
+mod recycle;
+// Output path template module
+mod output_path;
+// Source file lock / growth check module
+mod file_access;
+// Network share detection / local staging module
+mod network_paths;
+// Single-instance / file hand-off module
+mod single_instance;
+// Command line argument module
+mod cli;
+// UAC-elevated context menu registration module
+mod elevation;
+
+// Linux / macOS file manager integration module
+mod file_manager;
+
+// szhimatar:// URL protocol module
+mod url_protocol;
+
+// System tray module
+mod tray;
+
+// OS notification module
+mod notifications;
+
+// Start with system module
+mod autostart;
+
+// Global keyboard shortcut module
+mod shortcuts;
+
+// OS theme module
+mod system_theme;
+
+// Hardware detection module
+mod hardware;
+
+// System diagnostics module
+mod system_info;
+
+// Crash report module
+mod crash;
+// Orphaned process cleanup module
+mod orphans;
+// Graceful shutdown module
+mod shutdown;
+// Render progress throttling module
+mod progress;
+// Job history module
+mod history;
+// Interrupted job resume module
+mod resume;
+// Duplicate job detection module
+mod duplicates;
+// Media library scan module
+mod library;
+// Media info cache module
+mod media_cache;
+// FFmpeg installations module
+mod ffmpeg_versions;
+// FFmpeg feature check module
+mod ffmpeg_features;
+// FFmpeg deep search module
+mod ffmpeg_search;
+// Update check module
+mod updater;
+// Update rollback module
+mod update_rollback;
+// Network settings module
+mod network;
+// Portable mode module
+mod portable;
+// Job phases module
+mod phases;
+// Source analysis module
+mod analysis;
+// Per-title complexity module
+mod complexity;
+// Quality ladder module
+mod quality_ladder;
+// Comparison clip module
+mod comparison;
+// Queue schedule module
+mod schedule;
+// Idle trigger module
+mod idle;
+// Render error classification module
+mod render_errors;
+// Local HTTP API module
+mod http_api;
+// Webhook notifications module
+mod webhooks;
+// Post-render upload module
+mod uploads;
+// URL import module
+mod url_import;
+// External tool hooks module
+mod hooks;
+// Plugin presets module
+mod plugins;
+// Frame export module
+mod frames;
+// Video stabilization module
+mod stabilize;
+// Color adjustment (LUT / eq) module
+mod color;
+// Folder import module
+mod folder_import;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Settings {
     theme: String,
@@ -27,6 +170,18 @@ struct Settings {
     ffmpeg_path: String,
     ffprobe_path: String,
     output_suffix: String,
+    /// Output naming template, see output_path::TEMPLATE_PLACEHOLDERS ({suffix} is output_suffix)
+    #[serde(rename = "outputTemplate", default = "default_output_template")]
+    output_template: String,
+    /// Global output folder; empty writes next to the sources
+    #[serde(rename = "outputDirectory", default)]
+    output_directory: String,
+    /// Recreate the source folder structure beneath output_directory
+    #[serde(rename = "mirrorSourceFolders", default)]
+    mirror_source_folders: bool,
+    /// Render network sources/outputs through a local temp copy
+    #[serde(rename = "stageNetworkFiles", default)]
+    stage_network_files: bool,
     #[serde(default)]
     use_background_image: bool,
     #[serde(default)]
@@ -35,6 +190,7 @@ struct Settings {
     glass_opacity: f32,
     #[serde(default = "default_glass_blur", rename = "glassBlur")]
     glass_blur: f32,
+    /// One of encode_args::SUPPORTED_VIDEO_CODECS (h264 | h265 | hevc | av1 | vp9)
     default_video_codec: String,
     default_audio_codec: String,
     #[serde(rename = "gpuAvailable")]
@@ -45,12 +201,82 @@ struct Settings {
     screen_animation: String,
     #[serde(rename = "performanceMode", default)]
     performance_mode: bool,
+    /// Decode sources on the GPU (-hwaccel chosen per source codec and encoder)
+    #[serde(rename = "hwDecode", default)]
+    hw_decode: bool,
+    /// CPU priority renders start with (normal | below_normal | idle)
+    #[serde(rename = "renderPriority", default)]
+    render_priority: proc::Priority,
+    /// What to do on battery power (none | pause | power_saver)
+    #[serde(rename = "batteryAction", default)]
+    battery_action: power::BatteryAction,
+    /// Temperature limits checked during renders
+    #[serde(default)]
+    thermal: thermal::ThermalSettings,
+    /// Check finished renders (off | duration | decode)
+    #[serde(rename = "verifyOutput", default)]
+    verify_output: verify::VerifyMode,
+    /// Hash input and output of successful renders for the statistics manifest
+    #[serde(rename = "recordHashes", default)]
+    record_hashes: bool,
+    /// Move sources to the recycle bin once their output passed verification
+    #[serde(rename = "trashSourceAfterSuccess", default)]
+    trash_source_after_success: bool,
+    /// Move inputs of unattended jobs that keep failing into `_failed` (see quarantine.rs)
+    #[serde(rename = "quarantineFailedInputs", default)]
+    quarantine_failed_inputs: bool,
+    /// Wildcards skipped when a folder is queued (see cli::FolderFilter)
+    #[serde(rename = "folderExcludePatterns", default)]
+    folder_exclude_patterns: Vec<String>,
+    /// Toasts when renders and the queue finish
+    #[serde(default)]
+    notifications: notifications::NotificationSettings,
+    /// System-wide hotkeys (accelerators such as "CmdOrCtrl+Alt+P", empty = off)
+    #[serde(default)]
+    shortcuts: shortcuts::ShortcutSettings,
+    /// List crash reports not shown yet when the app starts
+    #[serde(rename = "showCrashReports", default)]
+    show_crash_reports: bool,
+    /// How long quitting waits for running renders to finalize their outputs
+    #[serde(rename = "shutdownTimeoutSecs", default = "default_shutdown_timeout_secs")]
+    shutdown_timeout_secs: u64,
+    /// Releases offered by the update check (stable | beta)
+    #[serde(rename = "updateChannel", default)]
+    update_channel: updater::UpdateChannel,
+    /// Proxy and CA certificate for update checks and downloads
+    #[serde(default)]
+    network: network::NetworkSettings,
+    /// Start time or daily window for renders
+    #[serde(rename = "queueSchedule", default)]
+    queue_schedule: schedule::QueueSchedule,
+    /// Run renders only while the machine is idle
+    #[serde(rename = "idleTrigger", default)]
+    idle_trigger: idle::IdleTrigger,
+    /// Fail renders running longer than this many minutes (0 = no limit)
+    #[serde(rename = "jobTimeoutMinutes", default)]
+    job_timeout_minutes: u32,
+    /// Opt-in local REST/WebSocket server for remote control
+    #[serde(rename = "httpApi", default)]
+    http_api: http_api::ApiSettings,
+    /// URLs notified when jobs complete or fail and when the queue finishes
+    #[serde(default)]
+    webhooks: Vec<webhooks::Webhook>,
+    /// SFTP / S3 / WebDAV targets presets can upload their outputs to
+    #[serde(rename = "uploadDestinations", default)]
+    upload_destinations: Vec<uploads::UploadDestination>,
+    /// Scripts run before and after each render
+    #[serde(default)]
+    hooks: hooks::HookSettings,
 }
 
 fn default_screen_animation() -> String {
     "default".to_string()
 }
 
+fn default_output_template() -> String {
+    output_path::DEFAULT_OUTPUT_TEMPLATE.to_string()
+}
+
 fn default_glass_opacity() -> f32 {
     0.15
 }
@@ -59,6 +285,10 @@ fn default_glass_blur() -> f32 {
     12.0
 }
 
+fn default_shutdown_timeout_secs() -> u64 {
+    10
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -68,6 +298,10 @@ impl Default for Settings {
             ffmpeg_path: "ffmpeg".to_string(),
             ffprobe_path: "ffprobe".to_string(),
             output_suffix: "_szhatoe".to_string(),
+            output_template: default_output_template(),
+            output_directory: String::new(),
+            mirror_source_folders: false,
+            stage_network_files: false,
             use_background_image: false,
             background_image_path: "".to_string(),
             glass_opacity: 0.15,
@@ -78,11 +312,37 @@ impl Default for Settings {
             render_mode: "cpu".to_string(),
             screen_animation: "default".to_string(),
             performance_mode: false,
+            hw_decode: false,
+            render_priority: proc::Priority::Normal,
+            battery_action: power::BatteryAction::None,
+            thermal: thermal::ThermalSettings::default(),
+            verify_output: verify::VerifyMode::Off,
+            record_hashes: false,
+            trash_source_after_success: false,
+            quarantine_failed_inputs: false,
+            folder_exclude_patterns: Vec::new(),
+            notifications: notifications::NotificationSettings::default(),
+            shortcuts: shortcuts::ShortcutSettings::default(),
+            show_crash_reports: false,
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            update_channel: updater::UpdateChannel::Stable,
+            network: network::NetworkSettings::default(),
+            queue_schedule: schedule::QueueSchedule::default(),
+            idle_trigger: idle::IdleTrigger::default(),
+            job_timeout_minutes: 0,
+            http_api: http_api::ApiSettings::default(),
+            webhooks: Vec::new(),
+            upload_destinations: Vec::new(),
+            hooks: hooks::HookSettings::default(),
         }
     }
 }
 
 fn get_app_data_dir() -> PathBuf {
+    // data/ beside the executable in portable mode
+    if let Some(dir) = portable::data_dir() {
+        return dir.to_path_buf();
+    }
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
     home.join(".szhimatar")
 }
@@ -100,6 +360,7 @@ fn ensure_app_dirs() -> Result<(), String> {
     fs::create_dir_all(&logs_dir).map_err(|e| e.to_string())?;
     fs::create_dir_all(&stats_dir).map_err(|e| e.to_string())?;
     fs::create_dir_all(&presets_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(plugins::get_plugins_dir()).map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -119,23 +380,44 @@ fn load_settings() -> Result<Settings, String> {
 #[tauri::command]
 fn save_settings(settings: Settings) -> Result<(), String> {
     let settings_path = get_app_data_dir().join("settings.json");
+
+    if !encode_args::SUPPORTED_VIDEO_CODECS.contains(&settings.default_video_codec.as_str()) {
+        return Err(format!(
+            "Unknown default video codec '{}' (expected one of: {})",
+            settings.default_video_codec,
+            encode_args::SUPPORTED_VIDEO_CODECS.join(", ")
+        ));
+    }
+
     let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
 
     fs::write(&settings_path, content).map_err(|e| e.to_string())
 }
 
-/// Check GPU (NVENC) compatibility and persist result in settings.json
+/// Check the GPU encoder (NVENC, Quick Sync on Intel, AMF / VAAPI on AMD) and persist
+/// the result in settings.json.
+/// Returns an actionable status (e.g. "driver too old") rather than a bare flag.
 /// WARNING: This can be overridden for UI testing, but actual FFmpeg rendering
 /// will still use real hardware capabilities
 #[tauri::command]
-fn check_gpu_compatibility() -> Result<bool, String> {
+fn check_gpu_compatibility() -> Result<gpu::GpuCompatibility, String> {
     // Check for override first (for UI testing only)
     if let Some(override_config) = load_hardware_override() {
         println!(
             "[HARDWARE OVERRIDE] GPU Available: {}",
             override_config.gpu_available
         );
-        return Ok(override_config.gpu_available);
+        return Ok(gpu::GpuCompatibility {
+            available: override_config.gpu_available,
+            status: if override_config.gpu_available {
+                gpu::GpuStatus::Ok
+            } else {
+                gpu::GpuStatus::NoGpu
+            },
+            driver_version: None,
+            required_driver: None,
+            message: "Hardware override enabled".to_string(),
+        });
     }
 
     let config = load_ffmpeg_config();
@@ -143,36 +425,21 @@ fn check_gpu_compatibility() -> Result<bool, String> {
         return Err("FFmpeg path not configured".to_string());
     }
 
-    // Run `ffmpeg -hide_banner -encoders` and search for nvenc encoders
-    #[cfg(target_os = "windows")]
-    let output = {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        Command::new(&config.ffmpeg_path)
-            .creation_flags(CREATE_NO_WINDOW)
-            .args(["-hide_banner", "-encoders"])
-            .output()
-            .map_err(|e| format!("Failed to run ffmpeg: {}", e))?
-    };
-
-    #[cfg(not(target_os = "windows"))]
-    let output = Command::new(&config.ffmpeg_path)
-        .args(["-hide_banner", "-encoders"])
-        .output()
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-    let gpu_available = stdout.contains("nvenc");
+    let result = gpu::diagnose_gpu(&config.ffmpeg_path)?;
+    let _ = write_log(format!(
+        "[GPU CHECK] status={:?} driver={:?} required={:?}: {}",
+        result.status, result.driver_version, result.required_driver, result.message
+    ));
 
     // Persist in settings
     let mut settings = load_settings().unwrap_or_default();
-    settings.gpu_available = gpu_available;
+    settings.gpu_available = result.available;
     let _ = save_settings(settings);
 
-    Ok(gpu_available)
+    Ok(result)
 }
 
-/// Detect hardware information (CPU and GPU vendors)
+/// Detect hardware information (CPU and GPU vendors, models and core counts)
 #[tauri::command]
 fn detect_hardware_info() -> Result<HardwareInfo, String> {
     // Check for override first (for testing UI only)
@@ -184,28 +451,46 @@ fn detect_hardware_info() -> Result<HardwareInfo, String> {
         return Ok(HardwareInfo {
             cpu_vendor: override_config.cpu_vendor,
             gpu_vendor: override_config.gpu_vendor,
+            ..Default::default()
         });
     }
 
     // Use real hardware detection
-    let (cpu_vendor, cpu_reason) = detect_cpu_vendor();
-    let (gpu_vendor, gpu_reason) = detect_gpu_vendor();
+    let cpu = hardware::cpu_info();
+    let gpu_models = hardware::display_adapters().unwrap_or_else(|e| {
+        let _ = write_log(format!("[HW DETECT] Failed to list display adapters: {}", e));
+        Vec::new()
+    });
+    let gpu_vendor = hardware::gpu_vendor(&gpu_models).to_string();
 
     let _ = write_log(format!(
-        "[HW DETECT] Result -> CPU vendor='{}' ({}) | GPU vendor='{}' ({})",
-        cpu_vendor, cpu_reason, gpu_vendor, gpu_reason
+        "[HW DETECT] Result -> CPU vendor='{}' model={:?} cores={:?}/{} | GPU vendor='{}' adapters={:?}",
+        cpu.vendor, cpu.model, cpu.physical_cores, cpu.logical_cores, gpu_vendor, gpu_models
     ));
 
     Ok(HardwareInfo {
-        cpu_vendor,
+        cpu_vendor: cpu.vendor,
         gpu_vendor,
+        cpu_model: cpu.model,
+        physical_cores: cpu.physical_cores,
+        logical_cores: cpu.logical_cores,
+        gpu_models,
     })
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Default, serde::Serialize, serde::Deserialize)]
 struct HardwareInfo {
     cpu_vendor: String,
     gpu_vendor: String,
+    #[serde(default)]
+    cpu_model: Option<String>,
+    #[serde(default)]
+    physical_cores: Option<usize>,
+    #[serde(default)]
+    logical_cores: usize,
+    /// Every display adapter, e.g. an Intel iGPU next to the NVIDIA card
+    #[serde(default)]
+    gpu_models: Vec<String>,
 }
 
 /// Hardware override configuration for testing (DOES NOT affect actual rendering)
@@ -242,193 +527,6 @@ fn load_hardware_override() -> Option<HardwareOverride> {
     }
 }
 
-fn summarize_for_log(input: &str, max_len: usize) -> String {
-    let compact = input.split_whitespace().collect::<Vec<_>>().join(" ");
-    if compact.len() <= max_len {
-        compact
-    } else {
-        format!("{}...", &compact[..max_len])
-    }
-}
-
-fn detect_cpu_vendor() -> (String, String) {
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-        // Use WMIC to get CPU info
-        let output = Command::new("wmic")
-            .creation_flags(CREATE_NO_WINDOW)
-            .args(["cpu", "get", "name"])
-            .output();
-
-        if let Ok(output) = output {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-            if stdout.contains("intel") {
-                return (
-                    "intel".to_string(),
-                    "wmic cpu get name output contains 'intel'".to_string(),
-                );
-            } else if stdout.contains("amd") {
-                return (
-                    "amd".to_string(),
-                    "wmic cpu get name output contains 'amd'".to_string(),
-                );
-            }
-
-            return (
-                "unknown".to_string(),
-                format!(
-                    "wmic cpu get name did not match known vendor (status={}) output='{}'",
-                    output.status,
-                    summarize_for_log(&stdout, 180)
-                ),
-            );
-        }
-
-        return (
-            "unknown".to_string(),
-            format!(
-                "wmic cpu get name failed: {}",
-                output
-                    .err()
-                    .map(|e| e.to_string())
-                    .unwrap_or_else(|| "unknown error".to_string())
-            ),
-        );
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        if let Ok(content) = fs::read_to_string("/proc/cpuinfo") {
-            let lower = content.to_lowercase();
-            if lower.contains("intel") {
-                return ("intel".to_string(), "cpuinfo contains 'intel'".to_string());
-            } else if lower.contains("amd") {
-                return ("amd".to_string(), "cpuinfo contains 'amd'".to_string());
-            }
-
-            return (
-                "unknown".to_string(),
-                format!(
-                    "/proc/cpuinfo read but vendor not matched: '{}'",
-                    summarize_for_log(&lower, 180)
-                ),
-            );
-        }
-
-        return (
-            "unknown".to_string(),
-            "failed to read /proc/cpuinfo".to_string(),
-        );
-    }
-
-    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
-    {
-        return ("unknown".to_string(), "platform fallback".to_string());
-    }
-
-    #[allow(unreachable_code)]
-    ("unknown".to_string(), "platform fallback".to_string())
-}
-
-fn detect_gpu_vendor() -> (String, String) {
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-        // Use WMIC to get GPU info
-        let output = Command::new("wmic")
-            .creation_flags(CREATE_NO_WINDOW)
-            .args(["path", "win32_videocontroller", "get", "name"])
-            .output();
-
-        if let Ok(output) = output {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-            if stdout.contains("nvidia")
-                || stdout.contains("geforce")
-                || stdout.contains("rtx")
-                || stdout.contains("gtx")
-            {
-                return (
-                    "nvidia".to_string(),
-                    "wmic win32_videocontroller output matched nvidia/geforce/rtx/gtx".to_string(),
-                );
-            } else if stdout.contains("amd") || stdout.contains("radeon") {
-                return (
-                    "amd".to_string(),
-                    "wmic win32_videocontroller output matched amd/radeon".to_string(),
-                );
-            } else if stdout.contains("intel") {
-                return (
-                    "intel".to_string(),
-                    "wmic win32_videocontroller output matched intel".to_string(),
-                );
-            }
-
-            return (
-                "unknown".to_string(),
-                format!(
-                    "wmic win32_videocontroller did not match known vendor (status={}) output='{}'",
-                    output.status,
-                    summarize_for_log(&stdout, 220)
-                ),
-            );
-        }
-
-        return (
-            "unknown".to_string(),
-            format!(
-                "wmic win32_videocontroller failed: {}",
-                output
-                    .err()
-                    .map(|e| e.to_string())
-                    .unwrap_or_else(|| "unknown error".to_string())
-            ),
-        );
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        let output = Command::new("lspci").output();
-
-        if let Ok(output) = output {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
-            if stdout.contains("nvidia") {
-                return (
-                    "nvidia".to_string(),
-                    "lspci output matched nvidia".to_string(),
-                );
-            } else if stdout.contains("amd") || stdout.contains("radeon") {
-                return (
-                    "amd".to_string(),
-                    "lspci output matched amd/radeon".to_string(),
-                );
-            }
-
-            return (
-                "unknown".to_string(),
-                format!(
-                    "lspci read but vendor not matched: '{}'",
-                    summarize_for_log(&stdout, 220)
-                ),
-            );
-        }
-
-        return ("unknown".to_string(), "lspci command failed".to_string());
-    }
-
-    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
-    {
-        return ("unknown".to_string(), "platform fallback".to_string());
-    }
-
-    #[allow(unreachable_code)]
-    ("unknown".to_string(), "platform fallback".to_string())
-}
-
 /// Save render mode to settings
 #[tauri::command]
 fn save_render_mode(mode: String) -> Result<(), String> {
@@ -708,19 +806,18 @@ fn save_ffmpeg_config(config: &FfmpegConfig) -> Result<(), String> {
 
 /// Get version string from binary by running it with -version
 fn get_binary_version_internal(path: &str) -> Option<String> {
-    Command::new(path)
-        .arg("-version")
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout)
-                    .ok()
-                    .and_then(|s| s.lines().next().map(|l| l.to_string()))
-            } else {
-                None
-            }
-        })
+    proc::run_captured(
+        proc::command(path).arg("-version"),
+        &proc::RunOptions::with_timeout(10),
+    )
+    .ok()
+    .and_then(|output| {
+        if output.success {
+            output.stdout.lines().next().map(|l| l.to_string())
+        } else {
+            None
+        }
+    })
 }
 
 /// Search for binary in PATH using 'where' (Windows) or 'which' (Unix)
@@ -734,15 +831,17 @@ fn find_binary_in_path(binary_name: &str) -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     {
         // Use 'where' command on Windows
-        if let Ok(output) = Command::new("where").arg(&exe_name).output() {
-            if output.status.success() {
-                if let Ok(result) = String::from_utf8(output.stdout) {
-                    // 'where' returns multiple paths, take first one
-                    if let Some(first_line) = result.lines().next() {
-                        let path = PathBuf::from(first_line.trim());
-                        if path.exists() {
-                            return path.canonicalize().ok();
-                        }
+        let output = proc::run_captured(
+            proc::command("where").arg(&exe_name),
+            &proc::RunOptions::with_timeout(10),
+        );
+        if let Ok(output) = output {
+            if output.success {
+                // 'where' returns multiple paths, take first one
+                if let Some(first_line) = output.stdout.lines().next() {
+                    let path = PathBuf::from(first_line.trim());
+                    if path.exists() {
+                        return path.canonicalize().ok();
                     }
                 }
             }
@@ -752,13 +851,15 @@ fn find_binary_in_path(binary_name: &str) -> Option<PathBuf> {
     #[cfg(not(target_os = "windows"))]
     {
         // Use 'which' command on Unix-like systems
-        if let Ok(output) = Command::new("which").arg(binary_name).output() {
-            if output.status.success() {
-                if let Ok(result) = String::from_utf8(output.stdout) {
-                    let path = PathBuf::from(result.trim());
-                    if path.exists() {
-                        return path.canonicalize().ok();
-                    }
+        let output = proc::run_captured(
+            proc::command("which").arg(binary_name),
+            &proc::RunOptions::with_timeout(10),
+        );
+        if let Ok(output) = output {
+            if output.success {
+                let path = PathBuf::from(output.stdout.trim());
+                if path.exists() {
+                    return path.canonicalize().ok();
                 }
             }
         }
@@ -786,7 +887,8 @@ fn find_next_to_app(binary_name: &str) -> Option<PathBuf> {
     None
 }
 
-/// Search standard directories (fast search)
+/// Search standard directories, package manager locations and (Windows) the App Paths
+/// registry (fast search)
 fn search_standard_dirs(binary_name: &str) -> Option<PathBuf> {
     let exe_name = if cfg!(windows) {
         format!("{}.exe", binary_name)
@@ -794,7 +896,13 @@ fn search_standard_dirs(binary_name: &str) -> Option<PathBuf> {
         binary_name.to_string()
     };
 
-    let standard_dirs = if cfg!(windows) {
+    if let Some(path) = ffmpeg_search::app_paths_entry(&exe_name) {
+        if get_binary_version_internal(&path.to_string_lossy()).is_some() {
+            return Some(path);
+        }
+    }
+
+    let mut standard_dirs = if cfg!(windows) {
         vec![
             PathBuf::from("C:\\ffmpeg\\bin"),
             PathBuf::from("C:\\ffmpeg"),
@@ -815,6 +923,7 @@ fn search_standard_dirs(binary_name: &str) -> Option<PathBuf> {
             dirs::home_dir().unwrap_or_default().join("ffmpeg"),
         ]
     };
+    standard_dirs.extend(ffmpeg_search::package_manager_dirs());
 
     for dir in standard_dirs {
         if !dir.exists() {
@@ -992,65 +1101,6 @@ fn check_ffmpeg_status() -> Result<FfmpegStatus, String> {
     Ok(status)
 }
 
-/// Deep recursive search (slow, last resort) - kept for compatibility
-fn deep_search(binary_name: &str, window: tauri::Window) -> Option<String> {
-    let exe_name = if cfg!(windows) {
-        format!("{}.exe", binary_name)
-    } else {
-        binary_name.to_string()
-    };
-
-    let search_roots = if cfg!(windows) {
-        vec![PathBuf::from("C:\\")]
-    } else {
-        vec![PathBuf::from("/")]
-    };
-
-    for root in search_roots {
-        let walker = WalkDir::new(&root)
-            .max_depth(10)
-            .follow_links(false)
-            .into_iter()
-            .filter_entry(|e| {
-                let path = e.path();
-                let path_str = path.to_string_lossy().to_lowercase();
-
-                if cfg!(windows) {
-                    if path_str.contains("windows\\winsxs")
-                        || path_str.contains("windows\\system32")
-                        || path_str.contains("$recycle.bin")
-                        || path_str.contains("system volume information")
-                    {
-                        return false;
-                    }
-                }
-
-                true
-            });
-
-        let mut checked_count = 0;
-        for entry in walker.filter_map(|e| e.ok()) {
-            let path = entry.path();
-
-            if path.file_name() == Some(std::ffi::OsStr::new(&exe_name)) {
-                if let Ok(abs_path) = path.canonicalize() {
-                    let path_str = abs_path.to_string_lossy().to_string();
-                    if get_binary_version_internal(&path_str).is_some() {
-                        return Some(path_str);
-                    }
-                }
-            }
-
-            checked_count += 1;
-            if checked_count % 100 == 0 {
-                let _ = window.emit("ffmpeg-search-progress", checked_count);
-            }
-        }
-    }
-
-    None
-}
-
 /// Fast search for FFmpeg - searches PATH and standard directories
 #[tauri::command]
 async fn search_ffmpeg_fast(window: tauri::Window) -> Result<FfmpegStatus, String> {
@@ -1081,7 +1131,9 @@ async fn search_ffmpeg_fast(window: tauri::Window) -> Result<FfmpegStatus, Strin
     check_ffmpeg_status()
 }
 
-/// Deep search (kept for compatibility with existing UI)
+/// Deep search of all local drives for the binaries the fast search missed. Runs on a
+/// blocking thread and can be stopped with cancel_ffmpeg_search; paths found until then
+/// are saved.
 #[tauri::command]
 async fn search_ffmpeg_deep(window: tauri::Window) -> Result<FfmpegStatus, String> {
     // First try fast search
@@ -1092,29 +1144,34 @@ async fn search_ffmpeg_deep(window: tauri::Window) -> Result<FfmpegStatus, Strin
     }
 
     // Deep search for missing binaries
-    let mut ffmpeg_path = fast_result.ffmpeg_path.clone();
-    let mut ffprobe_path = fast_result.ffprobe_path.clone();
-
+    let mut missing = Vec::new();
     if !fast_result.ffmpeg_found {
-        window
-            .emit("ffmpeg-search-stage", "Deep searching for ffmpeg...")
-            .ok();
-        if let Some(path) = deep_search("ffmpeg", window.clone()) {
-            ffmpeg_path = path;
-        }
+        missing.push("ffmpeg");
     }
-
     if !fast_result.ffprobe_found {
-        window
-            .emit("ffmpeg-search-stage", "Deep searching for ffprobe...")
-            .ok();
-        if let Some(path) = deep_search("ffprobe", window.clone()) {
-            ffprobe_path = path;
-        }
+        missing.push("ffprobe");
+    }
+    window
+        .emit(
+            "ffmpeg-search-stage",
+            format!("Deep searching for {}...", missing.join(" and ")),
+        )
+        .ok();
+    let search_window = window.clone();
+    let mut result = tokio::task::spawn_blocking(move || {
+        ffmpeg_search::deep_search(&missing, &search_window)
+    })
+    .await
+    .map_err(|e| format!("Deep search failed: {}", e))?;
+    if result.cancelled {
+        window.emit("ffmpeg-search-stage", "Search cancelled").ok();
     }
 
     // Save found paths
-    let _ = save_ffmpeg_paths(ffmpeg_path, ffprobe_path);
+    let _ = save_ffmpeg_paths(
+        result.found.remove("ffmpeg").unwrap_or(fast_result.ffmpeg_path),
+        result.found.remove("ffprobe").unwrap_or(fast_result.ffprobe_path),
+    );
 
     check_ffmpeg_status()
 }
@@ -1136,17 +1193,150 @@ fn set_ffmpeg_paths(ffmpeg_path: String, ffprobe_path: String) -> Result<FfmpegS
     check_ffmpeg_status()
 }
 
+/// Result of the startup revalidation of saved FFmpeg/FFprobe paths
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FfmpegRevalidation {
+    ffmpeg_ok: bool,
+    ffprobe_ok: bool,
+    repaired: bool,
+    ffmpeg_path: String,
+    ffprobe_path: String,
+}
+
+lazy_static::lazy_static! {
+    static ref FFMPEG_REVALIDATION: std::sync::Mutex<Option<FfmpegRevalidation>> =
+        std::sync::Mutex::new(None);
+}
+
+/// Check that saved binaries still run; rediscover and rewrite ffmpeg.json if they moved
+/// (e.g. a Homebrew upgrade changed the Cellar path). Empty paths are left to first-run setup.
+fn revalidate_ffmpeg_paths() -> FfmpegRevalidation {
+    let mut config = load_ffmpeg_config();
+    let mut repaired = false;
+
+    let mut check = |name: &str, path: &mut String| -> bool {
+        if path.is_empty() || get_binary_version_internal(path).is_some() {
+            return true;
+        }
+
+        match search_ffmpeg_single(name.to_string()) {
+            Ok(result) if result.found => {
+                let _ = write_log(format!(
+                    "[FFMPEG REVALIDATE] {} moved: '{}' -> '{}'",
+                    name, path, result.path
+                ));
+                *path = result.path;
+                repaired = true;
+                true
+            }
+            _ => {
+                let _ = write_log(format!(
+                    "[FFMPEG REVALIDATE] {} not found at '{}' and rediscovery failed",
+                    name, path
+                ));
+                false
+            }
+        }
+    };
+
+    let ffmpeg_ok = check("ffmpeg", &mut config.ffmpeg_path);
+    let ffprobe_ok = check("ffprobe", &mut config.ffprobe_path);
+
+    if repaired {
+        config.discovered_at = chrono::Utc::now().to_rfc3339();
+        let _ = save_ffmpeg_config(&config);
+    }
+
+    FfmpegRevalidation {
+        ffmpeg_ok,
+        ffprobe_ok,
+        repaired,
+        ffmpeg_path: config.ffmpeg_path,
+        ffprobe_path: config.ffprobe_path,
+    }
+}
+
+/// Get the result of the startup revalidation (None while it is still running)
+#[tauri::command]
+fn get_ffmpeg_revalidation_status() -> Option<FfmpegRevalidation> {
+    FFMPEG_REVALIDATION.lock().ok().and_then(|r| r.clone())
+}
+
 // ============================================================================
 // FFMPEG RENDERING COMMANDS
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct RenderJob {
     pub job_id: String,
     pub input_path: String,
     pub output_path: String,
     pub ffmpeg_args: Vec<String>,
-    pub duration_seconds: f64,
+    pub duration_seconds: units::Duration,
+    /// Optional user filtergraph appended to the video filter chain
+    #[serde(default)]
+    pub extra_filters: String,
+    /// Run an analysis pass first (libvpx-vp9, libx264, libx265, libaom-av1)
+    #[serde(default)]
+    pub two_pass: bool,
+    /// Options placed before -i (e.g. -hwaccel); filled from the hwDecode setting
+    #[serde(default)]
+    pub input_args: Vec<String>,
+    /// Limit FFmpeg to this many threads (None = all cores), e.g. half the cores
+    /// to keep the machine usable during long encodes
+    #[serde(default)]
+    pub cpu_threads: Option<u32>,
+    /// Also pin FFmpeg to the first `cpu_threads` cores
+    #[serde(default)]
+    pub cpu_affinity: bool,
+    /// CPU priority (None = the renderPriority setting)
+    #[serde(default)]
+    pub priority: Option<proc::Priority>,
+    /// Continue an interrupted render from its partial output
+    #[serde(default)]
+    pub resume: Option<resume::ResumePoint>,
+    /// Preset the job was queued with; selects its pinned FFmpeg installation
+    #[serde(default)]
+    pub preset_name: Option<String>,
+    /// Kill and fail the render when it runs longer than this, pauses included
+    /// (None = the jobTimeoutMinutes setting)
+    #[serde(default)]
+    pub max_duration_minutes: Option<u32>,
+    /// Upload the output here after a successful render (uploads::UploadDestination id)
+    #[serde(default)]
+    pub upload_destination: Option<String>,
+    /// Keep, mute, replace or mix in the audio (see encode_args::apply_audio_source)
+    #[serde(default)]
+    pub audio_source: encode_args::AudioSource,
+    /// Output plays this many times faster (None = 1.0); duration_seconds stays in
+    /// source time
+    #[serde(default)]
+    pub speed_factor: Option<f64>,
+    /// Stabilize shaky footage (a vidstab detect pass runs before the encode)
+    #[serde(default)]
+    pub stabilize: Option<stabilize::StabilizeOptions>,
+    /// LUT and brightness / contrast / saturation applied in the encode
+    #[serde(default)]
+    pub color: Option<color::ColorOptions>,
+    /// Rotation / flip and the handling of the source's rotation metadata
+    #[serde(default)]
+    pub orientation: Option<encode_args::Orientation>,
+    /// Resolution / frame rate caps, applied only when the source exceeds them
+    #[serde(default)]
+    pub limits: Option<encode_args::OutputLimits>,
+    /// Copy streams whose re-encode would gain nothing (see encode_args::apply_smart_copy)
+    #[serde(default)]
+    pub smart_copy: bool,
+    /// Output pixel format / bit depth (None = whatever the args already set)
+    #[serde(default)]
+    pub pixel_format: Option<encode_args::PixelFormat>,
+    /// Copy AC3 / E-AC3 / DTS source tracks the output container can carry
+    #[serde(default)]
+    pub audio_passthrough: bool,
+    /// Queued without the user picking the file (folder import, HTTP API); only such
+    /// inputs are quarantined, and only with the quarantineFailedInputs setting
+    #[serde(default)]
+    pub unattended: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1155,11 +1345,23 @@ pub struct RenderProgress {
     pub frame: u64,
     pub fps: f64,
     pub bitrate: String,
-    pub total_size: String,
-    pub time_seconds: f64,
+    pub total_size: units::ByteSize,
+    pub time_seconds: units::Duration,
     pub speed: f64,
+    /// Moving average of the speed; the ETA is based on it
+    pub smoothed_speed: f64,
+    /// Weighted progress over all phases of the job
     pub progress_percent: f64,
-    pub eta_seconds: f64,
+    pub eta_seconds: units::Duration,
+    pub phase: phases::JobPhase,
+    /// Progress within the current phase
+    pub phase_percent: f64,
+    /// 1-based position of the phase and the number of phases of the job
+    pub phase_index: usize,
+    pub phase_count: usize,
+    pub progress_basis: progress::ProgressBasis,
+    /// Frames to encode when progress is frame based
+    pub total_frames: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1167,16 +1369,32 @@ pub struct RenderResult {
     pub job_id: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Classified cause of a failure (None when successful, stopped or unrecognized)
+    #[serde(default)]
+    pub error_kind: Option<render_errors::RenderErrorKind>,
     pub output_path: String,
+    /// CPU / GPU / memory averages over the render
+    pub resources: Option<resource_monitor::ResourceSummary>,
+    /// Problems found by output verification; the job completed with warnings
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// The source was moved to the recycle bin (trashSourceAfterSuccess)
+    #[serde(default)]
+    pub source_trashed: bool,
+    /// Streams the smart mode copied instead of re-encoding ("video", "audio")
+    #[serde(default)]
+    pub copied_streams: Vec<String>,
 }
 
 /// Parse FFmpeg progress line and extract metrics
-fn parse_ffmpeg_progress_line(line: &str) -> Option<(u64, f64, String, String, f64, f64)> {
+fn parse_ffmpeg_progress_line(
+    line: &str,
+) -> Option<(u64, f64, units::ByteSize, String, units::Duration, f64)> {
     // Example line: frame=  150 fps=30 q=28.0 size=    1024kB time=00:00:05.00 bitrate=1677.7kbits/s speed=2.5x
     let frame_re = regex::Regex::new(r"frame=\s*(\d+)").ok()?;
     let fps_re = regex::Regex::new(r"fps=\s*([\d.]+)").ok()?;
     let size_re = regex::Regex::new(r"size=\s*(\S+)").ok()?;
-    let time_re = regex::Regex::new(r"time=(\d+):(\d+):(\d+\.?\d*)").ok()?;
+    let time_re = regex::Regex::new(r"time=\s*(\S+)").ok()?;
     let bitrate_re = regex::Regex::new(r"bitrate=\s*(\S+)").ok()?;
     let speed_re = regex::Regex::new(r"speed=\s*([\d.]+)x").ok()?;
 
@@ -1192,7 +1410,7 @@ fn parse_ffmpeg_progress_line(line: &str) -> Option<(u64, f64, String, String, f
         .unwrap_or(0.0);
     let size = size_re
         .captures(line)
-        .and_then(|c| Some(c.get(1)?.as_str().to_string()))
+        .and_then(|c| units::ByteSize::parse(c.get(1)?.as_str()))
         .unwrap_or_default();
     let bitrate = bitrate_re
         .captures(line)
@@ -1203,72 +1421,181 @@ fn parse_ffmpeg_progress_line(line: &str) -> Option<(u64, f64, String, String, f
         .and_then(|c| c.get(1)?.as_str().parse::<f64>().ok())
         .unwrap_or(0.0);
 
-    let time_seconds = if let Some(caps) = time_re.captures(line) {
-        let hours: f64 = caps.get(1)?.as_str().parse().ok()?;
-        let minutes: f64 = caps.get(2)?.as_str().parse().ok()?;
-        let seconds: f64 = caps.get(3)?.as_str().parse().ok()?;
-        hours * 3600.0 + minutes * 60.0 + seconds
+    let time = time_re
+        .captures(line)
+        .and_then(|c| units::Duration::parse(c.get(1)?.as_str()))
+        .unwrap_or_default();
+
+    Some((frame, fps, size, bitrate, time, speed))
+}
+
+/// Synthetic lavfi source for check_filtergraph: the probed size, frame rate and pixel
+/// format of the render's input, so filters that depend on them (crop, scale
+/// expressions, 10-bit formats) fail here and not mid-encode
+fn filtergraph_test_source(probe: Option<&serde_json::Value>) -> String {
+    let source = probe.and_then(encode_args::source_video);
+    let (width, height) = source.as_ref().map_or((320, 240), |s| (s.width, s.height));
+    let rate = source.as_ref().map(|s| s.fps).filter(|fps| *fps > 0.0).unwrap_or(25.0);
+    let mut lavfi = format!("testsrc=size={}x{}:rate={}:duration=0.2", width, height, rate);
+    if let Some(pix_fmt) = probe.and_then(encode_args::source_pix_fmt) {
+        lavfi.push_str(&format!(",format={}", pix_fmt));
+    }
+    lavfi
+}
+
+/// Syntax-check a filtergraph by running it against a tiny synthetic source shaped
+/// like the render's input (see filtergraph_test_source).
+/// Returns FFmpeg's parse errors so they surface before a long encode starts.
+fn check_filtergraph(
+    ffmpeg_path: &str,
+    graph: &str,
+    probe: Option<&serde_json::Value>,
+) -> Result<(), String> {
+    let source = filtergraph_test_source(probe);
+    let args = [
+        "-hide_banner",
+        "-v",
+        "error",
+        "-f",
+        "lavfi",
+        "-i",
+        source.as_str(),
+        "-filter_complex",
+        graph,
+        "-f",
+        "null",
+        "-",
+    ];
+
+    let output = proc::run_captured(
+        proc::command(ffmpeg_path).args(args),
+        &proc::RunOptions::with_timeout(15).label("filtergraph check"),
+    )
+    .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if output.success {
+        return Ok(());
+    }
+
+    let stderr = output.stderr.trim().to_string();
+    if output.timed_out {
+        Err("Filtergraph check timed out".to_string())
+    } else if stderr.is_empty() {
+        Err(format!("Invalid filtergraph (exit code {:?})", output.code))
     } else {
-        0.0
-    };
+        Err(stderr)
+    }
+}
+
+/// Append user filters to the existing -vf chain (or add one)
+fn apply_extra_filters(args: &mut Vec<String>, extra_filters: &str) -> Result<(), String> {
+    let copies_video = args
+        .windows(2)
+        .any(|w| (w[0] == "-c:v" || w[0] == "-vcodec") && w[1] == "copy");
+    if copies_video {
+        return Err("Custom filters cannot be used with video codec \"copy\"".to_string());
+    }
+    // A -vf chain next to a complex graph that already feeds the video would be ignored
+    if args.iter().any(|a| a == "-filter_complex") {
+        return Err("Custom filters cannot be combined with -filter_complex in the encoder \
+                    arguments; add them to that graph instead"
+            .to_string());
+    }
 
-    Some((frame, fps, size, bitrate, time_seconds, speed))
+    encode_args::push_video_filter(args, extra_filters);
+    Ok(())
 }
 
-/// Run FFmpeg render job with progress reporting
+/// Validate a custom filtergraph entered by the user
 #[tauri::command]
-async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<RenderResult, String> {
+async fn validate_filtergraph(graph: String) -> Result<(), String> {
     let config = load_ffmpeg_config();
-
     if config.ffmpeg_path.is_empty() {
         return Err("FFmpeg path not configured".to_string());
     }
 
-    // Log render log path in app.log (instead of generic started message)
-    let render_log_path = get_app_data_dir()
-        .join("logs")
-        .join("renders")
-        .join(format!("{}.log", job.job_id));
-    let log_message = format!(
-        "Render log file for job {}: {}",
-        job.job_id,
-        render_log_path.display()
-    );
-    let _ = write_log(log_message);
+    if graph.trim().is_empty() {
+        return Ok(());
+    }
 
-    let quoted_args = job
-        .ffmpeg_args
-        .iter()
-        .map(|a| {
-            if a.contains(' ') || a.contains('"') {
-                format!("\"{}\"", a.replace('"', "\\\""))
-            } else {
-                a.clone()
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(" ");
+    check_filtergraph(&config.ffmpeg_path, graph.trim(), None)
+}
 
-    let full_command = format!(
-        "\"{}\" -i \"{}\" {} \"{}\"",
-        config.ffmpeg_path, job.input_path, quoted_args, job.output_path
-    );
+/// Progress mapping for one FFmpeg invocation of a render job
+struct RenderPass<'a> {
+    phase: phases::JobPhase,
+    /// Phases of the job; places this pass on the overall progress bar
+    plan: &'a phases::PhasePlan,
+    /// Passes still to run after this one (for the ETA)
+    passes_after: u32,
+    /// Frames to encode per pass when progress is frame based
+    total_frames: Option<u64>,
+    /// The job's time limit runs out at this instant
+    deadline: Option<std::time::Instant>,
+}
 
-    let _ = write_render_log(
-        job.job_id.clone(),
-        format!(
-            "[RUN START]\njob_id={}\nffmpeg_path={}\ninput_path={}\noutput_path={}\nduration_seconds={}\nffmpeg_args_count={}\nffmpeg_args={}\nfull_command={}",
-            job.job_id,
-            config.ffmpeg_path,
-            job.input_path,
-            job.output_path,
-            job.duration_seconds,
-            job.ffmpeg_args.len(),
-            quoted_args,
-            full_command
-        ),
-    );
+impl RenderPass<'_> {
+    /// Done part (0..1) of this pass
+    fn fraction(&self, duration: f64, time: f64, frame: u64) -> f64 {
+        match self.total_frames {
+            Some(total) => (frame as f64 / total as f64).min(1.0),
+            None if duration > 0.0 => (time / duration).min(1.0),
+            None => 0.0,
+        }
+    }
+
+    /// Seconds left in this and the following passes, from the smoothed speed or fps
+    fn eta(&self, duration: f64, time: f64, frame: u64, speed: f64, fps: f64) -> f64 {
+        let passes = (1 + self.passes_after) as f64;
+        match self.total_frames {
+            Some(total) => progress::eta_seconds(total as f64 * passes - frame as f64, fps),
+            None if duration > 0.0 => progress::eta_seconds(duration * passes - time, speed),
+            None => 0.0,
+        }
+    }
+}
+
+/// Progress event at the start of a phase FFmpeg does not report on
+fn emit_phase_start(
+    window: &tauri::Window,
+    job_id: &str,
+    plan: &phases::PhasePlan,
+    phase: phases::JobPhase,
+    total_frames: Option<u64>,
+) {
+    let (phase_index, phase_count) = plan.position(phase);
+    let progress = RenderProgress {
+        job_id: job_id.to_string(),
+        frame: 0,
+        fps: 0.0,
+        bitrate: String::new(),
+        total_size: units::ByteSize::ZERO,
+        time_seconds: units::Duration::ZERO,
+        speed: 0.0,
+        smoothed_speed: 0.0,
+        progress_percent: plan.overall(phase, 0.0),
+        eta_seconds: units::Duration::ZERO,
+        phase,
+        phase_percent: 0.0,
+        phase_index,
+        phase_count,
+        progress_basis: progress::ProgressBasis::of(total_frames),
+        total_frames,
+    };
+    let _ = window.emit("render-progress", &progress);
+    http_api::broadcast("render-progress", &progress);
+}
 
+/// Spawn one FFmpeg pass of a render job and stream its progress until it exits.
+/// Returns the process result, collected error lines and whether the user stopped it.
+fn run_render_pass(
+    window: &tauri::Window,
+    job: &RenderJob,
+    ffmpeg_path: &str,
+    args: Vec<String>,
+    output_path: &str,
+    pass: RenderPass<'_>,
+) -> Result<(proc::ProcOutput, Vec<String>, bool), String> {
     // Register process with ProcessManager and get owned child handle
     let mut child = {
         let mut manager = PROCESS_MANAGER
@@ -1278,10 +1605,16 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
         let (child, pid) = manager
             .spawn_render(
                 job.job_id.clone(),
-                config.ffmpeg_path.clone(),
+                ffmpeg_path.to_string(),
                 job.input_path.clone(),
-                job.output_path.clone(),
-                job.ffmpeg_args.clone(),
+                output_path.to_string(),
+                args,
+                &process_manager::RenderSpawnOptions {
+                    input_args: job.input_args.clone(),
+                    cpu_threads: job.cpu_threads,
+                    cpu_affinity: job.cpu_affinity,
+                    priority: job.priority.unwrap_or_default(),
+                },
             )
             .map_err(|e| format!("Failed to spawn render: {}", e))?;
 
@@ -1289,30 +1622,48 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
         child
     };
 
-    // Read stderr in a separate thread for progress
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-
-    let job_id_stdout = job.job_id.clone();
-    let job_id_stderr = job.job_id.clone();
     let job_id_final = job.job_id.clone();
-    let duration = job.duration_seconds;
-    let window_stdout = window.clone();
-    let window_stderr = window.clone();
-    let window_final = window.clone();
-
-    // Spawn thread to read progress from stdout (pipe:1)
-    let stdout_handle = std::thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        let mut current_frame: u64 = 0;
-        let mut current_fps: f64 = 0.0;
-        let mut current_time: f64 = 0.0;
-        let mut current_speed: f64 = 0.0;
-        let mut current_bitrate = String::new();
-        let mut current_size = String::new();
-
-        for line in reader.lines() {
-            if let Ok(line) = line {
+    let duration = job.duration_seconds.as_secs_f64();
+    let (phase_index, phase_count) = pass.plan.position(pass.phase);
+
+    // Progress from stdout (-progress pipe:1, key=value pairs)
+    let mut current_frame: u64 = 0;
+    let mut current_fps: f64 = 0.0;
+    let mut current_time = units::Duration::ZERO;
+    let mut current_speed: f64 = 0.0;
+    let mut current_bitrate = String::new();
+    let mut current_size = units::ByteSize::ZERO;
+    let mut errors = Vec::new();
+    let mut throttle = progress::Throttle::new(progress::PROGRESS_INTERVAL);
+    let mut smoother = progress::SpeedSmoother::default();
+    let mut fps_smoother = progress::SpeedSmoother::default();
+    // Every report goes into the speed history; the history keeps one per second
+    let mut report = |progress: RenderProgress, now: std::time::Instant| {
+        progress::record_sample(
+            &progress.job_id,
+            now,
+            progress.phase,
+            progress.progress_percent,
+            progress.speed,
+            progress.smoothed_speed,
+            progress.fps,
+        );
+        if let Some(progress) = throttle.offer(progress, now) {
+            let _ = window.emit("render-progress", &progress);
+            http_api::broadcast("render-progress", &progress);
+        }
+    };
+
+    // Stream both pipes until FFmpeg exits (or the job's time limit kills it)
+    let options = proc::RunOptions {
+        timeout: pass
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(std::time::Instant::now())),
+        ..Default::default()
+    };
+    let output = proc::run_streaming(&mut child, &options, |stream, line| {
+        match stream {
+            proc::Stream::Stdout => {
                 // Parse progress format from -progress pipe:1
                 // Format is key=value pairs
                 if line.starts_with("frame=") {
@@ -1326,10 +1677,15 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
                 } else if line.starts_with("bitrate=") {
                     current_bitrate = line.trim_start_matches("bitrate=").to_string();
                 } else if line.starts_with("total_size=") {
-                    current_size = line.trim_start_matches("total_size=").to_string();
+                    if let Some(size) =
+                        units::ByteSize::parse(line.trim_start_matches("total_size="))
+                    {
+                        current_size = size;
+                    }
                 } else if line.starts_with("out_time_ms=") {
-                    if let Ok(val) = line.trim_start_matches("out_time_ms=").parse::<f64>() {
-                        current_time = val / 1_000_000.0; // Convert microseconds to seconds
+                    // Despite the name, out_time_ms is in microseconds
+                    if let Ok(val) = line.trim_start_matches("out_time_ms=").parse::<i64>() {
+                        current_time = units::Duration::from_micros(val);
                     }
                 } else if line.starts_with("speed=") {
                     let speed_str = line.trim_start_matches("speed=").trim_end_matches('x');
@@ -1338,87 +1694,90 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
                     }
                 } else if line.starts_with("progress=") {
                     // Emit progress event on each "progress=" line
-                    let progress_percent = if duration > 0.0 {
-                        (current_time / duration * 100.0).min(100.0)
-                    } else {
-                        0.0
-                    };
+                    let time = current_time.as_secs_f64();
+                    let fraction = pass.fraction(duration, time, current_frame);
 
-                    let eta_seconds = if current_speed > 0.0 && duration > 0.0 {
-                        (duration - current_time) / current_speed
-                    } else {
-                        0.0
-                    };
+                    let now = std::time::Instant::now();
+                    let smoothed_speed = smoother.update(current_speed, now);
+                    let smoothed_fps = fps_smoother.update(current_fps, now);
+                    let eta_seconds =
+                        pass.eta(duration, time, current_frame, smoothed_speed, smoothed_fps);
 
                     let progress = RenderProgress {
-                        job_id: job_id_stdout.clone(),
+                        job_id: job_id_final.clone(),
                         frame: current_frame,
                         fps: current_fps,
                         bitrate: current_bitrate.clone(),
-                        total_size: current_size.clone(),
+                        total_size: current_size,
                         time_seconds: current_time,
                         speed: current_speed,
-                        progress_percent,
-                        eta_seconds,
+                        smoothed_speed,
+                        progress_percent: pass.plan.overall(pass.phase, fraction),
+                        eta_seconds: units::Duration::from_secs_f64(eta_seconds),
+                        phase: pass.phase,
+                        phase_percent: fraction * 100.0,
+                        phase_index,
+                        phase_count,
+                        progress_basis: progress::ProgressBasis::of(pass.total_frames),
+                        total_frames: pass.total_frames,
                     };
 
-                    let _ = window_stdout.emit("render-progress", &progress);
+                    report(progress, now);
                 }
             }
-        }
-    });
-
-    // Spawn thread to read stderr for errors
-    let stderr_handle = std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        let mut errors = Vec::new();
-        for line in reader.lines() {
-            if let Ok(line) = line {
+            proc::Stream::Stderr => {
                 // Parse traditional stderr output for backup progress
                 if line.contains("frame=") && line.contains("time=") {
                     if let Some((frame, fps, size, bitrate, time, speed)) =
-                        parse_ffmpeg_progress_line(&line)
+                        parse_ffmpeg_progress_line(line)
                     {
-                        let progress_percent = if duration > 0.0 {
-                            (time / duration * 100.0).min(100.0)
-                        } else {
-                            0.0
-                        };
+                        let time_seconds = time.as_secs_f64();
+                        let fraction = pass.fraction(duration, time_seconds, frame);
 
-                        let eta_seconds = if speed > 0.0 && duration > 0.0 {
-                            (duration - time) / speed
-                        } else {
-                            0.0
-                        };
+                        let now = std::time::Instant::now();
+                        let smoothed_speed = smoother.update(speed, now);
+                        let smoothed_fps = fps_smoother.update(fps, now);
+                        let eta_seconds =
+                            pass.eta(duration, time_seconds, frame, smoothed_speed, smoothed_fps);
 
                         let progress = RenderProgress {
-                            job_id: job_id_stderr.clone(),
+                            job_id: job_id_final.clone(),
                             frame,
                             fps,
                             bitrate,
                             total_size: size,
                             time_seconds: time,
                             speed,
-                            progress_percent,
-                            eta_seconds,
+                            smoothed_speed,
+                            progress_percent: pass.plan.overall(pass.phase, fraction),
+                            eta_seconds: units::Duration::from_secs_f64(eta_seconds),
+                            phase: pass.phase,
+                            phase_percent: fraction * 100.0,
+                            phase_index,
+                            phase_count,
+                            progress_basis: progress::ProgressBasis::of(pass.total_frames),
+                            total_frames: pass.total_frames,
                         };
 
-                        let _ = window_stderr.emit("render-progress", &progress);
+                        report(progress, now);
                     }
                 }
-                // Collect error lines
-                if line.contains("Error") || line.contains("error") || line.contains("Invalid") {
-                    errors.push(line);
+                // Collect error lines (and the ones render_errors recognizes a cause in)
+                if line.contains("Error")
+                    || line.contains("error")
+                    || line.contains("Invalid")
+                    || render_errors::classify_line(line).is_some()
+                {
+                    errors.push(line.to_string());
                 }
             }
         }
-        errors
-    });
-
-    // Wait for process to complete
-    let status = child
-        .wait()
-        .map_err(|e| format!("FFmpeg process error: {}", e))?;
+    })
+    .map_err(|e| format!("FFmpeg process error: {}", e))?;
+    if let Some(progress) = throttle.flush() {
+        let _ = window.emit("render-progress", &progress);
+        http_api::broadcast("render-progress", &progress);
+    }
 
     // Check if this job was stopped by user
     let was_stopped = {
@@ -1428,10 +1787,6 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
         manager.take_stopped(&job_id_final)
     };
 
-    // Wait for threads
-    let _ = stdout_handle.join();
-    let errors = stderr_handle.join().unwrap_or_default();
-
     // Clean up process from manager
     {
         let mut manager = PROCESS_MANAGER
@@ -1441,68 +1796,828 @@ async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<Rend
         // eprintln!("🧹 [run_ffmpeg_render] Process cleaned up - Job: {}", job_id_final);
     }
 
+    Ok((output, errors, was_stopped))
+}
+
+/// Run FFmpeg render job with progress reporting, and record it in the job history
+#[tauri::command]
+async fn run_ffmpeg_render(window: tauri::Window, job: RenderJob) -> Result<RenderResult, String> {
+    // A re-run from the history encodes the whole source again
+    let original = RenderJob {
+        resume: None,
+        ..job.clone()
+    };
+    let started_at = chrono::Local::now();
+    progress::start_history(&job.job_id);
+    let result = match hooks::pre_render(&original).await {
+        Ok(()) => render_job(window.clone(), job).await,
+        Err(e) => Err(e),
+    };
+    hooks::post_render(&original, &result).await;
+    history::record(&original, started_at, &result);
+    webhooks::job_finished(&original, started_at, &result);
+    uploads::upload_output(&window, &original, &result);
+    url_import::render_finished(&original, &result);
+    result
+}
+
+async fn render_job(window: tauri::Window, mut job: RenderJob) -> Result<RenderResult, String> {
+    let mut config = load_ffmpeg_config();
+    if let Some((ffmpeg_path, ffprobe_path)) = job
+        .preset_name
+        .as_deref()
+        .and_then(ffmpeg_versions::preset_override)
+    {
+        config.ffmpeg_path = ffmpeg_path;
+        config.ffprobe_path = ffprobe_path;
+    }
+
+    if config.ffmpeg_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    // The queue may still dispatch a job while renders are being stopped for exit
+    if shutdown::in_progress() {
+        return Err("The app is shutting down".to_string());
+    }
+
+    // Validate custom filters up front instead of failing hours into the encode
+    let extra_filters = job.extra_filters.trim().to_string();
+    if !extra_filters.is_empty() {
+        let probe = media_cache::probe(&config.ffprobe_path, &job.input_path).ok();
+        if let Err(e) = check_filtergraph(&config.ffmpeg_path, &extra_filters, probe.as_ref())
+            .and_then(|_| apply_extra_filters(&mut job.ffmpeg_args, &extra_filters))
+        {
+            let _ = write_render_log(
+                job.job_id.clone(),
+                format!("[FILTERGRAPH INVALID]\n{}", e),
+            );
+            return Err(format!("Invalid custom filters: {}", e));
+        }
+    }
+
+    // Timelapse / slow motion; progress and verification then expect the shorter or
+    // longer output
+    if let Some(factor) = encode_args::effective_speed(job.speed_factor) {
+        if let Err(e) = encode_args::apply_speed(&mut job.ffmpeg_args, factor) {
+            let _ = write_render_log(job.job_id.clone(), format!("[SPEED] {}", e));
+            return Err(e);
+        }
+        job.duration_seconds = encode_args::speed_adjusted(job.duration_seconds, Some(factor));
+    }
+
+    if let Err(e) = encode_args::apply_audio_source(&mut job.ffmpeg_args, &job.audio_source) {
+        let _ = write_render_log(job.job_id.clone(), format!("[AUDIO SOURCE] {}", e));
+        return Err(e);
+    }
+
+    if let Some(orientation) = &job.orientation {
+        if let Err(e) = encode_args::apply_orientation(
+            &mut job.ffmpeg_args,
+            &mut job.input_args,
+            orientation,
+        ) {
+            let _ = write_render_log(job.job_id.clone(), format!("[ORIENTATION] {}", e));
+            return Err(e);
+        }
+    }
+
+    // Resolution / frame rate caps against the probed source; they go first in the -vf
+    // chain, only color grading and stabilization come before them
+    let mut fps_ratio = 1.0;
+    if let Some(limits) = job.limits {
+        let capped = media_cache::probe(&config.ffprobe_path, &job.input_path)
+            .ok()
+            .and_then(|probe| encode_args::source_video(&probe))
+            .ok_or_else(|| "Could not read the source resolution for the output caps".to_string())
+            .and_then(|source| {
+                encode_args::apply_limits(&mut job.ffmpeg_args, &limits, &source)
+                    .map(|fps| fps.filter(|_| source.fps > 0.0).map(|fps| fps / source.fps))
+            });
+        match capped {
+            Ok(ratio) => fps_ratio = ratio.unwrap_or(1.0),
+            Err(e) => {
+                let _ = write_render_log(job.job_id.clone(), format!("[LIMITS] {}", e));
+                return Err(e);
+            }
+        }
+    }
+
+    // Color grading, checked here so a broken LUT fails before FFmpeg starts
+    if let Some(options) = &job.color {
+        if let Err(e) = options
+            .validate()
+            .and_then(|_| color::apply_color(&mut job.ffmpeg_args, options))
+        {
+            let _ = write_render_log(job.job_id.clone(), format!("[COLOR] {}", e));
+            return Err(e);
+        }
+    }
+
+    // Stabilization: vidstabtransform joins the encode, the detect pass runs before it
+    let stabilization = job
+        .stabilize
+        .clone()
+        .map(|options| (options, stabilize::transforms_path(&job.job_id)));
+    if let Some((options, transforms)) = &stabilization {
+        if let Err(e) = options
+            .validate()
+            .and_then(|_| stabilize::apply_transform(&mut job.ffmpeg_args, options, transforms))
+        {
+            let _ = write_render_log(job.job_id.clone(), format!("[STABILIZE] {}", e));
+            return Err(e);
+        }
+    }
+
+    // Pixel format last in the -vf chain; 10-bit sources are converted down for
+    // 8-bit-only encoders
+    if let Some(format) = job.pixel_format {
+        let source = media_cache::probe(&config.ffprobe_path, &job.input_path)
+            .ok()
+            .and_then(|probe| encode_args::source_pix_fmt(&probe));
+        match encode_args::apply_pixel_format(&mut job.ffmpeg_args, format, source.as_deref()) {
+            Ok(applied) => {
+                let _ = write_render_log(
+                    job.job_id.clone(),
+                    format!("[PIXEL FORMAT] source {:?}, output {:?}", source, applied),
+                );
+            }
+            Err(e) => {
+                let _ = write_render_log(job.job_id.clone(), format!("[PIXEL FORMAT] {}", e));
+                return Err(e);
+            }
+        }
+    }
+
+    let mut copied_streams = Vec::new();
+    if job.audio_passthrough {
+        let container = std::path::Path::new(&job.output_path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let passed = media_cache::probe(&config.ffprobe_path, &job.input_path).and_then(|probe| {
+            encode_args::apply_audio_passthrough(&mut job.ffmpeg_args, &probe, &container)
+        });
+        match passed {
+            Ok(codec) => {
+                let _ = write_render_log(
+                    job.job_id.clone(),
+                    format!("[AUDIO PASSTHROUGH] copied: {:?}", codec),
+                );
+                if codec.is_some() {
+                    copied_streams.push("audio".to_string());
+                }
+            }
+            Err(e) => {
+                let _ = write_render_log(job.job_id.clone(), format!("[AUDIO PASSTHROUGH] {}", e));
+                return Err(e);
+            }
+        }
+    }
+
+    // Smart mode, judged on the final arguments: a stream already in the requested
+    // codec at no more than the requested bitrate is copied
+    if job.smart_copy {
+        match media_cache::probe(&config.ffprobe_path, &job.input_path) {
+            Ok(probe) => {
+                copied_streams.extend(encode_args::apply_smart_copy(&mut job.ffmpeg_args, &probe));
+                let _ = write_render_log(
+                    job.job_id.clone(),
+                    format!("[SMART COPY] copied streams: {:?}", copied_streams),
+                );
+            }
+            Err(e) => {
+                let _ = write_render_log(
+                    job.job_id.clone(),
+                    format!("[SMART COPY] source not probed, re-encoding everything: {}", e),
+                );
+            }
+        }
+    }
+
+    // Hardware encoders that need an explicit device (QSV, VAAPI) get it here, so
+    // the frontend-built args work on machines with several GPUs
+    encode_args::ensure_hw_device_args(&mut job.ffmpeg_args);
+
+    queue::note_render_started();
+
+    let settings = load_settings().unwrap_or_default();
+
+    // Renders start at the configured priority unless the job asks for its own
+    job.priority.get_or_insert(settings.render_priority);
+    if settings.job_timeout_minutes > 0 {
+        job.max_duration_minutes.get_or_insert(settings.job_timeout_minutes);
+    }
+    let quarantine_inputs = job.unattended && settings.quarantine_failed_inputs;
+
+    // Power saver on battery: half the cores at idle priority
+    if let Some((threads, priority)) = power::battery_render_limits() {
+        job.cpu_threads = Some(job.cpu_threads.map_or(threads, |t| t.min(threads)));
+        job.priority = Some(priority);
+        let _ = write_render_log(
+            job.job_id.clone(),
+            format!("[POWER] On battery, limiting to {} threads", threads),
+        );
+    }
+
+    // Two-pass encodes run an analysis pass first; its log lives next to the render logs
+    let passlog = if job.two_pass {
+        match encode_args::video_encoder_in(&job.ffmpeg_args) {
+            Some(encoder) if encode_args::supports_two_pass(encoder) => Some(
+                get_app_data_dir()
+                    .join("logs")
+                    .join("renders")
+                    .join(format!("{}-pass", job.job_id)),
+            ),
+            encoder => {
+                let _ = write_render_log(
+                    job.job_id.clone(),
+                    format!("[TWO-PASS] skipped, encoder {:?} does not support it", encoder),
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Recycling the source always needs at least the duration check to pass
+    let verify_mode = match settings.verify_output {
+        verify::VerifyMode::Off if settings.trash_source_after_success => {
+            verify::VerifyMode::Duration
+        }
+        mode => mode,
+    };
+
+    // Steps of this job on the progress bar; the duration check is too quick for one
+    let probe_source = settings.hw_decode && !job.input_args.iter().any(|a| a == "-hwaccel");
+    let mut job_phases = Vec::new();
+    if probe_source {
+        job_phases.push(phases::JobPhase::Analyzing);
+    }
+    if stabilization.is_some() {
+        job_phases.push(phases::JobPhase::Stabilizing);
+    }
+    if passlog.is_some() {
+        job_phases.extend([phases::JobPhase::Pass1, phases::JobPhase::Pass2]);
+    } else {
+        job_phases.push(phases::JobPhase::Encoding);
+    }
+    if job.resume.is_some() {
+        job_phases.push(phases::JobPhase::Muxing);
+    }
+    if verify_mode == verify::VerifyMode::Decode {
+        job_phases.push(phases::JobPhase::Verifying);
+    }
+    let plan = phases::PhasePlan::new(&job_phases);
+
+    // Frame-based progress when the duration cannot be trusted; a resumed render starts
+    // part way into the source, so it stays time based
+    let total_frames = if job.resume.is_none() && !config.ffprobe_path.is_empty() {
+        media_cache::probe(&config.ffprobe_path, &job.input_path)
+            .ok()
+            .and_then(|probe| {
+                // frame_total counts in source time; setpts keeps the frame rate, so a
+                // speed change drops or repeats frames, and a frame rate cap drops them
+                let speed = encode_args::effective_speed(job.speed_factor).unwrap_or(1.0);
+                progress::frame_total(&probe, job.duration_seconds.as_secs_f64() * speed)
+                    .map(|frames| ((frames as f64 * fps_ratio / speed).round() as u64).max(1))
+            })
+    } else {
+        None
+    };
+    if let Some(total_frames) = total_frames {
+        let _ = write_render_log(
+            job.job_id.clone(),
+            format!("[PROGRESS] frame based, total_frames={}", total_frames),
+        );
+    }
+
+    // Hardware decoding of the source, picked from its codec and the encoder in use
+    if probe_source {
+        emit_phase_start(
+            &window,
+            &job.job_id,
+            &plan,
+            phases::JobPhase::Analyzing,
+            total_frames,
+        );
+        let source_codec = probe_video_codec(&config.ffprobe_path, &job.input_path);
+        let hwaccel = source_codec.as_deref().and_then(|codec| {
+            encode_args::decode_hwaccel(
+                codec,
+                encode_args::video_encoder_in(&job.ffmpeg_args),
+                settings.gpu_available,
+            )
+        });
+        let _ = write_render_log(
+            job.job_id.clone(),
+            format!(
+                "[HW DECODE] source_codec={:?} hwaccel={:?}",
+                source_codec, hwaccel
+            ),
+        );
+        if let Some(hwaccel) = hwaccel {
+            job.input_args
+                .extend(["-hwaccel".to_string(), hwaccel.to_string()]);
+        }
+    }
+
+    // Log render log path in app.log (instead of generic started message)
+    let render_log_path = get_app_data_dir()
+        .join("logs")
+        .join("renders")
+        .join(format!("{}.log", job.job_id));
+    let log_message = format!(
+        "Render log file for job {}: {}",
+        job.job_id,
+        render_log_path.display()
+    );
+    let _ = write_log(log_message);
+
+    let quoted_args = job
+        .ffmpeg_args
+        .iter()
+        .map(|a| {
+            if a.contains(' ') || a.contains('"') {
+                format!("\"{}\"", a.replace('"', "\\\""))
+            } else {
+                a.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let full_command = format!(
+        "\"{}\" {} -i \"{}\" {} \"{}\"",
+        config.ffmpeg_path,
+        job.input_args.join(" "),
+        job.input_path,
+        quoted_args,
+        job.output_path
+    );
+
+    let _ = write_render_log(
+        job.job_id.clone(),
+        format!(
+            "[RUN START]\njob_id={}\nffmpeg_path={}\ninput_path={}\noutput_path={}\nduration_seconds={}\ntwo_pass={}\ncpu_threads={:?}\ncpu_affinity={}\npriority={:?}\nffmpeg_args_count={}\nffmpeg_args={}\nfull_command={}",
+            job.job_id,
+            config.ffmpeg_path,
+            job.input_path,
+            job.output_path,
+            job.duration_seconds,
+            job.two_pass,
+            job.cpu_threads,
+            job.cpu_affinity,
+            job.priority,
+            job.ffmpeg_args.len(),
+            quoted_args,
+            full_command
+        ),
+    );
+
+    // Output templates and mirrored output folders may name folders that do not exist yet
+    if let Some(parent) = std::path::Path::new(&job.output_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create output folder: {}", e))?;
+        }
+    }
+
+    // Network shares are often the real bottleneck: warn, and optionally render
+    // through a local copy of the source / output
+    let mut path_analysis = network_paths::analyze(&job.input_path, &job.output_path);
+    let mut staging = None;
+    if path_analysis.any_network() {
+        path_analysis.job_id = Some(job.job_id.clone());
+        if settings.stage_network_files {
+            staging = Some(network_paths::StagedPaths::prepare(&mut job, &path_analysis)?);
+            path_analysis.staged = true;
+        }
+        let _ = write_render_log(
+            job.job_id.clone(),
+            format!(
+                "[NETWORK] {} staged={} input_path={} output_path={}",
+                path_analysis.warnings.join("; "),
+                path_analysis.staged,
+                job.input_path,
+                job.output_path
+            ),
+        );
+        let _ = window.emit("path-analysis", &path_analysis);
+    }
+
+    // Render to "<name>.part.<ext>" and rename on success, so an interrupted render
+    // never leaves a truncated file under the final name
+    let final_output = job.output_path.clone();
+    let resume_head = match job.resume.take() {
+        Some(point) => Some(resume::prepare(&mut job, point)?),
+        None => None,
+    };
+    job.output_path = orphans::part_path(&final_output);
+
+    let deadline = job
+        .max_duration_minutes
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| {
+            std::time::Instant::now() + std::time::Duration::from_secs(u64::from(minutes) * 60)
+        });
+    let monitor = resource_monitor::ResourceMonitor::start(window.clone(), job.job_id.clone());
+    let detect = match &stabilization {
+        Some((options, transforms)) => {
+            stabilize::prepare(transforms)?;
+            Some(run_render_pass(
+                &window,
+                &job,
+                &config.ffmpeg_path,
+                stabilize::detect_args(&job.ffmpeg_args, options, transforms),
+                "-",
+                RenderPass {
+                    phase: phases::JobPhase::Stabilizing,
+                    plan: &plan,
+                    passes_after: if passlog.is_some() { 2 } else { 1 },
+                    total_frames,
+                    deadline,
+                },
+            )?)
+        }
+        None => None,
+    };
+    let (mut output, mut errors, was_stopped) = match (detect, &passlog) {
+        // A failed or stopped detect pass ends the job
+        (Some(detect), _) if !detect.0.success || detect.2 => detect,
+        (_, Some(passlog)) => {
+            let passlog = passlog.to_string_lossy().to_string();
+            let first = run_render_pass(
+                &window,
+                &job,
+                &config.ffmpeg_path,
+                encode_args::first_pass_args(&job.ffmpeg_args, &passlog),
+                "-",
+                RenderPass {
+                    phase: phases::JobPhase::Pass1,
+                    plan: &plan,
+                    passes_after: 1,
+                    total_frames,
+                    deadline,
+                },
+            )?;
+            if first.0.success && !first.2 {
+                run_render_pass(
+                    &window,
+                    &job,
+                    &config.ffmpeg_path,
+                    encode_args::second_pass_args(&job.ffmpeg_args, &passlog),
+                    &job.output_path,
+                    RenderPass {
+                        phase: phases::JobPhase::Pass2,
+                        plan: &plan,
+                        passes_after: 0,
+                        total_frames,
+                        deadline,
+                    },
+                )?
+            } else {
+                first
+            }
+        }
+        (_, None) => run_render_pass(
+            &window,
+            &job,
+            &config.ffmpeg_path,
+            job.ffmpeg_args.clone(),
+            &job.output_path,
+            RenderPass {
+                phase: phases::JobPhase::Encoding,
+                plan: &plan,
+                passes_after: 0,
+                total_frames,
+                deadline,
+            },
+        )?,
+    };
+    let resources = monitor.stop();
+    let _ = write_render_log(
+        job.job_id.clone(),
+        format!(
+            "[RESOURCES] samples={} avg_cpu={:?} avg_gpu={:?} avg_ffmpeg_cpu={:?} peak_ffmpeg_mb={:?}",
+            resources.samples,
+            resources.avg_cpu_percent,
+            resources.avg_gpu_percent,
+            resources.avg_process_cpu_percent,
+            resources.peak_process_memory_mb
+        ),
+    );
+
+    if let Some((_, transforms)) = &stabilization {
+        stabilize::cleanup(transforms);
+    }
+
+    // libvpx / x264 write <passlog>-0.log (+ .mbtree for x264), x265 <passlog>.log (+ .cutree)
+    if let Some(passlog) = &passlog {
+        if let (Some(dir), Some(prefix)) = (passlog.parent(), passlog.file_name()) {
+            let prefix = prefix.to_string_lossy().to_string();
+            for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+
+    let part_output = std::mem::replace(&mut job.output_path, final_output);
+    if output.success && !was_stopped {
+        let finished = match &resume_head {
+            // Resumed render: the earlier part plus the rest rendered now
+            Some(head) => {
+                emit_phase_start(
+                    &window,
+                    &job.job_id,
+                    &plan,
+                    phases::JobPhase::Muxing,
+                    total_frames,
+                );
+                resume::join(
+                    &config.ffmpeg_path,
+                    &job.job_id,
+                    head,
+                    &part_output,
+                    &job.output_path,
+                )
+            }
+            None => fs::rename(&part_output, &job.output_path).map_err(|e| {
+                format!("Failed to rename {} to {}: {}", part_output, job.output_path, e)
+            }),
+        };
+        if let Err(e) = finished {
+            let _ = write_render_log(job.job_id.clone(), format!("[OUTPUT] {}", e));
+            output.success = false;
+            errors.push(e);
+        }
+    } else if !shutdown::in_progress() || resume_head.is_some() {
+        // An output FFmpeg finalized while the app shut down is kept for resuming,
+        // unless it only continues an earlier part
+        let _ = fs::remove_file(&part_output);
+        if let Some(head) = &resume_head {
+            let _ = fs::remove_file(head);
+        }
+    }
+
+    // Staged render: copy the output back to the share and restore the job's paths
+    if let Some(staging) = staging {
+        if let Err(e) = staging.finish(&mut job, output.success && !was_stopped) {
+            let _ = write_render_log(job.job_id.clone(), format!("[NETWORK] {}", e));
+            output.success = false;
+            errors.push(e);
+        }
+    }
+    let window_final = window;
+
     // Log completion
     let log_message = format!(
         "Render job {} completed with status: {}",
         job.job_id,
-        if status.success() {
-            "success"
-        } else {
-            "failed"
-        }
+        if output.success { "success" } else { "failed" }
     );
     let _ = write_log(log_message);
 
     if was_stopped {
-        let _ = window_final.emit(
-            "render-stopped",
-            &serde_json::json!({
-                "job_id": job.job_id,
-                "stopped_by": "user"
-            }),
-        );
+        let stopped = serde_json::json!({
+            "job_id": job.job_id,
+            "stopped_by": "user"
+        });
+        let _ = window_final.emit("render-stopped", &stopped);
+        http_api::broadcast("render-stopped", &stopped);
 
         Ok(RenderResult {
             job_id: job.job_id,
             success: false,
             error: Some("stopped".to_string()),
+            error_kind: None,
             output_path: job.output_path,
+            resources: Some(resources),
+            warnings: Vec::new(),
+            source_trashed: false,
+            copied_streams: Vec::new(),
         })
-    } else if status.success() {
+    } else if output.success {
+        if verify_mode == verify::VerifyMode::Decode {
+            emit_phase_start(
+                &window_final,
+                &job.job_id,
+                &plan,
+                phases::JobPhase::Verifying,
+                total_frames,
+            );
+        }
+        let warnings = verify_render_output(&job, verify_mode);
+        if settings.record_hashes {
+            emit_render_hashes(&window_final, &job);
+        }
+        emit_render_sizes(&window_final, &job);
+        if quarantine_inputs {
+            let _ = quarantine::clear_input_failures(&job.input_path);
+        }
+        let source_trashed =
+            settings.trash_source_after_success && warnings.is_empty() && recycle_source(&job);
+
         // Emit complete event
         let _ = window_final.emit("render-complete", &job.job_id);
+        http_api::broadcast("render-complete", &job.job_id);
+        notifications::render_finished(&window_final, &job.input_path, None);
 
         Ok(RenderResult {
             job_id: job.job_id,
             success: true,
             error: None,
+            error_kind: None,
             output_path: job.output_path,
+            resources: Some(resources),
+            warnings,
+            source_trashed,
+            copied_streams,
         })
     } else {
-        let error_msg = if errors.is_empty() {
-            format!("FFmpeg exited with code: {:?}", status.code())
+        let crashed = crash::describe_crash(output.code, output.signal);
+        if let Some(description) = &crashed {
+            crash::record_ffmpeg_crash(&job.job_id, &job.input_path, description);
+        }
+        let error_kind = if output.timed_out {
+            Some(render_errors::RenderErrorKind::Timeout)
+        } else if crashed.is_some() {
+            Some(render_errors::RenderErrorKind::Crash)
         } else {
+            render_errors::classify(&errors)
+        };
+        if error_kind == Some(render_errors::RenderErrorKind::NvencSessionLimit) {
+            gpu::note_nvenc_session_limit(&job.job_id);
+        }
+        let error_msg = if output.timed_out {
+            let message = format!(
+                "Render exceeded its time limit of {} min and was stopped",
+                job.max_duration_minutes.unwrap_or_default()
+            );
+            let _ = write_render_log(job.job_id.clone(), format!("[TIMEOUT] {}", message));
+            message
+        } else if !errors.is_empty() {
             errors.join("\n")
+        } else if let Some(description) = crashed {
+            format!("FFmpeg crashed: {}", description)
+        } else {
+            format!("FFmpeg exited with code: {:?}", output.code)
+        };
+
+        // An unattended input that keeps failing for input-caused reasons moves to
+        // <dir>/_failed
+        let quarantined = quarantine_inputs
+            .then(|| quarantine::record_input_failure(&job.input_path, error_kind, &error_msg));
+        let error_msg = match quarantined {
+            Some(Ok(quarantine::FailureResult {
+                quarantined_path: Some(target),
+                failures,
+                ..
+            })) => format!("{}\nMoved to {} after {} failures", error_msg, target, failures),
+            Some(Err(e)) => {
+                let _ = write_render_log(job.job_id.clone(), format!("[QUARANTINE] {}", e));
+                error_msg
+            }
+            _ => error_msg,
         };
 
         // Emit error event
-        let _ = window_final.emit(
-            "render-error",
-            serde_json::json!({
-                "job_id": job.job_id,
-                "error": error_msg.clone()
-            }),
-        );
+        let render_error = serde_json::json!({
+            "job_id": job.job_id,
+            "error": error_msg.clone(),
+            "kind": error_kind
+        });
+        let _ = window_final.emit("render-error", &render_error);
+        http_api::broadcast("render-error", &render_error);
+        notifications::render_finished(&window_final, &job.input_path, Some(&error_msg));
 
         Ok(RenderResult {
             job_id: job.job_id,
             success: false,
             error: Some(error_msg),
+            error_kind,
             output_path: job.output_path,
+            resources: Some(resources),
+            warnings: Vec::new(),
+            source_trashed: false,
+            copied_streams: Vec::new(),
         })
     }
 }
 
+/// Post-render verification per the verifyOutput setting; returns the warnings
+fn verify_render_output(job: &RenderJob, mode: verify::VerifyMode) -> Vec<String> {
+    if mode == verify::VerifyMode::Off {
+        return Vec::new();
+    }
+
+    let decode = mode == verify::VerifyMode::Decode;
+    let warnings = match verify::verify(&job.output_path, Some(job.duration_seconds), decode) {
+        Ok(report) => {
+            let _ = write_render_log(
+                job.job_id.clone(),
+                format!(
+                    "[VERIFY] ok={} output_duration={:?} expected={:?} decode_errors={}",
+                    report.ok,
+                    report.output_duration,
+                    report.expected_duration,
+                    report.decode_errors.join(" | ")
+                ),
+            );
+            report.warnings
+        }
+        Err(e) => vec![format!("Verification failed: {}", e)],
+    };
+
+    if !warnings.is_empty() {
+        let _ = write_render_log(
+            job.job_id.clone(),
+            format!("[VERIFY] completed with warnings: {}", warnings.join("; ")),
+        );
+    }
+    warnings
+}
+
+/// Move a verified job's source to the recycle bin; trimmed renders keep it
+/// because the output only covers part of the source
+fn recycle_source(job: &RenderJob) -> bool {
+    let trimmed = job
+        .input_args
+        .iter()
+        .chain(&job.ffmpeg_args)
+        .any(|a| a == "-ss" || a == "-t" || a == "-to");
+    let result = if trimmed {
+        Err("Trimmed render, keeping the source".to_string())
+    } else {
+        recycle::trash_source(&job.job_id, &job.input_path, &job.output_path)
+    };
+
+    let _ = write_render_log(
+        job.job_id.clone(),
+        match &result {
+            Ok(()) => format!("[RECYCLE] Moved source to recycle bin: {}", job.input_path),
+            Err(e) => format!("[RECYCLE] {}", e),
+        },
+    );
+    result.is_ok()
+}
+
+/// Hash the input and output of a finished render and emit `render-hashes`
+/// so the statistics record can keep them
+fn emit_render_hashes(window: &tauri::Window, job: &RenderJob) {
+    let algorithm = hash::HashAlgorithm::default();
+    let hash = |path: &str| match hash::hash_file(std::path::Path::new(path), algorithm) {
+        Ok(digest) => Some(digest),
+        Err(e) => {
+            let _ = write_render_log(job.job_id.clone(), format!("[HASH] {}", e));
+            None
+        }
+    };
+
+    let hashes = hash::RenderHashes {
+        job_id: job.job_id.clone(),
+        algorithm,
+        input_hash: hash(&job.input_path),
+        output_hash: hash(&job.output_path),
+    };
+    let _ = write_render_log(
+        job.job_id.clone(),
+        format!(
+            "[HASH] {:?} input={} output={}",
+            algorithm,
+            hashes.input_hash.as_deref().unwrap_or("-"),
+            hashes.output_hash.as_deref().unwrap_or("-")
+        ),
+    );
+    let _ = window.emit("render-hashes", &hashes);
+}
+
+/// Payload of the `render-sizes` event
+#[derive(Debug, Clone, Serialize)]
+struct RenderSizes {
+    job_id: String,
+    input_size: units::ByteSize,
+    output_size: units::ByteSize,
+}
+
+/// Emit the input and output sizes of a finished render so the statistics record
+/// keeps them; runs before the source may be recycled
+fn emit_render_sizes(window: &tauri::Window, job: &RenderJob) {
+    let size = |path: &str| {
+        fs::metadata(path)
+            .map(|m| units::ByteSize::from_bytes(m.len()))
+            .unwrap_or_default()
+    };
+    let sizes = RenderSizes {
+        job_id: job.job_id.clone(),
+        input_size: size(&job.input_path),
+        output_size: size(&job.output_path),
+    };
+    let _ = window.emit("render-sizes", &sizes);
+}
+
 /// Request to stop a rendering job
 #[derive(Debug, Deserialize)]
 struct StopRenderRequest {
@@ -1510,6 +2625,20 @@ struct StopRenderRequest {
     job_id: String,
 }
 
+/// Change the CPU priority of a running render job
+#[tauri::command]
+fn set_render_priority(job_id: String, level: proc::Priority) -> Result<(), String> {
+    let pid = PROCESS_MANAGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get_pid(&job_id)
+        .ok_or_else(|| format!("Render job {} is not running", job_id))?;
+
+    proc::set_priority(pid, level)?;
+    let _ = write_render_log(job_id, format!("[PRIORITY] changed to {:?}", level));
+    Ok(())
+}
+
 /// Stop a running FFmpeg render job
 #[tauri::command]
 fn stop_ffmpeg_render(window: tauri::Window, request: StopRenderRequest) -> Result<bool, String> {
@@ -1535,21 +2664,7 @@ fn stop_ffmpeg_render(window: tauri::Window, request: StopRenderRequest) -> Resu
 
     // Kill the process by PID if we found it
     if let Some(pid) = pid {
-        #[cfg(target_os = "windows")]
-        {
-            // On Windows, use taskkill command
-            let _ = Command::new("taskkill")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .arg("/F") // Force kill
-                .output();
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            // On Unix/Linux, use kill command
-            let _ = Command::new("kill").arg("-9").arg(pid.to_string()).output();
-        }
+        proc::kill_pid(pid);
 
         // eprintln!("✅ [Tauri] stop_ffmpeg_render killed process - Job: {}, PID: {}", job_id, pid);
     }
@@ -1566,9 +2681,8 @@ fn stop_ffmpeg_render(window: tauri::Window, request: StopRenderRequest) -> Resu
     Ok(true)
 }
 
-/// Stop all running FFmpeg processes
-#[tauri::command]
-fn stop_all_renders(window: tauri::Window) -> Result<(), String> {
+/// Kill every running FFmpeg process; returns the (job id, PID) pairs that were stopped
+fn kill_all_renders() -> Result<Vec<(String, u32)>, String> {
     let pids = {
         let mut manager = PROCESS_MANAGER.lock().map_err(|e| e.to_string())?;
         let active_jobs = manager.active_jobs();
@@ -1579,21 +2693,16 @@ fn stop_all_renders(window: tauri::Window) -> Result<(), String> {
     };
 
     // Kill all processes by PID
-    for (job_id, pid) in pids {
-        #[cfg(target_os = "windows")]
-        {
-            let _ = Command::new("taskkill")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .arg("/F")
-                .output();
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            let _ = Command::new("kill").arg("-9").arg(pid.to_string()).output();
-        }
+    for (_, pid) in &pids {
+        proc::kill_pid(*pid);
+    }
+    Ok(pids)
+}
 
+/// Stop all running FFmpeg processes
+#[tauri::command]
+fn stop_all_renders(window: tauri::Window) -> Result<(), String> {
+    for (job_id, _) in kill_all_renders()? {
         let _ = window.emit(
             "render-stopped",
             &serde_json::json!({
@@ -1606,62 +2715,107 @@ fn stop_all_renders(window: tauri::Window) -> Result<(), String> {
     Ok(())
 }
 
+/// Codec name of the first video stream (None when ffprobe fails)
+fn probe_video_codec(ffprobe_path: &str, input_path: &str) -> Option<String> {
+    let output = proc::run_captured(
+        proc::command(ffprobe_path).args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=codec_name",
+            "-of",
+            "csv=p=0",
+            input_path,
+        ]),
+        &proc::RunOptions::with_timeout(30).label("ffprobe codec"),
+    )
+    .ok()?;
+
+    let codec = output.stdout.trim().to_string();
+    (output.success && !codec.is_empty()).then_some(codec)
+}
+
 /// Get video duration using FFprobe
 #[tauri::command]
-async fn get_video_duration(input_path: String) -> Result<f64, String> {
+async fn get_video_duration(input_path: String) -> Result<units::Duration, String> {
     let config = load_ffmpeg_config();
 
     if config.ffprobe_path.is_empty() {
         return Err("FFprobe path not configured".to_string());
     }
 
-    #[cfg(target_os = "windows")]
-    let output = {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        Command::new(&config.ffprobe_path)
-            .creation_flags(CREATE_NO_WINDOW)
-            .args([
-                "-v",
-                "quiet",
-                "-print_format",
-                "json",
-                "-show_format",
-                &input_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to run FFprobe: {}", e))?
-    };
+    let json = media_cache::probe(&config.ffprobe_path, &input_path)?;
 
-    #[cfg(not(target_os = "windows"))]
-    let output = Command::new(&config.ffprobe_path)
-        .args([
-            "-v",
-            "quiet",
-            "-print_format",
-            "json",
-            "-show_format",
-            &input_path,
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run FFprobe: {}", e))?;
+    Ok(probed_duration(&json))
+}
 
-    if !output.status.success() {
-        return Err("FFprobe failed to analyze file".to_string());
-    }
+fn probed_duration(json: &serde_json::Value) -> units::Duration {
+    json["format"]["duration"]
+        .as_str()
+        .and_then(units::Duration::parse)
+        .unwrap_or_default()
+}
 
-    let json_str = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse FFprobe output: {}", e))?;
+#[derive(Debug, Serialize, Clone)]
+struct DurationResult {
+    path: String,
+    duration: Option<units::Duration>,
+    error: Option<String>,
+}
 
-    let json: serde_json::Value =
-        serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+/// Payload of `probe-progress`, one per probed file
+#[derive(Debug, Serialize, Clone)]
+struct ProbeProgress {
+    done: usize,
+    total: usize,
+    result: DurationResult,
+}
 
-    let duration = json["format"]["duration"]
-        .as_str()
-        .and_then(|s| s.parse::<f64>().ok())
-        .unwrap_or(0.0);
+/// Durations of several files, probed in parallel (e.g. a dropped folder). Results keep
+/// the order of `paths`; each one is also emitted as `probe-progress` when it is ready.
+#[tauri::command]
+async fn get_video_durations(
+    window: tauri::Window,
+    paths: Vec<String>,
+) -> Result<Vec<DurationResult>, String> {
+    let config = load_ffmpeg_config();
+
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
 
-    Ok(duration)
+    let total = paths.len();
+    let mut results: std::collections::HashMap<String, DurationResult> =
+        std::collections::HashMap::with_capacity(total);
+    media_cache::probe_all(
+        &paths,
+        |path| {
+            let probed = media_cache::probe(&config.ffprobe_path, path);
+            DurationResult {
+                path: path.to_string(),
+                duration: probed.as_ref().ok().map(probed_duration),
+                error: probed.err(),
+            }
+        },
+        |result| {
+            let _ = window.emit(
+                "probe-progress",
+                &ProbeProgress {
+                    done: results.len() + 1,
+                    total,
+                    result: result.clone(),
+                },
+            );
+            results.insert(result.path.clone(), result);
+        },
+    );
+
+    Ok(paths
+        .iter()
+        .filter_map(|path| results.get(path).cloned())
+        .collect())
 }
 
 /// Get file size in bytes
@@ -1695,29 +2849,73 @@ fn write_render_log(job_id: String, message: String) -> Result<(), String> {
 
 // Preset management commands
 
-#[tauri::command]
-fn list_presets() -> Result<Vec<String>, String> {
-    let presets_dir = get_presets_dir();
+/// Where a listed preset comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PresetSource {
+    User,
+    /// A manifest in the plugins folder (read-only)
+    Plugin,
+}
 
-    if !presets_dir.exists() {
-        return Ok(Vec::new());
-    }
+#[derive(Debug, Clone, Serialize)]
+struct PresetInfo {
+    name: String,
+    source: PresetSource,
+    /// Plugin providing the preset
+    plugin: Option<String>,
+    description: Option<String>,
+    constraints: Option<plugins::PresetConstraints>,
+}
 
-    let mut preset_names = Vec::new();
+#[tauri::command]
+fn list_presets() -> Result<Vec<PresetInfo>, String> {
+    let presets_dir = get_presets_dir();
+    let mut presets = Vec::new();
 
-    for entry in fs::read_dir(&presets_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+    if presets_dir.exists() {
+        for entry in fs::read_dir(&presets_dir).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
 
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
-            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                preset_names.push(name.to_string());
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    presets.push(PresetInfo {
+                        name: name.to_string(),
+                        source: PresetSource::User,
+                        plugin: None,
+                        description: None,
+                        constraints: None,
+                    });
+                }
             }
         }
     }
 
-    preset_names.sort();
-    Ok(preset_names)
+    // A user preset with the same name shadows the plugin one
+    for preset in plugins::presets() {
+        if presets.iter().all(|p| p.name != preset.name) {
+            presets.push(PresetInfo {
+                name: preset.name,
+                source: PresetSource::Plugin,
+                plugin: Some(preset.plugin),
+                description: preset.description,
+                constraints: Some(preset.constraints),
+            });
+        }
+    }
+
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(presets)
+}
+
+/// Names of all presets, user and plugin
+fn preset_names() -> Vec<String> {
+    list_presets()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|preset| preset.name)
+        .collect()
 }
 
 #[tauri::command]
@@ -1731,6 +2929,7 @@ fn save_preset(name: String, content: String) -> Result<(), String> {
 
     fs::write(&preset_path, content).map_err(|e| format!("Failed to save preset: {}", e))?;
 
+    refresh_context_menu();
     Ok(())
 }
 
@@ -1740,7 +2939,9 @@ fn load_preset(name: String) -> Result<String, String> {
     let preset_path = presets_dir.join(format!("{}.json", name));
 
     if !preset_path.exists() {
-        return Err(format!("Preset '{}' not found", name));
+        return plugins::find(&name)
+            .map(|preset| preset.content())
+            .ok_or_else(|| format!("Preset '{}' not found", name));
     }
 
     fs::read_to_string(&preset_path).map_err(|e| format!("Failed to load preset: {}", e))
@@ -1806,6 +3007,7 @@ fn delete_preset(name: String) -> Result<(), String> {
 
     fs::remove_file(&preset_path).map_err(|e| format!("Failed to delete preset: {}", e))?;
 
+    refresh_context_menu();
     Ok(())
 }
 
@@ -1906,6 +3108,92 @@ fn export_statistics(output_path: String) -> Result<(), String> {
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct HeatmapBucket {
+    weekday: u32, // 0 = Monday
+    hour: u32,
+    renders: u64,
+    minutes_encoded: f64,
+    bytes_saved: units::ByteSize,
+    gb_saved: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatisticsHeatmap {
+    buckets: Vec<HeatmapBucket>,
+    max_renders: u64,
+    max_minutes_encoded: f64,
+    max_gb_saved: f64,
+}
+
+/// Bytes saved by one render record, from the sizes recorded at completion
+/// (render-sizes); records without them count as zero
+fn record_bytes_saved(record: &serde_json::Value) -> units::ByteSize {
+    let size_of = |key: &str| -> units::ByteSize {
+        record
+            .get(key)
+            .and_then(|v| serde_json::from_value::<units::ByteSize>(v.clone()).ok())
+            .unwrap_or_default()
+    };
+
+    let input = size_of("inputSizeBytes");
+    let output = size_of("outputSizeBytes");
+    if input.bytes() > 0 && output.bytes() > 0 {
+        input.saturating_sub(output)
+    } else {
+        units::ByteSize::ZERO
+    }
+}
+
+/// Aggregate completed renders by weekday and hour of day (local time) for the usage heatmap
+#[tauri::command]
+fn get_statistics_heatmap() -> Result<StatisticsHeatmap, String> {
+    use chrono::{Datelike, Timelike};
+
+    let stats: serde_json::Value = serde_json::from_str(&load_statistics()?)
+        .map_err(|e| format!("Failed to parse statistics: {}", e))?;
+
+    let mut buckets: Vec<HeatmapBucket> = (0..7 * 24)
+        .map(|i| HeatmapBucket {
+            weekday: i / 24,
+            hour: i % 24,
+            ..Default::default()
+        })
+        .collect();
+
+    for record in stats["renders"].as_array().into_iter().flatten() {
+        if record["status"].as_str() != Some("completed") {
+            continue;
+        }
+
+        let timestamp = record["completedAt"]
+            .as_str()
+            .or_else(|| record["createdAt"].as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&chrono::Local));
+        let Some(timestamp) = timestamp else {
+            continue;
+        };
+
+        let index = (timestamp.weekday().num_days_from_monday() * 24 + timestamp.hour()) as usize;
+        let bucket = &mut buckets[index];
+        bucket.renders += 1;
+        bucket.minutes_encoded += record["renderTime"].as_f64().unwrap_or(0.0) / 60.0;
+        bucket.bytes_saved += record_bytes_saved(record);
+    }
+
+    for bucket in &mut buckets {
+        bucket.gb_saved = bucket.bytes_saved.as_gib();
+    }
+
+    Ok(StatisticsHeatmap {
+        max_renders: buckets.iter().map(|b| b.renders).max().unwrap_or(0),
+        max_minutes_encoded: buckets.iter().map(|b| b.minutes_encoded).fold(0.0, f64::max),
+        max_gb_saved: buckets.iter().map(|b| b.gb_saved).fold(0.0, f64::max),
+        buckets,
+    })
+}
+
 // ============================================================================
 // Context Menu Registry Commands (Windows only)
 // ============================================================================
@@ -1935,10 +3223,92 @@ fn get_current_exe_path() -> Result<String, String> {
 }
 
 const CONTEXT_MENU_NAME: &str = "CompressWithSzhimatar";
+const CONTEXT_MENU_LABEL: &str = "Сжать Сжиматором";
+#[cfg(windows)]
+const FOLDER_CONTEXT_MENU_LABEL: &str = "Сжать все видео Сжиматором";
 const VIDEO_EXTENSIONS: &[&str] = &[
     ".mp4", ".mkv", ".avi", ".mov", ".wmv", ".flv", ".webm", ".m4v", ".mpeg", ".mpg", ".3gp",
 ];
 
+/// One verb of the context submenu (also a file manager entry, see file_manager.rs)
+#[derive(Debug, Clone, PartialEq)]
+struct ContextMenuEntry {
+    /// Registry key under shell\; Explorer sorts the submenu by it
+    key: String,
+    label: String,
+    preset: Option<String>,
+}
+
+/// Submenu verbs: the current settings first, then one per saved preset
+fn context_menu_entries(presets: &[String]) -> Vec<ContextMenuEntry> {
+    let mut entries = vec![ContextMenuEntry {
+        key: "00_current".to_string(),
+        label: "Текущие настройки".to_string(),
+        preset: None,
+    }];
+    entries.extend(presets.iter().enumerate().map(|(i, name)| {
+        let safe: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        ContextMenuEntry {
+            key: format!("{:02}_{}", i + 1, safe),
+            label: name.clone(),
+            preset: Some(name.clone()),
+        }
+    }));
+    entries
+}
+
+/// Where the verb is registered: (shell key path, path placeholder, label). Files get
+/// "%1"; a folder's background menu only knows the folder through "%V". Folder
+/// launches are expanded into their videos by cli::parse_args.
+#[cfg(windows)]
+fn context_menu_targets() -> Vec<(String, &'static str, &'static str)> {
+    let mut targets: Vec<_> = VIDEO_EXTENSIONS
+        .iter()
+        .map(|ext| {
+            let shell_path = format!(r"SystemFileAssociations\{}\shell", ext);
+            (shell_path, "%1", CONTEXT_MENU_LABEL)
+        })
+        .collect();
+    let folder_label = FOLDER_CONTEXT_MENU_LABEL;
+    targets.push((r"Directory\shell".to_string(), "%1", folder_label));
+    targets.push((r"Directory\Background\shell".to_string(), "%V", folder_label));
+    targets
+}
+
+/// Command line Explorer runs for an entry (`placeholder` is the clicked path)
+#[cfg(windows)]
+fn context_menu_command(exe_path: &str, preset: Option<&str>, placeholder: &str) -> String {
+    match preset {
+        Some(preset) => format!(r#""{}" --preset "{}" "{}""#, exe_path, preset, placeholder),
+        None => format!(r#""{}" "{}""#, exe_path, placeholder),
+    }
+}
+
+/// Command of a registered verb: its own, or the first submenu entry's
+#[cfg(windows)]
+fn registered_context_command(key: &RegKey) -> Option<String> {
+    key.open_subkey("command")
+        .or_else(|_| key.open_subkey(r"shell\00_current\command"))
+        .ok()
+        .and_then(|command_key| command_key.get_value("").ok())
+}
+
+/// Re-register the context menu after presets changed (only if it is registered)
+fn refresh_context_menu() {
+    if matches!(check_context_menu_status(), Ok(status) if status.enabled) {
+        #[cfg(windows)]
+        let result = add_context_menu();
+        #[cfg(not(windows))]
+        let result = file_manager::install_file_manager_integration();
+        if let Err(e) = result {
+            let _ = write_log(format!("[CONTEXT MENU] Refresh after preset change failed: {}", e));
+        }
+    }
+}
+
 /// Check if context menu is registered and valid
 #[tauri::command]
 fn check_context_menu_status() -> Result<ContextMenuStatus, String> {
@@ -1956,10 +3326,10 @@ fn check_context_menu_status() -> Result<ContextMenuStatus, String> {
 
         match hkcr.open_subkey(&key_path) {
             Ok(key) => {
-                // Key exists, check command
-                let command_key = match key.open_subkey("command") {
-                    Ok(k) => k,
-                    Err(_) => {
+                // Key exists, check command (flat verb or submenu)
+                let registered_cmd = match registered_context_command(&key) {
+                    Some(cmd) => cmd,
+                    None => {
                         return Ok(ContextMenuStatus {
                             enabled: false,
                             registry_path: format!(
@@ -1973,7 +3343,6 @@ fn check_context_menu_status() -> Result<ContextMenuStatus, String> {
                     }
                 };
 
-                let registered_cmd: String = command_key.get_value("").unwrap_or_default();
                 let exe_valid = registered_cmd.contains(&exe_path);
 
                 Ok(ContextMenuStatus {
@@ -2002,104 +3371,134 @@ fn check_context_menu_status() -> Result<ContextMenuStatus, String> {
 
     #[cfg(not(windows))]
     {
-        Err("Context menu is only supported on Windows".to_string())
+        Ok(file_manager::integration_status())
     }
 }
 
-/// Add context menu entry to Windows registry for all video extensions
+/// Add context menu entry to Windows registry for all video extensions and folders.
+/// With saved presets it is a cascading submenu with one entry per preset. Linux and
+/// macOS use install_file_manager_integration instead.
 #[tauri::command]
 fn add_context_menu() -> Result<(), String> {
     #[cfg(windows)]
     {
-        let exe_path = get_current_exe_path()?;
-        let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
+        write_context_menu(&RegKey::predef(HKEY_CLASSES_ROOT))
+    }
+
+    #[cfg(not(windows))]
+    {
+        Err("The Explorer context menu is only available on Windows".to_string())
+    }
+}
+
+/// Write the context menu verbs under `classes`: HKEY_CLASSES_ROOT, or
+/// HKLM\SOFTWARE\Classes for a machine-wide registration by the elevated helper
+#[cfg(windows)]
+fn write_context_menu(classes: &RegKey) -> Result<(), String> {
+    let exe_path = get_current_exe_path()?;
+
+    // Helper to check for admin required error
+    fn check_admin_error<T>(result: Result<T, std::io::Error>) -> Result<T, String> {
+        result.map_err(|e| {
+            let err_str = e.to_string();
+            if err_str.contains("Access is denied") || e.raw_os_error() == Some(5) {
+                "ADMIN_REQUIRED".to_string()
+            } else {
+                format!("Registry error: {}", err_str)
+            }
+        })
+    }
 
-        // Helper to check for admin required error
-        fn check_admin_error<T>(result: Result<T, std::io::Error>) -> Result<T, String> {
-            result.map_err(|e| {
-                let err_str = e.to_string();
-                if err_str.contains("Access is denied") || e.raw_os_error() == Some(5) {
-                    "ADMIN_REQUIRED".to_string()
-                } else {
-                    format!("Registry error: {}", err_str)
-                }
-            })
+    let presets = preset_names();
+    let entries = context_menu_entries(&presets);
+
+    // Register for each video extension and for folders
+    for (shell_path, placeholder, label) in context_menu_targets() {
+        let key_path = format!(r"{}\{}", shell_path, CONTEXT_MENU_NAME);
+
+        // Start clean so entries of deleted presets disappear
+        if let Ok(shell_key) = classes.open_subkey_with_flags(&shell_path, KEY_WRITE) {
+            let _ = shell_key.delete_subkey_all(CONTEXT_MENU_NAME);
         }
 
-        // Register for each video extension
-        for ext in VIDEO_EXTENSIONS {
-            let key_path = format!(
-                r"SystemFileAssociations\{}\shell\{}",
-                ext, CONTEXT_MENU_NAME
-            );
+        // Create main key
+        let (key, _) = check_admin_error(classes.create_subkey(&key_path))?;
 
-            // Create main key
-            let (key, _) = check_admin_error(hkcr.create_subkey(&key_path))?;
+        // Set icon
+        check_admin_error(key.set_value("Icon", &format!("{},0", exe_path)))?;
 
+        if presets.is_empty() {
             // Set display name
-            check_admin_error(key.set_value("", &"Сжать Сжиматором"))?;
-
-            // Set icon
-            check_admin_error(key.set_value("Icon", &format!("{},0", exe_path)))?;
+            check_admin_error(key.set_value("", &label))?;
 
             // Create command subkey
             let (command_key, _) = check_admin_error(key.create_subkey("command"))?;
 
             // Set command
-            let command = format!(r#""{}" "%1""#, exe_path);
+            let command = context_menu_command(&exe_path, None, placeholder);
             check_admin_error(command_key.set_value("", &command))?;
+            continue;
         }
 
-        Ok(())
+        // Cascading submenu (Windows 7+): MUIVerb + empty SubCommands + nested shell
+        check_admin_error(key.set_value("MUIVerb", &label))?;
+        check_admin_error(key.set_value("SubCommands", &""))?;
+        for entry in &entries {
+            let (entry_key, _) =
+                check_admin_error(key.create_subkey(format!(r"shell\{}", entry.key)))?;
+            check_admin_error(entry_key.set_value("MUIVerb", &entry.label))?;
+            let (command_key, _) = check_admin_error(entry_key.create_subkey("command"))?;
+            let command = context_menu_command(&exe_path, entry.preset.as_deref(), placeholder);
+            check_admin_error(command_key.set_value("", &command))?;
+        }
     }
 
-    #[cfg(not(windows))]
-    {
-        Err("Context menu is only supported on Windows".to_string())
-    }
+    Ok(())
 }
 
-/// Remove context menu entry from Windows registry for all video extensions
+/// Remove context menu entry from Windows registry for all video extensions and folders
 #[tauri::command]
 fn remove_context_menu() -> Result<(), String> {
     #[cfg(windows)]
     {
-        let hkcr = RegKey::predef(HKEY_CLASSES_ROOT);
+        delete_context_menu(&RegKey::predef(HKEY_CLASSES_ROOT))
+    }
 
-        // Remove for each video extension
-        for ext in VIDEO_EXTENSIONS {
-            let shell_path = format!(r"SystemFileAssociations\{}\shell", ext);
+    #[cfg(not(windows))]
+    {
+        Err("The Explorer context menu is only available on Windows".to_string())
+    }
+}
 
-            // Try to open shell key with write access
-            if let Ok(shell_key) = hkcr.open_subkey_with_flags(&shell_path, KEY_WRITE) {
-                // Try to delete the key tree, ignore if not exists
-                let _ = shell_key.delete_subkey_all(CONTEXT_MENU_NAME);
-            }
+/// Delete the context menu verbs under `classes` (see write_context_menu)
+#[cfg(windows)]
+fn delete_context_menu(classes: &RegKey) -> Result<(), String> {
+    // Remove for each video extension and for folders
+    for (shell_path, _, _) in context_menu_targets() {
+        // Try to open shell key with write access
+        if let Ok(shell_key) = classes.open_subkey_with_flags(&shell_path, KEY_WRITE) {
+            // Try to delete the key tree, ignore if not exists
+            let _ = shell_key.delete_subkey_all(CONTEXT_MENU_NAME);
         }
+    }
 
-        // Verify at least one was removed by checking if any still exist
-        let test_ext = VIDEO_EXTENSIONS[0];
-        let key_path = format!(
-            r"SystemFileAssociations\{}\shell\{}",
-            test_ext, CONTEXT_MENU_NAME
-        );
-
-        if hkcr.open_subkey(&key_path).is_ok() {
-            // Key still exists, probably need admin rights
-            return Err("ADMIN_REQUIRED".to_string());
-        }
+    // Verify at least one was removed by checking if any still exist
+    let test_ext = VIDEO_EXTENSIONS[0];
+    let key_path = format!(
+        r"SystemFileAssociations\{}\shell\{}",
+        test_ext, CONTEXT_MENU_NAME
+    );
 
-        Ok(())
+    if classes.open_subkey(&key_path).is_ok() {
+        // Key still exists, probably need admin rights
+        return Err("ADMIN_REQUIRED".to_string());
     }
 
-    #[cfg(not(windows))]
-    {
-        Err("Context menu is only supported on Windows".to_string())
-    }
+    Ok(())
 }
 
 // ============================================================================
-// SIMPLE UPDATE SYSTEM (NO SIGNING)
+// SIMPLE UPDATE SYSTEM (MINISIGN-SIGNED ARTIFACTS)
 // ============================================================================
 
 use sha2::{Digest, Sha256};
@@ -2110,15 +3509,181 @@ fn get_updates_dir() -> PathBuf {
     get_app_data_dir().join("updates")
 }
 
-/// Download update file from URL with progress reporting
+/// Attempts of an update download before giving up
+const UPDATE_DOWNLOAD_ATTEMPTS: u32 = 5;
+/// Longest wait between two attempts
+const UPDATE_RETRY_MAX_DELAY_SECS: u64 = 30;
+
+static UPDATE_DOWNLOAD_CANCELLED: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+
+fn update_download_cancelled() -> bool {
+    UPDATE_DOWNLOAD_CANCELLED.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+/// Why one download attempt ended early
+enum DownloadError {
+    /// Worth another attempt; the partial file is kept and resumed
+    Retry(String),
+    Fatal(String),
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<total>` header
+fn content_range_start(response: &reqwest::blocking::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Download `url` into `part_path`, continuing after the bytes already there
+fn download_update_attempt(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &std::path::Path,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), DownloadError> {
+    download_attempt(client, url, part_path, update_download_cancelled, |downloaded, total| {
+        let _ = app_handle.emit_all(
+            "update-download-progress",
+            serde_json::json!({
+                "downloaded": downloaded,
+                "total": total
+            }),
+        );
+    })
+}
+
+/// Download `url` into `part_path`, continuing after the bytes already there.
+/// `on_progress` gets the bytes downloaded so far and the total (0 when unknown).
+fn download_attempt(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part_path: &std::path::Path,
+    cancelled: impl Fn() -> bool,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(), DownloadError> {
+    let mut offset = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request
+        .send()
+        .map_err(|e| DownloadError::Retry(format!("Download request failed: {}", e)))?;
+
+    let status = response.status();
+    let mut file = match status {
+        reqwest::StatusCode::PARTIAL_CONTENT if content_range_start(&response) == Some(offset) => {
+            fs::OpenOptions::new()
+                .append(true)
+                .open(part_path)
+                .map_err(|e| DownloadError::Fatal(format!("Failed to open file: {}", e)))?
+        }
+        reqwest::StatusCode::PARTIAL_CONTENT | reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+            // The partial file does not match what the server has; start over
+            let _ = fs::remove_file(part_path);
+            return Err(DownloadError::Retry(format!(
+                "Server cannot resume at byte {} ({})",
+                offset, status
+            )));
+        }
+        _ if status.is_success() => {
+            // Range ignored: the full file follows
+            offset = 0;
+            fs::File::create(part_path)
+                .map_err(|e| DownloadError::Fatal(format!("Failed to create file: {}", e)))?
+        }
+        _ if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            return Err(DownloadError::Retry(format!(
+                "Download failed with status: {}",
+                status
+            )));
+        }
+        _ => {
+            return Err(DownloadError::Fatal(format!(
+                "Download failed with status: {}",
+                status
+            )));
+        }
+    };
+
+    let total_size = response.content_length().map_or(0, |length| offset + length);
+    let mut downloaded = offset;
+    let mut reader = response;
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        if cancelled() {
+            return Err(DownloadError::Fatal("Download cancelled".to_string()));
+        }
+        let bytes_read = reader
+            .read(&mut buffer)
+            .map_err(|e| DownloadError::Retry(format!("Failed to read response: {}", e)))?;
+
+        if bytes_read == 0 {
+            break;
+        }
+
+        file.write_all(&buffer[..bytes_read])
+            .map_err(|e| DownloadError::Fatal(format!("Failed to write file: {}", e)))?;
+
+        downloaded += bytes_read as u64;
+        on_progress(downloaded, total_size);
+    }
+
+    file.flush()
+        .map_err(|e| DownloadError::Fatal(format!("Failed to flush file: {}", e)))?;
+    if total_size > 0 && downloaded < total_size {
+        return Err(DownloadError::Retry(format!(
+            "Connection closed after {} of {} bytes",
+            downloaded, total_size
+        )));
+    }
+    Ok(())
+}
+
+/// Fetch the minisign signature of an update next to the downloaded file
+fn download_update_signature(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    signature_path: &std::path::Path,
+) -> Result<(), String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to download update signature: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Update signature not available (status {})",
+            response.status()
+        ));
+    }
+    let signature = response
+        .text()
+        .map_err(|e| format!("Failed to download update signature: {}", e))?;
+    fs::write(signature_path, signature)
+        .map_err(|e| format!("Failed to save update signature: {}", e))
+}
+
+/// Download update file from URL with progress reporting. An interrupted download is
+/// kept as `<file>.part` and resumed with an HTTP Range request, on the next attempt
+/// (up to UPDATE_DOWNLOAD_ATTEMPTS, with exponential backoff) or the next call.
+/// The file is kept only when its minisign signature (`signature_url`, by default
+/// `<url>.minisig`) verifies; the signature is stored next to it for apply_update.
 #[tauri::command]
 async fn download_update(
     app_handle: tauri::AppHandle,
     url: String,
     expected_hash: Option<String>,
+    signature_url: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    use std::io::Write;
-
     // Create updates directory
     let updates_dir = get_updates_dir();
     fs::create_dir_all(&updates_dir).map_err(|e| format!("Failed to create updates dir: {}", e))?;
@@ -2126,6 +3691,13 @@ async fn download_update(
     // Determine filename from URL
     let filename = url.split('/').last().unwrap_or("update.exe");
     let download_path = updates_dir.join(filename);
+    let part_path = updates_dir.join(format!("{}.part", filename));
+    let signature_path =
+        updates_dir.join(format!("{}.{}", filename, updater::SIGNATURE_EXTENSION));
+    let signature_url = signature_url
+        .unwrap_or_else(|| format!("{}.{}", url, updater::SIGNATURE_EXTENSION));
+
+    UPDATE_DOWNLOAD_CANCELLED.store(false, std::sync::atomic::Ordering::SeqCst);
 
     // Download file using blocking client in spawn_blocking
     let url_clone = url.clone();
@@ -2135,74 +3707,45 @@ async fn download_update(
 
     let result = tokio::task::spawn_blocking(move || {
         // Create HTTP client
-        let client = reqwest::blocking::Client::builder()
+        let client = network::client_builder()?
             .timeout(std::time::Duration::from_secs(300))
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-        // Start download
-        let response = client
-            .get(&url_clone)
-            .send()
-            .map_err(|e| format!("Download request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!(
-                "Download failed with status: {}",
-                response.status()
-            ));
-        }
-
-        let total_size = response.content_length().unwrap_or(0);
-        let mut downloaded: u64 = 0;
-
-        // Create file
-        let mut file = std::fs::File::create(&download_path_clone)
-            .map_err(|e| format!("Failed to create file: {}", e))?;
-
-        // Create hasher for integrity check
-        let mut hasher = Sha256::new();
-
-        // Read and write in chunks with progress
-        let mut reader = response;
-        let mut buffer = [0u8; 8192];
-
+        let mut attempt = 1;
         loop {
-            let bytes_read = reader
-                .read(&mut buffer)
-                .map_err(|e| format!("Failed to read response: {}", e))?;
-
-            if bytes_read == 0 {
-                break;
+            match download_update_attempt(&client, &url_clone, &part_path, &app_handle_clone) {
+                Ok(()) => break,
+                Err(DownloadError::Fatal(e)) => return Err(e),
+                Err(DownloadError::Retry(e)) if attempt >= UPDATE_DOWNLOAD_ATTEMPTS => {
+                    return Err(e)
+                }
+                Err(DownloadError::Retry(e)) => {
+                    let delay = (1u64 << (attempt - 1)).min(UPDATE_RETRY_MAX_DELAY_SECS);
+                    let _ = write_log(format!(
+                        "[UPDATE] Download attempt {} failed ({}), retrying in {}s",
+                        attempt, e, delay
+                    ));
+                    // Sleep in slices so a cancel is not held up by the backoff
+                    let resume_at =
+                        std::time::Instant::now() + std::time::Duration::from_secs(delay);
+                    while std::time::Instant::now() < resume_at {
+                        if update_download_cancelled() {
+                            return Err("Download cancelled".to_string());
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                    }
+                    attempt += 1;
+                }
             }
-
-            file.write_all(&buffer[..bytes_read])
-                .map_err(|e| format!("Failed to write file: {}", e))?;
-
-            hasher.update(&buffer[..bytes_read]);
-
-            downloaded += bytes_read as u64;
-
-            // Emit progress event
-            let _ = app_handle_clone.emit_all(
-                "update-download-progress",
-                serde_json::json!({
-                    "downloaded": downloaded,
-                    "total": total_size
-                }),
-            );
         }
 
-        file.flush()
-            .map_err(|e| format!("Failed to flush file: {}", e))?;
-        drop(file);
-
         // Verify hash if provided
         if let Some(expected) = expected_hash_clone {
-            let hash = hex::encode(hasher.finalize());
+            let hash = hash::hash_file(&part_path, hash::HashAlgorithm::Sha256)?;
             if hash.to_lowercase() != expected.to_lowercase() {
                 // Delete file if hash doesn't match
-                let _ = std::fs::remove_file(&download_path_clone);
+                let _ = std::fs::remove_file(&part_path);
                 return Err(format!(
                     "Hash mismatch: expected {}, got {}",
                     expected, hash
@@ -2210,6 +3753,17 @@ async fn download_update(
             }
         }
 
+        // Refuse unsigned or tampered files before they reach the updates folder
+        let verified = download_update_signature(&client, &signature_url, &signature_path)
+            .and_then(|()| updater::verify_signature(&part_path, &signature_path));
+        if let Err(e) = verified {
+            let _ = std::fs::remove_file(&part_path);
+            let _ = std::fs::remove_file(&signature_path);
+            return Err(e);
+        }
+
+        std::fs::rename(&part_path, &download_path_clone)
+            .map_err(|e| format!("Failed to finish download: {}", e))?;
         Ok(download_path_clone.to_string_lossy().to_string())
     })
     .await
@@ -2217,11 +3771,7 @@ async fn download_update(
 
     match result {
         Ok(path) => {
-            // If it's a zip file, extract it
-            if filename.ends_with(".zip") {
-                extract_update_zip(&PathBuf::from(&path))?;
-            }
-
+            let _ = write_log(format!("[UPDATE] Downloaded and verified {}", path));
             Ok(serde_json::json!({
                 "success": true,
                 "path": path
@@ -2234,14 +3784,21 @@ async fn download_update(
     }
 }
 
-/// Extract zip file to updates directory
-fn extract_update_zip(zip_path: &PathBuf) -> Result<(), String> {
+/// Stop a running update download; the partial file is kept for resuming
+#[tauri::command]
+fn cancel_update_download() {
+    UPDATE_DOWNLOAD_CANCELLED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Extract the executable of a zip to updates directory
+fn extract_update_zip(zip_path: &PathBuf) -> Result<PathBuf, String> {
     let file = std::fs::File::open(zip_path).map_err(|e| format!("Failed to open zip: {}", e))?;
 
     let mut archive =
         zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
 
     let updates_dir = get_updates_dir();
+    let mut extracted = None;
 
     for i in 0..archive.len() {
         let mut file = archive
@@ -2260,46 +3817,89 @@ fn extract_update_zip(zip_path: &PathBuf) -> Result<(), String> {
 
             std::io::copy(&mut file, &mut outfile)
                 .map_err(|e| format!("Failed to extract file: {}", e))?;
+            extracted = Some(outpath);
         }
     }
 
-    // Remove zip after extraction
-    let _ = std::fs::remove_file(zip_path);
+    extracted.ok_or_else(|| "No executable in the update archive".to_string())
+}
 
-    Ok(())
+/// Newest downloaded update with its signature (`<file>.minisig`) next to it
+fn find_signed_update(updates_dir: &std::path::Path) -> Result<(PathBuf, PathBuf), String> {
+    std::fs::read_dir(updates_dir)
+        .map_err(|e| format!("Failed to read updates dir: {}", e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let mut signature = path.clone().into_os_string();
+            signature.push(format!(".{}", updater::SIGNATURE_EXTENSION));
+            let signature = PathBuf::from(signature);
+            signature.is_file().then_some((path, signature))
+        })
+        .max_by_key(|(path, _)| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .ok_or_else(|| "No signed update found; download the update again".to_string())
 }
 
 /// Apply downloaded update - creates a batch script and restarts
 #[tauri::command]
-fn apply_update() -> Result<serde_json::Value, String> {
+fn apply_update(app_handle: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    shutdown::ensure_no_renders()?;
     let updates_dir = get_updates_dir();
 
-    // Find the new exe
-    let new_exe = std::fs::read_dir(&updates_dir)
-        .map_err(|e| format!("Failed to read updates dir: {}", e))?
-        .filter_map(|e| e.ok())
-        .find(|e| {
-            e.path()
-                .extension()
-                .map(|ext| ext == "exe")
-                .unwrap_or(false)
-        })
-        .ok_or("No update executable found")?;
+    // Verify the download again: the file may have changed since download_update
+    let (artifact, signature) = find_signed_update(&updates_dir)?;
+    if let Err(e) = updater::verify_signature(&artifact, &signature) {
+        let _ = write_log(format!("[UPDATE] Refused {}: {}", artifact.display(), e));
+        return Err(e);
+    }
+
+    let new_exe_path = match updater::artifact_kind(&artifact) {
+        Some(updater::UpdateArtifact::Zip) => extract_update_zip(&artifact)?,
+        Some(updater::UpdateArtifact::Executable) => artifact,
+        None => {
+            let _ = write_log(format!("[UPDATE] Cannot install {}", artifact.display()));
+            return Err(format!(
+                "{} cannot be installed automatically; get it from the release page",
+                artifact.display()
+            ));
+        }
+    };
 
-    let new_exe_path = new_exe.path();
+    // Get current exe path (the AppImage itself, not its temporary mount)
+    let current_exe = PathBuf::from(file_manager::exe_path()?);
 
-    // Get current exe path
-    let current_exe =
-        std::env::current_exe().map_err(|e| format!("Failed to get current exe: {}", e))?;
+    // Keep the running binary for rollback_update
+    let backup = update_rollback::backup_path(&current_exe);
+    update_rollback::record_update(&backup)?;
+
+    // Run update script, then exit the way closing the window does
+    spawn_replace_script(&new_exe_path, &current_exe, Some(&backup))?;
+    shutdown::exit(&app_handle);
+    Ok(serde_json::json!({ "success": true }))
+}
+
+/// Start a script that waits for the app to exit, copies `source` over `target` (after
+/// copying `target` to `backup`, when given) and starts `target` again
+fn spawn_replace_script(
+    source: &std::path::Path,
+    target: &std::path::Path,
+    backup: Option<&std::path::Path>,
+) -> Result<(), String> {
+    let updates_dir = get_updates_dir();
+    fs::create_dir_all(&updates_dir).map_err(|e| format!("Failed to create updates dir: {}", e))?;
 
-    // Create and run update script, then exit
     #[cfg(target_os = "windows")]
     {
         let batch_path = updates_dir.join("update.bat");
 
         // Clean paths to support Cyrillic: remove UNC prefix
-        let src = new_exe_path.to_string_lossy().replace("\\\\?\\", "");
-        let dst = current_exe.to_string_lossy().replace("\\\\?\\", "");
+        let clean = |path: &std::path::Path| path.to_string_lossy().replace("\\\\?\\", "");
+        let src = clean(source);
+        let dst = clean(target);
+        let backup_line = backup
+            .map(|backup| format!("copy /y \"{}\" \"{}\"\r\n", dst, clean(backup)))
+            .unwrap_or_default();
 
         // Minimal batch script, CRLF line endings, no leading spaces
         let batch_content = format!(
@@ -2307,10 +3907,11 @@ fn apply_update() -> Result<serde_json::Value, String> {
 chcp 65001 > nul\r\n\
 timeout /t 3 /nobreak > nul\r\n\
 taskkill /F /IM Szhimatar.exe /T > nul 2>&1\r\n\
+{}\
 copy /y \"{}\" \"{}\"\r\n\
 start \"\" \"{}\"\r\n\
 del \"%~f0\"",
-            src, dst, dst
+            backup_line, src, dst, dst
         );
 
         std::fs::write(&batch_path, batch_content.as_bytes())
@@ -2320,25 +3921,27 @@ del \"%~f0\"",
             .args(["/C", &batch_path.to_string_lossy()])
             .spawn()
             .map_err(|e| format!("Failed to start update script: {}", e))?;
-
-        std::process::exit(0);
     }
 
     #[cfg(not(target_os = "windows"))]
     {
         let script_path = updates_dir.join("update.sh");
+        let backup_line = backup
+            .map(|backup| format!("cp -f \"{}\" \"{}\"\n", target.display(), backup.display()))
+            .unwrap_or_default();
         let script_content = format!(
             r#"#!/bin/bash
 sleep 2
-cp -f "{}" "{}"
+{}cp -f "{}" "{}"
 chmod +x "{}"
 "{}" &
 rm -f "$0"
 "#,
-            new_exe_path.display(),
-            current_exe.display(),
-            current_exe.display(),
-            current_exe.display()
+            backup_line,
+            source.display(),
+            target.display(),
+            target.display(),
+            target.display()
         );
 
         std::fs::write(&script_path, script_content)
@@ -2348,9 +3951,9 @@ rm -f "$0"
             .arg(&script_path)
             .spawn()
             .map_err(|e| format!("Failed to start update script: {}", e))?;
-
-        std::process::exit(0);
     }
+
+    Ok(())
 }
 
 /// Restart the application
@@ -2360,29 +3963,10 @@ fn restart_app(app_handle: tauri::AppHandle) {
     app_handle.exit(0);
 }
 
-/// Get files passed via command line arguments
+/// Get files passed via command line arguments (folders expand into their videos)
 #[tauri::command]
 fn get_cli_files() -> Vec<String> {
-    let args: Vec<String> = std::env::args().skip(1).collect();
-
-    // Filter to only video files that exist
-    let video_extensions = [
-        "mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "m4v", "mpeg", "mpg", "3gp",
-    ];
-
-    args.into_iter()
-        .filter(|arg| {
-            let path = std::path::Path::new(arg);
-            if !path.exists() || !path.is_file() {
-                return false;
-            }
-            if let Some(ext) = path.extension() {
-                video_extensions.contains(&ext.to_string_lossy().to_lowercase().as_str())
-            } else {
-                false
-            }
-        })
-        .collect()
+    cli::get_cli_launch().files
 }
 
 // ============================================================================
@@ -2468,26 +4052,14 @@ async fn get_preview_frame(
     ]);
 
     // Run FFmpeg
-    #[cfg(target_os = "windows")]
-    let output = {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        Command::new(&config.ffmpeg_path)
-            .creation_flags(CREATE_NO_WINDOW)
-            .args(&cmd_args)
-            .output()
-            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?
-    };
-
-    #[cfg(not(target_os = "windows"))]
-    let output = Command::new(&config.ffmpeg_path)
-        .args(&cmd_args)
-        .output()
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg error: {}", stderr));
+    let output = proc::run_captured(
+        proc::command(&config.ffmpeg_path).args(&cmd_args),
+        &proc::RunOptions::with_timeout(60).label("preview frame"),
+    )
+    .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.success {
+        return Err(format!("FFmpeg error: {}", output.stderr));
     }
 
     // Read file and encode to base64
@@ -2509,7 +4081,7 @@ async fn get_preview_frame(
 async fn get_preview_video(
     input_path: String,
     time_seconds: f64,
-    duration: f64,
+    duration: units::Duration,
     settings: PreviewSettings,
 ) -> Result<String, String> {
     let config = load_ffmpeg_config();
@@ -2577,11 +4149,35 @@ async fn get_preview_video(
     // Ensure yuv420p for compatibility (MUST be last in filter chain)
     filters.push("format=yuv420p".to_string());
 
+    // AV1 (SVT-AV1 / av1_nvenc / av1_qsv) is resolved by the shared arg builder
+    let av1_video = if settings.codec == "av1" {
+        let options = encode_args::EncodeOptions {
+            codec: settings.codec.clone(),
+            crf: settings.crf.clone(),
+            bitrate: settings.bitrate.clone().unwrap_or_default(),
+            rate_control: encode_args::RateControl::Auto,
+            preset: settings
+                .preset
+                .clone()
+                .unwrap_or_else(|| "medium".to_string()),
+            prefer_gpu: settings.prefer_gpu.unwrap_or(false),
+            hardware: None,
+            orientation: None,
+        };
+        let (resolved, _) =
+            encode_args::resolve_video_encoder(&options, &encode_args::available_encoder_names());
+        Some((options, resolved))
+    } else {
+        None
+    };
+
     // Determine encoder based on codec and GPU preference
     let use_nvenc = settings.prefer_gpu.unwrap_or(false)
         && (settings.codec == "h264" || settings.codec == "h265" || settings.codec == "hevc");
 
-    let encoder = if use_nvenc {
+    let encoder = if let Some((_, resolved)) = &av1_video {
+        resolved.name.as_str()
+    } else if use_nvenc {
         if settings.codec == "h265" || settings.codec == "hevc" {
             "hevc_nvenc"
         } else {
@@ -2601,7 +4197,7 @@ async fn get_preview_video(
         "-i".to_string(),
         input_path.clone(),
         "-t".to_string(),
-        format!("{:.1}", duration.min(5.0)), // Max 5 seconds for preview
+        format!("{:.1}", duration.as_secs_f64().min(5.0)), // Max 5 seconds for preview
         "-c:v".to_string(),
         encoder.to_string(),
     ];
@@ -2614,7 +4210,9 @@ async fn get_preview_video(
         .unwrap_or(false);
     let has_crf = !settings.crf.is_empty() && settings.crf != "auto";
 
-    if use_nvenc {
+    if let Some((options, resolved)) = &av1_video {
+        cmd_args.extend(encode_args::video_quality_args(options, resolved));
+    } else if use_nvenc {
         // NVENC rate control - MUST match RenderService.ts logic exactly
         if has_bitrate {
             let bitrate_val: f64 = settings
@@ -2778,12 +4376,16 @@ async fn get_preview_video(
         cmd_args.push(filters.join(","));
     }
 
+    // Hardware device / upload for QSV and VAAPI (uploaded frames keep their hw pix_fmt)
+    encode_args::ensure_hw_device_args(&mut cmd_args);
+    if encode_args::hw_upload_filter(encoder).is_none() {
+        cmd_args.extend(["-pix_fmt".to_string(), "yuv420p".to_string()]);
+    }
+
     // Audio - disable for preview (speed)
     // Output settings
     cmd_args.extend([
         "-an".to_string(),
-        "-pix_fmt".to_string(),
-        "yuv420p".to_string(),
         "-movflags".to_string(),
         "+faststart".to_string(),
         "-y".to_string(),
@@ -2801,26 +4403,14 @@ async fn get_preview_video(
     eprintln!("{}", full_cmd);
 
     // Run FFmpeg
-    #[cfg(target_os = "windows")]
-    let output = {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        Command::new(&config.ffmpeg_path)
-            .creation_flags(CREATE_NO_WINDOW)
-            .args(&cmd_args)
-            .output()
-            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?
-    };
-
-    #[cfg(not(target_os = "windows"))]
-    let output = Command::new(&config.ffmpeg_path)
-        .args(&cmd_args)
-        .output()
-        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg error: {}", stderr));
+    let output = proc::run_captured(
+        proc::command(&config.ffmpeg_path).args(&cmd_args),
+        &proc::RunOptions::with_timeout(120).label("preview video"),
+    )
+    .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.success {
+        return Err(format!("FFmpeg error: {}", output.stderr));
     }
 
     // Validate output file exists and has content
@@ -2854,28 +4444,8 @@ async fn get_video_info_for_preview(input_path: String) -> Result<VideoPreviewIn
         return Err("FFprobe not configured".to_string());
     }
 
-    #[cfg(target_os = "windows")]
-    let output = {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        Command::new(&config.ffprobe_path)
-            .creation_flags(CREATE_NO_WINDOW)
-            .args([
-                "-v",
-                "quiet",
-                "-show_entries",
-                "format=duration:stream=width,height,r_frame_rate",
-                "-of",
-                "json",
-                &input_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to run ffprobe: {}", e))?
-    };
-
-    #[cfg(not(target_os = "windows"))]
-    let output = Command::new(&config.ffprobe_path)
-        .args([
+    let output = proc::run_captured(
+        proc::command(&config.ffprobe_path).args([
             "-v",
             "quiet",
             "-show_entries",
@@ -2883,18 +4453,18 @@ async fn get_video_info_for_preview(input_path: String) -> Result<VideoPreviewIn
             "-of",
             "json",
             &input_path,
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+        ]),
+        &proc::RunOptions::with_timeout(30).label("ffprobe preview info"),
+    )
+    .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout)
+    let json: serde_json::Value = serde_json::from_str(&output.stdout)
         .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
 
     let duration = json["format"]["duration"]
         .as_str()
-        .and_then(|s| s.parse::<f64>().ok())
-        .unwrap_or(0.0);
+        .and_then(units::Duration::parse)
+        .unwrap_or_default();
 
     let streams = json["streams"].as_array();
     let (width, height) = streams
@@ -2915,11 +4485,168 @@ async fn get_video_info_for_preview(input_path: String) -> Result<VideoPreviewIn
 
 #[derive(Debug, Serialize, Deserialize)]
 struct VideoPreviewInfo {
-    duration: f64,
+    duration: units::Duration,
     width: u32,
     height: u32,
 }
 
+// ============================================================================
+// SCRUB PREVIEW SPRITES
+// ============================================================================
+
+const SPRITE_THUMB_WIDTH: u32 = 160;
+const SPRITE_COLUMNS: u32 = 10;
+const SPRITE_MAX_FRAMES: u32 = 300;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SpriteFrame {
+    time: f64,
+    x: u32,
+    y: u32,
+}
+
+/// Sprite sheet + frame index used by the timeline hover-scrub preview
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ScrubSpriteIndex {
+    input_path: String,
+    modified: u64,
+    interval_seconds: f64,
+    sprite_path: String,
+    thumb_width: u32,
+    thumb_height: u32,
+    columns: u32,
+    rows: u32,
+    frames: Vec<SpriteFrame>,
+}
+
+fn get_sprite_cache_dir() -> PathBuf {
+    get_app_data_dir().join("cache").join("sprites")
+}
+
+/// Cache key for a source file: hash of the path and its modification time
+fn sprite_cache_key(input_path: &str, modified: u64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(input_path.as_bytes());
+    hasher.update(modified.to_le_bytes());
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+/// Generate a sprite sheet of evenly spaced thumbnails for hover-scrub previews.
+/// Results are cached per file (path + mtime) under ~/.szhimatar/cache/sprites
+#[tauri::command]
+async fn generate_scrub_sprites(
+    input_path: String,
+    interval_seconds: f64,
+) -> Result<ScrubSpriteIndex, String> {
+    let config = load_ffmpeg_config();
+    if config.ffmpeg_path.trim().is_empty() {
+        return Err("FFmpeg not configured".to_string());
+    }
+
+    if !interval_seconds.is_finite() || interval_seconds <= 0.0 {
+        return Err("Interval must be greater than zero".to_string());
+    }
+
+    let modified = fs::metadata(&input_path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Failed to read file metadata: {}", e))?
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let cache_dir = get_sprite_cache_dir().join(sprite_cache_key(&input_path, modified));
+    let index_path = cache_dir.join("index.json");
+
+    // Reuse cached sprite if it was generated with the same interval
+    if let Ok(content) = fs::read_to_string(&index_path) {
+        if let Ok(index) = serde_json::from_str::<ScrubSpriteIndex>(&content) {
+            if (index.interval_seconds - interval_seconds).abs() < f64::EPSILON
+                && PathBuf::from(&index.sprite_path).exists()
+            {
+                return Ok(index);
+            }
+        }
+    }
+
+    let info = get_video_info_for_preview(input_path.clone()).await?;
+    if info.duration.is_zero() || info.width == 0 || info.height == 0 {
+        return Err("Could not determine video duration or dimensions".to_string());
+    }
+
+    // Never exceed the sprite frame budget - widen the interval for long videos
+    let duration = info.duration.as_secs_f64();
+    let mut interval = interval_seconds;
+    if (duration / interval).ceil() > SPRITE_MAX_FRAMES as f64 {
+        interval = duration / SPRITE_MAX_FRAMES as f64;
+    }
+
+    let frame_count = ((duration / interval).ceil() as u32).max(1);
+    let columns = frame_count.min(SPRITE_COLUMNS);
+    let rows = frame_count.div_ceil(columns);
+
+    // Keep thumbnail height even and proportional to the source
+    let thumb_width = SPRITE_THUMB_WIDTH;
+    let thumb_height =
+        (((thumb_width as f64 * info.height as f64 / info.width as f64) / 2.0).round() as u32 * 2)
+            .max(2);
+
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+    let sprite_path = cache_dir.join("sprite.jpg");
+
+    let cmd_args: Vec<String> = vec![
+        "-hide_banner".to_string(),
+        "-i".to_string(),
+        input_path.clone(),
+        "-vf".to_string(),
+        format!(
+            "fps=1/{:.3},scale={}:{},tile={}x{}",
+            interval, thumb_width, thumb_height, columns, rows
+        ),
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-q:v".to_string(),
+        "4".to_string(),
+        "-y".to_string(),
+        sprite_path.to_string_lossy().to_string(),
+    ];
+
+    let output = proc::run_captured(
+        proc::command(&config.ffmpeg_path).args(&cmd_args),
+        &proc::RunOptions::default().label("scrub sprites"),
+    )
+    .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.success {
+        return Err(format!("FFmpeg error: {}", output.stderr));
+    }
+
+    let frames = (0..frame_count)
+        .map(|i| SpriteFrame {
+            time: i as f64 * interval,
+            x: (i % columns) * thumb_width,
+            y: (i / columns) * thumb_height,
+        })
+        .collect();
+
+    let index = ScrubSpriteIndex {
+        input_path,
+        modified,
+        interval_seconds,
+        sprite_path: sprite_path.to_string_lossy().to_string(),
+        thumb_width,
+        thumb_height,
+        columns,
+        rows,
+        frames,
+    };
+
+    let content = serde_json::to_string_pretty(&index)
+        .map_err(|e| format!("Failed to serialize sprite index: {}", e))?;
+    fs::write(&index_path, content).map_err(|e| format!("Failed to write sprite index: {}", e))?;
+
+    Ok(index)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct NetworkProxyVpnStatus {
     proxy_enabled: bool,
@@ -2974,15 +4701,12 @@ fn detect_proxy_settings_windows() -> (bool, Vec<String>) {
 
 #[cfg(windows)]
 fn detect_vpn_interfaces_windows() -> Vec<String> {
-    use std::os::windows::process::CommandExt;
-    const CREATE_NO_WINDOW: u32 = 0x08000000;
-
     let script = "$adapters = Get-NetAdapter -ErrorAction SilentlyContinue | Select-Object Name, InterfaceDescription, Status; $adapters | ConvertTo-Json -Compress";
 
-    let output = Command::new("powershell")
-        .creation_flags(CREATE_NO_WINDOW)
-        .args(["-NoProfile", "-Command", script])
-        .output();
+    let output = proc::run_captured(
+        proc::command("powershell").args(["-NoProfile", "-Command", script]),
+        &proc::RunOptions::with_timeout(15).label("vpn adapters"),
+    );
 
     let keywords = [
         "vpn",
@@ -3016,7 +4740,7 @@ fn detect_vpn_interfaces_windows() -> Vec<String> {
         return matches;
     };
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stdout = output.stdout.trim().to_string();
     if stdout.is_empty() {
         return matches;
     }
@@ -3076,8 +4800,6 @@ fn detect_vpn_interfaces_windows() -> Vec<String> {
 #[cfg(windows)]
 fn detect_clash_activity_windows() -> Vec<String> {
     use std::collections::HashMap;
-    use std::os::windows::process::CommandExt;
-    const CREATE_NO_WINDOW: u32 = 0x08000000;
 
     let mut details: Vec<String> = Vec::new();
     let clash_ports = [7890_u16, 7891, 7892, 7897, 9090, 9091, 9097];
@@ -3090,18 +4812,18 @@ fn detect_clash_activity_windows() -> Vec<String> {
         "flclash",
     ];
 
-    let process_output = Command::new("powershell")
-        .creation_flags(CREATE_NO_WINDOW)
-        .args([
+    let process_output = proc::run_captured(
+        proc::command("powershell").args([
             "-NoProfile",
             "-Command",
             "$p = Get-Process -ErrorAction SilentlyContinue | Select-Object Id, ProcessName; $p | ConvertTo-Json -Compress",
-        ])
-        .output();
+        ]),
+        &proc::RunOptions::with_timeout(15).label("process list"),
+    );
 
     let mut process_map: HashMap<u32, String> = HashMap::new();
     if let Ok(output) = process_output {
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let stdout = output.stdout.trim().to_string();
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
             let mut push_proc = |item: &serde_json::Value| {
                 let pid = item.get("Id").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
@@ -3134,13 +4856,13 @@ fn detect_clash_activity_windows() -> Vec<String> {
         }
     }
 
-    let netstat_output = Command::new("netstat")
-        .creation_flags(CREATE_NO_WINDOW)
-        .args(["-ano", "-p", "tcp"])
-        .output();
+    let netstat_output = proc::run_captured(
+        proc::command("netstat").args(["-ano", "-p", "tcp"]),
+        &proc::RunOptions::with_timeout(15).label("netstat"),
+    );
 
     if let Ok(output) = netstat_output {
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = output.stdout;
 
         for line in stdout.lines() {
             let trimmed = line.trim();
@@ -3227,8 +4949,82 @@ fn main() {
     if let Err(e) = ensure_app_dirs() {
         eprintln!("Failed to create app directories: {}", e);
     }
+    crash::install_panic_hook();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Relaunched through UAC just to write machine-wide registry keys
+    if let Some(code) = elevation::run_helper(&args) {
+        std::process::exit(code);
+    }
+
+    // Later launches (e.g. each context-menu click) hand their files to the running window
+    let listener = match single_instance::acquire(&args) {
+        single_instance::Instance::Secondary => return,
+        single_instance::Instance::Primary(listener) => listener,
+    };
+
+    // A freshly installed version that keeps failing to start is rolled back here
+    update_rollback::check_startup();
+
+    // Daily snapshot of settings and presets
+    if let Err(e) = backups::create_daily_snapshot_if_due() {
+        eprintln!("Failed to create daily backup: {}", e);
+    }
+
+    // FFmpeg processes and unfinished outputs left behind by a crashed session
+    let previous_jobs = orphans::check_previous_session();
+    // Read before the frontend saves its (empty) queue over it
+    resume::load_previous_session(previous_jobs);
+
+    let start_minimized = args.iter().any(|arg| arg == autostart::MINIMIZED_ARG);
 
     tauri::Builder::default()
+        .system_tray(tray::build())
+        .on_system_tray_event(tray::handle_event)
+        .on_window_event(tray::handle_window_event)
+        .setup(move |app| {
+            if let Some(listener) = listener {
+                single_instance::serve(listener, app.handle());
+            }
+
+            if let Err(e) = shortcuts::apply(&app.handle()) {
+                let _ = write_log(format!("[SHORTCUTS] {}", e));
+            }
+            if let Err(e) = http_api::apply(&app.handle()) {
+                let _ = write_log(format!("[HTTP-API] {}", e));
+            }
+            plugins::scan();
+
+            // A freshly installed version that stays up counts as working
+            update_rollback::confirm_startup_later();
+
+            // Started at login with --minimized: stay in the tray
+            if start_minimized {
+                if let Some(window) = app.get_window("main") {
+                    let _ = window.hide();
+                }
+            }
+
+            // Revalidate saved FFmpeg paths off the main thread; only bother the UI on failure
+            let handle = app.handle();
+            std::thread::spawn(move || {
+                let result = revalidate_ffmpeg_paths();
+                // Stored first, so a window asking after the event was sent still sees it
+                if let Ok(mut slot) = FFMPEG_REVALIDATION.lock() {
+                    *slot = Some(result.clone());
+                }
+                if !result.ffmpeg_ok || !result.ffprobe_ok {
+                    let _ = handle.emit_all("ffmpeg-repair-failed", &result);
+                }
+            });
+
+            power::start_power_monitor(app.handle());
+            schedule::start_schedule_monitor(app.handle());
+            idle::start_idle_monitor(app.handle());
+            system_theme::start_theme_monitor(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_settings,
             save_settings,
@@ -3245,46 +5041,159 @@ fn main() {
             check_ffmpeg_status,
             search_ffmpeg_fast,
             search_ffmpeg_deep,
+            ffmpeg_search::cancel_ffmpeg_search,
             set_ffmpeg_paths,
             search_ffmpeg_single,
             resolve_absolute_path,
             get_binary_version,
             save_ffmpeg_paths,
             load_ffmpeg_paths,
+            get_ffmpeg_revalidation_status,
+            encoders::get_encoder_options,
+            encoders::list_encoders,
+            encoders::list_decoders,
+            encoders::list_muxers,
+            ffmpeg_features::check_ffmpeg_features,
+            codec_compat::validate_codec_container,
+            encode_args::build_encode_args,
+            encode_args::validate_audio_source,
+            encode_args::get_default_peak_bitrate,
+            color::validate_lut,
+            encode_args::get_av1_support,
+            gpu::get_gpu_capabilities,
+            gpu::get_nvenc_session_state,
+            gpu::get_qsv_support,
+            gpu::get_amd_support,
+            queue::set_post_queue_action,
+            queue::get_post_queue_action,
+            queue::cancel_post_queue_action,
+            queue::notify_queue_finished,
+            queue::save_queue_state,
+            // Job history commands
+            history::list_job_history,
+            history::rerun_job,
+            history::clear_history,
+            // Interrupted job commands
+            resume::list_interrupted_jobs,
+            resume::resume_interrupted_jobs,
+            resume::dismiss_interrupted_jobs,
+            // Duplicate job commands
+            duplicates::check_duplicate_jobs,
+            // Library commands
+            library::scan_folder,
+            // Media info cache commands
+            media_cache::get_media_info,
+            get_video_durations,
+            // FFmpeg installation commands
+            ffmpeg_versions::list_ffmpeg_installations,
+            ffmpeg_versions::set_active_ffmpeg,
+            ffmpeg_versions::import_ffmpeg_installation,
+            ffmpeg_versions::remove_ffmpeg_installation,
+            ffmpeg_versions::get_preset_ffmpeg_overrides,
+            ffmpeg_versions::set_preset_ffmpeg,
+            media_cache::clear_media_cache,
+            power::get_power_state,
+            schedule::get_queue_schedule_state,
+            idle::get_idle_state,
+            benchmark::run_benchmark,
+            verify::verify_output,
+            hash::compute_file_hash,
+            recycle::list_trashed_sources,
+            recycle::restore_trashed_source,
+            output_path::render_output_path,
+            file_access::check_file_access,
+            network_paths::analyze_render_paths,
             // Preset commands
             list_presets,
             save_preset,
             load_preset,
             load_default_preset,
             delete_preset,
+            // Backup commands
+            backups::list_backups,
+            backups::create_backup,
+            backups::restore_backup,
+            backups::backup_app_data,
+            backups::restore_app_data,
             // Render commands
             run_ffmpeg_render,
+            progress::get_job_speed_history,
+            analysis::analyze_bitrate,
+            analysis::get_audio_peaks,
+            complexity::analyze_complexity,
+            quality_ladder::run_quality_ladder,
+            quality_ladder::cancel_quality_ladder,
+            comparison::generate_comparison,
+            frames::export_frames,
             stop_ffmpeg_render,
+            set_render_priority,
             stop_all_renders,
             get_video_duration,
             get_file_size_bytes,
             write_render_log,
+            validate_filtergraph,
             // Statistics commands
             load_statistics,
             save_statistics,
             clear_statistics,
             export_statistics,
+            get_statistics_heatmap,
             // Context menu commands
             check_context_menu_status,
             add_context_menu,
             remove_context_menu,
+            elevation::elevate_and_register_context_menu,
+            elevation::elevate_and_unregister_context_menu,
+            file_manager::install_file_manager_integration,
+            file_manager::remove_file_manager_integration,
+            url_protocol::register_url_protocol,
+            url_protocol::unregister_url_protocol,
+            url_protocol::is_url_protocol_registered,
+            // Tray commands
+            tray::update_tray_status,
+            // Autostart commands
+            autostart::set_autostart,
+            autostart::get_autostart,
+            // Global shortcut commands
+            shortcuts::apply_global_shortcuts,
+            http_api::apply_http_api,
+            http_api::generate_api_token,
+            webhooks::test_webhook,
+            url_import::enqueue_url,
+            url_import::cancel_url_download,
+            folder_import::enqueue_folder,
+            shortcuts::resume_suspended_renders,
+            // OS theme commands
+            system_theme::get_system_theme,
+            // Diagnostics commands
+            system_info::get_system_info,
+            system_info::export_support_bundle,
+            // Crash report commands
+            crash::list_crash_reports,
+            crash::delete_crash_report,
+            crash::mark_crash_reports_seen,
+            // Orphaned process commands
+            orphans::list_orphaned_processes,
+            orphans::cleanup_orphaned_processes,
             get_cli_files,
+            cli::get_cli_launch,
             // Update commands
+            updater::check_for_updates,
             download_update,
+            cancel_update_download,
             apply_update,
+            update_rollback::get_update_rollback,
+            update_rollback::rollback_update,
             restart_app,
             // Preview commands
             get_preview_frame,
             get_preview_video,
             get_video_info_for_preview,
+            generate_scrub_sprites,
             // Network safety checks
             check_network_proxy_vpn_status,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(shutdown::handle_run_event);
 }
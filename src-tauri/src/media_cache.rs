@@ -0,0 +1,238 @@
+// Media info cache
+// ffprobe results are kept in ~/.szhimatar/media_cache.json, keyed by path and
+// checked against the file's size and modification time, so re-opening a folder or
+// re-adding files does not probe them again. A file that changed is probed anew.
+// The cache is written at most every SAVE_INTERVAL and on exit.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Oldest entries are dropped beyond this
+const MAX_ENTRIES: usize = 20_000;
+/// Minimum time between two writes of the cache file
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+/// Parallel ffprobe processes of probe_all
+const MAX_PROBE_WORKERS: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    /// Modification time in nanoseconds since the Unix epoch
+    modified: u64,
+    /// Seconds since the Unix epoch, for eviction
+    cached_at: u64,
+    /// `ffprobe -show_format -show_streams -of json` output
+    probe: serde_json::Value,
+}
+
+#[derive(Default)]
+struct MediaCache {
+    entries: Option<HashMap<String, CacheEntry>>,
+    dirty: bool,
+    last_save: Option<Instant>,
+}
+
+lazy_static! {
+    static ref CACHE: Mutex<MediaCache> = Mutex::new(MediaCache::default());
+}
+
+fn cache_file() -> PathBuf {
+    crate::get_app_data_dir().join("media_cache.json")
+}
+
+impl MediaCache {
+    /// Entries, read from disk on first use
+    fn entries(&mut self) -> &mut HashMap<String, CacheEntry> {
+        self.entries.get_or_insert_with(|| {
+            std::fs::read_to_string(cache_file())
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default()
+        })
+    }
+
+    fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let entries = self.entries();
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        if excess > 0 {
+            let mut by_age: Vec<(u64, String)> = entries
+                .iter()
+                .map(|(path, entry)| (entry.cached_at, path.clone()))
+                .collect();
+            by_age.sort();
+            for (_, path) in by_age.into_iter().take(excess) {
+                entries.remove(&path);
+            }
+        }
+        let result = serde_json::to_string(entries)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(cache_file(), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            let _ = crate::write_log(format!("[MEDIA CACHE] Failed to save: {}", e));
+        }
+        self.dirty = false;
+        self.last_save = Some(Instant::now());
+    }
+}
+
+/// Size and modification time identifying the current content of a file
+fn file_stamp(path: &str) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos() as u64;
+    Some((metadata.len(), modified))
+}
+
+fn run_ffprobe(ffprobe_path: &str, path: &str) -> Result<serde_json::Value, String> {
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffprobe_path).args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+            path,
+        ]),
+        &crate::proc::RunOptions::with_timeout(30).label("ffprobe media info"),
+    )
+    .map_err(|e| format!("Failed to run FFprobe: {}", e))?;
+    if !output.success {
+        return Err("FFprobe failed to analyze file".to_string());
+    }
+    serde_json::from_str(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+/// ffprobe's format and streams of a media file, from the cache while the file is
+/// unchanged
+pub fn probe(ffprobe_path: &str, path: &str) -> Result<serde_json::Value, String> {
+    let stamp = file_stamp(path);
+    if let (Some((size, modified)), Ok(mut cache)) = (stamp, CACHE.lock()) {
+        let cached = cache
+            .entries()
+            .get(path)
+            .filter(|entry| entry.size == size && entry.modified == modified);
+        if let Some(entry) = cached {
+            return Ok(entry.probe.clone());
+        }
+    }
+
+    // Probe without holding the lock; folder scans run several probes at once
+    let probe = run_ffprobe(ffprobe_path, path)?;
+    if let (Some((size, modified)), Ok(mut cache)) = (stamp, CACHE.lock()) {
+        let entry = CacheEntry {
+            size,
+            modified,
+            cached_at: chrono::Utc::now().timestamp().max(0) as u64,
+            probe: probe.clone(),
+        };
+        cache.entries().insert(path.to_string(), entry);
+        cache.dirty = true;
+        if cache
+            .last_save
+            .is_none_or(|last| last.elapsed() >= SAVE_INTERVAL)
+        {
+            cache.save();
+        }
+    }
+    Ok(probe)
+}
+
+/// Run `probe` on every path with a bounded pool of worker threads; `on_result` gets
+/// the results in completion order. The cache is flushed at the end.
+pub fn probe_all<R: Send>(
+    paths: &[String],
+    probe: impl Fn(&str) -> R + Sync,
+    mut on_result: impl FnMut(R),
+) {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(2)
+        .min(MAX_PROBE_WORKERS)
+        .min(paths.len().max(1));
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next, probe) = (&next, &probe);
+            scope.spawn(move || {
+                while let Some(path) = paths.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    if sender.send(probe(path)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        for result in receiver {
+            on_result(result);
+        }
+    });
+    flush();
+}
+
+/// Write pending entries, e.g. at the end of a folder scan or on exit
+pub fn flush() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.save();
+    }
+}
+
+/// Format and streams of a media file as reported by ffprobe (cached)
+#[tauri::command]
+pub async fn get_media_info(input_path: String) -> Result<serde_json::Value, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
+    probe(&config.ffprobe_path, &input_path)
+}
+
+/// Forget every cached probe; returns how many there were
+#[tauri::command]
+pub fn clear_media_cache() -> Result<usize, String> {
+    let mut cache = CACHE.lock().map_err(|e| e.to_string())?;
+    let count = cache.entries().len();
+    *cache = MediaCache::default();
+    cache.entries = Some(HashMap::new());
+    match std::fs::remove_file(cache_file()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to clear media cache: {}", e))
+        }
+        _ => {
+            let _ = crate::write_log(format!("[MEDIA CACHE] Cleared {} entries", count));
+            Ok(count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_stamp_changes_with_content() {
+        let path = std::env::temp_dir().join("szhimatar-media-cache-test.mp4");
+        let path_str = path.to_string_lossy().to_string();
+        std::fs::write(&path, b"first").unwrap();
+        let first = file_stamp(&path_str).unwrap();
+        assert_eq!(first.0, 5);
+        std::fs::write(&path, b"second").unwrap();
+        assert_ne!(file_stamp(&path_str), Some(first));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(file_stamp(&path_str), None);
+    }
+}
@@ -0,0 +1,139 @@
+// Network settings
+// Proxy and extra root certificate for the app's own HTTP requests (update checks and
+// downloads). The "system" proxy mode keeps reqwest's detection: the HTTP(S)_PROXY
+// variables, plus the Internet Settings on Windows and the network settings on macOS.
+// A custom CA lets requests pass TLS-inspecting corporate proxies.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    #[default]
+    System,
+    /// Direct connections, ignoring the system proxy
+    None,
+    Manual,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkSettings {
+    pub proxy_mode: ProxyMode,
+    /// Host name or URL ("proxy.corp", "https://10.0.0.1:8443") of the manual proxy
+    pub proxy_host: String,
+    /// 0 keeps the port of the host URL
+    pub proxy_port: u16,
+    pub proxy_username: String,
+    /// Stored as plain text in settings.json
+    pub proxy_password: String,
+    /// PEM (one or more certificates) or DER file trusted in addition to the system roots
+    pub ca_certificate: String,
+}
+
+/// Proxy URL of the manual settings; http:// unless the host names a scheme
+fn proxy_url(host: &str, port: u16) -> Option<String> {
+    let host = host.trim().trim_end_matches('/');
+    if host.is_empty() {
+        return None;
+    }
+    let url = if host.contains("://") {
+        host.to_string()
+    } else {
+        format!("http://{}", host)
+    };
+    Some(if port > 0 {
+        format!("{}:{}", url, port)
+    } else {
+        url
+    })
+}
+
+fn load_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let content =
+        std::fs::read(path).map_err(|e| format!("Failed to read CA certificate: {}", e))?;
+    if content.starts_with(b"-----BEGIN") {
+        reqwest::Certificate::from_pem_bundle(&content)
+    } else {
+        reqwest::Certificate::from_der(&content).map(|certificate| vec![certificate])
+    }
+    .map_err(|e| format!("Invalid CA certificate {}: {}", path, e))
+}
+
+/// Apply the proxy and CA settings to a client builder
+pub fn apply(
+    mut builder: reqwest::blocking::ClientBuilder,
+    settings: &NetworkSettings,
+) -> Result<reqwest::blocking::ClientBuilder, String> {
+    match settings.proxy_mode {
+        ProxyMode::System => {}
+        ProxyMode::None => builder = builder.no_proxy(),
+        ProxyMode::Manual => {
+            let url = proxy_url(&settings.proxy_host, settings.proxy_port)
+                .ok_or("Proxy host not configured")?;
+            let mut proxy =
+                reqwest::Proxy::all(&url).map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+            if !settings.proxy_username.is_empty() {
+                proxy = proxy.basic_auth(&settings.proxy_username, &settings.proxy_password);
+            }
+            builder = builder.proxy(proxy);
+        }
+    }
+    if !settings.ca_certificate.trim().is_empty() {
+        for certificate in load_certificates(settings.ca_certificate.trim())? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+/// Client builder with the app's User-Agent and the network settings applied
+pub fn client_builder() -> Result<reqwest::blocking::ClientBuilder, String> {
+    let settings = crate::load_settings()
+        .map(|settings| settings.network)
+        .unwrap_or_default();
+    apply(
+        reqwest::blocking::Client::builder()
+            .user_agent(concat!("Szhimatar/", env!("CARGO_PKG_VERSION"))),
+        &settings,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_url() {
+        assert_eq!(
+            proxy_url("proxy.corp", 3128).as_deref(),
+            Some("http://proxy.corp:3128")
+        );
+        assert_eq!(
+            proxy_url(" https://10.0.0.1:8443/ ", 0).as_deref(),
+            Some("https://10.0.0.1:8443")
+        );
+        assert_eq!(proxy_url("  ", 8080), None);
+    }
+
+    #[test]
+    fn test_apply() {
+        let manual = NetworkSettings {
+            proxy_mode: ProxyMode::Manual,
+            ..Default::default()
+        };
+        assert!(apply(reqwest::blocking::Client::builder(), &manual).is_err());
+        let manual = NetworkSettings {
+            proxy_host: "proxy.corp".to_string(),
+            proxy_port: 3128,
+            proxy_username: "user".to_string(),
+            ..manual
+        };
+        assert!(apply(reqwest::blocking::Client::builder(), &manual).is_ok());
+        let missing_ca = NetworkSettings {
+            ca_certificate: "/nonexistent/corp-ca.pem".to_string(),
+            ..Default::default()
+        };
+        assert!(apply(reqwest::blocking::Client::builder(), &missing_ca).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+// Network path detection
+// Flags sources and outputs on network shares (UNC paths, mapped drives, NFS/SMB
+// mounts) and optionally stages them through a local temp folder, since encoding
+// straight over SMB is often slower than the encoder itself.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Filesystem types treated as network storage in the mount table
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "cifs",
+    "smb3",
+    "smbfs",
+    "nfs",
+    "nfs4",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetDriveTypeW(lpRootPathName: *const u16) -> u32;
+}
+
+#[cfg(windows)]
+const DRIVE_REMOTE: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathKind {
+    Local,
+    /// \\server\share\...
+    Unc,
+    /// Drive letter mapped to a share (Windows)
+    #[cfg_attr(not(windows), allow(dead_code))]
+    MappedDrive,
+    /// NFS/SMB/sshfs mount point (Linux, macOS)
+    NetworkMount,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathInfo {
+    pub path: String,
+    pub kind: PathKind,
+    pub network: bool,
+    /// Mount filesystem type when known (e.g. "cifs")
+    pub filesystem: Option<String>,
+}
+
+/// Result of analyze_render_paths, also emitted as the `path-analysis` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PathAnalysis {
+    pub job_id: Option<String>,
+    pub input: PathInfo,
+    pub output: PathInfo,
+    pub warnings: Vec<String>,
+    /// Input is copied to / output rendered in a local temp folder (stageNetworkFiles)
+    pub staged: bool,
+}
+
+impl PathAnalysis {
+    pub fn any_network(&self) -> bool {
+        self.input.network || self.output.network
+    }
+}
+
+/// \\server\share, //server/share and \\?\UNC\server\share (not \\?\C:\ or \\.\)
+fn is_unc(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    if lower.starts_with(r"\\?\unc\") {
+        return true;
+    }
+    if lower.starts_with(r"\\?\") || lower.starts_with(r"\\.\") {
+        return false;
+    }
+    path.starts_with(r"\\") || path.starts_with("//")
+}
+
+/// (mount point, filesystem type) pairs from /proc/self/mounts
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_mounts(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            Some((mount_point, fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// (mount point, filesystem type) pairs from macOS `mount` output, e.g.
+/// "//user@nas/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)"
+#[cfg(any(target_os = "macos", test))]
+fn parse_mount_output(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount_point, options) = rest.rsplit_once(" (")?;
+            let filesystem = options.split([',', ')']).next()?.trim();
+            Some((mount_point.to_string(), filesystem.to_string()))
+        })
+        .collect()
+}
+
+fn mount_table() -> Vec<(String, String)> {
+    #[cfg(target_os = "linux")]
+    {
+        fs::read_to_string("/proc/self/mounts")
+            .map(|content| parse_proc_mounts(&content))
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::proc::run_captured(
+            &mut crate::proc::command("mount"),
+            &crate::proc::RunOptions::with_timeout(10).label("mount"),
+        )
+        .ok()
+        .filter(|o| o.success)
+        .map(|o| parse_mount_output(&o.stdout))
+        .unwrap_or_default()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        Vec::new()
+    }
+}
+
+/// Filesystem of the deepest mount containing `path`, if it is a network one
+fn network_filesystem(path: &Path, mounts: &[(String, String)]) -> Option<String> {
+    mounts
+        .iter()
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.len())
+        .map(|(_, filesystem)| filesystem)
+        .filter(|filesystem| NETWORK_FILESYSTEMS.contains(&filesystem.as_str()))
+        .cloned()
+}
+
+/// Mount points of network filesystems (Linux, macOS)
+pub fn network_mount_points() -> Vec<PathBuf> {
+    mount_table()
+        .into_iter()
+        .filter(|(_, filesystem)| NETWORK_FILESYSTEMS.contains(&filesystem.as_str()))
+        .map(|(mount_point, _)| PathBuf::from(mount_point))
+        .collect()
+}
+
+#[cfg(windows)]
+pub fn is_mapped_drive(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' || !bytes[0].is_ascii_alphabetic() {
+        return false;
+    }
+    let root: Vec<u16> = format!("{}:\\", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+fn classify(path: &str, mounts: &[(String, String)]) -> PathInfo {
+    let mut info = PathInfo {
+        path: path.to_string(),
+        kind: PathKind::Local,
+        network: false,
+        filesystem: None,
+    };
+
+    if is_unc(path) {
+        info.kind = PathKind::Unc;
+    } else {
+        #[cfg(windows)]
+        if is_mapped_drive(path) {
+            info.kind = PathKind::MappedDrive;
+        }
+
+        if info.kind == PathKind::Local {
+            if let Some(filesystem) = network_filesystem(Path::new(path), mounts) {
+                info.kind = PathKind::NetworkMount;
+                info.filesystem = Some(filesystem);
+            }
+        }
+    }
+    info.network = info.kind != PathKind::Local;
+    info
+}
+
+/// Classify a render's input and output and describe the likely slowdown
+pub fn analyze(input_path: &str, output_path: &str) -> PathAnalysis {
+    let mounts = mount_table();
+    let input = classify(input_path, &mounts);
+    let output = classify(output_path, &mounts);
+
+    let mut warnings = Vec::new();
+    if input.network {
+        warnings.push(format!(
+            "Source is on a network share ({}); reading it over the network may limit encoding speed",
+            input.path
+        ));
+    }
+    if output.network {
+        warnings.push(format!(
+            "Output is on a network share ({}); writing it over the network may limit encoding speed",
+            output.path
+        ));
+    }
+
+    PathAnalysis {
+        job_id: None,
+        input,
+        output,
+        warnings,
+        staged: false,
+    }
+}
+
+/// Local copies used while rendering network files
+pub struct StagedPaths {
+    dir: PathBuf,
+    original_input: String,
+    original_output: String,
+}
+
+impl StagedPaths {
+    /// Copy a network input into a per-job temp folder and point the job at local
+    /// files; a network output is rendered locally and copied back by `finish`.
+    pub fn prepare(job: &mut crate::RenderJob, analysis: &PathAnalysis) -> Result<Self, String> {
+        let dir = std::env::temp_dir()
+            .join("szhimatar-staging")
+            .join(&job.job_id);
+        fs::create_dir_all(dir.join("out"))
+            .map_err(|e| format!("Failed to create staging folder: {}", e))?;
+
+        let staged = StagedPaths {
+            dir,
+            original_input: job.input_path.clone(),
+            original_output: job.output_path.clone(),
+        };
+        let file_name = |path: &str| Path::new(path).file_name().unwrap_or_default().to_owned();
+
+        if analysis.input.network {
+            let local = staged.dir.join(file_name(&staged.original_input));
+            if let Err(e) = fs::copy(&staged.original_input, &local) {
+                let _ = fs::remove_dir_all(&staged.dir);
+                return Err(format!("Failed to copy source to local staging: {}", e));
+            }
+            job.input_path = local.to_string_lossy().to_string();
+        }
+        if analysis.output.network {
+            // Separate folder so a source and output with the same name cannot collide
+            let local = staged
+                .dir
+                .join("out")
+                .join(file_name(&staged.original_output));
+            job.output_path = local.to_string_lossy().to_string();
+        }
+        Ok(staged)
+    }
+
+    /// Restore the job's original paths, copying a successful local output back to
+    /// the share. The temp folder is kept when the copy fails so the render is not lost.
+    pub fn finish(self, job: &mut crate::RenderJob, success: bool) -> Result<(), String> {
+        let local_output = std::mem::replace(&mut job.output_path, self.original_output);
+        job.input_path = self.original_input;
+
+        if success && local_output != job.output_path {
+            if let Err(e) = fs::copy(&local_output, &job.output_path) {
+                return Err(format!(
+                    "Failed to copy output back to the network share ({}), the render is kept at {}",
+                    e, local_output
+                ));
+            }
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+        Ok(())
+    }
+}
+
+/// Whether a render's source and output are on network shares
+#[tauri::command]
+pub async fn analyze_render_paths(input_path: String, output_path: String) -> PathAnalysis {
+    analyze(&input_path, &output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unc_detection() {
+        assert!(is_unc(r"\\nas\videos\clip.mp4"));
+        assert!(is_unc("//nas/videos/clip.mp4"));
+        assert!(is_unc(r"\\?\UNC\nas\videos\clip.mp4"));
+        assert!(!is_unc(r"\\?\C:\videos\clip.mp4"));
+        assert!(!is_unc(r"C:\videos\clip.mp4"));
+    }
+
+    #[test]
+    fn test_mount_tables() {
+        let linux = parse_proc_mounts(
+            "/dev/sda1 / ext4 rw 0 0\n//nas/media /mnt/my\\040media cifs rw 0 0\n",
+        );
+        assert_eq!(
+            network_filesystem(Path::new("/mnt/my media/a.mp4"), &linux),
+            Some("cifs".to_string())
+        );
+        assert_eq!(network_filesystem(Path::new("/home/a.mp4"), &linux), None);
+
+        let macos = parse_mount_output(
+            "/dev/disk3s1 on / (apfs, local, journaled)\n//me@nas/share on /Volumes/share (smbfs, nodev, nosuid, mounted by me)\n",
+        );
+        assert_eq!(
+            network_filesystem(Path::new("/Volumes/share/x.mov"), &macos),
+            Some("smbfs".to_string())
+        );
+    }
+}
@@ -0,0 +1,166 @@
+// OS notifications
+// Toasts when a render completes or fails and when the whole queue is done: a WinRT
+// toast through PowerShell on Windows, Notification Center through osascript on macOS
+// and notify-send elsewhere. Nothing is shown while the window has focus unless the
+// notifications.whenFocused setting asks for it.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::Manager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Also notify while the app window is focused
+    pub when_focused: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            when_focused: false,
+        }
+    }
+}
+
+/// Results since the queue last finished, for the summary notification
+#[derive(Default)]
+struct QueueTally {
+    completed: u32,
+    failed: u32,
+}
+
+lazy_static! {
+    static ref TALLY: Mutex<QueueTally> = Mutex::new(QueueTally::default());
+}
+
+fn should_notify(window: &tauri::Window) -> bool {
+    let settings = crate::load_settings().unwrap_or_default().notifications;
+    settings.enabled && (settings.when_focused || !window.is_focused().unwrap_or(false))
+}
+
+#[cfg(target_os = "windows")]
+fn notification_command(app_id: &str, title: &str, body: &str) -> std::process::Command {
+    // Single quotes are the only thing to escape inside a PowerShell literal
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier({}).Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(title),
+        quote(body),
+        quote(app_id)
+    );
+    let mut cmd = crate::proc::command("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(_app_id: &str, title: &str, body: &str) -> std::process::Command {
+    // Texts go in as arguments so they need no AppleScript escaping
+    let mut cmd = crate::proc::command("osascript");
+    cmd.args([
+        "-e",
+        "on run argv",
+        "-e",
+        "display notification (item 2 of argv) with title (item 1 of argv)",
+        "-e",
+        "end run",
+        title,
+        body,
+    ]);
+    cmd
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn notification_command(_app_id: &str, title: &str, body: &str) -> std::process::Command {
+    let mut cmd = crate::proc::command("notify-send");
+    cmd.args(["--app-name=Szhimatar", "--", title, body]);
+    cmd
+}
+
+/// Show a notification without blocking the caller
+fn show(window: &tauri::Window, title: String, body: String) {
+    // Windows attributes toasts to the AppUserModelID the installer gave the shortcut
+    let app_id = window.config().tauri.bundle.identifier.clone();
+    std::thread::spawn(move || {
+        let result = crate::proc::run_captured(
+            &mut notification_command(&app_id, &title, &body),
+            &crate::proc::RunOptions::with_timeout(15).label("notification"),
+        );
+        match result {
+            Ok(output) if output.success => {}
+            Ok(output) => {
+                let _ = crate::write_log(format!(
+                    "[NOTIFY] Notification failed: {}",
+                    output.stderr.trim()
+                ));
+            }
+            Err(e) => {
+                let _ = crate::write_log(format!("[NOTIFY] Notification failed: {}", e));
+            }
+        }
+    });
+}
+
+/// A render finished; `error` is None on success
+pub fn render_finished(window: &tauri::Window, input_path: &str, error: Option<&str>) {
+    if let Ok(mut tally) = TALLY.lock() {
+        match error {
+            None => tally.completed += 1,
+            Some(_) => tally.failed += 1,
+        }
+    }
+    if !should_notify(window) {
+        return;
+    }
+
+    let file_name = Path::new(input_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| input_path.to_string());
+    match error {
+        None => show(window, "Рендер завершён".to_string(), file_name),
+        Some(error) => {
+            let reason: String = error
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(160)
+                .collect();
+            show(
+                window,
+                "Ошибка рендера".to_string(),
+                format!("{}\n{}", file_name, reason),
+            );
+        }
+    }
+}
+
+/// The frontend's queue drained (see queue::notify_queue_finished)
+pub fn queue_finished(window: &tauri::Window) {
+    let tally = match TALLY.lock() {
+        Ok(mut tally) => std::mem::take(&mut *tally),
+        Err(_) => return,
+    };
+    // A single render already got its own notification
+    if tally.completed + tally.failed < 2 || !should_notify(window) {
+        return;
+    }
+
+    let body = if tally.failed == 0 {
+        format!("Готово: {}", tally.completed)
+    } else {
+        format!("Готово: {}, с ошибкой: {}", tally.completed, tally.failed)
+    };
+    show(window, "Очередь завершена".to_string(), body);
+}
@@ -0,0 +1,220 @@
+// Orphaned processes
+// Every FFmpeg render is recorded in ~/.szhimatar/running.json (PID, process start time,
+// output) while it runs. Entries still there at launch belong to a session that crashed
+// or was killed: FFmpeg processes that outlived it can be killed with
+// cleanup_orphaned_processes, and the unfinished `.part` outputs they leave are removed.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Marker inserted before the extension of an output while it renders
+const PART_MARKER: &str = ".part";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunEntry {
+    pub job_id: String,
+    pub pid: u32,
+    /// Process start time (seconds since the Unix epoch), so a reused PID is not
+    /// mistaken for the render
+    pub start_time: u64,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanCleanup {
+    pub killed: usize,
+    pub removed_outputs: Vec<String>,
+}
+
+lazy_static! {
+    /// Renders of this session, mirrored to the run file
+    static ref RUNNING: Mutex<Vec<RunEntry>> = Mutex::new(Vec::new());
+    /// Still running FFmpeg processes of the previous session, found at launch
+    static ref ORPHANS: Mutex<Vec<RunEntry>> = Mutex::new(Vec::new());
+}
+
+fn run_file() -> PathBuf {
+    crate::get_app_data_dir().join("running.json")
+}
+
+fn save(entries: &[RunEntry]) {
+    let result = if entries.is_empty() {
+        match std::fs::remove_file(run_file()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    } else {
+        serde_json::to_string_pretty(entries)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(run_file(), content).map_err(|e| e.to_string()))
+    };
+    if let Err(e) = result {
+        let _ = crate::write_log(format!("[ORPHANS] Failed to update run file: {}", e));
+    }
+}
+
+/// Output FFmpeg writes to while rendering: "clip.mp4" -> "clip.part.mp4". The
+/// extension stays last because FFmpeg picks the container from it.
+pub fn part_path(output_path: &str) -> String {
+    let path = Path::new(output_path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}{}.{}", stem, PART_MARKER, ext.to_string_lossy()),
+        None => format!("{}{}", stem, PART_MARKER),
+    };
+    path.with_file_name(name).to_string_lossy().to_string()
+}
+
+fn is_part_path(path: &str) -> bool {
+    Path::new(path)
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().ends_with(PART_MARKER))
+        || path.ends_with(PART_MARKER)
+}
+
+/// Start time of `pid` when it is an FFmpeg process
+fn ffmpeg_start_time(pid: u32) -> Option<u64> {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    if !system.refresh_process(pid) {
+        return None;
+    }
+    let process = system.process(pid)?;
+    if process.name().to_lowercase().contains("ffmpeg") {
+        Some(process.start_time())
+    } else {
+        None
+    }
+}
+
+fn is_alive(entry: &RunEntry) -> bool {
+    ffmpeg_start_time(entry.pid) == Some(entry.start_time)
+}
+
+fn remove_part_output(entry: &RunEntry) -> Option<String> {
+    if !is_part_path(&entry.output_path) {
+        return None;
+    }
+    match std::fs::remove_file(&entry.output_path) {
+        Ok(()) => Some(entry.output_path.clone()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            let _ = crate::write_log(format!(
+                "[ORPHANS] Failed to remove {}: {}",
+                entry.output_path, e
+            ));
+            None
+        }
+    }
+}
+
+/// Called by the process manager right after spawning a render
+pub fn record_spawn(job_id: &str, pid: u32, output_path: &str) {
+    let entry = RunEntry {
+        job_id: job_id.to_string(),
+        pid,
+        start_time: ffmpeg_start_time(pid).unwrap_or_default(),
+        output_path: output_path.to_string(),
+    };
+    if let Ok(mut running) = RUNNING.lock() {
+        running.retain(|e| e.job_id != job_id);
+        running.push(entry);
+        save(&running);
+    }
+}
+
+/// Called by the process manager once a render process is gone
+pub fn record_exit(job_id: &str) {
+    if let Ok(mut running) = RUNNING.lock() {
+        let before = running.len();
+        running.retain(|e| e.job_id != job_id);
+        if running.len() != before {
+            save(&running);
+        }
+    }
+}
+
+/// Read the run file left by the previous session. Outputs of renders that died with
+/// it are removed right away; FFmpeg processes still running are kept for the prompt.
+/// Returns the ids of the jobs it was rendering.
+pub fn check_previous_session() -> Vec<String> {
+    let entries: Vec<RunEntry> = match std::fs::read_to_string(run_file()) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => return Vec::new(),
+    };
+    save(&[]);
+    let job_ids = entries.iter().map(|e| e.job_id.clone()).collect();
+
+    let (alive, dead): (Vec<RunEntry>, Vec<RunEntry>) = entries.into_iter().partition(is_alive);
+    for path in dead.iter().filter_map(remove_part_output) {
+        let _ = crate::write_log(format!("[ORPHANS] Removed leftover output {}", path));
+    }
+    if !alive.is_empty() {
+        let _ = crate::write_log(format!(
+            "[ORPHANS] {} FFmpeg process(es) of the previous session still running",
+            alive.len()
+        ));
+    }
+    if let Ok(mut orphans) = ORPHANS.lock() {
+        *orphans = alive;
+    }
+    job_ids
+}
+
+/// FFmpeg processes left running by the previous session
+#[tauri::command]
+pub fn list_orphaned_processes() -> Vec<RunEntry> {
+    ORPHANS
+        .lock()
+        .map(|orphans| orphans.iter().filter(|e| is_alive(e)).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Kill the FFmpeg processes left by the previous session and remove their outputs
+#[tauri::command]
+pub fn cleanup_orphaned_processes() -> Result<OrphanCleanup, String> {
+    let orphans = ORPHANS
+        .lock()
+        .map(|mut orphans| std::mem::take(&mut *orphans))
+        .map_err(|e| e.to_string())?;
+
+    let mut killed = 0;
+    for entry in orphans.iter().filter(|e| is_alive(e)) {
+        crate::proc::kill_pid(entry.pid);
+        killed += 1;
+    }
+    if killed > 0 {
+        // Give Windows a moment to release the output file handles
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+    let removed_outputs: Vec<String> = orphans.iter().filter_map(remove_part_output).collect();
+
+    let _ = crate::write_log(format!(
+        "[ORPHANS] Killed {} process(es), removed {} output(s)",
+        killed,
+        removed_outputs.len()
+    ));
+    Ok(OrphanCleanup {
+        killed,
+        removed_outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path("/videos/clip.mp4"),
+            Path::new("/videos/clip.part.mp4").to_string_lossy()
+        );
+        assert_eq!(part_path("clip"), "clip.part");
+        assert!(is_part_path(&part_path("/videos/a.b.mkv")));
+        assert!(!is_part_path("/videos/clip.mp4"));
+        assert!(!is_part_path("-"));
+    }
+}
@@ -0,0 +1,321 @@
+// Output path templates
+// Resolves where a render writes to from a naming template such as
+// `{parent}/{name}_{codec}_{height}p{suffix}.{ext}`, optionally under a dedicated
+// output directory that mirrors the source folder structure.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Equivalent of the old flat `output_suffix` behaviour
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{parent}/{name}{suffix}.{ext}";
+
+/// Placeholders a template may use
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "parent", "name", "ext", "suffix", "codec", "width", "height", "preset", "date", "time",
+];
+
+/// Render details a template can refer to (all optional)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputPathOptions {
+    pub codec: Option<String>,
+    pub preset: Option<String>,
+    /// Output container extension without the dot; defaults to the source's
+    pub container: Option<String>,
+    /// Target resolution "WIDTHxHEIGHT"; the source is probed when missing
+    pub resolution: Option<String>,
+    /// Overrides the output_suffix setting
+    pub suffix: Option<String>,
+    /// Overrides the outputDirectory setting (empty = next to the source)
+    pub output_directory: Option<String>,
+    /// Folder a batch was started from; mirroring keeps paths relative to it
+    pub source_root: Option<String>,
+}
+
+/// Where outputs go when they should not sit next to their sources
+#[derive(Debug, Clone, Default)]
+pub struct OutputDirectory {
+    pub root: PathBuf,
+    /// Recreate the source folders beneath `root`
+    pub mirror: bool,
+}
+
+impl OutputDirectory {
+    /// Folder for outputs of sources in `source_dir`. Without a usable
+    /// `source_root` the whole source path is mirrored (drive letter included).
+    pub fn folder_for(&self, source_dir: &Path, source_root: Option<&Path>) -> PathBuf {
+        if !self.mirror {
+            return self.root.clone();
+        }
+
+        let relative = match source_root.and_then(|root| source_dir.strip_prefix(root).ok()) {
+            Some(relative) => relative.to_path_buf(),
+            None => source_dir
+                .components()
+                .filter_map(|c| match c {
+                    Component::Prefix(prefix) => Some(PathBuf::from(sanitize_component(
+                        &prefix.as_os_str().to_string_lossy(),
+                    ))),
+                    Component::Normal(part) => Some(PathBuf::from(part)),
+                    _ => None,
+                })
+                .collect(),
+        };
+        self.root.join(relative)
+    }
+}
+
+/// Values substituted into a template
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    pub parent: String,
+    pub name: String,
+    pub ext: String,
+    pub suffix: String,
+    pub codec: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub preset: String,
+    pub date: String,
+    pub time: String,
+}
+
+/// Characters that cannot appear in a file name on Windows (the strictest target)
+fn sanitize_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Substitute `{placeholder}`s; unknown placeholders and unclosed braces are errors
+pub fn expand_template(template: &str, values: &TemplateValues) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|e| start + e)
+            .ok_or_else(|| format!("Unclosed '{{' in output template: {}", template))?;
+        let key = &rest[start + 1..end];
+
+        let number = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+        let value = match key {
+            // The source folder keeps its separators; everything else is one path component
+            "parent" => values.parent.clone(),
+            "name" => sanitize_component(&values.name),
+            "ext" => sanitize_component(&values.ext),
+            "suffix" => sanitize_component(&values.suffix),
+            "codec" => sanitize_component(&values.codec),
+            "width" => number(values.width),
+            "height" => number(values.height),
+            "preset" => sanitize_component(&values.preset),
+            "date" => values.date.clone(),
+            "time" => values.time.clone(),
+            other => {
+                return Err(format!(
+                    "Unknown placeholder {{{}}} in output template (available: {})",
+                    other,
+                    TEMPLATE_PLACEHOLDERS.join(", ")
+                ))
+            }
+        };
+        result.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    if result.trim().is_empty() {
+        return Err("Output template produced an empty path".to_string());
+    }
+    Ok(result)
+}
+
+/// Parse "1920x1080" into (width, height)
+fn parse_resolution(resolution: &str) -> Option<(u32, u32)> {
+    let (w, h) = resolution.trim().split_once(['x', 'X'])?;
+    Some((w.trim().parse().ok()?, h.trim().parse().ok()?))
+}
+
+fn probe_resolution(ffprobe_path: &str, input: &str) -> Option<(u32, u32)> {
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffprobe_path).args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=width,height",
+            "-of",
+            "csv=p=0:s=x",
+            input,
+        ]),
+        &crate::proc::RunOptions::with_timeout(30).label("ffprobe resolution"),
+    )
+    .ok()
+    .filter(|o| o.success)?;
+    parse_resolution(&output.stdout)
+}
+
+/// Resolve the output path for `input`. Relative results are placed in the source
+/// folder (or its counterpart under `output_directory`), and a result equal to the input gets "_out" so the source is never overwritten.
+pub fn resolve_output_path(
+    input: &str,
+    template: &str,
+    options: &OutputPathOptions,
+    default_suffix: &str,
+    output_directory: Option<&OutputDirectory>,
+) -> Result<String, String> {
+    let input_path = Path::new(input);
+    let source_dir = input_path.parent().unwrap_or(Path::new(""));
+    let parent = match output_directory {
+        Some(dir) => dir.folder_for(source_dir, options.source_root.as_deref().map(Path::new)),
+        None => source_dir.to_path_buf(),
+    };
+    let name = input_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Input has no file name: {}", input))?;
+    let ext = options
+        .container
+        .clone()
+        .filter(|c| !c.is_empty())
+        .or_else(|| {
+            input_path
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "mp4".to_string());
+
+    let wants_size = template.contains("{width}") || template.contains("{height}");
+    let size = options
+        .resolution
+        .as_deref()
+        .and_then(parse_resolution)
+        .or_else(|| {
+            wants_size
+                .then(|| probe_resolution(&crate::load_ffmpeg_config().ffprobe_path, input))
+                .flatten()
+        });
+
+    let now = chrono::Local::now();
+    let values = TemplateValues {
+        parent: parent.to_string_lossy().to_string(),
+        name,
+        ext: ext.trim_start_matches('.').to_string(),
+        suffix: options
+            .suffix
+            .clone()
+            .unwrap_or_else(|| default_suffix.to_string()),
+        codec: options.codec.clone().unwrap_or_default(),
+        width: size.map(|s| s.0),
+        height: size.map(|s| s.1),
+        preset: options.preset.clone().unwrap_or_default(),
+        date: now.format("%Y-%m-%d").to_string(),
+        time: now.format("%H-%M-%S").to_string(),
+    };
+
+    let mut output = PathBuf::from(expand_template(template, &values)?);
+    if output.is_relative() {
+        output = parent.join(output);
+    }
+    if output == input_path {
+        let file_name = format!("{}_out.{}", values.name, values.ext);
+        output.set_file_name(file_name);
+    }
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// Output path for a source file, from `template` or the outputTemplate setting,
+/// placed under the outputDirectory setting when one is configured
+#[tauri::command]
+pub fn render_output_path(
+    input: String,
+    template: Option<String>,
+    options: Option<OutputPathOptions>,
+) -> Result<String, String> {
+    let settings = crate::load_settings().unwrap_or_default();
+    let options = options.unwrap_or_default();
+    let template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(settings.output_template);
+
+    let root = options
+        .output_directory
+        .clone()
+        .unwrap_or(settings.output_directory);
+    let output_directory = (!root.trim().is_empty()).then(|| OutputDirectory {
+        root: PathBuf::from(root.trim()),
+        mirror: settings.mirror_source_folders,
+    });
+
+    resolve_output_path(
+        &input,
+        &template,
+        &options,
+        &settings.output_suffix,
+        output_directory.as_ref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_template() {
+        let values = TemplateValues {
+            parent: "/videos/trip".to_string(),
+            name: "clip".to_string(),
+            ext: "mp4".to_string(),
+            suffix: "_szhatoe".to_string(),
+            codec: "h265".to_string(),
+            height: Some(720),
+            ..Default::default()
+        };
+        assert_eq!(
+            expand_template("{parent}/{name}_{codec}_{height}p{suffix}.{ext}", &values).unwrap(),
+            "/videos/trip/clip_h265_720p_szhatoe.mp4"
+        );
+        assert!(expand_template("{name}_{bogus}.mp4", &values).is_err());
+        assert!(expand_template("{name.mp4", &values).is_err());
+    }
+
+    #[test]
+    fn test_resolve_never_overwrites_input() {
+        let options = OutputPathOptions {
+            resolution: Some("1280x720".to_string()),
+            ..Default::default()
+        };
+        let output = resolve_output_path("/v/a.mp4", "{name}.{ext}", &options, "", None).unwrap();
+        assert_eq!(PathBuf::from(output), PathBuf::from("/v/a_out.mp4"));
+    }
+
+    #[test]
+    fn test_output_directory_mirroring() {
+        let dir = OutputDirectory {
+            root: PathBuf::from("/out"),
+            mirror: true,
+        };
+        let source = Path::new("/media/cam/2024/trip");
+        assert_eq!(
+            dir.folder_for(source, Some(Path::new("/media/cam"))),
+            PathBuf::from("/out/2024/trip")
+        );
+        // Outside the batch root the full source path is mirrored
+        assert_eq!(
+            dir.folder_for(source, Some(Path::new("/elsewhere"))),
+            PathBuf::from("/out/media/cam/2024/trip")
+        );
+        let flat = OutputDirectory {
+            mirror: false,
+            ..dir
+        };
+        assert_eq!(flat.folder_for(source, None), PathBuf::from("/out"));
+    }
+}
@@ -0,0 +1,112 @@
+// Job phases
+// A render can take several steps (two passes, joining a resumed render, decoding the
+// output to verify it). Each `render-progress` event names the phase it belongs to, and
+// progress_percent is the weighted progress over all phases of the job, so the bar no
+// longer drops from 100% back to 0% between two passes.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPhase {
+    /// Probing / measuring the source before the encode
+    Analyzing,
+    /// Camera motion detection pass of a stabilized render (vidstabdetect)
+    Stabilizing,
+    /// Single-pass encode
+    Encoding,
+    /// Analysis pass of a two-pass encode
+    Pass1,
+    Pass2,
+    /// Joining the parts of a resumed render
+    Muxing,
+    /// Decoding the output to check it
+    Verifying,
+}
+
+impl JobPhase {
+    /// Share of the work compared to a full encode of the source
+    fn weight(self) -> f64 {
+        match self {
+            JobPhase::Analyzing => 0.1,
+            // Decodes the whole source, but encodes nothing
+            JobPhase::Stabilizing => 0.4,
+            JobPhase::Encoding | JobPhase::Pass2 => 1.0,
+            // Encoders run their first pass with faster settings
+            JobPhase::Pass1 => 0.6,
+            JobPhase::Muxing => 0.05,
+            JobPhase::Verifying => 0.25,
+        }
+    }
+}
+
+/// The phases of one job and the share of the progress bar each covers
+#[derive(Debug, Clone)]
+pub struct PhasePlan {
+    /// Phase with its (start, end) percent
+    phases: Vec<(JobPhase, (f64, f64))>,
+}
+
+impl PhasePlan {
+    pub fn new(phases: &[JobPhase]) -> Self {
+        let total: f64 = phases.iter().map(|phase| phase.weight()).sum();
+        let mut start = 0.0;
+        let phases = phases
+            .iter()
+            .map(|&phase| {
+                let end = start + phase.weight() / total * 100.0;
+                let range = (start, end);
+                start = end;
+                (phase, range)
+            })
+            .collect();
+        Self { phases }
+    }
+
+    /// Percent range of a phase; a phase outside the plan covers nothing
+    pub fn range(&self, phase: JobPhase) -> (f64, f64) {
+        self.phases
+            .iter()
+            .find(|(p, _)| *p == phase)
+            .map(|(_, range)| *range)
+            .unwrap_or((100.0, 100.0))
+    }
+
+    /// Overall percent with `fraction` (0..1) of `phase` done
+    pub fn overall(&self, phase: JobPhase, fraction: f64) -> f64 {
+        let (start, end) = self.range(phase);
+        start + fraction.clamp(0.0, 1.0) * (end - start)
+    }
+
+    /// 1-based position of a phase and the number of phases
+    pub fn position(&self, phase: JobPhase) -> (usize, usize) {
+        let index = self
+            .phases
+            .iter()
+            .position(|(p, _)| *p == phase)
+            .map_or(self.phases.len(), |i| i + 1);
+        (index, self.phases.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_plan() {
+        let plan = PhasePlan::new(&[JobPhase::Pass1, JobPhase::Pass2, JobPhase::Verifying]);
+        assert_eq!(plan.range(JobPhase::Pass1).0, 0.0);
+        assert_eq!(plan.range(JobPhase::Pass1).1, plan.range(JobPhase::Pass2).0);
+        assert!((plan.range(JobPhase::Verifying).1 - 100.0).abs() < 1e-9);
+        // Progress only moves forward across phases
+        assert!(plan.overall(JobPhase::Pass1, 1.0) <= plan.overall(JobPhase::Pass2, 0.0));
+        assert!(plan.overall(JobPhase::Pass2, 1.0) < 100.0);
+        assert_eq!(plan.position(JobPhase::Pass2), (2, 3));
+
+        let single = PhasePlan::new(&[JobPhase::Encoding]);
+        assert_eq!(single.range(JobPhase::Encoding), (0.0, 100.0));
+        assert_eq!(single.overall(JobPhase::Encoding, 0.5), 50.0);
+        assert_eq!(single.range(JobPhase::Muxing), (100.0, 100.0));
+    }
+}
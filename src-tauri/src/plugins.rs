@@ -0,0 +1,351 @@
+// Plugin presets
+// Third parties ship extra encode profiles as JSON manifests in the `plugins` folder of
+// the app data directory. The folder is scanned at startup; every preset is checked
+// against the preset schema (the video/audio settings the frontend understands) and
+// listed by list_presets with `source: plugin`. Plugin presets are read-only: saving
+// one under the same name creates a user preset that shadows it.
+//
+// {
+//   "name": "AV1 pack", "version": "1.0", "author": "...",
+//   "presets": [{
+//     "name": "AV1 archive", "description": "...",
+//     "video": { "codec": "av1", "crf": "32", "filters": [{ "name": "denoise", "enabled": true }] },
+//     "audio": { "codec": "opus", "bitrate": "128" },
+//     "constraints": { "encoders": ["libsvtav1"], "containers": ["mkv", "webm"] }
+//   }]
+// }
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// What a plugin preset needs to work; shown with the preset, not enforced
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct PresetConstraints {
+    /// FFmpeg encoders the profile uses (see `ffmpeg -encoders`)
+    pub encoders: Vec<String>,
+    /// Output containers the profile is meant for
+    pub containers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ManifestPreset {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    video: Map<String, Value>,
+    #[serde(default)]
+    audio: Map<String, Value>,
+    #[serde(default)]
+    constraints: PresetConstraints,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    author: String,
+    presets: Vec<ManifestPreset>,
+}
+
+/// A validated preset of a plugin
+#[derive(Debug, Clone)]
+pub struct PluginPreset {
+    pub name: String,
+    pub description: Option<String>,
+    /// Name of the plugin (manifest) that provides it
+    pub plugin: String,
+    pub constraints: PresetConstraints,
+    video: Map<String, Value>,
+    audio: Map<String, Value>,
+}
+
+impl PluginPreset {
+    /// The preset in the format load_preset returns. Video/audio hold only the fields
+    /// the manifest sets; the frontend fills the rest with its defaults.
+    pub fn content(&self) -> String {
+        let preset = serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "video": self.video,
+            "audio": self.audio,
+            "mainScreen": {},
+            "source": "plugin",
+            "plugin": self.plugin,
+        });
+        serde_json::to_string_pretty(&preset).unwrap_or_default()
+    }
+}
+
+lazy_static! {
+    static ref PRESETS: RwLock<Vec<PluginPreset>> = RwLock::new(Vec::new());
+}
+
+/// JSON type a preset setting must have
+enum Kind {
+    Text,
+    Bool,
+    Number,
+    OneOf(&'static [&'static str]),
+    /// [{ name: string, enabled: bool }]
+    Toggles,
+    /// [{ frequency: number, gain: number }]
+    Bands,
+}
+
+/// VideoSettings in src/types/index.ts
+const VIDEO_FIELDS: &[(&str, Kind)] = &[
+    ("codec", Kind::Text),
+    ("bitrate", Kind::Text),
+    ("fps", Kind::Text),
+    ("fpsAuto", Kind::Bool),
+    ("resolution", Kind::Text),
+    ("aspectRatio", Kind::Text),
+    ("aspectRatioAuto", Kind::Bool),
+    ("crf", Kind::Text),
+    ("rateControl", Kind::OneOf(&["auto", "capped_crf"])),
+    ("twoPass", Kind::Bool),
+    ("preset", Kind::Text),
+    ("speed", Kind::Number),
+    ("rotation", Kind::OneOf(&["none", "90", "180", "270"])),
+    ("flip", Kind::OneOf(&["none", "horizontal", "vertical"])),
+    ("sourceRotation", Kind::OneOf(&["bake", "keep"])),
+    ("resolutionCap", Kind::OneOf(&["none", "720", "1080", "1440"])),
+    ("fpsCap", Kind::OneOf(&["none", "30", "60"])),
+    ("smartCopy", Kind::Bool),
+    ("pixelFormat", Kind::OneOf(&["auto", "yuv420p", "yuv420p10le"])),
+    ("filters", Kind::Toggles),
+    ("extraFilters", Kind::Text),
+    ("resamplingEnabled", Kind::Bool),
+    ("resamplingIntensity", Kind::Number),
+    ("stabilize", Kind::Bool),
+    ("stabilizeShakiness", Kind::Number),
+    ("stabilizeSmoothing", Kind::Number),
+    ("lutPath", Kind::Text),
+    ("brightness", Kind::Number),
+    ("contrast", Kind::Number),
+    ("saturation", Kind::Number),
+];
+
+/// AudioSettings in src/types/index.ts
+const AUDIO_FIELDS: &[(&str, Kind)] = &[
+    ("codec", Kind::Text),
+    ("bitrate", Kind::Text),
+    ("channels", Kind::Text),
+    ("sampleRate", Kind::Text),
+    ("volume", Kind::Text),
+    ("gain", Kind::Text),
+    ("normalization", Kind::Bool),
+    ("pitch", Kind::Number),
+    ("noiseReduction", Kind::Text),
+    ("autoSelect", Kind::Bool),
+    ("effects", Kind::Toggles),
+    ("equalizer", Kind::Bands),
+    ("track", Kind::OneOf(&["keep", "mute", "replace", "mix"])),
+    ("trackPath", Kind::Text),
+    ("trackStart", Kind::Number),
+    ("trackLoop", Kind::Bool),
+    ("trackVolume", Kind::Number),
+    ("flacCompression", Kind::Number),
+    ("passthrough", Kind::Bool),
+];
+
+/// Key of an object in a settings list and the check its value must pass
+type ObjectField = (&'static str, fn(&Value) -> bool);
+
+fn list_of_objects(value: &Value, fields: &[ObjectField]) -> bool {
+    value.as_array().is_some_and(|items| {
+        items.iter().all(|item| {
+            item.as_object().is_some_and(|object| {
+                object.len() == fields.len()
+                    && fields
+                        .iter()
+                        .all(|(key, check)| object.get(*key).is_some_and(check))
+            })
+        })
+    })
+}
+
+impl Kind {
+    fn accepts(&self, value: &Value) -> bool {
+        match self {
+            Kind::Text => value.is_string(),
+            Kind::Bool => value.is_boolean(),
+            Kind::Number => value.is_number(),
+            Kind::OneOf(values) => value.as_str().is_some_and(|v| values.contains(&v)),
+            Kind::Toggles => list_of_objects(
+                value,
+                &[("name", Value::is_string), ("enabled", Value::is_boolean)],
+            ),
+            Kind::Bands => list_of_objects(
+                value,
+                &[("frequency", Value::is_number), ("gain", Value::is_number)],
+            ),
+        }
+    }
+}
+
+fn check_settings(
+    section: &str,
+    settings: &Map<String, Value>,
+    schema: &[(&str, Kind)],
+) -> Result<(), String> {
+    for (key, value) in settings {
+        let (_, kind) = schema
+            .iter()
+            .find(|(name, _)| name == key)
+            .ok_or_else(|| format!("unknown {} setting \"{}\"", section, key))?;
+        if !kind.accepts(value) {
+            return Err(format!("{}.{} has the wrong type", section, key));
+        }
+    }
+    Ok(())
+}
+
+/// Same rule as preset names typed in the preset manager (they become file names)
+fn valid_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ' '))
+}
+
+fn validate(preset: &ManifestPreset) -> Result<(), String> {
+    if !valid_name(&preset.name) {
+        return Err("names may only use letters, digits, spaces, '-' and '_'".to_string());
+    }
+    if preset.video.is_empty() && preset.audio.is_empty() {
+        return Err("sets no video or audio settings".to_string());
+    }
+    check_settings("video", &preset.video, VIDEO_FIELDS)?;
+    check_settings("audio", &preset.audio, AUDIO_FIELDS)
+}
+
+/// Presets of one manifest that pass validation; the rejected ones are logged
+fn load_manifest(path: &Path) -> Result<Vec<PluginPreset>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let manifest: Manifest = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let _ = crate::write_log(format!(
+        "[PLUGINS] {} {} by {} ({} presets)",
+        manifest.name,
+        manifest.version,
+        manifest.author,
+        manifest.presets.len()
+    ));
+
+    let mut presets = Vec::new();
+    for preset in manifest.presets {
+        if let Err(e) = validate(&preset) {
+            let _ = crate::write_log(format!(
+                "[PLUGINS] {}: preset \"{}\" rejected: {}",
+                manifest.name, preset.name, e
+            ));
+            continue;
+        }
+        presets.push(PluginPreset {
+            name: preset.name.trim().to_string(),
+            description: preset.description,
+            plugin: manifest.name.clone(),
+            constraints: preset.constraints,
+            video: preset.video,
+            audio: preset.audio,
+        });
+    }
+    Ok(presets)
+}
+
+pub fn get_plugins_dir() -> PathBuf {
+    crate::get_app_data_dir().join("plugins")
+}
+
+/// Read every manifest in the plugins folder (called at startup)
+pub fn scan() {
+    let mut manifests: Vec<PathBuf> = std::fs::read_dir(get_plugins_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    manifests.sort();
+
+    let mut presets: Vec<PluginPreset> = Vec::new();
+    for path in manifests {
+        match load_manifest(&path) {
+            Ok(loaded) => {
+                for preset in loaded {
+                    // The first manifest (by file name) providing a name wins
+                    if presets.iter().any(|p| p.name == preset.name) {
+                        let _ = crate::write_log(format!(
+                            "[PLUGINS] {}: preset \"{}\" already provided by another plugin",
+                            preset.plugin, preset.name
+                        ));
+                    } else {
+                        presets.push(preset);
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = crate::write_log(format!(
+                    "[PLUGINS] Invalid manifest {}: {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+    }
+
+    if let Ok(mut slot) = PRESETS.write() {
+        *slot = presets;
+    }
+}
+
+/// Presets loaded by the last scan
+pub fn presets() -> Vec<PluginPreset> {
+    PRESETS.read().map(|p| p.clone()).unwrap_or_default()
+}
+
+pub fn find(name: &str) -> Option<PluginPreset> {
+    PRESETS
+        .read()
+        .ok()
+        .and_then(|presets| presets.iter().find(|p| p.name == name).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(json: &str) -> ManifestPreset {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&preset(
+            r#"{"name": "AV1 archive", "video": {"codec": "av1", "crf": "32",
+                "filters": [{"name": "denoise", "enabled": true}]},
+                "constraints": {"encoders": ["libsvtav1"]}}"#
+        ))
+        .is_ok());
+        // Wrong type, unknown field, bad value, no settings, bad name
+        assert!(validate(&preset(r#"{"name": "A", "video": {"crf": 32}}"#)).is_err());
+        assert!(validate(&preset(r#"{"name": "A", "video": {"x265Params": "a"}}"#)).is_err());
+        assert!(validate(&preset(r#"{"name": "A", "video": {"rotation": "45"}}"#)).is_err());
+        assert!(validate(&preset(r#"{"name": "A"}"#)).is_err());
+        assert!(validate(&preset(r#"{"name": "a/b", "audio": {"codec": "aac"}}"#)).is_err());
+        assert!(serde_json::from_str::<ManifestPreset>(
+            r#"{"name": "A", "constraints": {"gpu": true}}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,52 @@
+// Portable mode
+// With a portable.flag file next to the executable, or when started with --portable,
+// the app keeps everything it would write to ~/.szhimatar (settings, presets, logs,
+// stats, queue, backups) in data/ beside the executable instead, so it can live on a USB
+// stick. The WebView still keeps its own cache in the user profile.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Launch argument that turns portable mode on without the flag file
+pub const PORTABLE_ARG: &str = "--portable";
+/// File next to the executable that turns portable mode on
+const FLAG_FILE: &str = "portable.flag";
+
+static DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// data/ beside the executable when the flag file or argument is present
+fn portable_dir(exe_dir: &Path, args: &[String]) -> Option<PathBuf> {
+    let enabled = exe_dir.join(FLAG_FILE).is_file() || args.iter().any(|arg| arg == PORTABLE_ARG);
+    enabled.then(|| exe_dir.join("data"))
+}
+
+/// App data folder of portable mode; None for a regular installation. Decided once
+/// per process.
+pub fn data_dir() -> Option<&'static Path> {
+    DATA_DIR
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            portable_dir(exe.parent()?, &args)
+        })
+        .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_dir() {
+        let dir = std::env::temp_dir().join(format!("szhimatar-portable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(portable_dir(&dir, &[]), None);
+        assert_eq!(
+            portable_dir(&dir, &["--portable".to_string()]),
+            Some(dir.join("data"))
+        );
+        std::fs::write(dir.join(FLAG_FILE), b"").unwrap();
+        assert_eq!(portable_dir(&dir, &[]), Some(dir.join("data")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,291 @@
+// Power management
+// Keeps the machine awake while renders run (SetThreadExecutionState on Windows,
+// caffeinate on macOS, systemd-inhibit on Linux) and watches AC / battery power
+// so the queue can pause or renders can throttle on battery.
+
+use serde::{Deserialize, Serialize};
+use std::process::Child;
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)] // layout must match SYSTEM_POWER_STATUS
+struct SystemPowerStatus {
+    ac_line_status: u8,
+    battery_flag: u8,
+    battery_life_percent: u8,
+    system_status_flag: u8,
+    battery_life_time: u32,
+    battery_full_life_time: u32,
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn SetThreadExecutionState(flags: u32) -> u32;
+    fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+}
+
+/// Prevents system sleep until dropped
+pub struct SleepInhibitor {
+    /// Helper process holding the inhibit lock (caffeinate / systemd-inhibit)
+    helper: Option<Child>,
+    /// Closing this channel lets the Windows holder thread clear its execution state
+    #[cfg(target_os = "windows")]
+    _release: std::sync::mpsc::Sender<()>,
+}
+
+impl SleepInhibitor {
+    /// Acquire a sleep inhibitor; `reason` is shown by systemd (`systemd-inhibit --list`)
+    pub fn acquire(reason: &str) -> Result<Self, String> {
+        #[cfg(target_os = "windows")]
+        {
+            const ES_CONTINUOUS: u32 = 0x8000_0000;
+            const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+            let _ = reason;
+
+            // The execution state belongs to the calling thread, so a dedicated
+            // thread holds it for the lifetime of the inhibitor
+            let (release, released) = std::sync::mpsc::channel::<()>();
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel::<bool>();
+            std::thread::spawn(move || {
+                // SAFETY: plain Win32 call without pointers
+                let ok = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+                let _ = ready_tx.send(ok != 0);
+                let _ = released.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            });
+
+            match ready_rx.recv() {
+                Ok(true) => Ok(Self {
+                    helper: None,
+                    _release: release,
+                }),
+                _ => Err("SetThreadExecutionState failed".to_string()),
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let mut cmd = if cfg!(target_os = "macos") {
+                // -i: prevent idle sleep, -w: also exit if the app dies
+                let mut cmd = crate::proc::command("caffeinate");
+                cmd.args(["-i", "-w", &std::process::id().to_string()]);
+                cmd
+            } else {
+                let mut cmd = crate::proc::command("systemd-inhibit");
+                cmd.args([
+                    "--what=sleep:idle",
+                    "--who=Szhimatar",
+                    &format!("--why={}", reason),
+                    "--mode=block",
+                    "sleep",
+                    "infinity",
+                ]);
+                cmd
+            };
+
+            let helper = cmd
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .map_err(|e| format!("Failed to start sleep inhibitor: {}", e))?;
+            Ok(Self {
+                helper: Some(helper),
+            })
+        }
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        if let Some(mut helper) = self.helper.take() {
+            let _ = helper.kill();
+            let _ = helper.wait();
+        }
+    }
+}
+
+// ============================================================================
+// AC / battery detection
+// ============================================================================
+
+/// How often the power source is checked
+const POWER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// None on desktops or when the charge level is unknown
+    pub battery_percent: Option<u8>,
+}
+
+/// What to do when the machine runs on battery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryAction {
+    #[default]
+    None,
+    /// Hold the queue until AC power returns (the running job finishes)
+    Pause,
+    /// Start renders on half the cores at idle priority
+    PowerSaver,
+}
+
+/// Payload of the `power-state-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerStateEvent {
+    #[serde(flatten)]
+    pub state: PowerState,
+    pub action: BatteryAction,
+    /// The frontend should pause its queue
+    pub pause_queue: bool,
+    /// AC power is back; a queue paused for battery may continue
+    pub resume_queue: bool,
+}
+
+/// Parse `pmset -g batt` ("Now drawing from 'Battery Power'" ... "87%;")
+#[cfg(any(target_os = "macos", test))]
+pub fn parse_pmset_batt(output: &str) -> PowerState {
+    let percent = output
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|part| part.strip_suffix('%')?.parse::<u8>().ok());
+    PowerState {
+        on_battery: output.contains("'Battery Power'"),
+        battery_percent: percent,
+    }
+}
+
+/// Current power source (AC is assumed when it cannot be read)
+pub fn read_power_state() -> PowerState {
+    #[cfg(target_os = "windows")]
+    {
+        let mut status = SystemPowerStatus::default();
+        // SAFETY: `status` is a properly laid out SYSTEM_POWER_STATUS
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return PowerState::default();
+        }
+        const NO_SYSTEM_BATTERY: u8 = 128;
+        PowerState {
+            on_battery: status.ac_line_status == 0,
+            battery_percent: (status.battery_flag & NO_SYSTEM_BATTERY == 0
+                && status.battery_life_percent <= 100)
+                .then_some(status.battery_life_percent),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::proc::run_captured(
+            crate::proc::command("pmset").args(["-g", "batt"]),
+            &crate::proc::RunOptions::with_timeout(10),
+        )
+        .map(|o| parse_pmset_batt(&o.stdout))
+        .unwrap_or_default()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let read = |path: std::path::PathBuf| {
+            std::fs::read_to_string(path)
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let mut ac_online = false;
+        let mut battery_percent = None;
+        let mut has_battery = false;
+        if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+            for supply in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                match read(supply.join("type")).as_str() {
+                    "Mains" | "USB" => ac_online |= read(supply.join("online")) == "1",
+                    // scope=Device marks mice/keyboards with their own battery
+                    "Battery" if read(supply.join("scope")) != "Device" => {
+                        has_battery = true;
+                        battery_percent =
+                            battery_percent.or(read(supply.join("capacity")).parse().ok());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        PowerState {
+            on_battery: has_battery && !ac_online,
+            battery_percent,
+        }
+    }
+}
+
+/// Current power source
+#[tauri::command]
+pub fn get_power_state() -> PowerState {
+    read_power_state()
+}
+
+fn battery_action() -> BatteryAction {
+    crate::load_settings()
+        .map(|s| s.battery_action)
+        .unwrap_or_default()
+}
+
+/// CPU limits for a render starting now: (threads, priority) when the machine is
+/// on battery and the power saver is enabled
+pub fn battery_render_limits() -> Option<(u32, crate::proc::Priority)> {
+    if battery_action() != BatteryAction::PowerSaver || !read_power_state().on_battery {
+        return None;
+    }
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(2);
+    Some(((cores / 2).max(1), crate::proc::Priority::Idle))
+}
+
+/// Poll the power source and emit `power-state-changed` when it changes
+/// (and once at startup so the UI can show it)
+pub fn start_power_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last: Option<PowerState> = None;
+        loop {
+            let state = read_power_state();
+            let source_changed = last.map(|l| l.on_battery) != Some(state.on_battery);
+            if source_changed {
+                let action = battery_action();
+                let event = PowerStateEvent {
+                    state,
+                    action,
+                    pause_queue: action == BatteryAction::Pause && state.on_battery,
+                    resume_queue: action == BatteryAction::Pause
+                        && !state.on_battery
+                        && last.is_some(),
+                };
+                let _ = crate::write_log(format!(
+                    "[POWER] on_battery={} percent={:?} action={:?}",
+                    state.on_battery, state.battery_percent, action
+                ));
+                let _ = tauri::Manager::emit_all(&app, "power-state-changed", &event);
+            }
+            last = Some(state);
+            std::thread::sleep(POWER_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset_batt() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t87%; discharging; 4:12 remaining present: true\n";
+        assert_eq!(
+            parse_pmset_batt(battery),
+            PowerState {
+                on_battery: true,
+                battery_percent: Some(87)
+            }
+        );
+        assert!(!parse_pmset_batt("Now drawing from 'AC Power'\n").on_battery);
+    }
+}
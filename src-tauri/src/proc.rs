@@ -0,0 +1,580 @@
+// Subprocess runner
+// One place for spawning helper processes: hidden console window on Windows,
+// timeouts, cancellation, output decoding and failure logging.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often a running process is polled for exit / timeout / cancel
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Create a command that never opens a console window on Windows
+pub fn command<S: AsRef<OsStr>>(program: S) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new(program);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    Command::new(program)
+}
+
+/// CPU scheduling priority of a process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    #[default]
+    Normal,
+    BelowNormal,
+    Idle,
+}
+
+impl Priority {
+    /// Unix nice value
+    #[cfg(not(target_os = "windows"))]
+    fn nice(self) -> i32 {
+        match self {
+            Priority::Normal => 0,
+            Priority::BelowNormal => 10,
+            Priority::Idle => 19,
+        }
+    }
+
+    /// Windows priority class
+    #[cfg(target_os = "windows")]
+    fn priority_class(self) -> u32 {
+        match self {
+            Priority::Normal => 0x0000_0020,
+            Priority::BelowNormal => 0x0000_4000,
+            Priority::Idle => 0x0000_0040,
+        }
+    }
+}
+
+/// Like `command`, but the process starts at `priority`
+/// (priority class creation flag on Windows, wrapped in `nice` elsewhere)
+pub fn command_with_priority<S: AsRef<OsStr>>(program: S, priority: Priority) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new(program);
+        cmd.creation_flags(CREATE_NO_WINDOW | priority.priority_class());
+        cmd
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if priority == Priority::Normal {
+            return Command::new(program);
+        }
+        // nice execs the program, so the PID stays the one of the real process
+        let mut cmd = Command::new("nice");
+        cmd.arg("-n").arg(priority.nice().to_string()).arg(program);
+        cmd
+    }
+}
+
+/// Limits applied while waiting for a process
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Kill the process when it runs longer than this
+    pub timeout: Option<Duration>,
+    /// Kill the process as soon as this flag becomes true
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// Written to app.log when the process fails to start, times out or is cancelled
+    pub log_label: Option<String>,
+}
+
+impl RunOptions {
+    pub fn with_timeout(seconds: u64) -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(seconds)),
+            ..Default::default()
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.log_label = Some(label.to_string());
+        self
+    }
+}
+
+/// Result of a finished process
+#[derive(Debug, Clone, Default)]
+pub struct ProcOutput {
+    /// Exit code (None when killed or terminated by a signal)
+    pub code: Option<i32>,
+    /// Signal that terminated the process (Unix only)
+    pub signal: Option<i32>,
+    pub success: bool,
+    /// Decoded stdout (empty for run_streaming and run_reading)
+    pub stdout: String,
+    /// Decoded stderr (empty for run_streaming)
+    pub stderr: String,
+    /// Killed because RunOptions::timeout elapsed
+    pub timed_out: bool,
+}
+
+/// Which pipe a streamed line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Decode process output: UTF-16LE (wmic, some PowerShell hosts) or UTF-8 with
+/// lossy replacement; a leading BOM is dropped in both cases
+pub fn decode_output(bytes: &[u8]) -> String {
+    let has_utf16_bom = bytes.starts_with(&[0xFF, 0xFE]);
+    let looks_utf16 = bytes.len() >= 4
+        && bytes.len().is_multiple_of(2)
+        && bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count() * 2 > bytes.len() / 2;
+
+    if has_utf16_bom || looks_utf16 {
+        let body = if has_utf16_bom { &bytes[2..] } else { bytes };
+        let units: Vec<u16> = body
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+
+    let body = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    String::from_utf8_lossy(body).to_string()
+}
+
+fn log_event(options: &RunOptions, message: String) {
+    if let Some(label) = &options.log_label {
+        let _ = crate::write_log(format!("[PROC] {}: {}", label, message));
+    }
+}
+
+/// Wait for a child, killing it on timeout or cancellation
+fn wait_with_limits(child: &mut Child, options: &RunOptions) -> Result<ProcOutput, String> {
+    let started = Instant::now();
+
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| format!("Failed to wait for process: {}", e))?
+        {
+            #[cfg(unix)]
+            let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+            #[cfg(not(unix))]
+            let signal = None;
+            return Ok(ProcOutput {
+                code: status.code(),
+                signal,
+                success: status.success(),
+                ..Default::default()
+            });
+        }
+
+        let timed_out = options
+            .timeout
+            .map(|limit| started.elapsed() >= limit)
+            .unwrap_or(false);
+        let cancelled = options
+            .cancel
+            .as_ref()
+            .map(|flag| flag.load(Ordering::Relaxed))
+            .unwrap_or(false);
+
+        if timed_out || cancelled {
+            let _ = child.kill();
+            let _ = child.wait();
+            if timed_out {
+                log_event(
+                    options,
+                    format!("timed out after {:.1}s", started.elapsed().as_secs_f64()),
+                );
+            } else {
+                log_event(options, "cancelled".to_string());
+            }
+            return Ok(ProcOutput {
+                timed_out,
+                ..Default::default()
+            });
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Spawn with stdin closed and stdout/stderr piped
+pub fn spawn_piped(cmd: &mut Command, options: &RunOptions) -> Result<Child, String> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            log_event(
+                options,
+                format!("failed to start {:?}: {}", cmd.get_program(), e),
+            );
+            e.to_string()
+        })
+}
+
+/// Run a process to completion and capture its decoded stdout/stderr
+pub fn run_captured(cmd: &mut Command, options: &RunOptions) -> Result<ProcOutput, String> {
+    let mut child = spawn_piped(cmd, options)?;
+
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+
+    // Pipes are drained on their own threads so a chatty process cannot block on a full pipe
+    std::thread::scope(|scope| {
+        let stdout_reader = scope.spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = stdout.as_mut() {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        });
+        let stderr_reader = scope.spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = stderr.as_mut() {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        });
+
+        let mut output = wait_with_limits(&mut child, options)?;
+        output.stdout = decode_output(&stdout_reader.join().unwrap_or_default());
+        output.stderr = decode_output(&stderr_reader.join().unwrap_or_default());
+        Ok(output)
+    })
+}
+
+/// Run a process to completion, handing its raw stdout to `on_data` as it arrives (for
+/// binary output too large to buffer, such as decoded audio). Only stderr is captured.
+pub fn run_reading<F>(
+    cmd: &mut Command,
+    options: &RunOptions,
+    mut on_data: F,
+) -> Result<ProcOutput, String>
+where
+    F: FnMut(&[u8]) + Send,
+{
+    let mut child = spawn_piped(cmd, options)?;
+
+    let stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+
+    std::thread::scope(|scope| {
+        let stdout_reader = scope.spawn(move || {
+            if let Some(mut pipe) = stdout {
+                let mut buf = [0u8; 65536];
+                loop {
+                    match pipe.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => on_data(&buf[..n]),
+                    }
+                }
+            }
+        });
+        let stderr_reader = scope.spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = stderr.as_mut() {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        });
+
+        let mut output = wait_with_limits(&mut child, options)?;
+        let _ = stdout_reader.join();
+        output.stderr = decode_output(&stderr_reader.join().unwrap_or_default());
+        Ok(output)
+    })
+}
+
+/// Read a pipe and hand every line to `on_line`. Lines end at '\n' or '\r'
+/// (FFmpeg rewrites its stats line with '\r'); empty lines are skipped.
+fn read_lines<R: Read>(mut pipe: R, mut on_line: impl FnMut(&str)) {
+    let mut buf = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+
+    loop {
+        let read = match pipe.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        for &byte in &buf[..read] {
+            if byte == b'\n' || byte == b'\r' {
+                if !pending.is_empty() {
+                    on_line(&decode_output(&pending));
+                    pending.clear();
+                }
+            } else {
+                pending.push(byte);
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        on_line(&decode_output(&pending));
+    }
+}
+
+/// Stream the output of a spawned child (stdout/stderr must be piped) line by line
+/// until it exits. Takes the child rather than a Command so callers can register
+/// it first (renders are tracked by the ProcessManager before waiting).
+pub fn run_streaming<F>(
+    child: &mut Child,
+    options: &RunOptions,
+    on_line: F,
+) -> Result<ProcOutput, String>
+where
+    F: FnMut(Stream, &str) + Send,
+{
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let on_line = Mutex::new(on_line);
+
+    std::thread::scope(|scope| {
+        let on_line = &on_line;
+        let emit = move |stream: Stream, line: &str| {
+            if let Ok(mut callback) = on_line.lock() {
+                callback(stream, line);
+            }
+        };
+
+        let stdout_reader = scope.spawn(move || {
+            if let Some(pipe) = stdout {
+                read_lines(pipe, |line| emit(Stream::Stdout, line));
+            }
+        });
+        let stderr_reader = scope.spawn(move || {
+            if let Some(pipe) = stderr {
+                read_lines(pipe, |line| emit(Stream::Stderr, line));
+            }
+        });
+
+        let output = wait_with_limits(child, options);
+        let _ = stdout_reader.join();
+        let _ = stderr_reader.join();
+        output
+    })
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn SetProcessAffinityMask(process: *mut std::ffi::c_void, mask: usize) -> i32;
+    pub(crate) fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut std::ffi::c_void;
+    fn SetPriorityClass(process: *mut std::ffi::c_void, class: u32) -> i32;
+    pub(crate) fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtSuspendProcess(process: *mut std::ffi::c_void) -> i32;
+    fn NtResumeProcess(process: *mut std::ffi::c_void) -> i32;
+}
+
+/// Change the priority of a running process. On Unix every thread is reniced;
+/// going back to a higher priority may need privileges.
+pub fn set_priority(pid: u32, priority: Priority) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        const PROCESS_SET_INFORMATION: u32 = 0x0200;
+        // SAFETY: plain Win32 calls; the handle is closed before returning
+        unsafe {
+            let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+            if handle.is_null() {
+                return Err(format!(
+                    "OpenProcess failed: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            let ok = SetPriorityClass(handle, priority.priority_class());
+            let error = std::io::Error::last_os_error();
+            CloseHandle(handle);
+            if ok == 0 {
+                return Err(format!("SetPriorityClass failed: {}", error));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // Linux nice values are per thread; FFmpeg's workers live in /proc/<pid>/task
+        let mut ids: Vec<String> = std::fs::read_dir(format!("/proc/{}/task", pid))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if ids.is_empty() {
+            ids.push(pid.to_string());
+        }
+
+        let mut cmd = command("renice");
+        cmd.arg(priority.nice().to_string()).arg("-p").args(&ids);
+        let output = run_captured(&mut cmd, &RunOptions::with_timeout(10).label("renice"))?;
+        if !output.success {
+            return Err(format!("renice failed: {}", output.stderr.trim()));
+        }
+        Ok(())
+    }
+}
+
+/// Restrict a spawned process to the first `cores` logical CPUs
+/// (SetProcessAffinityMask on Windows, taskset on Linux; unsupported on macOS)
+pub fn pin_to_first_cores(child: &Child, cores: usize) -> Result<(), String> {
+    let total = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let cores = cores.clamp(1, total);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::io::AsRawHandle;
+        let cores = cores.min(usize::BITS as usize);
+        let mask = if cores == usize::BITS as usize {
+            usize::MAX
+        } else {
+            (1usize << cores) - 1
+        };
+        // SAFETY: the handle belongs to a live Child owned by the caller
+        let ok = unsafe { SetProcessAffinityMask(child.as_raw_handle() as _, mask) };
+        if ok == 0 {
+            return Err(format!(
+                "SetProcessAffinityMask failed: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // -a applies to every thread FFmpeg has started so far
+        let output = run_captured(
+            command("taskset").args([
+                "-a",
+                "-p",
+                "-c",
+                &format!("0-{}", cores - 1),
+                &child.id().to_string(),
+            ]),
+            &RunOptions::with_timeout(10).label("taskset"),
+        )?;
+        if !output.success {
+            return Err(format!("taskset failed: {}", output.stderr.trim()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (child, cores);
+        Err("CPU affinity is not supported on this platform".to_string())
+    }
+}
+
+/// Force-kill a process by PID (taskkill /F on Windows, kill -9 elsewhere)
+pub fn kill_pid(pid: u32) {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = command("taskkill");
+        cmd.args(["/PID", &pid.to_string(), "/F"]);
+        cmd
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = command("kill");
+        cmd.args(["-9", &pid.to_string()]);
+        cmd
+    };
+
+    let _ = run_captured(&mut cmd, &RunOptions::with_timeout(10).label("kill"));
+}
+
+/// Suspend or resume every thread of a process
+/// (NtSuspendProcess / NtResumeProcess on Windows, SIGSTOP / SIGCONT elsewhere)
+pub fn suspend_pid(pid: u32, suspend: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+        // SAFETY: plain Win32 calls; the handle is closed before returning
+        unsafe {
+            let handle = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+            if handle.is_null() {
+                return Err(format!(
+                    "OpenProcess failed: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            let status = if suspend {
+                NtSuspendProcess(handle)
+            } else {
+                NtResumeProcess(handle)
+            };
+            CloseHandle(handle);
+            if status != 0 {
+                return Err(format!(
+                    "NtSuspendProcess/NtResumeProcess failed: {:#x}",
+                    status
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let signal = if suspend { "-STOP" } else { "-CONT" };
+        let output = run_captured(
+            command("kill").args([signal, &pid.to_string()]),
+            &RunOptions::with_timeout(10).label("kill"),
+        )?;
+        if !output.success {
+            return Err(format!("kill {} failed: {}", signal, output.stderr.trim()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf16_with_bom() {
+        let bytes = [0xFF, 0xFE, b'N', 0, b'a', 0, b'm', 0, b'e', 0];
+        assert_eq!(decode_output(&bytes), "Name");
+    }
+
+    #[test]
+    fn test_decode_utf8_strips_bom() {
+        assert_eq!(decode_output(b"\xEF\xBB\xBFffmpeg"), "ffmpeg");
+        assert_eq!(decode_output("видео".as_bytes()), "видео");
+    }
+
+    #[test]
+    fn test_read_lines_splits_carriage_returns() {
+        let mut lines = Vec::new();
+        read_lines(&b"frame=1\rframe=2\r\nprogress=end\n"[..], |l| {
+            lines.push(l.to_string())
+        });
+        assert_eq!(lines, vec!["frame=1", "frame=2", "progress=end"]);
+    }
+}
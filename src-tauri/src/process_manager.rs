@@ -4,7 +4,8 @@
 use lazy_static::lazy_static;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::io::Write;
+use std::process::{Child, ChildStdin, Stdio};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -29,10 +30,47 @@ pub struct RenderProcess {
     pub pid: u32,
 }
 
+/// Per-render process options applied by spawn_render
+#[derive(Debug, Clone, Default)]
+pub struct RenderSpawnOptions {
+    /// Options placed before -i (e.g. -hwaccel)
+    pub input_args: Vec<String>,
+    /// Limit decoder/encoder/filter threads; None = FFmpeg default (all cores)
+    pub cpu_threads: Option<u32>,
+    /// Also pin the process to the first `cpu_threads` cores
+    pub cpu_affinity: bool,
+    /// CPU priority the process starts with
+    pub priority: crate::proc::Priority,
+}
+
+/// Thread-limit arguments for `threads` threads. -threads before -i limits the
+/// decoder; the output -threads / -filter_threads / x265 pools limit the rest.
+/// Returns (input args, output args); an explicit -threads in `ffmpeg_args` wins.
+pub fn thread_limit_args(threads: u32, ffmpeg_args: &[String]) -> (Vec<String>, Vec<String>) {
+    let threads = threads.to_string();
+    let input = vec!["-threads".to_string(), threads.clone()];
+    let mut output = vec!["-filter_threads".to_string(), threads.clone()];
+
+    if !ffmpeg_args.iter().any(|a| a == "-threads") {
+        output.extend(["-threads".to_string(), threads.clone()]);
+    }
+    // x265 sizes its thread pools itself and ignores -threads
+    let uses_x265 = crate::encode_args::video_encoder_in(ffmpeg_args) == Some("libx265");
+    if uses_x265 && !ffmpeg_args.iter().any(|a| a == "-x265-params") {
+        output.extend(["-x265-params".to_string(), format!("pools={}", threads)]);
+    }
+
+    (input, output)
+}
+
 /// Manages all active FFmpeg processes
 pub struct ProcessManager {
     processes: HashMap<String, RenderProcess>,
+    /// FFmpeg reads keyboard commands from stdin; "q" makes it finish the file and exit
+    stdins: HashMap<String, ChildStdin>,
     stopped: HashSet<String>,
+    /// Held while any render runs so the machine does not sleep mid-encode
+    sleep_inhibitor: Option<crate::power::SleepInhibitor>,
 }
 
 impl ProcessManager {
@@ -40,7 +78,9 @@ impl ProcessManager {
     pub fn new() -> Self {
         Self {
             processes: HashMap::new(),
+            stdins: HashMap::new(),
             stopped: HashSet::new(),
+            sleep_inhibitor: None,
         }
     }
 
@@ -52,6 +92,7 @@ impl ProcessManager {
     /// * `input_path` - Input video file path
     /// * `output_path` - Output video file path
     /// * `ffmpeg_args` - FFmpeg command arguments
+    /// * `options` - Input arguments and CPU limits
     ///
     /// # Returns
     /// Result with (Child, PID) tuple or error message
@@ -65,42 +106,47 @@ impl ProcessManager {
         input_path: String,
         output_path: String,
         ffmpeg_args: Vec<String>,
+        options: &RenderSpawnOptions,
     ) -> Result<(Child, u32), String> {
-        // Build command with CREATE_NO_WINDOW on Windows
-        #[cfg(target_os = "windows")]
-        let mut cmd = {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            let mut cmd = Command::new(&ffmpeg_path);
-            cmd.creation_flags(CREATE_NO_WINDOW);
-            cmd
-        };
+        // Build command (no console window on Windows, started at the requested priority)
+        let mut cmd = crate::proc::command_with_priority(&ffmpeg_path, options.priority);
 
-        #[cfg(not(target_os = "windows"))]
-        let mut cmd = Command::new(&ffmpeg_path);
+        let cpu_threads = options.cpu_threads.filter(|t| *t > 0);
+        let (thread_input_args, thread_output_args) = cpu_threads
+            .map(|t| thread_limit_args(t, &ffmpeg_args))
+            .unwrap_or_default();
 
         // Build full command
         cmd.arg("-y") // Overwrite output
+            .args(&options.input_args)
+            .args(&thread_input_args)
             .arg("-i")
             .arg(&input_path)
             .args(&ffmpeg_args)
+            .args(&thread_output_args)
             .arg("-progress")
             .arg("pipe:1")
             .arg("-stats_period")
             .arg("0.5")
             .arg(&output_path)
-            .stdin(Stdio::null())
+            .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
         // Spawn process
-        let child = cmd
+        let mut child = cmd
             .spawn()
             .map_err(|e| format!("Failed to spawn FFmpeg: {}", e))?;
 
         // Get PID
         let pid = child.id();
 
+        if let Some(cores) = cpu_threads.filter(|_| options.cpu_affinity) {
+            if let Err(e) = crate::proc::pin_to_first_cores(&child, cores as usize) {
+                let _ = crate::write_log(format!("[AFFINITY] job {}: {}", job_id, e));
+            }
+        }
+
         // Store metadata ONLY (not the Child, which goes to the caller)
         let process = RenderProcess {
             id: job_id.clone(),
@@ -110,8 +156,13 @@ impl ProcessManager {
             pid,
         };
 
-        // Store in map for tracking/lookup
+        // Store in map for tracking/lookup, and in the run file in case the app dies
+        crate::orphans::record_spawn(&job_id, pid, &output_path);
+        if let Some(stdin) = child.stdin.take() {
+            self.stdins.insert(job_id.clone(), stdin);
+        }
         self.processes.insert(job_id.clone(), process);
+        self.update_sleep_inhibitor();
 
         // eprintln!("✅ [ProcessManager] Spawned FFmpeg process - Job: {}, PID: {}", job_id, pid);
 
@@ -146,6 +197,16 @@ impl ProcessManager {
         }
     }
 
+    /// Ask a render's FFmpeg to quit the way pressing "q" does: it stops encoding,
+    /// finalizes the output file and exits. Returns false when the request could not be
+    /// sent; the caller then has to kill the process.
+    pub fn request_quit(&mut self, job_id: &str) -> bool {
+        match self.stdins.get_mut(job_id) {
+            Some(stdin) => stdin.write_all(b"q").and_then(|_| stdin.flush()).is_ok(),
+            None => false,
+        }
+    }
+
     /// Stop all running renders
     pub fn stop_all_renders(&mut self) {
         let job_ids: Vec<String> = self.processes.keys().cloned().collect();
@@ -160,9 +221,33 @@ impl ProcessManager {
     /// Clean up finished process
     pub fn remove_process(&mut self, job_id: &str) {
         if self.processes.remove(job_id).is_some() {
+            crate::orphans::record_exit(job_id);
             eprintln!("✅ [ProcessManager] Cleaned up process - Job: {}", job_id);
         }
+        self.stdins.remove(job_id);
         self.stopped.remove(job_id);
+        self.update_sleep_inhibitor();
+    }
+
+    /// Acquire the sleep inhibitor when the first render starts and release it
+    /// when the last one finishes
+    fn update_sleep_inhibitor(&mut self) {
+        if self.processes.is_empty() {
+            if self.sleep_inhibitor.take().is_some() {
+                let _ = crate::write_log("[POWER] Sleep allowed again".to_string());
+            }
+        } else if self.sleep_inhibitor.is_none() {
+            match crate::power::SleepInhibitor::acquire("Rendering video") {
+                Ok(inhibitor) => {
+                    self.sleep_inhibitor = Some(inhibitor);
+                    let _ =
+                        crate::write_log("[POWER] Preventing sleep while rendering".to_string());
+                }
+                Err(e) => {
+                    let _ = crate::write_log(format!("[POWER] {}", e));
+                }
+            }
+        }
     }
 
     /// Verify process is actually killed (useful for post-kill verification)
@@ -255,6 +340,7 @@ impl RenderProcessContext {
             input_path,
             output_path,
             ffmpeg_args,
+            &RenderSpawnOptions::default(),
         )?;
 
         Ok(Self { job_id, child, pid })
@@ -287,4 +373,25 @@ mod tests {
         let jobs = manager.active_jobs();
         assert!(jobs.is_empty());
     }
+
+    #[test]
+    fn test_thread_limit_args() {
+        let args: Vec<String> = ["-c:v", "libx265", "-crf", "24"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let (input, output) = thread_limit_args(4, &args);
+        assert_eq!(input, vec!["-threads", "4"]);
+        assert_eq!(
+            output,
+            vec![
+                "-filter_threads",
+                "4",
+                "-threads",
+                "4",
+                "-x265-params",
+                "pools=4"
+            ]
+        );
+    }
 }
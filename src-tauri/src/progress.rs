@@ -0,0 +1,351 @@
+// Render progress throttling
+// FFmpeg reports progress on both pipes (-progress on stdout, stats on stderr) every
+// half second; with several jobs running, emitting every report flooded the IPC channel
+// and made the UI stutter. Reports are now coalesced per job: only the newest is kept,
+// and it is emitted at most every PROGRESS_INTERVAL.
+// The ETA comes from an exponential moving average of the speed instead of the last
+// report, and each job keeps a rolling speed/fps history for the UI graph.
+// Progress is measured against the source duration, or against its frame count when the
+// duration cannot be trusted (variable frame rate, image sequences).
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two `render-progress` events of one job
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Keeps the newest update and lets it through at most once per interval
+pub struct Throttle<T> {
+    interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<T>,
+}
+
+impl<T> Throttle<T> {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: None,
+            pending: None,
+        }
+    }
+
+    /// Record an update; returns the newest one when it is time to emit
+    pub fn offer(&mut self, update: T, now: Instant) -> Option<T> {
+        self.pending = Some(update);
+        let due = self
+            .last_emit
+            .is_none_or(|last| now.duration_since(last) >= self.interval);
+        if due {
+            self.last_emit = Some(now);
+            self.pending.take()
+        } else {
+            None
+        }
+    }
+
+    /// The update held back since the last emit, e.g. once the process exited
+    pub fn flush(&mut self) -> Option<T> {
+        self.pending.take()
+    }
+}
+
+/// Time constant of the speed average; older reports fade out over about this long
+const SPEED_SMOOTHING: Duration = Duration::from_secs(5);
+/// Minimum time between two samples of a job's speed history
+const HISTORY_INTERVAL: Duration = Duration::from_secs(1);
+/// Samples kept per job (the last 10 minutes)
+const HISTORY_SAMPLES: usize = 600;
+/// Jobs whose history is kept; the oldest is dropped first
+const HISTORY_JOBS: usize = 16;
+
+/// Exponential moving average of FFmpeg's speed. Time based, so the duplicate reports of
+/// the two pipes do not weigh twice.
+#[derive(Default)]
+pub struct SpeedSmoother {
+    value: Option<f64>,
+    last: Option<Instant>,
+}
+
+impl SpeedSmoother {
+    /// Fold in a speed report and return the average; 0 (nothing encoded yet) is skipped
+    pub fn update(&mut self, speed: f64, now: Instant) -> f64 {
+        if speed > 0.0 {
+            self.value = Some(match (self.value, self.last) {
+                (Some(value), Some(last)) => {
+                    let dt = now.duration_since(last).as_secs_f64();
+                    let alpha = 1.0 - (-dt / SPEED_SMOOTHING.as_secs_f64()).exp();
+                    value + alpha * (speed - value)
+                }
+                _ => speed,
+            });
+            self.last = Some(now);
+        }
+        self.value.unwrap_or(0.0)
+    }
+}
+
+/// Seconds left for `remaining` seconds of media at `speed`
+pub fn eta_seconds(remaining: f64, speed: f64) -> f64 {
+    if speed > 0.0 && remaining > 0.0 {
+        remaining / speed
+    } else {
+        0.0
+    }
+}
+
+/// What progress_percent of a render is measured against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressBasis {
+    /// Encoded media time of the job duration
+    Time,
+    /// Encoded frames of the source's frame count
+    Frames,
+}
+
+impl ProgressBasis {
+    /// Basis of a render with the frame count from frame_total
+    pub fn of(total_frames: Option<u64>) -> Self {
+        match total_frames {
+            Some(_) => ProgressBasis::Frames,
+            None => ProgressBasis::Time,
+        }
+    }
+}
+
+/// Frames to encode when the duration is unreliable for progress, from the ffprobe
+/// JSON of the source. None keeps time-based progress.
+pub fn frame_total(probe: &serde_json::Value, job_duration: f64) -> Option<u64> {
+    let streams = probe["streams"].as_array()?;
+    let video = streams
+        .iter()
+        .find(|s| s["codec_type"] == "video" && s["disposition"]["attached_pic"] != 1)?;
+    let rate =
+        |key: &str| crate::library::parse_frame_rate(video[key].as_str().unwrap_or_default());
+    let (avg_rate, real_rate) = (rate("avg_frame_rate"), rate("r_frame_rate"));
+    let source_duration = probe["format"]["duration"]
+        .as_str()
+        .or(video["duration"].as_str())
+        .and_then(|d| d.parse::<f64>().ok())
+        .filter(|d| *d > 0.0);
+    let format_name = probe["format"]["format_name"].as_str().unwrap_or_default();
+
+    let image_input = format_name == "image2" || format_name.ends_with("_pipe");
+    let variable_rate =
+        avg_rate > 0.0 && real_rate > 0.0 && (avg_rate / real_rate - 1.0).abs() > 0.01;
+    if job_duration > 0.0 && source_duration.is_some() && !image_input && !variable_rate {
+        return None;
+    }
+
+    let fps = if avg_rate > 0.0 { avg_rate } else { real_rate };
+    let frames = video["nb_frames"]
+        .as_str()
+        .and_then(|n| n.parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .map(|n| n as f64)
+        .or_else(|| source_duration.map(|d| d * fps).filter(|n| *n >= 1.0))?;
+    // A trimmed job encodes only part of the source
+    let frames = match source_duration {
+        Some(source) if job_duration > 0.0 && job_duration + 0.5 < source => {
+            frames * job_duration / source
+        }
+        _ => frames,
+    };
+    Some((frames.round() as u64).max(1))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeedSample {
+    /// Seconds since the job started
+    pub elapsed_seconds: f64,
+    pub phase: crate::phases::JobPhase,
+    pub progress_percent: f64,
+    pub speed: f64,
+    pub smoothed_speed: f64,
+    pub fps: f64,
+}
+
+struct JobHistory {
+    job_id: String,
+    started: Instant,
+    last_sample: Option<Instant>,
+    samples: VecDeque<SpeedSample>,
+}
+
+static SPEED_HISTORY: Mutex<VecDeque<JobHistory>> = Mutex::new(VecDeque::new());
+
+/// Start an empty history for a job (again, when it is re-run)
+pub fn start_history(job_id: &str) {
+    let Ok(mut jobs) = SPEED_HISTORY.lock() else {
+        return;
+    };
+    jobs.retain(|history| history.job_id != job_id);
+    if jobs.len() >= HISTORY_JOBS {
+        jobs.pop_front();
+    }
+    jobs.push_back(JobHistory {
+        job_id: job_id.to_string(),
+        started: Instant::now(),
+        last_sample: None,
+        samples: VecDeque::new(),
+    });
+}
+
+/// Add a sample unless the last one is less than HISTORY_INTERVAL old
+pub fn record_sample(
+    job_id: &str,
+    now: Instant,
+    phase: crate::phases::JobPhase,
+    progress_percent: f64,
+    speed: f64,
+    smoothed_speed: f64,
+    fps: f64,
+) {
+    let Ok(mut jobs) = SPEED_HISTORY.lock() else {
+        return;
+    };
+    let Some(history) = jobs.iter_mut().find(|history| history.job_id == job_id) else {
+        return;
+    };
+    if history
+        .last_sample
+        .is_some_and(|last| now.duration_since(last) < HISTORY_INTERVAL)
+    {
+        return;
+    }
+    history.last_sample = Some(now);
+    if history.samples.len() >= HISTORY_SAMPLES {
+        history.samples.pop_front();
+    }
+    history.samples.push_back(SpeedSample {
+        elapsed_seconds: now.duration_since(history.started).as_secs_f64(),
+        phase,
+        progress_percent,
+        speed,
+        smoothed_speed,
+        fps,
+    });
+}
+
+/// Speed/fps samples of a job, oldest first; empty for unknown jobs
+#[tauri::command]
+pub fn get_job_speed_history(job_id: String) -> Vec<SpeedSample> {
+    SPEED_HISTORY
+        .lock()
+        .ok()
+        .and_then(|jobs| {
+            jobs.iter()
+                .find(|history| history.job_id == job_id)
+                .map(|history| history.samples.iter().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(Duration::from_millis(250));
+        assert_eq!(throttle.offer(1, start), Some(1));
+        assert_eq!(throttle.offer(2, start + Duration::from_millis(100)), None);
+        assert_eq!(throttle.offer(3, start + Duration::from_millis(200)), None);
+        // The newest update wins once the interval passed
+        assert_eq!(
+            throttle.offer(4, start + Duration::from_millis(260)),
+            Some(4)
+        );
+        assert_eq!(throttle.flush(), None);
+        assert_eq!(throttle.offer(5, start + Duration::from_millis(300)), None);
+        assert_eq!(throttle.flush(), Some(5));
+    }
+
+    #[test]
+    fn test_speed_smoother() {
+        let start = Instant::now();
+        let mut smoother = SpeedSmoother::default();
+        assert_eq!(smoother.update(0.0, start), 0.0);
+        assert_eq!(smoother.update(2.0, start), 2.0);
+        // A spike only moves the average part of the way
+        let spiked = smoother.update(4.0, start + Duration::from_millis(500));
+        assert!(spiked > 2.0 && spiked < 2.5);
+        // A report at the same instant (the other pipe) changes nothing
+        assert_eq!(
+            smoother.update(4.0, start + Duration::from_millis(500)),
+            spiked
+        );
+        let settled = smoother.update(4.0, start + Duration::from_secs(60));
+        assert!((settled - 4.0).abs() < 0.01);
+        assert_eq!(eta_seconds(120.0, 2.0), 60.0);
+        assert_eq!(eta_seconds(120.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_speed_history() {
+        let job_id = "test_speed_history";
+        start_history(job_id);
+        let now = Instant::now();
+        let phase = crate::phases::JobPhase::Encoding;
+        record_sample(job_id, now, phase, 10.0, 1.5, 1.4, 30.0);
+        record_sample(
+            job_id,
+            now + Duration::from_millis(300),
+            phase,
+            11.0,
+            1.6,
+            1.4,
+            31.0,
+        );
+        record_sample(
+            job_id,
+            now + Duration::from_secs(1),
+            phase,
+            12.0,
+            1.7,
+            1.5,
+            32.0,
+        );
+        let history = get_job_speed_history(job_id.to_string());
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].fps, 32.0);
+        start_history(job_id);
+        assert!(get_job_speed_history(job_id.to_string()).is_empty());
+        assert!(get_job_speed_history("unknown".to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_frame_total() {
+        let probe = |format: &str, avg: &str, frames: &str| {
+            serde_json::json!({
+                "format": {"format_name": format, "duration": "10.000000"},
+                "streams": [
+                    {"codec_type": "video", "disposition": {"attached_pic": 0},
+                     "avg_frame_rate": avg, "r_frame_rate": "30/1", "nb_frames": frames}
+                ]
+            })
+        };
+        // Constant frame rate with a known duration stays time based
+        assert_eq!(
+            frame_total(&probe("mov,mp4,m4a,3gp,3g2,mj2", "30/1", "300"), 10.0),
+            None
+        );
+        // Variable frame rate counts frames
+        assert_eq!(
+            frame_total(&probe("mov,mp4,m4a,3gp,3g2,mj2", "24/1", "240"), 10.0),
+            Some(240)
+        );
+        // Without nb_frames: duration x fps, scaled to the trimmed part
+        assert_eq!(frame_total(&probe("image2", "30/1", ""), 5.0), Some(150));
+        // Unknown job duration
+        assert_eq!(
+            frame_total(&probe("matroska,webm", "30/1", "300"), 0.0),
+            Some(300)
+        );
+        assert_eq!(frame_total(&serde_json::json!({"streams": []}), 0.0), None);
+    }
+}
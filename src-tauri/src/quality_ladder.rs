@@ -0,0 +1,312 @@
+// Quality ladder
+// run_quality_ladder encodes the same short segment from the middle of the source at
+// each requested CRF and measures its VMAF and size, so users can see where a lower
+// CRF stops buying visible quality. The encodes and VMAF runs go one after another
+// through the ProcessManager under LADDER_JOB_ID, which lets cancel_quality_ladder
+// stop them like a render.
+
+use crate::process_manager::PROCESS_MANAGER;
+use serde::Serialize;
+
+/// ProcessManager id of the running ladder step (one ladder at a time)
+const LADDER_JOB_ID: &str = "quality-ladder";
+const SEGMENT_SECONDS: f64 = 10.0;
+const MAX_STEPS: usize = 10;
+const LADDER_ENCODER: &str = "libx264";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LadderStep {
+    pub crf: u32,
+    pub size_bytes: u64,
+    pub bitrate_kbps: f64,
+    pub vmaf: f64,
+    pub encode_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityLadder {
+    pub encoder: String,
+    pub segment_start: f64,
+    pub segment_seconds: f64,
+    /// Ordered from the lowest (best) CRF up
+    pub steps: Vec<LadderStep>,
+}
+
+/// Payload of the `quality-ladder-progress` event (sent before each CRF is encoded)
+#[derive(Debug, Clone, Serialize)]
+pub struct LadderProgress {
+    pub crf: u32,
+    pub index: usize,
+    pub total: usize,
+}
+
+/// Valid CRFs, deduplicated and sorted, at most MAX_STEPS
+fn ladder_crfs(crf_values: &[u32]) -> Vec<u32> {
+    let mut crfs: Vec<u32> = crf_values
+        .iter()
+        .copied()
+        .filter(|crf| *crf <= 51)
+        .collect();
+    crfs.sort_unstable();
+    crfs.dedup();
+    crfs.truncate(MAX_STEPS);
+    crfs
+}
+
+/// Start and length of the segment: the middle of the source
+fn segment(duration: f64) -> (f64, f64) {
+    if duration <= SEGMENT_SECONDS {
+        (0.0, duration.max(0.0))
+    } else {
+        ((duration - SEGMENT_SECONDS) / 2.0, SEGMENT_SECONDS)
+    }
+}
+
+/// "VMAF score: 93.456" of the libvmaf summary line
+fn parse_vmaf(line: &str) -> Option<f64> {
+    line.split("VMAF score:")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse::<f64>().ok())
+}
+
+/// Run one FFmpeg step under LADDER_JOB_ID. Returns the stderr lines, or None when
+/// cancel_quality_ladder stopped it.
+fn run_step(
+    ffmpeg_path: &str,
+    input_path: &str,
+    input_args: Vec<String>,
+    args: Vec<String>,
+    output_path: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let mut child = {
+        let mut manager = PROCESS_MANAGER
+            .lock()
+            .map_err(|e| format!("Failed to lock ProcessManager: {}", e))?;
+        let (child, _pid) = manager.spawn_render(
+            LADDER_JOB_ID.to_string(),
+            ffmpeg_path.to_string(),
+            input_path.to_string(),
+            output_path.to_string(),
+            args,
+            &crate::process_manager::RenderSpawnOptions {
+                input_args,
+                ..Default::default()
+            },
+        )?;
+        child
+    };
+
+    let mut stderr = Vec::new();
+    let output = crate::proc::run_streaming(
+        &mut child,
+        &crate::proc::RunOptions::default(),
+        |stream, line| {
+            if stream == crate::proc::Stream::Stderr {
+                stderr.push(line.to_string());
+            }
+        },
+    );
+
+    let stopped = {
+        let mut manager = PROCESS_MANAGER
+            .lock()
+            .map_err(|e| format!("Failed to lock ProcessManager: {}", e))?;
+        let stopped = manager.take_stopped(LADDER_JOB_ID);
+        manager.remove_process(LADDER_JOB_ID);
+        stopped
+    };
+    let output = output?;
+    if stopped {
+        return Ok(None);
+    }
+    if !output.success {
+        let error = stderr
+            .iter()
+            .rev()
+            .find(|line| !line.contains("frame="))
+            .cloned()
+            .unwrap_or_else(|| format!("FFmpeg exited with code {:?}", output.code));
+        return Err(error);
+    }
+    Ok(Some(stderr))
+}
+
+/// Encode the segment at `crf` and score it; None when cancelled
+fn ladder_step(
+    ffmpeg_path: &str,
+    input: &str,
+    (start, length): (f64, f64),
+    crf: u32,
+) -> Result<Option<LadderStep>, String> {
+    let encoded = std::env::temp_dir().join(format!(
+        "szhimatar-ladder-{}-{}.mkv",
+        std::process::id(),
+        crf
+    ));
+    let encoded_path = encoded.to_string_lossy().to_string();
+    let start_arg = format!("{:.3}", start);
+    let length_arg = format!("{:.3}", length);
+    let to_args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+
+    let started = std::time::Instant::now();
+    let encoded_ok = run_step(
+        ffmpeg_path,
+        input,
+        to_args(&["-ss", &start_arg]),
+        to_args(&[
+            "-t",
+            &length_arg,
+            "-map",
+            "0:v:0",
+            "-an",
+            "-sn",
+            "-c:v",
+            LADDER_ENCODER,
+            "-preset",
+            "medium",
+            "-crf",
+            &crf.to_string(),
+        ]),
+        &encoded_path,
+    );
+    let encode_seconds = started.elapsed().as_secs_f64();
+    let step = encoded_ok.and_then(|done| {
+        if done.is_none() {
+            return Ok(None);
+        }
+        let size_bytes = std::fs::metadata(&encoded).map(|m| m.len()).unwrap_or(0);
+        // Distorted first, reference second; the segment of the source is cut the same way
+        let scored = run_step(
+            ffmpeg_path,
+            input,
+            to_args(&["-i", &encoded_path, "-ss", &start_arg, "-t", &length_arg]),
+            to_args(&["-lavfi", "[0:v][1:v:0]libvmaf", "-f", "null"]),
+            "-",
+        )
+        .map_err(|e| {
+            if e.contains("No such filter") {
+                "The quality ladder needs an FFmpeg build with libvmaf".to_string()
+            } else {
+                e
+            }
+        })?;
+        let Some(lines) = scored else {
+            return Ok(None);
+        };
+        let vmaf = lines
+            .iter()
+            .find_map(|line| parse_vmaf(line))
+            .ok_or("VMAF measurement failed")?;
+        Ok(Some(LadderStep {
+            crf,
+            size_bytes,
+            bitrate_kbps: size_bytes as f64 * 8.0 / 1000.0 / length.max(0.001),
+            vmaf,
+            encode_seconds,
+        }))
+    });
+    let _ = std::fs::remove_file(&encoded);
+    step
+}
+
+/// Encode a segment of the source at each CRF and report VMAF and size per CRF
+#[tauri::command]
+pub async fn run_quality_ladder(
+    window: tauri::Window,
+    input: String,
+    crf_values: Vec<u32>,
+) -> Result<QualityLadder, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffmpeg_path.is_empty() || config.ffprobe_path.is_empty() {
+        return Err("FFmpeg path not configured".to_string());
+    }
+    let crfs = ladder_crfs(&crf_values);
+    if crfs.is_empty() {
+        return Err("No valid CRF values (0-51)".to_string());
+    }
+    if PROCESS_MANAGER
+        .lock()
+        .map_err(|e| format!("Failed to lock ProcessManager: {}", e))?
+        .has_process(LADDER_JOB_ID)
+    {
+        return Err("A quality ladder is already running".to_string());
+    }
+
+    let duration = crate::verify::probe_duration(&config.ffprobe_path, &input)
+        .map(|d| d.as_secs_f64())
+        .ok_or("Could not read the duration of the file")?;
+    let (segment_start, segment_seconds) = segment(duration);
+
+    let mut steps = Vec::new();
+    for (index, crf) in crfs.iter().enumerate() {
+        let _ = window.emit(
+            "quality-ladder-progress",
+            LadderProgress {
+                crf: *crf,
+                index,
+                total: crfs.len(),
+            },
+        );
+        match ladder_step(
+            &config.ffmpeg_path,
+            &input,
+            (segment_start, segment_seconds),
+            *crf,
+        )? {
+            Some(step) => {
+                let _ = crate::write_log(format!(
+                    "[LADDER] {} crf={} size={} vmaf={:.2}",
+                    input, step.crf, step.size_bytes, step.vmaf
+                ));
+                steps.push(step);
+            }
+            None => return Err("Quality ladder cancelled".to_string()),
+        }
+    }
+
+    Ok(QualityLadder {
+        encoder: LADDER_ENCODER.to_string(),
+        segment_start,
+        segment_seconds,
+        steps,
+    })
+}
+
+/// Stop the running quality ladder step; run_quality_ladder then returns an error
+#[tauri::command]
+pub fn cancel_quality_ladder() -> Result<bool, String> {
+    let pid = {
+        let mut manager = PROCESS_MANAGER.lock().map_err(|e| e.to_string())?;
+        if !manager.stop_render(LADDER_JOB_ID) {
+            return Ok(false);
+        }
+        manager.get_pid(LADDER_JOB_ID)
+    };
+    if let Some(pid) = pid {
+        crate::proc::kill_pid(pid);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladder_crfs() {
+        assert_eq!(ladder_crfs(&[28, 18, 23, 23, 60]), vec![18, 23, 28]);
+        assert_eq!(ladder_crfs(&(0..20).collect::<Vec<_>>()).len(), MAX_STEPS);
+    }
+
+    #[test]
+    fn test_segment_and_vmaf() {
+        assert_eq!(segment(6.0), (0.0, 6.0));
+        assert_eq!(segment(110.0), (50.0, SEGMENT_SECONDS));
+        assert_eq!(
+            parse_vmaf("[Parsed_libvmaf_0 @ 0x55d1] VMAF score: 93.456789"),
+            Some(93.456789)
+        );
+        assert_eq!(parse_vmaf("frame=  250 fps=80"), None);
+    }
+}
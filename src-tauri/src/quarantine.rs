@@ -0,0 +1,296 @@
+// Failed input quarantine
+// Counts repeated input-caused failures (render_errors) of unattended jobs (folder
+// imports, the HTTP API) per input and, past a threshold, moves the file into a
+// `_failed` subfolder next to it with a `.error.json` sidecar describing the failure,
+// so broken files are not queued again and again. Off unless quarantineFailedInputs is
+// set; files the user picked by hand are never moved.
+
+use crate::render_errors::RenderErrorKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Failures allowed before an input is quarantined
+const MAX_FAILURES: u32 = 3;
+const QUARANTINE_DIR_NAME: &str = "_failed";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct FailureRecord {
+    count: u32,
+    #[serde(default)]
+    kind: Option<RenderErrorKind>,
+    last_error: String,
+    last_failed_at: String,
+}
+
+/// Sidecar written next to a quarantined file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuarantineReport {
+    pub original_path: String,
+    pub quarantined_path: String,
+    pub error_kind: Option<RenderErrorKind>,
+    pub error: String,
+    pub failures: u32,
+    pub quarantined_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailureResult {
+    pub failures: u32,
+    pub quarantined: bool,
+    pub quarantined_path: Option<String>,
+}
+
+fn get_failures_path() -> PathBuf {
+    crate::get_app_data_dir().join("failures.json")
+}
+
+fn load_failures(path: &Path) -> HashMap<String, FailureRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_failures(path: &Path, failures: &HashMap<String, FailureRecord>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(failures)
+        .map_err(|e| format!("Failed to serialize failures: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to save failures: {}", e))
+}
+
+/// Failures that point at the file itself. A full disk, a missing encoder or the
+/// NVENC session limit would hit any input, so they never count.
+fn counts_against_input(kind: Option<RenderErrorKind>) -> bool {
+    matches!(
+        kind,
+        Some(RenderErrorKind::CorruptInput | RenderErrorKind::Crash)
+    )
+}
+
+/// `<dir>/<name>`, or `<stem>-2.<ext>`, `<stem>-3.<ext>`, ... when a file of that name
+/// (or its sidecar) was quarantined before
+fn unique_target(dir: &Path, file_name: &str) -> PathBuf {
+    let name = Path::new(file_name);
+    let stem = name
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = name
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let taken = |path: &Path| path.exists() || sidecar_path(path).exists();
+    let mut target = dir.join(file_name);
+    let mut n = 2;
+    while taken(&target) {
+        target = dir.join(format!("{}-{}{}", stem, n, extension));
+        n += 1;
+    }
+    target
+}
+
+fn sidecar_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_os_string();
+    name.push(".error.json");
+    PathBuf::from(name)
+}
+
+/// Move the input into `<dir>/_failed/` and write the `.error.json` sidecar
+fn quarantine_file(input: &Path, record: &FailureRecord) -> Result<PathBuf, String> {
+    let parent = input
+        .parent()
+        .ok_or_else(|| "Input has no parent directory".to_string())?;
+    let file_name = input
+        .file_name()
+        .ok_or_else(|| "Input has no file name".to_string())?;
+
+    let quarantine_dir = parent.join(QUARANTINE_DIR_NAME);
+    fs::create_dir_all(&quarantine_dir)
+        .map_err(|e| format!("Failed to create quarantine folder: {}", e))?;
+
+    let target = unique_target(&quarantine_dir, &file_name.to_string_lossy());
+    fs::rename(input, &target).map_err(|e| format!("Failed to move file: {}", e))?;
+
+    let report = QuarantineReport {
+        original_path: input.to_string_lossy().to_string(),
+        quarantined_path: target.to_string_lossy().to_string(),
+        error_kind: record.kind,
+        error: record.last_error.clone(),
+        failures: record.count,
+        quarantined_at: chrono::Local::now().to_rfc3339(),
+    };
+
+    let content = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("Failed to serialize report: {}", e))?;
+    fs::write(sidecar_path(&target), content)
+        .map_err(|e| format!("Failed to write sidecar: {}", e))?;
+
+    Ok(target)
+}
+
+/// Count a failure of `input_path` in the failures file at `failures_path` and
+/// quarantine the input once it failed MAX_FAILURES times for input-caused reasons
+fn record_failure(
+    failures_path: &Path,
+    input_path: &str,
+    kind: Option<RenderErrorKind>,
+    error: &str,
+) -> Result<FailureResult, String> {
+    let mut failures = load_failures(failures_path);
+    if !counts_against_input(kind) {
+        let count = failures.get(input_path).map_or(0, |r| r.count);
+        return Ok(FailureResult {
+            failures: count,
+            quarantined: false,
+            quarantined_path: None,
+        });
+    }
+
+    let record = failures.entry(input_path.to_string()).or_default();
+    record.count += 1;
+    record.kind = kind;
+    record.last_error = error.to_string();
+    record.last_failed_at = chrono::Local::now().to_rfc3339();
+    let record = record.clone();
+
+    if record.count < MAX_FAILURES {
+        save_failures(failures_path, &failures)?;
+        return Ok(FailureResult {
+            failures: record.count,
+            quarantined: false,
+            quarantined_path: None,
+        });
+    }
+
+    let target = quarantine_file(Path::new(input_path), &record)?;
+    failures.remove(input_path);
+    save_failures(failures_path, &failures)?;
+
+    Ok(FailureResult {
+        failures: record.count,
+        quarantined: true,
+        quarantined_path: Some(target.to_string_lossy().to_string()),
+    })
+}
+
+fn clear_failures(failures_path: &Path, input_path: &str) -> Result<(), String> {
+    let mut failures = load_failures(failures_path);
+    if failures.remove(input_path).is_some() {
+        save_failures(failures_path, &failures)?;
+    }
+    Ok(())
+}
+
+/// Called by render_job for a failed unattended render; quarantines the input after
+/// repeated input-caused failures
+pub fn record_input_failure(
+    input_path: &str,
+    kind: Option<RenderErrorKind>,
+    error: &str,
+) -> Result<FailureResult, String> {
+    let result = record_failure(&get_failures_path(), input_path, kind, error)?;
+    if let Some(target) = &result.quarantined_path {
+        let _ = crate::write_log(format!(
+            "[QUARANTINE] {} moved to {} after {} failures",
+            input_path, target, result.failures
+        ));
+    }
+    Ok(result)
+}
+
+/// Called by render_job after a successful unattended render of the input
+pub fn clear_input_failures(input_path: &str) -> Result<(), String> {
+    clear_failures(&get_failures_path(), input_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "szhimatar-quarantine-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_quarantine_after_repeated_failures() {
+        let dir = temp_dir("repeat");
+        let failures = dir.join("failures.json");
+        let input = dir.join("clip.mp4");
+        fs::write(&input, b"broken").unwrap();
+        let path = input.to_string_lossy().to_string();
+        let corrupt = Some(RenderErrorKind::CorruptInput);
+
+        // Failures that say nothing about the file are not counted
+        let full = record_failure(&failures, &path, Some(RenderErrorKind::DiskFull), "full");
+        assert_eq!(full.unwrap().failures, 0);
+
+        for expected in 1..MAX_FAILURES {
+            let result = record_failure(&failures, &path, corrupt, "moov atom not found").unwrap();
+            assert_eq!(result.failures, expected);
+            assert!(!result.quarantined);
+        }
+        let result = record_failure(&failures, &path, corrupt, "moov atom not found").unwrap();
+        assert!(result.quarantined);
+
+        let target = dir.join(QUARANTINE_DIR_NAME).join("clip.mp4");
+        assert_eq!(
+            result.quarantined_path,
+            Some(target.to_string_lossy().to_string())
+        );
+        assert!(target.exists() && !input.exists());
+        let report: QuarantineReport =
+            serde_json::from_str(&fs::read_to_string(sidecar_path(&target)).unwrap()).unwrap();
+        assert_eq!(report.error_kind, corrupt);
+        assert_eq!(report.failures, MAX_FAILURES);
+        assert!(load_failures(&failures).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quarantine_keeps_earlier_rejects() {
+        let dir = temp_dir("clash");
+        let quarantine_dir = dir.join(QUARANTINE_DIR_NAME);
+        fs::create_dir_all(&quarantine_dir).unwrap();
+        fs::write(quarantine_dir.join("clip.mp4"), b"first").unwrap();
+        fs::write(quarantine_dir.join("clip-2.mp4.error.json"), b"{}").unwrap();
+
+        let input = dir.join("clip.mp4");
+        fs::write(&input, b"second").unwrap();
+        let record = FailureRecord {
+            count: MAX_FAILURES,
+            kind: Some(RenderErrorKind::Crash),
+            ..Default::default()
+        };
+        let target = quarantine_file(&input, &record).unwrap();
+
+        assert_eq!(target, quarantine_dir.join("clip-3.mp4"));
+        assert_eq!(fs::read(quarantine_dir.join("clip.mp4")).unwrap(), b"first");
+        assert_eq!(fs::read(&target).unwrap(), b"second");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_success_clears_failures() {
+        let dir = temp_dir("clear");
+        let failures = dir.join("failures.json");
+        let path = dir.join("clip.mp4").to_string_lossy().to_string();
+
+        record_failure(&failures, &path, Some(RenderErrorKind::Crash), "crashed").unwrap();
+        assert_eq!(load_failures(&failures)[&path].count, 1);
+        clear_failures(&failures, &path).unwrap();
+        assert!(load_failures(&failures).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,412 @@
+// Render queue state
+// The frontend dispatches jobs; the backend keeps queue-level state that has to
+// outlive a single render, such as the action to run once the queue drains, and
+// persists the frontend's queue to queue.json so it survives a crash or shutdown.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Seconds between the queue finishing and the post-queue action running
+pub const POST_ACTION_COUNTDOWN_SECS: u32 = 60;
+
+/// What to do after the last queued job finishes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PostQueueAction {
+    #[default]
+    None,
+    Shutdown,
+    Sleep,
+    Hibernate,
+    /// Run a user script (.bat/.cmd/.ps1 on Windows, any executable or .sh elsewhere)
+    RunScript {
+        path: String,
+    },
+}
+
+#[derive(Default)]
+struct QueueState {
+    post_action: PostQueueAction,
+    /// A render ran since the queue last drained (an empty start must not shut down)
+    rendered_since_finish: bool,
+    /// Cancels the running countdown, if any
+    countdown_cancel: Option<Arc<AtomicBool>>,
+}
+
+impl QueueState {
+    /// Start a countdown when the drained queue rendered something and an action is
+    /// set; returns the action and the countdown's cancel flag
+    fn start_countdown(&mut self) -> Option<(PostQueueAction, Arc<AtomicBool>)> {
+        let rendered = std::mem::take(&mut self.rendered_since_finish);
+        if !rendered || self.post_action == PostQueueAction::None {
+            return None;
+        }
+        if let Some(previous) = self.countdown_cancel.take() {
+            previous.store(true, Ordering::Relaxed);
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.countdown_cancel = Some(cancel.clone());
+        Some((self.post_action.clone(), cancel))
+    }
+
+    /// The countdown ran out: returns whether the action should run. The action is
+    /// one-shot, so the next queue does not shut the machine down again.
+    fn finish_countdown(&mut self, cancel: &AtomicBool) -> bool {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.countdown_cancel = None;
+        self.post_action = PostQueueAction::None;
+        true
+    }
+
+    /// Stop a running countdown and clear its action; false when none was running
+    fn cancel_countdown(&mut self) -> bool {
+        match self.countdown_cancel.take() {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                self.post_action = PostQueueAction::None;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+lazy_static! {
+    static ref QUEUE: Mutex<QueueState> = Mutex::new(QueueState::default());
+    /// Queue as last saved by the frontend
+    static ref CURRENT: Mutex<PersistedQueue> = Mutex::new(PersistedQueue::default());
+}
+
+/// Payload of the `post-queue-countdown` event (emitted every second)
+#[derive(Debug, Clone, Serialize)]
+pub struct PostQueueCountdown {
+    pub action: PostQueueAction,
+    pub seconds_left: u32,
+}
+
+/// Payload of the `post-queue-action` event (after the action ran or failed)
+#[derive(Debug, Clone, Serialize)]
+pub struct PostQueueResult {
+    pub action: PostQueueAction,
+    pub error: Option<String>,
+}
+
+/// Payload of the `queue-control` event
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueControl {
+    /// pause | resume | stop_all
+    pub action: &'static str,
+}
+
+/// Ask the frontend queue to pause, resume or stop (tray menu, global shortcuts)
+pub fn request_queue_control(app: &tauri::AppHandle, action: &'static str) {
+    use tauri::Manager;
+    let _ = app.emit_all("queue-control", QueueControl { action });
+}
+
+/// Called by run_ffmpeg_render when a job starts
+pub fn note_render_started() {
+    if let Ok(mut state) = QUEUE.lock() {
+        state.rendered_since_finish = true;
+    }
+}
+
+/// Set the action to run after the last queued job finishes. It runs once and is
+/// then reset to None (also when its countdown is cancelled).
+#[tauri::command]
+pub fn set_post_queue_action(action: PostQueueAction) -> Result<(), String> {
+    if let PostQueueAction::RunScript { path } = &action {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("Script not found: {}", path));
+        }
+    }
+
+    let _ = crate::write_log(format!("[POST-QUEUE] Action set to {:?}", action));
+    QUEUE.lock().map_err(|e| e.to_string())?.post_action = action;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_post_queue_action() -> Result<PostQueueAction, String> {
+    Ok(QUEUE.lock().map_err(|e| e.to_string())?.post_action.clone())
+}
+
+/// Cancel a running post-queue countdown; returns false when none was running
+#[tauri::command]
+pub fn cancel_post_queue_action(window: tauri::Window) -> Result<bool, String> {
+    let cancelled = QUEUE.lock().map_err(|e| e.to_string())?.cancel_countdown();
+    if cancelled {
+        let _ = crate::write_log("[POST-QUEUE] Countdown cancelled".to_string());
+        let _ = window.emit("post-queue-cancelled", ());
+    }
+    Ok(cancelled)
+}
+
+/// Called by the frontend when its queue has drained. Starts the countdown for the
+/// configured post-queue action; returns whether a countdown started.
+#[tauri::command]
+pub fn notify_queue_finished(window: tauri::Window) -> Result<bool, String> {
+    let started = {
+        let mut state = QUEUE.lock().map_err(|e| e.to_string())?;
+        if state.rendered_since_finish {
+            crate::notifications::queue_finished(&window);
+            crate::webhooks::queue_finished();
+        }
+        state.start_countdown()
+    };
+    let Some((action, cancel)) = started else {
+        return Ok(false);
+    };
+
+    let _ = crate::write_log(format!(
+        "[POST-QUEUE] Queue finished, running {:?} in {}s",
+        action, POST_ACTION_COUNTDOWN_SECS
+    ));
+
+    std::thread::spawn(move || {
+        for seconds_left in (1..=POST_ACTION_COUNTDOWN_SECS).rev() {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = window.emit(
+                "post-queue-countdown",
+                PostQueueCountdown {
+                    action: action.clone(),
+                    seconds_left,
+                },
+            );
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        let run = QUEUE
+            .lock()
+            .map(|mut state| state.finish_countdown(&cancel))
+            .unwrap_or(false);
+        if !run {
+            return;
+        }
+
+        let error = run_post_action(&action).err();
+        let _ = crate::write_log(format!(
+            "[POST-QUEUE] {:?} finished: {}",
+            action,
+            error.as_deref().unwrap_or("ok")
+        ));
+        let _ = window.emit("post-queue-action", PostQueueResult { action, error });
+    });
+
+    Ok(true)
+}
+
+/// Queue job as persisted to queue.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedJob {
+    pub id: String,
+    pub input_path: String,
+    pub output_path: String,
+    /// Frontend RenderStatus: pending, processing, paused, completed, ...
+    pub status: String,
+    #[serde(default)]
+    pub trim_start_sec: f64,
+    #[serde(default)]
+    pub trim_end_sec: f64,
+    /// Ids of the jobs that must complete before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// The job last sent to run_ffmpeg_render, once the job started
+    #[serde(default)]
+    pub render_job: Option<crate::RenderJob>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PersistedQueue {
+    pub preset_name: Option<String>,
+    pub paused: bool,
+    pub jobs: Vec<PersistedJob>,
+}
+
+fn queue_file() -> PathBuf {
+    crate::get_app_data_dir().join("queue.json")
+}
+
+/// Queue saved by the previous session, if any
+pub fn load_persisted_queue() -> Option<PersistedQueue> {
+    let content = std::fs::read_to_string(queue_file()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Called by the frontend whenever jobs are added, removed or change status. Ignored
+/// during shutdown, so the jobs being stopped stay recorded as running.
+#[tauri::command]
+pub fn save_queue_state(state: PersistedQueue) -> Result<(), String> {
+    if crate::shutdown::in_progress() {
+        return Ok(());
+    }
+    let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = state;
+    }
+    std::fs::write(queue_file(), content).map_err(|e| format!("Failed to save queue: {}", e))
+}
+
+/// Queue as last saved by the frontend (empty before its first save)
+pub fn current_queue() -> PersistedQueue {
+    CURRENT.lock().map(|q| q.clone()).unwrap_or_default()
+}
+
+/// System command for a power action
+fn power_command(action: &PostQueueAction) -> Result<std::process::Command, String> {
+    let (program, args): (&str, &[&str]) = match action {
+        #[cfg(target_os = "windows")]
+        PostQueueAction::Shutdown => ("shutdown", &["/s", "/t", "0"]),
+        #[cfg(target_os = "windows")]
+        PostQueueAction::Sleep => ("rundll32.exe", &["powrprof.dll,SetSuspendState", "0,1,0"]),
+        #[cfg(target_os = "windows")]
+        PostQueueAction::Hibernate => ("shutdown", &["/h"]),
+
+        #[cfg(target_os = "macos")]
+        PostQueueAction::Shutdown => (
+            "osascript",
+            &["-e", "tell app \"System Events\" to shut down"],
+        ),
+        #[cfg(target_os = "macos")]
+        PostQueueAction::Sleep => ("pmset", &["sleepnow"]),
+        #[cfg(target_os = "macos")]
+        PostQueueAction::Hibernate => return Err("Hibernate is not supported on macOS".to_string()),
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        PostQueueAction::Shutdown => ("systemctl", &["poweroff"]),
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        PostQueueAction::Sleep => ("systemctl", &["suspend"]),
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        PostQueueAction::Hibernate => ("systemctl", &["hibernate"]),
+
+        PostQueueAction::None | PostQueueAction::RunScript { .. } => {
+            return Err("Not a power action".to_string())
+        }
+    };
+
+    let mut cmd = crate::proc::command(program);
+    cmd.args(args);
+    Ok(cmd)
+}
+
+/// Command that runs a user script with the right interpreter
+fn script_command(path: &str) -> std::process::Command {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let mut cmd = match extension.as_str() {
+        "ps1" => {
+            let mut cmd = crate::proc::command("powershell");
+            cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]);
+            cmd
+        }
+        "bat" | "cmd" => {
+            let mut cmd = crate::proc::command("cmd");
+            cmd.arg("/C");
+            cmd
+        }
+        "sh" => crate::proc::command("sh"),
+        _ => return crate::proc::command(path),
+    };
+    cmd.arg(path);
+    cmd
+}
+
+fn run_post_action(action: &PostQueueAction) -> Result<(), String> {
+    let (mut cmd, options) = match action {
+        PostQueueAction::None => return Ok(()),
+        // Scripts may do real work (uploads, copies), so they get an hour
+        PostQueueAction::RunScript { path } => (
+            script_command(path),
+            crate::proc::RunOptions::with_timeout(3600).label("post-queue script"),
+        ),
+        _ => (
+            power_command(action)?,
+            crate::proc::RunOptions::with_timeout(30).label("post-queue action"),
+        ),
+    };
+
+    let output = crate::proc::run_captured(&mut cmd, &options)?;
+    if output.timed_out {
+        return Err("Timed out".to_string());
+    }
+    if !output.success {
+        return Err(format!(
+            "Exited with code {:?}: {}",
+            output.code,
+            output.stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with(action: PostQueueAction) -> QueueState {
+        QueueState {
+            post_action: action,
+            rendered_since_finish: true,
+            countdown_cancel: None,
+        }
+    }
+
+    #[test]
+    fn test_countdown_needs_a_render_and_an_action() {
+        let mut idle = state_with(PostQueueAction::Shutdown);
+        idle.rendered_since_finish = false;
+        assert!(idle.start_countdown().is_none());
+
+        let mut no_action = state_with(PostQueueAction::None);
+        assert!(no_action.start_countdown().is_none());
+        assert!(!no_action.rendered_since_finish);
+    }
+
+    #[test]
+    fn test_post_action_is_one_shot() {
+        let mut state = state_with(PostQueueAction::Sleep);
+        let (action, cancel) = state.start_countdown().unwrap();
+        assert_eq!(action, PostQueueAction::Sleep);
+        assert!(state.finish_countdown(&cancel));
+        assert_eq!(state.post_action, PostQueueAction::None);
+        assert!(state.countdown_cancel.is_none());
+
+        // The next drained queue does nothing
+        state.rendered_since_finish = true;
+        assert!(state.start_countdown().is_none());
+    }
+
+    #[test]
+    fn test_cancel_stops_the_countdown() {
+        let mut state = state_with(PostQueueAction::Shutdown);
+        let (_, cancel) = state.start_countdown().unwrap();
+        assert!(state.cancel_countdown());
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(!state.finish_countdown(&cancel));
+        assert_eq!(state.post_action, PostQueueAction::None);
+        assert!(!state.cancel_countdown());
+    }
+
+    #[test]
+    fn test_new_countdown_replaces_the_running_one() {
+        let mut state = state_with(PostQueueAction::Hibernate);
+        let (_, first) = state.start_countdown().unwrap();
+        state.rendered_since_finish = true;
+        let (_, second) = state.start_countdown().unwrap();
+        assert!(first.load(Ordering::Relaxed));
+        assert!(!state.finish_countdown(&first));
+        assert!(state.finish_countdown(&second));
+    }
+}
@@ -0,0 +1,112 @@
+// Source recycling
+// Opt-in "delete original after success": once a render passed output verification
+// its source goes to the recycle bin (never a permanent delete), and the move is
+// recorded in trashed.json so the UI can offer a restore.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Records kept; older ones are dropped (the files stay in the recycle bin)
+const MAX_RECORDS: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashedSource {
+    pub job_id: String,
+    pub original_path: String,
+    pub output_path: String,
+    pub trashed_at: String,
+}
+
+fn get_trashed_path() -> PathBuf {
+    crate::get_app_data_dir().join("trashed.json")
+}
+
+fn load_records() -> Vec<TrashedSource> {
+    fs::read_to_string(get_trashed_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_records(records: &[TrashedSource]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Failed to serialize trashed sources: {}", e))?;
+    fs::write(get_trashed_path(), content)
+        .map_err(|e| format!("Failed to save trashed sources: {}", e))
+}
+
+/// Move a rendered job's source to the recycle bin. Refuses when the output is
+/// missing, empty or the same file as the source.
+pub fn trash_source(job_id: &str, input_path: &str, output_path: &str) -> Result<(), String> {
+    let input = Path::new(input_path);
+    let output = Path::new(output_path);
+
+    let output_size = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    if output_size == 0 {
+        return Err("Output is missing or empty, keeping the source".to_string());
+    }
+    let same_file = match (fs::canonicalize(input), fs::canonicalize(output)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => input == output,
+    };
+    if same_file {
+        return Err("Output overwrote the source, nothing to recycle".to_string());
+    }
+
+    trash::delete(input).map_err(|e| format!("Failed to move source to recycle bin: {}", e))?;
+
+    let mut records = load_records();
+    records.push(TrashedSource {
+        job_id: job_id.to_string(),
+        original_path: input_path.to_string(),
+        output_path: output_path.to_string(),
+        trashed_at: chrono::Local::now().to_rfc3339(),
+    });
+    if records.len() > MAX_RECORDS {
+        records.drain(..records.len() - MAX_RECORDS);
+    }
+    save_records(&records)
+}
+
+/// Sources moved to the recycle bin after successful renders, newest first
+#[tauri::command]
+pub fn list_trashed_sources() -> Vec<TrashedSource> {
+    let mut records = load_records();
+    records.reverse();
+    records
+}
+
+/// Put a recycled source back where it was
+#[tauri::command]
+pub fn restore_trashed_source(original_path: String) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = original_path;
+        Err("Restoring is not supported on macOS, use \"Put Back\" in the Trash".to_string())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if Path::new(&original_path).exists() {
+            return Err(format!("A file already exists at {}", original_path));
+        }
+
+        let items = trash::os_limited::list()
+            .map_err(|e| format!("Failed to read the recycle bin: {}", e))?;
+        // The same path may have been recycled more than once; restore the latest
+        let item = items
+            .into_iter()
+            .filter(|item| item.original_path() == Path::new(&original_path))
+            .max_by_key(|item| item.time_deleted)
+            .ok_or_else(|| format!("{} is no longer in the recycle bin", original_path))?;
+        trash::os_limited::restore_all([item])
+            .map_err(|e| format!("Failed to restore {}: {}", original_path, e))?;
+
+        let mut records = load_records();
+        records.retain(|r| r.original_path != original_path);
+        save_records(&records)?;
+        let _ = crate::write_log(format!("[RECYCLE] Restored {}", original_path));
+        Ok(())
+    }
+}
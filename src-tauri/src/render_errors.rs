@@ -0,0 +1,147 @@
+// Render error classification
+// FFmpeg reports failures as free-form stderr lines. classify_line recognizes the
+// failures users can act on (a missing file, a full disk, an encoder the build lacks,
+// the NVENC session limit of consumer GPUs, ...) so RenderResult carries a typed
+// error_kind and the UI and queue can react to it without matching "Error" strings.
+
+use serde::{Deserialize, Serialize};
+
+/// Why a render failed, for failures the UI and the queue treat differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderErrorKind {
+    /// The input (or a file the job refers to) does not exist
+    FileNotFound,
+    /// The FFmpeg build lacks the encoder/decoder, or the container cannot hold the codec
+    UnsupportedCodec,
+    /// The GPU refused another NVENC session (consumer cards allow only a few at once)
+    NvencSessionLimit,
+    /// The output drive is full
+    DiskFull,
+    /// The input cannot be read or the output cannot be written
+    PermissionDenied,
+    /// The input is damaged or truncated
+    CorruptInput,
+    /// FFmpeg crashed (see the crash reports)
+    Crash,
+    /// The job ran longer than its max_duration_minutes
+    Timeout,
+}
+
+/// stderr fragments per kind, checked in this order (the first kind with a matching
+/// line wins, so specific causes go before the generic ones they also trigger)
+const PATTERNS: &[(RenderErrorKind, &[&str])] = &[
+    (
+        RenderErrorKind::NvencSessionLimit,
+        &[
+            "OpenEncodeSessionEx failed",
+            "incompatible client key",
+            "No NVENC capable devices found",
+        ],
+    ),
+    (
+        RenderErrorKind::DiskFull,
+        &[
+            "No space left on device",
+            "not enough space on the disk",
+            "Disk quota exceeded",
+        ],
+    ),
+    (
+        RenderErrorKind::PermissionDenied,
+        &[
+            "Permission denied",
+            "Operation not permitted",
+            "Access is denied",
+            "Read-only file system",
+        ],
+    ),
+    (
+        RenderErrorKind::FileNotFound,
+        &["No such file or directory", "does not exist"],
+    ),
+    (
+        RenderErrorKind::UnsupportedCodec,
+        &[
+            "Unknown encoder",
+            "Unknown decoder",
+            "Encoder (codec",
+            "Decoder (codec",
+            "Could not find tag for codec",
+            "not currently supported in container",
+            "Unsupported codec",
+            "codec not currently supported",
+        ],
+    ),
+    (
+        RenderErrorKind::CorruptInput,
+        &[
+            "Invalid data found when processing input",
+            "moov atom not found",
+            "Invalid NAL unit size",
+            "EBML header parsing failed",
+            "error while decoding",
+            "corrupt",
+            "Truncated",
+        ],
+    ),
+];
+
+/// Kind of failure a single stderr line points at
+pub fn classify_line(line: &str) -> Option<RenderErrorKind> {
+    PATTERNS
+        .iter()
+        .find(|(_, fragments)| fragments.iter().any(|fragment| line.contains(fragment)))
+        .map(|(kind, _)| *kind)
+}
+
+/// Kind of a failed render from its stderr lines, by the priority of PATTERNS
+pub fn classify<S: AsRef<str>>(lines: &[S]) -> Option<RenderErrorKind> {
+    let kinds: Vec<RenderErrorKind> = lines
+        .iter()
+        .filter_map(|line| classify_line(line.as_ref()))
+        .collect();
+    PATTERNS
+        .iter()
+        .map(|(kind, _)| *kind)
+        .find(|kind| kinds.contains(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_line() {
+        assert_eq!(
+            classify_line(
+                "[h264_nvenc @ 0x55] OpenEncodeSessionEx failed: out of memory (10): (no details)"
+            ),
+            Some(RenderErrorKind::NvencSessionLimit)
+        );
+        assert_eq!(
+            classify_line("/media/in.mkv: No such file or directory"),
+            Some(RenderErrorKind::FileNotFound)
+        );
+        assert_eq!(
+            classify_line("Unknown encoder 'libsvtav1'"),
+            Some(RenderErrorKind::UnsupportedCodec)
+        );
+        assert_eq!(
+            classify_line("[mov,mp4,m4a @ 0x7f] moov atom not found"),
+            Some(RenderErrorKind::CorruptInput)
+        );
+        assert_eq!(classify_line("Conversion failed!"), None);
+    }
+
+    #[test]
+    fn test_classify_priority() {
+        // The specific cause wins over what it breaks downstream
+        let lines = [
+            "[h264 @ 0x55] error while decoding MB 12 40, bytestream -5",
+            "av_interleaved_write_frame(): No space left on device",
+        ];
+        assert_eq!(classify(&lines), Some(RenderErrorKind::DiskFull));
+        assert_eq!(classify(&["Error while filtering"]), None);
+    }
+}
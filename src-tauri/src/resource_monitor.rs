@@ -0,0 +1,599 @@
+// Resource monitor
+// Samples system CPU / memory, NVIDIA GPU utilization, temperatures and the FFmpeg
+// process's own usage once per second while a render runs, emitting `resource-stats`
+// events and `thermal-warning` when the configured temperature limits are crossed.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Temperatures are read every this many samples (the Windows query is slow)
+const TEMPERATURE_EVERY: u32 = 5;
+
+/// Payload of the `resource-stats` event
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResourceStats {
+    pub job_id: String,
+    /// Whole-machine CPU usage (0-100)
+    pub cpu_percent: Option<f32>,
+    pub memory_used_mb: Option<u64>,
+    pub memory_total_mb: Option<u64>,
+    /// NVIDIA GPU utilization (0-100), None without nvidia-smi
+    pub gpu_percent: Option<f32>,
+    /// FFmpeg's share of the whole machine's CPU (0-100)
+    pub process_cpu_percent: Option<f32>,
+    pub process_memory_mb: Option<u64>,
+    /// Only filled on samples that read temperatures
+    pub cpu_temp_c: Option<f32>,
+    pub gpu_temp_c: Option<f32>,
+}
+
+/// Averages over a whole render, attached to RenderResult
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceSummary {
+    pub samples: u32,
+    pub avg_cpu_percent: Option<f32>,
+    pub avg_gpu_percent: Option<f32>,
+    pub avg_process_cpu_percent: Option<f32>,
+    pub peak_process_memory_mb: Option<u64>,
+}
+
+/// Running mean of an optional metric
+#[derive(Default)]
+struct Mean {
+    sum: f64,
+    count: u32,
+}
+
+impl Mean {
+    fn add(&mut self, value: Option<f32>) {
+        if let Some(v) = value {
+            self.sum += v as f64;
+            self.count += 1;
+        }
+    }
+
+    fn get(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum / self.count as f64) as f32)
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    samples: u32,
+    cpu: Mean,
+    gpu: Mean,
+    process_cpu: Mean,
+    peak_process_memory_mb: Option<u64>,
+}
+
+impl Totals {
+    fn add(&mut self, stats: &ResourceStats) {
+        self.samples += 1;
+        self.cpu.add(stats.cpu_percent);
+        self.gpu.add(stats.gpu_percent);
+        self.process_cpu.add(stats.process_cpu_percent);
+        self.peak_process_memory_mb = self.peak_process_memory_mb.max(stats.process_memory_mb);
+    }
+
+    fn summary(&self) -> ResourceSummary {
+        ResourceSummary {
+            samples: self.samples,
+            avg_cpu_percent: self.cpu.get(),
+            avg_gpu_percent: self.gpu.get(),
+            avg_process_cpu_percent: self.process_cpu.get(),
+            peak_process_memory_mb: self.peak_process_memory_mb,
+        }
+    }
+}
+
+/// Samples resources for one render job until stopped. The FFmpeg PID is looked
+/// up in the ProcessManager each tick, so two-pass renders are followed across passes.
+pub struct ResourceMonitor {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<ResourceSummary>>,
+}
+
+impl ResourceMonitor {
+    pub fn start(window: tauri::Window, job_id: String) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+
+        let thermal_settings = crate::load_settings()
+            .map(|s| s.thermal)
+            .unwrap_or_default();
+
+        let handle = std::thread::spawn(move || {
+            let mut sampler = Sampler::default();
+            let mut totals = Totals::default();
+            let mut thermal = crate::thermal::ThermalGuard::new(thermal_settings);
+            let mut last_pid = None;
+            while !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(SAMPLE_INTERVAL);
+                let pid = crate::PROCESS_MANAGER
+                    .lock()
+                    .ok()
+                    .and_then(|m| m.get_pid(&job_id));
+                let Some(pid) = pid else { continue };
+                last_pid = Some(pid);
+
+                let stats = sampler.sample(&job_id, pid);
+                if sampler.read_temperatures() {
+                    let was_paused = thermal.is_paused();
+                    if let Some(warning) =
+                        thermal.check(&job_id, stats.cpu_temp_c, stats.gpu_temp_c)
+                    {
+                        apply_thermal_warning(&warning, pid, was_paused);
+                        let _ = window.emit("thermal-warning", &warning);
+                    }
+                }
+                // The first tick only primes the CPU counters
+                if stats.cpu_percent.is_some() || stats.process_cpu_percent.is_some() {
+                    totals.add(&stats);
+                    let _ = window.emit("resource-stats", &stats);
+                }
+            }
+
+            // Never leave a render suspended when monitoring ends
+            if let (true, Some(pid)) = (thermal.is_paused(), last_pid) {
+                let _ = crate::proc::suspend_pid(pid, false);
+            }
+            totals.summary()
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop sampling and return the averages
+    pub fn stop(mut self) -> ResourceSummary {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .take()
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for ResourceMonitor {
+    /// A render that bails out early still ends the sampling thread
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Suspend or resume FFmpeg for a thermal state change and log it
+fn apply_thermal_warning(warning: &crate::thermal::ThermalWarning, pid: u32, was_paused: bool) {
+    let action = if warning.paused {
+        Some(true)
+    } else if warning.resumed && was_paused {
+        Some(false)
+    } else {
+        None
+    };
+    let result = action.map(|suspend| crate::proc::suspend_pid(pid, suspend));
+
+    let _ = crate::write_render_log(
+        warning.job_id.clone(),
+        format!(
+            "[THERMAL] cpu={:?}C gpu={:?}C limits={}/{}C paused={} resumed={} {}",
+            warning.cpu_c,
+            warning.gpu_c,
+            warning.cpu_limit_c,
+            warning.gpu_limit_c,
+            warning.paused,
+            warning.resumed,
+            match result {
+                Some(Err(e)) => e,
+                _ => String::new(),
+            }
+        ),
+    );
+}
+
+// ============================================================================
+// Sampling
+// ============================================================================
+
+/// CPU time counters; system times are (busy, total), process time is in the same unit
+#[derive(Clone, Copy)]
+struct CpuTimes {
+    busy: u64,
+    total: u64,
+    process: Option<u64>,
+}
+
+#[derive(Default)]
+struct Sampler {
+    last: Option<(u32, CpuTimes)>,
+    /// Set once nvidia-smi fails so it is not spawned every second
+    no_nvidia_smi: bool,
+    /// Set once no CPU sensor is found
+    no_cpu_sensor: bool,
+    ticks: u32,
+}
+
+impl Sampler {
+    /// Whether the sample just taken included temperatures (the 1st, 6th, 11th, ...)
+    fn read_temperatures(&self) -> bool {
+        self.ticks % TEMPERATURE_EVERY == 1
+    }
+
+    fn sample(&mut self, job_id: &str, pid: u32) -> ResourceStats {
+        self.ticks += 1;
+        let (memory_used_mb, memory_total_mb) = memory_mb();
+        let mut stats = ResourceStats {
+            job_id: job_id.to_string(),
+            memory_used_mb,
+            memory_total_mb,
+            process_memory_mb: process_memory_mb(pid),
+            ..Default::default()
+        };
+
+        if !self.no_nvidia_smi {
+            let gpu = nvidia_smi_sample();
+            self.no_nvidia_smi = gpu.is_none();
+            if let Some((utilization, temperature)) = gpu {
+                stats.gpu_percent = Some(utilization);
+                stats.gpu_temp_c = temperature.filter(|_| self.read_temperatures());
+            }
+        }
+        if self.read_temperatures() && !self.no_cpu_sensor {
+            stats.cpu_temp_c = crate::thermal::read_cpu_temperature();
+            self.no_cpu_sensor = stats.cpu_temp_c.is_none();
+        }
+
+        match cpu_times(pid) {
+            Some(now) => {
+                // Counters from a previous pass's process do not apply to the new PID
+                if let Some((last_pid, last)) = self.last {
+                    let (cpu, process) = cpu_percent(&last, &now, last_pid == pid);
+                    stats.cpu_percent = cpu;
+                    stats.process_cpu_percent = process;
+                }
+                self.last = Some((pid, now));
+            }
+            None => {
+                let (cpu, process) = instant_cpu_percent(pid);
+                stats.cpu_percent = cpu;
+                stats.process_cpu_percent = process;
+            }
+        }
+
+        stats
+    }
+}
+
+/// Usage between two counter snapshots
+fn cpu_percent(last: &CpuTimes, now: &CpuTimes, same_process: bool) -> (Option<f32>, Option<f32>) {
+    let total = now.total.saturating_sub(last.total);
+    if total == 0 {
+        return (None, None);
+    }
+    let percent = |delta: u64| ((delta as f64 / total as f64) * 100.0).min(100.0) as f32;
+
+    let cpu = percent(now.busy.saturating_sub(last.busy));
+    let process = match (same_process, last.process, now.process) {
+        (true, Some(before), Some(after)) => Some(percent(after.saturating_sub(before))),
+        _ => None,
+    };
+    (Some(cpu), process)
+}
+
+/// (utilization %, temperature °C) of the busiest / hottest NVIDIA GPU
+fn nvidia_smi_sample() -> Option<(f32, Option<f32>)> {
+    let output = crate::proc::run_captured(
+        crate::proc::command("nvidia-smi").args([
+            "--query-gpu=utilization.gpu,temperature.gpu",
+            "--format=csv,noheader,nounits",
+        ]),
+        &crate::proc::RunOptions::with_timeout(5),
+    )
+    .ok()
+    .filter(|o| o.success)?;
+    parse_nvidia_smi_sample(&output.stdout)
+}
+
+/// One "utilization, temperature" line per GPU
+pub fn parse_nvidia_smi_sample(stdout: &str) -> Option<(f32, Option<f32>)> {
+    let gpus: Vec<(f32, Option<f32>)> = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',').map(|f| f.trim().parse::<f32>().ok());
+            Some((fields.next()??, fields.next().flatten()))
+        })
+        .collect();
+    let utilization = gpus.iter().map(|g| g.0).reduce(f32::max)?;
+    let temperature = gpus.iter().filter_map(|g| g.1).reduce(f32::max);
+    Some((utilization, temperature))
+}
+
+// ---------------------------------------------------------------------------
+// Linux: /proc
+// ---------------------------------------------------------------------------
+
+/// (busy, total) jiffies from the aggregate line of /proc/stat
+#[cfg(any(target_os = "linux", test))]
+pub fn parse_proc_stat(content: &str) -> Option<(u64, u64)> {
+    let fields: Vec<u64> = content
+        .lines()
+        .next()?
+        .strip_prefix("cpu ")?
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    // user nice system idle iowait irq softirq steal (guest is already in user)
+    let total: u64 = fields.iter().take(8).sum();
+    let idle = fields.get(3).copied().unwrap_or(0) + fields.get(4).copied().unwrap_or(0);
+    Some((total.saturating_sub(idle), total))
+}
+
+/// utime + stime jiffies from /proc/<pid>/stat
+#[cfg(any(target_os = "linux", test))]
+pub fn parse_proc_pid_stat(content: &str) -> Option<u64> {
+    // The command name may contain spaces, so count fields after its closing paren
+    let fields: Vec<&str> = content
+        .get(content.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// Value in kB of a `Key:   123 kB` line (/proc/meminfo, /proc/<pid>/status)
+#[cfg(any(target_os = "linux", test))]
+fn proc_kb(content: &str, key: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix(key)?.strip_prefix(':'))
+        .and_then(|v| v.split_whitespace().next()?.parse().ok())
+}
+
+#[cfg(target_os = "linux")]
+fn cpu_times(pid: u32) -> Option<CpuTimes> {
+    let (busy, total) = parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)?;
+    let process = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|s| parse_proc_pid_stat(&s));
+    Some(CpuTimes {
+        busy,
+        total,
+        process,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") else {
+        return (None, None);
+    };
+    let total = proc_kb(&meminfo, "MemTotal");
+    let available = proc_kb(&meminfo, "MemAvailable");
+    (
+        total
+            .zip(available)
+            .map(|(t, a)| t.saturating_sub(a) / 1024),
+        total.map(|t| t / 1024),
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn process_memory_mb(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    proc_kb(&status, "VmRSS").map(|kb| kb / 1024)
+}
+
+// ---------------------------------------------------------------------------
+// Windows: kernel32
+// ---------------------------------------------------------------------------
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+struct MemoryStatusEx {
+    length: u32,
+    memory_load: u32,
+    total_phys: u64,
+    avail_phys: u64,
+    total_page_file: u64,
+    avail_page_file: u64,
+    total_virtual: u64,
+    avail_virtual: u64,
+    avail_extended_virtual: u64,
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)] // layout must match PROCESS_MEMORY_COUNTERS
+struct ProcessMemoryCounters {
+    cb: u32,
+    page_fault_count: u32,
+    peak_working_set_size: usize,
+    working_set_size: usize,
+    quota_peak_paged_pool_usage: usize,
+    quota_paged_pool_usage: usize,
+    quota_peak_non_paged_pool_usage: usize,
+    quota_non_paged_pool_usage: usize,
+    pagefile_usage: usize,
+    peak_pagefile_usage: usize,
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetSystemTimes(idle: *mut u64, kernel: *mut u64, user: *mut u64) -> i32;
+    fn GlobalMemoryStatusEx(status: *mut MemoryStatusEx) -> i32;
+    fn GetProcessTimes(
+        process: *mut std::ffi::c_void,
+        creation: *mut u64,
+        exit: *mut u64,
+        kernel: *mut u64,
+        user: *mut u64,
+    ) -> i32;
+    fn K32GetProcessMemoryInfo(
+        process: *mut std::ffi::c_void,
+        counters: *mut ProcessMemoryCounters,
+        cb: u32,
+    ) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+/// Run `f` with a query handle to `pid`
+#[cfg(target_os = "windows")]
+fn with_process<T>(pid: u32, f: impl FnOnce(*mut std::ffi::c_void) -> Option<T>) -> Option<T> {
+    use crate::proc::{CloseHandle, OpenProcess};
+    // SAFETY: the handle is checked and closed below
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return None;
+    }
+    let result = f(handle);
+    unsafe { CloseHandle(handle) };
+    result
+}
+
+#[cfg(target_os = "windows")]
+fn cpu_times(pid: u32) -> Option<CpuTimes> {
+    let (mut idle, mut kernel, mut user) = (0u64, 0u64, 0u64);
+    // SAFETY: FILETIMEs are written as plain 64-bit counts of 100ns
+    if unsafe { GetSystemTimes(&mut idle, &mut kernel, &mut user) } == 0 {
+        return None;
+    }
+    // Kernel time includes idle time
+    let total = kernel + user;
+    let process = with_process(pid, |handle| {
+        let (mut creation, mut exit, mut kernel, mut user) = (0u64, 0u64, 0u64, 0u64);
+        let ok =
+            unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) };
+        (ok != 0).then_some(kernel + user)
+    });
+    // Process times count every core, system times too, so the ratio is machine share
+    Some(CpuTimes {
+        busy: total.saturating_sub(idle),
+        total,
+        process,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    let mut status = MemoryStatusEx {
+        length: std::mem::size_of::<MemoryStatusEx>() as u32,
+        ..Default::default()
+    };
+    // SAFETY: `length` is set as the API requires
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return (None, None);
+    }
+    const MB: u64 = 1024 * 1024;
+    (
+        Some(status.total_phys.saturating_sub(status.avail_phys) / MB),
+        Some(status.total_phys / MB),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn process_memory_mb(pid: u32) -> Option<u64> {
+    with_process(pid, |handle| {
+        let mut counters = ProcessMemoryCounters::default();
+        let cb = std::mem::size_of::<ProcessMemoryCounters>() as u32;
+        let ok = unsafe { K32GetProcessMemoryInfo(handle, &mut counters, cb) };
+        (ok != 0).then_some(counters.working_set_size as u64 / (1024 * 1024))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// macOS and others: ps / sysctl
+// ---------------------------------------------------------------------------
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn cpu_times(_pid: u32) -> Option<CpuTimes> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn memory_mb() -> (Option<u64>, Option<u64>) {
+    let total = crate::proc::run_captured(
+        crate::proc::command("sysctl").args(["-n", "hw.memsize"]),
+        &crate::proc::RunOptions::with_timeout(5),
+    )
+    .ok()
+    .and_then(|o| o.stdout.trim().parse::<u64>().ok());
+    (None, total.map(|b| b / (1024 * 1024)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn process_memory_mb(pid: u32) -> Option<u64> {
+    let output = crate::proc::run_captured(
+        crate::proc::command("ps").args(["-o", "rss=", "-p", &pid.to_string()]),
+        &crate::proc::RunOptions::with_timeout(5),
+    )
+    .ok()?;
+    output.stdout.trim().parse::<u64>().ok().map(|kb| kb / 1024)
+}
+
+/// Fallback when no CPU counters are available: ps reports per-core percentages
+fn instant_cpu_percent(pid: u32) -> (Option<f32>, Option<f32>) {
+    if cfg!(any(target_os = "linux", target_os = "windows")) {
+        return (None, None);
+    }
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get() as f32)
+        .unwrap_or(1.0);
+    let ps = |args: &[&str]| {
+        crate::proc::run_captured(
+            crate::proc::command("ps").args(args),
+            &crate::proc::RunOptions::with_timeout(5),
+        )
+        .ok()
+        .map(|o| {
+            o.stdout
+                .lines()
+                .filter_map(|l| l.trim().parse::<f32>().ok())
+                .sum::<f32>()
+        })
+    };
+
+    let cpu = ps(&["-A", "-o", "%cpu="]).map(|p| (p / cores).min(100.0));
+    let process = ps(&["-o", "%cpu=", "-p", &pid.to_string()]).map(|p| (p / cores).min(100.0));
+    (cpu, process)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stats() {
+        let stat = "cpu  100 10 50 800 40 0 0 0 0 0\ncpu0 50 5 25 400 20 0 0 0 0 0\n";
+        assert_eq!(parse_proc_stat(stat), Some((160, 1000)));
+
+        let pid_stat =
+            "4242 (ffmpeg -i a b) R 1 4242 4242 0 -1 4194304 5000 0 0 0 700 300 0 0 20 0 9 0";
+        assert_eq!(parse_proc_pid_stat(pid_stat), Some(1000));
+
+        assert_eq!(
+            proc_kb("MemTotal:       16318480 kB\nMemFree: 1 kB\n", "MemTotal"),
+            Some(16318480)
+        );
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi_sample() {
+        assert_eq!(
+            parse_nvidia_smi_sample("35, 61\n80, 58\n"),
+            Some((80.0, Some(61.0)))
+        );
+        assert_eq!(parse_nvidia_smi_sample("12, [N/A]\n"), Some((12.0, None)));
+        assert_eq!(parse_nvidia_smi_sample(""), None);
+    }
+}
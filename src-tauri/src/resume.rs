@@ -0,0 +1,346 @@
+// Interrupted job resume
+// queue.json (saved by the frontend) and running.json (saved by the process manager)
+// outlive a crash or an unexpected shutdown. At launch the unfinished jobs of the
+// previous session are collected; the frontend offers to queue them again. A render
+// stopped gracefully left a finalized `.part` output: when the job allows it, only the
+// rest of the source is encoded (input `-ss`) and joined to that part, otherwise the
+// encode starts over.
+
+use crate::queue::PersistedJob;
+use crate::units;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Parts shorter than this are not worth joining; the job starts over
+const MIN_RESUME_SECONDS: f64 = 10.0;
+
+/// Queue statuses of jobs that had not run yet
+const WAITING_STATUSES: [&str; 3] = ["pending", "waiting-for-file", "paused"];
+
+/// Where an interrupted render continues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumePoint {
+    /// Finalized output of the interrupted render
+    pub part_path: String,
+    /// Source position the part ends at
+    pub offset: units::Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptedState {
+    /// The output was finished after all; nothing to redo
+    Complete,
+    /// Encoding continues where the `.part` output ends
+    Resumable,
+    /// Encoding starts over
+    Restart,
+    /// Still waiting in the queue
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptedJob {
+    pub id: String,
+    pub input_path: String,
+    pub output_path: String,
+    pub state: InterruptedState,
+    pub trim_start_sec: f64,
+    pub trim_end_sec: f64,
+    /// Ids (of the previous session) of the jobs this one waited for
+    pub depends_on: Vec<String>,
+    /// The job as last sent to the backend, `resume` set when resumable; None when it
+    /// never started and is built from the current settings
+    pub job: Option<crate::RenderJob>,
+}
+
+lazy_static! {
+    /// Unfinished jobs of the previous session and the ids it was rendering
+    static ref PREVIOUS: Mutex<(Vec<PersistedJob>, Vec<String>)> =
+        Mutex::new((Vec::new(), Vec::new()));
+}
+
+/// Read the queue of the previous session before the frontend overwrites it.
+/// `running_ids` are the jobs its run file still listed.
+pub fn load_previous_session(running_ids: Vec<String>) {
+    let Some(queue) = crate::queue::load_persisted_queue() else {
+        return;
+    };
+    let jobs: Vec<PersistedJob> = queue
+        .jobs
+        .into_iter()
+        .filter(|job| {
+            job.status == "processing"
+                || WAITING_STATUSES.contains(&job.status.as_str())
+                || running_ids.contains(&job.id)
+        })
+        .collect();
+    if !jobs.is_empty() {
+        let _ = crate::write_log(format!(
+            "[RESUME] {} unfinished job(s) in the previous session",
+            jobs.len()
+        ));
+    }
+    if let Ok(mut previous) = PREVIOUS.lock() {
+        *previous = (jobs, running_ids);
+    }
+}
+
+/// Whether encoding can start at an offset and be joined to the earlier part
+fn can_resume(job: &crate::RenderJob) -> bool {
+    let seeks = |args: &[String]| {
+        args.iter()
+            .any(|a| matches!(a.as_str(), "-ss" | "-t" | "-to"))
+    };
+    // The offset is in output time, which a speed change, an external audio track or
+    // the stabilization transforms would have to map back to their own time; a smart
+    // copy could only cut at a keyframe
+    !job.two_pass
+        && crate::encode_args::effective_speed(job.speed_factor).is_none()
+        && job.stabilize.is_none()
+        && !job.smart_copy
+        && matches!(
+            job.audio_source,
+            crate::encode_args::AudioSource::Keep | crate::encode_args::AudioSource::Mute
+        )
+        && !job.duration_seconds.is_zero()
+        && !seeks(&job.ffmpeg_args)
+        && !seeks(&job.input_args)
+        && crate::encode_args::video_encoder_in(&job.ffmpeg_args) != Some("copy")
+}
+
+fn classify(
+    persisted: &PersistedJob,
+    running_ids: &[String],
+    ffprobe_path: &str,
+) -> InterruptedJob {
+    let mut job = persisted.render_job.clone();
+    let interrupted = persisted.status == "processing" || running_ids.contains(&persisted.id);
+
+    let state = match &mut job {
+        _ if !interrupted => InterruptedState::Pending,
+        None => InterruptedState::Restart,
+        Some(job) => {
+            job.resume = None;
+            let part_path = crate::orphans::part_path(&persisted.output_path);
+            let complete = Path::new(&persisted.output_path).exists()
+                && crate::verify::probe_duration(ffprobe_path, &persisted.output_path).is_some_and(
+                    |d| {
+                        let expected = crate::encode_args::speed_adjusted(
+                            job.duration_seconds,
+                            job.speed_factor,
+                        );
+                        crate::verify::duration_mismatch(d, expected).is_none()
+                    },
+                );
+            let part_duration = if can_resume(job) && Path::new(&part_path).exists() {
+                crate::verify::probe_duration(ffprobe_path, &part_path)
+            } else {
+                None
+            };
+            match part_duration {
+                _ if complete => InterruptedState::Complete,
+                Some(offset)
+                    if offset.as_secs_f64() >= MIN_RESUME_SECONDS
+                        && offset.as_secs_f64() < job.duration_seconds.as_secs_f64() =>
+                {
+                    job.resume = Some(ResumePoint { part_path, offset });
+                    InterruptedState::Resumable
+                }
+                _ => InterruptedState::Restart,
+            }
+        }
+    };
+    InterruptedJob {
+        id: persisted.id.clone(),
+        input_path: persisted.input_path.clone(),
+        output_path: persisted.output_path.clone(),
+        state,
+        trim_start_sec: persisted.trim_start_sec,
+        trim_end_sec: persisted.trim_end_sec,
+        depends_on: persisted.depends_on.clone(),
+        job,
+    }
+}
+
+/// Unfinished jobs of the previous session, except those whose FFmpeg still runs
+fn interrupted_jobs(previous: &(Vec<PersistedJob>, Vec<String>)) -> Vec<InterruptedJob> {
+    let orphaned: Vec<String> = crate::orphans::list_orphaned_processes()
+        .into_iter()
+        .map(|entry| entry.job_id)
+        .collect();
+    let ffprobe_path = crate::load_ffmpeg_config().ffprobe_path;
+    previous
+        .0
+        .iter()
+        .filter(|job| !orphaned.contains(&job.id) && Path::new(&job.input_path).exists())
+        .map(|job| classify(job, &previous.1, &ffprobe_path))
+        .collect()
+}
+
+fn remove_part(output_path: &str) {
+    let _ = std::fs::remove_file(crate::orphans::part_path(output_path));
+}
+
+/// Jobs the previous session left unfinished
+#[tauri::command]
+pub fn list_interrupted_jobs() -> Vec<InterruptedJob> {
+    PREVIOUS
+        .lock()
+        .map(|previous| interrupted_jobs(&previous))
+        .unwrap_or_default()
+}
+
+/// Hand the unfinished jobs to the frontend for queueing; complete ones are dropped
+#[tauri::command]
+pub fn resume_interrupted_jobs() -> Result<Vec<InterruptedJob>, String> {
+    let previous = PREVIOUS
+        .lock()
+        .map(|mut previous| std::mem::take(&mut *previous))
+        .map_err(|e| e.to_string())?;
+    let jobs: Vec<InterruptedJob> = interrupted_jobs(&previous)
+        .into_iter()
+        .filter(|job| job.state != InterruptedState::Complete)
+        .collect();
+    for job in jobs.iter().filter(|j| j.state == InterruptedState::Restart) {
+        remove_part(&job.output_path);
+    }
+    let _ = crate::write_log(format!(
+        "[RESUME] Re-queued {} job(s), {} continuing from their partial output",
+        jobs.len(),
+        jobs.iter()
+            .filter(|j| j.state == InterruptedState::Resumable)
+            .count()
+    ));
+    Ok(jobs)
+}
+
+/// Forget the unfinished jobs and remove the partial outputs they left
+#[tauri::command]
+pub fn dismiss_interrupted_jobs() {
+    let previous = PREVIOUS
+        .lock()
+        .map(|mut previous| std::mem::take(&mut *previous))
+        .unwrap_or_default();
+    for job in &previous.0 {
+        remove_part(&job.output_path);
+    }
+}
+
+/// "clip.mp4" -> "clip.head.part.mp4": the earlier part, kept aside while the rest renders
+fn head_path(output_path: &str) -> String {
+    let path = Path::new(output_path);
+    let head = match path.extension() {
+        Some(ext) => path.with_extension(format!("head.{}", ext.to_string_lossy())),
+        None => path.with_extension("head"),
+    };
+    crate::orphans::part_path(&head.to_string_lossy())
+}
+
+/// Set the earlier part aside and make `job` encode only the rest of the source.
+/// Returns the path of the earlier part for `join`.
+pub fn prepare(job: &mut crate::RenderJob, point: ResumePoint) -> Result<String, String> {
+    let head = head_path(&job.output_path);
+    std::fs::rename(&point.part_path, &head).map_err(|e| {
+        format!(
+            "Failed to keep the partial output {}: {}",
+            point.part_path, e
+        )
+    })?;
+    let offset = point.offset.as_secs_f64();
+    job.input_args
+        .extend(["-ss".to_string(), format!("{:.3}", offset)]);
+    job.duration_seconds =
+        units::Duration::from_secs_f64(job.duration_seconds.as_secs_f64() - offset);
+    Ok(head)
+}
+
+/// Line of an FFmpeg concat list; quotes in the path are escaped as '\''
+fn concat_entry(path: &str) -> String {
+    format!("file '{}'\n", path.replace('\'', "'\\''"))
+}
+
+/// Join the earlier part and the newly rendered rest into `output`, without re-encoding
+pub fn join(
+    ffmpeg_path: &str,
+    job_id: &str,
+    head: &str,
+    rest: &str,
+    output: &str,
+) -> Result<(), String> {
+    let list = std::env::temp_dir().join(format!("szhimatar-join-{}.txt", job_id));
+    std::fs::write(&list, concat_entry(head) + &concat_entry(rest))
+        .map_err(|e| format!("Failed to write concat list: {}", e))?;
+    let list_arg = list.to_string_lossy().to_string();
+    let result = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args([
+            "-y",
+            "-hide_banner",
+            "-v",
+            "error",
+            "-f",
+            "concat",
+            "-safe",
+            "0",
+            "-i",
+            &list_arg,
+            "-map",
+            "0",
+            "-c",
+            "copy",
+            output,
+        ]),
+        &crate::proc::RunOptions::with_timeout(600).label("concat resumed render"),
+    );
+    let _ = std::fs::remove_file(&list);
+    match result {
+        Ok(o) if o.success => {
+            let _ = std::fs::remove_file(head);
+            let _ = std::fs::remove_file(rest);
+            Ok(())
+        }
+        Ok(o) => Err(format!(
+            "Failed to join the resumed render: {}",
+            o.stderr.lines().last().unwrap_or_default()
+        )),
+        Err(e) => Err(format!("Failed to join the resumed render: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_resume() {
+        let job = crate::RenderJob {
+            ffmpeg_args: vec!["-c:v".to_string(), "libx264".to_string()],
+            duration_seconds: units::Duration::from_secs_f64(60.0),
+            ..Default::default()
+        };
+        assert!(can_resume(&job));
+        assert!(!can_resume(&crate::RenderJob {
+            two_pass: true,
+            ..job.clone()
+        }));
+        assert!(!can_resume(&crate::RenderJob {
+            input_args: vec!["-ss".to_string(), "5".to_string()],
+            ..job.clone()
+        }));
+        assert!(!can_resume(&crate::RenderJob {
+            ffmpeg_args: vec!["-c:v".to_string(), "copy".to_string()],
+            ..job
+        }));
+    }
+
+    #[test]
+    fn test_head_path_and_concat_entry() {
+        assert_eq!(
+            head_path("/videos/clip.mp4"),
+            Path::new("/videos/clip.head.part.mp4").to_string_lossy()
+        );
+        assert_eq!(concat_entry("/v/it's.mp4"), "file '/v/it'\\''s.mp4'\n");
+    }
+}
@@ -0,0 +1,237 @@
+// Queue schedule
+// Lets the queue run only from a given time ("start at 23:30") or only inside a daily
+// window ("between 01:00 and 07:00", crossing midnight is fine). A monitor thread
+// checks the schedule and, like the battery policy, asks the frontend queue to hold
+// while it is closed and to continue once it opens. When the window closes during a
+// render, the running FFmpeg processes are suspended too if the settings ask for it.
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// How often the schedule is checked
+const SCHEDULE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleMode {
+    /// Renders run whenever the queue is started
+    #[default]
+    Off,
+    /// Renders wait until `start_at`
+    StartAt,
+    /// Renders run only between `window_start` and `window_end` each day
+    Window,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueueSchedule {
+    pub mode: ScheduleMode,
+    /// Local date and time, "2026-10-17T01:00" (as a datetime-local input gives it)
+    /// or RFC 3339
+    pub start_at: String,
+    /// "HH:MM", local time
+    pub window_start: String,
+    pub window_end: String,
+    /// Suspend running renders when the window closes instead of letting them finish
+    pub suspend_running: bool,
+}
+
+impl Default for QueueSchedule {
+    fn default() -> Self {
+        Self {
+            mode: ScheduleMode::Off,
+            start_at: String::new(),
+            window_start: "01:00".to_string(),
+            window_end: "07:00".to_string(),
+            suspend_running: false,
+        }
+    }
+}
+
+/// Whether renders may run now, and when they may next start
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleState {
+    pub mode: ScheduleMode,
+    pub open: bool,
+    /// RFC 3339 time the schedule opens next (None while open or when it never does)
+    pub opens_at: Option<String>,
+}
+
+/// Payload of the `queue-schedule-changed` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleEvent {
+    #[serde(flatten)]
+    pub state: ScheduleState,
+    /// The frontend should hold its queue
+    pub pause_queue: bool,
+    /// The schedule opened; a queue held for it may continue
+    pub resume_queue: bool,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn parse_start_at(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Local));
+    }
+    ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+}
+
+impl QueueSchedule {
+    /// Whether renders may run at `now`; an unreadable schedule never holds the queue
+    pub fn is_open(&self, now: DateTime<Local>) -> bool {
+        match self.mode {
+            ScheduleMode::Off => true,
+            ScheduleMode::StartAt => parse_start_at(&self.start_at).is_none_or(|at| now >= at),
+            ScheduleMode::Window => {
+                let (Some(start), Some(end)) =
+                    (parse_time(&self.window_start), parse_time(&self.window_end))
+                else {
+                    return true;
+                };
+                let time = now.time();
+                if start == end {
+                    true
+                } else if start < end {
+                    start <= time && time < end
+                } else {
+                    // Overnight window, e.g. 23:00-06:00
+                    time >= start || time < end
+                }
+            }
+        }
+    }
+
+    /// Next time the schedule opens after `now` (None when it is open)
+    pub fn opens_at(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if self.is_open(now) {
+            return None;
+        }
+        match self.mode {
+            ScheduleMode::Off => None,
+            ScheduleMode::StartAt => parse_start_at(&self.start_at),
+            ScheduleMode::Window => {
+                let start = parse_time(&self.window_start)?;
+                let today = now.date_naive().and_time(start);
+                let next = if today > now.naive_local() {
+                    today
+                } else {
+                    today + chrono::Duration::days(1)
+                };
+                Local.from_local_datetime(&next).earliest()
+            }
+        }
+    }
+
+    pub fn state(&self, now: DateTime<Local>) -> ScheduleState {
+        ScheduleState {
+            mode: self.mode,
+            open: self.is_open(now),
+            opens_at: self.opens_at(now).map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+fn load_schedule() -> QueueSchedule {
+    crate::load_settings()
+        .map(|settings| settings.queue_schedule)
+        .unwrap_or_default()
+}
+
+/// Current schedule state, e.g. for the frontend to hold a queue started while it is closed
+#[tauri::command]
+pub fn get_queue_schedule_state() -> ScheduleState {
+    load_schedule().state(Local::now())
+}
+
+/// Check the schedule and emit `queue-schedule-changed` when it opens or closes
+/// (and once at startup so the UI can show it)
+pub fn start_schedule_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last_open: Option<bool> = None;
+        loop {
+            let schedule = load_schedule();
+            let state = schedule.state(Local::now());
+            if last_open != Some(state.open) {
+                let closed = !state.open;
+                if closed && last_open.is_some() && schedule.suspend_running {
+                    crate::shortcuts::suspend_renders();
+                }
+                let _ = crate::write_log(format!(
+                    "[SCHEDULE] mode={:?} open={} opens_at={:?}",
+                    state.mode, state.open, state.opens_at
+                ));
+                let event = ScheduleEvent {
+                    pause_queue: closed,
+                    resume_queue: state.open && last_open.is_some(),
+                    state,
+                };
+                let _ = tauri::Manager::emit_all(&app, "queue-schedule-changed", &event);
+                last_open = Some(event.state.open);
+            }
+            std::thread::sleep(SCHEDULE_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 10, hour, minute, 0)
+            .earliest()
+            .unwrap()
+    }
+
+    fn window(start: &str, end: &str) -> QueueSchedule {
+        QueueSchedule {
+            mode: ScheduleMode::Window,
+            window_start: start.to_string(),
+            window_end: end.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        let night = window("01:00", "07:00");
+        assert!(night.is_open(at(3, 0)));
+        assert!(!night.is_open(at(7, 0)));
+        assert!(!night.is_open(at(12, 0)));
+        assert_eq!(
+            night.opens_at(at(12, 0)),
+            Some(at(1, 0) + chrono::Duration::days(1))
+        );
+        assert_eq!(night.opens_at(at(0, 30)), Some(at(1, 0)));
+        assert_eq!(night.opens_at(at(3, 0)), None);
+
+        let overnight = window("23:00", "06:00");
+        assert!(overnight.is_open(at(23, 30)));
+        assert!(overnight.is_open(at(5, 59)));
+        assert!(!overnight.is_open(at(6, 0)));
+        // Unreadable times do not hold the queue
+        assert!(window("late", "07:00").is_open(at(12, 0)));
+    }
+
+    #[test]
+    fn test_start_at() {
+        let schedule = QueueSchedule {
+            mode: ScheduleMode::StartAt,
+            start_at: "2026-03-10T22:30".to_string(),
+            ..Default::default()
+        };
+        assert!(!schedule.is_open(at(22, 0)));
+        assert!(schedule.is_open(at(22, 30)));
+        assert_eq!(schedule.opens_at(at(22, 0)), Some(at(22, 30)));
+        assert!(QueueSchedule::default().is_open(at(12, 0)));
+    }
+}
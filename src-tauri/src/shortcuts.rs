@@ -0,0 +1,124 @@
+// Global shortcuts
+// System-wide hotkeys that pause/resume the queue and stop all renders while another
+// app, e.g. a game, has focus. Pausing by hotkey also suspends the running FFmpeg
+// processes so they give up the CPU/GPU immediately instead of finishing the job.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, GlobalShortcutManager};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShortcutSettings {
+    /// e.g. "CmdOrCtrl+Alt+P"; empty disables the hotkey
+    pub pause_resume: String,
+    pub stop_all: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ShortcutAction {
+    PauseResume,
+    StopAll,
+}
+
+lazy_static! {
+    /// FFmpeg processes suspended by the pause hotkey, resumed with the queue
+    static ref SUSPENDED_PIDS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+}
+
+/// Suspend the running FFmpeg processes until resume_suspended_renders
+pub fn suspend_renders() {
+    let pids = crate::PROCESS_MANAGER
+        .lock()
+        .map(|manager| manager.active_pids())
+        .unwrap_or_default();
+    let mut suspended = match SUSPENDED_PIDS.lock() {
+        Ok(suspended) => suspended,
+        Err(_) => return,
+    };
+    for (job_id, pid) in pids {
+        match crate::proc::suspend_pid(pid, true) {
+            Ok(()) => suspended.push(pid),
+            Err(e) => {
+                let _ =
+                    crate::write_render_log(job_id, format!("[SHORTCUTS] Suspend failed: {}", e));
+            }
+        }
+    }
+}
+
+fn resume_renders() {
+    let pids = SUSPENDED_PIDS
+        .lock()
+        .map(|mut suspended| std::mem::take(&mut *suspended))
+        .unwrap_or_default();
+    for pid in pids {
+        // The process may have been killed meanwhile; nothing to do then
+        let _ = crate::proc::suspend_pid(pid, false);
+    }
+}
+
+fn run(app: &AppHandle, action: ShortcutAction) {
+    let _ = crate::write_log(format!("[SHORTCUTS] {:?}", action));
+    match action {
+        ShortcutAction::PauseResume if crate::tray::queue_paused() => {
+            resume_renders();
+            crate::queue::request_queue_control(app, "resume");
+        }
+        ShortcutAction::PauseResume => {
+            suspend_renders();
+            crate::queue::request_queue_control(app, "pause");
+        }
+        ShortcutAction::StopAll => {
+            // Killing works on suspended processes too; forget them
+            SUSPENDED_PIDS.lock().map(|mut s| s.clear()).ok();
+            let _ = crate::kill_all_renders();
+            crate::queue::request_queue_control(app, "stop_all");
+        }
+    }
+}
+
+/// (Re-)register the hotkeys from settings. Every hotkey that could be registered is
+/// active afterwards; the error lists the ones that are invalid or taken by another app.
+pub fn apply(app: &AppHandle) -> Result<(), String> {
+    let settings = crate::load_settings().unwrap_or_default().shortcuts;
+    let mut manager = app.global_shortcut_manager();
+    manager.unregister_all().map_err(|e| e.to_string())?;
+
+    let mut errors = Vec::new();
+    for (accelerator, action) in [
+        (settings.pause_resume, ShortcutAction::PauseResume),
+        (settings.stop_all, ShortcutAction::StopAll),
+    ] {
+        let accelerator = accelerator.trim();
+        if accelerator.is_empty() {
+            continue;
+        }
+        let handle = app.clone();
+        if let Err(e) = manager.register(accelerator, move || run(&handle, action)) {
+            errors.push(format!("{}: {}", accelerator, e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to register shortcuts: {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+/// Called by the frontend after the shortcut settings were saved
+#[tauri::command]
+pub fn apply_global_shortcuts(app: AppHandle) -> Result<(), String> {
+    apply(&app)
+}
+
+/// Called by the frontend when the queue resumes, however it was paused
+#[tauri::command]
+pub fn resume_suspended_renders() {
+    resume_renders();
+}
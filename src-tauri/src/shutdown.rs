@@ -0,0 +1,101 @@
+// Graceful shutdown
+// Quitting the app (tray "Выход", the last window closing, the OS ending the session)
+// used to leave FFmpeg running headless. Now running renders are asked to quit the way
+// pressing "q" does, so they finalize their outputs; after `shutdownTimeoutSecs` the
+// survivors are killed, and only then the app exits. The frontend persists the queue as
+// it changes; saves arriving during shutdown are ignored so the interrupted jobs stay
+// recorded as running.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether the app is on its way out
+pub fn in_progress() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+fn active_count() -> usize {
+    crate::PROCESS_MANAGER
+        .lock()
+        .map(|manager| manager.active_count())
+        .unwrap_or_default()
+}
+
+/// Refuse to swap the binary under running renders: the update script replaces it
+/// after a fixed delay, not after the renders finalized their outputs
+pub fn ensure_no_renders() -> Result<(), String> {
+    match active_count() {
+        0 => Ok(()),
+        count => Err(format!(
+            "{} render(s) still running; stop them or let the queue finish first",
+            count
+        )),
+    }
+}
+
+/// Ask every render to quit, wait up to `timeout` for them and kill the rest
+fn stop_renders(timeout: Duration) {
+    let pids = match crate::PROCESS_MANAGER.lock() {
+        Ok(mut manager) => {
+            let pids = manager.active_pids();
+            manager.stop_all_renders();
+            pids.into_iter()
+                .filter(|(job_id, _)| !manager.request_quit(job_id))
+                .collect::<Vec<_>>()
+        }
+        Err(_) => return,
+    };
+    // A suspended FFmpeg cannot read the quit request
+    crate::shortcuts::resume_suspended_renders();
+    for (_, pid) in pids {
+        crate::proc::kill_pid(pid);
+    }
+
+    let deadline = Instant::now() + timeout;
+    while active_count() > 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    if active_count() > 0 {
+        let _ = crate::write_log(format!(
+            "[SHUTDOWN] Renders did not finish within {}s, killing them",
+            timeout.as_secs()
+        ));
+        let _ = crate::kill_all_renders();
+    }
+}
+
+/// Stop the renders gracefully, then exit. Runs once; later calls are ignored.
+pub fn exit(app: &AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let timeout = crate::load_settings()
+        .unwrap_or_default()
+        .shutdown_timeout_secs;
+    let _ = crate::write_log(format!(
+        "[SHUTDOWN] Exiting with {} render(s) running",
+        active_count()
+    ));
+    let _ = app.emit_all("app-shutting-down", ());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        stop_renders(Duration::from_secs(timeout));
+        crate::media_cache::flush();
+        let _ = crate::write_log("[SHUTDOWN] Done".to_string());
+        app.exit(0);
+    });
+}
+
+/// Tauri run loop hook: hold the exit until the renders are stopped
+pub fn handle_run_event(app: &AppHandle, event: tauri::RunEvent) {
+    if let tauri::RunEvent::ExitRequested { api, .. } = event {
+        if !in_progress() && active_count() > 0 {
+            api.prevent_exit();
+            exit(app);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// Single instance
+// The first launch listens on a local socket (a named pipe on Windows); later launches,
+// e.g. one per file selected in the Explorer context menu, pass their arguments to it
+// and exit, so files end up in the existing window's queue.
+
+use interprocess::local_socket::{prelude::*, GenericNamespaced, Listener, ListenerOptions};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use tauri::Manager;
+
+/// Reply a running instance sends once it has taken the files
+const ACK: &str = "ok";
+
+/// What a secondary launch sends: its raw arguments and where they are relative to
+#[derive(Debug, Serialize, Deserialize)]
+struct Handoff {
+    cwd: String,
+    args: Vec<String>,
+}
+
+pub enum Instance {
+    /// This process owns the app; serve the listener (None if it could not be created)
+    Primary(Option<Listener>),
+    /// Another instance took the arguments; exit
+    Secondary,
+}
+
+fn socket_name() -> io::Result<interprocess::local_socket::Name<'static>> {
+    // Per user, so two accounts on one machine each get their own window
+    let user = std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default();
+    format!("szhimatar-{}.sock", user).to_ns_name::<GenericNamespaced>()
+}
+
+fn hand_off(args: &[String]) -> io::Result<()> {
+    let stream = interprocess::local_socket::Stream::connect(socket_name()?)?;
+    let mut stream = BufReader::new(stream);
+
+    let handoff = Handoff {
+        cwd: std::env::current_dir()
+            .map(|d| d.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        args: args.to_vec(),
+    };
+    let mut message = serde_json::to_string(&handoff).map_err(io::Error::other)?;
+    message.push('\n');
+    stream.get_mut().write_all(message.as_bytes())?;
+
+    let mut reply = String::new();
+    stream.read_line(&mut reply)?;
+    if reply.trim() == ACK {
+        Ok(())
+    } else {
+        Err(io::Error::other("Running instance did not acknowledge"))
+    }
+}
+
+/// Become the primary instance, or pass `args` to the one already running
+pub fn acquire(args: &[String]) -> Instance {
+    let create = |overwrite: bool| {
+        ListenerOptions::new()
+            .name(socket_name()?)
+            .try_overwrite(overwrite)
+            .create_sync()
+    };
+
+    match create(false) {
+        Ok(listener) => Instance::Primary(Some(listener)),
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => match hand_off(args) {
+            Ok(()) => Instance::Secondary,
+            Err(handoff_error) => {
+                // Nobody answered: a leftover socket from a crashed instance
+                let _ = crate::write_log(format!(
+                    "[SINGLE INSTANCE] Hand-off failed ({}), taking over",
+                    handoff_error
+                ));
+                Instance::Primary(create(true).ok())
+            }
+        },
+        Err(e) => {
+            let _ = crate::write_log(format!("[SINGLE INSTANCE] Listener failed: {}", e));
+            Instance::Primary(None)
+        }
+    }
+}
+
+fn handle_connection(
+    conn: interprocess::local_socket::Stream,
+    app: &tauri::AppHandle,
+) -> io::Result<()> {
+    let mut conn = BufReader::new(conn);
+    let mut line = String::new();
+    conn.read_line(&mut line)?;
+    let handoff: Handoff = serde_json::from_str(line.trim()).map_err(io::Error::other)?;
+    conn.get_mut().write_all(format!("{}\n", ACK).as_bytes())?;
+
+    // Relative arguments are relative to the launching process, not to us
+    let launch = crate::cli::parse_args(
+        handoff.args,
+        Some(std::path::Path::new(&handoff.cwd)),
+        &crate::cli::FolderFilter::from_settings(),
+    );
+    let _ = crate::write_log(format!(
+        "[SINGLE INSTANCE] Received {} file(s) from another launch (preset={:?})",
+        launch.files.len(),
+        launch.preset
+    ));
+
+    if launch.minimized {
+        // Nothing to show; e.g. the login launch raced a manual start
+    } else if let Some(window) = app.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit_all("files-received", launch);
+    Ok(())
+}
+
+/// Accept hand-offs from later launches for the lifetime of the app
+pub fn serve(listener: Listener, app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        for conn in listener.incoming() {
+            let result = conn.and_then(|conn| handle_connection(conn, &app));
+            if let Err(e) = result {
+                let _ = crate::write_log(format!("[SINGLE INSTANCE] Hand-off error: {}", e));
+            }
+        }
+    });
+}
@@ -0,0 +1,142 @@
+// Video stabilization
+// Shaky phone / action-cam footage is stabilized in two passes with libvidstab: a
+// detect pass (vidstabdetect) writes the camera motion to a transforms file, then the
+// encode runs vidstabtransform first in its -vf chain to smooth that motion out. The
+// transforms file lives in the temp dir for the duration of the job.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StabilizeOptions {
+    /// How shaky the footage is, 1 (little) - 10 (very)
+    pub shakiness: u8,
+    /// Frames before and after each frame used to smooth the camera path (0 = static
+    /// camera)
+    pub smoothing: u32,
+}
+
+impl Default for StabilizeOptions {
+    fn default() -> Self {
+        Self {
+            shakiness: 5,
+            smoothing: 10,
+        }
+    }
+}
+
+impl StabilizeOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=10).contains(&self.shakiness) {
+            return Err("Stabilization shakiness must be between 1 and 10".to_string());
+        }
+        if self.smoothing > 500 {
+            return Err("Stabilization smoothing must be at most 500 frames".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Transforms file of a job (in the temp dir, removed by `cleanup`)
+pub fn transforms_path(job_id: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("szhimatar-vidstab")
+        .join(format!("{}.trf", job_id))
+}
+
+/// Output trims (-ss / -t / -to) of the encode, so the detect pass stops where it does
+fn trim_args(args: &[String]) -> Vec<String> {
+    args.windows(2)
+        .filter(|w| matches!(w[0].as_str(), "-ss" | "-t" | "-to"))
+        .flat_map(|w| w.iter().cloned())
+        .collect()
+}
+
+/// Arguments of the detect pass (its output is discarded, write to "-")
+pub fn detect_args(args: &[String], options: &StabilizeOptions, transforms: &Path) -> Vec<String> {
+    let mut detect = trim_args(args);
+    detect.extend([
+        "-vf".to_string(),
+        format!(
+            "vidstabdetect=shakiness={}:accuracy=15:result={}",
+            options.shakiness,
+            crate::encode_args::filter_path(transforms)
+        ),
+        "-an".to_string(),
+        "-f".to_string(),
+        "null".to_string(),
+    ]);
+    detect
+}
+
+/// Put vidstabtransform at the start of the -vf chain, so it sees the frames the detect
+/// pass measured (before scaling, retiming or the hardware upload)
+pub fn apply_transform(
+    args: &mut Vec<String>,
+    options: &StabilizeOptions,
+    transforms: &Path,
+) -> Result<(), String> {
+    if crate::encode_args::video_encoder_in(args) == Some("copy") {
+        return Err("Stabilization cannot be used with video codec \"copy\"".to_string());
+    }
+    // optzoom=1 zooms just enough to hide the borders the correction moves into view
+    let filter = format!(
+        "vidstabtransform=input={}:smoothing={}:optzoom=1:interpol=bicubic,unsharp=5:5:0.8:3:3:0.4",
+        crate::encode_args::filter_path(transforms),
+        options.smoothing
+    );
+    crate::encode_args::prepend_video_filter(args, &filter);
+    Ok(())
+}
+
+/// Create the folder of the transforms file
+pub fn prepare(transforms: &Path) -> Result<(), String> {
+    if let Some(dir) = transforms.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create the stabilization folder: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn cleanup(transforms: &Path) {
+    let _ = std::fs::remove_file(transforms);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stabilize_args() {
+        let options = StabilizeOptions::default();
+        let transforms = Path::new("C:\\Temp\\szhimatar-vidstab\\job.trf");
+        let encode = args(&["-ss", "5.000", "-c:v", "libx264", "-vf", "scale=1280:720"]);
+
+        let detect = detect_args(&encode, &options, transforms);
+        assert_eq!(&detect[..2], &args(&["-ss", "5.000"])[..]);
+        assert_eq!(
+            detect[3],
+            "vidstabdetect=shakiness=5:accuracy=15:result='C\\:/Temp/szhimatar-vidstab/job.trf'"
+        );
+        assert!(detect.ends_with(&args(&["-an", "-f", "null"])));
+
+        let mut transformed = encode.clone();
+        apply_transform(&mut transformed, &options, transforms).unwrap();
+        let chain = &transformed[5];
+        assert!(chain.starts_with("vidstabtransform=input='C\\:/Temp/"));
+        assert!(chain.ends_with(",scale=1280:720"));
+
+        assert!(apply_transform(&mut args(&["-c:v", "copy"]), &options, transforms).is_err());
+        assert!(StabilizeOptions {
+            shakiness: 0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,267 @@
+// System diagnostics
+// Collects what support questions usually need (OS, memory, CPU, GPUs with VRAM, free
+// space on the drives the app writes to, FFmpeg version) for the diagnostics panel, and
+// packs it together with settings (secrets blanked) and recent logs into a support
+// bundle zip.
+
+use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// How much of the end of app.log goes into a support bundle
+const BUNDLE_APP_LOG_BYTES: u64 = 2 * 1024 * 1024;
+/// Most recent render logs included in a support bundle
+const BUNDLE_RENDER_LOGS: usize = 20;
+/// Settings fields blanked in a support bundle: (section, field), where the section is
+/// an object or a list of objects
+const SECRET_SETTINGS: &[(&str, &str)] = &[
+    ("network", "proxyPassword"),
+    ("httpApi", "token"),
+    ("webhooks", "url"),
+    ("uploadDestinations", "password"),
+    ("uploadDestinations", "accessKey"),
+    ("uploadDestinations", "secretKey"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskSpace {
+    /// "appData", "output" or "temp"
+    pub role: String,
+    pub path: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub app_version: String,
+    /// e.g. "Windows 11 Pro 23H2" or "Linux 24.04 Ubuntu"
+    pub os_version: String,
+    pub kernel_version: Option<String>,
+    pub arch: String,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    pub cpu_model: Option<String>,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub gpus: Vec<crate::hardware::GpuAdapter>,
+    pub disks: Vec<DiskSpace>,
+    pub ffmpeg_path: String,
+    /// First line of `ffmpeg -version`; None when FFmpeg is missing or broken
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_version: Option<String>,
+}
+
+/// Disk holding `path`: the one with the longest mount point that contains it
+fn disk_for<'a>(disks: &'a [sysinfo::Disk], path: &Path) -> Option<&'a sysinfo::Disk> {
+    disks
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+fn disk_space() -> Vec<DiskSpace> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let output_directory = crate::load_settings()
+        .map(|settings| settings.output_directory)
+        .unwrap_or_default();
+
+    let mut roles: Vec<(&str, PathBuf)> = vec![("appData", crate::get_app_data_dir())];
+    if !output_directory.trim().is_empty() {
+        roles.push(("output", PathBuf::from(output_directory.trim())));
+    }
+    roles.push(("temp", std::env::temp_dir()));
+
+    roles
+        .into_iter()
+        .filter_map(|(role, path)| {
+            let disk = disk_for(&disks, &path)?;
+            Some(DiskSpace {
+                role: role.to_string(),
+                path: path.to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                total_bytes: disk.total_space(),
+                free_bytes: disk.available_space(),
+            })
+        })
+        .collect()
+}
+
+/// OS, hardware, disk space and FFmpeg details for diagnostics
+#[tauri::command]
+pub fn get_system_info() -> Result<SystemInfo, String> {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let cpu = crate::hardware::cpu_info();
+    let gpus = crate::hardware::gpu_adapters().unwrap_or_else(|e| {
+        let _ = crate::write_log(format!("[SYSINFO] Failed to list display adapters: {}", e));
+        Vec::new()
+    });
+    let ffmpeg = crate::load_ffmpeg_config();
+    let version = |path: &str| {
+        if path.is_empty() {
+            None
+        } else {
+            crate::get_binary_version_internal(path)
+        }
+    };
+
+    Ok(SystemInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os_version: sysinfo::System::long_os_version()
+            .unwrap_or_else(|| std::env::consts::OS.to_string()),
+        kernel_version: sysinfo::System::kernel_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        total_memory_bytes: system.total_memory(),
+        available_memory_bytes: system.available_memory(),
+        cpu_model: cpu.model,
+        physical_cores: cpu.physical_cores,
+        logical_cores: cpu.logical_cores,
+        gpus,
+        disks: disk_space(),
+        ffmpeg_version: version(&ffmpeg.ffmpeg_path),
+        ffprobe_version: version(&ffmpeg.ffprobe_path),
+        ffmpeg_path: ffmpeg.ffmpeg_path,
+    })
+}
+
+/// The last `max_bytes` of a file (the whole file when it is smaller)
+pub fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Render logs, newest first
+fn recent_render_logs(limit: usize) -> Vec<PathBuf> {
+    let dir = crate::get_app_data_dir().join("logs").join("renders");
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+                .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+                .collect()
+        })
+        .unwrap_or_default();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter().take(limit).map(|(_, path)| path).collect()
+}
+
+/// settings.json with passwords, tokens, keys and webhook URLs blanked (None when it
+/// does not parse, so nothing unredacted is bundled)
+fn redact_settings(content: &[u8]) -> Option<Vec<u8>> {
+    let mut settings: serde_json::Value = serde_json::from_slice(content).ok()?;
+    for (section, field) in SECRET_SETTINGS {
+        let Some(section) = settings.get_mut(*section) else {
+            continue;
+        };
+        let objects: Vec<&mut serde_json::Value> = match section {
+            serde_json::Value::Array(items) => items.iter_mut().collect(),
+            other => vec![other],
+        };
+        for object in objects {
+            if let Some(value) = object.get_mut(*field) {
+                *value = serde_json::Value::String(String::new());
+            }
+        }
+    }
+    serde_json::to_vec_pretty(&settings).ok()
+}
+
+/// Zip system info, settings, FFmpeg config and recent logs for a bug report
+#[tauri::command]
+pub fn export_support_bundle(output_zip: String) -> Result<(), String> {
+    let info = get_system_info()?;
+    let app_dir = crate::get_app_data_dir();
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write zip: {}", e);
+    let write_error = |e: std::io::Error| format!("Failed to write zip: {}", e);
+
+    let file = std::fs::File::create(&output_zip)
+        .map_err(|e| format!("Failed to create {}: {}", output_zip, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut entries: Vec<(String, Vec<u8>)> = vec![(
+        "system_info.json".to_string(),
+        serde_json::to_vec_pretty(&info).map_err(|e| e.to_string())?,
+    )];
+    if let Some(content) = std::fs::read(app_dir.join("settings.json"))
+        .ok()
+        .and_then(|content| redact_settings(&content))
+    {
+        entries.push(("settings.json".to_string(), content));
+    }
+    if let Ok(content) = std::fs::read(app_dir.join("ffmpeg.json")) {
+        entries.push(("ffmpeg.json".to_string(), content));
+    }
+    if let Ok(content) = read_tail(&app_dir.join("logs").join("app.log"), BUNDLE_APP_LOG_BYTES) {
+        entries.push(("logs/app.log".to_string(), content));
+    }
+    for path in recent_render_logs(BUNDLE_RENDER_LOGS) {
+        if let (Some(name), Ok(content)) = (path.file_name(), std::fs::read(&path)) {
+            entries.push((format!("logs/renders/{}", name.to_string_lossy()), content));
+        }
+    }
+
+    for (name, content) in entries {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(&content).map_err(write_error)?;
+    }
+    zip.finish().map_err(zip_error)?;
+
+    let _ = crate::write_log(format!(
+        "[SYSINFO] Support bundle exported to {}",
+        output_zip
+    ));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_tail() {
+        let path = std::env::temp_dir().join(format!("szhimatar-tail-{}.log", std::process::id()));
+        std::fs::write(&path, b"0123456789").unwrap();
+        assert_eq!(read_tail(&path, 4).unwrap(), b"6789");
+        assert_eq!(read_tail(&path, 100).unwrap(), b"0123456789");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_redact_settings() {
+        let settings = serde_json::json!({
+            "outputDirectory": "D:/Renders",
+            "network": { "proxyHost": "proxy.corp", "proxyPassword": "hunter2" },
+            "httpApi": { "enabled": true, "token": "api-token" },
+            "webhooks": [{ "url": "https://discord.com/api/webhooks/1/secret-hook" }],
+            "uploadDestinations": [{
+                "name": "Bucket",
+                "password": "dav-password",
+                "accessKey": "AKIAEXAMPLE",
+                "secretKey": "s3-secret"
+            }]
+        });
+        let redacted = redact_settings(settings.to_string().as_bytes()).unwrap();
+        let text = String::from_utf8(redacted).unwrap();
+        for secret in [
+            "hunter2",
+            "api-token",
+            "secret-hook",
+            "dav-password",
+            "AKIA",
+            "s3-secret",
+        ] {
+            assert!(!text.contains(secret), "{} left in the bundle", secret);
+        }
+        assert!(text.contains("D:/Renders") && text.contains("proxy.corp"));
+        assert!(redact_settings(b"{ not json").is_none());
+    }
+}
@@ -0,0 +1,173 @@
+// OS theme
+// Reads whether the OS is in dark or light mode (AppsUseLightTheme on Windows,
+// AppleInterfaceStyle on macOS, the XDG desktop portal's color-scheme on Linux) and
+// emits `system-theme-changed` when it flips, so the "auto" theme follows it live.
+
+use serde::Serialize;
+
+const THEME_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemTheme {
+    Light,
+    Dark,
+}
+
+/// `gdbus` reply of org.freedesktop.portal.Settings.Read, e.g. "(<<uint32 1>>,)".
+/// 1 prefers dark, 2 prefers light, 0 means no preference.
+#[cfg(any(test, not(any(windows, target_os = "macos"))))]
+fn parse_portal_color_scheme(output: &str) -> Option<SystemTheme> {
+    let value = output.split("uint32").nth(1)?;
+    let digits: String = value
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    match digits.parse::<u32>().ok()? {
+        1 => Some(SystemTheme::Dark),
+        2 => Some(SystemTheme::Light),
+        _ => None,
+    }
+}
+
+/// `gsettings get org.gnome.desktop.interface color-scheme|gtk-theme`, e.g. "'prefer-dark'"
+#[cfg(any(test, not(any(windows, target_os = "macos"))))]
+fn parse_gsettings_value(output: &str) -> Option<SystemTheme> {
+    let value = output.trim().trim_matches('\'').to_lowercase();
+    if value.is_empty() {
+        None
+    } else if value.contains("dark") {
+        Some(SystemTheme::Dark)
+    } else {
+        Some(SystemTheme::Light)
+    }
+}
+
+#[cfg(windows)]
+fn read_system_theme() -> Option<SystemTheme> {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    let light: u32 = winreg::RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize")
+        .ok()?
+        .get_value("AppsUseLightTheme")
+        .ok()?;
+    Some(if light == 0 {
+        SystemTheme::Dark
+    } else {
+        SystemTheme::Light
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn read_system_theme() -> Option<SystemTheme> {
+    // The key only exists in dark mode; the command fails in light mode
+    let output = crate::proc::run_captured(
+        crate::proc::command("defaults").args(["read", "-g", "AppleInterfaceStyle"]),
+        &crate::proc::RunOptions::with_timeout(5).label("defaults"),
+    )
+    .ok()?;
+    Some(
+        if output.success && output.stdout.trim().eq_ignore_ascii_case("dark") {
+            SystemTheme::Dark
+        } else {
+            SystemTheme::Light
+        },
+    )
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn read_system_theme() -> Option<SystemTheme> {
+    let run = |cmd: &mut std::process::Command, label: &'static str| {
+        crate::proc::run_captured(cmd, &crate::proc::RunOptions::with_timeout(5).label(label))
+            .ok()
+            .filter(|output| output.success)
+            .map(|output| output.stdout)
+    };
+
+    let portal = run(
+        crate::proc::command("gdbus").args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+            "--method",
+            "org.freedesktop.portal.Settings.Read",
+            "org.freedesktop.appearance",
+            "color-scheme",
+        ]),
+        "gdbus",
+    );
+    if let Some(theme) = portal.as_deref().and_then(parse_portal_color_scheme) {
+        return Some(theme);
+    }
+
+    // No portal (or no preference): GNOME's own setting, then the GTK theme name
+    ["color-scheme", "gtk-theme"].iter().find_map(|key| {
+        let output = run(
+            crate::proc::command("gsettings").args(["get", "org.gnome.desktop.interface", key]),
+            "gsettings",
+        )?;
+        match parse_gsettings_value(&output)? {
+            // "default" color-scheme says nothing; the GTK theme may still be dark
+            SystemTheme::Light if *key == "color-scheme" && output.contains("default") => None,
+            theme => Some(theme),
+        }
+    })
+}
+
+/// Poll the OS theme and emit `system-theme-changed` when it changes
+pub fn start_theme_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last = read_system_theme();
+        loop {
+            std::thread::sleep(THEME_POLL_INTERVAL);
+            let theme = match read_system_theme() {
+                Some(theme) if Some(theme) != last => theme,
+                _ => continue,
+            };
+            let _ = crate::write_log(format!("[THEME] System theme changed to {:?}", theme));
+            let _ = tauri::Manager::emit_all(&app, "system-theme-changed", theme);
+            last = Some(theme);
+        }
+    });
+}
+
+/// Current OS theme; light when it cannot be determined
+#[tauri::command]
+pub fn get_system_theme() -> SystemTheme {
+    read_system_theme().unwrap_or(SystemTheme::Light)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme_outputs() {
+        assert_eq!(
+            parse_portal_color_scheme("(<<uint32 1>>,)\n"),
+            Some(SystemTheme::Dark)
+        );
+        assert_eq!(
+            parse_portal_color_scheme("(<uint32 2>,)"),
+            Some(SystemTheme::Light)
+        );
+        assert_eq!(parse_portal_color_scheme("(<<uint32 0>>,)"), None);
+        assert_eq!(parse_portal_color_scheme(""), None);
+
+        assert_eq!(
+            parse_gsettings_value("'prefer-dark'\n"),
+            Some(SystemTheme::Dark)
+        );
+        assert_eq!(
+            parse_gsettings_value("'Adwaita-dark'"),
+            Some(SystemTheme::Dark)
+        );
+        assert_eq!(parse_gsettings_value("'default'"), Some(SystemTheme::Light));
+        assert_eq!(parse_gsettings_value(""), None);
+    }
+}
@@ -0,0 +1,188 @@
+// Thermal monitoring
+// Reads CPU temperatures where the platform exposes them (hwmon on Linux, ACPI
+// thermal zones on Windows) and decides when a render should warn or pause.
+
+use serde::{Deserialize, Serialize};
+
+/// A render paused for heat resumes once every sensor is this far below its limit
+pub const RESUME_HYSTERESIS_C: f32 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThermalSettings {
+    pub cpu_limit_c: f32,
+    pub gpu_limit_c: f32,
+    /// Suspend FFmpeg while over a limit instead of only warning
+    pub pause_on_overheat: bool,
+}
+
+impl Default for ThermalSettings {
+    fn default() -> Self {
+        Self {
+            cpu_limit_c: 95.0,
+            gpu_limit_c: 87.0,
+            pause_on_overheat: false,
+        }
+    }
+}
+
+/// Payload of the `thermal-warning` event
+#[derive(Debug, Clone, Serialize)]
+pub struct ThermalWarning {
+    pub job_id: String,
+    pub cpu_c: Option<f32>,
+    pub gpu_c: Option<f32>,
+    pub cpu_limit_c: f32,
+    pub gpu_limit_c: f32,
+    /// The render was suspended until it cools down
+    pub paused: bool,
+    /// Temperatures are back to normal (and a paused render continues)
+    pub resumed: bool,
+}
+
+/// Tracks whether a render is over its temperature limits
+pub struct ThermalGuard {
+    settings: ThermalSettings,
+    overheated: bool,
+}
+
+impl ThermalGuard {
+    pub fn new(settings: ThermalSettings) -> Self {
+        Self {
+            settings,
+            overheated: false,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.overheated && self.settings.pause_on_overheat
+    }
+
+    /// Feed a temperature reading; returns a warning when the state changes
+    pub fn check(
+        &mut self,
+        job_id: &str,
+        cpu_c: Option<f32>,
+        gpu_c: Option<f32>,
+    ) -> Option<ThermalWarning> {
+        let over =
+            |temp: Option<f32>, limit: f32, margin: f32| temp.is_some_and(|t| t > limit - margin);
+        let (cpu_limit, gpu_limit) = (self.settings.cpu_limit_c, self.settings.gpu_limit_c);
+
+        let warning = |paused, resumed| ThermalWarning {
+            job_id: job_id.to_string(),
+            cpu_c,
+            gpu_c,
+            cpu_limit_c: cpu_limit,
+            gpu_limit_c: gpu_limit,
+            paused,
+            resumed,
+        };
+
+        if !self.overheated && (over(cpu_c, cpu_limit, 0.0) || over(gpu_c, gpu_limit, 0.0)) {
+            self.overheated = true;
+            return Some(warning(self.settings.pause_on_overheat, false));
+        }
+
+        let cooled = !over(cpu_c, cpu_limit, RESUME_HYSTERESIS_C)
+            && !over(gpu_c, gpu_limit, RESUME_HYSTERESIS_C);
+        if self.overheated && cooled {
+            self.overheated = false;
+            return Some(warning(false, true));
+        }
+
+        None
+    }
+}
+
+/// Hottest CPU sensor in °C, None when the platform does not expose one
+pub fn read_cpu_temperature() -> Option<f32> {
+    #[cfg(target_os = "linux")]
+    {
+        // hwmon drivers for CPU packages; thermal zones are the fallback
+        const CPU_HWMON: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+        let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+        let millidegrees = |s: String| s.trim().parse::<f32>().ok().map(|m| m / 1000.0);
+
+        let mut hottest: Option<f32> = None;
+        for hwmon in std::fs::read_dir("/sys/class/hwmon")
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let dir = hwmon.path();
+            let is_cpu = read(dir.join("name")).is_some_and(|n| CPU_HWMON.contains(&n.trim()));
+            if !is_cpu {
+                continue;
+            }
+            for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with("temp") && name.ends_with("_input") {
+                    if let Some(t) = read(entry.path()).and_then(millidegrees) {
+                        hottest = Some(hottest.map_or(t, |h| h.max(t)));
+                    }
+                }
+            }
+        }
+        if hottest.is_some() {
+            return hottest;
+        }
+
+        std::fs::read_dir("/sys/class/thermal")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("thermal_zone"))
+            .filter_map(|e| read(e.path().join("temp")).and_then(millidegrees))
+            .reduce(f32::max)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // ACPI thermal zones report tenths of a Kelvin; many machines need admin rights
+        let output = crate::proc::run_captured(
+            crate::proc::command("powershell").args([
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance -Namespace root/wmi -ClassName MSAcpi_ThermalZoneTemperature | ForEach-Object { $_.CurrentTemperature }",
+            ]),
+            &crate::proc::RunOptions::with_timeout(10),
+        )
+        .ok()
+        .filter(|o| o.success)?;
+        output
+            .stdout
+            .lines()
+            .filter_map(|l| l.trim().parse::<f32>().ok())
+            .map(|tenths_kelvin| tenths_kelvin / 10.0 - 273.15)
+            .reduce(f32::max)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thermal_guard_pauses_and_resumes_with_hysteresis() {
+        let mut guard = ThermalGuard::new(ThermalSettings {
+            pause_on_overheat: true,
+            ..Default::default()
+        });
+
+        assert!(guard.check("job", Some(80.0), Some(70.0)).is_none());
+        let hot = guard.check("job", Some(97.0), Some(70.0)).unwrap();
+        assert!(hot.paused && !hot.resumed);
+        // Still within the hysteresis band
+        assert!(guard.check("job", Some(90.0), Some(70.0)).is_none());
+        assert!(guard.is_paused());
+        let cool = guard.check("job", Some(84.0), Some(70.0)).unwrap();
+        assert!(cool.resumed && !cool.paused);
+        assert!(!guard.is_paused());
+    }
+}
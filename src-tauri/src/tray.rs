@@ -0,0 +1,178 @@
+// System tray
+// Shows queue progress in the tray tooltip and lets the queue be paused, stopped or the
+// app quit while the window is hidden. Closing the window while renders run hides it
+// to the tray instead of exiting. The queue lives in the frontend (RenderService), so
+// menu actions go to it as `queue-control` events and it reports back via update_tray_status.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, CustomMenuItem, GlobalWindowEvent, Manager, SystemTray, SystemTrayEvent,
+    SystemTrayMenu, SystemTrayMenuItem, WindowEvent,
+};
+
+const STATUS_ITEM: &str = "status";
+const PAUSE_ITEM: &str = "pause_resume";
+const STOP_ITEM: &str = "stop_all";
+const SHOW_ITEM: &str = "show";
+const QUIT_ITEM: &str = "quit";
+
+const PAUSE_LABEL: &str = "Пауза очереди";
+const RESUME_LABEL: &str = "Продолжить очередь";
+const IDLE_LABEL: &str = "Нет заданий";
+
+/// Queue state reported by the frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrayStatus {
+    /// Jobs rendering right now
+    pub active_jobs: usize,
+    /// Jobs still waiting in the queue
+    pub pending_jobs: usize,
+    /// Average progress of the active jobs, 0-100
+    pub progress: f64,
+    pub paused: bool,
+}
+
+lazy_static! {
+    static ref STATUS: Mutex<TrayStatus> = Mutex::new(TrayStatus::default());
+}
+
+/// Queue state as last reported by the frontend
+pub fn status() -> TrayStatus {
+    STATUS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Whether the frontend queue is paused, as last reported
+pub fn queue_paused() -> bool {
+    status().paused
+}
+
+/// Tooltip / status line, e.g. "2 задания, 45%"
+fn status_text(status: &TrayStatus) -> String {
+    if status.active_jobs == 0 && status.pending_jobs == 0 {
+        return IDLE_LABEL.to_string();
+    }
+    let jobs = status.active_jobs + status.pending_jobs;
+    let noun = match (jobs % 10, jobs % 100) {
+        (1, n) if n != 11 => "задание",
+        (2..=4, n) if !(12..=14).contains(&n) => "задания",
+        _ => "заданий",
+    };
+    let mut text = format!("{} {}", jobs, noun);
+    if status.active_jobs > 0 {
+        text.push_str(&format!(", {:.0}%", status.progress.clamp(0.0, 100.0)));
+    }
+    if status.paused {
+        text.push_str(" (пауза)");
+    }
+    text
+}
+
+pub fn build() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(STATUS_ITEM, IDLE_LABEL).disabled())
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(PAUSE_ITEM, PAUSE_LABEL).disabled())
+        .add_item(CustomMenuItem::new(STOP_ITEM, "Остановить всё").disabled())
+        .add_item(CustomMenuItem::new(SHOW_ITEM, "Открыть окно"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(QUIT_ITEM, "Выход"));
+    SystemTray::new().with_menu(menu).with_tooltip("Szhimatar")
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Stop running renders and exit; the frontend may already be hidden or gone
+fn quit(app: &AppHandle) {
+    let _ = crate::write_log("[TRAY] Quit from tray".to_string());
+    crate::shutdown::exit(app);
+}
+
+pub fn handle_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => {
+            let action = match id.as_str() {
+                PAUSE_ITEM if status().paused => "resume",
+                PAUSE_ITEM => "pause",
+                STOP_ITEM => "stop_all",
+                SHOW_ITEM => return show_main_window(app),
+                QUIT_ITEM => return quit(app),
+                _ => return,
+            };
+            crate::queue::request_queue_control(app, action);
+        }
+        _ => {}
+    }
+}
+
+/// Closing the window while renders run only hides it; the tray keeps the app reachable
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event.event() {
+        let status = status();
+        if status.active_jobs > 0 || (status.pending_jobs > 0 && !status.paused) {
+            api.prevent_close();
+            let _ = event.window().hide();
+        }
+    }
+}
+
+/// Called by the frontend whenever the queue changes
+#[tauri::command]
+pub fn update_tray_status(app: AppHandle, status: TrayStatus) -> Result<(), String> {
+    let text = status_text(&status);
+    let busy = status.active_jobs > 0 || status.pending_jobs > 0;
+
+    let tray = app.tray_handle();
+    tray.set_tooltip(&format!("Szhimatar: {}", text))
+        .map_err(|e| e.to_string())?;
+    tray.get_item(STATUS_ITEM)
+        .set_title(text)
+        .map_err(|e| e.to_string())?;
+    let pause_item = tray.get_item(PAUSE_ITEM);
+    pause_item
+        .set_title(if status.paused {
+            RESUME_LABEL
+        } else {
+            PAUSE_LABEL
+        })
+        .map_err(|e| e.to_string())?;
+    pause_item.set_enabled(busy).map_err(|e| e.to_string())?;
+    tray.get_item(STOP_ITEM)
+        .set_enabled(status.active_jobs > 0)
+        .map_err(|e| e.to_string())?;
+
+    crate::http_api::broadcast("queue-status", &status);
+    *STATUS.lock().map_err(|e| e.to_string())? = status;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_text() {
+        let mut status = TrayStatus::default();
+        assert_eq!(status_text(&status), IDLE_LABEL);
+
+        status.active_jobs = 2;
+        status.progress = 45.4;
+        assert_eq!(status_text(&status), "2 задания, 45%");
+
+        status.active_jobs = 0;
+        status.pending_jobs = 11;
+        status.paused = true;
+        assert_eq!(status_text(&status), "11 заданий (пауза)");
+
+        status.pending_jobs = 21;
+        assert_eq!(status_text(&status), "21 задание (пауза)");
+    }
+}
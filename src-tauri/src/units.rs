@@ -0,0 +1,229 @@
+// Typed media durations and byte sizes
+// FFmpeg reports both as unit-suffixed strings ("00:01:02.50", "1024kB", "1.5MiB",
+// "N/A"); they are parsed here once and travel through the API as plain numbers
+// (seconds / bytes) so the UI never has to re-parse them.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Media duration / timestamp in seconds (serialized as a number of seconds)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Duration(f64);
+
+impl Duration {
+    pub const ZERO: Duration = Duration(0.0);
+
+    /// Negative and non-finite values are clamped to zero
+    pub fn from_secs_f64(seconds: f64) -> Self {
+        if seconds.is_finite() && seconds > 0.0 {
+            Duration(seconds)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    pub fn from_micros(micros: i64) -> Self {
+        Self::from_secs_f64(micros as f64 / 1_000_000.0)
+    }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 <= 0.0
+    }
+
+    /// Parse "HH:MM:SS(.ff)", "MM:SS", plain seconds ("62.5") or "N/A" (None)
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("n/a") {
+            return None;
+        }
+
+        let negative = text.starts_with('-');
+        let mut seconds = 0.0;
+        for part in text.trim_start_matches('-').split(':') {
+            seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+        }
+
+        Some(if negative {
+            Duration::ZERO
+        } else {
+            Self::from_secs_f64(seconds)
+        })
+    }
+}
+
+impl fmt::Display for Duration {
+    /// HH:MM:SS
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.0.round() as u64;
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            total / 3600,
+            (total % 3600) / 60,
+            total % 60
+        )
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    /// Accepts seconds as a number or any string understood by Duration::parse
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RawValue::deserialize(deserializer)? {
+            RawValue::Number(seconds) => Ok(Duration::from_secs_f64(seconds)),
+            RawValue::Text(text) => Ok(Duration::parse(&text).unwrap_or_default()),
+        }
+    }
+}
+
+/// Size in bytes (serialized as a number of bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    pub const ZERO: ByteSize = ByteSize(0);
+
+    pub fn from_bytes(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+
+    pub fn as_gib(&self) -> f64 {
+        self.0 as f64 / 1_073_741_824.0
+    }
+
+    pub fn saturating_sub(self, other: ByteSize) -> ByteSize {
+        ByteSize(self.0.saturating_sub(other.0))
+    }
+
+    /// Parse FFmpeg size strings: "1024kB", "512KiB", "1.5MB", "3 GiB", "123456",
+    /// "0B"; "N/A" gives None. FFmpeg's own "kB"/"MB" are binary multiples.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("n/a") {
+            return None;
+        }
+
+        let split = text
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let value: f64 = number.parse().ok()?;
+
+        let multiplier: f64 = match unit.trim().to_lowercase().as_str() {
+            "" | "b" | "bytes" => 1.0,
+            "k" | "kb" | "kib" => 1024.0,
+            "m" | "mb" | "mib" => 1024.0 * 1024.0,
+            "g" | "gb" | "gib" => 1024.0 * 1024.0 * 1024.0,
+            "t" | "tb" | "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+            _ => return None,
+        };
+
+        Some(ByteSize((value * multiplier).round() as u64))
+    }
+}
+
+impl std::ops::Add for ByteSize {
+    type Output = ByteSize;
+
+    fn add(self, other: ByteSize) -> ByteSize {
+        ByteSize(self.0.saturating_add(other.0))
+    }
+}
+
+impl std::ops::AddAssign for ByteSize {
+    fn add_assign(&mut self, other: ByteSize) {
+        self.0 = self.0.saturating_add(other.0);
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Human readable, binary units ("1.50 MB")
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            write!(f, "{} B", self.0)
+        } else {
+            write!(f, "{:.2} {}", value, UNITS[unit])
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    /// Accepts bytes as a number or any string understood by ByteSize::parse
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match RawValue::deserialize(deserializer)? {
+            RawValue::Number(bytes) if bytes.is_finite() && bytes > 0.0 => {
+                Ok(ByteSize(bytes.round() as u64))
+            }
+            RawValue::Number(_) => Ok(ByteSize::ZERO),
+            RawValue::Text(text) => Ok(ByteSize::parse(&text).unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Number(f64),
+    Text(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_durations() {
+        assert_eq!(Duration::parse("00:01:02.50"), Some(Duration(62.5)));
+        assert_eq!(Duration::parse("01:00"), Some(Duration(60.0)));
+        assert_eq!(Duration::parse("12.25"), Some(Duration(12.25)));
+        assert_eq!(Duration::parse("-00:00:00.04"), Some(Duration::ZERO));
+        assert_eq!(Duration::parse("N/A"), None);
+        assert_eq!(Duration::from_secs_f64(3725.0).to_string(), "01:02:05");
+    }
+
+    #[test]
+    fn test_parse_sizes() {
+        assert_eq!(ByteSize::parse("1024kB"), Some(ByteSize(1024 * 1024)));
+        assert_eq!(ByteSize::parse("512KiB"), Some(ByteSize(512 * 1024)));
+        assert_eq!(ByteSize::parse("1.5MB"), Some(ByteSize(1_572_864)));
+        assert_eq!(ByteSize::parse("123456"), Some(ByteSize(123_456)));
+        assert_eq!(ByteSize::parse("N/A"), None);
+        assert_eq!(ByteSize::parse("12 parsecs"), None);
+    }
+
+    #[test]
+    fn test_serde_numbers_and_strings() {
+        let size: ByteSize = serde_json::from_str("\"2kB\"").unwrap();
+        assert_eq!(serde_json::to_string(&size).unwrap(), "2048");
+        let duration: Duration = serde_json::from_str("\"00:00:10\"").unwrap();
+        assert_eq!(serde_json::to_string(&duration).unwrap(), "10.0");
+        let duration: Duration = serde_json::from_str("4.5").unwrap();
+        assert_eq!(duration.as_secs_f64(), 4.5);
+    }
+}
@@ -0,0 +1,180 @@
+// Update rollback
+// apply_update keeps the binary it replaces next to it as "<name>.old<.ext>"
+// (Szhimatar.old.exe) and records it in updates/rollback.json. rollback_update copies it
+// back. A freshly installed version counts as working once it has been up for
+// STARTUP_GRACE_SECS; when MAX_FAILED_STARTS launches in a row end before that, the next
+// launch rolls back by itself.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long a new version has to stay up to count as started
+const STARTUP_GRACE_SECS: u64 = 20;
+/// Launches of a new version ending early before it is rolled back
+const MAX_FAILED_STARTS: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackInfo {
+    /// Version of the kept binary
+    pub previous_version: String,
+    pub backup_path: String,
+    /// RFC 3339 time of apply_update
+    pub installed_at: String,
+    /// The new version has not been up for STARTUP_GRACE_SECS yet
+    #[serde(default)]
+    pub pending: bool,
+    /// Launches of the new version that ended within the grace period
+    #[serde(default)]
+    pub failed_starts: u32,
+}
+
+fn rollback_file() -> PathBuf {
+    crate::get_updates_dir().join("rollback.json")
+}
+
+fn load() -> Option<RollbackInfo> {
+    std::fs::read_to_string(rollback_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save(info: &RollbackInfo) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+    std::fs::write(rollback_file(), content)
+        .map_err(|e| format!("Failed to save rollback info: {}", e))
+}
+
+/// "<stem>.old.<ext>" next to the binary
+pub fn backup_path(exe: &Path) -> PathBuf {
+    let stem = exe.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match exe.extension() {
+        Some(ext) => format!("{}.old.{}", stem, ext.to_string_lossy()),
+        None => format!("{}.old", stem),
+    };
+    exe.with_file_name(name)
+}
+
+/// Remember the backup apply_update is about to make of the running binary
+pub fn record_update(backup: &Path) -> Result<(), String> {
+    save(&RollbackInfo {
+        previous_version: env!("CARGO_PKG_VERSION").to_string(),
+        backup_path: backup.to_string_lossy().to_string(),
+        installed_at: chrono::Local::now().to_rfc3339(),
+        pending: true,
+        failed_starts: 0,
+    })
+}
+
+/// Backup that can be restored: it exists and holds another version than the running one
+fn available() -> Option<RollbackInfo> {
+    load().filter(|info| {
+        info.previous_version != env!("CARGO_PKG_VERSION") && Path::new(&info.backup_path).is_file()
+    })
+}
+
+/// Copy the backup over the running binary and restart; the caller exits afterwards
+fn restore(info: &RollbackInfo) -> Result<(), String> {
+    let current_exe =
+        std::env::current_exe().map_err(|e| format!("Failed to get current exe: {}", e))?;
+    crate::spawn_replace_script(Path::new(&info.backup_path), &current_exe, None)?;
+    save(&RollbackInfo {
+        pending: false,
+        failed_starts: 0,
+        ..info.clone()
+    })
+}
+
+/// Count a launch of a freshly installed version, before the window is created. After
+/// MAX_FAILED_STARTS early exits in a row the previous version is restored and this
+/// process exits.
+pub fn check_startup() {
+    let Some(mut info) = load().filter(|info| info.pending) else {
+        return;
+    };
+    if info.previous_version == env!("CARGO_PKG_VERSION") {
+        // The copy did not happen: still the old binary
+        let _ =
+            crate::write_log("[UPDATE] Update was not installed, nothing to confirm".to_string());
+        info.pending = false;
+        let _ = save(&info);
+        return;
+    }
+    if info.failed_starts >= MAX_FAILED_STARTS && Path::new(&info.backup_path).is_file() {
+        let _ = crate::write_log(format!(
+            "[UPDATE] Version {} ended early {} times, rolling back to {}",
+            env!("CARGO_PKG_VERSION"),
+            info.failed_starts,
+            info.previous_version
+        ));
+        match restore(&info) {
+            Ok(()) => std::process::exit(0),
+            Err(e) => {
+                let _ = crate::write_log(format!("[UPDATE] Rollback failed: {}", e));
+            }
+        }
+        return;
+    }
+    info.failed_starts += 1;
+    let _ = save(&info);
+}
+
+/// Mark the new version as working once it has been up for STARTUP_GRACE_SECS
+pub fn confirm_startup_later() {
+    if !load().is_some_and(|info| info.pending) {
+        return;
+    }
+    std::thread::spawn(|| {
+        std::thread::sleep(Duration::from_secs(STARTUP_GRACE_SECS));
+        if let Some(info) = load().filter(|info| info.pending) {
+            let _ = crate::write_log(format!(
+                "[UPDATE] Version {} started fine",
+                env!("CARGO_PKG_VERSION")
+            ));
+            let _ = save(&RollbackInfo {
+                pending: false,
+                failed_starts: 0,
+                ..info
+            });
+        }
+    });
+}
+
+/// The version rollback_update would restore, if any
+#[tauri::command]
+pub fn get_update_rollback() -> Option<RollbackInfo> {
+    available()
+}
+
+/// Restore the binary kept by the last update and restart
+#[tauri::command]
+pub fn rollback_update(app_handle: tauri::AppHandle) -> Result<(), String> {
+    crate::shutdown::ensure_no_renders()?;
+    let info = available().ok_or("No previous version to roll back to")?;
+    let _ = crate::write_log(format!(
+        "[UPDATE] Rolling back from {} to {}",
+        env!("CARGO_PKG_VERSION"),
+        info.previous_version
+    ));
+    restore(&info)?;
+    crate::shutdown::exit(&app_handle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_path() {
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            backup_path(&dir.join("Szhimatar.exe")),
+            dir.join("Szhimatar.old.exe")
+        );
+        assert_eq!(
+            backup_path(&dir.join("szhimatar")),
+            dir.join("szhimatar.old")
+        );
+    }
+}
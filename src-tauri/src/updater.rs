@@ -0,0 +1,533 @@
+// Update check
+// Releases are discovered through the GitHub Releases API: the newest release of the
+// chosen channel (stable: regular releases only; beta: pre-releases too) that is newer
+// than the running version is reported with its changelog and the download asset for
+// this platform. Downloading and installing stay with download_update / apply_update.
+// Release assets are signed with minisign; apply_update only installs an artifact whose
+// "<asset>.minisig" verifies against the public key built into the app.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/sharkye1/Szhimatar2/releases?per_page=30";
+
+/// minisign public key ("RW..." line of minisign.pub) release assets are signed with,
+/// set when building a release. Without it updates cannot be verified and are refused.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("SZHIMATAR_UPDATE_PUBLIC_KEY");
+
+/// Extension of the minisign signature published next to each asset
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Pre-releases as well
+    Beta,
+}
+
+/// Semantic version; build metadata is ignored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers ("beta.2" -> ["beta", "2"]); empty for a release
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    /// "v1.4.0", "1.4", "1.5.0-beta.2+build.7"
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_start_matches(['v', 'V']);
+        let text = text.split('+').next()?;
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(String::from).collect()),
+            None => (text, Vec::new()),
+        };
+        let mut numbers = core.split('.').map(|part| part.parse::<u64>());
+        let major = numbers.next()?.ok()?;
+        let minor = numbers.next().unwrap_or(Ok(0)).ok()?;
+        let patch = numbers.next().unwrap_or(Ok(0)).ok()?;
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// Semver precedence: a release ranks above its pre-releases, numeric identifiers
+/// compare as numbers and below alphanumeric ones
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if order != Ordering::Equal {
+                            return order;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    /// "sha256:<hex>" on assets uploaded since mid 2025
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// A newer release with an asset for this platform exists
+    pub available: bool,
+    pub version: Option<String>,
+    /// Release notes (markdown)
+    pub changelog: Option<String>,
+    pub asset_url: Option<String>,
+    pub asset_name: Option<String>,
+    /// SHA-256 of the asset, when the release publishes one
+    pub asset_hash: Option<String>,
+    /// minisign signature of the asset ("<asset>.minisig")
+    pub signature_url: Option<String>,
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+    pub release_url: Option<String>,
+}
+
+/// Words in asset names that mark the platform they are built for
+fn platform_keywords() -> &'static [&'static str] {
+    if cfg!(windows) {
+        &["windows", "win64", "win", "x64"]
+    } else if cfg!(target_os = "macos") {
+        &["macos", "darwin", "mac", "aarch64", "universal"]
+    } else {
+        &["linux", "appimage", "amd64"]
+    }
+}
+
+/// File types apply_update can install on this platform: the Windows .exe (also zipped)
+/// and the Linux AppImage. Tarballs and macOS app bundles need the release page.
+fn installable_extensions() -> &'static [&'static str] {
+    if cfg!(windows) {
+        &[".exe", ".zip"]
+    } else if cfg!(target_os = "macos") {
+        &[]
+    } else {
+        &[".appimage"]
+    }
+}
+
+/// How apply_update installs a downloaded update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateArtifact {
+    /// Replaces the running executable as is (.exe, .AppImage)
+    Executable,
+    /// Zip with the Windows .exe inside
+    Zip,
+}
+
+/// What apply_update does with `artifact`; None for anything it cannot install on
+/// this platform, so an archive is never copied over the running binary
+pub fn artifact_kind(artifact: &Path) -> Option<UpdateArtifact> {
+    let name = artifact.file_name()?.to_string_lossy().to_lowercase();
+    if !installable_extensions()
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    {
+        return None;
+    }
+    Some(if name.ends_with(".zip") {
+        UpdateArtifact::Zip
+    } else {
+        UpdateArtifact::Executable
+    })
+}
+
+/// Download asset for this platform: installable and named after it (a Windows .exe
+/// needs no name), preferring the more specific names
+fn pick_asset(assets: &[GithubAsset]) -> Option<&GithubAsset> {
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let name = asset.name.to_lowercase();
+            if !installable_extensions()
+                .iter()
+                .any(|ext| name.ends_with(ext))
+            {
+                return None;
+            }
+            // Earlier keywords are the more specific ones
+            let rank = platform_keywords()
+                .iter()
+                .rev()
+                .enumerate()
+                .filter(|(_, keyword)| name.contains(*keyword))
+                .map(|(rank, _)| rank + 1)
+                .max()
+                .unwrap_or(0);
+            (rank > 0 || (cfg!(windows) && name.ends_with(".exe"))).then_some((asset, rank))
+        })
+        .max_by_key(|(_, rank)| *rank)
+        .map(|(asset, _)| asset)
+}
+
+/// Newest release of `channel` above `current`, with its asset
+fn newest_release<'a>(
+    releases: &'a [GithubRelease],
+    current: &Version,
+    channel: UpdateChannel,
+) -> Option<(&'a GithubRelease, Version, &'a GithubAsset)> {
+    releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == UpdateChannel::Beta || !release.prerelease)
+        .filter_map(|release| {
+            let version = Version::parse(&release.tag_name)?;
+            let asset = pick_asset(&release.assets)?;
+            Some((release, version, asset))
+        })
+        .filter(|(_, version, _)| version > current)
+        .max_by(|a, b| a.1.cmp(&b.1))
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    crate::network::client_builder()?
+        .timeout(Duration::from_secs(20))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// SHA-256 of an asset: its digest, else a "<name>.sha256" asset next to it
+fn asset_hash(
+    client: &reqwest::blocking::Client,
+    release: &GithubRelease,
+    asset: &GithubAsset,
+) -> Option<String> {
+    if let Some(hash) = asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+    {
+        return Some(hash.to_lowercase());
+    }
+    let checksum_name = format!("{}.sha256", asset.name).to_lowercase();
+    let checksum = release
+        .assets
+        .iter()
+        .find(|a| a.name.to_lowercase() == checksum_name)?;
+    let text = client
+        .get(&checksum.browser_download_url)
+        .send()
+        .ok()?
+        .text()
+        .ok()?;
+    text.split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_lowercase)
+}
+
+/// "<asset>.minisig" of the release
+fn signature_asset<'a>(release: &'a GithubRelease, asset: &GithubAsset) -> Option<&'a GithubAsset> {
+    let signature_name = format!("{}.{}", asset.name, SIGNATURE_EXTENSION).to_lowercase();
+    release
+        .assets
+        .iter()
+        .find(|a| a.name.to_lowercase() == signature_name)
+}
+
+/// Check `artifact` against its minisign `signature` with `public_key`. Only pre-hashed
+/// signatures (the minisign default) are accepted, so the file is streamed, not loaded.
+fn verify_with_key(public_key: &str, artifact: &Path, signature: &Path) -> Result<(), String> {
+    let public_key = minisign_verify::PublicKey::from_base64(public_key.trim())
+        .map_err(|e| format!("Invalid update public key: {}", e))?;
+    let signature = std::fs::read_to_string(signature)
+        .map_err(|e| format!("Failed to read update signature: {}", e))?;
+    let signature = minisign_verify::Signature::decode(&signature)
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|e| format!("Update signature rejected: {}", e))?;
+
+    let mut file = std::fs::File::open(artifact)
+        .map_err(|e| format!("Failed to open {}: {}", artifact.display(), e))?;
+    let mut buffer = [0u8; 65536];
+    loop {
+        let bytes_read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", artifact.display(), e))?;
+        if bytes_read == 0 {
+            break;
+        }
+        verifier.update(&buffer[..bytes_read]);
+    }
+    verifier
+        .finalize()
+        .map_err(|e| format!("Update signature rejected: {}", e))
+}
+
+/// Check a downloaded update against its minisign signature and the key built into
+/// the app; an error means the file must not be installed
+pub fn verify_signature(artifact: &Path, signature: &Path) -> Result<(), String> {
+    let public_key = UPDATE_PUBLIC_KEY
+        .filter(|key| !key.trim().is_empty())
+        .ok_or("This build has no update signing key; install updates manually")?;
+    verify_with_key(public_key, artifact, signature)
+}
+
+fn check(channel: UpdateChannel) -> Result<UpdateCheck, String> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    let current =
+        Version::parse(current_version).ok_or("Cannot parse the running version".to_string())?;
+    let client = http_client()?;
+    let response = client
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .map_err(|e| format!("Update check failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Update check failed with status: {}",
+            response.status()
+        ));
+    }
+    let body = response
+        .text()
+        .map_err(|e| format!("Update check failed: {}", e))?;
+    let releases: Vec<GithubRelease> =
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse releases: {}", e))?;
+
+    let mut result = UpdateCheck {
+        current_version: current_version.to_string(),
+        channel,
+        available: false,
+        version: None,
+        changelog: None,
+        asset_url: None,
+        asset_name: None,
+        asset_hash: None,
+        signature_url: None,
+        published_at: None,
+        prerelease: false,
+        release_url: None,
+    };
+    if let Some((release, version, asset)) = newest_release(&releases, &current, channel) {
+        result = UpdateCheck {
+            available: true,
+            version: Some(version.to_string()),
+            changelog: release.body.clone(),
+            asset_url: Some(asset.browser_download_url.clone()),
+            asset_name: Some(asset.name.clone()),
+            asset_hash: asset_hash(&client, release, asset),
+            signature_url: signature_asset(release, asset)
+                .map(|signature| signature.browser_download_url.clone()),
+            published_at: release.published_at.clone(),
+            prerelease: release.prerelease,
+            release_url: Some(release.html_url.clone()),
+            ..result
+        };
+    }
+    Ok(result)
+}
+
+/// Look for a release newer than the running version. `channel` defaults to the
+/// updateChannel setting.
+#[tauri::command]
+pub async fn check_for_updates(channel: Option<UpdateChannel>) -> Result<UpdateCheck, String> {
+    let channel = channel.unwrap_or_else(|| {
+        crate::load_settings()
+            .map(|settings| settings.update_channel)
+            .unwrap_or_default()
+    });
+    let result = tokio::task::spawn_blocking(move || check(channel))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?;
+    match &result {
+        Ok(check) if check.available => {
+            let _ = crate::write_log(format!(
+                "[UPDATE] {} available ({:?} channel, running {})",
+                check.version.as_deref().unwrap_or_default(),
+                channel,
+                check.current_version
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let _ = crate::write_log(format!("[UPDATE] {}", e));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_version_order() {
+        assert_eq!(v("v1.4"), v("1.4.0"));
+        assert!(v("1.10.0") > v("1.9.3"));
+        assert!(v("1.4.0") > v("1.4.0-beta.2"));
+        assert!(v("1.4.0-beta.10") > v("1.4.0-beta.2"));
+        assert!(v("1.4.0-beta") > v("1.4.0-alpha.5"));
+        assert!(v("1.4.0-beta.1") > v("1.4.0-beta"));
+        assert_eq!(v("2.0.0+build.7").to_string(), "2.0.0");
+        assert!(Version::parse("latest").is_none());
+    }
+
+    #[test]
+    fn test_newest_release() {
+        let asset = |name: &str| GithubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            digest: None,
+        };
+        let installable = if cfg!(windows) {
+            "Szhimatar-windows-x64.zip"
+        } else {
+            "Szhimatar-linux-amd64.AppImage"
+        };
+        let release = |tag: &str, prerelease: bool| GithubRelease {
+            tag_name: tag.to_string(),
+            body: None,
+            draft: false,
+            prerelease,
+            published_at: None,
+            html_url: String::new(),
+            assets: vec![
+                asset("latest.json"),
+                asset("Szhimatar-linux-amd64.tar.gz"),
+                asset("Szhimatar-macos-aarch64.zip"),
+                asset(installable),
+            ],
+        };
+        let releases = vec![
+            release("v1.3.2", false),
+            release("v1.4.0", false),
+            release("v1.5.0-beta.1", true),
+        ];
+        let current = v("1.3.2");
+        if cfg!(target_os = "macos") {
+            // Nothing apply_update can install
+            assert!(newest_release(&releases, &current, UpdateChannel::Stable).is_none());
+            return;
+        }
+
+        let (_, version, found) =
+            newest_release(&releases, &current, UpdateChannel::Stable).unwrap();
+        assert_eq!(version, v("1.4.0"));
+        assert_eq!(found.name, installable);
+        let (_, version, _) = newest_release(&releases, &current, UpdateChannel::Beta).unwrap();
+        assert_eq!(version, v("1.5.0-beta.1"));
+        assert!(newest_release(&releases, &v("1.4.0"), UpdateChannel::Stable).is_none());
+    }
+
+    #[test]
+    fn test_archives_are_not_executables() {
+        for name in [
+            "Szhimatar-linux-amd64.tar.gz",
+            "Szhimatar.AppImage.tar.gz",
+            "Szhimatar-macos-aarch64.tar.gz",
+        ] {
+            assert_eq!(artifact_kind(Path::new(name)), None);
+        }
+        let native = if cfg!(windows) {
+            Some("Szhimatar.exe")
+        } else if cfg!(target_os = "macos") {
+            None
+        } else {
+            Some("Szhimatar-linux-amd64.AppImage")
+        };
+        if let Some(name) = native {
+            assert_eq!(
+                artifact_kind(Path::new(name)),
+                Some(UpdateArtifact::Executable)
+            );
+        }
+        let zip_kind = artifact_kind(Path::new("Szhimatar-windows-x64.zip"));
+        assert_eq!(zip_kind, cfg!(windows).then_some(UpdateArtifact::Zip));
+    }
+
+    #[test]
+    fn test_verify_with_key() {
+        // Pre-hashed signature of the bytes "test" (minisign-verify test vector)
+        let public_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let signature = "untrusted comment: signature from minisign secret key\n\
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=\n\
+trusted comment: timestamp:1556193335\tfile:test\n\
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==\n";
+        let dir = std::env::temp_dir().join("szhimatar-updater-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (artifact, signature_path) = (dir.join("update.zip"), dir.join("update.zip.minisig"));
+        std::fs::write(&signature_path, signature).unwrap();
+
+        std::fs::write(&artifact, b"test").unwrap();
+        assert!(verify_with_key(public_key, &artifact, &signature_path).is_ok());
+        std::fs::write(&artifact, b"Test").unwrap();
+        assert!(verify_with_key(public_key, &artifact, &signature_path).is_err());
+        std::fs::write(&signature_path, "not a signature").unwrap();
+        assert!(verify_with_key(public_key, &artifact, &signature_path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
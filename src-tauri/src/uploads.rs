@@ -0,0 +1,552 @@
+// Post-render uploads
+// A preset can name an upload destination (mainScreen.uploadDestination); once a job
+// with one renders successfully, its output is uploaded there on a background thread.
+// S3-compatible storage gets a single SigV4-signed PUT (objects up to 5 GB), WebDAV a
+// PUT with basic auth, and SFTP goes through the system `sftp` client in batch mode, so
+// it uses the user's SSH keys and agent. Failed attempts are retried with a growing
+// delay; progress is reported with `upload-progress`, the outcome with
+// `upload-complete` or `upload-error`.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Attempts per upload, including the first
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, doubled for each further one
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Least time between two `upload-progress` events of a job
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+/// Largest object a single S3 PUT may create
+const S3_MAX_PUT_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadKind {
+    #[default]
+    Sftp,
+    S3,
+    Webdav,
+}
+
+/// Where outputs are uploaded; which fields are used depends on `kind`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UploadDestination {
+    /// Referenced by presets (mainScreen.uploadDestination)
+    pub id: String,
+    pub name: String,
+    pub kind: UploadKind,
+    /// WebDAV: folder URL the file is put into. S3: endpoint, e.g.
+    /// "https://s3.eu-central-1.amazonaws.com"
+    pub url: String,
+    /// SFTP host and port (0 = 22)
+    pub host: String,
+    pub port: u16,
+    /// SFTP and WebDAV user
+    pub username: String,
+    /// WebDAV password
+    pub password: String,
+    /// SFTP private key (empty = the SSH agent / default keys)
+    pub key_path: String,
+    /// S3 bucket, region ("us-east-1" when empty) and credentials
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// SFTP folder or S3 key prefix the file is put into
+    pub remote_path: String,
+}
+
+/// Payload of the `upload-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgress {
+    pub job_id: String,
+    pub destination: String,
+    pub sent_bytes: u64,
+    pub total_bytes: u64,
+    pub percent: f64,
+    pub attempt: u32,
+}
+
+/// Payload of the `upload-complete` and `upload-error` events
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadResult {
+    pub job_id: String,
+    pub destination: String,
+    /// URL or remote path of the uploaded file
+    pub remote: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Reports how much of the wrapped reader has been read
+struct ProgressReader<R, F: FnMut(u64)> {
+    inner: R,
+    sent: u64,
+    last_report: Option<Instant>,
+    report: F,
+}
+
+impl<R: Read, F: FnMut(u64)> Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.sent += read as u64;
+        let due = self
+            .last_report
+            .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL);
+        if due || read == 0 {
+            self.last_report = Some(Instant::now());
+            (self.report)(self.sent);
+        }
+        Ok(read)
+    }
+}
+
+/// RFC 3986 percent-encoding of a path, keeping the `/` separators
+fn encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// `prefix/name` without doubled or leading slashes
+fn join_remote(prefix: &str, name: &str) -> String {
+    let prefix = prefix.trim().trim_matches('/');
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// AWS Signature Version 4 signing key for `date` (YYYYMMDD)
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let date_key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date);
+    let region_key = hmac_sha256(&date_key, region);
+    let service_key = hmac_sha256(&region_key, service);
+    hmac_sha256(&service_key, "aws4_request")
+}
+
+/// Authorization header of an S3 PUT with an unsigned payload
+fn s3_authorization(
+    destination: &UploadDestination,
+    host: &str,
+    canonical_uri: &str,
+    amz_date: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let region = match destination.region.trim() {
+        "" => "us-east-1",
+        region => region,
+    };
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:UNSIGNED-PAYLOAD\nx-amz-date:{}\n\n{}\nUNSIGNED-PAYLOAD",
+        canonical_uri, host, amz_date, signed_headers
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signature = hex::encode(hmac_sha256(
+        &signing_key(destination.secret_key.trim(), date, region, "s3"),
+        &string_to_sign,
+    ));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        destination.access_key.trim(),
+        scope,
+        signed_headers,
+        signature
+    )
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    // No overall timeout: large outputs on slow uplinks take hours
+    crate::network::client_builder()?
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(None)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// PUT the file to `url`; returns the URL
+fn put_file(
+    url: reqwest::Url,
+    file: &Path,
+    headers: Vec<(&'static str, String)>,
+    basic_auth: Option<(&str, &str)>,
+    on_progress: impl FnMut(u64) + Send + 'static,
+) -> Result<String, String> {
+    let total = std::fs::metadata(file)
+        .map_err(|e| format!("Cannot read {}: {}", file.display(), e))?
+        .len();
+    let reader = ProgressReader {
+        inner: std::fs::File::open(file)
+            .map_err(|e| format!("Cannot open {}: {}", file.display(), e))?,
+        sent: 0,
+        last_report: None,
+        report: on_progress,
+    };
+    let mut request = http_client()?
+        .put(url.clone())
+        .body(reqwest::blocking::Body::sized(reader, total));
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    if let Some((username, password)) = basic_auth {
+        request = request.basic_auth(username, Some(password));
+    }
+    let response = request
+        .send()
+        .map_err(|e| format!("Upload failed: {}", e))?;
+    if response.status().is_success() {
+        Ok(url.to_string())
+    } else {
+        let status = response.status();
+        let body: String = response
+            .text()
+            .unwrap_or_default()
+            .chars()
+            .take(300)
+            .collect();
+        Err(format!("HTTP {}: {}", status, body.trim()))
+    }
+}
+
+fn upload_s3(
+    destination: &UploadDestination,
+    file: &Path,
+    name: &str,
+    on_progress: impl FnMut(u64) + Send + 'static,
+) -> Result<String, String> {
+    if destination.bucket.trim().is_empty() || destination.access_key.trim().is_empty() {
+        return Err("S3 bucket and access key are required".to_string());
+    }
+    if std::fs::metadata(file).map(|m| m.len()).unwrap_or(0) > S3_MAX_PUT_BYTES {
+        return Err("Files over 5 GB cannot be uploaded to S3 in one piece".to_string());
+    }
+    let endpoint = reqwest::Url::parse(destination.url.trim())
+        .map_err(|e| format!("Invalid S3 endpoint: {}", e))?;
+    let host = match (endpoint.host_str(), endpoint.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err("Invalid S3 endpoint".to_string()),
+    };
+    // Path-style addressing works with AWS and the S3-compatible services alike
+    let canonical_uri = encode_path(&format!(
+        "/{}/{}",
+        destination.bucket.trim(),
+        join_remote(&destination.remote_path, name)
+    ));
+    let url = endpoint
+        .join(&canonical_uri)
+        .map_err(|e| format!("Invalid S3 object URL: {}", e))?;
+    let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let authorization = s3_authorization(destination, &host, &canonical_uri, &amz_date);
+    put_file(
+        url,
+        file,
+        vec![
+            ("x-amz-content-sha256", "UNSIGNED-PAYLOAD".to_string()),
+            ("x-amz-date", amz_date),
+            ("authorization", authorization),
+        ],
+        None,
+        on_progress,
+    )
+}
+
+fn upload_webdav(
+    destination: &UploadDestination,
+    file: &Path,
+    name: &str,
+    on_progress: impl FnMut(u64) + Send + 'static,
+) -> Result<String, String> {
+    let folder = format!("{}/", destination.url.trim().trim_end_matches('/'));
+    let url = reqwest::Url::parse(&folder)
+        .and_then(|folder| folder.join(&encode_path(name)))
+        .map_err(|e| format!("Invalid WebDAV URL: {}", e))?;
+    let auth = (!destination.username.is_empty())
+        .then_some((destination.username.as_str(), destination.password.as_str()));
+    put_file(url, file, Vec::new(), auth, on_progress)
+}
+
+/// Path quoted for an sftp batch file
+fn sftp_quote(path: &str) -> String {
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn upload_sftp(destination: &UploadDestination, file: &Path, name: &str) -> Result<String, String> {
+    if destination.host.trim().is_empty() {
+        return Err("SFTP host is required".to_string());
+    }
+    let remote = format!(
+        "{}/{}",
+        destination.remote_path.trim().trim_end_matches('/'),
+        name
+    );
+    let batch = std::env::temp_dir().join(format!(
+        "szhimatar-sftp-{}-{}.txt",
+        std::process::id(),
+        chrono::Local::now().timestamp_millis()
+    ));
+    std::fs::write(
+        &batch,
+        format!(
+            "put {} {}\n",
+            sftp_quote(&file.to_string_lossy()),
+            sftp_quote(&remote)
+        ),
+    )
+    .map_err(|e| format!("Failed to write the sftp batch file: {}", e))?;
+
+    let port = if destination.port == 0 {
+        22
+    } else {
+        destination.port
+    };
+    let target = if destination.username.trim().is_empty() {
+        destination.host.trim().to_string()
+    } else {
+        format!(
+            "{}@{}",
+            destination.username.trim(),
+            destination.host.trim()
+        )
+    };
+    let mut cmd = crate::proc::command("sftp");
+    cmd.arg("-b")
+        .arg(&batch)
+        .args(["-P", &port.to_string()])
+        .args([
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+        ]);
+    if !destination.key_path.trim().is_empty() {
+        cmd.args(["-i", destination.key_path.trim()]);
+    }
+    cmd.arg(&target);
+    // At least 100 KB/s
+    let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+    let result = crate::proc::run_captured(
+        &mut cmd,
+        &crate::proc::RunOptions::with_timeout(300 + size / 100_000).label("sftp upload"),
+    );
+    let _ = std::fs::remove_file(&batch);
+
+    let output = result?;
+    if output.timed_out {
+        return Err("SFTP upload timed out".to_string());
+    }
+    if !output.success {
+        let error = output
+            .stderr
+            .lines()
+            .rfind(|line| !line.trim().is_empty())
+            .unwrap_or("sftp failed")
+            .trim()
+            .to_string();
+        return Err(format!("SFTP upload failed: {}", error));
+    }
+    Ok(format!("sftp://{}:{}{}", target, port, remote))
+}
+
+fn find_destination(id: &str) -> Option<UploadDestination> {
+    crate::load_settings()
+        .ok()?
+        .upload_destinations
+        .into_iter()
+        .find(|destination| destination.id == id)
+}
+
+/// Upload the output of a successful job to its preset's destination, if it has one
+/// (called by run_ffmpeg_render; returns immediately)
+pub fn upload_output(
+    window: &tauri::Window,
+    job: &crate::RenderJob,
+    result: &Result<crate::RenderResult, String>,
+) {
+    let Some(id) = job
+        .upload_destination
+        .as_deref()
+        .filter(|id| !id.is_empty())
+    else {
+        return;
+    };
+    if !matches!(result, Ok(result) if result.success) {
+        return;
+    }
+    let job_id = job.job_id.clone();
+    let Some(destination) = find_destination(id) else {
+        let _ = crate::write_render_log(
+            job_id,
+            format!("[UPLOAD] Destination {} no longer exists", id),
+        );
+        return;
+    };
+    let window = window.clone();
+    let output = std::path::PathBuf::from(&job.output_path);
+    std::thread::spawn(move || upload_with_retries(&window, &job_id, &destination, &output));
+}
+
+fn upload_with_retries(
+    window: &tauri::Window,
+    job_id: &str,
+    destination: &UploadDestination,
+    file: &Path,
+) {
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let total = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+    let _ = crate::write_render_log(
+        job_id.to_string(),
+        format!(
+            "[UPLOAD] {} ({} bytes) -> {} ({:?})",
+            file.display(),
+            total,
+            destination.name,
+            destination.kind
+        ),
+    );
+
+    let mut delay = RETRY_DELAY;
+    let mut outcome = Err(String::new());
+    for attempt in 1..=MAX_ATTEMPTS {
+        let report = {
+            let window = window.clone();
+            let job_id = job_id.to_string();
+            let destination = destination.name.clone();
+            move |sent_bytes: u64| {
+                let _ = window.emit(
+                    "upload-progress",
+                    UploadProgress {
+                        job_id: job_id.clone(),
+                        destination: destination.clone(),
+                        sent_bytes,
+                        total_bytes: total,
+                        percent: if total > 0 {
+                            (sent_bytes as f64 / total as f64 * 100.0).min(100.0)
+                        } else {
+                            0.0
+                        },
+                        attempt,
+                    },
+                );
+            }
+        };
+        outcome = match destination.kind {
+            UploadKind::S3 => upload_s3(destination, file, &name, report),
+            UploadKind::Webdav => upload_webdav(destination, file, &name, report),
+            // The sftp client reports no progress in batch mode
+            UploadKind::Sftp => {
+                report(0);
+                let uploaded = upload_sftp(destination, file, &name);
+                if uploaded.is_ok() {
+                    report(total);
+                }
+                uploaded
+            }
+        };
+        match &outcome {
+            Ok(_) => break,
+            Err(e) => {
+                let _ = crate::write_render_log(
+                    job_id.to_string(),
+                    format!(
+                        "[UPLOAD] Attempt {}/{} failed: {}",
+                        attempt, MAX_ATTEMPTS, e
+                    ),
+                );
+                if attempt < MAX_ATTEMPTS {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+            }
+        }
+    }
+
+    let (event, result) = match outcome {
+        Ok(remote) => {
+            let _ = crate::write_render_log(
+                job_id.to_string(),
+                format!("[UPLOAD] Uploaded to {}", remote),
+            );
+            (
+                "upload-complete",
+                UploadResult {
+                    job_id: job_id.to_string(),
+                    destination: destination.name.clone(),
+                    remote: Some(remote),
+                    error: None,
+                },
+            )
+        }
+        Err(error) => (
+            "upload-error",
+            UploadResult {
+                job_id: job_id.to_string(),
+                destination: destination.name.clone(),
+                remote: None,
+                error: Some(error),
+            },
+        ),
+    };
+    let _ = window.emit(event, &result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(
+            encode_path("/bucket/renders/my clip (1).mp4"),
+            "/bucket/renders/my%20clip%20%281%29.mp4"
+        );
+        assert_eq!(encode_path("/b/ä.mp4"), "/b/%C3%A4.mp4");
+        assert_eq!(join_remote("/renders/", "a.mp4"), "renders/a.mp4");
+        assert_eq!(join_remote("", "a.mp4"), "a.mp4");
+        assert_eq!(
+            sftp_quote(r#"C:\Videos\say "hi".mp4"#),
+            r#""C:\\Videos\\say \"hi\".mp4""#
+        );
+    }
+}
@@ -0,0 +1,296 @@
+// Download-then-compress
+// enqueue_url downloads a direct video URL into a temp folder and hands the file to the
+// frontend queue like a file opened from the command line (`files-received`), so it
+// goes through the normal pipeline with the chosen preset. A dropped connection is
+// resumed from the `.part` file with a Range request (same attempts and backoff as
+// update downloads), also when the same URL is queued again later. The downloaded file
+// is deleted once its render succeeds; failed renders keep it for a re-run.
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+const RETRY_MAX_DELAY_SECS: u64 = 30;
+/// Least time between two `url-download-progress` events of a download
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+lazy_static! {
+    /// Cancel flags of the running downloads by id
+    static ref DOWNLOADS: Mutex<HashMap<String, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+    /// Downloaded files waiting for their render
+    static ref DOWNLOADED: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+/// Payload of the `url-download-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct UrlDownloadProgress {
+    pub id: String,
+    pub url: String,
+    pub file_name: String,
+    pub downloaded: u64,
+    /// 0 when the server does not send a length
+    pub total: u64,
+}
+
+/// Payload of the `url-download-complete` and `url-download-error` events
+#[derive(Debug, Clone, Serialize)]
+pub struct UrlDownloadResult {
+    pub id: String,
+    pub url: String,
+    /// The downloaded file, queued with `files-received`
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+fn downloads_dir() -> PathBuf {
+    std::env::temp_dir().join("szhimatar-downloads")
+}
+
+/// Stable short key of a URL, so a re-queued URL finds its partial download
+fn url_key(url: &str) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Local file name for a URL: its last path segment without characters file systems
+/// reject, "video.mp4" when it has none
+fn file_name_for(url: &reqwest::Url) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        // '+' is literal in a path, not a form-encoded space
+        .and_then(|segment| crate::url_protocol::percent_decode(&segment.replace('+', "%2B")))
+        .unwrap_or_default();
+    let name: String = segment
+        .chars()
+        .map(|c| {
+            if c.is_control() || "<>:\"/\\|?*".contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = name.trim().trim_matches('.');
+    if name.is_empty() {
+        "video.mp4".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Folder the render goes to when the caller names none (next to the download would
+/// be the temp folder): the output folder from the settings, else Downloads
+fn default_output_dir() -> Option<String> {
+    let settings = crate::load_settings().unwrap_or_default();
+    if !settings.output_directory.trim().is_empty() {
+        return Some(settings.output_directory);
+    }
+    dirs::download_dir()
+        .or_else(dirs::video_dir)
+        .or_else(dirs::home_dir)
+        .map(|dir| dir.to_string_lossy().to_string())
+}
+
+/// Download with retries into `target`; the partial file is `<target>.part`
+fn download(
+    app: &tauri::AppHandle,
+    id: &str,
+    url: &str,
+    target: &Path,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let client = crate::network::client_builder()?
+        .connect_timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let part_path = target.with_file_name(format!(
+        "{}.part",
+        target.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let file_name = target
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let cancelled = || cancel.load(Ordering::Relaxed);
+
+    let mut attempt = 1;
+    loop {
+        let mut last_report: Option<Instant> = None;
+        let result =
+            crate::download_attempt(&client, url, &part_path, cancelled, |downloaded, total| {
+                let finished = total > 0 && downloaded >= total;
+                if finished || last_report.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+                    last_report = Some(Instant::now());
+                    let _ = app.emit_all(
+                        "url-download-progress",
+                        UrlDownloadProgress {
+                            id: id.to_string(),
+                            url: url.to_string(),
+                            file_name: file_name.clone(),
+                            downloaded,
+                            total,
+                        },
+                    );
+                }
+            });
+        match result {
+            Ok(()) => break,
+            Err(crate::DownloadError::Fatal(e)) => return Err(e),
+            Err(crate::DownloadError::Retry(e)) if attempt >= DOWNLOAD_ATTEMPTS => return Err(e),
+            Err(crate::DownloadError::Retry(e)) => {
+                let delay = (1u64 << (attempt - 1)).min(RETRY_MAX_DELAY_SECS);
+                let _ = crate::write_log(format!(
+                    "[URL] Download attempt {} of {} failed ({}), retrying in {}s",
+                    attempt, url, e, delay
+                ));
+                // Sleep in slices so a cancel is not held up by the backoff
+                let resume_at = Instant::now() + Duration::from_secs(delay);
+                while Instant::now() < resume_at {
+                    if cancelled() {
+                        return Err("Download cancelled".to_string());
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                attempt += 1;
+            }
+        }
+    }
+
+    std::fs::rename(&part_path, target).map_err(|e| format!("Failed to finish download: {}", e))
+}
+
+/// Download a direct video URL, then queue it with `preset` (which also starts the
+/// queue, as on the command line). Returns the download id used by the events and
+/// cancel_url_download; the download runs in the background.
+#[tauri::command]
+pub fn enqueue_url(
+    app: tauri::AppHandle,
+    url: String,
+    preset: Option<String>,
+    output_dir: Option<String>,
+) -> Result<String, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err("Only http:// and https:// URLs can be downloaded".to_string());
+    }
+    let url = parsed.to_string();
+    let folder = downloads_dir().join(url_key(&url));
+    std::fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create the download folder: {}", e))?;
+    let target = folder.join(file_name_for(&parsed));
+
+    let id = format!("url-{}", chrono::Local::now().timestamp_millis());
+    let cancel = Arc::new(AtomicBool::new(false));
+    DOWNLOADS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(id.clone(), cancel.clone());
+    let _ = crate::write_log(format!("[URL] Downloading {} to {}", url, target.display()));
+
+    let download_id = id.clone();
+    std::thread::spawn(move || {
+        let id = download_id;
+        let result = download(&app, &id, &url, &target, &cancel);
+        if let Ok(mut downloads) = DOWNLOADS.lock() {
+            downloads.remove(&id);
+        }
+        let path = target.to_string_lossy().to_string();
+        match result {
+            Ok(()) => {
+                let _ = crate::write_log(format!("[URL] Downloaded {}", path));
+                if let Ok(mut downloaded) = DOWNLOADED.lock() {
+                    downloaded.insert(target.clone());
+                }
+                let _ = app.emit_all(
+                    "url-download-complete",
+                    UrlDownloadResult {
+                        id,
+                        url,
+                        path: Some(path.clone()),
+                        error: None,
+                    },
+                );
+                let launch = crate::cli::CliLaunch {
+                    files: vec![path],
+                    preset: preset.filter(|p| !p.is_empty()),
+                    output_dir: output_dir
+                        .filter(|d| !d.trim().is_empty())
+                        .or_else(default_output_dir),
+                    ..Default::default()
+                };
+                let _ = app.emit_all("files-received", &launch);
+            }
+            Err(error) => {
+                let _ = crate::write_log(format!("[URL] Download of {} failed: {}", url, error));
+                let _ = app.emit_all(
+                    "url-download-error",
+                    UrlDownloadResult {
+                        id,
+                        url,
+                        path: None,
+                        error: Some(error),
+                    },
+                );
+            }
+        }
+    });
+    Ok(id)
+}
+
+/// Stop a running download; its partial file is kept so queueing the URL again resumes it
+#[tauri::command]
+pub fn cancel_url_download(id: String) -> bool {
+    DOWNLOADS
+        .lock()
+        .ok()
+        .and_then(|downloads| downloads.get(&id).cloned())
+        .map(|cancel| cancel.store(true, Ordering::Relaxed))
+        .is_some()
+}
+
+/// Called by run_ffmpeg_render: delete a downloaded input once its render succeeded
+pub fn render_finished(job: &crate::RenderJob, result: &Result<crate::RenderResult, String>) {
+    if !matches!(result, Ok(result) if result.success) {
+        return;
+    }
+    let input = PathBuf::from(&job.input_path);
+    let Ok(mut downloaded) = DOWNLOADED.lock() else {
+        return;
+    };
+    if downloaded.remove(&input) {
+        let _ = std::fs::remove_file(&input);
+        if let Some(folder) = input.parent() {
+            // Only removed when empty
+            let _ = std::fs::remove_dir(folder);
+        }
+        let _ = crate::write_log(format!("[URL] Removed downloaded {}", input.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_for() {
+        let name = |url: &str| file_name_for(&reqwest::Url::parse(url).unwrap());
+        assert_eq!(
+            name("https://cdn.example.com/media/My%20Clip.mp4?sig=abc"),
+            "My Clip.mp4"
+        );
+        assert_eq!(name("https://example.com/a/b%3Ac.mkv"), "b_c.mkv");
+        assert_eq!(name("https://example.com/"), "video.mp4");
+        assert_eq!(url_key("https://a/x.mp4"), url_key("https://a/x.mp4"));
+        assert_ne!(url_key("https://a/x.mp4"), url_key("https://a/y.mp4"));
+    }
+}
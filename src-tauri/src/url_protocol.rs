@@ -0,0 +1,275 @@
+// szhimatar:// deep links
+//   szhimatar://compress?path=<file or folder>[&path=...][&preset=<name>][&output_dir=<dir>]
+// The OS launches the app with the URL as its only argument (and single_instance hands
+// it to the running window); cli::parse_args turns it into the equivalent options.
+// Links can come from any web page, so they only queue files and never start a render.
+//
+// macOS delivers URLs as an Apple Event instead of an argument, which Tauri 1 does not
+// forward to the app, so the protocol is only registered on Windows and Linux.
+
+use std::path::Path;
+
+pub const SCHEME: &str = "szhimatar";
+
+/// Decode %XX escapes and '+' (form encoding) in a query value
+pub(crate) fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            b => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn is_url(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 1)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+}
+
+/// Command line arguments equivalent to a szhimatar:// URL
+fn url_to_args(url: &str) -> Result<Vec<String>, String> {
+    let rest = url[SCHEME.len() + 1..].trim_start_matches('/');
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if !action
+        .trim_end_matches('/')
+        .eq_ignore_ascii_case("compress")
+    {
+        return Err(format!("Unknown action '{}'", action));
+    }
+
+    // Relative paths would resolve against whatever folder the browser runs in
+    let absolute = |value: String| {
+        if Path::new(&value).is_absolute() {
+            Ok(value)
+        } else {
+            Err(format!("Path must be absolute: {}", value))
+        }
+    };
+
+    let mut args = vec!["--queue-only".to_string()];
+    let mut has_path = false;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value).ok_or_else(|| format!("Malformed value of '{}'", key))?;
+        match key {
+            "path" => {
+                args.push(absolute(value)?);
+                has_path = true;
+            }
+            "preset" => args.push(format!("--preset={}", value)),
+            "output_dir" => args.push(format!("--output-dir={}", absolute(value)?)),
+            // Unknown keys are ignored so newer links still work in older versions
+            _ => {}
+        }
+    }
+
+    if has_path {
+        Ok(args)
+    } else {
+        Err("No path in URL".to_string())
+    }
+}
+
+/// Replace szhimatar:// URLs among launch arguments with the options they stand for.
+/// A URL that cannot be used is kept as is and ends up in CliLaunch::ignored.
+pub fn expand_urls(args: Vec<String>) -> Vec<String> {
+    args.into_iter()
+        .flat_map(|arg| {
+            if !is_url(&arg) {
+                return vec![arg];
+            }
+            match url_to_args(&arg) {
+                Ok(expanded) => expanded,
+                Err(e) => {
+                    let _ = crate::write_log(format!("[URL] Ignoring {}: {}", arg, e));
+                    vec![arg]
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+const CLASSES_KEY: &str = r"Software\Classes\szhimatar";
+
+/// Per-user registration, no administrator rights needed
+#[cfg(windows)]
+fn register(exe: &str) -> Result<(), String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let registry_error = |e: std::io::Error| format!("Registry error: {}", e);
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(CLASSES_KEY)
+        .map_err(registry_error)?;
+    key.set_value("", &"URL:Szhimatar")
+        .map_err(registry_error)?;
+    key.set_value("URL Protocol", &"").map_err(registry_error)?;
+
+    let (icon, _) = key.create_subkey("DefaultIcon").map_err(registry_error)?;
+    icon.set_value("", &format!("{},0", exe))
+        .map_err(registry_error)?;
+    let (command, _) = key
+        .create_subkey(r"shell\open\command")
+        .map_err(registry_error)?;
+    command
+        .set_value("", &format!(r#""{}" "%1""#, exe))
+        .map_err(registry_error)
+}
+
+#[cfg(windows)]
+fn unregister() -> Result<(), String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    match winreg::RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(CLASSES_KEY) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Registry error: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/// Command the protocol currently launches
+#[cfg(windows)]
+fn registered_command() -> Option<String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    winreg::RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(format!(r"{}\shell\open\command", CLASSES_KEY))
+        .ok()?
+        .get_value("")
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_file() -> Result<std::path::PathBuf, String> {
+    Ok(crate::file_manager::data_home()?.join("applications/szhimatar-url.desktop"))
+}
+
+/// Hidden .desktop entry handling x-scheme-handler/szhimatar, made the default handler
+#[cfg(target_os = "linux")]
+fn register(exe: &str) -> Result<(), String> {
+    let path = desktop_file()?;
+    let content = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Szhimatar\n\
+         Exec={} %u\n\
+         NoDisplay=true\n\
+         Terminal=false\n\
+         MimeType=x-scheme-handler/{};\n",
+        crate::file_manager::desktop_exec_quote(exe),
+        SCHEME
+    );
+    crate::file_manager::write_file(&path, &content, false)?;
+
+    let output = crate::proc::run_captured(
+        crate::proc::command("xdg-mime").args([
+            "default",
+            "szhimatar-url.desktop",
+            &format!("x-scheme-handler/{}", SCHEME),
+        ]),
+        &crate::proc::RunOptions::with_timeout(15).label("xdg-mime"),
+    )?;
+    if output.success {
+        Ok(())
+    } else {
+        Err(format!("xdg-mime failed: {}", output.stderr.trim()))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), String> {
+    // mimeapps.list keeps pointing at the missing file, which handlers simply skip
+    crate::file_manager::remove_path(&desktop_file()?)
+}
+
+#[cfg(target_os = "linux")]
+fn registered_command() -> Option<String> {
+    std::fs::read_to_string(desktop_file().ok()?)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Exec=").map(|exec| exec.to_string()))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn register(_exe: &str) -> Result<(), String> {
+    Err("szhimatar:// links are not supported on this platform".to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn unregister() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn registered_command() -> Option<String> {
+    None
+}
+
+/// Make szhimatar:// links open this executable
+#[tauri::command]
+pub fn register_url_protocol() -> Result<(), String> {
+    let exe = crate::file_manager::exe_path()?;
+    register(&exe)?;
+    let _ = crate::write_log(format!("[URL] Registered {}:// for {}", SCHEME, exe));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unregister_url_protocol() -> Result<(), String> {
+    unregister()
+}
+
+/// Whether szhimatar:// links currently open this executable
+#[tauri::command]
+pub fn is_url_protocol_registered() -> bool {
+    let exe = crate::file_manager::exe_path().unwrap_or_default();
+    registered_command().is_some_and(|command| !exe.is_empty() && command.contains(&exe))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_to_args() {
+        #[cfg(windows)]
+        let (path, encoded) = (r"C:\Videos\my clip.mp4", "C%3A%5CVideos%5Cmy+clip.mp4");
+        #[cfg(not(windows))]
+        let (path, encoded) = ("/videos/my clip.mp4", "%2Fvideos%2Fmy%20clip.mp4");
+
+        let url = format!(
+            "SZHIMATAR://compress/?path={}&preset=Web%20720p&x=1",
+            encoded
+        );
+        assert!(is_url(&url));
+        assert_eq!(
+            url_to_args(&url).unwrap(),
+            vec!["--queue-only", path, "--preset=Web 720p"]
+        );
+
+        assert!(url_to_args("szhimatar://compress?path=clip.mp4").is_err());
+        assert!(url_to_args("szhimatar://delete?path=%2Fa.mp4").is_err());
+        assert!(url_to_args("szhimatar://compress?preset=x").is_err());
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(
+            expand_urls(vec!["szhimatar://nope".to_string()]),
+            vec!["szhimatar://nope"]
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// Output verification
+// Checks a finished render by comparing its probed duration against the expected
+// one and, optionally, by decoding it completely (`-f null -`).
+
+use crate::units;
+use serde::{Deserialize, Serialize};
+
+/// Decode errors kept in a report
+const MAX_DECODE_ERRORS: usize = 20;
+
+/// Post-render verification level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyMode {
+    #[default]
+    Off,
+    /// Compare probed output duration with the expected duration
+    Duration,
+    /// Duration check plus a full decode of the output
+    Decode,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub ok: bool,
+    pub output_duration: Option<units::Duration>,
+    pub expected_duration: Option<units::Duration>,
+    pub decode_errors: Vec<String>,
+    /// Human-readable problems; empty when `ok`
+    pub warnings: Vec<String>,
+}
+
+/// Allowed difference between output and expected duration: 2%, at least one second
+/// (container padding, audio priming and frame rounding all shift the end slightly)
+pub fn duration_mismatch(output: units::Duration, expected: units::Duration) -> Option<String> {
+    let (output, expected) = (output.as_secs_f64(), expected.as_secs_f64());
+    if expected <= 0.0 {
+        return None;
+    }
+    let tolerance = (expected * 0.02).max(1.0);
+    if (expected - output).abs() <= tolerance {
+        return None;
+    }
+    Some(if output < expected {
+        format!(
+            "Output looks truncated: {:.1}s of {:.1}s expected",
+            output, expected
+        )
+    } else {
+        format!(
+            "Output is longer than expected: {:.1}s vs {:.1}s",
+            output, expected
+        )
+    })
+}
+
+/// Container duration of a media file
+pub fn probe_duration(ffprobe_path: &str, path: &str) -> Option<units::Duration> {
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffprobe_path).args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "csv=p=0",
+            path,
+        ]),
+        &crate::proc::RunOptions::with_timeout(30).label("ffprobe duration"),
+    )
+    .ok()
+    .filter(|o| o.success)?;
+    units::Duration::parse(output.stdout.trim())
+}
+
+/// Decode the whole file and collect FFmpeg's error lines
+fn decode_errors(
+    ffmpeg_path: &str,
+    path: &str,
+    duration: Option<units::Duration>,
+) -> Result<Vec<String>, String> {
+    // Decoding runs many times faster than realtime; the floor covers slow disks
+    let timeout = 300 + duration.map_or(0, |d| d.as_secs_f64() as u64);
+    let output = crate::proc::run_captured(
+        crate::proc::command(ffmpeg_path).args([
+            "-hide_banner",
+            "-v",
+            "error",
+            "-i",
+            path,
+            "-f",
+            "null",
+            "-",
+        ]),
+        &crate::proc::RunOptions::with_timeout(timeout).label("verify decode"),
+    )?;
+    if output.timed_out {
+        return Err("Decode check timed out".to_string());
+    }
+
+    let mut errors: Vec<String> = output
+        .stderr
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(MAX_DECODE_ERRORS)
+        .map(str::to_string)
+        .collect();
+    if !output.success && errors.is_empty() {
+        errors.push(format!("FFmpeg exited with code {:?}", output.code));
+    }
+    Ok(errors)
+}
+
+/// Verify an output file; `expected` is the duration the render should have produced
+pub fn verify(
+    output_path: &str,
+    expected: Option<units::Duration>,
+    decode: bool,
+) -> Result<VerifyReport, String> {
+    let config = crate::load_ffmpeg_config();
+    if config.ffprobe_path.is_empty() {
+        return Err("FFprobe path not configured".to_string());
+    }
+    if !std::path::Path::new(output_path).is_file() {
+        return Err(format!("Output file not found: {}", output_path));
+    }
+
+    let mut report = VerifyReport {
+        output_duration: probe_duration(&config.ffprobe_path, output_path),
+        expected_duration: expected.filter(|d| !d.is_zero()),
+        ..Default::default()
+    };
+
+    match (report.output_duration, report.expected_duration) {
+        (None, _) => report
+            .warnings
+            .push("Could not read the output duration".to_string()),
+        (Some(output), Some(expected)) => {
+            report.warnings.extend(duration_mismatch(output, expected));
+        }
+        _ => {}
+    }
+
+    if decode {
+        match decode_errors(&config.ffmpeg_path, output_path, report.output_duration) {
+            Ok(errors) => {
+                if !errors.is_empty() {
+                    report
+                        .warnings
+                        .push(format!("{} decode error(s) in the output", errors.len()));
+                }
+                report.decode_errors = errors;
+            }
+            Err(e) => report.warnings.push(e),
+        }
+    }
+
+    report.ok = report.warnings.is_empty();
+    Ok(report)
+}
+
+/// Check a rendered file. The expected duration comes from `source_path` when given;
+/// `decode` also decodes the whole output (slower, catches corrupt frames).
+#[tauri::command]
+pub async fn verify_output(
+    output_path: String,
+    source_path: Option<String>,
+    decode: Option<bool>,
+) -> Result<VerifyReport, String> {
+    let config = crate::load_ffmpeg_config();
+    let expected = source_path
+        .as_deref()
+        .and_then(|source| probe_duration(&config.ffprobe_path, source));
+    verify(&output_path, expected, decode.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_mismatch_tolerance() {
+        let secs = units::Duration::from_secs_f64;
+        assert!(duration_mismatch(secs(599.2), secs(600.0)).is_none());
+        assert!(duration_mismatch(secs(10.8), secs(10.0)).is_none());
+        assert!(duration_mismatch(secs(300.0), secs(600.0))
+            .unwrap()
+            .contains("truncated"));
+        assert!(duration_mismatch(secs(5.0), units::Duration::ZERO).is_none());
+    }
+}
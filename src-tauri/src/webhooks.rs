@@ -0,0 +1,347 @@
+// Webhooks
+// POSTs a JSON payload to user-defined URLs when a job completes or fails and when the
+// queue finishes, for Discord/Slack/n8n integrations. The payload carries the job, its
+// result and stats, plus a one-line summary as `content` (Discord) and `text` (Slack)
+// so chat webhooks show something readable without a relay. Deliveries run on their
+// own thread and are retried with a growing delay; they never hold up the queue.
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Attempts per delivery, including the first
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, doubled for each further one
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Webhook {
+    pub url: String,
+    pub on_complete: bool,
+    pub on_fail: bool,
+    pub on_queue_finished: bool,
+}
+
+impl Default for Webhook {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            on_complete: true,
+            on_fail: true,
+            on_queue_finished: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    JobComplete,
+    JobFailed,
+    QueueFinished,
+    /// Sent by test_webhook
+    Test,
+}
+
+impl Webhook {
+    fn wants(&self, event: WebhookEvent) -> bool {
+        !self.url.trim().is_empty()
+            && match event {
+                WebhookEvent::JobComplete => self.on_complete,
+                WebhookEvent::JobFailed => self.on_fail,
+                WebhookEvent::QueueFinished => self.on_queue_finished,
+                WebhookEvent::Test => true,
+            }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStats {
+    pub elapsed_seconds: f64,
+    pub input_size_bytes: Option<u64>,
+    pub output_size_bytes: Option<u64>,
+    pub resources: Option<crate::resource_monitor::ResourceSummary>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobPayload {
+    pub job_id: String,
+    pub input: String,
+    pub output: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_kind: Option<crate::render_errors::RenderErrorKind>,
+    pub stats: JobStats,
+}
+
+/// Body of every webhook request
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub app: &'static str,
+    pub version: &'static str,
+    pub timestamp: String,
+    /// Summary line for Discord
+    pub content: String,
+    /// Same summary for Slack
+    pub text: String,
+    /// Set for job_complete / job_failed
+    pub job: Option<JobPayload>,
+    /// Jobs completed and failed since the queue last finished (queue_finished)
+    pub completed: Option<u32>,
+    pub failed: Option<u32>,
+}
+
+impl WebhookPayload {
+    fn new(event: WebhookEvent, summary: String) -> Self {
+        Self {
+            event,
+            app: "Szhimatar",
+            version: env!("CARGO_PKG_VERSION"),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            text: summary.clone(),
+            content: summary,
+            job: None,
+            completed: None,
+            failed: None,
+        }
+    }
+}
+
+/// Results since the queue last finished, for the queue_finished payload
+#[derive(Default)]
+struct QueueTally {
+    completed: u32,
+    failed: u32,
+}
+
+lazy_static! {
+    static ref TALLY: Mutex<QueueTally> = Mutex::new(QueueTally::default());
+}
+
+fn load_webhooks() -> Vec<Webhook> {
+    crate::load_settings()
+        .map(|settings| settings.webhooks)
+        .unwrap_or_default()
+}
+
+fn file_size(path: &str) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|m| m.len())
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    crate::network::client_builder()?
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// POST the payload once; returns the HTTP status
+fn post(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    payload: &WebhookPayload,
+) -> Result<u16, String> {
+    let response = client
+        .post(url.trim())
+        .json(payload)
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(format!("HTTP {}", status))
+    }
+}
+
+/// POST with retries; client errors other than 429 are not retried
+fn deliver(client: &reqwest::blocking::Client, url: &str, payload: &WebhookPayload) {
+    let mut delay = RETRY_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        match post(client, url, payload) {
+            Ok(_) => return,
+            Err(e) => {
+                let permanent = e.starts_with("HTTP 4") && !e.starts_with("HTTP 429");
+                let _ = crate::write_log(format!(
+                    "[WEBHOOK] {:?} to {} failed (attempt {}/{}): {}",
+                    payload.event, url, attempt, MAX_ATTEMPTS, e
+                ));
+                if permanent || attempt == MAX_ATTEMPTS {
+                    return;
+                }
+            }
+        }
+        std::thread::sleep(delay);
+        delay *= 2;
+    }
+}
+
+/// Send the payload to every webhook that subscribed to its event, off the caller's thread
+fn dispatch(payload: WebhookPayload) {
+    let urls: Vec<String> = load_webhooks()
+        .into_iter()
+        .filter(|webhook| webhook.wants(payload.event))
+        .map(|webhook| webhook.url)
+        .collect();
+    if urls.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        let client = match http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                let _ = crate::write_log(format!("[WEBHOOK] {}", e));
+                return;
+            }
+        };
+        for url in urls {
+            deliver(&client, &url, &payload);
+        }
+    });
+}
+
+/// Called by run_ffmpeg_render once a job finished (like history::record)
+pub fn job_finished(
+    job: &crate::RenderJob,
+    started_at: chrono::DateTime<chrono::Local>,
+    result: &Result<crate::RenderResult, String>,
+) {
+    let (success, error, error_kind, resources, warnings) = match result {
+        // Stopped by the user: not a result anyone waits for
+        Ok(result) if result.error.as_deref() == Some("stopped") => return,
+        Ok(result) => (
+            result.success,
+            result.error.clone(),
+            result.error_kind,
+            result.resources.clone(),
+            result.warnings.clone(),
+        ),
+        Err(e) => (false, Some(e.clone()), None, None, Vec::new()),
+    };
+    if let Ok(mut tally) = TALLY.lock() {
+        if success {
+            tally.completed += 1;
+        } else {
+            tally.failed += 1;
+        }
+    }
+
+    let (event, summary) = if success {
+        (
+            WebhookEvent::JobComplete,
+            format!("✅ Рендер завершён: {}", file_name(&job.input_path)),
+        )
+    } else {
+        let reason = error
+            .as_deref()
+            .and_then(|e| e.lines().next())
+            .unwrap_or_default();
+        (
+            WebhookEvent::JobFailed,
+            format!(
+                "❌ Ошибка рендера: {} ({})",
+                file_name(&job.input_path),
+                reason
+            ),
+        )
+    };
+    let mut payload = WebhookPayload::new(event, summary);
+    payload.job = Some(JobPayload {
+        job_id: job.job_id.clone(),
+        input: job.input_path.clone(),
+        output: job.output_path.clone(),
+        success,
+        error,
+        error_kind,
+        stats: JobStats {
+            elapsed_seconds: (chrono::Local::now() - started_at).num_milliseconds() as f64 / 1000.0,
+            input_size_bytes: file_size(&job.input_path),
+            output_size_bytes: if success {
+                file_size(&job.output_path)
+            } else {
+                None
+            },
+            resources,
+            warnings,
+        },
+    });
+    dispatch(payload);
+}
+
+/// The frontend's queue drained (see queue::notify_queue_finished)
+pub fn queue_finished() {
+    let tally = match TALLY.lock() {
+        Ok(mut tally) => std::mem::take(&mut *tally),
+        Err(_) => return,
+    };
+    let summary = if tally.failed == 0 {
+        format!("🏁 Очередь завершена. Готово: {}", tally.completed)
+    } else {
+        format!(
+            "🏁 Очередь завершена. Готово: {}, с ошибкой: {}",
+            tally.completed, tally.failed
+        )
+    };
+    let mut payload = WebhookPayload::new(WebhookEvent::QueueFinished, summary);
+    payload.completed = Some(tally.completed);
+    payload.failed = Some(tally.failed);
+    dispatch(payload);
+}
+
+/// Send a test payload to `url` once and return the HTTP status it answered with
+#[tauri::command]
+pub async fn test_webhook(url: String) -> Result<u16, String> {
+    if !url.trim().starts_with("http://") && !url.trim().starts_with("https://") {
+        return Err("The webhook URL must start with http:// or https://".to_string());
+    }
+    tokio::task::spawn_blocking(move || {
+        let payload = WebhookPayload::new(
+            WebhookEvent::Test,
+            "👋 Проверка вебхука Szhimatar".to_string(),
+        );
+        post(&http_client()?, &url, &payload)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wants() {
+        let webhook = Webhook {
+            url: "https://example.com/hook".to_string(),
+            on_fail: false,
+            ..Default::default()
+        };
+        assert!(webhook.wants(WebhookEvent::JobComplete));
+        assert!(!webhook.wants(WebhookEvent::JobFailed));
+        assert!(webhook.wants(WebhookEvent::Test));
+        assert!(!Webhook::default().wants(WebhookEvent::JobComplete));
+    }
+
+    #[test]
+    fn test_payload() {
+        let payload = WebhookPayload::new(WebhookEvent::QueueFinished, "done".to_string());
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], "queue_finished");
+        assert_eq!(json["content"], "done");
+        assert_eq!(json["text"], "done");
+        assert!(json["job"].is_null());
+    }
+}
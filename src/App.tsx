@@ -1,6 +1,7 @@
-import { useState, useEffect, useCallback } from 'react';
+import { useState, useEffect, useCallback, useRef } from 'react';
 import { AnimatePresence } from 'framer-motion';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
 import { ThemeProvider } from './contexts/ThemeContext';
 import { LanguageProvider } from './contexts/LanguageContext';
 import { SettingsProvider, useSettings } from './contexts/SettingsContext';
@@ -11,6 +12,7 @@ import AudioSettings from './pages/AudioSettings';
 import GeneralSettings from './pages/GeneralSettings';
 import MotionScreen from './components/MotionScreen';
 import CursorGlow from './components/CursorGlow';
+import FfmpegRepairNotice from './components/FfmpegRepairNotice';
 import {
   VideoSettings as VideoSettingsType,
   AudioSettings as AudioSettingsType,
@@ -21,6 +23,8 @@ import {
   DEFAULT_AUDIO_SETTINGS,
   DEFAULT_MAIN_SCREEN_SETTINGS,
   DEFAULT_WATERMARK_SETTINGS,
+  GpuCompatibility,
+  withPresetDefaults,
 } from './types';
 
 type Screen = 'main' | 'video' | 'audio' | 'general';
@@ -30,6 +34,15 @@ type DefaultPresetResponse = {
   content: string;
 };
 
+// Result of get_cli_launch / payload of files-received (see cli.rs)
+type CliLaunch = {
+  files: string[];
+  preset: string | null;
+  output_dir: string | null;
+  queue_only: boolean;
+  unattended: boolean;
+};
+
 function AppContent() {
   const { performanceMode, screenAnimation } = useSettings();
   const [currentScreen, setCurrentScreen] = useState<Screen>('main');
@@ -39,6 +52,9 @@ function AppContent() {
   const [watermarkSettings, setWatermarkSettings] = useState<WatermarkSettingsType>(DEFAULT_WATERMARK_SETTINGS);
   const [selectedPresetName, setSelectedPresetName] = useState<string>('');
   const [cliFiles, setCliFiles] = useState<string[]>([]);
+  const [cliAutoStart, setCliAutoStart] = useState(false);
+  const [cliUnattended, setCliUnattended] = useState(false);
+  const cliPresetApplied = useRef(false);
 
   // Mouse tracking for glassmorphism light effects
   const handleMouseMove = useCallback((e: React.MouseEvent<HTMLDivElement>) => {
@@ -70,8 +86,8 @@ function AppContent() {
         } else {
           // First run: check GPU compatibility
           try {
-            const available = await invoke<boolean>('check_gpu_compatibility');
-            RenderService.setGpuAvailability(!!available);
+            const gpu = await invoke<GpuCompatibility>('check_gpu_compatibility');
+            RenderService.setGpuAvailability(gpu.available);
           } catch (e) {
             console.warn('GPU check failed:', e);
             RenderService.setGpuAvailability(false);
@@ -95,7 +111,8 @@ function AppContent() {
     const applyDefaultPreset = async () => {
       try {
         const defaultPreset = await invoke<DefaultPresetResponse | null>('load_default_preset');
-        if (defaultPreset) {
+        // A --preset from the command line wins over the default preset
+        if (defaultPreset && !cliPresetApplied.current) {
           const p = JSON.parse(defaultPreset.content) as AppPreset;
           setVideoSettings(p.video);
           setAudioSettings(p.audio);
@@ -113,22 +130,68 @@ function AppContent() {
     applyDefaultPreset();
   }, []);
 
+  // Apply --preset / --output-dir, then queue the files (and start unless --queue-only)
+  const applyCliLaunch = useCallback(async (launch: CliLaunch) => {
+    if (launch.files.length === 0) return;
+
+    let presetMainScreen: MainScreenSettings | null = null;
+    if (launch.preset) {
+      try {
+        const content = await invoke<string>('load_preset', { name: launch.preset });
+        const p = withPresetDefaults(JSON.parse(content) as AppPreset);
+        cliPresetApplied.current = true;
+        setVideoSettings(p.video);
+        setAudioSettings(p.audio);
+        presetMainScreen = p.mainScreen;
+        if (p.watermark) {
+          setWatermarkSettings(p.watermark);
+        }
+        setSelectedPresetName(launch.preset);
+      } catch (error) {
+        console.error(`[App] CLI preset "${launch.preset}" could not be applied:`, error);
+      }
+    }
+
+    const outputDir = launch.output_dir;
+    if (outputDir) {
+      setMainScreenSettings(prev => ({
+        ...(presetMainScreen ?? prev),
+        saveInSourceDirectory: false,
+        customOutputPath: outputDir,
+      }));
+    } else if (presetMainScreen) {
+      setMainScreenSettings(presetMainScreen);
+    }
+
+    console.log('[App] CLI files received:', launch.files);
+    setCliAutoStart(cliPresetApplied.current && !launch.queue_only);
+    setCliUnattended(launch.unattended);
+    setCliFiles(launch.files);
+  }, []);
+
   // Check for CLI files passed from context menu
   useEffect(() => {
     const loadCliFiles = async () => {
       try {
-        const files = await invoke<string[]>('get_cli_files');
-        if (files && files.length > 0) {
-          console.log('[App] CLI files received:', files);
-          setCliFiles(files);
-        }
+        await applyCliLaunch(await invoke<CliLaunch>('get_cli_launch'));
       } catch (error) {
         console.error('Failed to get CLI files:', error);
       }
     };
 
     loadCliFiles();
-  }, []);
+  }, [applyCliLaunch]);
+
+  // Files from later launches (context menu clicks while the app is already open)
+  useEffect(() => {
+    const unlisten = listen<CliLaunch>('files-received', (event) => {
+      void applyCliLaunch(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [applyCliLaunch]);
 
   const navigateTo = (screen: Screen) => setCurrentScreen(screen);
   const goBack = () => setCurrentScreen('main');
@@ -195,6 +258,8 @@ function AppContent() {
             selectedPresetName={selectedPresetName}
             setSelectedPresetName={setSelectedPresetName}
             cliFiles={cliFiles}
+            cliAutoStart={cliAutoStart}
+            cliUnattended={cliUnattended}
             onCliFilesProcessed={() => setCliFiles([])}
           />
         );
@@ -216,6 +281,7 @@ function AppContent() {
           >
             {performanceMode ? currentScreenNode : <AnimatePresence mode="wait" initial={false}>{currentScreenNode}</AnimatePresence>}
       </div>
+      <FfmpegRepairNotice />
     </>
   );
 }
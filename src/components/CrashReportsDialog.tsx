@@ -0,0 +1,122 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { AlertTriangle, Trash2, X } from 'lucide-react';
+import type { CrashReport } from '../types';
+
+/**
+ * CrashReportsDialog Component
+ *
+ * On launch, lists crash reports (see crash.rs) the user has not seen yet when the
+ * showCrashReports setting is on. Closing the dialog marks them as seen.
+ */
+
+export const CrashReportsDialog: React.FC = () => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [reports, setReports] = useState<CrashReport[]>([]);
+  const [expandedId, setExpandedId] = useState<string | null>(null);
+
+  useEffect(() => {
+    const loadReports = async () => {
+      try {
+        const settings = await invoke<any>('load_settings');
+        if (!settings.showCrashReports) return;
+        const all = await invoke<CrashReport[]>('list_crash_reports');
+        setReports(all.filter((report) => !report.seen));
+      } catch (error) {
+        console.error('Failed to load crash reports:', error);
+      }
+    };
+    loadReports();
+  }, []);
+
+  const handleClose = async () => {
+    setReports([]);
+    try {
+      await invoke('mark_crash_reports_seen');
+    } catch (error) {
+      console.error('Failed to mark crash reports as seen:', error);
+    }
+  };
+
+  const handleDelete = async (id: string) => {
+    try {
+      await invoke('delete_crash_report', { id });
+      setReports((current) => current.filter((report) => report.id !== id));
+    } catch (error) {
+      console.error('Failed to delete crash report:', error);
+    }
+  };
+
+  return (
+    <AnimatePresence>
+      {reports.length > 0 && (
+        <motion.div
+          className="stats-overlay"
+          onClick={handleClose}
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+          transition={{ duration: 0.2 }}
+        >
+          <motion.div
+            className="stats-modal"
+            onClick={(e) => e.stopPropagation()}
+            initial={{ opacity: 0, y: 20, scale: 0.98 }}
+            animate={{ opacity: 1, y: 0, scale: 1 }}
+            exit={{ opacity: 0, y: 10, scale: 0.98 }}
+            transition={{ duration: 0.25, ease: 'easeOut' }}
+            style={{ color: theme.colors.text, width: 'min(760px, 92vw)' }}
+          >
+            <div className="stats-modal-header">
+              <span className="stats-modal-title" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+                <AlertTriangle size={18} strokeWidth={2} color={theme.colors.error} /> {t('crashReports.title') || 'Crash reports'}
+              </span>
+              <button className="stats-modal-close" onClick={handleClose} style={{ color: theme.colors.text }}>
+                <X size={18} strokeWidth={1.5} />
+              </button>
+            </div>
+
+            <div style={{ padding: 16, overflowY: 'auto', display: 'flex', flexDirection: 'column', gap: 10 }}>
+              <div style={{ fontSize: 13, color: theme.colors.textSecondary }}>
+                {t('crashReports.description')}
+              </div>
+              {reports.map((report) => (
+                <div key={report.id} style={{ padding: 12, borderRadius: 8, border: '1px solid rgba(255, 255, 255, 0.1)', background: 'rgba(var(--theme-bg-rgb), 0.2)' }}>
+                  <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', gap: 8 }}>
+                    <span style={{ fontSize: 12, color: theme.colors.textSecondary }}>
+                      {new Date(report.timestamp).toLocaleString()} · {report.kind === 'ffmpeg' ? 'FFmpeg' : `Szhimatar ${report.app_version}`}
+                    </span>
+                    <button
+                      onClick={() => handleDelete(report.id)}
+                      title={t('crashReports.delete') || 'Delete'}
+                      style={{ background: 'transparent', border: 'none', color: theme.colors.error, cursor: 'pointer' }}
+                    >
+                      <Trash2 size={16} strokeWidth={1.5} />
+                    </button>
+                  </div>
+                  <div
+                    onClick={() => setExpandedId(expandedId === report.id ? null : report.id)}
+                    style={{ marginTop: 6, fontSize: 13, cursor: 'pointer', wordBreak: 'break-word' }}
+                  >
+                    {report.message}
+                  </div>
+                  {expandedId === report.id && (
+                    <pre style={{ marginTop: 8, maxHeight: 240, overflow: 'auto', fontSize: 11, whiteSpace: 'pre-wrap', userSelect: 'text' }}>
+                      {[report.backtrace, report.log_tail].filter(Boolean).join('\n\n')}
+                    </pre>
+                  )}
+                </div>
+              ))}
+            </div>
+          </motion.div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+};
+
+export default CrashReportsDialog;
@@ -0,0 +1,185 @@
+import React, { useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open, save } from '@tauri-apps/api/dialog';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { Activity, Archive, Download, Upload } from 'lucide-react';
+import type { SystemInfo } from '../types';
+
+/**
+ * DiagnosticsPanel Component
+ *
+ * Shows OS, hardware, disk space and FFmpeg details (get_system_info) and exports
+ * them together with settings and recent logs as a support bundle zip. Also backs up
+ * settings, presets, FFmpeg config and statistics to a zip and restores them, e.g. on
+ * a new PC.
+ */
+
+const formatBytes = (bytes: number | null | undefined): string => {
+  if (bytes === null || bytes === undefined) return '—';
+  const gib = bytes / 1024 ** 3;
+  return gib >= 1 ? `${gib.toFixed(1)} GB` : `${Math.round(bytes / 1024 ** 2)} MB`;
+};
+
+export const DiagnosticsPanel: React.FC = () => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [info, setInfo] = useState<SystemInfo | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [exporting, setExporting] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const handleCollect = async () => {
+    setLoading(true);
+    setMessage(null);
+    try {
+      setInfo(await invoke<SystemInfo>('get_system_info'));
+    } catch (error) {
+      console.error('Failed to collect system info:', error);
+      setMessage(String(error));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleExportBundle = async () => {
+    const date = new Date().toISOString().slice(0, 10);
+    const outputZip = await save({
+      defaultPath: `szhimatar-support-${date}.zip`,
+      filters: [{ name: 'ZIP', extensions: ['zip'] }],
+    });
+    if (!outputZip) return;
+
+    setExporting(true);
+    setMessage(null);
+    try {
+      await invoke('export_support_bundle', { outputZip });
+      setMessage(t('diagnostics.bundleSaved') || 'Support bundle saved');
+    } catch (error) {
+      console.error('Failed to export support bundle:', error);
+      setMessage(String(error));
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  const handleBackupData = async () => {
+    const date = new Date().toISOString().slice(0, 10);
+    const outputZip = await save({
+      defaultPath: `szhimatar-backup-${date}.zip`,
+      filters: [{ name: 'ZIP', extensions: ['zip'] }],
+    });
+    if (!outputZip) return;
+
+    setExporting(true);
+    setMessage(null);
+    try {
+      await invoke('backup_app_data', { outputZip });
+      setMessage(t('diagnostics.dataBackedUp'));
+    } catch (error) {
+      console.error('Failed to back up app data:', error);
+      setMessage(String(error));
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  const handleRestoreData = async () => {
+    const zipPath = await open({
+      multiple: false,
+      filters: [{ name: 'ZIP', extensions: ['zip'] }],
+    });
+    if (!zipPath || typeof zipPath !== 'string') return;
+    if (!confirm(t('diagnostics.restoreDataPrompt'))) return;
+
+    setExporting(true);
+    setMessage(null);
+    try {
+      const manifest = await invoke<{ files: unknown[] }>('restore_app_data', { zipPath });
+      setMessage(t('diagnostics.dataRestored').replace('{count}', String(manifest.files.length)));
+    } catch (error) {
+      console.error('Failed to restore app data:', error);
+      setMessage(String(error));
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  const buttonStyle: React.CSSProperties = {
+    background: theme.colors.primary,
+    color: '#fff',
+    padding: '10px 14px',
+    border: 'none',
+    borderRadius: 6,
+    cursor: 'pointer',
+    display: 'flex',
+    alignItems: 'center',
+    justifyContent: 'center',
+    gap: '6px',
+    flex: 1,
+  };
+
+  const rows: [string, string][] = info ? [
+    [t('diagnostics.os') || 'OS', `${info.os_version} (${info.arch})${info.kernel_version ? `, ${info.kernel_version}` : ''}`],
+    [t('diagnostics.memory') || 'RAM', `${formatBytes(info.available_memory_bytes)} / ${formatBytes(info.total_memory_bytes)}`],
+    [t('diagnostics.cpu') || 'CPU', `${info.cpu_model || '—'} (${info.physical_cores ?? '?'}C/${info.logical_cores}T)`],
+    ...info.gpus.map((gpu): [string, string] => [
+      t('diagnostics.gpu') || 'GPU',
+      gpu.vram_bytes ? `${gpu.name} (${formatBytes(gpu.vram_bytes)})` : gpu.name,
+    ]),
+    ...info.disks.map((disk): [string, string] => [
+      t(`diagnostics.disk.${disk.role}`) || disk.role,
+      `${formatBytes(disk.free_bytes)} / ${formatBytes(disk.total_bytes)} (${disk.mount_point})`,
+    ]),
+    ['FFmpeg', info.ffmpeg_version || (t('diagnostics.notFound') || 'Not found')],
+    ['FFprobe', info.ffprobe_version || (t('diagnostics.notFound') || 'Not found')],
+  ] : [];
+
+  return (
+    <div
+      style={{
+        display: 'flex',
+        flexDirection: 'column',
+        gap: 12,
+        padding: 12,
+        borderRadius: 8,
+      }}
+      className="glass-card"
+    >
+      {rows.length > 0 && (
+        <div style={{ display: 'grid', gridTemplateColumns: 'auto 1fr', gap: '6px 12px', fontSize: 13 }}>
+          {rows.map(([label, value], index) => (
+            <React.Fragment key={index}>
+              <span style={{ color: theme.colors.textSecondary }}>{label}</span>
+              <span style={{ color: theme.colors.text, wordBreak: 'break-word', userSelect: 'text' }}>{value}</span>
+            </React.Fragment>
+          ))}
+        </div>
+      )}
+
+      <div style={{ display: 'flex', gap: 8 }}>
+        <button onClick={handleCollect} disabled={loading} style={{ ...buttonStyle, opacity: loading ? 0.7 : 1 }}>
+          <Activity size={16} strokeWidth={1.5} /> {loading ? '...' : (t('diagnostics.collect') || 'Collect system info')}
+        </button>
+        <button onClick={handleExportBundle} disabled={exporting} style={{ ...buttonStyle, opacity: exporting ? 0.7 : 1 }}>
+          <Download size={16} strokeWidth={1.5} /> {exporting ? '...' : (t('diagnostics.exportBundle') || 'Export support bundle')}
+        </button>
+      </div>
+
+      <div style={{ display: 'flex', gap: 8 }}>
+        <button onClick={handleBackupData} disabled={exporting} style={{ ...buttonStyle, opacity: exporting ? 0.7 : 1 }}>
+          <Archive size={16} strokeWidth={1.5} /> {t('diagnostics.backupData')}
+        </button>
+        <button onClick={handleRestoreData} disabled={exporting} style={{ ...buttonStyle, opacity: exporting ? 0.7 : 1 }}>
+          <Upload size={16} strokeWidth={1.5} /> {t('diagnostics.restoreData')}
+        </button>
+      </div>
+
+      {message && (
+        <div style={{ fontSize: 12, color: theme.colors.textSecondary }}>{message}</div>
+      )}
+    </div>
+  );
+};
+
+export default DiagnosticsPanel;
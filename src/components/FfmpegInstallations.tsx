@@ -0,0 +1,133 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open } from '@tauri-apps/api/dialog';
+import { useLanguage } from '../contexts/LanguageContext';
+import type { PresetInfo } from '../types';
+import '../styles/FfmpegManager.css';
+
+interface FfmpegInstallation {
+  id: string;
+  ffmpeg_path: string;
+  ffprobe_path: string;
+  version: string | null;
+  managed: boolean;
+  active: boolean;
+}
+
+/**
+ * FFmpeg builds kept side by side in the app data folder, the active one,
+ * and the presets pinned to a specific build
+ */
+export function FfmpegInstallations() {
+  const { t } = useLanguage();
+  const [installations, setInstallations] = useState<FfmpegInstallation[]>([]);
+  const [presets, setPresets] = useState<string[]>([]);
+  const [overrides, setOverrides] = useState<Record<string, string>>({});
+  const [error, setError] = useState('');
+
+  const load = async () => {
+    try {
+      const [list, presetList, pinned] = await Promise.all([
+        invoke<FfmpegInstallation[]>('list_ffmpeg_installations'),
+        invoke<PresetInfo[]>('list_presets'),
+        invoke<Record<string, string>>('get_preset_ffmpeg_overrides'),
+      ]);
+      setInstallations(list);
+      setPresets(presetList.map((preset) => preset.name));
+      setOverrides(pinned);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const run = async (action: () => Promise<unknown>) => {
+    try {
+      await action();
+      setError('');
+    } catch (err) {
+      setError(String(err));
+    }
+    await load();
+  };
+
+  const handleImport = async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (!selected || typeof selected !== 'string') return;
+    const name = selected.split(/[\\/]/).filter(Boolean).pop() || 'ffmpeg';
+    await run(() => invoke('import_ffmpeg_installation', { sourceDir: selected, name }));
+  };
+
+  const managed = installations.filter((i) => i.managed);
+
+  return (
+    <div className="manual-section ffmpeg-installations">
+      <h3>{t('ffmpeg.installations')}</h3>
+      <p className="help-text">{t('ffmpeg.installationsHelp')}</p>
+
+      {installations.map((installation) => (
+        <div key={installation.id} className="installation-row">
+          <div className="installation-info">
+            <strong>{installation.id}</strong>
+            {installation.active && (
+              <span className="status-found"> {t('ffmpeg.activeInstallation')}</span>
+            )}
+            <div className="status-version">
+              {installation.version || t('ffmpeg.installationBroken')}
+            </div>
+          </div>
+          {!installation.active && (
+            <button
+              className="btn-secondary"
+              disabled={!installation.version}
+              onClick={() => run(() => invoke('set_active_ffmpeg', { id: installation.id }))}
+            >
+              {t('ffmpeg.useInstallation')}
+            </button>
+          )}
+          {installation.managed && !installation.active && (
+            <button
+              className="btn-secondary"
+              onClick={() => run(() => invoke('remove_ffmpeg_installation', { id: installation.id }))}
+            >
+              {t('ffmpeg.removeInstallation')}
+            </button>
+          )}
+        </div>
+      ))}
+
+      <button onClick={handleImport} className="btn-primary">
+        {t('ffmpeg.importInstallation')}
+      </button>
+
+      {managed.length > 0 && presets.length > 0 && (
+        <div className="preset-overrides">
+          <h4>{t('ffmpeg.presetOverrides')}</h4>
+          {presets.map((preset) => (
+            <div key={preset} className="path-input-group">
+              <label>{preset}</label>
+              <select
+                value={overrides[preset] || ''}
+                onChange={(e) =>
+                  run(() => invoke('set_preset_ffmpeg', { presetName: preset, id: e.target.value || null }))
+                }
+              >
+                <option value="">{t('ffmpeg.activeInstallation')}</option>
+                {managed.map((installation) => (
+                  <option key={installation.id} value={installation.id}>
+                    {installation.id}
+                  </option>
+                ))}
+              </select>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {error && <div className="error-message">{error}</div>}
+    </div>
+  );
+}
@@ -1,9 +1,10 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { listen } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/api/dialog';
 import { ffmpegFinder } from '../services/FFmpegFinder';
 import { useLanguage } from '../contexts/LanguageContext';
+import { FfmpegInstallations } from './FfmpegInstallations';
 import '../styles/FfmpegManager.css';
 
 interface FfmpegStatus {
@@ -39,9 +40,12 @@ export function FfmpegManager() {
   const [searchStage, setSearchStage] = useState<SearchStage>('idle');
   const [searchMessage, setSearchMessage] = useState('');
   const [filesChecked, setFilesChecked] = useState(0);
+  const [searchDirectory, setSearchDirectory] = useState('');
+  const searchCancelled = useRef(false);
   const [error, setError] = useState('');
   const [customFfmpegPath, setCustomFfmpegPath] = useState('');
   const [customFfprobePath, setCustomFfprobePath] = useState('');
+  const [featureProblems, setFeatureProblems] = useState<string[]>([]);
 
   // Check status on mount
   useEffect(() => {
@@ -52,8 +56,9 @@ export function FfmpegManager() {
       setSearchMessage(event.payload);
     });
 
-    const unlistenProgress = listen<number>('ffmpeg-search-progress', (event) => {
-      setFilesChecked(event.payload);
+    const unlistenProgress = listen<{ checked: number; directory: string }>('ffmpeg-search-progress', (event) => {
+      setFilesChecked(event.payload.checked);
+      setSearchDirectory(event.payload.directory);
     });
 
     return () => {
@@ -62,6 +67,17 @@ export function FfmpegManager() {
     };
   }, []);
 
+  // Report capabilities missing from the configured build (libx265, NVENC, ...)
+  useEffect(() => {
+    if (!status?.ffmpeg_found) {
+      setFeatureProblems([]);
+      return;
+    }
+    invoke<{ problems: string[] }>('check_ffmpeg_features')
+      .then((report) => setFeatureProblems(report.problems))
+      .catch((err) => console.error('Failed to check FFmpeg features:', err));
+  }, [status?.ffmpeg_found, status?.ffmpeg_path]);
+
   const checkStatus = async () => {
     try {
       const result = await ffmpegFinder.findFFmpeg(false);
@@ -114,6 +130,8 @@ export function FfmpegManager() {
     setSearchMessage(t('ffmpeg.deepSearching'));
     setError('');
     setFilesChecked(0);
+    setSearchDirectory('');
+    searchCancelled.current = false;
 
     try {
       const result = await invoke<FfmpegStatus>('search_ffmpeg_deep');
@@ -123,13 +141,22 @@ export function FfmpegManager() {
         ffmpeg_path: cleanPath(result.ffmpeg_path),
         ffprobe_path: cleanPath(result.ffprobe_path)
       });
-      setSearchStage('complete');
+      setSearchStage(searchCancelled.current ? 'idle' : 'complete');
     } catch (err) {
       setError(String(err));
       setSearchStage('error');
     }
   };
 
+  const handleCancelDeepSearch = async () => {
+    searchCancelled.current = true;
+    try {
+      await invoke('cancel_ffmpeg_search');
+    } catch (err) {
+      console.error('Failed to cancel FFmpeg search:', err);
+    }
+  };
+
   const handleSelectFfmpeg = async () => {
     const selected = await open({
       multiple: false,
@@ -257,6 +284,17 @@ export function FfmpegManager() {
 
       {renderStatus()}
 
+      {featureProblems.length > 0 && (
+        <div className="feature-problems">
+          <strong>{t('ffmpeg.missingFeatures')}</strong>
+          <ul>
+            {featureProblems.map((problem) => (
+              <li key={problem}>{problem}</li>
+            ))}
+          </ul>
+        </div>
+      )}
+
       {error && <div className="error-message">{error}</div>}
 
       {searchStage === 'idle' && (!status?.ffmpeg_found || !status?.ffprobe_found) && (
@@ -302,7 +340,15 @@ export function FfmpegManager() {
           <div className="spinner large"></div>
           <p>{searchMessage || t('ffmpeg.deepSearching')}</p>
           {filesChecked > 0 && <p className="files-checked">{t('ffmpeg.checkedLocations').replace('{count}', String(filesChecked))}</p>}
+          {searchDirectory && (
+            <p className="search-directory" title={searchDirectory}>
+              {cleanPath(searchDirectory)}
+            </p>
+          )}
           <p className="deep-warning">{t('ffmpeg.pleaseWait')}</p>
+          <button onClick={handleCancelDeepSearch} className="btn-secondary">
+            {t('ffmpeg.cancel')}
+          </button>
         </div>
       )}
 
@@ -360,6 +406,8 @@ export function FfmpegManager() {
         </button>
       </div>
 
+      <FfmpegInstallations />
+
       <div className="help-section">
         <details>
           <summary>{t('ffmpeg.whereToGet')}</summary>
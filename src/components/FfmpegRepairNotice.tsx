@@ -0,0 +1,92 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { AlertTriangle, X } from 'lucide-react';
+import { FfmpegManager } from './FfmpegManager';
+
+/**
+ * FfmpegRepairNotice Component
+ *
+ * The saved FFmpeg / FFprobe paths are checked at startup and rediscovered when the
+ * binaries moved (revalidate_ffmpeg_paths in main.rs). When that fails, this dialog
+ * names the broken paths and offers the FFmpeg setup to pick new ones.
+ */
+
+// Result of get_ffmpeg_revalidation_status / payload of ffmpeg-repair-failed
+interface FfmpegRevalidation {
+  ffmpeg_ok: boolean;
+  ffprobe_ok: boolean;
+  repaired: boolean;
+  ffmpeg_path: string;
+  ffprobe_path: string;
+}
+
+const failed = (result: FfmpegRevalidation | null): result is FfmpegRevalidation =>
+  !!result && (!result.ffmpeg_ok || !result.ffprobe_ok);
+
+export const FfmpegRepairNotice: React.FC = () => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [failure, setFailure] = useState<FfmpegRevalidation | null>(null);
+
+  useEffect(() => {
+    // The check may have finished before this window started listening
+    invoke<FfmpegRevalidation | null>('get_ffmpeg_revalidation_status')
+      .then((result) => {
+        if (failed(result)) setFailure(result);
+      })
+      .catch((err) => console.warn('[FfmpegRepair] get_ffmpeg_revalidation_status failed:', err));
+
+    const unlisten = listen<FfmpegRevalidation>('ffmpeg-repair-failed', (event) => setFailure(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const brokenPaths = failure
+    ? [!failure.ffmpeg_ok && failure.ffmpeg_path, !failure.ffprobe_ok && failure.ffprobe_path].filter(Boolean).join(', ')
+    : '';
+
+  return (
+    <AnimatePresence>
+      {failure && (
+        <motion.div
+          className="stats-overlay"
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+          transition={{ duration: 0.2 }}
+        >
+          <motion.div
+            className="stats-modal"
+            initial={{ opacity: 0, y: 20, scale: 0.98 }}
+            animate={{ opacity: 1, y: 0, scale: 1 }}
+            exit={{ opacity: 0, y: 10, scale: 0.98 }}
+            transition={{ duration: 0.25, ease: 'easeOut' }}
+            style={{ color: theme.colors.text, width: 'min(640px, 92vw)', maxHeight: '90vh', overflowY: 'auto' }}
+          >
+            <div className="stats-modal-header">
+              <span className="stats-modal-title" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+                <AlertTriangle size={18} strokeWidth={1.5} /> {t('ffmpeg.repairFailedTitle')}
+              </span>
+              <button className="stats-modal-close" onClick={() => setFailure(null)} style={{ color: theme.colors.text }}>
+                <X size={18} strokeWidth={1.5} />
+              </button>
+            </div>
+            <div style={{ padding: 16, display: 'flex', flexDirection: 'column', gap: 12 }}>
+              <div style={{ fontSize: 13, wordBreak: 'break-all' }}>
+                {t('ffmpeg.repairFailed').replace('{paths}', brokenPaths)}
+              </div>
+              <FfmpegManager />
+            </div>
+          </motion.div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+};
+
+export default FfmpegRepairNotice;
@@ -0,0 +1,220 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { FolderPlus, X } from 'lucide-react';
+import type { FolderImportOptions, FolderImportPreview } from '../types';
+
+/**
+ * FolderImportDialog Component
+ *
+ * Queues every video of a folder tree with the selected preset through enqueue_folder
+ * (see folder_import.rs). Preview probes the matches and shows the work they add up
+ * to; the queued files arrive like files from the command line (files-received).
+ */
+
+interface FolderImportDialogProps {
+  isOpen: boolean;
+  presetName: string | null;
+  outputDir: string | null;
+  onClose: () => void;
+}
+
+/** "*.mov, raw/" -> ["*.mov", "raw/"] */
+const splitPatterns = (value: string): string[] => value.split(/[,;\n]+/).map((p) => p.trim()).filter(Boolean);
+
+const formatHours = (seconds: number): string => {
+  const hours = Math.floor(seconds / 3600);
+  const minutes = Math.round((seconds % 3600) / 60);
+  return hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`;
+};
+
+export const FolderImportDialog: React.FC<FolderImportDialogProps> = ({ isOpen, presetName, outputDir, onClose }) => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [folder, setFolder] = useState('');
+  const [include, setInclude] = useState('');
+  const [exclude, setExclude] = useState('');
+  const [minSizeMb, setMinSizeMb] = useState('0');
+  const [recursive, setRecursive] = useState(true);
+  const [preview, setPreview] = useState<FolderImportPreview | null>(null);
+  const [progress, setProgress] = useState<{ scanned: number; total: number } | null>(null);
+  const [message, setMessage] = useState('');
+
+  // A changed selection invalidates the preview
+  useEffect(() => {
+    setPreview(null);
+    setMessage('');
+  }, [folder, include, exclude, minSizeMb, recursive]);
+
+  useEffect(() => {
+    const unlisten = listen<{ scanned: number; total: number }>('folder-import-progress', (event) => {
+      setProgress(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleBrowse = async () => {
+    const selected = await open({ directory: true, multiple: false, defaultPath: folder || undefined });
+    if (selected && typeof selected === 'string') {
+      setFolder(selected);
+    }
+  };
+
+  const run = async (previewOnly: boolean) => {
+    const options: FolderImportOptions = {
+      include: splitPatterns(include),
+      exclude: splitPatterns(exclude),
+      min_size: Math.round(Math.max(0, parseFloat(minSizeMb) || 0) * 1048576),
+      recursive,
+      output_dir: outputDir,
+      queue_only: false,
+      preview_only: previewOnly,
+    };
+    setProgress({ scanned: 0, total: 0 });
+    setMessage('');
+    try {
+      const result = await invoke<FolderImportPreview>('enqueue_folder', { path: folder, preset: presetName, options });
+      setPreview(result);
+      if (result.queued) {
+        onClose();
+      } else if (result.files.length === 0) {
+        setMessage(t('folderImport.noMatches'));
+      }
+    } catch (error) {
+      setMessage(String(error));
+    } finally {
+      setProgress(null);
+    }
+  };
+
+  const busy = progress !== null;
+  const readable = preview ? preview.files.length - preview.unreadable : 0;
+
+  return (
+    <AnimatePresence>
+      {isOpen && (
+        <motion.div
+          className="stats-overlay"
+          onClick={busy ? undefined : onClose}
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+          transition={{ duration: 0.2 }}
+        >
+          <motion.div
+            className="stats-modal"
+            onClick={(e) => e.stopPropagation()}
+            initial={{ opacity: 0, y: 20, scale: 0.98 }}
+            animate={{ opacity: 1, y: 0, scale: 1 }}
+            exit={{ opacity: 0, y: 10, scale: 0.98 }}
+            transition={{ duration: 0.25, ease: 'easeOut' }}
+            style={{ color: theme.colors.text, width: 'min(560px, 92vw)' }}
+          >
+            <div className="stats-modal-header">
+              <span className="stats-modal-title" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+                <FolderPlus size={18} strokeWidth={1.5} /> {t('folderImport.title')}
+              </span>
+              <button className="stats-modal-close" onClick={onClose} disabled={busy} style={{ color: theme.colors.text }}>
+                <X size={18} strokeWidth={1.5} />
+              </button>
+            </div>
+
+            <div style={{ padding: 16, display: 'flex', flexDirection: 'column', gap: 12 }}>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                <input type="text" value={folder} onChange={(e) => setFolder(e.target.value)} style={{ flex: 1 }} />
+                <button type="button" onClick={handleBrowse}>{t('folderImport.browse')}</button>
+              </div>
+
+              <label style={{ display: 'flex', flexDirection: 'column', gap: 4 }}>
+                <span>{t('folderImport.include')}</span>
+                <input type="text" value={include} placeholder="*.mov, day?/*.mp4" onChange={(e) => setInclude(e.target.value)} />
+              </label>
+              <label style={{ display: 'flex', flexDirection: 'column', gap: 4 }}>
+                <span>{t('folderImport.exclude')}</span>
+                <input type="text" value={exclude} placeholder="Proxy/, *.tmp.mp4" onChange={(e) => setExclude(e.target.value)} />
+              </label>
+
+              <div style={{ display: 'flex', alignItems: 'center', gap: 16 }}>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                  <span>{t('folderImport.minSize')}</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={10}
+                    value={minSizeMb}
+                    onChange={(e) => setMinSizeMb(e.target.value)}
+                    style={{ width: 90 }}
+                  />
+                </label>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 6, cursor: 'pointer' }}>
+                  <input type="checkbox" checked={recursive} onChange={(e) => setRecursive(e.target.checked)} />
+                  {t('folderImport.recursive')}
+                </label>
+              </div>
+              <div style={{ fontSize: 12, color: theme.colors.textSecondary }}>{t('folderImport.patternsHint')}</div>
+
+              {progress && progress.total > 0 && (
+                <div style={{ height: 6, borderRadius: 3, background: theme.colors.border, overflow: 'hidden' }}>
+                  <div
+                    style={{
+                      width: `${(progress.scanned / progress.total) * 100}%`,
+                      height: '100%',
+                      background: theme.colors.primary,
+                      transition: 'width 0.2s',
+                    }}
+                  />
+                </div>
+              )}
+
+              {preview && preview.files.length > 0 && (
+                <>
+                  <div style={{ fontSize: 13 }}>
+                    {t('folderImport.summary')
+                      .replace('{count}', String(readable))
+                      .replace('{size}', `${(preview.total_size / 1073741824).toFixed(2)} GB`)
+                      .replace('{duration}', formatHours(preview.total_duration))}
+                    {preview.unreadable > 0 && ` ${t('folderImport.unreadable').replace('{count}', String(preview.unreadable))}`}
+                  </div>
+                  <div style={{ maxHeight: 160, overflowY: 'auto', fontSize: 12, color: theme.colors.textSecondary }}>
+                    {preview.files.map((file) => (
+                      <div
+                        key={file.path}
+                        title={file.error ?? undefined}
+                        style={{ wordBreak: 'break-all', color: file.error ? theme.colors.error : undefined }}
+                      >
+                        {file.path}
+                      </div>
+                    ))}
+                  </div>
+                </>
+              )}
+              {message && <div style={{ fontSize: 13, color: theme.colors.textSecondary }}>{message}</div>}
+
+              <div style={{ display: 'flex', gap: 8, justifyContent: 'flex-end' }}>
+                <button type="button" onClick={() => void run(true)} disabled={busy || !folder.trim()}>
+                  {t('folderImport.preview')}
+                </button>
+                <button
+                  className="main-action-button"
+                  onClick={() => void run(false)}
+                  disabled={busy || !folder.trim()}
+                  style={{ background: theme.colors.primary, color: '#fff' }}
+                >
+                  {t('folderImport.enqueue')}
+                </button>
+              </div>
+            </div>
+          </motion.div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+};
+
+export default FolderImportDialog;
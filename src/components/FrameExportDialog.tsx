@@ -0,0 +1,198 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { Image as ImageIcon, X } from 'lucide-react';
+import type { FrameExportResult, FrameFormat, FrameSelection } from '../types';
+
+/**
+ * FrameExportDialog Component
+ *
+ * Saves stills of a queued video with export_frames (see frames.rs): one frame every
+ * N seconds or one per listed timestamp ("90", "1:30" or "0:01:30.5").
+ */
+
+interface FrameExportDialogProps {
+  inputPath: string | null;
+  onClose: () => void;
+}
+
+/** Seconds from "90", "1:30" or "1:02:03.5"; null when not a time */
+export const parseTimestamp = (value: string): number | null => {
+  const parts = value.trim().split(':');
+  if (parts.length > 3 || parts.some((part) => part.trim() === '' || isNaN(Number(part)))) {
+    return null;
+  }
+  return parts.reduce((total, part) => total * 60 + Number(part), 0);
+};
+
+export const FrameExportDialog: React.FC<FrameExportDialogProps> = ({ inputPath, onClose }) => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [mode, setMode] = useState<FrameSelection['mode']>('interval');
+  const [intervalSeconds, setIntervalSeconds] = useState('10');
+  const [timestamps, setTimestamps] = useState('');
+  const [format, setFormat] = useState<FrameFormat>('png');
+  const [outputDir, setOutputDir] = useState('');
+  const [progress, setProgress] = useState<number | null>(null);
+  const [message, setMessage] = useState('');
+
+  useEffect(() => {
+    if (!inputPath) return;
+    // Next to the video by default
+    const cut = Math.max(inputPath.lastIndexOf('/'), inputPath.lastIndexOf('\\'));
+    setOutputDir(cut > 0 ? inputPath.substring(0, cut) : '');
+    setProgress(null);
+    setMessage('');
+  }, [inputPath]);
+
+  useEffect(() => {
+    const unlisten = listen<{ input: string; percent: number }>('frame-export-progress', (event) => {
+      if (event.payload.input === inputPath) {
+        setProgress(event.payload.percent);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [inputPath]);
+
+  const handleBrowse = async () => {
+    const selected = await open({ directory: true, multiple: false, defaultPath: outputDir || undefined });
+    if (selected && typeof selected === 'string') {
+      setOutputDir(selected);
+    }
+  };
+
+  const handleExport = async () => {
+    if (!inputPath) return;
+    let selection: FrameSelection;
+    if (mode === 'interval') {
+      selection = { mode, seconds: parseFloat(intervalSeconds) || 0 };
+    } else {
+      const times = timestamps.split(/[\s,;]+/).filter(Boolean).map(parseTimestamp);
+      if (times.length === 0 || times.some((time) => time === null)) {
+        setMessage(t('frames.invalidTimestamps'));
+        return;
+      }
+      selection = { mode, times: times as number[] };
+    }
+
+    setProgress(0);
+    setMessage('');
+    try {
+      const result = await invoke<FrameExportResult>('export_frames', { input: inputPath, selection, format, outputDir });
+      setMessage(t('frames.done').replace('{count}', String(result.files.length)));
+    } catch (error) {
+      setMessage(String(error));
+    } finally {
+      setProgress(null);
+    }
+  };
+
+  const busy = progress !== null;
+
+  return (
+    <AnimatePresence>
+      {inputPath && (
+        <motion.div
+          className="stats-overlay"
+          onClick={busy ? undefined : onClose}
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+          transition={{ duration: 0.2 }}
+        >
+          <motion.div
+            className="stats-modal"
+            onClick={(e) => e.stopPropagation()}
+            initial={{ opacity: 0, y: 20, scale: 0.98 }}
+            animate={{ opacity: 1, y: 0, scale: 1 }}
+            exit={{ opacity: 0, y: 10, scale: 0.98 }}
+            transition={{ duration: 0.25, ease: 'easeOut' }}
+            style={{ color: theme.colors.text, width: 'min(520px, 92vw)' }}
+          >
+            <div className="stats-modal-header">
+              <span className="stats-modal-title" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+                <ImageIcon size={18} strokeWidth={1.5} /> {t('frames.title')}
+              </span>
+              <button className="stats-modal-close" onClick={onClose} disabled={busy} style={{ color: theme.colors.text }}>
+                <X size={18} strokeWidth={1.5} />
+              </button>
+            </div>
+
+            <div style={{ padding: 16, display: 'flex', flexDirection: 'column', gap: 12 }}>
+              <div style={{ fontSize: 12, color: theme.colors.textSecondary, wordBreak: 'break-all' }}>{inputPath}</div>
+
+              <div style={{ display: 'flex', gap: 16 }}>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 6, cursor: 'pointer' }}>
+                  <input type="radio" checked={mode === 'interval'} onChange={() => setMode('interval')} />
+                  {t('frames.everyN')}
+                </label>
+                <label style={{ display: 'flex', alignItems: 'center', gap: 6, cursor: 'pointer' }}>
+                  <input type="radio" checked={mode === 'timestamps'} onChange={() => setMode('timestamps')} />
+                  {t('frames.atTimestamps')}
+                </label>
+              </div>
+
+              {mode === 'interval' ? (
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                  <span>{t('frames.interval')}</span>
+                  <input
+                    type="number"
+                    min={0.04}
+                    step={0.5}
+                    value={intervalSeconds}
+                    onChange={(e) => setIntervalSeconds(e.target.value)}
+                    style={{ width: 90 }}
+                  />
+                </div>
+              ) : (
+                <input
+                  type="text"
+                  value={timestamps}
+                  placeholder="0:05, 1:30, 12:00.5"
+                  onChange={(e) => setTimestamps(e.target.value)}
+                />
+              )}
+
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                <span>{t('frames.format')}</span>
+                <select value={format} onChange={(e) => setFormat(e.target.value as FrameFormat)}>
+                  <option value="png">PNG</option>
+                  <option value="jpeg">JPEG</option>
+                </select>
+              </div>
+
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                <input type="text" value={outputDir} onChange={(e) => setOutputDir(e.target.value)} style={{ flex: 1 }} />
+                <button type="button" onClick={handleBrowse}>{t('frames.browse')}</button>
+              </div>
+
+              {busy && (
+                <div style={{ height: 6, borderRadius: 3, background: theme.colors.border, overflow: 'hidden' }}>
+                  <div style={{ width: `${progress}%`, height: '100%', background: theme.colors.primary, transition: 'width 0.2s' }} />
+                </div>
+              )}
+              {message && <div style={{ fontSize: 13, color: theme.colors.textSecondary }}>{message}</div>}
+
+              <button
+                className="main-action-button"
+                onClick={handleExport}
+                disabled={busy || !outputDir.trim()}
+                style={{ background: theme.colors.primary, color: '#fff', alignSelf: 'flex-end' }}
+              >
+                {t('frames.export')}
+              </button>
+            </div>
+          </motion.div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+};
+
+export default FrameExportDialog;
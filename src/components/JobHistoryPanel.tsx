@@ -0,0 +1,215 @@
+/**
+ * JobHistoryPanel - finished renders stored by the backend (history.rs)
+ *
+ * Unlike the statistics list, a re-run here queues the job with the exact FFmpeg
+ * arguments it used, not with the current settings.
+ */
+
+import React, { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { Check, X, RotateCcw, Trash2 } from 'lucide-react';
+import useRenderQueue from '../hooks/useRenderQueue';
+import type { BackendRenderJob } from '../services/RenderService';
+import type { JobHistoryEntry } from '../types';
+import '../styles/StatisticsPanel.css';
+
+interface JobHistoryPanelProps {
+  onClose?: () => void;
+}
+
+type StatusFilter = 'all' | 'success' | 'failed';
+
+const formatElapsed = (seconds: number): string => {
+  const total = Math.round(seconds);
+  const h = Math.floor(total / 3600);
+  const m = Math.floor((total % 3600) / 60);
+  const s = total % 60;
+  return h > 0 ? `${h}h ${m}m` : m > 0 ? `${m}m ${s}s` : `${s}s`;
+};
+
+const formatDate = (isoDate: string) => {
+  const date = new Date(isoDate);
+  return date.toLocaleDateString() + ' ' + date.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+};
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+const JobHistoryPanel: React.FC<JobHistoryPanelProps> = ({ onClose }) => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const { addHistoryJob } = useRenderQueue();
+
+  const [entries, setEntries] = useState<JobHistoryEntry[]>([]);
+  const [status, setStatus] = useState<StatusFilter>('all');
+  const [search, setSearch] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const loadHistory = useCallback(async () => {
+    try {
+      const list = await invoke<JobHistoryEntry[]>('list_job_history', {
+        filter: { status: status === 'all' ? null : status, search: search || null, limit: 500 },
+      });
+      setEntries(list);
+    } catch (e) {
+      console.error('[JobHistoryPanel] Failed to load history:', e);
+    }
+  }, [status, search]);
+
+  useEffect(() => {
+    void loadHistory();
+  }, [loadHistory]);
+
+  const rerun = async (historyId: string) => {
+    const job = await invoke<BackendRenderJob>('rerun_job', { historyId });
+    await addHistoryJob(job);
+  };
+
+  const handleRerun = async (entry: JobHistoryEntry) => {
+    setError(null);
+    try {
+      await rerun(entry.id);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleRerunFailed = async () => {
+    setError(null);
+    const failures: string[] = [];
+    for (const entry of entries.filter((e) => !e.success)) {
+      try {
+        await rerun(entry.id);
+      } catch (e) {
+        failures.push(String(e));
+      }
+    }
+    if (failures.length > 0) {
+      setError(failures.join('\n'));
+    }
+  };
+
+  const handleClear = async () => {
+    try {
+      await invoke('clear_history');
+      setEntries([]);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="statistics-panel" style={{ color: theme.colors.text }}>
+      {/* Header */}
+      <div className="stats-header" style={{ borderColor: theme.colors.border }}>
+        <h2>{t('jobHistory.title') || 'Job history'}</h2>
+        {onClose && (
+          <button onClick={onClose} className="close-btn" style={{ color: theme.colors.text }}>
+            ×
+          </button>
+        )}
+      </div>
+
+      {/* Toolbar */}
+      <div className="stats-toolbar" style={{ borderColor: theme.colors.border }}>
+        <div className="filter-buttons">
+          {(['all', 'success', 'failed'] as const).map((f) => (
+            <button
+              key={f}
+              onClick={() => setStatus(f)}
+              className={status === f ? 'active' : ''}
+              style={{
+                background: status === f ? theme.colors.primary : theme.colors.background,
+                color: status === f ? '#fff' : theme.colors.text,
+                borderColor: theme.colors.border,
+              }}
+            >
+              {t(`jobHistory.filter.${f}`) || f}
+            </button>
+          ))}
+          <input
+            type="text"
+            value={search}
+            onChange={(e) => setSearch(e.target.value)}
+            placeholder={t('jobHistory.search') || 'Search'}
+            style={{ minWidth: 160 }}
+          />
+        </div>
+        <div className="action-buttons">
+          <button
+            onClick={handleRerunFailed}
+            disabled={!entries.some((e) => !e.success)}
+            style={{ background: theme.colors.primary, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}
+          >
+            <RotateCcw size={16} strokeWidth={1.5} /> {t('jobHistory.rerunFailed') || 'Re-run failed'}
+          </button>
+          <button
+            onClick={handleClear}
+            style={{ background: theme.colors.error, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}
+          >
+            <Trash2 size={16} strokeWidth={1.5} /> {t('stats.clear') || 'Clear'}
+          </button>
+        </div>
+      </div>
+
+      {error && (
+        <div className="history-error" style={{ color: theme.colors.error, padding: '8px 16px', whiteSpace: 'pre-wrap' }}>
+          {error}
+        </div>
+      )}
+
+      {/* History List */}
+      <div className="render-history">
+        {entries.length === 0 ? (
+          <div className="empty-state" style={{ color: theme.colors.textSecondary }}>
+            {t('stats.noRenders') || 'No renders in history'}
+          </div>
+        ) : (
+          entries.map((entry) => (
+            <div key={entry.id} className="history-item" style={{ borderColor: theme.colors.border }}>
+              <div className="history-main">
+                <div className="history-info">
+                  <span className="history-filename">{fileName(entry.job.input_path)}</span>
+                  <span className="history-output" style={{ color: theme.colors.textSecondary }}>
+                    → {entry.job.output_path}
+                  </span>
+                </div>
+                <div className="history-meta" style={{ color: theme.colors.textSecondary }}>
+                  <span className="history-status" style={{ color: entry.success ? theme.colors.success : theme.colors.error }}>
+                    {entry.success ? <Check size={14} strokeWidth={2} /> : <X size={14} strokeWidth={2} />}{' '}
+                    {entry.success ? t('stats.completed') || 'Completed' : t('stats.error') || 'Error'}
+                  </span>
+                  <span>•</span>
+                  <span>{formatDate(entry.started_at)}</span>
+                  <span>•</span>
+                  <span>{formatElapsed(entry.elapsed_seconds)}</span>
+                </div>
+                {entry.error && (
+                  <div className="history-error" style={{ color: theme.colors.error }}>
+                    {entry.error}
+                  </div>
+                )}
+                <div className="history-settings" style={{ color: theme.colors.textSecondary }}>
+                  <span>{entry.job.ffmpeg_args.join(' ')}</span>
+                </div>
+              </div>
+              <div className="history-actions">
+                <button
+                  onClick={() => handleRerun(entry)}
+                  className="re-render-btn overwrite"
+                  title={t('jobHistory.rerun') || 'Re-run with the same arguments'}
+                  style={{ display: 'flex', alignItems: 'center', justifyContent: 'center' }}
+                >
+                  <RotateCcw size={14} strokeWidth={2} />
+                </button>
+              </div>
+            </div>
+          ))
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default JobHistoryPanel;
@@ -0,0 +1,176 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTheme } from '../contexts/ThemeContext';
+import { useLanguage } from '../contexts/LanguageContext';
+import { Power } from 'lucide-react';
+import type { PostQueueAction, PostQueueCountdown, PostQueueResult } from '../types';
+
+/**
+ * PostQueueActionControl Component
+ *
+ * Picks what happens once the queue drains (set_post_queue_action, see queue.rs) and
+ * shows the backend's countdown with a Cancel button. The action runs once; the
+ * backend then resets it to none, and so does cancelling the countdown.
+ */
+
+type ActionKind = PostQueueAction['kind'];
+
+const KIND_LABELS: Record<ActionKind, string> = {
+  none: 'queue.postActionNone',
+  shutdown: 'queue.postActionShutdown',
+  sleep: 'queue.postActionSleep',
+  hibernate: 'queue.postActionHibernate',
+  run_script: 'queue.postActionScript',
+};
+
+const NONE: PostQueueAction = { kind: 'none' };
+
+export const PostQueueActionControl: React.FC = () => {
+  const { theme } = useTheme();
+  const { t } = useLanguage();
+  const [action, setAction] = useState<PostQueueAction>(NONE);
+  const [countdown, setCountdown] = useState<PostQueueCountdown | null>(null);
+  const [failure, setFailure] = useState<PostQueueResult | null>(null);
+
+  useEffect(() => {
+    invoke<PostQueueAction>('get_post_queue_action')
+      .then(setAction)
+      .catch((err) => console.warn('[PostQueueAction] get_post_queue_action failed:', err));
+
+    const unlisteners = [
+      listen<PostQueueCountdown>('post-queue-countdown', (event) => setCountdown(event.payload)),
+      listen('post-queue-cancelled', () => {
+        setCountdown(null);
+        setAction(NONE);
+      }),
+      listen<PostQueueResult>('post-queue-action', (event) => {
+        setCountdown(null);
+        setAction(NONE);
+        if (event.payload.error) {
+          setFailure(event.payload);
+        }
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, []);
+
+  const actionLabel = (value: PostQueueAction): string =>
+    value.kind === 'run_script' ? value.path.split(/[\\/]/).pop() ?? value.path : t(KIND_LABELS[value.kind]);
+
+  const handleChange = async (kind: ActionKind) => {
+    let next: PostQueueAction;
+    if (kind === 'run_script') {
+      const selected = await open({ multiple: false, directory: false });
+      if (!selected || typeof selected !== 'string') {
+        return;
+      }
+      next = { kind, path: selected };
+    } else {
+      next = { kind } as PostQueueAction;
+    }
+    try {
+      await invoke('set_post_queue_action', { action: next });
+      setAction(next);
+    } catch (err) {
+      console.warn('[PostQueueAction] set_post_queue_action failed:', err);
+      setFailure({ action: next, error: String(err) });
+    }
+  };
+
+  const handleCancel = () => {
+    invoke<boolean>('cancel_post_queue_action').catch((err) => {
+      console.warn('[PostQueueAction] cancel_post_queue_action failed:', err);
+    });
+  };
+
+  const dialog = countdown ?? failure;
+
+  return (
+    <>
+      <label
+        title={t('queue.postActionOnce')}
+        style={{ display: 'flex', alignItems: 'center', gap: '4px', marginLeft: '12px' }}
+      >
+        <Power size={14} strokeWidth={2} />
+        <select
+          aria-label={t('queue.postAction')}
+          value={action.kind}
+          onChange={(e) => void handleChange(e.target.value as ActionKind)}
+          style={{ fontSize: '0.8rem', maxWidth: '160px' }}
+        >
+          {(Object.keys(KIND_LABELS) as ActionKind[]).map((kind) => (
+            <option key={kind} value={kind}>
+              {kind === 'run_script' && action.kind === 'run_script' ? actionLabel(action) : t(KIND_LABELS[kind])}
+            </option>
+          ))}
+        </select>
+      </label>
+
+      <AnimatePresence>
+        {dialog && (
+          <motion.div
+            className="stats-overlay"
+            initial={{ opacity: 0 }}
+            animate={{ opacity: 1 }}
+            exit={{ opacity: 0 }}
+            transition={{ duration: 0.2 }}
+          >
+            <motion.div
+              className="stats-modal"
+              initial={{ opacity: 0, y: 20, scale: 0.98 }}
+              animate={{ opacity: 1, y: 0, scale: 1 }}
+              exit={{ opacity: 0, y: 10, scale: 0.98 }}
+              transition={{ duration: 0.25, ease: 'easeOut' }}
+              style={{ color: theme.colors.text, width: 'min(400px, 92vw)' }}
+            >
+              <div className="stats-modal-header">
+                <span className="stats-modal-title" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+                  <Power size={18} strokeWidth={1.5} /> {t('queue.postActionCountdownTitle')}
+                </span>
+              </div>
+              <div style={{ padding: 16, display: 'flex', flexDirection: 'column', gap: 16 }}>
+                {countdown ? (
+                  <div style={{ fontSize: 15 }}>
+                    {t('queue.postActionCountdown')
+                      .replace('{action}', actionLabel(countdown.action))
+                      .replace('{seconds}', String(countdown.seconds_left))}
+                  </div>
+                ) : (
+                  failure && (
+                    <div style={{ fontSize: 13, color: theme.colors.error }}>
+                      {t('queue.postActionFailed')
+                        .replace('{action}', actionLabel(failure.action))
+                        .replace('{error}', failure.error ?? '')}
+                    </div>
+                  )
+                )}
+                <div style={{ display: 'flex', justifyContent: 'flex-end' }}>
+                  {countdown ? (
+                    <button
+                      className="main-action-button"
+                      onClick={handleCancel}
+                      style={{ background: theme.colors.primary, color: '#fff' }}
+                    >
+                      {t('queue.postActionCancel')}
+                    </button>
+                  ) : (
+                    <button type="button" onClick={() => setFailure(null)}>
+                      {t('buttons.close')}
+                    </button>
+                  )}
+                </div>
+              </div>
+            </motion.div>
+          </motion.div>
+        )}
+      </AnimatePresence>
+    </>
+  );
+};
+
+export default PostQueueActionControl;
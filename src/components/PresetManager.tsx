@@ -9,11 +9,12 @@ import type {
   AudioSettings, 
   MainScreenSettings,
   WatermarkSettings,
+  PresetInfo,
 } from '../types';
+import { withPresetDefaults } from '../types';
 import '../styles/PresetManager.css';
 
-type PresetEntry = {
-  name: string;
+type PresetEntry = PresetInfo & {
   isDefault: boolean;
 };
 
@@ -57,16 +58,19 @@ const PresetManager: React.FC<PresetManagerProps> = ({
 
   const loadPresetList = async () => {
     try {
-      const presetNames = await invoke<string[]>('list_presets');
+      const list = await invoke<PresetInfo[]>('list_presets');
       const entries = await Promise.all(
-        presetNames.map(async (name) => {
+        list.map(async (info) => {
+          if (info.source === 'plugin') {
+            return { ...info, isDefault: false } as PresetEntry;
+          }
           try {
-            const content = await invoke<string>('load_preset', { name });
+            const content = await invoke<string>('load_preset', { name: info.name });
             const parsed = JSON.parse(content) as AppPreset;
-            return { name, isDefault: !!parsed.isDefault } as PresetEntry;
+            return { ...info, isDefault: !!parsed.isDefault } as PresetEntry;
           } catch (error) {
-            console.error('Failed to parse preset', name, error);
-            return { name, isDefault: false } as PresetEntry;
+            console.error('Failed to parse preset', info.name, error);
+            return { ...info, isDefault: false } as PresetEntry;
           }
         })
       );
@@ -137,7 +141,7 @@ const PresetManager: React.FC<PresetManagerProps> = ({
         throw new Error('Invalid preset structure');
       }
 
-      onApplyPreset(preset); // This will also update selectedPresetName via handleApplyPreset
+      onApplyPreset(withPresetDefaults(preset)); // This will also update selectedPresetName via handleApplyPreset
       showNotification(t('presets.applied'));
     } catch (error) {
       console.error('Failed to load preset:', error);
@@ -169,12 +173,13 @@ const PresetManager: React.FC<PresetManagerProps> = ({
     if (!selectedPresetName) return;
     try {
       setIsLoading(true);
-      const presetNames = await invoke<string[]>('list_presets');
+      const list = await invoke<PresetInfo[]>('list_presets');
       await Promise.all(
-        presetNames.map(async (name) => {
+        // A plugin preset made default is saved as a user preset
+        list.filter((p) => p.source === 'user' || p.name === selectedPresetName).map(async ({ name }) => {
           const content = await invoke<string>('load_preset', { name });
-          const parsed = JSON.parse(content) as AppPreset;
-          const updated: AppPreset = { ...parsed, isDefault: name === selectedPresetName };
+          const parsed = withPresetDefaults(JSON.parse(content) as AppPreset);
+          const updated: AppPreset = { ...parsed, source: undefined, plugin: undefined, isDefault: name === selectedPresetName };
           await invoke('save_preset', {
             name: parsed.name || name,
             content: JSON.stringify(updated, null, 2),
@@ -191,6 +196,8 @@ const PresetManager: React.FC<PresetManagerProps> = ({
     }
   };
 
+  const selectedPreset = presets.find((p) => p.name === selectedPresetName);
+
   return (
     <div className="preset-manager" style={{ borderColor: theme.colors.border }}>
       <div className="preset-selector-row">
@@ -203,8 +210,16 @@ const PresetManager: React.FC<PresetManagerProps> = ({
           >
             <option value="">{t('presets.selectPreset')}</option>
             {presets.map((p) => (
-              <option key={p.name} value={p.name}>
+              <option
+                key={p.name}
+                value={p.name}
+                title={p.source === 'plugin'
+                  ? [p.description, p.constraints?.encoders.length ? `${t('presets.requires')}: ${p.constraints.encoders.join(', ')}` : '']
+                    .filter(Boolean).join('\n')
+                  : undefined}
+              >
                 {p.isDefault ? `✓ ${p.name}` : p.name}
+                {p.source === 'plugin' ? ` (${t('presets.plugin')}: ${p.plugin})` : ''}
               </option>
             ))}
           </select>
@@ -240,7 +255,7 @@ const PresetManager: React.FC<PresetManagerProps> = ({
 
           <button
             onClick={() => setShowDeleteDialog(true)}
-            disabled={!selectedPresetName || isLoading}
+            disabled={!selectedPresetName || isLoading || selectedPreset?.source === 'plugin'}
             className="btn-delete"
             style={{ background: theme.colors.error, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}
           >
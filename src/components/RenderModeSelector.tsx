@@ -25,6 +25,10 @@ export type RenderMode = 'cpu' | 'gpu' | 'duo';
 interface HardwareInfo {
   cpu_vendor: string;
   gpu_vendor: string;
+  cpu_model?: string | null;
+  physical_cores?: number | null;
+  logical_cores?: number;
+  gpu_models?: string[];
 }
 
 interface RenderModeSelectorProps {
@@ -275,7 +279,14 @@ const RenderModeSelector: React.FC<RenderModeSelectorProps> = ({
             <CpuIcon />
           </div>
           <span className="button-label">CPU</span>
-          <span className="vendor-badge">{hardwareInfo.cpu_vendor.toUpperCase()}</span>
+          <span
+            className="vendor-badge"
+            title={hardwareInfo.cpu_model
+              ? `${hardwareInfo.cpu_model} (${hardwareInfo.physical_cores ?? '?'}C/${hardwareInfo.logical_cores ?? '?'}T)`
+              : undefined}
+          >
+            {hardwareInfo.cpu_vendor.toUpperCase()}
+          </span>
         </div>
         {isCpuActive && <div className="active-indicator" />}
       </motion.button>
@@ -319,7 +330,9 @@ const RenderModeSelector: React.FC<RenderModeSelectorProps> = ({
             <GpuIcon />
           </div>
           <span className="button-label">GPU</span>
-          <span className="vendor-badge">{gpuAvailable ? hardwareInfo.gpu_vendor.toUpperCase() : 'N/A'}</span>
+          <span className="vendor-badge" title={hardwareInfo.gpu_models?.join('\n') || undefined}>
+            {gpuAvailable ? hardwareInfo.gpu_vendor.toUpperCase() : 'N/A'}
+          </span>
         </div>
         {isGpuActive && <div className="active-indicator" />}
       </motion.button>
@@ -69,6 +69,11 @@ const themes: Record<string, Theme> = {
   'amoled-night': amoledNightTheme,
 };
 
+// "auto" follows the OS dark/light mode with these themes
+const AUTO_THEME = 'auto';
+const AUTO_LIGHT_THEME = 'light';
+const AUTO_DARK_THEME = 'dark-blue';
+
 // Convert hex color to RGB values
 const hexToRgb = (hex: string): string => {
   const result = /^#?([a-f\d]{2})([a-f\d]{2})([a-f\d]{2})$/i.exec(hex);
@@ -141,7 +146,7 @@ const resolveBackgroundImageUrl = async (filePath: string): Promise<string | nul
 
 export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) => {
   const [themeName, setThemeName] = useState<string>('light');
-  const [theme, setThemeState] = useState<Theme>(themes.light);
+  const [systemTheme, setSystemTheme] = useState<'light' | 'dark'>('light');
   const [modifiedTheme, setModifiedThemeState] = useState<boolean>(false);
   const [useImageBackground, setUseImageBackground] = useState<boolean>(false);
   const [backgroundImagePath, setBackgroundImagePath] = useState<string>('');
@@ -154,9 +159,8 @@ export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) =
       try {
         const { invoke } = await import('@tauri-apps/api/tauri');
         const settings = await invoke<any>('load_settings');
-        if (settings.theme && themes[settings.theme]) {
+        if (settings.theme && (settings.theme === AUTO_THEME || themes[settings.theme])) {
           setThemeName(settings.theme);
-          setThemeState(themes[settings.theme]);
         }
         if (settings.modifiedTheme !== undefined) {
           setModifiedThemeState(settings.modifiedTheme);
@@ -174,6 +178,39 @@ export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) =
     loadTheme();
   }, []);
 
+  useEffect(() => {
+    // Track the OS dark/light mode for the "auto" theme
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    const watchSystemTheme = async () => {
+      try {
+        const { invoke } = await import('@tauri-apps/api/tauri');
+        const { listen } = await import('@tauri-apps/api/event');
+        const stop = await listen<'light' | 'dark'>('system-theme-changed', (event) => {
+          setSystemTheme(event.payload);
+        });
+        if (disposed) {
+          stop();
+          return;
+        }
+        unlisten = stop;
+        setSystemTheme(await invoke<'light' | 'dark'>('get_system_theme'));
+      } catch (error) {
+        console.error('Failed to read system theme:', error);
+      }
+    };
+    watchSystemTheme();
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  const activeThemeName = themeName === AUTO_THEME
+    ? (systemTheme === 'dark' ? AUTO_DARK_THEME : AUTO_LIGHT_THEME)
+    : themeName;
+  const theme = themes[activeThemeName] || themes.light;
+
   useEffect(() => {
     let cancelled = false;
     let performanceModeEnabled = false;
@@ -220,7 +257,7 @@ export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) =
     root.style.setProperty('--surface-rgb', hexToRgb(theme.colors.surface));
     
     // Set light/dark theme specific variables
-    const isLight = isLightTheme(themeName);
+    const isLight = isLightTheme(activeThemeName);
     root.style.setProperty('--bg-brightness', isLight ? '1.0' : '0.7');
     root.style.setProperty('--bg-overlay-opacity', useImageBackground ? '0.05' : (isLight ? '0.3' : '0.4'));
     
@@ -245,7 +282,7 @@ export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) =
     const applyThemeGradient = () => {
       if (!bgElement) return;
       bgElement.classList.remove('has-image-background');
-      const bgStyle = themeBackgrounds[themeName] || themeBackgrounds['dark-blue'];
+      const bgStyle = themeBackgrounds[activeThemeName] || themeBackgrounds['dark-blue'];
       bgElement.style.backgroundImage = '';
       bgElement.style.background = bgStyle;
       bgElement.style.backgroundSize = '200% 200%';
@@ -286,12 +323,11 @@ export const ThemeProvider: React.FC<{ children: ReactNode }> = ({ children }) =
     return () => {
       cancelled = true;
     };
-  }, [theme, themeName, modifiedTheme, useImageBackground, backgroundImagePath, glassOpacity, glassBlur]);
+  }, [theme, activeThemeName, modifiedTheme, useImageBackground, backgroundImagePath, glassOpacity, glassBlur]);
 
   const setTheme = (name: string) => {
-    if (themes[name]) {
+    if (name === AUTO_THEME || themes[name]) {
       setThemeName(name);
-      setThemeState(themes[name]);
     }
   };
 
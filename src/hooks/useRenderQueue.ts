@@ -19,7 +19,7 @@
  */
 
 import { useState, useEffect, useCallback } from 'react';
-import RenderService, { RenderJob, RenderQueueState } from '../services/RenderService';
+import RenderService, { BackendRenderJob, RenderJob, RenderQueueState } from '../services/RenderService';
 import type { VideoSettings, AudioSettings, WatermarkSettings, MainScreenSettings } from '../types';
 
 export interface UseRenderQueueReturn {
@@ -38,9 +38,12 @@ export interface UseRenderQueueReturn {
   pendingJobs: number;
   
   // Actions
-  addFiles: (filePaths: string[]) => Promise<RenderJob[]>;
+  addFiles: (filePaths: string[], unattended?: boolean) => Promise<RenderJob[]>;
   addToQueue: (inputPath: string, outputPath: string, trimStartSec?: number, trimEndSec?: number) => Promise<RenderJob>;
+  addHistoryJob: (historyJob: BackendRenderJob) => Promise<RenderJob>;
+  findDuplicates: (inputPaths: string[]) => Promise<DuplicateConflict[]>;
   updateJobTrim: (jobId: string, trimStartSec: number, trimEndSec: number) => boolean;
+  setJobDependencies: (jobId: string, dependsOn: string[]) => boolean;
   removeJob: (jobId: string) => boolean;
   clearCompleted: () => void;
   start: () => Promise<void>;
@@ -89,8 +92,8 @@ export function useRenderQueue(): UseRenderQueueReturn {
   const pendingJobs = state.jobs.filter(j => j.status === 'pending').length;
 
   // Actions
-  const addFiles = useCallback(async (filePaths: string[]): Promise<RenderJob[]> => {
-    return RenderService.addToQueue(filePaths);
+  const addFiles = useCallback(async (filePaths: string[], unattended = false): Promise<RenderJob[]> => {
+    return RenderService.addToQueue(filePaths, unattended);
   }, []);
 
   const addToQueue = useCallback(async (
@@ -102,10 +105,22 @@ export function useRenderQueue(): UseRenderQueueReturn {
     return RenderService.addToQueueWithOutput(inputPath, outputPath, trimStartSec, trimEndSec);
   }, []);
 
+  const addHistoryJob = useCallback(async (historyJob: BackendRenderJob): Promise<RenderJob> => {
+    return RenderService.addHistoryJob(historyJob);
+  }, []);
+
+  const findDuplicates = useCallback(async (inputPaths: string[]): Promise<DuplicateConflict[]> => {
+    return RenderService.findDuplicates(inputPaths);
+  }, []);
+
   const updateJobTrim = useCallback((jobId: string, trimStartSec: number, trimEndSec: number): boolean => {
     return RenderService.updateJobTrim(jobId, trimStartSec, trimEndSec);
   }, []);
 
+  const setJobDependencies = useCallback((jobId: string, dependsOn: string[]): boolean => {
+    return RenderService.setJobDependencies(jobId, dependsOn);
+  }, []);
+
   const removeJob = useCallback((jobId: string): boolean => {
     return RenderService.removeFromQueue(jobId);
   }, []);
@@ -171,7 +186,10 @@ export function useRenderQueue(): UseRenderQueueReturn {
     // Actions
     addFiles,
     addToQueue,
+    addHistoryJob,
+    findDuplicates,
     updateJobTrim,
+    setJobDependencies,
     removeJob,
     clearCompleted,
     start,
@@ -1,13 +1,16 @@
 import React, { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { open } from '@tauri-apps/api/dialog';
 import { useLanguage } from '../contexts/LanguageContext';
 import { useTheme } from '../contexts/ThemeContext';
-import { AudioSettings as AudioSettingsType } from '../types/index';
+import { AudioSettings as AudioSettingsType, AudioTrackMode } from '../types/index';
 import { AlertTriangle, Info } from 'lucide-react';
 import { 
   getCodecOptions, 
   applyCodecConstraints,
   hasActiveFilters,
-  validateAudioSettings
+  validateAudioSettings,
+  getAudioSource
 } from '../utils/audioValidation';
 import '../styles/AudioSettings.css';
 
@@ -22,6 +25,7 @@ const AudioSettings: React.FC<AudioSettingsProps> = ({ onBack, settings, setSett
   const { theme } = useTheme();
   const [expandedSection, setExpandedSection] = useState<'noise' | 'effects' | null>(null);
   const [warning, setWarning] = useState<string | null>(null);
+  const [trackError, setTrackError] = useState<string | null>(null);
 
   // Codec presets with compatibility info
   const codecs = [
@@ -70,6 +74,29 @@ const AudioSettings: React.FC<AudioSettingsProps> = ({ onBack, settings, setSett
     }
   }, [settings.codec]); // Only validate on codec change to avoid loops
 
+  // Let the backend check the external track (file exists, volume range) as a render would
+  useEffect(() => {
+    if (settings.track !== 'replace' && settings.track !== 'mix') {
+      setTrackError(null);
+      return;
+    }
+    // Copy is replaced by AAC when rendering with an external track
+    const codec = settings.codec === 'copy' ? 'aac' : settings.codec;
+    invoke('validate_audio_source', { source: getAudioSource(settings), audioArgs: ['-c:a', codec] })
+      .then(() => setTrackError(null))
+      .catch((error) => setTrackError(String(error)));
+  }, [settings.track, settings.trackPath, settings.trackStart, settings.trackVolume, settings.codec]);
+
+  const handleBrowseTrack = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: 'Audio', extensions: ['mp3', 'wav', 'flac', 'aac', 'm4a', 'ogg', 'opus', 'wma'] }],
+    });
+    if (selected && typeof selected === 'string') {
+      setSettings(prev => ({ ...prev, trackPath: selected }));
+    }
+  };
+
   const canUseEffects = () => {
     const codec = codecs.find(c => c.value === settings.codec);
     return codec?.supportsEffects ?? true;
@@ -164,6 +191,74 @@ const AudioSettings: React.FC<AudioSettingsProps> = ({ onBack, settings, setSett
           <div className="setting-hint">{t('audioSettings.autoSelectHint')}</div>
         </div>
 
+        {/* Audio Track */}
+        <div className="setting-group">
+          <label>{t('audioSettings.track')}</label>
+          <select
+            value={settings.track}
+            onChange={(e) => setSettings(prev => ({ ...prev, track: e.target.value as AudioTrackMode }))}
+          >
+            {(['keep', 'mute', 'replace', 'mix'] as const).map(mode => (
+              <option key={mode} value={mode}>{t(`audioSettings.trackModes.${mode}`)}</option>
+            ))}
+          </select>
+          {(settings.track === 'replace' || settings.track === 'mix') && (
+            <>
+              <div style={{ display: 'flex', gap: 8, marginTop: 8 }}>
+                <input
+                  type="text"
+                  value={settings.trackPath}
+                  placeholder={t('audioSettings.trackFile')}
+                  onChange={(e) => setSettings(prev => ({ ...prev, trackPath: e.target.value }))}
+                  style={{ flex: 1 }}
+                />
+                <button type="button" onClick={handleBrowseTrack}>{t('audioSettings.trackBrowse')}</button>
+              </div>
+              {settings.track === 'replace' ? (
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 8 }}>
+                  <span>{t('audioSettings.trackStart')}</span>
+                  <input
+                    type="number"
+                    min={0}
+                    step={0.5}
+                    value={settings.trackStart}
+                    onChange={(e) => setSettings(prev => ({ ...prev, trackStart: Math.max(0, parseFloat(e.target.value) || 0) }))}
+                    style={{ width: 90 }}
+                  />
+                </div>
+              ) : (
+                <div className="slider-with-label" style={{ marginTop: 8 }}>
+                  <span style={{ marginRight: '12px' }}>{t('audioSettings.trackVolume')}</span>
+                  <input
+                    type="range"
+                    value={settings.trackVolume}
+                    onChange={(e) => setSettings(prev => ({ ...prev, trackVolume: parseInt(e.target.value, 10) }))}
+                    min="0"
+                    max="400"
+                    step="5"
+                    style={{ flex: 1 }}
+                  />
+                  <span style={{ marginLeft: '12px', minWidth: '60px', color: theme.colors.textSecondary }}>
+                    {settings.trackVolume}%
+                  </span>
+                </div>
+              )}
+              <label className="checkbox-label" style={{ marginTop: 8 }}>
+                <input
+                  type="checkbox"
+                  checked={settings.trackLoop}
+                  onChange={(e) => setSettings(prev => ({ ...prev, trackLoop: e.target.checked }))}
+                />
+                <span>{t('audioSettings.trackLoop')}</span>
+              </label>
+            </>
+          )}
+          {trackError && (
+            <div className="setting-hint" style={{ color: theme.colors.error }}>{trackError}</div>
+          )}
+          <div className="setting-hint">{t('audioSettings.trackHint')}</div>
+        </div>
+
         {/* Basic Settings Row 1 */}
         <div className="setting-row">
           <div className="setting-group flex-1">
@@ -239,6 +334,42 @@ const AudioSettings: React.FC<AudioSettingsProps> = ({ onBack, settings, setSett
           </div>
         </div>
 
+        {/* FLAC compression level */}
+        {settings.codec === 'flac' && (
+          <div className="setting-group">
+            <label>{t('audioSettings.flacCompression')}</label>
+            <div className="slider-with-label">
+              <input
+                type="range"
+                value={settings.flacCompression ?? 5}
+                onChange={(e) => setSettings(prev => ({ ...prev, flacCompression: parseInt(e.target.value, 10) }))}
+                min="0"
+                max="12"
+                step="1"
+                style={{ flex: 1 }}
+              />
+              <span style={{ marginLeft: '12px', minWidth: '60px', color: theme.colors.textSecondary }}>
+                {settings.flacCompression ?? 5}
+              </span>
+            </div>
+            <div className="setting-hint">{t('audioSettings.flacCompressionHint')}</div>
+          </div>
+        )}
+
+        {/* AC3 / E-AC3 / DTS passthrough */}
+        <div className="setting-group">
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.passthrough ?? false}
+              onChange={(e) => setSettings(prev => ({ ...prev, passthrough: e.target.checked }))}
+              disabled={settings.codec === 'copy'}
+            />
+            <span>{t('audioSettings.passthrough')}</span>
+          </label>
+          <div className="setting-hint">{t('audioSettings.passthroughHint')}</div>
+        </div>
+
         {/* Volume & Gain */}
         <div className="setting-row">
           <div className="setting-group flex-1">
@@ -6,8 +6,10 @@ import { useTheme } from '../contexts/ThemeContext';
 import { useSettings, ScreenAnimationType } from '../contexts/SettingsContext';
 import { FfmpegManager } from '../components/FfmpegManager';
 import { VideoGuide } from '../components/VideoGuide';
+import { DiagnosticsPanel } from '../components/DiagnosticsPanel';
 import { APP_VERSION } from '../version';
-import { UpdateService, UpdateState } from '../services/UpdateService';
+import { GpuCompatibility, DEFAULT_OUTPUT_TEMPLATE, DEFAULT_NETWORK, NetworkSettings, ProxyMode, DEFAULT_QUEUE_SCHEDULE, QueueSchedule, ScheduleMode, DEFAULT_IDLE_TRIGGER, IdleTrigger, DEFAULT_HTTP_API, ApiSettings, DEFAULT_WEBHOOK, Webhook, DEFAULT_UPLOAD_DESTINATION, UploadDestination, UploadKind, DEFAULT_HOOKS, HookSettings, RenderPriority, BatteryAction, VerifyMode, DEFAULT_THERMAL, ThermalSettings, TrashedSource } from '../types';
+import { UpdateService, UpdateState, UpdateChannel } from '../services/UpdateService';
 import { AlertTriangle, FolderOpen, Plus, Minus } from 'lucide-react';
 import '../styles/SettingsWindow.css';
 
@@ -37,6 +39,38 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
   const [modifiedTheme, setModifiedTheme] = useState<boolean>(appModifiedTheme);
   const [language, setLanguage] = useState('ru');
   const [outputSuffix, setOutputSuffix] = useState('_szhatoe');
+  const [outputTemplate, setOutputTemplate] = useState(DEFAULT_OUTPUT_TEMPLATE);
+  const [outputDirectory, setOutputDirectory] = useState('');
+  const [mirrorSourceFolders, setMirrorSourceFolders] = useState(false);
+  const [stageNetworkFiles, setStageNetworkFiles] = useState(false);
+  const [folderExcludePatterns, setFolderExcludePatterns] = useState('');
+  const [notificationsEnabled, setNotificationsEnabled] = useState(true);
+  const [notifyWhenFocused, setNotifyWhenFocused] = useState(false);
+  const [showCrashReports, setShowCrashReports] = useState(false);
+  const [updateChannel, setUpdateChannel] = useState<UpdateChannel>('stable');
+  const [network, setNetwork] = useState<NetworkSettings>(DEFAULT_NETWORK);
+  const [queueSchedule, setQueueSchedule] = useState<QueueSchedule>(DEFAULT_QUEUE_SCHEDULE);
+  const [idleTrigger, setIdleTrigger] = useState<IdleTrigger>(DEFAULT_IDLE_TRIGGER);
+  const [jobTimeoutMinutes, setJobTimeoutMinutes] = useState(0);
+  const [hwDecode, setHwDecode] = useState(false);
+  const [renderPriority, setRenderPriority] = useState<RenderPriority>('normal');
+  const [batteryAction, setBatteryAction] = useState<BatteryAction>('none');
+  const [thermal, setThermal] = useState<ThermalSettings>(DEFAULT_THERMAL);
+  const [verifyOutput, setVerifyOutput] = useState<VerifyMode>('off');
+  const [trashSourceAfterSuccess, setTrashSourceAfterSuccess] = useState(false);
+  const [quarantineFailedInputs, setQuarantineFailedInputs] = useState(false);
+  const [trashedSources, setTrashedSources] = useState<TrashedSource[]>([]);
+  const [restoreMessage, setRestoreMessage] = useState('');
+  const [shutdownTimeoutSecs, setShutdownTimeoutSecs] = useState(10);
+  const [httpApi, setHttpApi] = useState<ApiSettings>(DEFAULT_HTTP_API);
+  const [httpApiError, setHttpApiError] = useState('');
+  const [webhooks, setWebhooks] = useState<Webhook[]>([]);
+  const [webhookTests, setWebhookTests] = useState<Record<number, string>>({});
+  const [uploadDestinations, setUploadDestinations] = useState<UploadDestination[]>([]);
+  const [hooks, setHooks] = useState<HookSettings>(DEFAULT_HOOKS);
+  const [pauseShortcut, setPauseShortcut] = useState('');
+  const [stopShortcut, setStopShortcut] = useState('');
+  const [shortcutError, setShortcutError] = useState('');
   const [useImageBackground, setUseImageBackground] = useState<boolean>(appUseImageBackground);
   const [backgroundImagePath, setBackgroundImagePath] = useState<string>(appBackgroundImagePath);
   const [glassOpacity, setGlassOpacity] = useState<number>(appGlassOpacity);
@@ -54,15 +88,24 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
     exe_valid: boolean;
     loading: boolean;
   }>({ enabled: false, exe_valid: false, loading: true });
+  const [urlProtocolRegistered, setUrlProtocolRegistered] = useState(false);
+  const [autostart, setAutostart] = useState<{ enabled: boolean; minimized: boolean }>({ enabled: false, minimized: false });
 
   // Update state
   const [updateState, setUpdateState] = useState<UpdateState>(UpdateService.getState());
   const [showRestartPrompt, setShowRestartPrompt] = useState(false);
+  const [rollbackVersion, setRollbackVersion] = useState<string | null>(null);
 
   useEffect(() => {
     loadSettings();
     loadLogsPath();
     checkContextMenuStatus();
+    invoke<boolean>('is_url_protocol_registered').then(setUrlProtocolRegistered).catch(() => {});
+    loadTrashedSources();
+    invoke<{ enabled: boolean; minimized: boolean }>('get_autostart').then(setAutostart).catch(() => {});
+    invoke<{ previous_version: string } | null>('get_update_rollback')
+      .then((rollback) => setRollbackVersion(rollback?.previous_version ?? null))
+      .catch(() => {});
     
     // Subscribe to update state changes
     const unsubscribe = UpdateService.subscribe(setUpdateState);
@@ -101,6 +144,33 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
     modifiedTheme,
     language,
     outputSuffix,
+    outputTemplate,
+    outputDirectory,
+    mirrorSourceFolders,
+    stageNetworkFiles,
+    folderExcludePatterns,
+    notificationsEnabled,
+    notifyWhenFocused,
+    showCrashReports,
+    updateChannel,
+    network,
+    queueSchedule,
+    idleTrigger,
+    jobTimeoutMinutes,
+    hwDecode,
+    renderPriority,
+    batteryAction,
+    thermal,
+    verifyOutput,
+    trashSourceAfterSuccess,
+    quarantineFailedInputs,
+    shutdownTimeoutSecs,
+    httpApi,
+    webhooks,
+    uploadDestinations,
+    hooks,
+    pauseShortcut,
+    stopShortcut,
     useImageBackground,
     backgroundImagePath,
     glassOpacity,
@@ -157,6 +227,33 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
       }
       setLanguage(settings.language);
       setOutputSuffix(settings.output_suffix);
+      if (settings.outputTemplate) setOutputTemplate(settings.outputTemplate);
+      setOutputDirectory(settings.outputDirectory || '');
+      setMirrorSourceFolders(!!settings.mirrorSourceFolders);
+      setStageNetworkFiles(!!settings.stageNetworkFiles);
+      setFolderExcludePatterns((settings.folderExcludePatterns || []).join(', '));
+      setNotificationsEnabled(settings.notifications?.enabled ?? true);
+      setNotifyWhenFocused(!!settings.notifications?.whenFocused);
+      setShowCrashReports(!!settings.showCrashReports);
+      setUpdateChannel(settings.updateChannel === 'beta' ? 'beta' : 'stable');
+      setNetwork({ ...DEFAULT_NETWORK, ...settings.network });
+      setQueueSchedule({ ...DEFAULT_QUEUE_SCHEDULE, ...settings.queueSchedule });
+      setIdleTrigger({ ...DEFAULT_IDLE_TRIGGER, ...settings.idleTrigger });
+      setJobTimeoutMinutes(settings.jobTimeoutMinutes ?? 0);
+      setHwDecode(!!settings.hwDecode);
+      setRenderPriority(settings.renderPriority || 'normal');
+      setBatteryAction(settings.batteryAction || 'none');
+      setThermal({ ...DEFAULT_THERMAL, ...settings.thermal });
+      setVerifyOutput(settings.verifyOutput || 'off');
+      setTrashSourceAfterSuccess(!!settings.trashSourceAfterSuccess);
+      setQuarantineFailedInputs(!!settings.quarantineFailedInputs);
+      setShutdownTimeoutSecs(settings.shutdownTimeoutSecs ?? 10);
+      setHttpApi({ ...DEFAULT_HTTP_API, ...settings.httpApi });
+      setWebhooks((settings.webhooks || []).map((webhook: Partial<Webhook>) => ({ ...DEFAULT_WEBHOOK, ...webhook })));
+      setUploadDestinations((settings.uploadDestinations || []).map((destination: Partial<UploadDestination>) => ({ ...DEFAULT_UPLOAD_DESTINATION, ...destination })));
+      setHooks({ ...DEFAULT_HOOKS, ...settings.hooks });
+      setPauseShortcut(settings.shortcuts?.pauseResume || '');
+      setStopShortcut(settings.shortcuts?.stopAll || '');
       setUseImageBackground(!!settings.use_background_image);
       setBackgroundImagePath(settings.background_image_path || '');
       if (settings.glassOpacity !== undefined) setGlassOpacity(settings.glassOpacity);
@@ -175,8 +272,8 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
       // First run GPU check if key missing
       if (settings.gpuAvailable === undefined) {
         try {
-          const available = await invoke<boolean>('check_gpu_compatibility');
-          setGpuAvailable(!!available);
+          const gpu = await invoke<GpuCompatibility>('check_gpu_compatibility');
+          setGpuAvailable(gpu.available);
         } catch (e) {
           console.warn('GPU check failed:', e);
         }
@@ -213,16 +310,107 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
     }
   };
 
+  const handleToggleUrlProtocol = async (enabled: boolean) => {
+    try {
+      await invoke(enabled ? 'register_url_protocol' : 'unregister_url_protocol');
+      setUrlProtocolRegistered(enabled);
+    } catch (error) {
+      alert(String(error));
+    }
+  };
+
+  // Settings autosave shortly after each change; register once editing is done
+  const applyShortcuts = () => {
+    window.setTimeout(() => {
+      invoke('apply_global_shortcuts')
+        .then(() => setShortcutError(''))
+        .catch((error) => setShortcutError(String(error)));
+    }, 300);
+  };
+
+  const applyHttpApi = () => {
+    window.setTimeout(() => {
+      invoke('apply_http_api')
+        .then(() => setHttpApiError(''))
+        .catch((error) => setHttpApiError(String(error)));
+    }, 300);
+  };
+
+  const handleHttpApiChange = async (changes: Partial<ApiSettings>) => {
+    const next = { ...httpApi, ...changes };
+    if (next.enabled && !next.token) {
+      next.token = await invoke<string>('generate_api_token');
+    }
+    setHttpApi(next);
+    applyHttpApi();
+  };
+
+  const updateWebhook = (index: number, changes: Partial<Webhook>) => {
+    setWebhooks(webhooks.map((webhook, i) => (i === index ? { ...webhook, ...changes } : webhook)));
+  };
+
+  const removeWebhook = (index: number) => {
+    setWebhooks(webhooks.filter((_, i) => i !== index));
+    setWebhookTests({});
+  };
+
+  const updateDestination = (index: number, changes: Partial<UploadDestination>) => {
+    setUploadDestinations(uploadDestinations.map((destination, i) => (i === index ? { ...destination, ...changes } : destination)));
+  };
+
+  const addDestination = () => {
+    const id = `upload-${Date.now().toString(36)}`;
+    setUploadDestinations([...uploadDestinations, { ...DEFAULT_UPLOAD_DESTINATION, id, name: `${t('settings.uploadDestination')} ${uploadDestinations.length + 1}` }]);
+  };
+
+  const handleTestWebhook = async (index: number) => {
+    setWebhookTests((prev) => ({ ...prev, [index]: t('settings.webhookTesting') }));
+    try {
+      const status = await invoke<number>('test_webhook', { url: webhooks[index].url });
+      setWebhookTests((prev) => ({ ...prev, [index]: `${t('settings.webhookTestOk')} (HTTP ${status})` }));
+    } catch (error) {
+      setWebhookTests((prev) => ({ ...prev, [index]: String(error) }));
+    }
+  };
+
+  const handleAutostartChange = async (enabled: boolean, minimized: boolean) => {
+    try {
+      await invoke('set_autostart', { enabled, minimized });
+      setAutostart({ enabled, minimized });
+    } catch (error) {
+      alert(String(error));
+    }
+  };
+
+  // Registry writes that need administrator rights go through a UAC prompt
+  const runElevated = async (command: string, successMessage: string) => {
+    if (!confirm(t('contextMenu.elevatePrompt'))) {
+      alert(t('contextMenu.adminRequired'));
+      return;
+    }
+    try {
+      await invoke(command);
+      await checkContextMenuStatus();
+      alert(successMessage);
+    } catch (error) {
+      console.error(`${command} failed:`, error);
+      alert(`${t('contextMenu.adminRequired')}\n${error}`);
+    }
+  };
+
+  // Windows registers Explorer verbs; Linux and macOS install file manager entries
+  const isWindows = navigator.userAgent.toLowerCase().includes('windows');
+
   const handleAddContextMenu = async () => {
     try {
-      await invoke('add_context_menu');
+      await invoke(isWindows ? 'add_context_menu' : 'install_file_manager_integration');
       await checkContextMenuStatus();
       alert(t('contextMenu.added'));
     } catch (error: unknown) {
       // Tauri errors can come as string or object with message
       const errorStr = typeof error === 'string' ? error : String(error);
       if (errorStr === 'ADMIN_REQUIRED' || errorStr.includes('ADMIN_REQUIRED')) {
-        alert(t('contextMenu.adminRequired'));
+        await runElevated('elevate_and_register_context_menu', t('contextMenu.added'));
       } else {
         console.error('Failed to add context menu:', error);
         alert(t('contextMenu.errorAdding'));
@@ -232,14 +420,14 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
 
   const handleRemoveContextMenu = async () => {
     try {
-      await invoke('remove_context_menu');
+      await invoke(isWindows ? 'remove_context_menu' : 'remove_file_manager_integration');
       await checkContextMenuStatus();
       alert(t('contextMenu.removed'));
     } catch (error: unknown) {
       // Tauri errors can come as string or object with message
       const errorStr = typeof error === 'string' ? error : String(error);
       if (errorStr === 'ADMIN_REQUIRED' || errorStr.includes('ADMIN_REQUIRED')) {
-        alert(t('contextMenu.adminRequired'));
+        await runElevated('elevate_and_unregister_context_menu', t('contextMenu.removed'));
       } else {
         console.error('Failed to remove context menu:', error);
         alert(t('contextMenu.errorRemoving'));
@@ -271,6 +459,15 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
     }
   };
 
+  const handleClearMediaCache = async () => {
+    try {
+      const count = await invoke<number>('clear_media_cache');
+      alert(t('diagnostics.mediaCacheCleared').replace('{count}', String(count)));
+    } catch (error) {
+      console.error('Failed to clear media cache:', error);
+    }
+  };
+
   // Update handlers
   const handleCheckUpdate = async () => {
     await UpdateService.checkForUpdates();
@@ -288,6 +485,20 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
     await UpdateService.applyUpdate();
   };
 
+  const loadTrashedSources = () => {
+    invoke<TrashedSource[]>('list_trashed_sources').then(setTrashedSources).catch(() => {});
+  };
+
+  const handleRestoreSource = async (originalPath: string) => {
+    try {
+      await invoke('restore_trashed_source', { originalPath });
+      setRestoreMessage(`${t('settings.trashedRestored')} ${originalPath}`);
+    } catch (error) {
+      setRestoreMessage(String(error));
+    }
+    loadTrashedSources();
+  };
+
   const persistSettings = async () => {
     try {
       const settings = await invoke<any>('load_settings');
@@ -298,6 +509,31 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
           modifiedTheme: modifiedTheme,
           language,
           output_suffix: outputSuffix,
+          outputTemplate: outputTemplate.trim() || DEFAULT_OUTPUT_TEMPLATE,
+          outputDirectory: outputDirectory.trim(),
+          mirrorSourceFolders,
+          stageNetworkFiles,
+          folderExcludePatterns: folderExcludePatterns.split(',').map((p) => p.trim()).filter(Boolean),
+          notifications: { enabled: notificationsEnabled, whenFocused: notifyWhenFocused },
+          showCrashReports,
+          updateChannel,
+          network,
+          queueSchedule,
+          idleTrigger,
+          jobTimeoutMinutes,
+          hwDecode,
+          renderPriority,
+          batteryAction,
+          thermal,
+          verifyOutput,
+          trashSourceAfterSuccess,
+          quarantineFailedInputs,
+          shutdownTimeoutSecs,
+          httpApi,
+          webhooks: webhooks.filter((webhook) => webhook.url.trim()),
+          uploadDestinations,
+          hooks,
+          shortcuts: { pauseResume: pauseShortcut.trim(), stopAll: stopShortcut.trim() },
           use_background_image: useImageBackground,
           background_image_path: backgroundImagePath,
           glassOpacity: glassOpacity,
@@ -333,6 +569,7 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
           <div className="setting-group" style={{ flex: '1 1 200px', minWidth: '200px' }}>
             <label>{t('settings.theme')}</label>
             <select value={themeName} onChange={(e) => setThemeName(e.target.value)} style={{ marginBottom: '8px' }}>
+              <option value="auto">{t('settings.themeNames.auto') || 'System (auto)'}</option>
               <option value="light">{t('settings.themeNames.light') || 'Cloud Day'}</option>
               <option value="dark-red">{t('settings.themeNames.darkRed') || 'Crimson Cellar'}</option>
               <option value="blue-ocean">{t('settings.themeNames.blueOcean') || 'Blue Ocean'}</option>
@@ -548,6 +785,539 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
                  placeholder="_szhatoe" />
         </div>
 
+        <div className="setting-group">
+          <label>{t('settings.outputTemplate')}</label>
+          <input type="text" value={outputTemplate} onChange={(e) => setOutputTemplate(e.target.value)}
+                 placeholder={DEFAULT_OUTPUT_TEMPLATE}
+                 title="{parent} {name} {ext} {suffix} {codec} {width} {height} {preset} {date} {time}" />
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.outputDirectory')}</label>
+          <input type="text" value={outputDirectory} onChange={(e) => setOutputDirectory(e.target.value)} />
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={mirrorSourceFolders}
+              disabled={!outputDirectory.trim()}
+              onChange={(e) => setMirrorSourceFolders(e.target.checked)}
+            />
+            {t('settings.mirrorSourceFolders')}
+          </label>
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={stageNetworkFiles}
+              onChange={(e) => setStageNetworkFiles(e.target.checked)}
+            />
+            {t('settings.stageNetworkFiles')}
+          </label>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.folderExcludePatterns')}</label>
+          <input type="text" value={folderExcludePatterns} onChange={(e) => setFolderExcludePatterns(e.target.value)}
+                 placeholder="*.tmp.mp4, Proxy/" />
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={notificationsEnabled}
+              onChange={(e) => setNotificationsEnabled(e.target.checked)}
+            />
+            {t('settings.notifications')}
+          </label>
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={notifyWhenFocused}
+              disabled={!notificationsEnabled}
+              onChange={(e) => setNotifyWhenFocused(e.target.checked)}
+            />
+            {t('settings.notifyWhenFocused')}
+          </label>
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={autostart.enabled}
+              onChange={(e) => handleAutostartChange(e.target.checked, autostart.minimized)}
+            />
+            {t('settings.autostart')}
+          </label>
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={autostart.minimized}
+              disabled={!autostart.enabled}
+              onChange={(e) => handleAutostartChange(true, e.target.checked)}
+            />
+            {t('settings.autostartMinimized')}
+          </label>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.pauseShortcut')}</label>
+          <input type="text" value={pauseShortcut} onChange={(e) => setPauseShortcut(e.target.value)}
+                 onBlur={applyShortcuts} placeholder="CmdOrCtrl+Alt+P" />
+          <label>{t('settings.stopShortcut')}</label>
+          <input type="text" value={stopShortcut} onChange={(e) => setStopShortcut(e.target.value)}
+                 onBlur={applyShortcuts} placeholder="CmdOrCtrl+Alt+S" />
+          {shortcutError && (
+            <div style={{ fontSize: 12, color: theme.colors.error }}>{shortcutError}</div>
+          )}
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.schedule')}</label>
+          <select
+            value={queueSchedule.mode}
+            onChange={(e) => setQueueSchedule({ ...queueSchedule, mode: e.target.value as ScheduleMode })}
+          >
+            <option value="off">{t('settings.scheduleOff')}</option>
+            <option value="start_at">{t('settings.scheduleStartAt')}</option>
+            <option value="window">{t('settings.scheduleWindow')}</option>
+          </select>
+          {queueSchedule.mode === 'start_at' && (
+            <input type="datetime-local" value={queueSchedule.startAt} style={{ marginTop: 4 }}
+                   onChange={(e) => setQueueSchedule({ ...queueSchedule, startAt: e.target.value })} />
+          )}
+          {queueSchedule.mode === 'window' && (
+            <>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 4 }}>
+                <input type="time" value={queueSchedule.windowStart}
+                       onChange={(e) => setQueueSchedule({ ...queueSchedule, windowStart: e.target.value })} />
+                <span>–</span>
+                <input type="time" value={queueSchedule.windowEnd}
+                       onChange={(e) => setQueueSchedule({ ...queueSchedule, windowEnd: e.target.value })} />
+              </div>
+              <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+                <input
+                  type="checkbox"
+                  checked={queueSchedule.suspendRunning}
+                  onChange={(e) => setQueueSchedule({ ...queueSchedule, suspendRunning: e.target.checked })}
+                />
+                {t('settings.scheduleSuspend')}
+              </label>
+            </>
+          )}
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={idleTrigger.enabled}
+              onChange={(e) => setIdleTrigger({ ...idleTrigger, enabled: e.target.checked })}
+            />
+            {t('settings.idleTrigger')}
+          </label>
+          {idleTrigger.enabled && (
+            <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 4 }}>
+              <input
+                type="number"
+                min={1}
+                max={240}
+                value={idleTrigger.idleMinutes}
+                style={{ width: 80 }}
+                onChange={(e) => setIdleTrigger({ ...idleTrigger, idleMinutes: Math.max(1, parseInt(e.target.value, 10) || 1) })}
+              />
+              <span>{t('settings.idleMinutes')}</span>
+            </div>
+          )}
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.idleTriggerHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.jobTimeout')}</label>
+          <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+            <input
+              type="number"
+              min={0}
+              max={1440}
+              value={jobTimeoutMinutes}
+              style={{ width: 80 }}
+              onChange={(e) => setJobTimeoutMinutes(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+            <span>{t('settings.jobTimeoutMinutes')}</span>
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input type="checkbox" checked={hwDecode} onChange={(e) => setHwDecode(e.target.checked)} />
+            {t('settings.hwDecode')}
+          </label>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.hwDecodeHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.renderPriority')}</label>
+          <select value={renderPriority} onChange={(e) => setRenderPriority(e.target.value as RenderPriority)}>
+            <option value="normal">{t('settings.renderPriorityNormal')}</option>
+            <option value="below_normal">{t('settings.renderPriorityBelowNormal')}</option>
+            <option value="idle">{t('settings.renderPriorityIdle')}</option>
+          </select>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.batteryAction')}</label>
+          <select value={batteryAction} onChange={(e) => setBatteryAction(e.target.value as BatteryAction)}>
+            <option value="none">{t('settings.batteryActionNone')}</option>
+            <option value="pause">{t('settings.batteryActionPause')}</option>
+            <option value="power_saver">{t('settings.batteryActionPowerSaver')}</option>
+          </select>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.thermal')}</label>
+          <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+            <span>{t('settings.thermalCpu')}</span>
+            <input
+              type="number"
+              min={50}
+              max={110}
+              value={thermal.cpuLimitC}
+              style={{ width: 80 }}
+              onChange={(e) => setThermal({ ...thermal, cpuLimitC: Math.max(50, parseInt(e.target.value, 10) || 50) })}
+            />
+            <span>{t('settings.thermalGpu')}</span>
+            <input
+              type="number"
+              min={50}
+              max={110}
+              value={thermal.gpuLimitC}
+              style={{ width: 80 }}
+              onChange={(e) => setThermal({ ...thermal, gpuLimitC: Math.max(50, parseInt(e.target.value, 10) || 50) })}
+            />
+            <span>°C</span>
+          </div>
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={thermal.pauseOnOverheat}
+              onChange={(e) => setThermal({ ...thermal, pauseOnOverheat: e.target.checked })}
+            />
+            {t('settings.thermalPause')}
+          </label>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.verifyOutput')}</label>
+          <select value={verifyOutput} onChange={(e) => setVerifyOutput(e.target.value as VerifyMode)}>
+            <option value="off">{t('settings.verifyOutputOff')}</option>
+            <option value="duration">{t('settings.verifyOutputDuration')}</option>
+            <option value="decode">{t('settings.verifyOutputDecode')}</option>
+          </select>
+          <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={trashSourceAfterSuccess}
+              onChange={(e) => setTrashSourceAfterSuccess(e.target.checked)}
+            />
+            {t('settings.trashSourceAfterSuccess')}
+          </label>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.trashSourceHint')}
+          </div>
+          {trashedSources.length > 0 && (
+            <div style={{ marginTop: 8 }}>
+              <div style={{ fontSize: 13 }}>{t('settings.trashedSources')}</div>
+              <div style={{ maxHeight: 160, overflowY: 'auto', marginTop: 4 }}>
+                {trashedSources.map((source) => (
+                  <div
+                    key={`${source.job_id}-${source.trashed_at}`}
+                    style={{ display: 'flex', alignItems: 'center', gap: 8, fontSize: 12, marginBottom: 4 }}
+                  >
+                    <span style={{ flex: 1, wordBreak: 'break-all' }} title={source.output_path}>
+                      {source.original_path}
+                    </span>
+                    <span style={{ color: theme.colors.textSecondary }}>
+                      {new Date(source.trashed_at).toLocaleString()}
+                    </span>
+                    <button type="button" onClick={() => void handleRestoreSource(source.original_path)}>
+                      {t('settings.trashedRestore')}
+                    </button>
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+          {restoreMessage && (
+            <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>{restoreMessage}</div>
+          )}
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={quarantineFailedInputs}
+              onChange={(e) => setQuarantineFailedInputs(e.target.checked)}
+            />
+            {t('settings.quarantineFailedInputs')}
+          </label>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.quarantineFailedInputsHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.shutdownTimeout')}</label>
+          <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+            <input
+              type="number"
+              min={0}
+              max={600}
+              value={shutdownTimeoutSecs}
+              style={{ width: 80 }}
+              onChange={(e) => setShutdownTimeoutSecs(Math.max(0, parseInt(e.target.value, 10) || 0))}
+            />
+            <span>{t('settings.shutdownTimeoutSecs')}</span>
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={httpApi.enabled}
+              onChange={(e) => void handleHttpApiChange({ enabled: e.target.checked })}
+            />
+            {t('settings.httpApi')}
+          </label>
+          {httpApi.enabled && (
+            <>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 4 }}>
+                <span>{t('settings.httpApiPort')}</span>
+                <input
+                  type="number"
+                  min={1024}
+                  max={65535}
+                  value={httpApi.port}
+                  style={{ width: 90 }}
+                  onChange={(e) => setHttpApi({ ...httpApi, port: Math.min(65535, Math.max(1, parseInt(e.target.value, 10) || 8765)) })}
+                  onBlur={applyHttpApi}
+                />
+              </div>
+              <label className="checkbox-label" style={{ marginTop: '4px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+                <input
+                  type="checkbox"
+                  checked={httpApi.allowLan}
+                  onChange={(e) => void handleHttpApiChange({ allowLan: e.target.checked })}
+                />
+                {t('settings.httpApiAllowLan')}
+              </label>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 4 }}>
+                <span>{t('settings.httpApiToken')}</span>
+                <input type="text" readOnly value={httpApi.token} style={{ flex: 1, fontFamily: 'monospace' }}
+                       onFocus={(e) => e.target.select()} />
+                <button
+                  type="button"
+                  onClick={async () => handleHttpApiChange({ token: await invoke<string>('generate_api_token') })}
+                >
+                  {t('settings.httpApiRegenerate')}
+                </button>
+              </div>
+            </>
+          )}
+          {httpApiError && (
+            <div style={{ fontSize: 12, color: theme.colors.error }}>{httpApiError}</div>
+          )}
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.httpApiHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.webhooks')}</label>
+          {webhooks.map((webhook, index) => (
+            <div key={index} style={{ marginTop: 4 }}>
+              <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                <input type="text" value={webhook.url} placeholder="https://discord.com/api/webhooks/..." style={{ flex: 1 }}
+                       onChange={(e) => updateWebhook(index, { url: e.target.value })} />
+                <button type="button" disabled={!webhook.url.trim()} onClick={() => handleTestWebhook(index)}>
+                  {t('settings.webhookTest')}
+                </button>
+                <button type="button" onClick={() => removeWebhook(index)}>✕</button>
+              </div>
+              <div style={{ display: 'flex', flexWrap: 'wrap', gap: 12, marginTop: 4 }}>
+                {([
+                  ['onComplete', 'settings.webhookOnComplete'],
+                  ['onFail', 'settings.webhookOnFail'],
+                  ['onQueueFinished', 'settings.webhookOnQueueFinished'],
+                ] as const).map(([field, label]) => (
+                  <label key={field} className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+                    <input
+                      type="checkbox"
+                      checked={webhook[field]}
+                      onChange={(e) => updateWebhook(index, { [field]: e.target.checked })}
+                    />
+                    {t(label)}
+                  </label>
+                ))}
+              </div>
+              {webhookTests[index] && (
+                <div style={{ fontSize: 12, color: theme.colors.textSecondary }}>{webhookTests[index]}</div>
+              )}
+            </div>
+          ))}
+          <button type="button" style={{ marginTop: 4 }} onClick={() => setWebhooks([...webhooks, { ...DEFAULT_WEBHOOK }])}>
+            {t('settings.webhookAdd')}
+          </button>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.webhooksHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.uploadDestinations')}</label>
+          {uploadDestinations.map((destination, index) => {
+            const field = (key: keyof UploadDestination, placeholder: string, type = 'text') => (
+              <input
+                type={type}
+                value={String(destination[key])}
+                placeholder={placeholder}
+                style={{ flex: 1, minWidth: 120 }}
+                onChange={(e) => updateDestination(index, { [key]: type === 'number' ? parseInt(e.target.value, 10) || 0 : e.target.value })}
+              />
+            );
+            return (
+              <div key={destination.id} style={{ marginTop: 6 }}>
+                <div style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
+                  {field('name', t('settings.uploadName'))}
+                  <select
+                    value={destination.kind}
+                    onChange={(e) => updateDestination(index, { kind: e.target.value as UploadKind })}
+                  >
+                    <option value="sftp">SFTP</option>
+                    <option value="s3">S3</option>
+                    <option value="webdav">WebDAV</option>
+                  </select>
+                  <button type="button" onClick={() => setUploadDestinations(uploadDestinations.filter((_, i) => i !== index))}>✕</button>
+                </div>
+                <div style={{ display: 'flex', flexWrap: 'wrap', gap: 8, marginTop: 4 }}>
+                  {destination.kind === 'sftp' && (
+                    <>
+                      {field('host', 'example.com')}
+                      {field('port', '22', 'number')}
+                      {field('username', t('settings.uploadUsername'))}
+                      {field('keyPath', t('settings.uploadKeyPath'))}
+                      {field('remotePath', '/srv/videos')}
+                    </>
+                  )}
+                  {destination.kind === 's3' && (
+                    <>
+                      {field('url', 'https://s3.eu-central-1.amazonaws.com')}
+                      {field('region', 'us-east-1')}
+                      {field('bucket', t('settings.uploadBucket'))}
+                      {field('accessKey', 'Access key')}
+                      {field('secretKey', 'Secret key', 'password')}
+                      {field('remotePath', 'renders/')}
+                    </>
+                  )}
+                  {destination.kind === 'webdav' && (
+                    <>
+                      {field('url', 'https://cloud.example.com/remote.php/dav/files/me/Videos')}
+                      {field('username', t('settings.uploadUsername'))}
+                      {field('password', t('settings.uploadPassword'), 'password')}
+                    </>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+          <button type="button" style={{ marginTop: 4 }} onClick={addDestination}>
+            {t('settings.uploadAdd')}
+          </button>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.uploadHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.hooks')}</label>
+          {([
+            ['preRender', 'settings.hookPreRender'],
+            ['postRenderSuccess', 'settings.hookPostSuccess'],
+            ['postRenderFailure', 'settings.hookPostFailure'],
+          ] as const).map(([key, label]) => (
+            <div key={key} style={{ display: 'flex', gap: 8, alignItems: 'center', marginTop: 4 }}>
+              <span style={{ minWidth: 160 }}>{t(label)}</span>
+              <input
+                type="text"
+                value={hooks[key]}
+                placeholder={t('settings.hookNone')}
+                onChange={(e) => setHooks({ ...hooks, [key]: e.target.value })}
+                style={{ flex: 1 }}
+              />
+              <button
+                type="button"
+                onClick={async () => {
+                  const selected = await open({ multiple: false });
+                  if (selected && typeof selected === 'string') {
+                    setHooks({ ...hooks, [key]: selected });
+                  }
+                }}
+              >
+                {t('settings.hookBrowse')}
+              </button>
+            </div>
+          ))}
+          <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginTop: 8 }}>
+            <span>{t('settings.hookTimeout')}</span>
+            <input
+              type="number"
+              min={1}
+              value={hooks.timeoutSecs}
+              onChange={(e) => setHooks({ ...hooks, timeoutSecs: Math.max(1, parseInt(e.target.value, 10) || 600) })}
+              style={{ width: 90 }}
+            />
+          </div>
+          <div style={{ fontSize: 12, color: theme.colors.textSecondary, marginTop: 4 }}>
+            {t('settings.hooksHint')}
+          </div>
+        </div>
+
+        <div className="setting-group">
+          <label>{t('settings.proxy')}</label>
+          <select
+            value={network.proxyMode}
+            onChange={(e) => setNetwork({ ...network, proxyMode: e.target.value as ProxyMode })}
+          >
+            <option value="system">{t('settings.proxySystem')}</option>
+            <option value="none">{t('settings.proxyNone')}</option>
+            <option value="manual">{t('settings.proxyManual')}</option>
+          </select>
+          {network.proxyMode === 'manual' && (
+            <div style={{ display: 'flex', flexWrap: 'wrap', gap: 8, marginTop: 4 }}>
+              <input type="text" value={network.proxyHost} placeholder="proxy.example.com"
+                     onChange={(e) => setNetwork({ ...network, proxyHost: e.target.value })}
+                     style={{ flex: '2 1 200px' }} />
+              <input type="number" min={0} max={65535} value={network.proxyPort || ''} placeholder="3128"
+                     onChange={(e) => setNetwork({ ...network, proxyPort: Number(e.target.value) || 0 })}
+                     style={{ flex: '1 1 80px' }} />
+              <input type="text" value={network.proxyUsername} placeholder={t('settings.proxyUsername')}
+                     onChange={(e) => setNetwork({ ...network, proxyUsername: e.target.value })}
+                     style={{ flex: '1 1 140px' }} />
+              <input type="password" value={network.proxyPassword} placeholder={t('settings.proxyPassword')}
+                     onChange={(e) => setNetwork({ ...network, proxyPassword: e.target.value })}
+                     style={{ flex: '1 1 140px' }} />
+            </div>
+          )}
+          <label>{t('settings.caCertificate')}</label>
+          <input type="text" value={network.caCertificate} placeholder="corp-ca.pem"
+                 onChange={(e) => setNetwork({ ...network, caCertificate: e.target.value })} />
+        </div>
+
         <div className="setting-group">
           <label>{t('ffmpeg.configurationLabel')}</label>
           <button 
@@ -580,10 +1350,10 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
                     return;
                   }
                   
-                  const available = await invoke<boolean>('check_gpu_compatibility');
-                  setGpuAvailable(!!available);
-                  await invoke('write_log', { message: `GPU NVENC available: ${available}` });
-                  alert(available ? t('gpu.compatibleFound') : t('gpu.notFoundOrUnavailable'));
+                  const gpu = await invoke<GpuCompatibility>('check_gpu_compatibility');
+                  setGpuAvailable(gpu.available);
+                  await invoke('write_log', { message: `GPU NVENC available: ${gpu.available} (${gpu.status})` });
+                  alert(gpu.available ? t('gpu.compatibleFound') : `${t('gpu.notFoundOrUnavailable')}\n${gpu.message}`);
                 } catch (e) {
                   console.error('GPU check error', e);
                   alert(t('gpu.checkError'));
@@ -601,7 +1371,7 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
 
         {/* Context Menu Section */}
         <div className="setting-group">
-          <label>{t('contextMenu.title')}</label>
+          <label>{t(isWindows ? 'contextMenu.title' : 'contextMenu.titleFileManager')}</label>
           <div
             style={{
               display: 'flex',
@@ -671,8 +1441,17 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
             </button>
 
             <div style={{ fontSize: 12, color: theme.colors.textSecondary }}>
-              {t('contextMenu.description')}
+              {t(isWindows ? 'contextMenu.description' : 'contextMenu.descriptionFileManager')}
             </div>
+
+            <label className="checkbox-label" style={{ display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+              <input
+                type="checkbox"
+                checked={urlProtocolRegistered}
+                onChange={(e) => handleToggleUrlProtocol(e.target.checked)}
+              />
+              {t('contextMenu.urlProtocol')}
+            </label>
           </div>
         </div>
 
@@ -710,6 +1489,33 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
           </div>
         </div>
 
+        <div className="setting-group">
+          <label>{t('diagnostics.title')}</label>
+          <label className="checkbox-label" style={{ marginBottom: '8px', display: 'flex', alignItems: 'center', gap: '8px', cursor: 'pointer' }}>
+            <input
+              type="checkbox"
+              checked={showCrashReports}
+              onChange={(e) => setShowCrashReports(e.target.checked)}
+            />
+            {t('diagnostics.showCrashReports')}
+          </label>
+          <button
+            onClick={handleClearMediaCache}
+            style={{
+              background: 'transparent',
+              color: theme.colors.primary,
+              border: `1px solid ${theme.colors.border}`,
+              borderRadius: 6,
+              padding: '6px 12px',
+              marginBottom: '8px',
+              cursor: 'pointer',
+            }}
+          >
+            {t('diagnostics.clearMediaCache')}
+          </button>
+          <DiagnosticsPanel />
+        </div>
+
         <div className="setting-group">
           <label>{t('app.version')}</label>
           <div style={{ 
@@ -722,6 +1528,18 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
             {/* Current version */}
             <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center' }}>
               <span style={{ color: theme.colors.textSecondary }}>v{APP_VERSION}</span>
+
+              {/* Release channel */}
+              <label style={{ display: 'flex', alignItems: 'center', gap: 6, fontSize: 13 }}>
+                {t('update.channel')}
+                <select
+                  value={updateChannel}
+                  onChange={(e) => setUpdateChannel(e.target.value as UpdateChannel)}
+                >
+                  <option value="stable">{t('update.channelStable')}</option>
+                  <option value="beta">{t('update.channelBeta')}</option>
+                </select>
+              </label>
               
               {/* Check for updates button */}
               <button
@@ -825,6 +1643,20 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
                       {updateState.progress.percent}%
                     </span>
                   )}
+                  <button
+                    onClick={() => UpdateService.cancelDownload()}
+                    style={{
+                      marginLeft: 'auto',
+                      background: 'transparent',
+                      color: theme.colors.textSecondary,
+                      border: 'none',
+                      cursor: 'pointer',
+                      fontSize: 13,
+                      padding: '4px 8px',
+                    }}
+                  >
+                    {t('update.cancelDownload')}
+                  </button>
                 </div>
                 {/* Progress bar */}
                 {updateState.progress && updateState.progress.total > 0 && (
@@ -905,6 +1737,32 @@ const GeneralSettings: React.FC<GeneralSettingsProps> = ({ onBack }) => {
                 {updateState.error}
               </div>
             )}
+
+            {/* Previous version kept by the last update */}
+            {rollbackVersion && (
+              <div style={{ 
+                display: 'flex', 
+                justifyContent: 'space-between',
+                alignItems: 'center',
+                fontSize: 13,
+                color: theme.colors.textSecondary,
+              }}>
+                <span>{t('update.rollbackAvailable')} v{rollbackVersion}</span>
+                <button
+                  onClick={() => invoke('rollback_update').catch((e) => alert(String(e)))}
+                  style={{
+                    background: 'transparent',
+                    color: theme.colors.primary,
+                    border: 'none',
+                    cursor: 'pointer',
+                    fontSize: 13,
+                    padding: '4px 8px',
+                  }}
+                >
+                  {t('update.rollback')}
+                </button>
+              </div>
+            )}
           </div>
         </div>
 
@@ -1,6 +1,7 @@
 import React, { useCallback, useEffect, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { open } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
 import { AnimatePresence, motion } from 'framer-motion';
 import { useLanguage } from '../contexts/LanguageContext';
 import { useTheme } from '../contexts/ThemeContext';
@@ -9,8 +10,13 @@ import RenderModeSelector from '../components/RenderModeSelector';
 import PreviewPanel from '../components/PreviewPanel';
 import useRenderQueue from '../hooks/useRenderQueue';
 import StatisticsPanel from '../components/StatisticsPanel';
+import CrashReportsDialog from '../components/CrashReportsDialog';
+import FrameExportDialog from '../components/FrameExportDialog';
+import FolderImportDialog from '../components/FolderImportDialog';
+import JobHistoryPanel from '../components/JobHistoryPanel';
+import PostQueueActionControl from '../components/PostQueueActionControl';
 import { UpdateService, UpdateState } from '../services/UpdateService';
-import { Film, Volume2, Settings, BarChart3, Folder, Play, Pause, Square, RefreshCw, Sparkles, HardDrive, Check, X, Clock, AlertTriangle, Trash2 } from 'lucide-react';
+import { Film, Volume2, Settings, BarChart3, History, Folder, FolderPlus, Globe, Play, Pause, Square, RefreshCw, Sparkles, HardDrive, Check, X, Image as ImageIcon, Clock, AlertTriangle, Trash2 } from 'lucide-react';
 import type { RenderJob } from '../services/RenderService';
 import type {
   AppPreset,
@@ -18,10 +24,21 @@ import type {
   AudioSettings,
   MainScreenSettings,
   WatermarkSettings,
+  InterruptedJob,
+  UploadDestination,
 } from '../types';
 import '../styles/MainWindow.css';
 console.log("Импорты завершены")
 
+/** A download started with enqueue_url */
+interface UrlDownload {
+  id: string;
+  fileName: string;
+  downloaded: number;
+  total: number;
+  error?: string;
+}
+
 const FolderSyncIcon: React.FC<{ color: string }> = ({ color }) => (
   <svg
     aria-hidden
@@ -82,6 +99,8 @@ interface MainWindowProps {
   selectedPresetName: string;
   setSelectedPresetName: React.Dispatch<React.SetStateAction<string>>;
   cliFiles?: string[];
+  cliAutoStart?: boolean; // Start rendering once the CLI files are queued (--preset launches)
+  cliUnattended?: boolean; // CLI files come from a folder import or the HTTP API (see quarantine.rs)
   onCliFilesProcessed?: () => void;
 }
 
@@ -119,6 +138,8 @@ const MainWindow: React.FC<MainWindowProps> = ({
   selectedPresetName,
   setSelectedPresetName,
   cliFiles,
+  cliAutoStart,
+  cliUnattended,
   onCliFilesProcessed,
 }) => {
   // console.log('MainWindow render');
@@ -137,7 +158,10 @@ const MainWindow: React.FC<MainWindowProps> = ({
     pendingJobs,
     addFiles,
     addToQueue,
+    addHistoryJob,
+    findDuplicates,
     updateJobTrim,
+    setJobDependencies,
     removeJob,
     clearCompleted,
     start,
@@ -155,11 +179,17 @@ const MainWindow: React.FC<MainWindowProps> = ({
   const minTrimDurationSec = 1;
 
   const [showStats, setShowStats] = useState(false);
+  const [showHistory, setShowHistory] = useState(false);
   const [showPreview, setShowPreview] = useState(false);
   const [selectedPreviewPath, setSelectedPreviewPath] = useState<string>('');
   const [updateAvailable, setUpdateAvailable] = useState(false);
   const [networkWarning, setNetworkWarning] = useState<string | null>(null);
   const [trimFramePreview, setTrimFramePreview] = useState<TrimFramePreviewState | null>(null);
+  const [uploadDestinations, setUploadDestinations] = useState<UploadDestination[]>([]);
+  const [videoUrl, setVideoUrl] = useState('');
+  const [frameExportPath, setFrameExportPath] = useState<string | null>(null);
+  const [showFolderImport, setShowFolderImport] = useState(false);
+  const [urlDownloads, setUrlDownloads] = useState<UrlDownload[]>([]);
   const trimPreviewDebounceRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const trimPreviewHideRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const trimPreviewRequestRef = useRef(0);
@@ -183,6 +213,51 @@ const MainWindow: React.FC<MainWindowProps> = ({
     return () => clearTimeout(timer);
   }, []);
 
+  // Offer to kill FFmpeg processes that outlived a crashed session,
+  // then to queue the jobs it left unfinished again
+  useEffect(() => {
+    const checkOrphanedProcesses = async () => {
+      try {
+        const orphans = await invoke<{ pid: number }[]>('list_orphaned_processes');
+        if (orphans.length === 0) return;
+        if (!confirm(t('main.orphanedProcessesPrompt').replace('{count}', String(orphans.length)))) return;
+        const result = await invoke<{ killed: number; removed_outputs: string[] }>('cleanup_orphaned_processes');
+        console.log('[MainWindow] Orphaned processes cleaned up:', result);
+      } catch (error) {
+        console.error('[MainWindow] Orphaned process cleanup failed:', error);
+      }
+    };
+    const checkInterruptedJobs = async () => {
+      try {
+        const interrupted = await invoke<InterruptedJob[]>('list_interrupted_jobs');
+        const unfinished = interrupted.filter((job) => job.state !== 'complete');
+        if (unfinished.length === 0) return;
+        if (!confirm(t('main.resumeInterruptedPrompt').replace('{count}', String(unfinished.length)))) {
+          await invoke('dismiss_interrupted_jobs');
+          return;
+        }
+        const jobs = await invoke<InterruptedJob[]>('resume_interrupted_jobs');
+        // Re-queued jobs get new ids; dependencies on jobs that finished are dropped
+        const newIds = new Map<string, string>();
+        for (const item of jobs) {
+          const job = item.job
+            ? await addHistoryJob(item.job)
+            : await addToQueue(item.input_path, item.output_path, item.trim_start_sec, item.trim_end_sec);
+          newIds.set(item.id, job.id);
+        }
+        for (const item of jobs) {
+          const dependsOn = item.depends_on.flatMap((id) => newIds.get(id) ?? []);
+          if (dependsOn.length > 0) {
+            setJobDependencies(newIds.get(item.id)!, dependsOn);
+          }
+        }
+      } catch (error) {
+        console.error('[MainWindow] Resuming interrupted jobs failed:', error);
+      }
+    };
+    checkOrphanedProcesses().then(checkInterruptedJobs);
+  }, []);
+
   // Check active VPN/proxy on startup and show safety warning
   useEffect(() => {
     const checkNetworkRisk = async () => {
@@ -233,6 +308,61 @@ const MainWindow: React.FC<MainWindowProps> = ({
     }
   }, [jobs, selectedPreviewPath]);
 
+  // Upload destinations are edited in the settings, which unmount this window
+  useEffect(() => {
+    invoke<{ uploadDestinations?: UploadDestination[] }>('load_settings')
+      .then((settings) => setUploadDestinations(settings.uploadDestinations || []))
+      .catch((error) => console.error('Failed to load upload destinations:', error));
+  }, []);
+
+  // URL downloads report here; a finished one arrives in the queue via files-received
+  useEffect(() => {
+    const update = (id: string, patch: Partial<UrlDownload>) =>
+      setUrlDownloads((prev) => prev.map((d) => (d.id === id ? { ...d, ...patch } : d)));
+    const unlisteners = [
+      listen<{ id: string; file_name: string; downloaded: number; total: number }>('url-download-progress', (event) => {
+        const { id, file_name, downloaded, total } = event.payload;
+        update(id, { fileName: file_name, downloaded, total });
+      }),
+      listen<{ id: string }>('url-download-complete', (event) => {
+        setUrlDownloads((prev) => prev.filter((d) => d.id !== event.payload.id));
+      }),
+      listen<{ id: string; error: string | null }>('url-download-error', (event) => {
+        update(event.payload.id, { error: event.payload.error || t('main.urlDownloadFailed') });
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, [t]);
+
+  const handleEnqueueUrl = async () => {
+    const url = videoUrl.trim();
+    if (!url) return;
+    try {
+      const id = await invoke<string>('enqueue_url', {
+        url,
+        preset: selectedPresetName || null,
+        outputDir: mainScreenSettings.saveInSourceDirectory ? null : mainScreenSettings.customOutputPath || null,
+      });
+      setUrlDownloads((prev) => [...prev, { id, fileName: url, downloaded: 0, total: 0 }]);
+      setVideoUrl('');
+    } catch (error) {
+      console.error('Failed to download from URL:', error);
+      setUrlDownloads((prev) => [...prev, { id: `rejected-${Date.now()}`, fileName: url, downloaded: 0, total: 0, error: String(error) }]);
+    }
+  };
+
+  const handleCancelUrlDownload = (download: UrlDownload) => {
+    if (download.error) {
+      setUrlDownloads((prev) => prev.filter((d) => d.id !== download.id));
+      return;
+    }
+    invoke('cancel_url_download', { id: download.id }).catch((error) =>
+      console.error('Failed to cancel URL download:', error)
+    );
+  };
+
   const handleToggleSaveInSourceDirectory = useCallback(() => {
     setMainScreenSettings((prev) => ({
       ...prev,
@@ -248,36 +378,68 @@ const MainWindow: React.FC<MainWindowProps> = ({
   }, [gpuAvailable, setRenderMode]);
 
   const closeStats = useCallback(() => setShowStats(false), []);
+  const closeHistory = useCallback(() => setShowHistory(false), []);
 
   // Close on Escape
   useEffect(() => {
-    if (!showStats) return;
+    if (!showStats && !showHistory) return;
     const onKey = (e: KeyboardEvent) => {
       if (e.key === 'Escape') {
         closeStats();
+        closeHistory();
       }
     };
     window.addEventListener('keydown', onKey);
     return () => window.removeEventListener('keydown', onKey);
-  }, [showStats, closeStats]);
+  }, [showStats, showHistory, closeStats, closeHistory]);
 
   // Update RenderService settings when preset changes
   useEffect(() => {
     updateSettings(videoSettings, audioSettings, watermarkSettings, mainScreenSettings, undefined, selectedPresetName);
   }, [videoSettings, audioSettings, watermarkSettings, mainScreenSettings, selectedPresetName, updateSettings]);
 
+  // Ask before queueing files that are already queued, rendering or compressed recently;
+  // returns the paths to queue
+  const confirmDuplicates = useCallback(async (paths: string[]): Promise<string[]> => {
+    try {
+      const conflicts = await findDuplicates(paths);
+      if (conflicts.length === 0) return paths;
+      const kindLabel = {
+        running: t('main.duplicateRunning'),
+        queued: t('main.duplicateQueued'),
+        recently_compressed: t('main.duplicateRecent'),
+      };
+      const files = conflicts
+        .slice(0, 10)
+        .map((c) => `• ${c.input_path.split(/[\\/]/).pop()} (${kindLabel[c.kind]})`)
+        .join('\n');
+      const prompt = t('main.duplicatesPrompt')
+        .replace('{count}', String(conflicts.length))
+        .replace('{files}', files);
+      if (confirm(prompt)) return paths;
+      const duplicates = new Set(conflicts.map((c) => c.input_path));
+      return paths.filter((path) => !duplicates.has(path));
+    } catch (error) {
+      console.warn('[MainWindow] Duplicate check failed:', error);
+      return paths;
+    }
+  }, [findDuplicates, t]);
+
   // Handle CLI files (from context menu)
   useEffect(() => {
     if (cliFiles && cliFiles.length > 0) {
       console.log('[MainWindow] Adding CLI files to queue:', cliFiles);
-      addFiles(cliFiles).then(() => {
+      confirmDuplicates(cliFiles).then((files) => addFiles(files, cliUnattended)).then(async () => {
         console.log('[MainWindow] CLI files added successfully');
         onCliFilesProcessed?.();
+        if (cliAutoStart) {
+          await start();
+        }
       }).catch(err => {
         console.error('[MainWindow] Failed to add CLI files:', err);
       });
     }
-  }, [cliFiles, addFiles, onCliFilesProcessed]);
+  }, [cliFiles, cliAutoStart, cliUnattended, addFiles, confirmDuplicates, start, onCliFilesProcessed]);
 
   const handleSelectFiles = async () => {
     try {
@@ -290,7 +452,7 @@ const MainWindow: React.FC<MainWindowProps> = ({
       });
 
       if (selected && Array.isArray(selected)) {
-        await addFiles(selected);
+        await addFiles(await confirmDuplicates(selected));
       }
     } catch (error) {
       console.error('Failed to select files:', error);
@@ -352,6 +514,7 @@ const MainWindow: React.FC<MainWindowProps> = ({
   const getStatusDisplay = (job: RenderJob) => {
     const statusConfig: Record<string, { text: string; color: string; icon: React.ReactNode }> = {
       pending: { text: t('queue.status.pending'), color: theme.colors.textSecondary, icon: <Clock size={14} strokeWidth={2} /> },
+      'waiting-for-file': { text: t('queue.status.waitingForFile'), color: theme.colors.warning, icon: <Clock size={14} strokeWidth={2} /> },
       processing: { text: t('queue.status.processing'), color: theme.colors.primary, icon: <RefreshCw size={14} strokeWidth={2} /> },
       completed: { text: t('queue.status.completed'), color: theme.colors.success, icon: <Check size={14} strokeWidth={2} /> },
       error: { text: t('queue.status.error'), color: theme.colors.error, icon: <X size={14} strokeWidth={2} /> },
@@ -536,6 +699,9 @@ const MainWindow: React.FC<MainWindowProps> = ({
           <button onClick={() => setShowStats(true)} style={{ background: theme.colors.primary, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}>
             <BarChart3 size={18} strokeWidth={1.5} /> {t('stats.title') || 'Statistics'}
           </button>
+          <button onClick={() => setShowHistory(true)} style={{ background: theme.colors.primary, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}>
+            <History size={18} strokeWidth={1.5} /> {t('jobHistory.title') || 'Job history'}
+          </button>
         </div>
       </header>
 
@@ -617,6 +783,69 @@ const MainWindow: React.FC<MainWindowProps> = ({
           <button className="main-action-button" onClick={handleSelectFiles} style={{ background: theme.colors.primary, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}>
             <Folder size={18} strokeWidth={1.5} /> {t('main.selectFiles')}
           </button>
+          <button
+            className="main-action-button"
+            onClick={() => setShowFolderImport(true)}
+            title={t('folderImport.title')}
+            style={{ background: 'transparent', color: theme.colors.text, border: `1px solid ${theme.colors.border}`, display: 'flex', alignItems: 'center', gap: '6px' }}
+          >
+            <FolderPlus size={18} strokeWidth={1.5} /> {t('folderImport.button')}
+          </button>
+          <div className="url-import" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+            <input
+              type="url"
+              value={videoUrl}
+              placeholder={t('main.urlPlaceholder')}
+              onChange={(e) => setVideoUrl(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') void handleEnqueueUrl();
+              }}
+              style={{
+                flex: 1,
+                minWidth: 0,
+                padding: '8px 10px',
+                borderRadius: '6px',
+                border: `1px solid ${theme.colors.border}`,
+                background: 'transparent',
+                color: theme.colors.text,
+              }}
+            />
+            <button
+              className="main-action-button"
+              onClick={() => void handleEnqueueUrl()}
+              disabled={!videoUrl.trim()}
+              style={{ background: theme.colors.primary, color: '#fff', display: 'flex', alignItems: 'center', gap: '6px' }}
+            >
+              <Globe size={18} strokeWidth={1.5} /> {t('main.addFromUrl')}
+            </button>
+          </div>
+          {urlDownloads.map((download) => (
+            <div
+              key={download.id}
+              style={{ display: 'flex', alignItems: 'center', gap: '8px', fontSize: '0.85rem', color: download.error ? theme.colors.error : theme.colors.textSecondary }}
+            >
+              <span style={{ flex: 1, overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }} title={download.fileName}>
+                {download.error
+                  ? `${t('main.urlDownloadFailed')}: ${download.error}`
+                  : `${t('main.urlDownloading')} ${download.fileName}`}
+              </span>
+              {!download.error && (
+                <span>
+                  {download.total > 0
+                    ? `${Math.floor((download.downloaded / download.total) * 100)}%`
+                    : `${(download.downloaded / 1048576).toFixed(1)} MB`}
+                </span>
+              )}
+              <button
+                type="button"
+                onClick={() => handleCancelUrlDownload(download)}
+                title={t('main.cancelDownload')}
+                style={{ background: 'transparent', border: 'none', cursor: 'pointer', color: theme.colors.textSecondary, display: 'flex' }}
+              >
+                <X size={14} />
+              </button>
+            </div>
+          ))}
           <div className="output-controls" style={{ display: 'flex', alignItems: 'center', gap: '8px', flexWrap: 'wrap' }}>
             <button
               className="main-action-button"
@@ -669,6 +898,21 @@ const MainWindow: React.FC<MainWindowProps> = ({
             </div>
           )}
 
+          {uploadDestinations.length > 0 && (
+            <div style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
+              <span style={{ color: theme.colors.textSecondary, fontSize: '0.85rem' }}>{t('main.uploadTo')}</span>
+              <select
+                value={mainScreenSettings.uploadDestination ?? ''}
+                onChange={(e) => setMainScreenSettings((prev) => ({ ...prev, uploadDestination: e.target.value || undefined }))}
+              >
+                <option value="">{t('main.noUpload')}</option>
+                {uploadDestinations.map((destination) => (
+                  <option key={destination.id} value={destination.id}>{destination.name || destination.id}</option>
+                ))}
+              </select>
+            </div>
+          )}
+
           {/* CPU/GPU/Duo Toggle - Advanced visual selector */}
           <div className="render-mode-inline" style={{ padding: '8px 0' }}>
             <RenderModeSelector
@@ -706,6 +950,7 @@ const MainWindow: React.FC<MainWindowProps> = ({
                   {t('queue.clearCompleted')}
                 </button>
               )}
+              <PostQueueActionControl />
             </div>
           </div>
           <div className={`queue-list ${needsTopPreviewSpace ? 'has-top-preview-room' : ''}`} style={{ borderColor: theme.colors.border }}>
@@ -733,7 +978,7 @@ const MainWindow: React.FC<MainWindowProps> = ({
                             background: `${statusDisplay.color}20`,
                             color: statusDisplay.color,
                             whiteSpace: 'nowrap'
-                          }}>
+                          }} title={item.waitingReason ?? item.pathWarnings?.join('\n')}>
                             <span>{statusDisplay.icon}</span>
                             <span>{statusDisplay.text}</span>
                           </span>
@@ -750,6 +995,22 @@ const MainWindow: React.FC<MainWindowProps> = ({
                               {item.assignedSlot.toUpperCase()}
                             </span>
                           )}
+                          {/* Streams the smart mode copied */}
+                          {item.status === 'completed' && item.copiedStreams && (
+                            <span
+                              title={t('queue.copiedStreams').replace('{streams}', item.copiedStreams.join(', '))}
+                              style={{
+                                background: theme.colors.textSecondary,
+                                color: '#fff',
+                                padding: '3px 6px',
+                                borderRadius: '4px',
+                                fontSize: '0.7rem',
+                                fontWeight: 'bold'
+                              }}
+                            >
+                              COPY
+                            </span>
+                          )}
                           {/* File name */}
                           <span className="item-name" title={item.inputPath} style={{ 
                             overflow: 'hidden', 
@@ -761,6 +1022,36 @@ const MainWindow: React.FC<MainWindowProps> = ({
                           </span>
                         </div>
                         <div className="item-actions" style={{ display: 'flex', gap: '10px', alignItems: 'center', marginLeft: '8px' }}>
+                          {/* Dependency: start only after another job completed */}
+                          {item.status === 'pending' && jobs.length > 1 && (
+                            <select
+                              value={item.dependsOn?.[0] ?? ''}
+                              title={t('queue.dependsOn')}
+                              onChange={(e) => setJobDependencies(item.id, e.target.value ? [e.target.value] : [])}
+                              style={{ fontSize: '0.75rem', maxWidth: '140px' }}
+                            >
+                              <option value="">{t('queue.noDependency')}</option>
+                              {jobs.filter(other => other.id !== item.id).map(other => (
+                                <option key={other.id} value={other.id}>{t('queue.after')} {other.fileName}</option>
+                              ))}
+                            </select>
+                          )}
+                          {/* Upload of the output after rendering */}
+                          {item.upload && (
+                            <span
+                              title={item.upload.error ?? item.upload.remote ?? item.upload.destination}
+                              style={{
+                                fontSize: '0.75rem',
+                                whiteSpace: 'nowrap',
+                                color: item.upload.status === 'error' ? theme.colors.error
+                                  : item.upload.status === 'done' ? theme.colors.success : theme.colors.textSecondary,
+                              }}
+                            >
+                              {item.upload.status === 'uploading' && `${t('queue.uploading')} ${item.upload.percent.toFixed(0)}%`}
+                              {item.upload.status === 'done' && t('queue.uploaded')}
+                              {item.upload.status === 'error' && t('queue.uploadFailed')}
+                            </span>
+                          )}
                           {/* FPS and speed for processing */}
                           {item.status === 'processing' && (
                             <span style={{ fontSize: '0.8rem', color: theme.colors.textSecondary, whiteSpace: 'nowrap' }}>
@@ -837,6 +1128,26 @@ const MainWindow: React.FC<MainWindowProps> = ({
                             </>
                           )}
 
+                          {/* Export stills of the source */}
+                          {item.status !== 'processing' && (
+                            <button
+                              onClick={() => setFrameExportPath(item.inputPath)}
+                              style={{
+                                display: 'flex',
+                                alignItems: 'center',
+                                padding: '4px 8px',
+                                background: 'transparent',
+                                border: `1px solid ${theme.colors.border}`,
+                                borderRadius: '6px',
+                                color: theme.colors.textSecondary,
+                                cursor: 'pointer',
+                              }}
+                              title={t('frames.title')}
+                            >
+                              <ImageIcon size={14} strokeWidth={1.5} />
+                            </button>
+                          )}
+
                           {/* Show in Explorer button for completed tasks */}
                           {item.status === 'completed' && item.outputPath && (
                             <button
@@ -870,7 +1181,7 @@ const MainWindow: React.FC<MainWindowProps> = ({
                           )}
 
                           {/* Delete button - larger and more visible */}
-                          {(item.status === 'pending' || item.status === 'completed' || item.status === 'error' || item.status === 'stopped') && (
+                          {(item.status === 'pending' || item.status === 'waiting-for-file' || item.status === 'completed' || item.status === 'error' || item.status === 'stopped') && (
                             <button
                               onClick={() => handleRemoveJob(item.id)}
                               style={{
@@ -1061,7 +1372,12 @@ const MainWindow: React.FC<MainWindowProps> = ({
                           color: theme.colors.textSecondary,
                           marginTop: '4px'
                         }}>
-                          <span>{item.progress.toFixed(1)}%</span>
+                          <span>
+                            {item.progress.toFixed(1)}%
+                            {item.phase && (item.phaseCount ?? 1) > 1 && (
+                              ` · ${t(`queue.phase.${item.phase}`)} (${item.phaseIndex}/${item.phaseCount})`
+                            )}
+                          </span>
                           <div style={{ display: 'flex', gap: '12px' }}>
                             {item.outputSizeBytes > 0 && (
                               <span style={{ fontFamily: 'monospace' }}>
@@ -1158,6 +1474,41 @@ const MainWindow: React.FC<MainWindowProps> = ({
         )}
       </AnimatePresence>
 
+      <AnimatePresence>
+        {showHistory && (
+          <motion.div
+            className="stats-overlay"
+            onClick={closeHistory}
+            initial={{ opacity: 0 }}
+            animate={{ opacity: 1 }}
+            exit={{ opacity: 0 }}
+            transition={{ duration: 0.2 }}
+          >
+            <motion.div
+              className="stats-modal"
+              onClick={(e) => e.stopPropagation()}
+              initial={{ opacity: 0, y: 20, scale: 0.98 }}
+              animate={{ opacity: 1, y: 0, scale: 1 }}
+              exit={{ opacity: 0, y: 10, scale: 0.98 }}
+              transition={{ duration: 0.25, ease: 'easeOut' }}
+              style={{ color: theme.colors.text }}
+            >
+              <JobHistoryPanel onClose={closeHistory} />
+            </motion.div>
+          </motion.div>
+        )}
+      </AnimatePresence>
+
+      {/* Crash reports from previous sessions */}
+      <CrashReportsDialog />
+      <FrameExportDialog inputPath={frameExportPath} onClose={() => setFrameExportPath(null)} />
+      <FolderImportDialog
+        isOpen={showFolderImport}
+        presetName={selectedPresetName || null}
+        outputDir={mainScreenSettings.saveInSourceDirectory ? null : mainScreenSettings.customOutputPath || null}
+        onClose={() => setShowFolderImport(false)}
+      />
+
       {/* Live Preview Panel */}
       <PreviewPanel
         inputPath={selectedPreviewPath}
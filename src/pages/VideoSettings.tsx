@@ -1,8 +1,9 @@
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import { open } from '@tauri-apps/api/dialog';
+import { invoke } from '@tauri-apps/api/tauri';
 import { useLanguage } from '../contexts/LanguageContext';
 import { useTheme } from '../contexts/ThemeContext';
-import { VideoSettings as VideoSettingsType, WatermarkSettings as WatermarkSettingsType } from '../types/index';
+import { LutInfo, VideoSettings as VideoSettingsType, WatermarkSettings as WatermarkSettingsType } from '../types/index';
 import PreviewPanel from '../components/PreviewPanel';
 import useRenderQueue from '../hooks/useRenderQueue';
 import { Info, AlertTriangle } from 'lucide-react';
@@ -108,6 +109,62 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
     }));
   };
 
+  const [lutInfo, setLutInfo] = useState<LutInfo | null>(null);
+  const [lutError, setLutError] = useState<string | null>(null);
+
+  // Let the backend parse the LUT as a render would
+  useEffect(() => {
+    setLutInfo(null);
+    setLutError(null);
+    if (!settings.lutPath?.trim()) return;
+    invoke<LutInfo>('validate_lut', { path: settings.lutPath })
+      .then(setLutInfo)
+      .catch((error) => setLutError(String(error)));
+  }, [settings.lutPath]);
+
+  const [extraFiltersError, setExtraFiltersError] = useState<string | null>(null);
+
+  // Let FFmpeg parse the custom filtergraph once typing pauses
+  useEffect(() => {
+    setExtraFiltersError(null);
+    const graph = settings.extraFilters?.trim();
+    if (!graph) return;
+    let cancelled = false;
+    const timeout = window.setTimeout(() => {
+      invoke('validate_filtergraph', { graph })
+        .catch((error) => {
+          if (!cancelled) setExtraFiltersError(String(error));
+        });
+    }, 500);
+    return () => {
+      cancelled = true;
+      window.clearTimeout(timeout);
+    };
+  }, [settings.extraFilters]);
+
+  // Peak a capped CRF encode falls back to without a bitrate (encode_args.rs)
+  const [defaultPeak, setDefaultPeak] = useState<number | null>(null);
+
+  useEffect(() => {
+    invoke<number>('get_default_peak_bitrate', { codec: settings.codec })
+      .then(setDefaultPeak)
+      .catch(() => setDefaultPeak(null));
+  }, [settings.codec]);
+
+  const handleSelectLut = async () => {
+    const selected = await open({ multiple: false, filters: [{ name: 'LUT', extensions: ['cube'] }] });
+    if (selected && typeof selected === 'string') {
+      setSettings(prev => ({ ...prev, lutPath: selected }));
+    }
+  };
+
+  // Brightness / contrast / saturation sliders (eq filter)
+  const colorSliders: { key: 'brightness' | 'contrast' | 'saturation'; min: number; max: number }[] = [
+    { key: 'brightness', min: -1, max: 1 },
+    { key: 'contrast', min: 0, max: 2 },
+    { key: 'saturation', min: 0, max: 3 },
+  ];
+
   const handleSelectWatermarkImage = async () => {
     try {
       const selected = await open({
@@ -166,6 +223,31 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
             <option value="vp9">{t('videoSettings.codecs.vp9')}</option>
             <option value="av1">{t('videoSettings.codecs.av1')}</option>
           </select>
+          <label className="filter-checkbox">
+            <input
+              type="checkbox"
+              checked={settings.smartCopy ?? false}
+              onChange={(e) => setSettings(prev => ({ ...prev, smartCopy: e.target.checked }))}
+            />
+            <span>{t('videoSettings.smartCopy')}</span>
+          </label>
+          <div className="preset-description">{t('videoSettings.smartCopyHint')}</div>
+        </div>
+
+        {/* Pixel format / bit depth */}
+        <div className="setting-group">
+          <label>{t('videoSettings.pixelFormat')}</label>
+          <select
+            value={settings.pixelFormat ?? 'auto'}
+            onChange={(e) => setSettings(prev => ({ ...prev, pixelFormat: e.target.value as VideoSettingsType['pixelFormat'] }))}
+          >
+            <option value="auto">{t('videoSettings.pixelFormatOptions.auto')}</option>
+            <option value="yuv420p">{t('videoSettings.pixelFormatOptions.yuv420p')}</option>
+            <option value="yuv420p10le" disabled={settings.codec === 'h264'}>
+              {t('videoSettings.pixelFormatOptions.yuv420p10le')}
+            </option>
+          </select>
+          <div className="preset-description">{t('videoSettings.pixelFormatHint')}</div>
         </div>
 
 {/* Resolution & Bitrate */}
@@ -244,6 +326,34 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
           </div>
         </div>
 
+        {/* Resolution / FPS caps (only lower what the source exceeds) */}
+        <div className="setting-row">
+          <div className="setting-group flex-1">
+            <label>{t('videoSettings.resolutionCap')}</label>
+            <select
+              value={settings.resolutionCap ?? 'none'}
+              onChange={(e) => setSettings(prev => ({ ...prev, resolutionCap: e.target.value as VideoSettingsType['resolutionCap'] }))}
+            >
+              <option value="none">{t('videoSettings.capNone')}</option>
+              <option value="720">720p</option>
+              <option value="1080">1080p</option>
+              <option value="1440">1440p</option>
+            </select>
+          </div>
+          <div className="setting-group flex-1">
+            <label>{t('videoSettings.fpsCap')}</label>
+            <select
+              value={settings.fpsCap ?? 'none'}
+              onChange={(e) => setSettings(prev => ({ ...prev, fpsCap: e.target.value as VideoSettingsType['fpsCap'] }))}
+            >
+              <option value="none">{t('videoSettings.capNone')}</option>
+              <option value="30">30 {t('videoSettings.fpsShort')}</option>
+              <option value="60">60 {t('videoSettings.fpsShort')}</option>
+            </select>
+          </div>
+        </div>
+        <div className="preset-description">{t('videoSettings.capHint')}</div>
+
         {/* Resampling Toggle and Intensity */}
         <div className="setting-group">
           <div style={{ display: 'flex', alignItems: 'center', gap: '12px' }}>
@@ -329,6 +439,32 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
           </div>
         )}
 
+        {/* Rate control */}
+        <div className="setting-group">
+          <label>{t('videoSettings.rateControl')}</label>
+          <select
+            value={settings.rateControl ?? 'auto'}
+            onChange={(e) => setSettings(prev => ({ ...prev, rateControl: e.target.value as VideoSettingsType['rateControl'] }))}
+          >
+            <option value="auto">{t('videoSettings.rateControlOptions.auto')}</option>
+            <option value="capped_crf">{t('videoSettings.rateControlOptions.cappedCrf')}</option>
+          </select>
+          {settings.rateControl === 'capped_crf' && (
+            <div className="preset-description">
+              {t('videoSettings.cappedCrfHint').replace('{peak}', defaultPeak !== null ? String(defaultPeak) : '?')}
+            </div>
+          )}
+          <label className="filter-checkbox">
+            <input
+              type="checkbox"
+              checked={settings.twoPass ?? false}
+              onChange={(e) => setSettings(prev => ({ ...prev, twoPass: e.target.checked }))}
+            />
+            <span>{t('videoSettings.twoPass')}</span>
+          </label>
+          <div className="preset-description">{t('videoSettings.twoPassHint')}</div>
+        </div>
+
         {/* Speed Slider */}
         <div className="setting-group">
           <label>{t('videoSettings.speed')}</label>
@@ -345,7 +481,22 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
             <span style={{ marginLeft: '12px', minWidth: '120px', color: theme.colors.textSecondary }}>
               {getSpeedLabel(settings.speed)}
             </span>
+            <input
+              type="number"
+              value={settings.speed}
+              onChange={(e) => {
+                const speed = parseFloat(e.target.value);
+                if (!isNaN(speed)) {
+                  setSettings(prev => ({ ...prev, speed: Math.max(0.1, Math.min(100, speed)) }));
+                }
+              }}
+              min="0.1"
+              max="100"
+              step="0.05"
+              style={{ width: '80px', marginLeft: '8px' }}
+            />
           </div>
+          <div className="preset-description">{t('videoSettings.speedHint')}</div>
         </div>
 
         {/* Transform Controls */}
@@ -374,7 +525,19 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
               <option value="vertical">{t('videoSettings.flipOptions.vertical')}</option>
             </select>
           </div>
+
+          <div className="setting-group flex-1">
+            <label>{t('videoSettings.sourceRotation')}</label>
+            <select
+              value={settings.sourceRotation ?? 'bake'}
+              onChange={(e) => setSettings(prev => ({ ...prev, sourceRotation: e.target.value as VideoSettingsType['sourceRotation'] }))}
+            >
+              <option value="bake">{t('videoSettings.sourceRotationOptions.bake')}</option>
+              <option value="keep">{t('videoSettings.sourceRotationOptions.keep')}</option>
+            </select>
+          </div>
         </div>
+        <div className="preset-description">{t('videoSettings.sourceRotationHint')}</div>
 
         {/* Filters */}
         <div className="filters-section">
@@ -393,6 +556,121 @@ const VideoSettings: React.FC<VideoSettingsProps> = ({
           </div>
         </div>
 
+        {/* Custom filtergraph */}
+        <div className="setting-group">
+          <label>{t('videoSettings.extraFilters')}</label>
+          <input
+            type="text"
+            value={settings.extraFilters ?? ''}
+            placeholder="eq=gamma=1.1,unsharp=5:5:0.8"
+            disabled={settings.codec === 'copy'}
+            onChange={(e) => setSettings(prev => ({ ...prev, extraFilters: e.target.value }))}
+          />
+          <div className="preset-description">{t('videoSettings.extraFiltersHint')}</div>
+          {extraFiltersError && (
+            <div className="preset-description" style={{ color: theme.colors.error, whiteSpace: 'pre-wrap' }}>
+              {t('videoSettings.extraFiltersInvalid')} {extraFiltersError}
+            </div>
+          )}
+        </div>
+
+        {/* Stabilization (vidstab) */}
+        <div className="setting-group">
+          <label className="filter-checkbox">
+            <input
+              type="checkbox"
+              checked={settings.stabilize}
+              onChange={(e) => setSettings(prev => ({ ...prev, stabilize: e.target.checked }))}
+            />
+            <span>{t('videoSettings.stabilize')}</span>
+          </label>
+          {settings.stabilize && (
+            <>
+              <div className="slider-with-label">
+                <span style={{ minWidth: '120px' }}>{t('videoSettings.stabilizeShakiness')}</span>
+                <input
+                  type="range"
+                  value={settings.stabilizeShakiness}
+                  onChange={(e) => setSettings(prev => ({ ...prev, stabilizeShakiness: parseInt(e.target.value, 10) }))}
+                  min="1"
+                  max="10"
+                  step="1"
+                  style={{ flex: 1 }}
+                />
+                <span style={{ marginLeft: '12px', minWidth: '40px', color: theme.colors.textSecondary }}>
+                  {settings.stabilizeShakiness}
+                </span>
+              </div>
+              <div className="slider-with-label">
+                <span style={{ minWidth: '120px' }}>{t('videoSettings.stabilizeSmoothing')}</span>
+                <input
+                  type="range"
+                  value={settings.stabilizeSmoothing}
+                  onChange={(e) => setSettings(prev => ({ ...prev, stabilizeSmoothing: parseInt(e.target.value, 10) }))}
+                  min="0"
+                  max="60"
+                  step="1"
+                  style={{ flex: 1 }}
+                />
+                <span style={{ marginLeft: '12px', minWidth: '40px', color: theme.colors.textSecondary }}>
+                  {settings.stabilizeSmoothing}
+                </span>
+              </div>
+            </>
+          )}
+          <div className="preset-description">{t('videoSettings.stabilizeHint')}</div>
+        </div>
+
+        {/* Color (lut3d / eq) */}
+        <div className="setting-group">
+          <label>{t('videoSettings.color')}</label>
+          <div style={{ display: 'flex', gap: '10px', alignItems: 'center' }}>
+            <button type="button" onClick={handleSelectLut}>{t('videoSettings.lutBrowse')}</button>
+            <span style={{ color: theme.colors.textSecondary, fontSize: '14px', flex: 1, overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }}>
+              {settings.lutPath || t('videoSettings.lutNone')}
+            </span>
+            {settings.lutPath && (
+              <button type="button" onClick={() => setSettings(prev => ({ ...prev, lutPath: '' }))}>
+                {t('videoSettings.lutClear')}
+              </button>
+            )}
+          </div>
+          {lutInfo && (
+            <div className="preset-description">
+              {t('videoSettings.lutLoaded').replace('{size}', String(lutInfo.size))}
+              {lutInfo.title ? ` (${lutInfo.title})` : ''}
+            </div>
+          )}
+          {lutError && (
+            <div className="preset-description" style={{ color: theme.colors.error }}>{lutError}</div>
+          )}
+          {colorSliders.map(({ key, min, max }) => (
+            <div className="slider-with-label" key={key}>
+              <span style={{ minWidth: '120px' }}>{t(`videoSettings.${key}`)}</span>
+              <input
+                type="range"
+                value={settings[key] ?? (key === 'brightness' ? 0 : 1)}
+                onChange={(e) => setSettings(prev => ({ ...prev, [key]: parseFloat(e.target.value) }))}
+                min={min}
+                max={max}
+                step="0.05"
+                style={{ flex: 1 }}
+              />
+              <span style={{ marginLeft: '12px', minWidth: '40px', color: theme.colors.textSecondary }}>
+                {(settings[key] ?? (key === 'brightness' ? 0 : 1)).toFixed(2)}
+              </span>
+            </div>
+          ))}
+          <button
+            type="button"
+            onClick={() => setSettings(prev => ({ ...prev, brightness: 0, contrast: 1, saturation: 1 }))}
+            style={{ alignSelf: 'flex-start' }}
+          >
+            {t('videoSettings.colorReset')}
+          </button>
+          <div className="preset-description">{t('videoSettings.colorHint')}</div>
+        </div>
+
         {/* Watermark */}
         <div className="watermark-section" style={{ marginTop: '30px', paddingTop: '20px', borderTop: `1px solid ${theme.colors.border}` }}>
           {/* <h3>{t('watermark.title')}</h3> */}
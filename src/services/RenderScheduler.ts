@@ -20,6 +20,8 @@ export class RenderScheduler {
   private slots: SchedulerSlots = { cpuSlot: null, gpuSlot: null };
   private mode: RenderMode = 'cpu';
   private gpuAvailable = false;
+  // Concurrent GPU jobs allowed; 0 while NVENC has no free session
+  private gpuCapacity = 1;
 
   /**
    * Enqueue job in FIFO order (no duplicates)
@@ -30,6 +32,13 @@ export class RenderScheduler {
     }
   }
 
+  /**
+   * Put a job back at the head of the queue (e.g. refused by the GPU, to run next)
+   */
+  requeueFront(jobId: string): void {
+    this.queue = [jobId, ...this.queue.filter(id => id !== jobId)];
+  }
+
   /**
    * Remove job from queue (if pending) and from slots (if active)
    */
@@ -72,6 +81,18 @@ export class RenderScheduler {
     }
   }
 
+  /**
+   * Cap concurrent GPU jobs (NVENC session limit); 0 keeps the GPU slot empty
+   * so jobs go to the CPU slot in duo mode or wait
+   */
+  setGpuCapacity(capacity: number): void {
+    this.gpuCapacity = Math.max(0, capacity);
+  }
+
+  getGpuCapacity(): number {
+    return this.gpuCapacity;
+  }
+
   getGpuAvailable(): boolean {
     return this.gpuAvailable;
   }
@@ -122,14 +143,25 @@ export class RenderScheduler {
    * Dispatch next jobs based on available slots and mode.
    * Returns list of (jobId, slot) to start. Caller must start them and
    * then call `occupy` to mark slot busy.
+   * Pending jobs for which `isBlocked` returns true (unfinished dependencies)
+   * keep their place in the queue and are skipped.
    */
-  planNext(isPending: (jobId: string) => boolean): DispatchTarget[] {
+  planNext(
+    isPending: (jobId: string) => boolean,
+    isBlocked: (jobId: string) => boolean = () => false,
+  ): DispatchTarget[] {
     const dispatch: DispatchTarget[] = [];
 
     const takeNext = (): string | null => {
-      while (this.queue.length > 0) {
-        const candidate = this.queue.shift()!;
-        if (isPending(candidate)) {
+      let index = 0;
+      while (index < this.queue.length) {
+        const candidate = this.queue[index];
+        if (!isPending(candidate)) {
+          this.queue.splice(index, 1);
+        } else if (isBlocked(candidate)) {
+          index++;
+        } else {
+          this.queue.splice(index, 1);
           return candidate;
         }
       }
@@ -137,7 +169,7 @@ export class RenderScheduler {
     };
 
     const cpuFree = this.slots.cpuSlot === null;
-    const gpuFree = this.slots.gpuSlot === null;
+    const gpuFree = this.slots.gpuSlot === null && this.gpuCapacity > 0;
 
     // Determine targets based on mode
     if (this.mode === 'cpu') {
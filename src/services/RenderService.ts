@@ -19,13 +19,16 @@ import {
   getCodecConstraints,
   getValidSampleRate,
   getValidChannels,
-  clampBitrate
+  clampBitrate,
+  getAudioSource,
+  getAudioPassthrough,
 } from '../utils/audioValidation';
 import type {
   VideoSettings,
   AudioSettings,
   WatermarkSettings,
   MainScreenSettings,
+  EncodeArgsResult,
 } from '../types';
 import StatisticsService from './StatisticsService';
 import RenderScheduler, { RenderMode, RenderSlot } from './RenderScheduler';
@@ -34,7 +37,10 @@ import RenderScheduler, { RenderMode, RenderSlot } from './RenderScheduler';
 // Types
 // ============================================================================
 
-export type RenderStatus = 'pending' | 'processing' | 'completed' | 'error' | 'paused' | 'stopped';
+// Step of a job reported with render-progress (see phases.rs)
+export type JobPhase = 'analyzing' | 'stabilizing' | 'encoding' | 'pass1' | 'pass2' | 'muxing' | 'verifying';
+
+export type RenderStatus = 'pending' | 'waiting-for-file' | 'processing' | 'completed' | 'error' | 'paused' | 'stopped';
 
 export interface RenderJob {
   id: string;
@@ -44,10 +50,18 @@ export interface RenderJob {
   inputSize: string; // Source file size (e.g., "120.3 MB")
   inputSizeBytes: number; // Source size in bytes for comparisons
   status: RenderStatus;
-  progress: number;
+  progress: number; // Weighted over all phases of the job
+  phase?: JobPhase;
+  phaseIndex?: number; // 1-based
+  phaseCount?: number;
   eta: number; // seconds
   etaFormatted: string;
   error?: string;
+  errorKind?: RenderErrorKind; // Set for failures the backend classified
+  warnings?: string[]; // Output verification problems ("completed with warnings")
+  copiedStreams?: string[]; // Streams the smart mode copied ("video", "audio")
+  waitingReason?: string; // Why a 'waiting-for-file' job is held back (locked / still growing)
+  pathWarnings?: string[]; // Source/output on a network share (path-analysis event)
   startTime?: number;
   endTime?: number;
   durationSeconds: number;
@@ -63,6 +77,41 @@ export interface RenderJob {
   assignedSlot?: 'cpu' | 'gpu'; // Which slot was used for this render
   trimStartSec: number; // Start point for trim (seconds)
   trimEndSec: number; // End point for trim (seconds)
+  historyJob?: BackendRenderJob; // Re-run from the job history: sent as-is instead of building args from settings
+  backendJob?: BackendRenderJob; // Job last sent to run_ffmpeg_render (persisted for resuming after a crash)
+  dependsOn?: string[]; // Jobs that must complete before this one starts; if one fails, this job fails too
+  nvencRetries?: number; // Times NVENC refused a session for this job
+  upload?: JobUpload; // Upload of the output to the preset's destination
+  unattended?: boolean; // Queued by a folder import or the HTTP API; may be quarantined after repeated failures
+}
+
+// Progress of a post-render upload (upload-progress / upload-complete / upload-error)
+export interface JobUpload {
+  status: 'uploading' | 'done' | 'error';
+  destination: string;
+  percent: number;
+  remote?: string;
+  error?: string;
+}
+
+// Job as run_ffmpeg_render receives it (stored in the job history)
+export interface BackendRenderJob {
+  job_id: string;
+  input_path: string;
+  output_path: string;
+  ffmpeg_args: string[];
+  duration_seconds: number;
+  [key: string]: unknown;
+}
+
+// Result of check_duplicate_jobs
+export interface DuplicateConflict {
+  input_path: string;
+  kind: 'running' | 'queued' | 'recently_compressed';
+  existing_path: string;
+  same_content: boolean;
+  output_path: string | null;
+  finished_at: string | null;
 }
 
 export interface RenderProgress {
@@ -70,18 +119,70 @@ export interface RenderProgress {
   frame: number;
   fps: number;
   bitrate: string;
-  total_size: string;
+  total_size: number; // bytes (parsed by the backend)
   time_seconds: number;
   speed: number;
+  smoothed_speed: number; // Moving average of speed; eta_seconds is based on it
   progress_percent: number;
   eta_seconds: number;
+  phase: JobPhase;
+  phase_percent: number;
+  phase_index: number;
+  phase_count: number;
+  progress_basis: 'time' | 'frames'; // Frames when the duration is unreliable (VFR, image sequences)
+  total_frames: number | null;
 }
 
+// One second of a job's speed history (get_job_speed_history)
+export interface SpeedSample {
+  elapsed_seconds: number;
+  phase: JobPhase;
+  progress_percent: number;
+  speed: number;
+  smoothed_speed: number;
+  fps: number;
+}
+
+export interface ResourceSummary {
+  samples: number;
+  avg_cpu_percent: number | null;
+  avg_gpu_percent: number | null;
+  avg_process_cpu_percent: number | null;
+  peak_process_memory_mb: number | null;
+}
+
+// Result of the check_file_access command
+export interface FileAccess {
+  path: string;
+  exists: boolean;
+  locked: boolean;
+  growing: boolean;
+  size_bytes: number;
+  ready: boolean;
+  reason: string | null;
+}
+
+// Kind of a failed render (RenderResult.error_kind, classified from FFmpeg's stderr)
+export type RenderErrorKind =
+  | 'file_not_found'
+  | 'unsupported_codec'
+  | 'nvenc_session_limit'
+  | 'disk_full'
+  | 'permission_denied'
+  | 'corrupt_input'
+  | 'crash'
+  | 'timeout';
+
 export interface RenderResult {
   job_id: string;
   success: boolean;
   error?: string;
+  error_kind?: RenderErrorKind | null;
   output_path: string;
+  resources?: ResourceSummary | null;
+  warnings?: string[];
+  source_trashed?: boolean;
+  copied_streams?: string[];
 }
 
 export interface RenderQueueState {
@@ -103,23 +204,48 @@ export class FFmpegCommandBuilder {
   private videoSettings: VideoSettings;
   private audioSettings: AudioSettings;
   private watermarkSettings?: WatermarkSettings;
-  private preferGpu: boolean;
+  private videoArgs: EncodeArgsResult | null;
   private outputPath: string;
 
+  /**
+   * `videoArgs` is the backend's encoder choice for the settings (see
+   * fetchVideoArgs); null only for codec "copy"
+   */
   constructor(
     videoSettings: VideoSettings,
     audioSettings: AudioSettings,
     watermarkSettings?: WatermarkSettings,
-    preferGpu: boolean = false,
+    videoArgs: EncodeArgsResult | null = null,
     outputPath: string = ''
   ) {
     this.videoSettings = videoSettings;
     this.audioSettings = audioSettings;
     this.watermarkSettings = watermarkSettings;
-    this.preferGpu = preferGpu;
+    this.videoArgs = videoArgs;
     this.outputPath = outputPath;
   }
 
+  /**
+   * Resolve the encoder and its rate control / preset arguments in the backend
+   * (build_encode_args): it knows which encoders work with this FFmpeg and GPU, so
+   * AV1 gets SVT-AV1 / av1_nvenc / av1_qsv and the GPU slot the right hardware API.
+   */
+  static async fetchVideoArgs(videoSettings: VideoSettings, preferGpu: boolean): Promise<EncodeArgsResult | null> {
+    if (videoSettings.codec === 'copy') {
+      return null;
+    }
+    return invoke<EncodeArgsResult>('build_encode_args', {
+      options: {
+        codec: videoSettings.codec,
+        crf: videoSettings.crf ?? '',
+        bitrate: videoSettings.bitrate ?? '',
+        rate_control: videoSettings.rateControl ?? 'auto',
+        preset: videoSettings.preset ?? '',
+        prefer_gpu: preferGpu,
+      },
+    });
+  }
+
   /**
    * Build FFmpeg arguments array from settings
    */
@@ -134,17 +260,11 @@ export class FFmpegCommandBuilder {
     if (this.videoSettings.codec === 'copy') {
       args.push('-c:v', 'copy');
     } else {
-      // Determine encoder based on mode (CPU vs GPU)
-      const encoder = this.getVideoEncoder();
-      const isNvenc = encoder.includes('nvenc');
-      
-      args.push('-c:v', encoder);
-
-      // Add encoder-specific quality/bitrate parameters
-      this.addEncoderQualityParams(args, encoder, isNvenc);
-
-      // Add encoder-specific preset
-      this.addEncoderPreset(args, encoder, isNvenc);
+      // Encoder with its rate control and preset, as resolved by the backend
+      if (!this.videoArgs) {
+        throw new Error(`No encoder resolved for codec ${this.videoSettings.codec}`);
+      }
+      args.push(...this.videoArgs.args);
 
       // FPS (clamp to valid range 1-240)
       if (!this.videoSettings.fpsAuto && this.videoSettings.fps) {
@@ -153,10 +273,7 @@ export class FFmpegCommandBuilder {
       }
 
       // Resolution - NVENC requires dimensions divisible by 2
-      if (!this.videoSettings.aspectRatioAuto &&
-          this.videoSettings.resolution &&
-          this.videoSettings.resolution !== 'original' &&
-          this.videoSettings.resolution !== 'source') {
+      if (this.hasFixedResolution()) {
         const [width, height] = this.videoSettings.resolution.split('x').map(Number);
         // Ensure dimensions are even (required for most encoders, especially NVENC)
         const evenWidth = Math.floor(width / 2) * 2;
@@ -164,13 +281,8 @@ export class FFmpegCommandBuilder {
         videoFilters.push(`scale=${evenWidth}:${evenHeight}`);
       }
 
-      // Speed/tempo adjustment
-      if (this.videoSettings.speed && this.videoSettings.speed !== 1.0) {
-        const pts = 1 / this.videoSettings.speed;
-        videoFilters.push(`setpts=${pts.toFixed(4)}*PTS`);
-        // Also adjust audio tempo
-        audioFilters.push(`atempo=${this.videoSettings.speed}`);
-      }
+      // Speed/tempo adjustment: the backend adds setpts/atempo for RenderJob.speed_factor
+      // (see getSpeedFactor)
 
       // Resampling (frame interpolation) using minterpolate
       if (this.videoSettings.resamplingEnabled) {
@@ -194,17 +306,8 @@ export class FFmpegCommandBuilder {
         videoFilters.push(mi);
       }
 
-      // Rotation
-      if (this.videoSettings.rotation && this.videoSettings.rotation !== 'none') {
-        const rotationMap: Record<string, string> = {
-          '90': 'transpose=1',
-          '180': 'transpose=1,transpose=1',
-          '270': 'transpose=2',
-        };
-        if (rotationMap[this.videoSettings.rotation]) {
-          videoFilters.push(rotationMap[this.videoSettings.rotation]);
-        }
-      }
+      // Rotation / flip: the backend adds transpose/hflip/vflip for RenderJob.orientation
+      // (see getOrientation)
 
       // Video filters from settings
       if (this.videoSettings.filters) {
@@ -224,14 +327,9 @@ export class FFmpegCommandBuilder {
           }
         }
       }
-      
-      // Ensure pixel format compatibility for all H.264/H.265 encoders
-      // Both NVENC and libx264/libx265 require yuv420p for maximum compatibility
-      // This fixes "Option not found" errors when source has non-standard pixel format
-      const codec = this.videoSettings.codec.toLowerCase();
-      if (codec === 'h264' || codec === 'h265' || codec === 'hevc') {
-        videoFilters.push('format=yuv420p');
-      }
+
+      // Pixel format: the backend ends the chain with format= for RenderJob.pixel_format
+      // (see getPixelFormat), converting 10-bit sources for 8-bit-only encoders
     }
 
     // ========== WATERMARK ==========
@@ -254,18 +352,27 @@ export class FFmpegCommandBuilder {
     // Use validated settings
     const validatedAudio = audioValidation.settings;
     const filtersActive = hasActiveFilters(validatedAudio);
+    // An external track is cut to the video's length, so it is always encoded
+    const externalTrack = this.audioSettings.track === 'replace' || this.audioSettings.track === 'mix';
+    // atempo retimes the audio of a speed change, which a stream copy cannot do
+    const retimed = this.getSpeedFactor() !== null;
+    // Last in the -af chain, after every filter that could change the layout
+    let surroundLayout: string | null = null;
     
-    // CRITICAL: If filters are active, we CANNOT use copy codec
-    if (validatedAudio.codec === 'copy' && !filtersActive) {
+    if (this.audioSettings.track === 'mute') {
+      // The backend drops the audio (-an) for RenderJob.audio_source
+      console.log('[FFmpegCommandBuilder] Audio: muted');
+    } else if (validatedAudio.codec === 'copy' && !filtersActive && !externalTrack && !retimed) {
+      // CRITICAL: If filters are active, we CANNOT use copy codec
       args.push('-c:a', 'copy');
       console.log('[FFmpegCommandBuilder] Audio: copy stream (no filters)');
     } else {
       // Get codec-specific constraints
       const constraints = getCodecConstraints(validatedAudio.codec);
       
-      // If copy was selected but filters are active, force re-encoding
-      if (validatedAudio.codec === 'copy' && filtersActive) {
-        console.warn('[FFmpegCommandBuilder] Filters active with copy codec, forcing AAC encoder');
+      // If copy was selected but filters are active, the track is replaced or retimed, force re-encoding
+      if (validatedAudio.codec === 'copy') {
+        console.warn('[FFmpegCommandBuilder] Filters, external track or speed change with copy codec, forcing AAC encoder');
         args.push('-c:a', 'aac');
         args.push('-b:a', '192k');
         args.push('-ac', '2');
@@ -282,7 +389,7 @@ export class FFmpegCommandBuilder {
         args.push('-ar', sampleRate.toString());
         console.log(`[FFmpegCommandBuilder] Audio sample rate: ${sampleRate}Hz (codec: ${validatedAudio.codec})`);
         
-        // Channels - use validated value (Opus/MP3: 1-2 only)
+        // Channels - use validated value (MP3: 1-2 only)
         const channels = getValidChannels(
           parseInt(validatedAudio.channels, 10),
           constraints
@@ -290,7 +397,7 @@ export class FFmpegCommandBuilder {
         args.push('-ac', channels.toString());
         console.log(`[FFmpegCommandBuilder] Audio channels: ${channels} (codec: ${validatedAudio.codec})`);
         
-        // Bitrate - use validated value (Opus: 16-128k, MP3: 64-192k)
+        // Bitrate - use validated value (Opus: 16-512k, MP3: 64-192k)
         if (constraints.bitrateOptions.length > 0 && validatedAudio.bitrate) {
           const bitrate = clampBitrate(
             parseInt(validatedAudio.bitrate, 10),
@@ -299,6 +406,18 @@ export class FFmpegCommandBuilder {
           args.push('-b:a', `${bitrate}k`);
           console.log(`[FFmpegCommandBuilder] Audio bitrate: ${bitrate}k (codec: ${validatedAudio.codec})`);
         }
+
+        // FLAC has no bitrate: the compression level trades encode speed for size
+        if (validatedAudio.codec === 'flac') {
+          const level = Math.max(0, Math.min(12, Math.round(validatedAudio.flacCompression ?? 5)));
+          args.push('-compression_level', level.toString());
+        }
+
+        // libopus only takes the plain 5.1 / 7.1 layouts, not the "(side)" variants
+        // most surround sources carry (the backend drops this for a passthrough copy)
+        if (validatedAudio.codec === 'opus' && (channels === 6 || channels === 8)) {
+          surroundLayout = `aformat=channel_layouts=${channels === 6 ? '5.1' : '7.1'}`;
+        }
       }
 
       // Volume adjustment (clamp to valid range 0.0 - 10.0)
@@ -371,6 +490,10 @@ export class FFmpegCommandBuilder {
           }
         }
       }
+
+      if (surroundLayout) {
+        audioFilters.push(surroundLayout);
+      }
     }
 
     // ========== APPLY FILTERS ==========
@@ -407,160 +530,108 @@ export class FFmpegCommandBuilder {
   }
 
   /**
-   * Get the video encoder name based on codec and GPU preference
+   * RenderJob.speed_factor: playback speed of the output, null when unchanged.
+   * Factors outside 0.5-2.0 need several atempo stages, which the backend chains.
    */
-  private getVideoEncoder(): string {
-    // Map codec names to FFmpeg encoders (CPU)
-    const cpuCodecMap: Record<string, string> = {
-      'h264': 'libx264',
-      'h265': 'libx265',
-      'hevc': 'libx265',
-      'vp9': 'libvpx-vp9',
-      'vp8': 'libvpx',
-      'av1': 'libaom-av1',
-      'mpeg4': 'mpeg4',
-      'prores': 'prores_ks',
-    };
+  getSpeedFactor(): number | null {
+    const speed = this.videoSettings.speed;
+    if (!speed || speed === 1.0 || this.videoSettings.codec === 'copy') {
+      return null;
+    }
+    return Math.max(0.1, Math.min(100, speed));
+  }
 
-    // GPU (NVENC) encoders for supported codecs
-    const gpuCodecMap: Record<string, string> = {
-      'h264': 'h264_nvenc',
-      'h265': 'hevc_nvenc',
-      'hevc': 'hevc_nvenc',
+  /**
+   * RenderJob.stabilize: vidstab options, null when off. The backend runs the
+   * vidstabdetect pass and puts vidstabtransform first in the -vf chain.
+   */
+  getStabilize(): { shakiness: number; smoothing: number } | null {
+    if (!this.videoSettings.stabilize || this.videoSettings.codec === 'copy') {
+      return null;
+    }
+    return {
+      shakiness: Math.max(1, Math.min(10, Math.round(this.videoSettings.stabilizeShakiness ?? 5))),
+      smoothing: Math.max(0, Math.min(500, Math.round(this.videoSettings.stabilizeSmoothing ?? 10))),
     };
+  }
 
-    if (this.preferGpu) {
-      const gpuEncoder = gpuCodecMap[this.videoSettings.codec];
-      if (!gpuEncoder) {
-        console.warn(`[FFmpegCommandBuilder] GPU not supported for codec ${this.videoSettings.codec}, falling back to CPU`);
-        return cpuCodecMap[this.videoSettings.codec] || this.videoSettings.codec;
-      }
-      return gpuEncoder;
+  /** A scale to the selected resolution is part of the args */
+  private hasFixedResolution(): boolean {
+    return !this.videoSettings.aspectRatioAuto &&
+      !!this.videoSettings.resolution &&
+      this.videoSettings.resolution !== 'original' &&
+      this.videoSettings.resolution !== 'source';
+  }
+
+  /**
+   * RenderJob.limits: resolution / frame rate caps, null when none applies. The backend
+   * probes the source and only scales or drops frames when it is above a cap. A fixed
+   * resolution wins over the resolution cap.
+   */
+  getLimits(): { max_height: number | null; max_fps: number | null } | null {
+    if (this.videoSettings.codec === 'copy') {
+      return null;
     }
+    const { resolutionCap = 'none', fpsCap = 'none' } = this.videoSettings;
+    const maxHeight = resolutionCap !== 'none' && !this.hasFixedResolution() ? Number(resolutionCap) : null;
+    const maxFps = fpsCap !== 'none' ? Number(fpsCap) : null;
+    return maxHeight === null && maxFps === null ? null : { max_height: maxHeight, max_fps: maxFps };
+  }
 
-    return cpuCodecMap[this.videoSettings.codec] || this.videoSettings.codec;
+  /**
+   * RenderJob.smart_copy: the backend compares the final args with the probed source
+   * and copies the streams a re-encode would not improve.
+   */
+  getSmartCopy(): boolean {
+    return !!this.videoSettings.smartCopy && this.videoSettings.codec !== 'copy';
   }
 
   /**
-   * Add encoder-specific quality/bitrate parameters
-   * CPU (libx264/libx265): uses -crf for quality
-   * GPU (NVENC): uses -cq (constant quality) or -b:v (bitrate mode)
+   * RenderJob.two_pass: the backend runs an analysis pass first when the resolved
+   * encoder supports it and encodes once otherwise.
    */
-  private addEncoderQualityParams(args: string[], encoder: string, isNvenc: boolean): void {
-    const hasBitrate = this.videoSettings.bitrate && this.videoSettings.bitrate !== 'auto';
-    const hasCrf = this.videoSettings.crf && this.videoSettings.crf !== 'auto';
-
-    if (isNvenc) {
-      // NVENC rate control
-      if (hasBitrate) {
-        // VBR mode with target bitrate
-        const bitrateValue = Math.max(0.1, Math.min(100, parseFloat(this.videoSettings.bitrate!)));
-        
-        args.push('-rc', 'vbr');
-        args.push('-b:v', `${bitrateValue}M`);
-        // Set maxrate to 1.5x target for VBR headroom
-        args.push('-maxrate', `${(bitrateValue * 1.5).toFixed(1)}M`);
-        // Buffer size = 2 seconds of video at max rate
-        args.push('-bufsize', `${(bitrateValue * 3).toFixed(0)}M`);
-      } else if (hasCrf) {
-        // CQ (Constant Quality) mode - NVENC equivalent of CRF
-        // NVENC CQ range is 0-51, same as CRF conceptually
-        const cqValue = Math.max(0, Math.min(51, parseInt(this.videoSettings.crf!, 10)));
-        
-        args.push('-rc', 'constqp');
-        args.push('-cq', cqValue.toString());
-        // Also set qp values for consistency
-        args.push('-qp', cqValue.toString());
-      } else {
-        // Default: use CQ mode with reasonable quality (CQ 23 is good default)
-        args.push('-rc', 'constqp');
-        args.push('-cq', '23');
-        args.push('-qp', '23');
-      }
-      
-      // NVENC-specific optimizations
-      args.push('-spatial-aq', '1');  // Spatial adaptive quantization
-      args.push('-temporal-aq', '1'); // Temporal adaptive quantization
-      
-      // B-frames for better compression (except for lowest latency)
-      if (encoder === 'hevc_nvenc') {
-        args.push('-b_ref_mode', 'middle');
-      }
-      
-    } else {
-      // CPU encoders (libx264, libx265, etc.)
-      if (hasBitrate && hasCrf) {
-        // If both are set, use two-pass-like behavior: CRF with max bitrate cap
-        const crfValue = Math.max(0, Math.min(51, parseInt(this.videoSettings.crf!, 10)));
-        const bitrateValue = Math.max(0.1, Math.min(100, parseFloat(this.videoSettings.bitrate!)));
-        
-        args.push('-crf', crfValue.toString());
-        args.push('-maxrate', `${bitrateValue}M`);
-        args.push('-bufsize', `${(bitrateValue * 2).toFixed(0)}M`);
-      } else if (hasBitrate) {
-        // Bitrate-only mode
-        const bitrateValue = Math.max(0.1, Math.min(100, parseFloat(this.videoSettings.bitrate!)));
-        args.push('-b:v', `${bitrateValue}M`);
-      } else if (hasCrf) {
-        // CRF-only mode (recommended for quality)
-        const crfValue = Math.max(0, Math.min(51, parseInt(this.videoSettings.crf!, 10)));
-        args.push('-crf', crfValue.toString());
-      } else {
-        // Default: use CRF 23 (good quality/size balance)
-        args.push('-crf', '23');
-      }
-    }
+  getTwoPass(): boolean {
+    return !!this.videoSettings.twoPass && this.videoSettings.codec !== 'copy';
   }
 
   /**
-   * Add encoder-specific preset
-   * CPU: ultrafast, superfast, veryfast, faster, fast, medium, slow, slower, veryslow
-   * NVENC: p1-p7 (fastest to slowest) or named presets
+   * Custom filtergraph for the backend to check against the source and append to -vf
    */
-  private addEncoderPreset(args: string[], encoder: string, isNvenc: boolean): void {
-    const preset = this.videoSettings.preset;
-    
-    if (!preset) {
-      // Default presets
-      if (isNvenc) {
-        args.push('-preset', 'p4'); // Balanced quality/speed for NVENC
-      } else if (encoder === 'libx264' || encoder === 'libx265') {
-        args.push('-preset', 'medium');
-      }
-      return;
-    }
+  getExtraFilters(): string {
+    return this.videoSettings.codec === 'copy' ? '' : (this.videoSettings.extraFilters ?? '').trim();
+  }
 
-    if (isNvenc) {
-      // Map CPU presets to NVENC presets
-      // NVENC presets: p1 (fastest) to p7 (slowest/best quality)
-      const nvencPresetMap: Record<string, string> = {
-        'ultrafast': 'p1',
-        'superfast': 'p2',
-        'veryfast': 'p3',
-        'faster': 'p4',
-        'fast': 'p4',
-        'medium': 'p5',
-        'slow': 'p6',
-        'slower': 'p7',
-        'veryslow': 'p7',
-        'placebo': 'p7',
-        // Also accept direct NVENC presets
-        'p1': 'p1', 'p2': 'p2', 'p3': 'p3', 'p4': 'p4',
-        'p5': 'p5', 'p6': 'p6', 'p7': 'p7',
-      };
-      
-      const nvencPreset = nvencPresetMap[preset.toLowerCase()] || 'p4';
-      args.push('-preset', nvencPreset);
-      
-      // Set tuning for NVENC (hq = high quality)
-      args.push('-tune', 'hq');
-      
-    } else if (encoder === 'libx264' || encoder === 'libx265') {
-      // CPU presets
-      const validPresets = ['ultrafast', 'superfast', 'veryfast', 'faster', 'fast', 'medium', 'slow', 'slower', 'veryslow', 'placebo'];
-      const cpuPreset = validPresets.includes(preset.toLowerCase()) ? preset.toLowerCase() : 'medium';
-      args.push('-preset', cpuPreset);
+  /**
+   * RenderJob.pixel_format: yuv420p, yuv420p10le or auto (10-bit sources stay 10-bit
+   * when the encoder supports it). The backend checks the encoder and adds the format
+   * conversion.
+   */
+  getPixelFormat(): VideoSettings['pixelFormat'] | null {
+    return this.videoSettings.codec === 'copy' ? null : this.videoSettings.pixelFormat ?? 'auto';
+  }
+
+  /**
+   * RenderJob.orientation: rotation, flip and what happens to the rotation tag of
+   * phone videos (baked into the frames by autorotate, or kept as metadata).
+   */
+  getOrientation(): { rotation: string; flip: string; source_rotation: string } {
+    return {
+      rotation: this.videoSettings.rotation ?? 'none',
+      flip: this.videoSettings.flip ?? 'none',
+      source_rotation: this.videoSettings.sourceRotation ?? 'bake',
+    };
+  }
+
+  /**
+   * RenderJob.color: LUT and eq adjustments, null when the picture is unchanged.
+   * The backend checks the .cube file before spawning FFmpeg.
+   */
+  getColor(): { lut_path: string; brightness: number; contrast: number; saturation: number } | null {
+    const { lutPath = '', brightness = 0, contrast = 1, saturation = 1 } = this.videoSettings;
+    if (this.videoSettings.codec === 'copy' || (!lutPath.trim() && brightness === 0 && contrast === 1 && saturation === 1)) {
+      return null;
     }
+    return { lut_path: lutPath.trim(), brightness, contrast, saturation };
   }
 
   /**
@@ -570,6 +641,15 @@ export class FFmpegCommandBuilder {
     const errors: string[] = [];
     const warnings: string[] = [];
 
+    if (this.videoSettings.sourceRotation === 'keep'
+      && (this.videoSettings.rotation !== 'none' || this.videoSettings.flip !== 'none')) {
+      errors.push('Rotation and flip need the phone rotation baked in');
+    }
+
+    if (this.videoSettings.pixelFormat === 'yuv420p10le' && this.videoSettings.codec === 'h264') {
+      errors.push('10-bit output needs H.265, VP9 or AV1');
+    }
+
     // Copy codec doesn't support filters
     if (this.videoSettings.codec === 'copy') {
       if (this.videoSettings.filters?.some(f => f.enabled)) {
@@ -578,6 +658,16 @@ export class FFmpegCommandBuilder {
       if (this.videoSettings.rotation !== 'none') {
         errors.push('Rotation not supported with codec "copy"');
       }
+      if (this.videoSettings.speed && this.videoSettings.speed !== 1.0) {
+        errors.push('Speed change not supported with codec "copy"');
+      }
+      if (this.videoSettings.stabilize) {
+        errors.push('Stabilization not supported with codec "copy"');
+      }
+      if (this.videoSettings.lutPath?.trim() || (this.videoSettings.brightness ?? 0) !== 0
+        || (this.videoSettings.contrast ?? 1) !== 1 || (this.videoSettings.saturation ?? 1) !== 1) {
+        errors.push('Color adjustments not supported with codec "copy"');
+      }
       if (this.videoSettings.flip !== 'none') {
         if (this.videoSettings.resamplingEnabled) {
           errors.push('Resampling not supported with codec "copy"');
@@ -589,6 +679,24 @@ export class FFmpegCommandBuilder {
       }
     }
 
+    if ((this.audioSettings.track === 'replace' || this.audioSettings.track === 'mix') &&
+        !this.audioSettings.trackPath?.trim()) {
+      errors.push('No audio file selected for the audio track');
+    }
+
+    // Passthrough copies the source track, so nothing may change the audio
+    if (getAudioPassthrough(this.audioSettings)) {
+      if (hasActiveFilters(this.audioSettings)) {
+        errors.push('Audio filters not supported with audio passthrough');
+      }
+      if (this.audioSettings.track === 'replace' || this.audioSettings.track === 'mix') {
+        errors.push('An external audio track cannot be used with audio passthrough');
+      }
+      if (this.videoSettings.speed && this.videoSettings.speed !== 1.0) {
+        errors.push('Speed change not supported with audio passthrough');
+      }
+    }
+
     if (this.audioSettings.codec === 'copy') {
       if (this.audioSettings.normalization) {
         errors.push('Audio normalization not supported with codec "copy"');
@@ -652,25 +760,27 @@ export class FFmpegCommandBuilder {
     const parts: string[] = [];
     
     // Show actual encoder being used
-    const encoder = this.videoSettings.codec === 'copy' ? 'copy' : this.getVideoEncoder();
-    const isNvenc = encoder.includes('nvenc');
+    const encoder = this.videoArgs?.encoder ?? { name: 'copy', hardware: false };
     
-    parts.push(`Video: ${encoder}${isNvenc ? ' (GPU)' : ''}`);
+    parts.push(`Video: ${encoder.name}${encoder.hardware ? ' (GPU)' : ''}`);
     
     if (this.videoSettings.codec !== 'copy') {
       if (this.videoSettings.bitrate && this.videoSettings.bitrate !== 'auto') {
         parts.push(`${this.videoSettings.bitrate}Mbps`);
       }
       if (this.videoSettings.crf && this.videoSettings.crf !== 'auto') {
-        // Show CQ for NVENC, CRF for CPU
-        parts.push(`${isNvenc ? 'CQ' : 'CRF'} ${this.videoSettings.crf}`);
+        // Show CQ for hardware encoders, CRF for CPU
+        parts.push(`${encoder.hardware ? 'CQ' : 'CRF'} ${this.videoSettings.crf}`);
       }
       if (this.videoSettings.preset) {
         parts.push(`preset: ${this.videoSettings.preset}`);
       }
     }
     
-    parts.push(`Audio: ${this.audioSettings.codec}`);
+    parts.push(`Audio: ${this.audioSettings.track === 'mute' ? 'muted' : this.audioSettings.codec}`);
+    if (this.audioSettings.track === 'replace' || this.audioSettings.track === 'mix') {
+      parts.push(`${this.audioSettings.track}: ${this.audioSettings.trackPath}`);
+    }
     if (this.audioSettings.bitrate && this.audioSettings.bitrate !== 'auto') {
       parts.push(`${this.audioSettings.bitrate}kbps`);
     }
@@ -697,6 +807,17 @@ class RenderServiceImpl {
   private unlistenComplete: UnlistenFn | null = null;
   private unlistenError: UnlistenFn | null = null;
   private unlistenStopped: UnlistenFn | null = null;
+  private unlistenPower: UnlistenFn | null = null;
+  private unlistenPathAnalysis: UnlistenFn | null = null;
+  private unlistenQueueControl: UnlistenFn | null = null;
+  private lastTrayStatus: string = '';
+  private lastQueueState: string = '';
+  private pausedForBattery: boolean = false;
+  private pausedForSchedule: boolean = false;
+  private unlistenSchedule: UnlistenFn | null = null;
+  private pausedForIdle: boolean = false;
+  private unlistenIdle: UnlistenFn | null = null;
+  private unlistenUploads: UnlistenFn[] = [];
 
   // Current settings
   private videoSettings: VideoSettings | null = null;
@@ -707,6 +828,10 @@ class RenderServiceImpl {
   private selectedPresetName: string | null = null;
 
   private static readonly MIN_TRIM_DURATION_SEC = 1;
+  private static readonly FILE_WAIT_RETRY_MS = 10000;
+  private static readonly NVENC_SESSION_POLL_MS = 15000;
+  private static readonly MAX_NVENC_RETRIES = 3;
+  private nvencPoll: ReturnType<typeof setTimeout> | null = null;
 
   constructor() {
     this.setupEventListeners();
@@ -728,8 +853,10 @@ class RenderServiceImpl {
       });
 
       // Listen for errors
-      this.unlistenError = await listen<{ job_id: string; error: string }>('render-error', (event) => {
-        this.handleJobError(event.payload.job_id, event.payload.error);
+      this.unlistenError = await listen<{ job_id: string; error: string; kind: RenderErrorKind | null }>('render-error', (event) => {
+        const kind = event.payload.kind ?? undefined;
+        if (this.handleNvencSessionLimit(event.payload.job_id, kind)) return;
+        this.handleJobError(event.payload.job_id, this.describeError(event.payload.error, kind), kind);
       });
 
       // Listen for stop events (new)
@@ -739,6 +866,84 @@ class RenderServiceImpl {
       
       // Store for cleanup
       this.unlistenStopped = unlistenStop;
+
+      // Battery policy: the backend asks to hold the queue on battery and to continue on AC
+      this.unlistenPower = await listen<{ pause_queue: boolean; resume_queue: boolean }>('power-state-changed', (event) => {
+        if (event.payload.pause_queue && this.isProcessing && !this.isPaused) {
+          this.pausedForBattery = true;
+          this.pause();
+        } else if (event.payload.resume_queue && this.pausedForBattery) {
+          this.pausedForBattery = false;
+          void this.resume();
+        }
+      });
+
+      // Render schedule: hold the queue while the start time / night window is closed
+      this.unlistenSchedule = await listen<{ pause_queue: boolean; resume_queue: boolean }>('queue-schedule-changed', (event) => {
+        if (event.payload.pause_queue && this.isProcessing && !this.isPaused) {
+          this.pausedForSchedule = true;
+          this.pause();
+        } else if (event.payload.resume_queue && this.pausedForSchedule) {
+          this.pausedForSchedule = false;
+          void this.resume();
+        }
+      });
+
+      // Idle trigger: run only while nobody uses the machine; the backend suspends
+      // running renders itself when the user comes back
+      this.unlistenIdle = await listen<{ pause_queue: boolean; resume_queue: boolean }>('idle-state-changed', (event) => {
+        if (event.payload.pause_queue && this.isProcessing && !this.isPaused) {
+          this.pausedForIdle = true;
+          this.pause();
+        } else if (event.payload.resume_queue && this.pausedForIdle) {
+          this.pausedForIdle = false;
+          void this.resume();
+        }
+      });
+
+      // Post-render uploads run in the backend after the job completed; mirror them on the job
+      const setUpload = (jobId: string, upload: JobUpload) => {
+        const job = this.jobs.get(jobId);
+        if (!job) return;
+        job.upload = upload;
+        this.notifyListeners();
+      };
+      this.unlistenUploads = await Promise.all([
+        listen<{ job_id: string; destination: string; percent: number }>('upload-progress', (event) => {
+          const { job_id, destination, percent } = event.payload;
+          setUpload(job_id, { status: 'uploading', destination, percent });
+        }),
+        listen<{ job_id: string; destination: string; remote: string | null }>('upload-complete', (event) => {
+          const { job_id, destination, remote } = event.payload;
+          setUpload(job_id, { status: 'done', destination, percent: 100, remote: remote ?? undefined });
+        }),
+        listen<{ job_id: string; destination: string; error: string | null }>('upload-error', (event) => {
+          const { job_id, destination, error } = event.payload;
+          setUpload(job_id, { status: 'error', destination, percent: 0, error: error ?? undefined });
+        }),
+      ]);
+
+      // Network shares slow renders down; keep the backend's warnings on the job
+      this.unlistenPathAnalysis = await listen<{ job_id: string | null; warnings: string[]; staged: boolean }>('path-analysis', (event) => {
+        const job = event.payload.job_id ? this.jobs.get(event.payload.job_id) : undefined;
+        if (!job) return;
+        job.pathWarnings = event.payload.warnings;
+        console.warn('[RenderService] Network path:', job.fileName, event.payload.warnings, `staged=${event.payload.staged}`);
+        this.notifyListeners();
+      });
+
+      // Tray menu and global shortcuts: the backend forwards them, the queue is ours to act on
+      this.unlistenQueueControl = await listen<{ action: 'start' | 'pause' | 'resume' | 'stop_all' }>('queue-control', (event) => {
+        if (event.payload.action === 'start' && !this.isProcessing) {
+          this.start().catch((error) => console.error('[RenderService] Remote start failed:', error));
+        } else if (event.payload.action === 'pause' && this.isProcessing && !this.isPaused) {
+          this.pause();
+        } else if (event.payload.action === 'resume') {
+          void this.resume();
+        } else if (event.payload.action === 'stop_all') {
+          void this.stop();
+        }
+      });
     } catch (error) {
       console.error('[RenderService] Failed to setup event listeners:', error);
     }
@@ -760,6 +965,26 @@ class RenderServiceImpl {
     if (this.unlistenStopped) {
       this.unlistenStopped();
     }
+    if (this.unlistenPathAnalysis) {
+      this.unlistenPathAnalysis();
+    }
+    if (this.unlistenPower) {
+      this.unlistenPower();
+    }
+    if (this.unlistenSchedule) {
+      this.unlistenSchedule();
+    }
+    if (this.unlistenIdle) {
+      this.unlistenIdle();
+    }
+    if (this.unlistenQueueControl) {
+      this.unlistenQueueControl();
+    }
+    this.unlistenUploads.forEach((unlisten) => unlisten());
+    this.unlistenUploads = [];
+    if (this.nvencPoll) {
+      clearTimeout(this.nvencPoll);
+    }
   }
 
   /**
@@ -856,12 +1081,84 @@ class RenderServiceImpl {
   private notifyListeners(): void {
     const jobs = Array.from(this.jobs.values());
     this.listeners.forEach(callback => callback(jobs));
+    this.reportTrayStatus(jobs);
+    this.persistQueueState(jobs);
+  }
+
+  /**
+   * Save the queue to the backend so it survives a crash or shutdown
+   * (only when jobs or their statuses changed, not on every progress tick)
+   */
+  private persistQueueState(jobs: RenderJob[]): void {
+    const state = {
+      presetName: this.selectedPresetName,
+      paused: this.isPaused,
+      jobs: jobs.map(job => ({
+        id: job.id,
+        inputPath: job.inputPath,
+        outputPath: job.outputPath,
+        status: job.status,
+        trimStartSec: job.trimStartSec,
+        trimEndSec: job.trimEndSec,
+        dependsOn: job.dependsOn ?? [],
+        renderJob: job.backendJob ?? null,
+      })),
+    };
+    const key = JSON.stringify(state);
+    if (key === this.lastQueueState) return;
+    this.lastQueueState = key;
+    invoke('save_queue_state', { state }).catch((error) => {
+      console.warn('[RenderService] Failed to save queue state:', error);
+    });
+  }
+
+  /**
+   * Send queue status to the tray tooltip (only when it changed)
+   */
+  private reportTrayStatus(jobs: RenderJob[]): void {
+    const active = jobs.filter(job => job.status === 'processing');
+    const status = {
+      active_jobs: active.length,
+      pending_jobs: jobs.filter(job => ['pending', 'waiting-for-file', 'paused'].includes(job.status)).length,
+      progress: active.length > 0
+        ? Math.round(active.reduce((sum, job) => sum + job.progress, 0) / active.length)
+        : 0,
+      paused: this.isPaused,
+    };
+    const key = JSON.stringify(status);
+    if (key === this.lastTrayStatus) return;
+    this.lastTrayStatus = key;
+    invoke('update_tray_status', { status }).catch((error) => {
+      console.warn('[RenderService] Failed to update tray:', error);
+    });
   }
 
   /**
    * Generate output path for a file
    */
-  private generateOutputPath(inputPath: string): string {
+  private async generateOutputPath(inputPath: string): Promise<string> {
+    // Custom output folder from the main screen overrides the outputDirectory setting
+    const usesCustomDir = !!this.mainScreenSettings
+      && !this.mainScreenSettings.saveInSourceDirectory
+      && !!this.mainScreenSettings.customOutputPath;
+    try {
+      return await invoke<string>('render_output_path', {
+        input: inputPath,
+        template: null,
+        options: {
+          codec: this.videoSettings?.codec ?? null,
+          preset: this.selectedPresetName,
+          container: null,
+          resolution: this.videoSettings?.resolution ?? null,
+          suffix: this.outputSuffix,
+          output_directory: usesCustomDir ? this.mainScreenSettings!.customOutputPath : null,
+          source_root: null,
+        },
+      });
+    } catch (error) {
+      console.warn('[RenderService] Output template failed, using default naming:', error);
+    }
+
     const parts = inputPath.split(/[\\/]/);
     const fileName = parts.pop() || 'output.mp4';
     const dirPath = parts.join('/');
@@ -883,23 +1180,19 @@ class RenderServiceImpl {
   }
 
   /**
-   * Add files to render queue
+   * Add files to render queue (unattended: from a folder import or the HTTP API)
    */
-  public async addToQueue(filePaths: string[]): Promise<RenderJob[]> {
+  public async addToQueue(filePaths: string[], unattended = false): Promise<RenderJob[]> {
     const newJobs: RenderJob[] = [];
+    const durations = await this.probeDurations(filePaths);
 
     for (const inputPath of filePaths) {
       const jobId = `job_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`;
       const fileName = inputPath.split(/[\\/]/).pop() || inputPath;
-      const outputPath = this.generateOutputPath(inputPath);
+      const outputPath = await this.generateOutputPath(inputPath);
 
-      // Get video duration
-      let durationSeconds = 0;
-      try {
-        durationSeconds = await invoke<number>('get_video_duration', { inputPath });
-      } catch (error) {
-        console.warn('[RenderService] Could not get duration for:', inputPath, error);
-      }
+      // Video duration (probed for all files at once above)
+      const durationSeconds = durations.get(inputPath) ?? 0;
 
       // Get source file size for before/after comparisons
       let inputSizeBytes = 0;
@@ -909,7 +1202,7 @@ class RenderServiceImpl {
         console.warn('[RenderService] Could not get file size for:', inputPath, error);
       }
       const inputSize = inputSizeBytes > 0
-        ? this.parseFileSize(`${inputSizeBytes}b`).formatted
+        ? this.formatFileSize(inputSizeBytes)
         : '—';
 
       const job: RenderJob = {
@@ -933,6 +1226,7 @@ class RenderServiceImpl {
         outputSizeBytes: 0,
         trimStartSec: 0,
         trimEndSec: durationSeconds > 0 ? durationSeconds : 0,
+        unattended,
       };
 
       this.jobs.set(jobId, job);
@@ -944,6 +1238,29 @@ class RenderServiceImpl {
     return newJobs;
   }
 
+  /**
+   * Durations of several files, probed in parallel by the backend
+   */
+  private async probeDurations(filePaths: string[]): Promise<Map<string, number>> {
+    const durations = new Map<string, number>();
+    try {
+      const results = await invoke<{ path: string; duration: number | null; error: string | null }[]>(
+        'get_video_durations',
+        { paths: filePaths }
+      );
+      for (const result of results) {
+        if (result.duration !== null) {
+          durations.set(result.path, result.duration);
+        } else {
+          console.warn('[RenderService] Could not get duration for:', result.path, result.error);
+        }
+      }
+    } catch (error) {
+      console.warn('[RenderService] Could not get durations:', error);
+    }
+    return durations;
+  }
+
   /**
    * Add file to render queue with explicit output path (for re-renders)
    */
@@ -972,7 +1289,7 @@ class RenderServiceImpl {
       console.warn('[RenderService] Could not get file size for:', inputPath, error);
     }
     const inputSize = inputSizeBytes > 0
-      ? this.parseFileSize(`${inputSizeBytes}b`).formatted
+      ? this.formatFileSize(inputSizeBytes)
       : '—';
 
     const job: RenderJob = {
@@ -1010,6 +1327,35 @@ class RenderServiceImpl {
     return job;
   }
 
+  /**
+   * Inputs that are already queued, rendering or were compressed recently
+   * (by path or by content), so the caller can ask before queueing them again
+   */
+  public async findDuplicates(inputPaths: string[]): Promise<DuplicateConflict[]> {
+    const queued = Array.from(this.jobs.values()).map(job => ({
+      input_path: job.inputPath,
+      status: job.status,
+    }));
+    return invoke<DuplicateConflict[]>('check_duplicate_jobs', { inputPaths, queued });
+  }
+
+  /**
+   * Speed/fps samples of a running or recently finished job (last 10 minutes), for graphs
+   */
+  public async getSpeedHistory(jobId: string): Promise<SpeedSample[]> {
+    return invoke<SpeedSample[]>('get_job_speed_history', { jobId });
+  }
+
+  /**
+   * Queue a job from the job history again with its original FFmpeg arguments
+   */
+  public async addHistoryJob(historyJob: BackendRenderJob): Promise<RenderJob> {
+    const job = await this.addToQueueWithOutput(historyJob.input_path, historyJob.output_path);
+    job.historyJob = historyJob;
+    this.notifyListeners();
+    return job;
+  }
+
   /**
    * Update trim for a queued job.
    * Trim can only be edited while job is pending.
@@ -1031,6 +1377,63 @@ class RenderServiceImpl {
     return true;
   }
 
+  /**
+   * Make a pending job wait for other jobs (e.g. concat, then compress, then extract
+   * audio). Rejects unknown jobs and dependencies that would form a cycle.
+   */
+  public setJobDependencies(jobId: string, dependsOn: string[]): boolean {
+    const job = this.jobs.get(jobId);
+    if (!job || job.status !== 'pending') return false;
+
+    const unique = Array.from(new Set(dependsOn));
+    if (unique.some(id => id === jobId || !this.jobs.has(id))) return false;
+
+    // A cycle exists when jobId is reachable from one of its new dependencies
+    const visited = new Set<string>();
+    const stack = [...unique];
+    while (stack.length > 0) {
+      const id = stack.pop()!;
+      if (id === jobId) return false;
+      if (visited.has(id)) continue;
+      visited.add(id);
+      stack.push(...(this.jobs.get(id)?.dependsOn ?? []));
+    }
+
+    job.dependsOn = unique.length > 0 ? unique : undefined;
+    this.notifyListeners();
+    if (this.isProcessing && !this.isPaused) {
+      this.dispatch();
+    }
+    return true;
+  }
+
+  /**
+   * A dependency of the job has not completed yet (removed or cleared ones count as done)
+   */
+  private isBlockedByDependencies(job: RenderJob): boolean {
+    return (job.dependsOn ?? []).some(id => {
+      const dependency = this.jobs.get(id);
+      return !!dependency && dependency.status !== 'completed';
+    });
+  }
+
+  /**
+   * Fail every pending job that (transitively) depends on a failed or removed job
+   */
+  private failDependents(jobId: string, reason: string): void {
+    const failed = this.jobs.get(jobId);
+    const name = failed ? failed.fileName : jobId;
+    this.jobs.forEach(job => {
+      if (job.status !== 'pending' && job.status !== 'waiting-for-file') return;
+      if (!(job.dependsOn ?? []).includes(jobId)) return;
+      job.status = 'error';
+      job.error = `${reason}: ${name}`;
+      this.scheduler.remove(job.id);
+      console.warn(`[RenderService] ${job.fileName} skipped, ${reason.toLowerCase()}: ${name}`);
+      this.failDependents(job.id, 'Dependency failed');
+    });
+  }
+
   /**
    * Remove job from queue
    */
@@ -1043,6 +1446,11 @@ class RenderServiceImpl {
       return false;
     }
 
+    // Jobs waiting for this one can no longer run as planned
+    if (job.status !== 'completed') {
+      this.failDependents(jobId, 'Dependency removed');
+    }
+
     this.jobs.delete(jobId);
     this.scheduler.remove(jobId);
     this.notifyListeners();
@@ -1084,6 +1492,33 @@ class RenderServiceImpl {
 
     this.isProcessing = true;
     this.isPaused = false;
+
+    // Started outside the render schedule: wait for queue-schedule-changed to open it
+    try {
+      const schedule = await invoke<{ open: boolean; opens_at: string | null }>('get_queue_schedule_state');
+      if (!schedule.open) {
+        console.log(`[RenderService] Waiting for the render schedule (opens ${schedule.opens_at})`);
+        this.pausedForSchedule = true;
+        this.isPaused = true;
+      }
+    } catch (error) {
+      console.warn('[RenderService] get_queue_schedule_state failed:', error);
+    }
+
+    // Idle trigger: wait until the machine has been idle long enough
+    if (!this.isPaused) {
+      try {
+        const idle = await invoke<{ idle: boolean; idle_seconds: number | null }>('get_idle_state');
+        if (!idle.idle) {
+          console.log(`[RenderService] Waiting for the machine to go idle (idle ${idle.idle_seconds}s)`);
+          this.pausedForIdle = true;
+          this.isPaused = true;
+        }
+      } catch (error) {
+        console.warn('[RenderService] get_idle_state failed:', error);
+      }
+    }
+
     this.notifyListeners();
     this.dispatch();
   }
@@ -1094,10 +1529,16 @@ class RenderServiceImpl {
   private dispatch(): void {
     if (this.isPaused || !this.isProcessing) return;
 
-    const planned = this.scheduler.planNext((jobId) => {
-      const job = this.jobs.get(jobId);
-      return !!job && job.status === 'pending';
-    });
+    const planned = this.scheduler.planNext(
+      (jobId) => {
+        const job = this.jobs.get(jobId);
+        return !!job && job.status === 'pending';
+      },
+      (jobId) => {
+        const job = this.jobs.get(jobId);
+        return !!job && this.isBlockedByDependencies(job);
+      },
+    );
 
     for (const target of planned) {
       this.scheduler.occupy(target.jobId, target.slot);
@@ -1110,14 +1551,56 @@ class RenderServiceImpl {
         const job = this.jobs.get(id);
         return job && job.status === 'pending';
       });
-      if (!pendingExists) {
+      const waitingExists = Array.from(this.jobs.values()).some(job => job.status === 'waiting-for-file');
+      if (!pendingExists && !waitingExists) {
         this.isProcessing = false;
         this.currentJobId = null;
         this.notifyListeners();
+        // Backend runs the post-queue action (shutdown/sleep/script) if one is set
+        invoke('notify_queue_finished').catch(err => {
+          console.warn('[RenderService] notify_queue_finished failed:', err);
+        });
       }
     }
   }
 
+  /**
+   * Hold back a job whose source is locked or still growing (e.g. an OBS recording
+   * in progress) and retry it later, so the slot goes to the next job meanwhile.
+   * Returns false when the job was deferred.
+   */
+  private async ensureSourceReady(job: RenderJob): Promise<boolean> {
+    let access: FileAccess;
+    try {
+      access = await invoke<FileAccess>('check_file_access', { path: job.inputPath });
+    } catch (error) {
+      console.warn('[RenderService] check_file_access failed:', error);
+      return true;
+    }
+    // A missing file is reported by the render itself
+    if (access.ready || !access.exists) return true;
+
+    console.log(`[RenderService] Waiting for ${job.fileName}: ${access.reason}`);
+    job.status = 'waiting-for-file';
+    job.waitingReason = access.reason || undefined;
+    this.scheduler.release(job.id);
+    this.notifyListeners();
+
+    setTimeout(() => {
+      const waiting = this.jobs.get(job.id);
+      if (!waiting || waiting.status !== 'waiting-for-file') return;
+      // Back to the end of the queue; jobs that were ready keep going first
+      waiting.status = 'pending';
+      waiting.waitingReason = undefined;
+      this.scheduler.enqueue(waiting.id);
+      this.notifyListeners();
+      this.dispatch();
+    }, RenderServiceImpl.FILE_WAIT_RETRY_MS);
+
+    this.dispatch();
+    return false;
+  }
+
   /**
    * Start a specific job
    */
@@ -1125,6 +1608,8 @@ class RenderServiceImpl {
     const job = this.jobs.get(jobId);
     if (!job) return;
 
+    if (!(await this.ensureSourceReady(job))) return;
+
     // Update job status
     job.status = 'processing';
     job.startTime = Date.now();
@@ -1171,6 +1656,19 @@ class RenderServiceImpl {
     }
 
     try {
+      // Re-run from the job history: the stored job already has its final arguments
+      if (job.historyJob) {
+        await invoke('write_render_log', {
+          jobId,
+          message: `Re-running from history: ${job.inputPath} -> ${job.outputPath}\nArgs: ${job.historyJob.ffmpeg_args.join(' ')}`
+        });
+        job.backendJob = { ...job.historyJob, job_id: jobId, input_path: job.inputPath, output_path: job.outputPath, unattended: false };
+        this.notifyListeners();
+        const result = await invoke<RenderResult>('run_ffmpeg_render', { job: job.backendJob });
+        this.handleRenderResult(jobId, result);
+        return;
+      }
+
       // Build FFmpeg arguments
       // AUTO FPS DETECTION: Detect FPS for each video if fpsAuto is enabled
       console.log(`[RenderService] Starting render for ${job.fileName} | fpsAuto: ${this.videoSettings?.fpsAuto}, fps: ${this.videoSettings?.fps}`);
@@ -1207,11 +1705,18 @@ class RenderServiceImpl {
       }
       
       const preferGpu = slot === 'gpu' && this.gpuAvailable;
+      const videoArgs = await FFmpegCommandBuilder.fetchVideoArgs(effectiveVideoSettings, preferGpu);
+      if (videoArgs && videoArgs.warnings.length > 0) {
+        await invoke('write_render_log', {
+          jobId,
+          message: `[ENCODER] ${videoArgs.encoder.name}: ${videoArgs.warnings.join('; ')}`
+        }).catch(() => {});
+      }
       const builder = new FFmpegCommandBuilder(
         effectiveVideoSettings,
         this.audioSettings!,
         this.watermarkSettings,
-        preferGpu,
+        videoArgs,
         job.outputPath
       );
 
@@ -1292,23 +1797,31 @@ class RenderServiceImpl {
       });
 
       // Start render
-      const result = await invoke<RenderResult>('run_ffmpeg_render', {
-        job: {
-          job_id: jobId,
-          input_path: job.inputPath,
-          output_path: job.outputPath,
-          ffmpeg_args: ffmpegArgsWithTrim,
-          duration_seconds: effectiveDurationSeconds,
-        }
-      });
+      job.backendJob = {
+        job_id: jobId,
+        input_path: job.inputPath,
+        output_path: job.outputPath,
+        ffmpeg_args: ffmpegArgsWithTrim,
+        duration_seconds: effectiveDurationSeconds,
+        preset_name: this.selectedPresetName,
+        upload_destination: this.mainScreenSettings?.uploadDestination || null,
+        audio_source: getAudioSource(this.audioSettings!),
+        speed_factor: builder.getSpeedFactor(),
+        stabilize: builder.getStabilize(),
+        color: builder.getColor(),
+        orientation: builder.getOrientation(),
+        limits: builder.getLimits(),
+        smart_copy: builder.getSmartCopy(),
+        two_pass: builder.getTwoPass(),
+        extra_filters: builder.getExtraFilters(),
+        pixel_format: builder.getPixelFormat(),
+        audio_passthrough: getAudioPassthrough(this.audioSettings!),
+        unattended: job.unattended ?? false,
+      };
+      this.notifyListeners();
+      const result = await invoke<RenderResult>('run_ffmpeg_render', { job: job.backendJob });
 
-      if (result.success) {
-        this.handleJobComplete(jobId);
-      } else {
-        // Parse and format FFmpeg error for user
-        const formattedError = this.formatFFmpegError(result.error || 'Unknown error');
-        this.handleJobError(jobId, formattedError);
-      }
+      this.handleRenderResult(jobId, result);
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error);
       const formattedError = this.formatFFmpegError(errorMessage);
@@ -1316,6 +1829,85 @@ class RenderServiceImpl {
     }
   }
 
+  /**
+   * NVENC refused a session (consumer cards allow only a few at once): instead of
+   * failing the GPU job, put it back at the head of the queue and keep the GPU slot
+   * empty until a session frees up. Duo mode meanwhile runs jobs on the CPU slot.
+   * Returns true when the failure was handled here.
+   */
+  private handleNvencSessionLimit(jobId: string, kind?: RenderErrorKind): boolean {
+    const job = this.jobs.get(jobId);
+    if (kind !== 'nvenc_session_limit' || !job || job.assignedSlot !== 'gpu') return false;
+    // render-error and the command result both report the failure
+    if (job.status !== 'processing') return true;
+    job.nvencRetries = (job.nvencRetries ?? 0) + 1;
+    if (job.nvencRetries > RenderServiceImpl.MAX_NVENC_RETRIES) return false;
+
+    console.warn(`[RenderService] NVENC session refused for ${job.fileName}, waiting for a free session`);
+    job.status = 'pending';
+    job.progress = 0;
+    job.startTime = undefined;
+    this.activeJobs.delete(jobId);
+    if (this.activeJobs.size === 0) {
+      this.currentJobId = null;
+    }
+    this.scheduler.release(jobId);
+    this.scheduler.requeueFront(jobId);
+    this.scheduler.setGpuCapacity(0);
+    this.scheduleNvencPoll();
+    this.notifyListeners();
+    if (this.isProcessing && !this.isPaused) {
+      this.dispatch();
+    }
+    return true;
+  }
+
+  /**
+   * Check get_nvenc_session_state until a GPU render may start again
+   */
+  private scheduleNvencPoll(): void {
+    if (this.nvencPoll) return;
+    this.nvencPoll = setTimeout(async () => {
+      this.nvencPoll = null;
+      try {
+        const state = await invoke<{ limit: number | null; active_sessions: number | null; can_start: boolean }>('get_nvenc_session_state');
+        if (!state.can_start) {
+          this.scheduleNvencPoll();
+          return;
+        }
+        console.log(`[RenderService] NVENC session available (limit ${state.limit}, open ${state.active_sessions})`);
+      } catch (error) {
+        console.warn('[RenderService] get_nvenc_session_state failed:', error);
+      }
+      this.scheduler.setGpuCapacity(1);
+      if (this.isProcessing && !this.isPaused) {
+        this.dispatch();
+      }
+    }, RenderServiceImpl.NVENC_SESSION_POLL_MS);
+  }
+
+  /**
+   * Complete or fail a job from the result of run_ffmpeg_render
+   */
+  private handleRenderResult(jobId: string, result: RenderResult): void {
+    const job = this.jobs.get(jobId);
+    if (result.success) {
+      if (job && result.warnings && result.warnings.length > 0) {
+        job.warnings = result.warnings;
+      }
+      if (job && result.copied_streams && result.copied_streams.length > 0) {
+        job.copiedStreams = result.copied_streams;
+      }
+      this.handleJobComplete(jobId);
+    } else {
+      // Parse and format FFmpeg error for user
+      const kind = result.error_kind ?? undefined;
+      if (this.handleNvencSessionLimit(jobId, kind)) return;
+      const formattedError = this.describeError(result.error || 'Unknown error', kind);
+      this.handleJobError(jobId, formattedError, kind);
+    }
+  }
+
   /**
    * Handle progress update from FFmpeg
    */
@@ -1324,6 +1916,9 @@ class RenderServiceImpl {
     if (!job) return;
 
     job.progress = Math.min(100, progress.progress_percent);
+    job.phase = progress.phase;
+    job.phaseIndex = progress.phase_index;
+    job.phaseCount = progress.phase_count;
     job.eta = progress.eta_seconds;
     job.etaFormatted = this.formatETA(progress.eta_seconds);
     job.currentTime = progress.time_seconds;
@@ -1332,17 +1927,16 @@ class RenderServiceImpl {
     job.bitrate = progress.bitrate;
     job.frame = progress.frame;
     
-    // Parse output file size from FFmpeg (e.g., "1024kB", "512KiB", "1.5MB")
+    // Output file size in bytes (backend already parsed FFmpeg's "1024kB" style strings)
     if (progress.total_size) {
-      const parsed = this.parseFileSize(progress.total_size);
-      job.outputSizeBytes = parsed.bytes;
-      job.outputSize = parsed.formatted;
+      job.outputSizeBytes = progress.total_size;
+      job.outputSize = this.formatFileSize(progress.total_size);
       
-      // Calculate estimated final size based on progress ratio
+      // Calculate estimated final size from the progress of the pass writing the output
       // Only estimate if we have at least 5% progress to avoid wild estimates
-      if (progress.progress_percent >= 5 && parsed.bytes > 0) {
-        const processedRatio = progress.progress_percent / 100;
-        const estimatedBytes = Math.round(parsed.bytes / processedRatio);
+      if (progress.phase_percent >= 5) {
+        const processedRatio = progress.phase_percent / 100;
+        const estimatedBytes = Math.round(progress.total_size / processedRatio);
         const estimatedMB = estimatedBytes / (1024 * 1024);
         
         job.estimatedFinalSizeBytes = estimatedBytes;
@@ -1356,44 +1950,13 @@ class RenderServiceImpl {
   }
 
   /**
-   * Parse FFmpeg size string to bytes and formatted MB string
+   * Format a byte count as MB (or GB past 1000 MB) with the precision the queue shows
    */
-  private parseFileSize(sizeStr: string): { bytes: number; formatted: string } {
-    const str = sizeStr.trim().toLowerCase();
-    let bytes = 0;
-    
-    // Match patterns like "1024kB", "512KiB", "1.5MB", "256mib"
-    const match = str.match(/^([\d.]+)\s*(kib|kb|mib|mb|gib|gb|b)?$/i);
-    if (match) {
-      const value = parseFloat(match[1]);
-      const unit = (match[2] || 'b').toLowerCase();
-      
-      switch (unit) {
-        case 'b':
-          bytes = value;
-          break;
-        case 'kb':
-        case 'kib':
-          bytes = value * 1024;
-          break;
-        case 'mb':
-        case 'mib':
-          bytes = value * 1024 * 1024;
-          break;
-        case 'gb':
-        case 'gib':
-          bytes = value * 1024 * 1024 * 1024;
-          break;
-      }
-    }
-    
-    // Format to MB with 1 decimal
+  private formatFileSize(bytes: number): string {
     const mb = bytes / (1024 * 1024);
-    const formatted = mb >= 1000 
-      ? `${(mb / 1024).toFixed(2)} GB` 
+    return mb >= 1000
+      ? `${(mb / 1024).toFixed(2)} GB`
       : `${mb.toFixed(1)} MB`;
-    
-    return { bytes, formatted };
   }
 
   /**
@@ -1471,13 +2034,14 @@ class RenderServiceImpl {
   /**
    * Handle job error
    */
-    private handleJobError(jobId: string, error: string): void {
+    private handleJobError(jobId: string, error: string, kind?: RenderErrorKind): void {
         const job = this.jobs.get(jobId);
         if (!job) return;
 
         const slot = job.assignedSlot || 'unknown';
         job.status = 'error';
         job.error = error;
+        job.errorKind = kind;
         job.endTime = Date.now();
         this.activeJobs.delete(jobId);
         this.scheduler.release(jobId);
@@ -1487,6 +2051,7 @@ class RenderServiceImpl {
         }
 
         console.error(`[RenderService] Job error on ${slot.toUpperCase()} slot:`, jobId, error);
+        this.failDependents(jobId, 'Dependency failed');
 
         // Log error with slot info
         invoke('write_render_log', {
@@ -1506,6 +2071,25 @@ class RenderServiceImpl {
   /**
    * Format FFmpeg error messages for user display
    */
+  /**
+   * What the user can do about a classified failure; unclassified errors go
+   * through formatFFmpegError
+   */
+  private static readonly ERROR_KIND_MESSAGES: Partial<Record<RenderErrorKind, string>> = {
+    file_not_found: 'Input file not found. It may have been moved, renamed or deleted.',
+    unsupported_codec: 'This FFmpeg build cannot encode or decode the codec, or the container cannot hold it. Try another codec or container.',
+    nvenc_session_limit: 'The GPU refused another NVENC session (too many GPU encodes at once). Run fewer GPU jobs in parallel or use the CPU.',
+    disk_full: 'The output drive is full. Free up space or choose another output folder.',
+    permission_denied: 'Permission denied. Check that the input can be read and the output folder can be written.',
+    corrupt_input: 'The input file is damaged or incomplete and cannot be decoded.',
+    crash: 'FFmpeg crashed. A crash report was saved; try another FFmpeg build or preset.',
+  };
+
+  private describeError(rawError: string, kind?: RenderErrorKind): string {
+    const message = kind && RenderServiceImpl.ERROR_KIND_MESSAGES[kind];
+    return message || this.formatFFmpegError(rawError);
+  }
+
   private formatFFmpegError(rawError: string): string {
     // Common FFmpeg error patterns and their user-friendly messages
     const errorPatterns: Array<{ pattern: RegExp; message: string }> = [
@@ -1613,6 +2197,11 @@ class RenderServiceImpl {
 
     this.isPaused = false;
 
+    // FFmpeg processes suspended by the pause hotkey continue where they stopped
+    invoke('resume_suspended_renders').catch((error) => {
+      console.warn('[RenderService] Failed to resume suspended renders:', error);
+    });
+
     // Resume current job or start next
     if (this.currentJobId) {
       const job = this.jobs.get(this.currentJobId);
@@ -1764,7 +2353,7 @@ class RenderServiceImpl {
     for (const job of allJobs) {
       if (job.status === 'processing' || job.status === 'paused') {
         processing.push(job);
-      } else if (job.status === 'pending') {
+      } else if (job.status === 'pending' || job.status === 'waiting-for-file') {
         pending.push(job);
       } else {
         finished.push(job);
@@ -53,6 +53,11 @@ export interface RenderStatRecord {
   eta: number;              // ETA in seconds (during render)
   etaFormatted: string;     // ETA formatted string
   error?: string;           // Error message if failed
+  hashAlgorithm?: string;   // Checksum algorithm of inputHash / outputHash
+  inputHash?: string;       // Source checksum (recordHashes setting)
+  outputHash?: string;      // Output checksum (recordHashes setting)
+  inputSizeBytes?: number;  // Source size when the render completed (render-sizes)
+  outputSizeBytes?: number; // Output size when the render completed (render-sizes)
   createdAt: string;        // ISO timestamp
   completedAt?: string;     // ISO timestamp when completed
 }
@@ -91,6 +96,8 @@ class StatisticsServiceImpl {
   private unlistenComplete: UnlistenFn | null = null;
   private unlistenError: UnlistenFn | null = null;
   private unlistenStopped: UnlistenFn | null = null;
+  private unlistenHashes: UnlistenFn | null = null;
+  private unlistenSizes: UnlistenFn | null = null;
   private saveDebounceTimer: ReturnType<typeof setTimeout> | null = null;
 
   constructor() {
@@ -200,6 +207,34 @@ class StatisticsServiceImpl {
       this.unlistenStopped = await listen<{ job_id: string; stopped_by: string }>('render-stopped', (event) => {
         this.markRenderStopped(event.payload.job_id);
       });
+
+      // Listen for input/output checksums of finished renders
+      this.unlistenHashes = await listen<{
+        job_id: string;
+        algorithm: string;
+        input_hash: string | null;
+        output_hash: string | null;
+      }>('render-hashes', (event) => {
+        const record = this.stats.renders.find(r => r.id === event.payload.job_id);
+        if (!record) return;
+        record.hashAlgorithm = event.payload.algorithm;
+        record.inputHash = event.payload.input_hash ?? undefined;
+        record.outputHash = event.payload.output_hash ?? undefined;
+        this.save();
+      });
+
+      // Listen for input/output sizes of finished renders (used for the bytes saved)
+      this.unlistenSizes = await listen<{
+        job_id: string;
+        input_size: number;
+        output_size: number;
+      }>('render-sizes', (event) => {
+        const record = this.stats.renders.find(r => r.id === event.payload.job_id);
+        if (!record) return;
+        record.inputSizeBytes = event.payload.input_size;
+        record.outputSizeBytes = event.payload.output_size;
+        this.save();
+      });
     } catch (error) {
       console.error('[StatisticsService] Failed to setup event listeners:', error);
     }
@@ -213,6 +248,8 @@ class StatisticsServiceImpl {
     if (this.unlistenComplete) this.unlistenComplete();
     if (this.unlistenError) this.unlistenError();
     if (this.unlistenStopped) this.unlistenStopped();
+    if (this.unlistenHashes) this.unlistenHashes();
+    if (this.unlistenSizes) this.unlistenSizes();
     
     // Save before cleanup
     await this.save();
@@ -1,17 +1,15 @@
 /**
- * UpdateService - Simple Update System (No Signing Required)
+ * UpdateService - Simple Update System (minisign-signed releases)
  * 
- * Downloads updates directly from GitHub Releases.
- * Supports .exe and .zip files with optional SHA256 hash verification.
+ * Finds releases through the GitHub Releases API (check_for_updates, honoring the
+ * stable/beta updateChannel setting) and downloads them directly.
+ * Supports .exe and .zip files with optional SHA256 hash verification. The backend
+ * keeps a download only when its .minisig signature verifies, and apply_update checks
+ * it again before installing.
  */
 
 import { invoke } from '@tauri-apps/api/tauri';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import { getVersion } from '@tauri-apps/api/app';
-import { fetch as tauriFetch, ResponseType } from '@tauri-apps/api/http';
-
-// Update endpoint - latest.json location
-const UPDATE_ENDPOINT = 'https://github.com/sharkye1/Szhimatar2/releases/latest/download/latest.json';
 
 export type UpdateStatus = 
   | 'idle'
@@ -22,16 +20,22 @@ export type UpdateStatus =
   | 'up-to-date'
   | 'error';
 
-export interface UpdateManifest {
-  version: string;
-  notes: string;
-  pub_date?: string;
-  platforms: {
-    [key: string]: {
-      url: string;
-      hash?: string;  // Optional SHA256 hash for integrity check
-    };
-  };
+export type UpdateChannel = 'stable' | 'beta';
+
+/** Result of the check_for_updates command */
+interface UpdateCheck {
+  current_version: string;
+  channel: UpdateChannel;
+  available: boolean;
+  version: string | null;
+  changelog: string | null;
+  asset_url: string | null;
+  asset_name: string | null;
+  asset_hash: string | null;
+  signature_url: string | null;
+  published_at: string | null;
+  prerelease: boolean;
+  release_url: string | null;
 }
 
 export interface UpdateInfo {
@@ -41,6 +45,9 @@ export interface UpdateInfo {
   releaseDate?: string;
   downloadUrl: string;
   hash?: string;
+  signatureUrl?: string;
+  prerelease?: boolean;
+  releaseUrl?: string;
 }
 
 export interface UpdateProgress {
@@ -69,6 +76,7 @@ class UpdateServiceClass {
   private listeners: Set<UpdateListener> = new Set();
   private progressUnlisten: UnlistenFn | null = null;
   private isInitialized = false;
+  private downloadCancelled = false;
 
   /**
    * Initialize the update service
@@ -91,7 +99,7 @@ class UpdateServiceClass {
       );
 
       this.isInitialized = true;
-      console.log('[UpdateService] Initialized (simple mode, no signing)');
+      console.log('[UpdateService] Initialized (signed updates)');
     } catch (error) {
       console.error('[UpdateService] Failed to initialize:', error);
     }
@@ -128,41 +136,6 @@ class UpdateServiceClass {
     return { ...this.state };
   }
 
-  /**
-   * Get current platform key for latest.json
-   */
-  private getPlatformKey(): string {
-    // Detect platform
-    const platform = navigator.platform.toLowerCase();
-    
-    if (platform.includes('win')) {
-      return 'windows-x86_64';
-    } else if (platform.includes('mac')) {
-      return 'darwin-x86_64';
-    } else if (platform.includes('linux')) {
-      return 'linux-x86_64';
-    }
-    
-    return 'windows-x86_64'; // Default
-  }
-
-  /**
-   * Compare semantic versions
-   * Returns true if newVersion > currentVersion
-   */
-  private isNewerVersion(currentVersion: string, newVersion: string): boolean {
-    const current = currentVersion.replace(/^v/, '').split('.').map(Number);
-    const next = newVersion.replace(/^v/, '').split('.').map(Number);
-
-    for (let i = 0; i < Math.max(current.length, next.length); i++) {
-      const c = current[i] || 0;
-      const n = next[i] || 0;
-      if (n > c) return true;
-      if (n < c) return false;
-    }
-    return false;
-  }
-
   /**
    * Check for available updates
    */
@@ -178,45 +151,26 @@ class UpdateServiceClass {
     });
 
     try {
-      // Fetch latest.json via Tauri's native HTTP client to avoid CORS
-      const response = await tauriFetch<UpdateManifest>(UPDATE_ENDPOINT, {
-        method: 'GET',
-        headers: { 'Accept': 'application/json' },
-        responseType: ResponseType.JSON,
-      });
-
-      if (!response.ok) {
-        throw new Error(`Failed to fetch update manifest: ${response.status}`);
-      }
-
-      const manifest: UpdateManifest = response.data;
-      const platformKey = this.getPlatformKey();
-      const platformData = manifest.platforms[platformKey];
+      // The backend queries the GitHub Releases API for the configured channel
+      const check = await invoke<UpdateCheck>('check_for_updates', { channel: null });
 
-      if (!platformData) {
-        console.log(`[UpdateService] No update available for platform: ${platformKey}`);
-        this.updateState({ status: 'up-to-date' });
-        return null;
-      }
-
-      // Get current version
-      const currentVersion = await this.getCurrentVersion();
-      
-      // Check if update is needed
-      if (!this.isNewerVersion(currentVersion, manifest.version)) {
-        console.log(`[UpdateService] Up to date (current: ${currentVersion}, latest: ${manifest.version})`);
+      if (!check.available || !check.version || !check.asset_url) {
+        console.log(`[UpdateService] Up to date (current: ${check.current_version}, channel: ${check.channel})`);
         this.updateState({ status: 'up-to-date' });
         return null;
       }
 
       // Update available!
       const info: UpdateInfo = {
-        currentVersion,
-        newVersion: manifest.version,
-        releaseNotes: manifest.notes,
-        releaseDate: manifest.pub_date,
-        downloadUrl: platformData.url,
-        hash: platformData.hash,
+        currentVersion: check.current_version,
+        newVersion: check.version,
+        releaseNotes: check.changelog || undefined,
+        releaseDate: check.published_at || undefined,
+        downloadUrl: check.asset_url,
+        hash: check.asset_hash || undefined,
+        signatureUrl: check.signature_url || undefined,
+        prerelease: check.prerelease,
+        releaseUrl: check.release_url || undefined,
       };
 
       this.updateState({
@@ -225,7 +179,7 @@ class UpdateServiceClass {
         error: null,
       });
 
-      console.log('[UpdateService] Update available:', manifest.version);
+      console.log('[UpdateService] Update available:', check.version);
       return info;
 
     } catch (error) {
@@ -255,13 +209,14 @@ class UpdateServiceClass {
       return false;
     }
 
+    this.downloadCancelled = false;
     this.updateState({
       status: 'downloading',
       progress: { downloaded: 0, total: 0, percent: 0 },
     });
 
     try {
-      const { downloadUrl, hash } = this.state.info;
+      const { downloadUrl, hash, signatureUrl } = this.state.info;
 
       // Call Rust command to download update
       const result = await invoke<{ success: boolean; path: string; error?: string }>(
@@ -269,9 +224,16 @@ class UpdateServiceClass {
         {
           url: downloadUrl,
           expectedHash: hash || null,
+          signatureUrl: signatureUrl || null,
         }
       );
 
+      if (!result.success && this.downloadCancelled) {
+        // Back to the offer; the partial download is kept for resuming
+        this.updateState({ status: 'update-available', progress: null });
+        return false;
+      }
+
       if (!result.success) {
         throw new Error(result.error || 'Download failed');
       }
@@ -298,6 +260,19 @@ class UpdateServiceClass {
     }
   }
 
+  /**
+   * Stop the running download; the next download resumes where it stopped
+   */
+  async cancelDownload(): Promise<void> {
+    if (this.state.status !== 'downloading') return;
+    this.downloadCancelled = true;
+    try {
+      await invoke('cancel_update_download');
+    } catch (error) {
+      console.error('[UpdateService] Cancel failed:', error);
+    }
+  }
+
   /**
    * Apply the downloaded update (replace exe and restart)
    */
@@ -414,14 +389,6 @@ class UpdateServiceClass {
     });
   }
 
-  private async getCurrentVersion(): Promise<string> {
-    try {
-      return await getVersion();
-    } catch {
-      return 'unknown';
-    }
-  }
-
   private formatError(error: unknown): string {
     // Check if error is string
     if (typeof error === 'string') {
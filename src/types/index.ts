@@ -6,10 +6,323 @@ export interface Settings {
   ffmpeg_path: string;
   ffprobe_path: string;
   output_suffix: string;
+  outputTemplate?: string;
+  outputDirectory?: string;
+  mirrorSourceFolders?: boolean;
+  stageNetworkFiles?: boolean;
+  folderExcludePatterns?: string[];
+  notifications?: { enabled: boolean; whenFocused: boolean };
+  shortcuts?: { pauseResume: string; stopAll: string };
   default_video_codec: string;
   default_audio_codec: string;
 }
 
+// Output naming template used when none is configured (see render_output_path)
+export const DEFAULT_OUTPUT_TEMPLATE = '{parent}/{name}{suffix}.{ext}';
+
+// Proxy and CA certificate for update checks and downloads (settings.network)
+export type ProxyMode = 'system' | 'none' | 'manual';
+
+export interface NetworkSettings {
+  proxyMode: ProxyMode;
+  proxyHost: string;
+  proxyPort: number;
+  proxyUsername: string;
+  proxyPassword: string;
+  caCertificate: string;
+}
+
+export const DEFAULT_NETWORK: NetworkSettings = {
+  proxyMode: 'system',
+  proxyHost: '',
+  proxyPort: 0,
+  proxyUsername: '',
+  proxyPassword: '',
+  caCertificate: '',
+};
+
+// When renders may run (settings.queueSchedule)
+export type ScheduleMode = 'off' | 'start_at' | 'window';
+
+export interface QueueSchedule {
+  mode: ScheduleMode;
+  startAt: string; // datetime-local value, e.g. "2026-10-17T01:00"
+  windowStart: string; // "HH:MM"
+  windowEnd: string;
+  suspendRunning: boolean; // Suspend running renders when the window closes
+}
+
+export const DEFAULT_QUEUE_SCHEDULE: QueueSchedule = {
+  mode: 'off',
+  startAt: '',
+  windowStart: '01:00',
+  windowEnd: '07:00',
+  suspendRunning: false,
+};
+
+// Run renders only while the machine is idle (settings.idleTrigger)
+export interface IdleTrigger {
+  enabled: boolean;
+  idleMinutes: number;
+}
+
+export const DEFAULT_IDLE_TRIGGER: IdleTrigger = {
+  enabled: false,
+  idleMinutes: 10,
+};
+
+// Render process priority, battery and verification modes (settings.renderPriority, batteryAction, verifyOutput)
+export type RenderPriority = 'normal' | 'below_normal' | 'idle';
+export type BatteryAction = 'none' | 'pause' | 'power_saver';
+export type VerifyMode = 'off' | 'duration' | 'decode';
+
+// Source moved to the recycle bin after its render (list_trashed_sources, see recycle.rs)
+export interface TrashedSource {
+  job_id: string;
+  original_path: string;
+  output_path: string;
+  trashed_at: string;
+}
+
+// Temperature limits checked during renders (settings.thermal)
+export interface ThermalSettings {
+  cpuLimitC: number;
+  gpuLimitC: number;
+  pauseOnOverheat: boolean; // Suspend FFmpeg while over a limit instead of only warning
+}
+
+export const DEFAULT_THERMAL: ThermalSettings = {
+  cpuLimitC: 95,
+  gpuLimitC: 87,
+  pauseOnOverheat: false,
+};
+
+// Local REST/WebSocket server for remote control (settings.httpApi)
+export interface ApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+  allowLan: boolean;
+}
+
+export const DEFAULT_HTTP_API: ApiSettings = {
+  enabled: false,
+  port: 8765,
+  token: '',
+  allowLan: false,
+};
+
+// URL notified on job and queue events (settings.webhooks)
+export interface Webhook {
+  url: string;
+  onComplete: boolean;
+  onFail: boolean;
+  onQueueFinished: boolean;
+}
+
+export const DEFAULT_WEBHOOK: Webhook = {
+  url: '',
+  onComplete: true,
+  onFail: true,
+  onQueueFinished: true,
+};
+
+// Post-render upload target (settings.uploadDestinations, see uploads.rs)
+export type UploadKind = 'sftp' | 's3' | 'webdav';
+
+export interface UploadDestination {
+  id: string;
+  name: string;
+  kind: UploadKind;
+  url: string;        // WebDAV folder URL / S3 endpoint
+  host: string;       // SFTP
+  port: number;       // SFTP (0 = 22)
+  username: string;   // SFTP / WebDAV
+  password: string;   // WebDAV
+  keyPath: string;    // SFTP private key (empty = SSH agent)
+  bucket: string;     // S3
+  region: string;     // S3 ("us-east-1" when empty)
+  accessKey: string;  // S3
+  secretKey: string;  // S3
+  remotePath: string; // SFTP folder / S3 key prefix
+}
+
+export const DEFAULT_UPLOAD_DESTINATION: UploadDestination = {
+  id: '',
+  name: '',
+  kind: 'sftp',
+  url: '',
+  host: '',
+  port: 22,
+  username: '',
+  password: '',
+  keyPath: '',
+  bucket: '',
+  region: '',
+  accessKey: '',
+  secretKey: '',
+  remotePath: '',
+};
+
+// export_frames (frames.rs)
+export type FrameFormat = 'png' | 'jpeg';
+
+export type FrameSelection =
+  | { mode: 'interval'; seconds: number }     // One frame every N seconds
+  | { mode: 'timestamps'; times: number[] };  // One frame per timestamp (seconds)
+
+export interface FrameExportResult {
+  output_dir: string;
+  files: string[];
+}
+
+// enqueue_folder (folder_import.rs)
+export interface FolderImportOptions {
+  include: string[];      // Wildcards ("*.mov", "raw/", "day?/*.mp4"); none = every video
+  exclude: string[];      // Skipped on top of the folderExcludePatterns setting
+  min_size: number;       // Bytes, 0 = no minimum
+  recursive: boolean;
+  output_dir: string | null;
+  queue_only: boolean;
+  preview_only: boolean;  // Probe and summarize without queueing
+}
+
+export interface FolderImportPreview {
+  files: { path: string; size: number; duration: number; error: string | null }[];
+  total_size: number;     // Bytes of the readable files
+  total_duration: number; // Seconds of media to encode
+  unreadable: number;
+  queued: boolean;
+}
+
+// build_encode_args (encode_args.rs)
+export interface EncodeArgsResult {
+  encoder: { name: string; family: string; hardware: boolean };
+  input_args: string[];            // Hardware device setup (the render adds it itself)
+  orientation_filter: string | null;
+  upload_filter: string | null;    // VAAPI hwupload (the render adds it itself)
+  args: string[];                  // -c:v <encoder> plus rate control and preset
+  warnings: string[];              // e.g. no hardware encoder for the codec
+}
+
+// set_post_queue_action / post-queue-* events (queue.rs); runs once, then resets to none
+export type PostQueueAction =
+  | { kind: 'none' }
+  | { kind: 'shutdown' }
+  | { kind: 'sleep' }
+  | { kind: 'hibernate' }
+  | { kind: 'run_script'; path: string };
+
+export interface PostQueueCountdown {
+  action: PostQueueAction;
+  seconds_left: number;
+}
+
+export interface PostQueueResult {
+  action: PostQueueAction;
+  error: string | null;
+}
+
+// validate_lut (color.rs)
+export interface LutInfo {
+  title: string | null;
+  size: number; // Points per axis
+}
+
+// Scripts run around each render (settings.hooks, see hooks.rs); empty = no hook
+export interface HookSettings {
+  preRender: string;
+  postRenderSuccess: string;
+  postRenderFailure: string;
+  timeoutSecs: number;
+}
+
+export const DEFAULT_HOOKS: HookSettings = {
+  preRender: '',
+  postRenderSuccess: '',
+  postRenderFailure: '',
+  timeoutSecs: 600,
+};
+
+// Result of the check_gpu_compatibility command
+export interface GpuCompatibility {
+  available: boolean;
+  status: 'ok' | 'not_in_build' | 'no_gpu' | 'driver_too_old' | 'encoder_failed';
+  driver_version: string | null;
+  required_driver: string | null;
+  message: string;
+}
+
+// Result of the get_system_info command
+export interface SystemInfo {
+  app_version: string;
+  os_version: string;
+  kernel_version: string | null;
+  arch: string;
+  total_memory_bytes: number;
+  available_memory_bytes: number;
+  cpu_model: string | null;
+  physical_cores: number | null;
+  logical_cores: number;
+  gpus: { name: string; vram_bytes: number | null }[];
+  disks: {
+    role: 'appData' | 'output' | 'temp';
+    path: string;
+    mount_point: string;
+    total_bytes: number;
+    free_bytes: number;
+  }[];
+  ffmpeg_path: string;
+  ffmpeg_version: string | null;
+  ffprobe_version: string | null;
+}
+
+// Entry of the list_job_history command
+export interface JobHistoryEntry {
+  id: string;
+  job: {
+    job_id: string;
+    input_path: string;
+    output_path: string;
+    ffmpeg_args: string[];
+    duration_seconds: number;
+    [key: string]: unknown;
+  };
+  success: boolean;
+  error: string | null;
+  warnings: string[];
+  started_at: string;
+  finished_at: string;
+  elapsed_seconds: number;
+  input_size_bytes: number | null;
+  output_size_bytes: number | null;
+}
+
+// Entry of the list_crash_reports command
+export interface CrashReport {
+  id: string;
+  kind: 'panic' | 'ffmpeg';
+  timestamp: string;
+  app_version: string;
+  message: string;
+  backtrace: string | null;
+  job_id: string | null;
+  log_tail: string;
+  seen: boolean;
+}
+
+// Entry of the list_interrupted_jobs / resume_interrupted_jobs commands
+export interface InterruptedJob {
+  id: string;
+  input_path: string;
+  output_path: string;
+  state: 'complete' | 'resumable' | 'restart' | 'pending';
+  trim_start_sec: number;
+  trim_end_sec: number;
+  depends_on: string[]; // Ids of the previous session's jobs this one waited for
+  job: JobHistoryEntry['job'] | null;
+}
+
 export interface VideoSettings {
   codec: string;
   bitrate: string;
@@ -19,13 +332,28 @@ export interface VideoSettings {
   aspectRatio: string;        // 16:9, 4:3, 21:9, etc.
   aspectRatioAuto?: boolean;  // Auto-detect Aspect Ratio
   crf: string;
+  rateControl: 'auto' | 'capped_crf'; // capped_crf: CRF quality with the bitrate as a peak
+  twoPass: boolean;           // Analysis pass first (x264, x265, VP9, libaom AV1; others encode once)
   preset: string;
   speed: number;              // 0.25 to 2.0 (slow to fast)
   rotation: 'none' | '90' | '180' | '270';
   flip: 'none' | 'horizontal' | 'vertical';
+  sourceRotation: 'bake' | 'keep'; // Phone rotation tag: turn the frames upright or keep the tag
+  resolutionCap: 'none' | '720' | '1080' | '1440'; // Downscale only sources above this
+  fpsCap: 'none' | '30' | '60';                     // Lower only frame rates above this
+  smartCopy: boolean;         // Copy streams the re-encode would not improve
+  pixelFormat: 'auto' | 'yuv420p' | 'yuv420p10le'; // auto: keep 10-bit sources 10-bit when the encoder can
   filters: VideoFilter[];     // Array of enabled filters
+  extraFilters: string;       // Custom FFmpeg filtergraph appended to the video filters ('' = none)
   resamplingEnabled?: boolean;    // Frame interpolation toggle
   resamplingIntensity?: number;   // 1-10
+  stabilize: boolean;         // vidstab two-pass stabilization
+  stabilizeShakiness: number; // 1-10 (how shaky the footage is)
+  stabilizeSmoothing: number; // Frames used to smooth the camera path
+  lutPath: string;            // .cube LUT applied with lut3d ('' = none)
+  brightness: number;         // -1 to 1 (0 = unchanged)
+  contrast: number;           // 0 to 2 (1 = unchanged)
+  saturation: number;         // 0 to 3 (1 = unchanged)
 }
 
 export interface VideoFilter {
@@ -43,17 +371,32 @@ export const DEFAULT_VIDEO_SETTINGS: VideoSettings = {
   aspectRatio: '16:9',
   aspectRatioAuto: false,
   crf: '23',
+  rateControl: 'auto',
+  twoPass: false,
   preset: 'medium',
   speed: 1.0,
   rotation: 'none',
   flip: 'none',
+  sourceRotation: 'bake',
+  resolutionCap: 'none',
+  fpsCap: 'none',
+  smartCopy: false,
+  pixelFormat: 'auto',
   filters: [
     { name: 'deinterlace', enabled: false },
     { name: 'denoise', enabled: false },
     { name: 'sharpen', enabled: false },
   ],
+  extraFilters: '',
   resamplingEnabled: false,
   resamplingIntensity: 5,
+  stabilize: false,
+  stabilizeShakiness: 5,
+  stabilizeSmoothing: 10,
+  lutPath: '',
+  brightness: 0,
+  contrast: 1,
+  saturation: 1,
 };
 
 export interface AudioEffect {
@@ -79,8 +422,24 @@ export interface AudioSettings {
   autoSelect: boolean;      // Auto-detect from metadata
   effects: AudioEffect[];   // reverb, chorus, compressor, etc.
   equalizer: EqualizerBand[];  // 5-band EQ
+  track: AudioTrackMode;    // What the output's audio is made of
+  trackPath: string;        // External audio file (replace / mix)
+  trackStart: number;       // Seconds skipped at the start of the file (replace)
+  trackLoop: boolean;       // Repeat the file when it is shorter than the video
+  trackVolume: number;      // Level of the mixed-in file, 0-400 (%)
+  flacCompression: number;  // FLAC -compression_level, 0 (fastest) - 12 (smallest)
+  passthrough: boolean;     // Copy AC3 / E-AC3 / DTS tracks the container can carry
 }
 
+export type AudioTrackMode = 'keep' | 'mute' | 'replace' | 'mix';
+
+// RenderJob.audio_source (see encode_args.rs)
+export type AudioSource =
+  | { mode: 'keep' }
+  | { mode: 'mute' }
+  | { mode: 'replace'; path: string; start_seconds: number; loop_audio: boolean }
+  | { mode: 'mix'; path: string; volume: number; loop_audio: boolean };
+
 export const DEFAULT_AUDIO_SETTINGS: AudioSettings = {
   codec: 'aac',
   bitrate: '192',
@@ -104,6 +463,13 @@ export const DEFAULT_AUDIO_SETTINGS: AudioSettings = {
     { frequency: 4000, gain: 0 },
     { frequency: 16000, gain: 0 },
   ],
+  track: 'keep',
+  trackPath: '',
+  trackStart: 0,
+  trackLoop: false,
+  trackVolume: 100,
+  flacCompression: 5,
+  passthrough: false,
 };
 
 export interface WatermarkSettings {
@@ -154,6 +520,7 @@ export interface Translations {
 export interface MainScreenSettings {
   saveInSourceDirectory: boolean;
   customOutputPath: string;
+  uploadDestination?: string; // UploadDestination id; outputs are uploaded there after rendering
 }
 
 export const DEFAULT_MAIN_SCREEN_SETTINGS: MainScreenSettings = {
@@ -172,6 +539,30 @@ export interface AppPreset {
   createdAt?: string;                 // ISO timestamp
   modifiedAt?: string;                // ISO timestamp
   isDefault?: boolean;                // Marks preset as default (only one allowed)
+  source?: PresetSource;              // Set by load_preset for plugin presets
+  plugin?: string;                    // Plugin providing the preset
+}
+
+// Plugin presets (plugins.rs) only carry the settings their manifest sets
+export const withPresetDefaults = (preset: AppPreset): AppPreset => ({
+  ...preset,
+  video: { ...DEFAULT_VIDEO_SETTINGS, ...preset.video },
+  audio: { ...DEFAULT_AUDIO_SETTINGS, ...preset.audio },
+  mainScreen: { ...DEFAULT_MAIN_SCREEN_SETTINGS, ...preset.mainScreen },
+});
+
+// Entry of list_presets
+export type PresetSource = 'user' | 'plugin';
+
+export interface PresetInfo {
+  name: string;
+  source: PresetSource;
+  plugin?: string;
+  description?: string;
+  constraints?: {
+    encoders: string[];   // FFmpeg encoders the profile uses
+    containers: string[]; // Output containers it is meant for
+  };
 }
 
 // Preset metadata for listing
@@ -2,13 +2,13 @@
  * Audio Validation Utility
  * 
  * Implements codec-specific validation rules to prevent FFmpeg errors:
- * - Opus: 48kHz sample rate, 1-2 channels, 16-128k bitrate
+ * - Opus: 48kHz sample rate, 1-8 channels, 16-512k bitrate
  * - MP3: 44.1kHz sample rate, 1-2 channels, 64-192k bitrate
  * - Other codecs: standard validation
  * - Copy mode: no filters allowed
  */
 
-import type { AudioSettings } from '../types';
+import type { AudioSettings, AudioSource } from '../types';
 
 // ============================================================================
 // Types
@@ -65,14 +65,14 @@ export const CODEC_CONSTRAINTS: Record<string, CodecConstraints> = {
     defaultSampleRate: 48000,
     fixedSampleRate: true, // Opus ONLY works with 48kHz
     
-    channels: [1, 2],
+    channels: [1, 2, 6, 8],
     defaultChannels: 2,
     fixedChannels: false,
     
     minBitrate: 16,
-    maxBitrate: 128,
+    maxBitrate: 512,
     defaultBitrate: 64,
-    bitrateOptions: [16, 24, 32, 48, 64, 96, 128],
+    bitrateOptions: [16, 24, 32, 48, 64, 96, 128, 160, 192, 256, 384, 512],
     
     supportsFilters: true,
     encoderName: 'libopus',
@@ -221,6 +221,32 @@ export function getActiveFilterNames(settings: AudioSettings): string[] {
   return names;
 }
 
+/**
+ * Audio track choice as RenderJob.audio_source; the backend checks the file and builds
+ * the -an / second input / -map arguments (encode_args::apply_audio_source)
+ */
+export function getAudioSource(settings: AudioSettings): AudioSource {
+  const { track, trackPath, trackStart, trackLoop, trackVolume } = settings;
+  switch (track) {
+    case 'mute':
+      return { mode: 'mute' };
+    case 'replace':
+      return { mode: 'replace', path: trackPath, start_seconds: Math.max(0, trackStart || 0), loop_audio: trackLoop };
+    case 'mix':
+      return { mode: 'mix', path: trackPath, volume: (trackVolume ?? 100) / 100, loop_audio: trackLoop };
+    default:
+      return { mode: 'keep' };
+  }
+}
+
+/**
+ * RenderJob.audio_passthrough: the backend copies AC3 / E-AC3 / DTS source tracks the
+ * output container can carry (encode_args::apply_audio_passthrough)
+ */
+export function getAudioPassthrough(settings: AudioSettings): boolean {
+  return !!settings.passthrough && settings.codec !== 'copy' && settings.track !== 'mute';
+}
+
 /**
  * Clamp bitrate to valid range for codec
  */